@@ -0,0 +1,92 @@
+use std::fs;
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use puzzles::{
+    camping::{self, Camping, GuessOrder, Map, Rules, SolverConfig},
+    generate::{self, GeneratorConfig},
+};
+
+/// The sizes of the generated maps, since the maps in the data directory are all 8 by 8.
+const SIZES: [usize; 4] = [8, 12, 16, 20];
+
+fn data_maps() -> Vec<Map> {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/data/camping/maps");
+    let mut paths = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "txt"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+        .iter()
+        .map(|path| Map::parse(fs::read_to_string(path).unwrap()).unwrap())
+        .collect()
+}
+
+/// Solves every map in the data directory.
+fn maps(c: &mut Criterion) {
+    let maps = data_maps();
+    c.bench_with_input(BenchmarkId::new("solve", "maps"), &maps, |b, maps| {
+        b.iter(|| {
+            for map in maps {
+                camping::solve(map).unwrap().unwrap();
+            }
+        })
+    });
+}
+
+/// Solves generated maps of growing size, which need not have a unique solution.
+fn sizes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sizes");
+    group.sample_size(10);
+    for size in SIZES {
+        let config = GeneratorConfig {
+            size: Some(size),
+            unique: false,
+            ..GeneratorConfig::default()
+        };
+        let map = generate::generate::<Camping>(&config).unwrap();
+        group.bench_with_input(BenchmarkId::new("solve", size), &map, |b, map| {
+            b.iter(|| camping::solve(map).unwrap().unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Solves generated maps guessing in scan order, which makes many guesses that break a row or column requirement
+/// and so shows how much propagation checking the lines before each guess saves.
+fn guess_heavy(c: &mut Criterion) {
+    let mut group = c.benchmark_group("guess_heavy");
+    group.sample_size(10);
+    let solver_config = SolverConfig {
+        guess_order: GuessOrder::Scan,
+        ..SolverConfig::default()
+    };
+    for size in SIZES {
+        let config = GeneratorConfig {
+            size: Some(size),
+            unique: false,
+            ..GeneratorConfig::default()
+        };
+        let map = generate::generate::<Camping>(&config).unwrap();
+        group.bench_with_input(BenchmarkId::new("solve", size), &map, |b, map| {
+            b.iter(|| camping::solve_with(map, &solver_config).unwrap().unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Applying the row and column rules on their own, to the first map of the data directory.
+fn fill_tents(c: &mut Criterion) {
+    let map = data_maps().swap_remove(0);
+    c.bench_function("fill_tents", |b| {
+        b.iter_batched(
+            || map.clone(),
+            |mut map| camping::fill_tents(&mut map, Rules::default()).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+criterion_group!(benches, maps, sizes, guess_heavy, fill_tents);
+criterion_main!(benches);
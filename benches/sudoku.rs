@@ -1,4 +1,36 @@
-use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::fs;
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use puzzles::sudoku::{self, Board, SolveState};
+
+/// How many sudokus of each set to solve.
+const SAMPLE: usize = 10;
+
+const SETS: [&str; 7] = [
+    "qqwing_simple",
+    "qqwing_easy",
+    "qqwing_intermediate",
+    "qqwing_expert",
+    "easy50",
+    "top95",
+    "hardest",
+];
+
+/// Reads `SAMPLE` sudokus of the set, spread evenly through it.
+fn sample(name: &str) -> Vec<Board> {
+    let path = format!(
+        "{}/data/sudoku/grids/{name}.txt",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let set = fs::read_to_string(path).unwrap();
+    let lines = set.lines().collect::<Vec<_>>();
+    let step = lines.len().div_ceil(SAMPLE).max(1);
+    lines
+        .into_iter()
+        .step_by(step)
+        .map(|line| Board::from_line(line, '.').unwrap())
+        .collect()
+}
 
 fn insane(c: &mut Criterion) {
     let board_line = include_str!("../data/sudoku/grids/insane.txt");
@@ -9,5 +41,51 @@ fn insane(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, insane);
+/// Solves a sample of each grid set, so that changes can be compared on easy and hard sudokus alike.
+fn sets(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sets");
+    group.sample_size(10);
+    for name in SETS {
+        let boards = sample(name);
+        group.bench_with_input(BenchmarkId::new("solve", name), &boards, |b, boards| {
+            b.iter(|| {
+                for board in boards {
+                    sudoku::solve(board).unwrap();
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+/// The parts of a solver step on their own, starting from the first sudoku of the hardest set.
+fn steps(c: &mut Criterion) {
+    let state = SolveState::from_board(&sample("hardest")[0]);
+    c.bench_function("restrict_groups", |b| {
+        b.iter_batched(
+            || state.clone(),
+            |mut state| state.restrict_groups().unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+
+    // Box/line reductions are only looked for once restricting the groups changes nothing more.
+    let mut restricted = state.clone();
+    loop {
+        let before = restricted.clone();
+        restricted.restrict_groups().unwrap();
+        if restricted == before {
+            break;
+        }
+    }
+    c.bench_function("box_line_reductions", |b| {
+        b.iter_batched(
+            || restricted.clone(),
+            |mut state| state.box_line_reductions().unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+criterion_group!(benches, insane, sets, steps);
 criterion_main!(benches);
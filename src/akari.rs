@@ -0,0 +1,26 @@
+mod board;
+pub use board::{Board, Cell};
+mod solver;
+pub use solver::{count_solutions, solve, Solution};
+
+/// Akari puzzles as a [`Puzzle`](crate::puzzle::Puzzle), in the text format of [`Board::parse`].
+pub struct Akari;
+
+impl crate::puzzle::Puzzle for Akari {
+    const NAME: &'static str = "akari puzzle";
+
+    type Instance = Board;
+    type Solution = Solution;
+
+    fn parse(input: &str) -> crate::Result<Board> {
+        Board::parse(input)
+    }
+
+    fn solve(board: &Board) -> crate::Result<Option<Solution>> {
+        Ok(solve(board))
+    }
+
+    fn count_solutions(board: &Board, limit: usize) -> crate::Result<usize> {
+        Ok(count_solutions(board, limit))
+    }
+}
@@ -0,0 +1,132 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cell {
+    Open,
+    /// A wall, possibly with the number of bulbs that must be placed next to it.
+    Wall(Option<u8>),
+}
+
+impl Cell {
+    pub fn to_char(self) -> char {
+        match self {
+            Cell::Open => '.',
+            Cell::Wall(None) => '#',
+            Cell::Wall(Some(number)) => (b'0' + number) as char,
+        }
+    }
+}
+
+/// An akari (light up) puzzle: place bulbs in open cells such that every open cell is lit,
+/// no bulb lights another bulb, and every numbered wall has exactly that many bulbs next to it.
+/// A bulb lights every cell in its row and column up to the nearest wall.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    cells: Grid<Cell>,
+}
+
+impl Board {
+    pub fn new(cells: Grid<Cell>) -> Result<Self> {
+        let (height, width) = cells.dim();
+        ensure!(
+            height > 0 && width > 0,
+            Invalid,
+            "Akari grids must have at least one cell."
+        );
+        if let Some(number) = cells.iter().find_map(|&cell| match cell {
+            Cell::Wall(Some(number)) if number > 4 => Some(number),
+            _ => None,
+        }) {
+            bail!(Invalid, "Wall numbers must be at most 4, but got {number}.");
+        }
+        Ok(Self { cells })
+    }
+
+    /// Parses a grid with `.` for open cells, `#` for walls and digits for numbered walls.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        let mut cells = Grid::from_elem((height, width), Cell::Open);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.chars().count() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} characters in row {row_index}. Got '{row}'."
+                );
+            }
+            for (col_index, c) in row.chars().enumerate() {
+                cells[Location::new(row_index, col_index)] = match c {
+                    '.' => Cell::Open,
+                    '#' | 'X' | 'x' => Cell::Wall(None),
+                    _ => Cell::Wall(Some(c.to_digit(10).ok_or_else(|| {
+                        Error::Parse(format!(
+                            "Invalid character '{c}' at ({row_index}, {col_index})."
+                        ))
+                    })? as u8)),
+                };
+            }
+        }
+        Self::new(cells)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("akari", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.cells.dim()
+    }
+
+    pub fn cell(&self, location: Location) -> Cell {
+        self.cells[location]
+    }
+
+    pub fn is_open(&self, location: Location) -> bool {
+        self.cell(location) == Cell::Open
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.cells.rows() {
+            writeln!(f, "{}", row.map(|cell| cell.to_char()).collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_walls_and_numbers() {
+        let board = Board::parse(include_str!("../../data/akari/puzzles/ten01.txt")).unwrap();
+        assert_eq!(board.dim(), (10, 10));
+        assert_eq!(board.cell(Location::new(0, 1)), Cell::Wall(None));
+        assert_eq!(board.cell(Location::new(1, 4)), Cell::Wall(Some(2)));
+        assert!(board.is_open(Location::new(0, 0)));
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        assert_eq!(
+            Board::parse("x.\n.X\n").unwrap(),
+            Board::parse("#.\n.#\n").unwrap()
+        );
+        assert!(Board::parse("5.\n..\n").is_err());
+        assert!(Board::parse("..\n.\n").is_err());
+        assert!(Board::parse(".?\n..\n").is_err());
+    }
+}
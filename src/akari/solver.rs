@@ -0,0 +1,228 @@
+use std::fmt::Display;
+
+use crate::{
+    grid::Grid,
+    location::{Direction, Location},
+    search::{self, Contradiction, SearchState},
+    shading::{Shade, ShadeGrid},
+};
+
+use super::{Board, Cell};
+
+/// The open cells each open cell can see, shared between all search states.
+struct Sight {
+    seen: Grid<Vec<Location>>,
+}
+
+impl Sight {
+    fn new(board: &Board) -> Self {
+        let dim = board.dim();
+        let seen = Grid::from_fn(dim, |loc| {
+            if !board.is_open(loc) {
+                return vec![];
+            }
+            Direction::ALL
+                .into_iter()
+                .flat_map(|direction| {
+                    loc.ray(direction, dim)
+                        .take_while(|&other| board.is_open(other))
+                })
+                .collect()
+        });
+        Self { seen }
+    }
+
+    fn seen(&self, location: Location) -> &[Location] {
+        &self.seen[location]
+    }
+}
+
+/// The bulbs of a solved puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    cells: Grid<Cell>,
+    bulbs: Grid<bool>,
+}
+
+impl Solution {
+    pub fn is_bulb(&self, location: Location) -> bool {
+        self.bulbs[location]
+    }
+}
+
+/// Walls are shown as in the puzzle, bulbs as `*` and lit cells as `.`.
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (cells, bulbs) in self.cells.rows().zip(self.bulbs.rows()) {
+            for (&cell, &bulb) in cells.zip(bulbs) {
+                write!(f, "{}", if bulb { '*' } else { cell.to_char() })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Bulbs are shaded. Walls are unshaded from the start.
+#[derive(Clone)]
+struct State<'a> {
+    board: &'a Board,
+    sight: &'a Sight,
+    grid: ShadeGrid,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board, sight: &'a Sight) -> Self {
+        let mut grid = ShadeGrid::new(board.dim());
+        for loc in Location::grid_iter(board.dim()) {
+            if !board.is_open(loc) {
+                grid.set(loc, Shade::Unshaded)
+                    .expect("Only walls have been set.");
+            }
+        }
+        Self { board, sight, grid }
+    }
+
+    /// Every numbered wall has exactly that many bulbs next to it.
+    fn apply_numbers(&mut self) -> Result<bool, Contradiction> {
+        let dim = self.grid.dim();
+        let mut changed = false;
+        for loc in Location::grid_iter(dim) {
+            if let Cell::Wall(Some(number)) = self.board.cell(loc) {
+                let adjacents = loc.adjacents(dim).into_iter().flatten().collect::<Vec<_>>();
+                changed |= self.grid.apply_count(&adjacents, number as usize)?;
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Blocks every cell a bulb can see, and places a bulb where it is the only way left to light a cell.
+    fn apply_lighting(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for loc in Location::grid_iter(self.grid.dim()) {
+            if !self.board.is_open(loc) {
+                continue;
+            }
+            if self.grid.is_shaded(loc) {
+                for &other in self.sight.seen(loc) {
+                    changed |= self.grid.set(other, Shade::Unshaded)?;
+                }
+                continue;
+            }
+            if self.is_lit(loc) {
+                continue;
+            }
+            match self.candidates(loc).collect::<Vec<_>>()[..] {
+                [] => return Err(Contradiction),
+                [candidate] => changed |= self.grid.set(candidate, Shade::Shaded)?,
+                _ => {}
+            }
+        }
+        Ok(changed)
+    }
+
+    fn is_lit(&self, location: Location) -> bool {
+        self.grid.is_shaded(location)
+            || self
+                .sight
+                .seen(location)
+                .iter()
+                .any(|&other| self.grid.is_shaded(other))
+    }
+
+    /// The undecided cells where a bulb would light `location`.
+    fn candidates(&self, location: Location) -> impl Iterator<Item = Location> + '_ {
+        std::iter::once(location)
+            .chain(self.sight.seen(location).iter().copied())
+            .filter(|&loc| self.grid.get(loc) == Shade::Unknown)
+    }
+
+    fn into_solution(self) -> Solution {
+        Solution {
+            cells: Grid::from_fn(self.board.dim(), |loc| self.board.cell(loc)),
+            bulbs: self.grid.cells().map(|&shade| shade == Shade::Shaded),
+        }
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.apply_numbers()?;
+            changed |= self.apply_lighting()?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        // Branch on a bulb for the unlit cell with the fewest ways left to light it.
+        let location = Location::grid_iter(self.grid.dim())
+            .filter(|&loc| self.board.is_open(loc) && !self.is_lit(loc))
+            .min_by_key(|&loc| self.candidates(loc).count())
+            .and_then(|loc| self.candidates(loc).next())
+            .or_else(|| self.grid.first_unknown());
+        let Some(location) = location else {
+            return vec![];
+        };
+        self.grid
+            .branch_on(location)
+            .into_iter()
+            .map(|grid| Self {
+                grid,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+pub fn solve(board: &Board) -> Option<Solution> {
+    let sight = Sight::new(board);
+    search::solve(State::new(board, &sight)).map(State::into_solution)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    let sight = Sight::new(board);
+    search::count_solutions(State::new(board, &sight), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follows_rules(board: &Board, solution: &Solution) -> bool {
+        let dim = board.dim();
+        let sees_bulbs = |loc: Location| {
+            Direction::ALL
+                .into_iter()
+                .flat_map(|direction| {
+                    loc.ray(direction, dim)
+                        .take_while(|&other| board.is_open(other))
+                })
+                .filter(|&other| solution.is_bulb(other))
+                .count()
+        };
+        Location::grid_iter(dim).all(|loc| match board.cell(loc) {
+            Cell::Open if solution.is_bulb(loc) => sees_bulbs(loc) == 0,
+            Cell::Open => sees_bulbs(loc) > 0,
+            Cell::Wall(number) => {
+                let adjacent = loc
+                    .adjacents(dim)
+                    .into_iter()
+                    .flatten()
+                    .filter(|&adj| solution.is_bulb(adj))
+                    .count();
+                !solution.is_bulb(loc) && number.is_none_or(|number| adjacent == number as usize)
+            }
+        })
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/akari/puzzles/ten01.txt")).unwrap();
+        let solution = solve(&board).unwrap();
+        assert!(follows_rules(&board, &solution));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
@@ -0,0 +1,30 @@
+mod board;
+pub use board::Board;
+mod solver;
+pub use solver::{count_solutions, solve};
+
+/// Aquarium puzzles as a [`Puzzle`](crate::puzzle::Puzzle), in the text format of [`Board::parse`].
+pub struct Aquarium;
+
+impl crate::puzzle::Puzzle for Aquarium {
+    const NAME: &'static str = "aquarium puzzle";
+
+    type Instance = Board;
+    type Solution = crate::shading::ShadeGrid;
+
+    fn parse(input: &str) -> crate::Result<Board> {
+        Board::parse(input)
+    }
+
+    fn parse_url(url: &str) -> crate::Result<Board> {
+        Board::from_puzz_link(url)
+    }
+
+    fn solve(board: &Board) -> crate::Result<Option<crate::shading::ShadeGrid>> {
+        Ok(solve(board))
+    }
+
+    fn count_solutions(board: &Board, limit: usize) -> crate::Result<usize> {
+        Ok(count_solutions(board, limit))
+    }
+}
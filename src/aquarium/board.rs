@@ -0,0 +1,182 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+    puzzlink::{self, Clue, PuzzLink},
+    region,
+};
+
+/// An aquarium puzzle: a grid divided into tanks that must be filled with water. Water in a tank settles at a
+/// single level, filling every cell of the tank at or below it, and the numbers outside the grid say how many
+/// cells of each row and column are filled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    tanks: Grid<usize>,
+    tank_cells: Vec<Vec<Location>>,
+    row_counts: Vec<Option<usize>>,
+    col_counts: Vec<Option<usize>>,
+}
+
+impl Board {
+    pub fn new(
+        tanks: Grid<usize>,
+        num_tanks: usize,
+        row_counts: Vec<Option<usize>>,
+        col_counts: Vec<Option<usize>>,
+    ) -> Result<Self> {
+        let (height, width) = tanks.dim();
+        ensure!(
+            height > 0 && width > 0,
+            Invalid,
+            "Aquarium grids must have at least one cell."
+        );
+        ensure!(
+            row_counts.len() == height && col_counts.len() == width,
+            Invalid,
+            "Expected {height} row counts and {width} column counts, but got {} and {}.",
+            row_counts.len(),
+            col_counts.len()
+        );
+        ensure!(
+            row_counts.iter().flatten().all(|&count| count <= width)
+                && col_counts.iter().flatten().all(|&count| count <= height),
+            Invalid,
+            "Counts cannot be larger than the number of cells in their line."
+        );
+        let mut tank_cells = vec![vec![]; num_tanks];
+        for loc in Location::grid_iter((height, width)) {
+            let tank = tanks[loc];
+            ensure!(
+                tank < num_tanks,
+                Invalid,
+                "Cell {loc} is in tank {tank}, but there are only {num_tanks} tanks."
+            );
+            tank_cells[tank].push(loc);
+        }
+        Ok(Self {
+            tanks,
+            tank_cells,
+            row_counts,
+            col_counts,
+        })
+    }
+
+    /// Parses a puzzle given as a line of row counts and a line of column counts, separated by commas with `?`
+    /// for a line without a count, followed by a grid of characters where each distinct character is a tank.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let mut lines = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty());
+        let mut counts = |name: &str| -> Result<Vec<Option<usize>>> {
+            let line = lines
+                .next()
+                .ok_or_else(|| Error::Parse(format!("No line of {name}.")))?;
+            line.split(',')
+                .map(|count| match count.trim() {
+                    "?" => Ok(None),
+                    count => count.parse::<usize>().map(Some),
+                })
+                .collect::<Result<_, _>>()
+                .map_err(|_| {
+                    Error::Parse(format!(
+                        "Expected {name} separated by commas. Got '{line}'."
+                    ))
+                })
+        };
+        let row_counts = counts("row counts")?;
+        let col_counts = counts("column counts")?;
+        let rows = lines.collect::<Vec<_>>();
+        let (tanks, num_tanks) = region::parse_regions(&rows)?;
+        Self::new(tanks, num_tanks, row_counts, col_counts)
+    }
+
+    /// Imports a puzzle from a puzz.link or pzv.jp URL.
+    pub fn from_puzz_link(url: &str) -> Result<Self> {
+        let link = PuzzLink::parse(url)?;
+        link.expect_kind(&["aquarium"])?;
+        let (tanks, num_tanks, rest) = puzzlink::decode_rooms(&link.body, link.width, link.height)?;
+        // The numbers above the columns come before the numbers left of the rows.
+        let counts = puzzlink::decode_number16(rest, link.width + link.height)?
+            .into_iter()
+            .map(|clue| match clue {
+                None | Some(Clue::Unknown) => None,
+                Some(Clue::Number(number)) => Some(number as usize),
+            })
+            .collect::<Vec<_>>();
+        let (col_counts, row_counts) = counts.split_at(link.width);
+        Self::new(tanks, num_tanks, row_counts.to_vec(), col_counts.to_vec())
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("aquarium", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.tanks.dim()
+    }
+
+    pub fn tank(&self, location: Location) -> usize {
+        self.tanks[location]
+    }
+
+    /// The cells of each tank.
+    pub fn tanks(&self) -> &[Vec<Location>] {
+        &self.tank_cells
+    }
+
+    pub fn row_counts(&self) -> &[Option<usize>] {
+        &self.row_counts
+    }
+
+    pub fn col_counts(&self) -> &[Option<usize>] {
+        &self.col_counts
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const NAMES: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+        let counts = |counts: &[Option<usize>]| {
+            counts
+                .iter()
+                .map(|count| count.map_or("?".to_string(), |count| count.to_string()))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        writeln!(f, "{}", counts(&self.row_counts))?;
+        writeln!(f, "{}", counts(&self.col_counts))?;
+        for row in self.tanks.rows() {
+            let row = row
+                .map(|&tank| NAMES[tank % NAMES.len()] as char)
+                .collect::<String>();
+            writeln!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_text_and_puzz_links() {
+        let board = Board::parse(include_str!("../../data/aquarium/puzzles/eight01.txt")).unwrap();
+        assert_eq!(board.dim(), (8, 8));
+        assert_eq!(board.tanks().len(), 16);
+        assert_eq!(board.row_counts()[..2], [None, Some(2)]);
+        assert_eq!(board.col_counts()[0], Some(7));
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        let link = "https://puzz.link/p?aquarium/8/8/ergsvmsnoi4g480siobmt80076535465g26684h";
+        assert_eq!(Board::from_puzz_link(link).unwrap(), board);
+        assert!(Board::parse("1,1\n1,1\nAB\n").is_err());
+        assert!(Board::parse("3,1\n1,1\nAB\nAB\n").is_err());
+        assert!(Board::parse("1,x\n1,1\nAB\nAB\n").is_err());
+    }
+}
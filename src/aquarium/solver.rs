@@ -0,0 +1,163 @@
+use std::ops::RangeInclusive;
+
+use crate::{
+    location::Location,
+    search::{self, Contradiction, SearchState},
+    shading::{self, Shade, ShadeGrid},
+};
+
+use super::Board;
+
+/// Water is shaded.
+#[derive(Clone, Debug)]
+struct State<'a> {
+    board: &'a Board,
+    lines: &'a [(Vec<Location>, usize)],
+    grid: ShadeGrid,
+    /// The rows the water surface of each tank can still be at, where a surface one below the bottom of the tank
+    /// means the tank is empty.
+    levels: Vec<RangeInclusive<usize>>,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board, lines: &'a [(Vec<Location>, usize)]) -> Self {
+        let levels = board
+            .tanks()
+            .iter()
+            .map(|cells| {
+                let top = cells.iter().map(|loc| loc.row).min().unwrap_or(0);
+                let bottom = cells.iter().map(|loc| loc.row).max().unwrap_or(0);
+                top..=bottom + 1
+            })
+            .collect();
+        Self {
+            board,
+            lines,
+            grid: ShadeGrid::new(board.dim()),
+            levels,
+        }
+    }
+
+    /// Water fills every cell of its tank at or below the surface, so a wet cell lowers the highest possible
+    /// surface to its row and a dry cell raises the lowest possible surface to below it.
+    /// Cells below the highest and above the lowest possible surface are then decided.
+    fn apply_levels(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for (tank, cells) in self.board.tanks().iter().enumerate() {
+            let (mut top, mut bottom) = self.levels[tank].clone().into_inner();
+            for &loc in cells {
+                match self.grid.get(loc) {
+                    Shade::Shaded => bottom = bottom.min(loc.row),
+                    Shade::Unshaded => top = top.max(loc.row + 1),
+                    Shade::Unknown => {}
+                }
+            }
+            if top > bottom {
+                return Err(Contradiction);
+            }
+            changed |= self.levels[tank] != (top..=bottom);
+            self.levels[tank] = top..=bottom;
+            for &loc in cells {
+                if loc.row >= bottom {
+                    changed |= self.grid.set(loc, Shade::Shaded)?;
+                } else if loc.row < top {
+                    changed |= self.grid.set(loc, Shade::Unshaded)?;
+                }
+            }
+        }
+        Ok(changed)
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.apply_levels()?;
+            for (line, count) in self.lines {
+                changed |= self.grid.apply_count(line, *count)?;
+            }
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(tank) = (0..self.levels.len())
+            .filter(|&tank| self.levels[tank].start() < self.levels[tank].end())
+            .min_by_key(|&tank| self.levels[tank].end() - self.levels[tank].start())
+        else {
+            return vec![];
+        };
+        self.levels[tank]
+            .clone()
+            .map(|level| {
+                let mut state = self.clone();
+                state.levels[tank] = level..=level;
+                state
+            })
+            .collect()
+    }
+}
+
+fn lines(board: &Board) -> Vec<(Vec<Location>, usize)> {
+    shading::counted_lines(board.row_counts(), board.col_counts())
+}
+
+/// Returns the solved grid with water as `#` and air as `.`, or `None` if the puzzle has no solution.
+pub fn solve(board: &Board) -> Option<ShadeGrid> {
+    let lines = lines(board);
+    search::solve(State::new(board, &lines)).map(|state| state.grid)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    let lines = lines(board);
+    search::count_solutions(State::new(board, &lines), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follows_rules(board: &Board, grid: &ShadeGrid) -> bool {
+        let (height, width) = board.dim();
+        let rows = (0..height).all(|row| {
+            board.row_counts()[row].is_none_or(|count| {
+                (0..width)
+                    .filter(|&col| grid.is_shaded(Location::new(row, col)))
+                    .count()
+                    == count
+            })
+        });
+        let cols = (0..width).all(|col| {
+            board.col_counts()[col].is_none_or(|count| {
+                (0..height)
+                    .filter(|&row| grid.is_shaded(Location::new(row, col)))
+                    .count()
+                    == count
+            })
+        });
+        // Every cell of a tank at or below a cell with water has water too.
+        let levels = board.tanks().iter().all(|cells| {
+            cells
+                .iter()
+                .filter(|&&loc| grid.is_shaded(loc))
+                .all(|water| {
+                    cells
+                        .iter()
+                        .filter(|loc| loc.row >= water.row)
+                        .all(|&loc| grid.is_shaded(loc))
+                })
+        });
+        rows && cols && levels
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/aquarium/puzzles/eight01.txt")).unwrap();
+        let grid = solve(&board).unwrap();
+        assert!(grid.is_complete());
+        assert!(follows_rules(&board, &grid));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
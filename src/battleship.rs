@@ -0,0 +1,26 @@
+mod board;
+pub use board::{Board, Segment};
+mod solver;
+pub use solver::{count_solutions, solve, Solution};
+
+/// Battleship puzzles as a [`Puzzle`](crate::puzzle::Puzzle), in the text format of [`Board::parse`].
+pub struct Battleship;
+
+impl crate::puzzle::Puzzle for Battleship {
+    const NAME: &'static str = "battleship puzzle";
+
+    type Instance = Board;
+    type Solution = Solution;
+
+    fn parse(input: &str) -> crate::Result<Board> {
+        Board::parse(input)
+    }
+
+    fn solve(board: &Board) -> crate::Result<Option<Solution>> {
+        Ok(solve(board))
+    }
+
+    fn count_solutions(board: &Board, limit: usize) -> crate::Result<usize> {
+        Ok(count_solutions(board, limit))
+    }
+}
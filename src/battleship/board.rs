@@ -0,0 +1,220 @@
+use std::{fmt::Display, path};
+
+use itertools::Itertools;
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::{Direction, Location},
+};
+
+/// What a cell of a battleship grid holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Segment {
+    Water,
+    /// A ship of length one.
+    Single,
+    /// A segment with ship on both sides along the ship.
+    Middle,
+    /// The end of a ship that continues in the given direction.
+    End(Direction),
+}
+
+impl Segment {
+    pub fn to_char(self) -> char {
+        match self {
+            Segment::Water => '~',
+            Segment::Single => 'o',
+            Segment::Middle => 'm',
+            Segment::End(Direction::Right) => '<',
+            Segment::End(Direction::Left) => '>',
+            Segment::End(Direction::Down) => '^',
+            Segment::End(Direction::Up) => 'v',
+        }
+    }
+
+    pub fn from_char(c: char) -> Option<Self> {
+        Some(match c {
+            '~' | 'w' => Segment::Water,
+            'o' => Segment::Single,
+            'm' => Segment::Middle,
+            '<' => Segment::End(Direction::Right),
+            '>' => Segment::End(Direction::Left),
+            '^' => Segment::End(Direction::Down),
+            'v' => Segment::End(Direction::Up),
+            _ => return None,
+        })
+    }
+}
+
+/// A battleship puzzle: place a fleet of straight ships in the grid such that ships do not touch, not even
+/// diagonally, every row and column contains as many ship segments as its count, and every clue is respected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    row_counts: Vec<usize>,
+    col_counts: Vec<usize>,
+    /// The length of every ship of the fleet, longest first.
+    fleet: Vec<usize>,
+    clues: Grid<Option<Segment>>,
+}
+
+impl Board {
+    pub fn new(
+        row_counts: Vec<usize>,
+        col_counts: Vec<usize>,
+        mut fleet: Vec<usize>,
+        clues: Grid<Option<Segment>>,
+    ) -> Result<Self> {
+        let (height, width) = clues.dim();
+        ensure!(
+            height > 0 && width > 0,
+            Invalid,
+            "Battleship grids must have at least one cell."
+        );
+        ensure!(
+            row_counts.len() == height && col_counts.len() == width,
+            Invalid,
+            "Expected {height} row counts and {width} column counts, but got {} and {}.",
+            row_counts.len(),
+            col_counts.len()
+        );
+        ensure!(
+            fleet.iter().all(|&length| length > 0),
+            Invalid,
+            "Ships must have a positive length."
+        );
+        let (rows, cols, ships) = (
+            row_counts.iter().sum::<usize>(),
+            col_counts.iter().sum::<usize>(),
+            fleet.iter().sum::<usize>(),
+        );
+        ensure!(
+            rows == ships && cols == ships, Invalid,
+            "The fleet has {ships} segments, but the row counts add up to {rows} and the column counts to {cols}."
+        );
+        fleet.sort_by(|a, b| b.cmp(a));
+        Ok(Self {
+            row_counts,
+            col_counts,
+            fleet,
+            clues,
+        })
+    }
+
+    /// Parses a puzzle given as a line of row counts, a line of column counts and a line of ship lengths,
+    /// all separated by commas, followed by a grid of clues. Clues are `~` for water, `o` for a ship of length one,
+    /// `m` for the middle of a ship and `<`, `>`, `^` and `v` for the ends of ships, with `.` for cells without a clue.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let mut lines = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty());
+        let mut numbers = |name: &str| -> Result<Vec<usize>> {
+            let line = lines
+                .next()
+                .ok_or_else(|| Error::Parse(format!("No line of {name}.")))?;
+            line.split(',')
+                .map(|number| number.trim().parse::<usize>())
+                .try_collect()
+                .map_err(|_| {
+                    Error::Parse(format!(
+                        "Expected {name} separated by commas. Got '{line}'."
+                    ))
+                })
+        };
+        let row_counts = numbers("row counts")?;
+        let col_counts = numbers("column counts")?;
+        let fleet = numbers("ship lengths")?;
+        let rows = lines.collect::<Vec<_>>();
+        let width = col_counts.len();
+        let mut clues = Grid::from_elem((rows.len(), width), None);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.chars().count() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} characters in row {row_index}. Got '{row}'."
+                );
+            }
+            for (col_index, c) in row.chars().enumerate() {
+                clues[Location::new(row_index, col_index)] = match c {
+                    '.' => None,
+                    _ => Some(Segment::from_char(c).ok_or_else(|| {
+                        Error::Parse(format!(
+                            "Invalid character '{c}' at ({row_index}, {col_index})."
+                        ))
+                    })?),
+                };
+            }
+        }
+        Self::new(row_counts, col_counts, fleet, clues)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("battleship", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.clues.dim()
+    }
+
+    pub fn row_counts(&self) -> &[usize] {
+        &self.row_counts
+    }
+
+    pub fn col_counts(&self) -> &[usize] {
+        &self.col_counts
+    }
+
+    pub fn fleet(&self) -> &[usize] {
+        &self.fleet
+    }
+
+    pub fn clue(&self, location: Location) -> Option<Segment> {
+        self.clues[location]
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.row_counts.iter().join(","))?;
+        writeln!(f, "{}", self.col_counts.iter().join(","))?;
+        writeln!(f, "{}", self.fleet.iter().join(","))?;
+        for row in self.clues.rows() {
+            for &clue in row {
+                write!(f, "{}", clue.map_or('.', Segment::to_char))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_counts_fleet_and_clues() {
+        let board = Board::parse(include_str!("../../data/battleship/puzzles/ten01.txt")).unwrap();
+        assert_eq!(board.dim(), (10, 10));
+        assert_eq!(board.row_counts()[1], 6);
+        assert_eq!(board.col_counts()[0], 3);
+        assert_eq!(board.fleet(), [4, 3, 3, 2, 2, 2, 1, 1, 1, 1]);
+        assert_eq!(board.clue(Location::new(3, 4)), Some(Segment::Single));
+        assert_eq!(board.clue(Location::new(5, 0)), Some(Segment::Water));
+        assert_eq!(board.clue(Location::new(0, 0)), None);
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        let ends = Board::parse("0,3\n1,1,1\n3\n...\n<m>\n").unwrap();
+        assert_eq!(
+            ends.clue(Location::new(1, 0)),
+            Some(Segment::End(Direction::Right))
+        );
+        assert_eq!(ends.clue(Location::new(1, 1)), Some(Segment::Middle));
+        assert!(Board::parse("0,3\n1,1,1\n2\n...\n...\n").is_err());
+        assert!(Board::parse("0,3\n1,1,1\n3\n...\n.x.\n").is_err());
+        assert!(Board::parse("0,3\n1,1,1\n").is_err());
+    }
+}
@@ -0,0 +1,354 @@
+use std::fmt::Display;
+
+use crate::{
+    grid::Grid,
+    location::{Direction, Location},
+    region,
+    search::{self, Contradiction, SearchState},
+    shading::{self, Shade, ShadeGrid},
+};
+
+use super::{Board, Segment};
+
+/// The ships of a solved puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    ships: Grid<bool>,
+}
+
+impl Solution {
+    pub fn is_ship(&self, location: Location) -> bool {
+        self.ships[location]
+    }
+
+    /// The segment at `location`, working out the shape of ships from their neighbours.
+    pub fn segment(&self, location: Location) -> Segment {
+        if !self.is_ship(location) {
+            return Segment::Water;
+        }
+        let continues = Direction::ALL
+            .into_iter()
+            .filter(|&direction| {
+                location
+                    .step(direction, self.ships.dim())
+                    .is_some_and(|next| self.is_ship(next))
+            })
+            .collect::<Vec<_>>();
+        match continues[..] {
+            [] => Segment::Single,
+            [direction] => Segment::End(direction),
+            _ => Segment::Middle,
+        }
+    }
+}
+
+/// Water is shown as `.` and ships by their segments, as in the clues.
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for loc in Location::grid_iter(self.ships.dim()) {
+            let c = match self.segment(loc) {
+                Segment::Water => '.',
+                segment => segment.to_char(),
+            };
+            write!(f, "{c}")?;
+            if loc.col + 1 == self.ships.width() {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Ship segments are shaded and water is unshaded.
+#[derive(Clone)]
+struct State<'a> {
+    board: &'a Board,
+    lines: &'a [(Vec<Location>, usize)],
+    grid: ShadeGrid,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board, lines: &'a [(Vec<Location>, usize)]) -> Self {
+        Self {
+            board,
+            lines,
+            grid: ShadeGrid::new(board.dim()),
+        }
+    }
+
+    /// Sets the neighbour of `location` in `direction`, where cells outside the grid count as water.
+    fn set_next(
+        &mut self,
+        location: Location,
+        direction: Direction,
+        shade: Shade,
+    ) -> Result<bool, Contradiction> {
+        match location.step(direction, self.grid.dim()) {
+            Some(next) => Ok(self.grid.set(next, shade)?),
+            None if shade == Shade::Unshaded => Ok(false),
+            None => Err(Contradiction),
+        }
+    }
+
+    /// The shade of the neighbour of `location` in `direction`, where cells outside the grid count as water.
+    fn next(&self, location: Location, direction: Direction) -> Shade {
+        location
+            .step(direction, self.grid.dim())
+            .map_or(Shade::Unshaded, |next| self.grid.get(next))
+    }
+
+    /// Makes the ship through `location` run along the horizontal or vertical axis.
+    fn set_axis(&mut self, location: Location, horizontal: bool) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for direction in Direction::ALL {
+            let shade = if direction.is_horizontal() == horizontal {
+                Shade::Shaded
+            } else {
+                Shade::Unshaded
+            };
+            changed |= self.set_next(location, direction, shade)?;
+        }
+        Ok(changed)
+    }
+
+    fn apply_clues(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for loc in Location::grid_iter(self.grid.dim()) {
+            let Some(clue) = self.board.clue(loc) else {
+                continue;
+            };
+            if clue == Segment::Water {
+                changed |= self.grid.set(loc, Shade::Unshaded)?;
+                continue;
+            }
+            changed |= self.grid.set(loc, Shade::Shaded)?;
+            match clue {
+                Segment::Single => {
+                    for direction in Direction::ALL {
+                        changed |= self.set_next(loc, direction, Shade::Unshaded)?;
+                    }
+                }
+                Segment::End(end) => {
+                    for direction in Direction::ALL {
+                        let shade = if direction == end {
+                            Shade::Shaded
+                        } else {
+                            Shade::Unshaded
+                        };
+                        changed |= self.set_next(loc, direction, shade)?;
+                    }
+                }
+                Segment::Middle => {
+                    let along = |horizontal: bool| {
+                        Direction::ALL
+                            .into_iter()
+                            .filter(move |direction| direction.is_horizontal() == horizontal)
+                    };
+                    let blocked = |horizontal| {
+                        along(horizontal)
+                            .any(|direction| self.next(loc, direction) == Shade::Unshaded)
+                    };
+                    let extended = |horizontal| {
+                        along(horizontal)
+                            .any(|direction| self.next(loc, direction) == Shade::Shaded)
+                    };
+                    if blocked(true) || extended(false) {
+                        changed |= self.set_axis(loc, false)?;
+                    } else if blocked(false) || extended(true) {
+                        changed |= self.set_axis(loc, true)?;
+                    }
+                }
+                Segment::Water => unreachable!("Water is handled above."),
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Ships cannot touch diagonally, which also keeps them straight.
+    fn apply_diagonals(&mut self) -> Result<bool, Contradiction> {
+        let dim = self.grid.dim();
+        let mut changed = false;
+        for loc in Location::grid_iter(dim) {
+            if !self.grid.is_shaded(loc) {
+                continue;
+            }
+            let adjacents = loc.adjacents(dim);
+            for neighbor in loc.neighbors(dim).into_iter().flatten() {
+                if !adjacents.contains(&Some(neighbor)) {
+                    changed |= self.grid.set(neighbor, Shade::Unshaded)?;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Matches the ships found so far against the fleet. A finished ship must have a length the fleet still has,
+    /// and an unfinished ship must be able to grow into one. An unfinished ship as long as the longest
+    /// ship it can still become is finished off with water.
+    fn apply_fleet(&mut self) -> Result<bool, Contradiction> {
+        let dim = self.grid.dim();
+        let ships = region::components(dim, |loc| self.grid.is_shaded(loc)).regions;
+        let longest = self.board.fleet().first().copied().unwrap_or(0);
+        let mut remaining = vec![0usize; longest + 1];
+        for &length in self.board.fleet() {
+            remaining[length] += 1;
+        }
+        let mut unfinished = vec![];
+        for ship in ships {
+            if ship.len() > longest {
+                return Err(Contradiction);
+            }
+            let boundary = region::boundary(dim, &ship);
+            if boundary
+                .iter()
+                .all(|&loc| self.grid.get(loc) == Shade::Unshaded)
+            {
+                remaining[ship.len()] =
+                    remaining[ship.len()].checked_sub(1).ok_or(Contradiction)?;
+            } else {
+                unfinished.push((ship.len(), boundary));
+            }
+        }
+        if self.grid.is_complete() && remaining.iter().any(|&count| count > 0) {
+            return Err(Contradiction);
+        }
+        let mut changed = false;
+        for (length, boundary) in unfinished {
+            let Some(max) = (length..=longest).rev().find(|&max| remaining[max] > 0) else {
+                return Err(Contradiction);
+            };
+            if max == length {
+                for loc in boundary {
+                    changed |= self.grid.set(loc, Shade::Unshaded)?;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    fn into_solution(self) -> Solution {
+        Solution {
+            ships: self.grid.cells().map(|&shade| shade == Shade::Shaded),
+        }
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.apply_clues()?;
+            changed |= self.apply_diagonals()?;
+            for (line, count) in self.lines {
+                changed |= self.grid.apply_count(line, *count)?;
+            }
+            changed |= self.apply_fleet()?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        // Prefer growing a ship, and otherwise the line with the fewest undecided cells.
+        let dim = self.grid.dim();
+        let next_to_ship = Location::grid_iter(dim).find(|&loc| {
+            self.grid.get(loc) == Shade::Unknown
+                && loc
+                    .adjacents(dim)
+                    .into_iter()
+                    .flatten()
+                    .any(|adj| self.grid.is_shaded(adj))
+        });
+        let Some(location) = next_to_ship.or_else(|| {
+            self.lines
+                .iter()
+                .filter_map(|(line, _)| {
+                    let unknowns = line
+                        .iter()
+                        .filter(|&&loc| self.grid.get(loc) == Shade::Unknown)
+                        .collect::<Vec<_>>();
+                    unknowns.first().map(|&&loc| (unknowns.len(), loc))
+                })
+                .min_by_key(|&(len, _)| len)
+                .map(|(_, loc)| loc)
+        }) else {
+            return vec![];
+        };
+        self.grid
+            .branch_on(location)
+            .into_iter()
+            .map(|grid| Self {
+                grid,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+/// Every row and column with its count.
+fn lines(board: &Board) -> Vec<(Vec<Location>, usize)> {
+    let known = |counts: &[usize]| counts.iter().copied().map(Some).collect::<Vec<_>>();
+    shading::counted_lines(&known(board.row_counts()), &known(board.col_counts()))
+}
+
+pub fn solve(board: &Board) -> Option<Solution> {
+    let lines = lines(board);
+    search::solve(State::new(board, &lines)).map(State::into_solution)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    let lines = lines(board);
+    search::count_solutions(State::new(board, &lines), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follows_rules(board: &Board, solution: &Solution) -> bool {
+        let dim = board.dim();
+        let ship = |loc| solution.is_ship(loc);
+        let rows = (0..dim.0).all(|row| {
+            (0..dim.1)
+                .filter(|&col| ship(Location::new(row, col)))
+                .count()
+                == board.row_counts()[row]
+        });
+        let cols = (0..dim.1).all(|col| {
+            (0..dim.0)
+                .filter(|&row| ship(Location::new(row, col)))
+                .count()
+                == board.col_counts()[col]
+        });
+        let clues = Location::grid_iter(dim).all(|loc| {
+            board
+                .clue(loc)
+                .is_none_or(|clue| clue == solution.segment(loc))
+        });
+        let ships = region::components(dim, ship).regions;
+        let straight = ships.iter().all(|cells| {
+            cells.iter().all(|loc| loc.row == cells[0].row)
+                || cells.iter().all(|loc| loc.col == cells[0].col)
+        });
+        // Ships touching diagonally would be separate components with neighbouring cells.
+        let apart = ships.iter().all(|cells| {
+            cells.iter().all(|loc| {
+                loc.neighbors(dim)
+                    .into_iter()
+                    .flatten()
+                    .all(|neighbor| !ship(neighbor) || cells.contains(&neighbor))
+            })
+        });
+        let mut lengths = ships.iter().map(Vec::len).collect::<Vec<_>>();
+        lengths.sort_by(|a, b| b.cmp(a));
+        rows && cols && clues && straight && apart && lengths == board.fleet()
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/battleship/puzzles/ten01.txt")).unwrap();
+        let solution = solve(&board).unwrap();
+        assert!(follows_rules(&board, &solution));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
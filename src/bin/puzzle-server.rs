@@ -0,0 +1,207 @@
+//! An HTTP service for the solvers. Every endpoint takes and returns JSON, and cancels the solver of any request that
+//! takes longer than the timeout or whose client goes away.
+
+use std::{net::SocketAddr, time::Duration};
+
+use anyhow::Result;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use clap::Parser;
+use puzzles::{
+    camping::{self, Camping, Map, SolverConfig},
+    cancel::CancelToken,
+    generate::GeneratorConfig,
+    puzzle::Puzzle,
+    sudoku::{self, Sudoku},
+    Error,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Parser)]
+struct Args {
+    /// Address to listen on.
+    #[arg(long, default_value = "127.0.0.1:3000")]
+    addr: SocketAddr,
+    /// Milliseconds a request may take before its solver is cancelled.
+    #[arg(long, default_value_t = 10_000)]
+    timeout_ms: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SudokuRequest {
+    /// An 81 character line with '.' for empty cells.
+    puzzle: String,
+}
+
+#[derive(Debug, Serialize)]
+struct SudokuSolution {
+    solution: String,
+    steps: u32,
+    guesses: u32,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct GenerateRequest {
+    #[serde(default)]
+    seed: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct GeneratedSudoku {
+    puzzle: String,
+    seed: u64,
+}
+
+/// An error as the response to a request, with a status depending on whose fault it is.
+struct ApiError(StatusCode, String);
+
+impl From<Error> for ApiError {
+    fn from(err: Error) -> Self {
+        let status = match err {
+            Error::Cancelled => StatusCode::REQUEST_TIMEOUT,
+            Error::NoSolution(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Error::Unsupported { .. } => StatusCode::NOT_IMPLEMENTED,
+            Error::Generate(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        Self(status, err.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let Self(status, error) = self;
+        (status, Json(serde_json::json!({ "error": error }))).into_response()
+    }
+}
+
+/// Cancels the token when dropped, which is when the request finishes, times out or is abandoned by the client.
+struct CancelOnDrop(CancelToken);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+/// Runs a solver on the blocking thread pool, cancelling it if it does not finish within the timeout.
+async fn run_solver<T, F>(timeout: Duration, solve: F) -> Result<T, ApiError>
+where
+    T: Send + 'static,
+    F: FnOnce(&CancelToken) -> puzzles::Result<T> + Send + 'static,
+{
+    let cancel = CancelOnDrop(CancelToken::new());
+    let token = cancel.0.clone();
+    let task = tokio::task::spawn_blocking(move || solve(&token));
+    match tokio::time::timeout(timeout, task).await {
+        Ok(Ok(result)) => Ok(result?),
+        Ok(Err(err)) => Err(ApiError(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+        Err(_) => Err(Error::Cancelled.into()),
+    }
+}
+
+async fn solve_sudoku(
+    State(timeout): State<Duration>,
+    Json(request): Json<SudokuRequest>,
+) -> Result<Json<SudokuSolution>, ApiError> {
+    let board = Sudoku::parse(&request.puzzle)?;
+    Sudoku::validate(&board)?;
+    run_solver(timeout, move |cancel| {
+        let (solution, steps, guesses) = sudoku::solve_cancellable(&board, cancel)?;
+        if !solution.finished() {
+            return Err(Error::NoSolution(Sudoku::NAME));
+        }
+        Ok(Json(SudokuSolution {
+            solution: Sudoku::format(&solution),
+            steps,
+            guesses,
+        }))
+    })
+    .await
+}
+
+async fn generate_sudoku(
+    State(timeout): State<Duration>,
+    request: Option<Json<GenerateRequest>>,
+) -> Result<Json<GeneratedSudoku>, ApiError> {
+    let Json(GenerateRequest { seed }) = request.unwrap_or_default();
+    run_solver(timeout, move |cancel| generate(seed, cancel).map(Json)).await
+}
+
+/// Generates the sudoku that `Sudoku::generate` gives for the seed, since its first candidate is always unique.
+fn generate(seed: u64, cancel: &CancelToken) -> puzzles::Result<GeneratedSudoku> {
+    let board = sudoku::generate_cancellable(&mut GeneratorConfig::seeded(seed).rng(), cancel)?;
+    Ok(GeneratedSudoku {
+        puzzle: Sudoku::format_instance(&board),
+        seed,
+    })
+}
+
+/// Takes and returns maps in their JSON form.
+async fn solve_camping(
+    State(timeout): State<Duration>,
+    Json(map): Json<Map>,
+) -> Result<Json<Map>, ApiError> {
+    Camping::validate(&map)?;
+    run_solver(timeout, move |cancel| {
+        camping::solve_cancellable(&map, &SolverConfig::default(), cancel)?
+            .map(Json)
+            .ok_or(Error::NoSolution(Camping::NAME))
+    })
+    .await
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let app = Router::new()
+        .route("/sudoku/solve", post(solve_sudoku))
+        .route("/sudoku/generate", post(generate_sudoku))
+        .route("/camping/solve", post(solve_camping))
+        .with_state(Duration::from_millis(args.timeout_ms));
+    let listener = tokio::net::TcpListener::bind(args.addr).await?;
+    println!("Listening on {}", args.addr);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+
+    #[test]
+    fn generates_as_the_library_does() {
+        let generated = generate(5, &CancelToken::new()).unwrap();
+        let board = Sudoku::generate(&GeneratorConfig::seeded(5)).unwrap();
+        assert_eq!(generated.puzzle, Sudoku::format_instance(&board));
+    }
+
+    #[tokio::test]
+    async fn timed_out_generation_stops() {
+        let (sender, receiver) = mpsc::channel();
+        let result = run_solver(Duration::ZERO, move |cancel| {
+            // Only start once the request has timed out, so that the generator sees the cancellation.
+            while !cancel.is_cancelled() {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            let result = generate(0, cancel);
+            sender
+                .send(matches!(result, Err(Error::Cancelled)))
+                .unwrap();
+            result
+        })
+        .await;
+        assert!(matches!(
+            result,
+            Err(ApiError(StatusCode::REQUEST_TIMEOUT, _))
+        ));
+        assert!(receiver.recv_timeout(Duration::from_secs(10)).unwrap());
+    }
+}
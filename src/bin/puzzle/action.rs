@@ -0,0 +1,314 @@
+//! The actions every game supports, all done through the game's [`Puzzle`] implementation.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use puzzles::{
+    difficulty::Difficulty,
+    generate::GeneratorConfig,
+    puzzle::{self, Puzzle},
+    solutions::SolutionIndex,
+};
+
+use crate::{
+    input::read_input,
+    output::{ensure_solved, OutputArgs, Record},
+    paths::Paths,
+    watch::watch,
+};
+
+#[derive(Clone, Debug, Args)]
+pub struct PuzzleArg {
+    /// Path to the puzzle, `-` for stdin, the puzzle itself, or a puzz.link URL for games that support them.
+    pub puzzle: String,
+}
+
+/// A puzzle to solve or rate, and how to write the result.
+#[derive(Clone, Debug, Args)]
+pub struct SolveArgs {
+    #[command(flatten)]
+    pub puzzle: PuzzleArg,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Clone, Debug, Args)]
+pub struct GenerateArgs {
+    /// The same seed always generates the same puzzle.
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+    /// The size of the puzzle, with a meaning that depends on the game. Chosen by the game by default.
+    #[arg(long)]
+    pub size: Option<usize>,
+    /// Only generate puzzles of this difficulty: easy, medium, hard or expert. Needs a game that rates puzzles.
+    #[arg(long)]
+    pub difficulty: Option<Difficulty>,
+    /// Allow puzzles with more than one solution.
+    #[arg(long)]
+    pub non_unique: bool,
+    /// How many puzzles to generate. Each is identified by the seed that generates it on its own.
+    #[arg(long, default_value_t = 1)]
+    pub count: usize,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+impl GenerateArgs {
+    pub fn config(&self) -> GeneratorConfig {
+        GeneratorConfig {
+            seed: self.seed,
+            size: self.size,
+            difficulty: self.difficulty,
+            unique: !self.non_unique,
+        }
+    }
+}
+
+/// The flags a game adds to `solve`, and how they change solving a puzzle. Without any of them set, puzzles are
+/// solved with [`Puzzle::solve`] and their solutions kept in the solution index.
+pub trait SolveOptions<P: Puzzle>: Args {
+    /// Changes each puzzle before it is solved, such as by adding words to a crossword.
+    fn prepare(&self, instance: P::Instance, _paths: &Paths) -> Result<P::Instance> {
+        Ok(instance)
+    }
+
+    /// Solves a puzzle in the game's own way, or returns `None` to solve it the usual way. Puzzles solved here
+    /// skip the solution index.
+    fn solve(&self, _id: &str, _instance: &P::Instance) -> Option<Result<Vec<Record>>> {
+        None
+    }
+}
+
+/// For games that add no flags to `solve`.
+#[derive(Clone, Debug, Args)]
+pub struct NoOptions {}
+
+impl<P: Puzzle> SolveOptions<P> for NoOptions {}
+
+/// The arguments of `generate` and how a game generates puzzles from them.
+pub trait GenerateCommand<P: Puzzle>: Args {
+    fn generate(self) -> Result<()>;
+}
+
+impl<P: Puzzle> GenerateCommand<P> for GenerateArgs {
+    fn generate(self) -> Result<()> {
+        let records = P::generate_many(&self.config(), self.count)?
+            .into_iter()
+            .map(|(seed, instance)| {
+                Record::generated(seed, P::format_instance(&instance)).with_puzzle::<P>(&instance)
+            })
+            .collect::<Vec<_>>();
+        self.output.write(&records)
+    }
+}
+
+/// The actions every game supports. Games can add flags to `solve` with `S` and replace the arguments of `generate`
+/// with `G`.
+#[derive(Clone, Debug, Subcommand)]
+pub enum Action<S: Args = NoOptions, G: Args = GenerateArgs> {
+    /// Solve a puzzle.
+    Solve {
+        #[command(flatten)]
+        args: SolveArgs,
+        #[command(flatten)]
+        options: S,
+        /// Check the puzzle file again every time it changes, reporting whether it is valid, whether its solution
+        /// is unique and how hard it is.
+        #[arg(long)]
+        watch: bool,
+        /// Neither look up stored solutions nor store new ones in the solution index.
+        #[arg(long, conflicts_with = "watch")]
+        no_index: bool,
+    },
+    /// Check that a puzzle follows the rules of the game without solving it.
+    Validate(PuzzleArg),
+    /// Rate how hard a puzzle is for the solver.
+    Rate(SolveArgs),
+    /// Generate a puzzle with a unique solution.
+    Generate(G),
+    /// Read a puzzle in any format the game supports and print it in the game's text format.
+    Convert(PuzzleArg),
+}
+
+impl<S: Args, G: Args> Action<S, G> {
+    pub fn run<P: Puzzle>(self, paths: &Paths) -> Result<()>
+    where
+        S: SolveOptions<P>,
+        G: GenerateCommand<P>,
+    {
+        match self {
+            Action::Solve {
+                args, watch: true, ..
+            } => watch::<P>(Path::new(&args.puzzle.puzzle))?,
+            Action::Solve {
+                args:
+                    SolveArgs {
+                        puzzle: PuzzleArg { puzzle },
+                        output,
+                    },
+                watch: false,
+                no_index,
+                options,
+            } => {
+                let index_path = (!no_index).then(|| paths.solution_index());
+                let mut index = match &index_path {
+                    Some(path) => SolutionIndex::load(path)?,
+                    None => SolutionIndex::default(),
+                };
+                let (ids, instances): (Vec<_>, Vec<_>) = read_puzzles::<P>(&puzzle)?
+                    .into_iter()
+                    .map(|(id, instance)| {
+                        // Puzzles that break the rules can send the solver searching until it gives up, so they are
+                        // reported as invalid before solving.
+                        let instance = instance
+                            .and_then(|instance| options.prepare(instance, paths))
+                            .and_then(|instance| {
+                                P::validate(&instance)
+                                    .with_context(|| format!("Invalid {}", P::NAME))?;
+                                Ok(instance)
+                            });
+                        (id, instance)
+                    })
+                    .unzip();
+                let custom = ids
+                    .iter()
+                    .zip(&instances)
+                    .map(|(id, instance)| options.solve(id, instance.as_ref().ok()?))
+                    .collect::<Vec<_>>();
+                let stored = instances
+                    .iter()
+                    .zip(&custom)
+                    .map(|(instance, custom)| {
+                        let instance = instance.as_ref().ok().filter(|_| custom.is_none())?;
+                        index.get::<P>(instance).map(str::to_string)
+                    })
+                    .collect::<Vec<_>>();
+                // Only the puzzles without stored solutions, that the options did not solve, are solved.
+                let unsolved = instances
+                    .iter()
+                    .zip(&custom)
+                    .zip(&stored)
+                    .filter(|((_, custom), stored)| custom.is_none() && stored.is_none())
+                    .filter_map(|((instance, _), _)| instance.as_ref().ok())
+                    .collect::<Vec<_>>();
+                let mut solutions = puzzle::solve_all::<P, _>(&unsolved, None).into_iter();
+                let num_stored = index.len();
+                let records = ids
+                    .into_iter()
+                    .zip(&instances)
+                    .zip(custom)
+                    .zip(stored)
+                    .flat_map(|(((id, instance), custom), stored)| {
+                        let instance = match instance {
+                            Ok(instance) => instance,
+                            Err(err) => return vec![Record::error(id, format!("{err:#}"))],
+                        };
+                        let records = match (custom, stored) {
+                            (Some(Ok(records)), _) => records,
+                            (Some(Err(err)), _) => vec![Record::error(id, format!("{err:#}"))],
+                            (None, Some(solution)) => vec![Record::solved(id, solution)],
+                            (None, None) => vec![match solutions
+                                .next()
+                                .expect("Every unsolved puzzle is solved.")
+                            {
+                                Ok(Some(solution)) => {
+                                    index.insert::<P>(instance, &solution);
+                                    Record::solved(id, P::format(&solution))
+                                }
+                                Ok(None) => Record::no_solution(id),
+                                Err(err) => Record::error(id, format!("{err:#}")),
+                            }],
+                        };
+                        records
+                            .into_iter()
+                            .map(|record| record.with_puzzle::<P>(instance))
+                            .collect()
+                    })
+                    .collect::<Vec<_>>();
+                if let Some(path) = index_path.filter(|_| index.len() > num_stored) {
+                    index
+                        .save(&path)
+                        .with_context(|| format!("Failed to save the solution index {path:?}."))?;
+                }
+                output.write(&records)?;
+                ensure_solved(&records)?;
+            }
+            Action::Validate(PuzzleArg { puzzle }) => {
+                let instance = read_puzzle::<P>(&puzzle)?;
+                P::validate(&instance)
+                    .with_context(|| format!("'{puzzle}' is not a valid {}.", P::NAME))?;
+                println!("'{puzzle}' is a valid {}.", P::NAME);
+            }
+            Action::Rate(SolveArgs {
+                puzzle: PuzzleArg { puzzle },
+                output,
+            }) => {
+                let instance = read_puzzle::<P>(&puzzle)?;
+                let rating = P::rating(&instance)?;
+                let record =
+                    Record::rated(puzzle, puzzle::kind::<P>(), rating).with_puzzle::<P>(&instance);
+                output.write(&[record])?;
+            }
+            Action::Generate(args) => args.generate()?,
+            Action::Convert(PuzzleArg { puzzle }) => {
+                print_text(&P::format_instance(&read_puzzle::<P>(&puzzle)?));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads a puzzle from a URL, stdin or a file, or otherwise from the argument itself, so that short puzzles such as
+/// cryptarithms can be given directly.
+pub fn read_puzzle<P: Puzzle>(arg: &str) -> Result<P::Instance> {
+    let instance = if arg.starts_with("http") {
+        P::parse_url(arg)
+    } else if is_input(arg) {
+        P::parse(&read_input(arg)?)
+    } else {
+        P::parse(arg)
+    };
+    instance.with_context(|| format!("Failed to parse {} '{arg}'.", P::NAME))
+}
+
+/// Like [`read_puzzle`], but reads every puzzle from stdin or a file, as split by [`Puzzle::split`]. If there is
+/// more than one, each is named by the argument and its index, such as `-[3]`. A puzzle that cannot be parsed does
+/// not stop the others from being read.
+pub fn read_puzzles<P: Puzzle>(arg: &str) -> Result<Vec<(String, Result<P::Instance>)>> {
+    if arg.starts_with("http") || !is_input(arg) {
+        return Ok(vec![(arg.to_string(), read_puzzle::<P>(arg))]);
+    }
+    let input = read_input(arg)?;
+    let parts = match P::split(&input) {
+        // Let the parser explain what is wrong with input without any puzzles.
+        parts if parts.is_empty() => vec![input.as_str()],
+        parts => parts,
+    };
+    let single = parts.len() == 1;
+    Ok(parts
+        .into_iter()
+        .enumerate()
+        .map(|(index, part)| {
+            let id = if single {
+                arg.to_string()
+            } else {
+                format!("{arg}[{index}]")
+            };
+            let instance =
+                P::parse(part).with_context(|| format!("Failed to parse {} '{id}'.", P::NAME));
+            (id, instance)
+        })
+        .collect())
+}
+
+/// Whether the argument refers to stdin or a file, rather than being a puzzle itself.
+pub fn is_input(arg: &str) -> bool {
+    arg == "-" || Path::new(arg).is_file()
+}
+
+/// Prints text with exactly one trailing newline, since some formats end in one and others do not. Other trailing
+/// whitespace is kept, as it can be part of a grid.
+pub fn print_text(text: &str) {
+    println!("{}", text.trim_end_matches('\n'));
+}
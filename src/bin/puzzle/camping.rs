@@ -1,90 +1,372 @@
 use std::{
     ffi::OsStr,
     fs::{self, File},
-    io::Write,
-    path::PathBuf,
+    io::{self, Read, Write},
+    path::Path,
 };
 
-use anyhow::{Context, Result};
-use clap::Args;
-use puzzles::camping::{self, Map, MaybeTransposedMap};
+use anyhow::{bail, Context, Result};
+use clap::{Args, Subcommand};
+use itertools::Itertools;
+use puzzles::{
+    camping::{self, GuessOrder, Map, MaybeTransposedMap, Rules, SolverConfig},
+    difficulty::Rating,
+    puzzle::Puzzle,
+    solutions::SolutionIndex,
+};
+
+use crate::{
+    action::{Action, GenerateArgs, PuzzleArg, SolveArgs},
+    output::{ensure_solved, OutputArgs, Record},
+    paths::Paths,
+    progress::{Progress, ProgressArgs},
+    watch::{describe, watch_with},
+};
+
+fn map_name(path: &Path) -> String {
+    path.file_stem()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .to_string()
+}
+
+fn read_map_dir(dir: &Path) -> Result<Vec<(String, String)>> {
+    let mut maps = fs::read_dir(dir)
+        .with_context(|| format!("Unable to read dir '{dir:?}'"))?
+        .map(|entry| {
+            let entry = entry.context("Error while getting map directory entry.")?;
+            let file_type = entry
+                .file_type()
+                .context("Error while getting map dir entry file type.")?;
+            let path = entry.path();
+            if file_type.is_file() && path.extension().and_then(OsStr::to_str) == Some("txt") {
+                read_map_file(&path).map(Some)
+            } else {
+                Ok(None)
+            }
+        })
+        .filter_map(Result::transpose)
+        .collect::<Result<Vec<_>>>()?;
+    maps.sort_by(|(name1, _), (name2, _)| name1.cmp(name2));
+    Ok(maps)
+}
+
+fn read_map_file(path: &Path) -> Result<(String, String)> {
+    let string = fs::read_to_string(path)
+        .with_context(|| format!("Error reading map file from path {path:?}"))?;
+    Ok((map_name(path), string))
+}
+
+/// Resolves a single map argument to the names and contents of the maps it refers to.
+///
+/// In order of precedence, an argument can be `-` for stdin, a directory of `.txt` map files,
+/// a path to a map file, a glob pattern, or the name of a map in `maps_dir`.
+fn read_map_arg(arg: &str, maps_dir: &Path) -> Result<Vec<(String, String)>> {
+    let path = Path::new(arg);
+    if arg == "-" {
+        let mut string = String::new();
+        io::stdin()
+            .read_to_string(&mut string)
+            .context("Failed to read map from stdin.")?;
+        Ok(vec![("stdin".to_string(), string)])
+    } else if path.is_dir() {
+        read_map_dir(path)
+    } else if path.is_file() {
+        Ok(vec![read_map_file(path)?])
+    } else if arg.contains(['*', '?', '[']) {
+        glob::glob(arg)
+            .with_context(|| format!("Invalid glob pattern '{arg}'."))?
+            .map(|path| {
+                let path = path.with_context(|| format!("Error while expanding glob '{arg}'."))?;
+                read_map_file(&path)
+            })
+            .collect()
+    } else {
+        let path = maps_dir.join(arg).with_extension("txt");
+        read_map_file(&path)
+            .map(|(_, string)| vec![(arg.to_string(), string)])
+            .with_context(|| format!("Failed to find map file for '{arg}'"))
+    }
+}
+
+fn read_map_args(args: &[String], maps_dir: &Path) -> Result<Vec<(String, String)>> {
+    if args.is_empty() {
+        read_map_dir(maps_dir)
+    } else {
+        args.iter()
+            .map(|arg| read_map_arg(arg, maps_dir))
+            .flatten_ok()
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum CampingAction {
+    /// Solve maps, writing the solutions to files unless `--stdout` is given.
+    Solve {
+        /// Map names, paths, directories, globs, or `-` for stdin. Solves every map in the data directory if empty.
+        maps: Vec<String>,
+        /// Print solutions instead of writing them to the output directory.
+        #[arg(long)]
+        stdout: bool,
+        /// Guess cells in row-major order instead of starting with the most constrained ones.
+        #[arg(long)]
+        scan_guesses: bool,
+        /// Print the map before and after every solver step, highlighting the changed tiles on a terminal.
+        #[arg(long)]
+        explain: bool,
+        /// Check the map file again every time it changes, reporting whether it is valid, whether its solution is
+        /// unique and how hard it is.
+        #[arg(long, conflicts_with_all = ["stdout", "scan_guesses", "explain"])]
+        watch: bool,
+        /// Neither look up stored solutions nor store new ones in the solution index.
+        #[arg(long, conflicts_with = "watch")]
+        no_index: bool,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Report every problem with a map instead of solving it.
+    Validate {
+        /// Map names, paths, directories, globs, or `-` for stdin.
+        #[arg(required = true)]
+        maps: Vec<String>,
+    },
+    /// Rate how hard a map is for the solver.
+    Rate(SolveArgs),
+    /// Generate a map with a unique solution.
+    Generate(GenerateArgs),
+    /// Read a map in any format the game supports and print it in the game's text format.
+    Convert(PuzzleArg),
+}
 
 #[derive(Clone, Debug, Args)]
 pub struct Camping {
-    map: Option<String>,
+    #[command(subcommand)]
+    action: CampingAction,
+    /// Use the variant rules where tents may touch diagonally.
+    #[arg(long, global = true)]
+    diagonal: bool,
 }
 
 impl Camping {
-    pub fn run(self) -> Result<()> {
-        let camping_dir = PathBuf::from("data/camping");
-        let maps_dir = camping_dir.join("maps");
-        let output_dir = camping_dir.join("solutions");
+    pub fn run(self, paths: &Paths, progress: &ProgressArgs) -> Result<()> {
+        let maps_dir = paths.data("camping").join("maps");
+        let rules = Rules {
+            diagonal_tents_allowed: self.diagonal,
+        };
+        match self.action {
+            CampingAction::Solve {
+                maps, watch: true, ..
+            } => {
+                let [map] = maps.as_slice() else {
+                    bail!("Can only watch a single map.");
+                };
+                let path = Path::new(map);
+                let path = if path.is_file() {
+                    path.to_path_buf()
+                } else {
+                    maps_dir.join(map).with_extension("txt")
+                };
+                let config = SolverConfig {
+                    rules,
+                    ..SolverConfig::default()
+                };
+                watch_with(&path, |text| check(text, &config))
+            }
+            CampingAction::Solve {
+                maps,
+                stdout,
+                scan_guesses,
+                explain,
+                watch: false,
+                no_index,
+                output,
+            } => {
+                let output_dir = (!stdout).then(|| paths.output("camping").join("solutions"));
+                let config = SolverConfig {
+                    guess_order: if scan_guesses {
+                        GuessOrder::Scan
+                    } else {
+                        GuessOrder::MostConstrained
+                    },
+                    rules,
+                };
+                let index_path = (!no_index).then(|| paths.solution_index());
+                let mut index = match &index_path {
+                    Some(path) => SolutionIndex::load(path)?,
+                    None => SolutionIndex::default(),
+                };
+                let num_stored = index.len();
+                let records = solve(
+                    &maps,
+                    &maps_dir,
+                    output_dir.as_deref(),
+                    &config,
+                    explain.then(|| output.is_terminal()),
+                    &mut index,
+                    progress,
+                )?;
+                if let Some(path) = index_path.filter(|_| index.len() > num_stored) {
+                    index
+                        .save(&path)
+                        .with_context(|| format!("Failed to save the solution index {path:?}."))?;
+                }
+                output.write(&records)?;
+                ensure_solved(&records)
+            }
+            CampingAction::Validate { maps } => validate(&maps, &maps_dir, rules),
+            CampingAction::Rate(arg) => <Action>::Rate(arg).run::<camping::Camping>(paths),
+            CampingAction::Generate(args) => {
+                <Action>::Generate(args).run::<camping::Camping>(paths)
+            }
+            CampingAction::Convert(arg) => <Action>::Convert(arg).run::<camping::Camping>(paths),
+        }
+    }
+}
+
+/// Describes the map as the watcher does for other games, but solving, counting and rating under the config's rules.
+fn check(text: &str, config: &SolverConfig) -> String {
+    let map = match camping::Camping::parse(text).and_then(|map| {
+        camping::Camping::validate(&map)?;
+        Ok(map)
+    }) {
+        Ok(map) => map,
+        Err(err) => return format!("Invalid {}: {err}", camping::Camping::NAME),
+    };
+    let rating = camping::solve_with_stats(&map, config).map(|(_, stats)| Rating {
+        score: stats.guesses,
+        difficulty: camping::Camping::grade(stats.guesses),
+    });
+    describe(
+        camping::count_solutions(&map, config, 2),
+        rating,
+        camping::solve_with(&map, config).map(|solution| solution.map(|map| map.to_string())),
+    )
+}
 
-        let maps = if let Some(map_name) = self.map {
-            vec![(
-                map_name.clone(),
-                Map::from_file(maps_dir.join(&map_name).with_extension("txt"))
-                    .with_context(|| format!("Failed to find map file for '{map_name}'"))?,
-            )]
+fn validate(map_args: &[String], maps_dir: &Path, rules: Rules) -> Result<()> {
+    let mut num_problems = 0;
+    for (map_name, string) in read_map_args(map_args, maps_dir)? {
+        // Infeasible maps are rejected by the regular parser, but those are exactly the ones we want to diagnose.
+        let map = Map::parse_unchecked(string)
+            .with_context(|| format!("Failed to parse map '{map_name}'"))?;
+        let problems = map.problems(rules);
+        if problems.is_empty() {
+            println!("No problems found in '{map_name}'.");
         } else {
-            fs::read_dir(maps_dir.as_path())
-                .with_context(|| format!("Unable to read dir '{maps_dir:?}'"))?
-                .flat_map(|entry| {
-                    let entry = match entry.context("Error while getting map directory entry.") {
-                        Ok(entry) => entry,
-                        Err(err) => return Some(Err(err)),
-                    };
-                    let file_type = match entry
-                        .file_type()
-                        .context("Error while getting map dir entry file type.")
-                    {
-                        Ok(file_type) => file_type,
-                        Err(err) => return Some(Err(err)),
-                    };
-                    if file_type.is_file()
-                        && entry
-                            .path()
-                            .extension()
-                            .and_then(OsStr::to_str)
-                            .is_some_and(|ext| ext == "txt")
-                    {
-                        let map_name = entry.file_name().to_string_lossy().to_string();
-                        let map = match Map::from_file(entry.path()).with_context(|| {
-                            format!("Error creating map from file for '{map_name}'.")
-                        }) {
-                            Ok(map) => map,
-                            Err(err) => return Some(Err(err)),
-                        };
-                        Some(Ok((map_name, map)))
+            for problem in problems.iter() {
+                println!("{map_name}: {problem}");
+            }
+        }
+        num_problems += problems.len();
+    }
+    if num_problems > 0 {
+        bail!("Found {num_problems} problems.")
+    }
+    Ok(())
+}
+
+/// The variant of the rules that solutions are kept under in the solution index, so that solutions under the variant
+/// and the usual rules do not replace each other.
+fn index_variant(rules: Rules) -> Option<&'static str> {
+    rules.diagonal_tents_allowed.then_some("diagonal")
+}
+
+/// Solves the maps, reading the solutions of maps in the index instead of solving them again unless explaining, and
+/// storing the new ones. `explain` is whether to highlight the changed tiles of each step, if explaining.
+fn solve(
+    map_args: &[String],
+    maps_dir: &Path,
+    output_dir: Option<&Path>,
+    config: &SolverConfig,
+    explain: Option<bool>,
+    index: &mut SolutionIndex,
+    progress: &ProgressArgs,
+) -> Result<Vec<Record>> {
+    let variant = index_variant(config.rules);
+    let maps = read_map_args(map_args, maps_dir)?
+        .into_iter()
+        .map(|(map_name, string)| {
+            Map::parse(string)
+                .with_context(|| format!("Error creating map from file for '{map_name}'."))
+                .map(|map| (map_name, map))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let progress = Progress::new(progress, maps.len());
+    let mut records = vec![];
+    for (map_name, map) in maps {
+        let mut explanation = String::new();
+        // Explaining makes a costlier solve, so what it cost is only reported without explaining.
+        let mut stats = None;
+        let stored = index
+            .get_variant::<camping::Camping>(&map, variant)
+            .filter(|_| explain.is_none())
+            .map(Map::parse_unchecked);
+        let result = if let Some(stored) = stored {
+            stored.map(Some)
+        } else if let Some(highlight) = explain {
+            camping::solve_traced(&map, config).map(|(solution, steps)| {
+                explanation = format!("Explanation for '{map_name}':\n");
+                for step in steps {
+                    if highlight {
+                        explanation.push_str(&format!("{}\n", step.highlighted()));
                     } else {
-                        None
+                        explanation.push_str(&format!("{step}\n"));
                     }
-                })
-                .collect::<Result<_>>()?
+                }
+                solution
+            })
+        } else {
+            camping::solve_with_stats(&map, config).map(|(solution, solve_stats)| {
+                stats = Some(solve_stats);
+                solution
+            })
         };
-        for (map_name, map) in maps {
-            match camping::solve(&map) {
-                Ok(Some(solution)) => {
-                    match map.is_valid() {
-                        Ok(()) => {}
-                        Err(err) => {
-                            eprintln!("Error while validating solution to '{map_name}': {err}");
-                            continue;
-                        }
-                    }
-                    fs::create_dir_all(&output_dir)
+        let mut record = match result {
+            Ok(Some(solution)) => {
+                if let Err(err) = map.is_valid(config.rules) {
+                    let text = format!("Error while validating solution to '{map_name}': {err}");
+                    records.push(
+                        Record::error(map_name, err)
+                            .with_puzzle::<camping::Camping>(&map)
+                            .with_text(text),
+                    );
+                    progress.inc();
+                    continue;
+                }
+                index.insert_variant::<camping::Camping>(&map, variant, &solution);
+                let record = Record::solved(map_name.as_str(), solution.to_string());
+                if let Some(output_dir) = output_dir {
+                    fs::create_dir_all(output_dir)
                         .context("Failed to ensure existance of solution directory")?;
-                    let mut file = File::create(output_dir.join(&map_name).with_extension("txt"))
+                    // Maps read from stdin have no name of their own, so their solutions are named by id.
+                    let file_name = match map_name.as_str() {
+                        "stdin" => map.id().to_string(),
+                        name => name.to_string(),
+                    };
+                    let mut file = File::create(output_dir.join(file_name).with_extension("txt"))
                         .with_context(|| {
                         format!("Failed to create solution file for map '{map_name}'")
                     })?;
                     write!(file, "{solution}")?;
-                    println!("Solution for '{map_name}' found and written to file.");
+                    record.with_text(format!(
+                        "Solution for '{map_name}' found and written to file."
+                    ))
+                } else {
+                    record.with_text(format!("{solution}\n"))
                 }
-                Ok(None) => println!("No solution found for '{map_name}'."),
-                Err(err) => eprintln!("Error while solving '{map}': {err}"),
             }
+            Ok(None) => Record::no_solution(map_name),
+            Err(err) => Record::error(map_name, err),
+        }
+        .with_puzzle::<camping::Camping>(&map);
+        if let Some(stats) = &stats {
+            record = record.with_solve_stats(stats);
         }
-        Ok(())
+        record.text.insert_str(0, &explanation);
+        records.push(record);
+        progress.inc();
     }
+    progress.finish();
+    Ok(records)
 }
@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use puzzles::{
+    collection::{self, Entry},
+    difficulty::Rating,
+    id::PuzzleId,
+    puzzle::{self, Puzzle, Visitor},
+};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+use crate::output::{ensure_solved, OutputArgs, Record};
+
+#[derive(Clone, Debug, Args)]
+pub struct CollectionArgs {
+    /// Path to the manifest of the collection, in TOML, JSON or a zip archive.
+    collection: PathBuf,
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum Collection {
+    /// Solve every puzzle in a collection, whatever its kind.
+    Solve(CollectionArgs),
+    /// Rate every puzzle in a collection.
+    Rate(CollectionArgs),
+}
+
+struct SolveEntry<'a>(&'a Entry);
+
+impl Visitor for SolveEntry<'_> {
+    type Output = puzzles::Result<(PuzzleId, Option<String>)>;
+
+    fn visit<P: Puzzle>(self) -> Self::Output {
+        let instance = P::parse(&self.0.puzzle)?;
+        let solution = P::solve(&instance)?.map(|solution| P::format(&solution));
+        Ok((PuzzleId::of::<P>(&instance), solution))
+    }
+}
+
+struct RateEntry<'a>(&'a Entry);
+
+impl Visitor for RateEntry<'_> {
+    type Output = puzzles::Result<(PuzzleId, Rating)>;
+
+    fn visit<P: Puzzle>(self) -> Self::Output {
+        let instance = P::parse(&self.0.puzzle)?;
+        Ok((PuzzleId::of::<P>(&instance), P::rating(&instance)?))
+    }
+}
+
+impl Collection {
+    pub fn run(self) -> Result<()> {
+        match self {
+            Collection::Solve(args) => {
+                let entries = load(&args)?;
+                let records = entries
+                    .par_iter()
+                    .map(
+                        |entry| match puzzle::visit(&entry.kind, SolveEntry(entry)) {
+                            Ok(Ok((id, Some(solution)))) => {
+                                Record::solved(&entry.id, solution).with_puzzle_id(id)
+                            }
+                            Ok(Ok((id, None))) => Record::no_solution(&entry.id).with_puzzle_id(id),
+                            Ok(Err(err)) | Err(err) => Record::error(&entry.id, err),
+                        },
+                    )
+                    .collect::<Vec<_>>();
+                args.output.write(&records)?;
+                ensure_solved(&records)
+            }
+            Collection::Rate(args) => {
+                let entries = load(&args)?;
+                let records = entries
+                    .par_iter()
+                    .map(|entry| match puzzle::visit(&entry.kind, RateEntry(entry)) {
+                        Ok(Ok((id, rating))) => {
+                            Record::rated(&entry.id, &entry.kind, rating).with_puzzle_id(id)
+                        }
+                        Ok(Err(err)) | Err(err) => Record::error(&entry.id, err),
+                    })
+                    .collect::<Vec<_>>();
+                args.output.write(&records)
+            }
+        }
+    }
+}
+
+fn load(args: &CollectionArgs) -> Result<Vec<Entry>> {
+    let collection = collection::load(&args.collection)
+        .with_context(|| format!("Failed to load collection {:?}.", args.collection))?;
+    Ok(collection.puzzles)
+}
@@ -0,0 +1,153 @@
+//! Converts files of puzzles between the formats of their game, or collections between manifest formats.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use puzzles::{
+    collection,
+    id::PuzzleId,
+    puzzle::{self, Puzzle, Visitor},
+};
+
+use crate::{action::print_text, input::read_input, paths};
+
+#[derive(Clone, Debug, Args)]
+pub struct Convert {
+    /// Files to convert, or `-` for stdin. A file may hold several puzzles, as its format separates them.
+    #[arg(required = true)]
+    inputs: Vec<String>,
+    /// The format to read, such as `line`, `grid`, `sdk` or `json` for sudokus. Defaults to the text format of the
+    /// game.
+    #[arg(long, conflicts_with = "collection")]
+    from: Option<String>,
+    /// The format to write, or `toml`, `json` or `zip` for collections.
+    #[arg(long)]
+    to: String,
+    /// The kind of puzzle, such as `sudoku`. Defaults to the game whose data directory a file is in.
+    #[arg(long, conflicts_with = "collection")]
+    kind: Option<String>,
+    /// Convert collection manifests, reading each in the format given by its extension.
+    #[arg(long)]
+    collection: bool,
+    /// Write each converted file to this directory, named after the input with the extension of the new format,
+    /// instead of printing it.
+    #[arg(long)]
+    out_dir: Option<PathBuf>,
+}
+
+/// Reads every puzzle in the input in one format and writes them in another.
+struct ConvertFile<'a> {
+    input: &'a str,
+    from: Option<&'a str>,
+    to: &'a str,
+}
+
+struct Converted {
+    /// The extension of the new format.
+    extension: &'static str,
+    text: String,
+    /// The id of the puzzle, if the input held only one.
+    id: Option<PuzzleId>,
+}
+
+impl Visitor for ConvertFile<'_> {
+    type Output = puzzles::Result<Converted>;
+
+    fn visit<P: Puzzle>(self) -> Self::Output {
+        let from = match self.from {
+            Some(name) => puzzle::find_format::<P>(name)?,
+            None => P::formats().swap_remove(0),
+        };
+        let to = puzzle::find_format::<P>(self.to)?;
+        let instances = from
+            .split(self.input)
+            .into_iter()
+            .map(|text| from.read(text))
+            .collect::<puzzles::Result<Vec<_>>>()?;
+        let texts = instances
+            .iter()
+            .map(|instance| to.write(instance))
+            .collect::<puzzles::Result<Vec<_>>>()?;
+        Ok(Converted {
+            extension: to.extension,
+            text: to.join(&texts),
+            id: match &instances[..] {
+                [instance] => Some(PuzzleId::of::<P>(instance)),
+                _ => None,
+            },
+        })
+    }
+}
+
+impl Convert {
+    pub fn run(self) -> Result<()> {
+        if let Some(dir) = &self.out_dir {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {dir:?}."))?;
+        }
+        for input in &self.inputs {
+            if self.collection {
+                self.convert_collection(input)
+            } else {
+                self.convert_puzzles(input)
+            }
+            .with_context(|| format!("Failed to convert '{input}'."))?;
+        }
+        Ok(())
+    }
+
+    fn convert_puzzles(&self, input: &str) -> Result<()> {
+        let Some(kind) = self
+            .kind
+            .clone()
+            .or_else(|| paths::kind_of_path(Path::new(input)))
+        else {
+            bail!("Cannot tell what kind of puzzle '{input}' is. Give it with --kind.");
+        };
+        let text = read_input(input)?;
+        let convert = ConvertFile {
+            input: &text,
+            from: self.from.as_deref(),
+            to: &self.to,
+        };
+        let converted = puzzle::visit(&kind, convert)??;
+        match self.output_path(input, converted.id, converted.extension) {
+            Some(path) => fs::write(&path, converted.text)
+                .with_context(|| format!("Failed to write {path:?}."))?,
+            None => print_text(&converted.text),
+        }
+        Ok(())
+    }
+
+    fn convert_collection(&self, input: &str) -> Result<()> {
+        let collection = collection::load(input)?;
+        match self.output_path(input, None, &self.to) {
+            Some(path) => collection::save(&collection, path)?,
+            None => match self.to.as_str() {
+                "toml" => print_text(&collection.to_toml()),
+                "json" => print_text(&serde_json::to_string_pretty(&collection)?),
+                "zip" => bail!(
+                    "Zip archives can only be written to files. Give a directory with --out-dir."
+                ),
+                to => bail!("Unknown collection format '{to}'. Expected toml, json or zip."),
+            },
+        }
+        Ok(())
+    }
+
+    /// Where to write the conversion of the input, or `None` to print it. A puzzle from stdin is named by its id.
+    fn output_path(&self, input: &str, id: Option<PuzzleId>, extension: &str) -> Option<PathBuf> {
+        let dir = self.out_dir.as_ref()?;
+        let stem = match (input, id) {
+            ("-", Some(id)) => id.to_string(),
+            ("-", None) => "stdin".to_string(),
+            (input, _) => Path::new(input)
+                .file_stem()
+                .map_or_else(|| input.to_string(), |stem| stem.to_string_lossy().into()),
+        };
+        Some(dir.join(format!("{stem}.{extension}")))
+    }
+}
@@ -0,0 +1,60 @@
+use anyhow::Result;
+use clap::Args;
+use puzzles::crossword_fill::{Crossword, CrosswordFill};
+
+use crate::{action::SolveOptions, input::read_input, paths::Paths};
+
+#[derive(Clone, Debug, Args)]
+pub struct CrosswordFillOptions {
+    /// Path to more words, one per line. Defaults to `crossword_fill/words.txt` in the data directory, if there is one.
+    #[arg(long)]
+    words: Option<String>,
+}
+
+impl SolveOptions<CrosswordFill> for CrosswordFillOptions {
+    fn prepare(&self, mut crossword: Crossword, paths: &Paths) -> Result<Crossword> {
+        let words = match &self.words {
+            Some(words) => Some(words.clone()),
+            None => {
+                let path = paths.data("crossword_fill").join("words.txt");
+                path.is_file().then(|| path.to_string_lossy().into_owned())
+            }
+        };
+        if let Some(words) = words {
+            crossword
+                .words
+                .extend(read_input(&words)?.lines().map(str::to_string));
+        }
+        Ok(crossword)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use puzzles::puzzle::{self, Puzzle};
+
+    use super::*;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        paths: Paths,
+    }
+
+    #[test]
+    fn solves_data_puzzles_with_the_default_words() {
+        let data_dir = concat!(env!("CARGO_MANIFEST_DIR"), "/data");
+        let paths = Cli::parse_from(["puzzle", "--data-dir", data_dir]).paths;
+        let options = CrosswordFillOptions { words: None };
+        for input in [
+            include_str!("../../../data/crossword_fill/puzzles/four01.txt"),
+            include_str!("../../../data/crossword_fill/puzzles/square01.txt"),
+        ] {
+            let crossword = CrosswordFill::parse(input).unwrap();
+            assert!(puzzle::solve_valid::<CrosswordFill>(&crossword).is_err());
+            let crossword = options.prepare(crossword, &paths).unwrap();
+            assert!(puzzle::solve_valid::<CrosswordFill>(&crossword).is_ok());
+        }
+    }
+}
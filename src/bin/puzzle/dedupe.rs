@@ -0,0 +1,118 @@
+//! Finds puzzles that appear more than once across files, even when rotated, reflected or otherwise written
+//! differently, by the id of their [`Puzzle::isomorphic_form`].
+
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use puzzles::{
+    id::PuzzleId,
+    puzzle::{self, Puzzle, Visitor},
+};
+
+use crate::{
+    input::read_input,
+    output::{OutputArgs, Record},
+    paths,
+};
+
+#[derive(Clone, Debug, Args)]
+pub struct Dedupe {
+    /// Files of puzzles, or `-` for stdin. A file may hold several puzzles, as the text format of its game separates
+    /// them. Puzzles are compared across every file, in order.
+    #[arg(required = true)]
+    files: Vec<String>,
+    /// The kind of puzzle, such as `sudoku`. Defaults to the game whose data directory a file is in.
+    #[arg(long)]
+    kind: Option<String>,
+    /// Rewrite each file without its duplicates, keeping the first of each puzzle.
+    #[arg(long)]
+    remove: bool,
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// Finds the duplicates in one file, remembering the puzzles seen so far.
+struct DedupeFile<'a> {
+    name: &'a str,
+    input: &'a str,
+    /// The name of the first puzzle with each id.
+    seen: &'a mut HashMap<PuzzleId, String>,
+}
+
+struct Deduped {
+    duplicates: Vec<Record>,
+    /// The file without its duplicates, if it had any.
+    kept: Option<String>,
+}
+
+impl Visitor for DedupeFile<'_> {
+    type Output = puzzles::Result<Deduped>;
+
+    fn visit<P: Puzzle>(self) -> Self::Output {
+        let format = P::formats().swap_remove(0);
+        let texts = format.split(self.input);
+        let mut duplicates = vec![];
+        let mut kept = vec![];
+        for (index, &text) in texts.iter().enumerate() {
+            let instance = format.read(text)?;
+            let id = PuzzleId::up_to_isomorphism::<P>(&instance);
+            let name = format!("{}[{index}]", self.name);
+            match self.seen.get(&id) {
+                Some(original) => {
+                    duplicates.push(Record::duplicate(name, original).with_puzzle_id(id));
+                }
+                None => {
+                    self.seen.insert(id, name);
+                    kept.push(text.to_string());
+                }
+            }
+        }
+        Ok(Deduped {
+            kept: (!duplicates.is_empty()).then(|| format.join(&kept)),
+            duplicates,
+        })
+    }
+}
+
+impl Dedupe {
+    pub fn run(self) -> Result<()> {
+        if self.remove && self.files.iter().any(|file| file == "-") {
+            bail!("Duplicates cannot be removed from stdin.");
+        }
+        let mut seen = HashMap::new();
+        let mut records = vec![];
+        let mut num_puzzles = 0;
+        // Files are read one at a time, so only the ids of the puzzles seen so far are kept in memory.
+        for file in &self.files {
+            let Some(kind) = self
+                .kind
+                .clone()
+                .or_else(|| paths::kind_of_path(Path::new(file)))
+            else {
+                bail!("Cannot tell what kind of puzzle '{file}' is. Give it with --kind.");
+            };
+            let input = read_input(file)?;
+            let seen_before = seen.len();
+            let dedupe = DedupeFile {
+                name: file,
+                input: &input,
+                seen: &mut seen,
+            };
+            let deduped = puzzle::visit(&kind, dedupe)?
+                .with_context(|| format!("Failed to read the puzzles in '{file}'."))?;
+            num_puzzles += seen.len() - seen_before + deduped.duplicates.len();
+            if let (true, Some(kept)) = (self.remove, deduped.kept) {
+                fs::write(file, kept).with_context(|| format!("Failed to write '{file}'."))?;
+            }
+            records.extend(deduped.duplicates);
+        }
+        let removed = if self.remove { "Removed" } else { "Found" };
+        let summary = format!(
+            "{removed} {} duplicates among {num_puzzles} puzzles.",
+            records.len()
+        );
+        records.push(Record::summary("dedupe", summary).with_stat("puzzles", num_puzzles as u64));
+        self.output.write(&records)
+    }
+}
@@ -0,0 +1,53 @@
+//! Shell completions and manual pages, generated from the definition of the command line.
+
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use anyhow::{Context, Result};
+use clap::{Args, CommandFactory};
+use clap_complete::Shell;
+
+use crate::Cli;
+
+#[derive(Clone, Debug, Args)]
+pub struct Completions {
+    /// The shell to complete in.
+    shell: Shell,
+}
+
+impl Completions {
+    pub fn run(self) -> Result<()> {
+        let mut command = Cli::command();
+        let name = command.get_name().to_string();
+        // Generating straight to stdout panics if it is closed early, such as when piped to `head`.
+        let mut script = vec![];
+        clap_complete::generate(self.shell, &mut command, name, &mut script);
+        io::stdout().write_all(&script)?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Args)]
+pub struct Man {
+    /// Write a page for every command and subcommand to this directory instead of printing the top-level page.
+    #[arg(long)]
+    out_dir: Option<PathBuf>,
+}
+
+impl Man {
+    pub fn run(self) -> Result<()> {
+        let command = Cli::command();
+        match self.out_dir {
+            Some(dir) => {
+                std::fs::create_dir_all(&dir)
+                    .with_context(|| format!("Failed to create {dir:?}."))?;
+                clap_mangen::generate_to(command, &dir)
+                    .with_context(|| format!("Failed to write manual pages to {dir:?}."))?;
+            }
+            None => clap_mangen::Man::new(command).render(&mut io::stdout())?,
+        }
+        Ok(())
+    }
+}
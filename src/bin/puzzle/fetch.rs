@@ -0,0 +1,232 @@
+//! Downloads puzzles from public sources into the data directory.
+//!
+//! Sources are listed in a TOML file, `sources.toml` in the data directory by default:
+//!
+//! ```toml
+//! [[sources]]
+//! name = "daily-sudoku"
+//! kind = "sudoku"
+//! url = "https://example.com/api/sudoku?date={date}"
+//! pointer = "/puzzle"
+//!
+//! [[sources]]
+//! name = "hashi"
+//! kind = "hashi"
+//! url = "https://puzz.link/p?hashi/9/7/1h2"
+//! ```
+
+use std::{
+    fs,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use puzzles::puzzle::{self, Puzzle, Visitor};
+use serde::Deserialize;
+
+use crate::paths::{self, Paths};
+
+#[derive(Clone, Debug, Args)]
+pub struct Fetch {
+    /// Names of the sources to fetch. Fetches every source if none are given.
+    names: Vec<String>,
+    /// The TOML file listing the sources. Defaults to `sources.toml` in the data directory.
+    #[arg(long)]
+    sources: Option<PathBuf>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Sources {
+    #[serde(default)]
+    sources: Vec<Source>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Source {
+    /// Names the fetched files, as `<name>-<date>.txt`.
+    name: String,
+    /// The kind of puzzle, by the name [`puzzle::visit`] knows it by.
+    kind: String,
+    /// Where to download the puzzle from. `{date}` is replaced by today's date, as in `2024-01-31`. puzz.link URLs
+    /// are read directly, since they hold the puzzle.
+    url: String,
+    /// A JSON pointer to the puzzle, such as `/puzzle`, for sources that answer in JSON.
+    #[serde(default)]
+    pointer: Option<String>,
+}
+
+/// Reads a downloaded puzzle, which is either in the text format of its kind or a puzz.link URL, and writes it in the
+/// text format.
+struct Convert<'a>(&'a str);
+
+impl Visitor for Convert<'_> {
+    type Output = puzzles::Result<String>;
+
+    fn visit<P: Puzzle>(self) -> Self::Output {
+        let text = self.0.trim();
+        let instance = if is_url(text) {
+            P::parse_url(text)?
+        } else {
+            P::parse(text)?
+        };
+        P::validate(&instance)?;
+        Ok(P::format_instance(&instance))
+    }
+}
+
+fn is_url(text: &str) -> bool {
+    text.starts_with("https://") || text.starts_with("http://")
+}
+
+fn is_puzz_link(url: &str) -> bool {
+    [
+        "https://puzz.link/",
+        "http://puzz.link/",
+        "https://pzv.jp/",
+        "http://pzv.jp/",
+    ]
+    .iter()
+    .any(|prefix| url.starts_with(prefix))
+}
+
+impl Fetch {
+    pub fn run(self, paths: &Paths) -> Result<()> {
+        let path = self
+            .sources
+            .clone()
+            .unwrap_or_else(|| paths.data_dir().join("sources.toml"));
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read sources {path:?}."))?;
+        let sources: Sources =
+            toml::from_str(&text).with_context(|| format!("Invalid sources {path:?}."))?;
+        for name in &self.names {
+            if !sources.sources.iter().any(|source| &source.name == name) {
+                bail!("No source named '{name}' in {path:?}.");
+            }
+        }
+        let chosen = sources
+            .sources
+            .iter()
+            .filter(|source| self.names.is_empty() || self.names.contains(&source.name))
+            .collect::<Vec<_>>();
+
+        let date = today();
+        let mut failures = 0;
+        for source in &chosen {
+            match fetch(source, &date, paths) {
+                Ok(path) => println!("Fetched '{}' to {path:?}.", source.name),
+                Err(err) => {
+                    failures += 1;
+                    eprintln!("Failed to fetch '{}': {err:#}", source.name);
+                }
+            }
+        }
+        if failures > 0 {
+            bail!("Failed to fetch {failures} of {} sources.", chosen.len());
+        }
+        Ok(())
+    }
+}
+
+/// Downloads and converts a puzzle, returning where it was written.
+fn fetch(source: &Source, date: &str, paths: &Paths) -> Result<PathBuf> {
+    let url = source.url.replace("{date}", date);
+    let text = if is_puzz_link(&url) {
+        url
+    } else {
+        ureq::get(&url)
+            .call()
+            .context("Failed to download.")?
+            .into_string()
+            .with_context(|| format!("Failed to read the answer from {url}."))?
+    };
+    let text = match &source.pointer {
+        Some(pointer) => {
+            let json: serde_json::Value =
+                serde_json::from_str(&text).context("The answer is not JSON.")?;
+            match json.pointer(pointer) {
+                Some(serde_json::Value::String(puzzle)) => puzzle.clone(),
+                Some(_) => bail!("The value at '{pointer}' is not a string."),
+                None => bail!("The answer has nothing at '{pointer}'."),
+            }
+        }
+        None => text,
+    };
+    let puzzle = puzzle::visit(&source.kind, Convert(&text))??;
+
+    let dir = paths
+        .data(&paths::data_dir_name(&source.kind))
+        .join("fetched");
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {dir:?}."))?;
+    let path = dir.join(format!("{}-{date}.txt", source.name));
+    fs::write(&path, puzzle).with_context(|| format!("Failed to write {path:?}."))?;
+    Ok(path)
+}
+
+/// Today's date in UTC, as in `2024-01-31`.
+fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("The clock is after 1970.")
+        .as_secs()
+        / 86_400;
+    let (year, month, day) = civil_from_days(days as i64);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Converts days since 1970-01-01 to a year, month and day, by Howard Hinnant's algorithm.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_days_to_dates() {
+        for (days, date) in [
+            (0, (1970, 1, 1)),
+            (-1, (1969, 12, 31)),
+            (11_016, (2000, 2, 29)),
+            (11_017, (2000, 3, 1)),
+            (19_782, (2024, 2, 29)),
+            (20_088, (2024, 12, 31)),
+            (47_540, (2100, 2, 28)),
+            (47_541, (2100, 3, 1)),
+        ] {
+            assert_eq!(civil_from_days(days), date, "{days} days");
+        }
+        let today = today();
+        assert_eq!(today.len(), 10);
+        assert!(today.as_str() >= "2024-01-01", "{today}");
+    }
+
+    #[test]
+    fn reads_the_default_sources() {
+        let sources: Sources = toml::from_str(include_str!("../../../data/sources.toml")).unwrap();
+        assert!(!sources.sources.is_empty());
+        for source in &sources.sources {
+            assert!(is_puzz_link(&source.url), "{}", source.url);
+            puzzle::visit(&source.kind, Convert(&source.url))
+                .unwrap()
+                .unwrap_or_else(|err| panic!("{}: {err}", source.name));
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//! Checks that the solvers still give the stored golden solutions. The golden solution of a puzzle file such as
+//! `data/akari/puzzles/ten01.txt` is `data/akari/puzzles/ten01.solution`. Files holding several puzzles have their
+//! solutions in the same order, separated by lines of `---`.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{ensure, Context, Result};
+use clap::Args;
+use puzzles::{
+    id::PuzzleId,
+    puzzle::{self, Puzzle, Visitor},
+};
+
+use crate::{
+    output::{OutputArgs, Record, Status},
+    paths,
+};
+
+/// The extension of golden solution files.
+const GOLDEN: &str = "solution";
+
+#[derive(Clone, Debug, Args)]
+pub struct VerifyGoldens {
+    /// A data directory, or the directory of a single game within one, such as `data/akari`.
+    dir: PathBuf,
+    /// Write the golden solutions from the current solutions instead of comparing, including for puzzles that have
+    /// none yet.
+    #[arg(long)]
+    bless: bool,
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// Solves every puzzle in a file and compares the solutions to the golden ones, or writes them if blessing. Returns
+/// the id of the puzzle with the outcome if the file holds only one.
+struct Verify<'a> {
+    puzzles: &'a str,
+    golden: Option<&'a str>,
+}
+
+enum Outcome {
+    Compared(Vec<String>),
+    Blessed(String),
+}
+
+impl Visitor for Verify<'_> {
+    type Output = puzzles::Result<(Option<PuzzleId>, Outcome)>;
+
+    fn visit<P: Puzzle>(self) -> Self::Output {
+        let instances = P::split(self.puzzles)
+            .into_iter()
+            .map(|input| {
+                let instance = P::parse(input)?;
+                P::validate(&instance)?;
+                Ok(instance)
+            })
+            .collect::<puzzles::Result<Vec<_>>>()?;
+        let id = match &instances[..] {
+            [instance] => Some(PuzzleId::of::<P>(instance)),
+            _ => None,
+        };
+        let solutions = puzzle::solve_all::<P, _>(&instances, None)
+            .into_iter()
+            .map(|solution| solution?.ok_or(puzzles::Error::NoSolution(P::NAME)))
+            .collect::<puzzles::Result<Vec<_>>>()?;
+
+        let Some(golden) = self.golden else {
+            let formatted = solutions
+                .iter()
+                .map(|solution| P::format(solution).trim_end().to_string())
+                .collect::<Vec<_>>();
+            return Ok((id, Outcome::Blessed(formatted.join("\n---\n") + "\n")));
+        };
+        let goldens = puzzle::split_blocks(golden);
+        if goldens.len() != solutions.len() {
+            let difference = format!(
+                "Expected {} solutions, found {}.",
+                goldens.len(),
+                solutions.len()
+            );
+            return Ok((id, Outcome::Compared(vec![difference])));
+        }
+        let several = solutions.len() > 1;
+        let differences = goldens
+            .iter()
+            .zip(&solutions)
+            .enumerate()
+            .flat_map(|(index, (golden, solution))| {
+                P::diff(golden, solution)
+                    .into_iter()
+                    .map(move |difference| {
+                        if several {
+                            format!("Puzzle {}: {difference}", index + 1)
+                        } else {
+                            difference
+                        }
+                    })
+            })
+            .collect();
+        Ok((id, Outcome::Compared(differences)))
+    }
+}
+
+impl VerifyGoldens {
+    pub fn run(self) -> Result<()> {
+        let mut records = vec![];
+        for (kind, path) in self.puzzle_files()? {
+            let golden_path = path.with_extension(GOLDEN);
+            let golden = if self.bless {
+                None
+            } else if golden_path.exists() {
+                Some(
+                    fs::read_to_string(&golden_path)
+                        .with_context(|| format!("Failed to read {golden_path:?}."))?,
+                )
+            } else {
+                // Puzzles without golden solutions are only solved when blessing.
+                continue;
+            };
+            let id = path.display().to_string();
+            let puzzles =
+                fs::read_to_string(&path).with_context(|| format!("Failed to read {path:?}."))?;
+            let verify = Verify {
+                puzzles: &puzzles,
+                golden: golden.as_deref(),
+            };
+            let (puzzle_id, record) = match puzzle::visit(&kind, verify) {
+                Ok(Ok((puzzle_id, Outcome::Compared(differences)))) => {
+                    (puzzle_id, Record::compared(id, differences))
+                }
+                Ok(Ok((puzzle_id, Outcome::Blessed(solution)))) => {
+                    fs::write(&golden_path, &solution)
+                        .with_context(|| format!("Failed to write {golden_path:?}."))?;
+                    (puzzle_id, Record::blessed(id, solution))
+                }
+                Ok(Err(err)) | Err(err) => (None, Record::error(id, err)),
+            };
+            records.push(Record {
+                puzzle_id,
+                ..record
+            });
+        }
+        self.output.write(&records)?;
+
+        let num_failed = records
+            .iter()
+            .filter(|record| matches!(record.status, Status::Differs | Status::Error))
+            .count();
+        ensure!(
+            num_failed == 0,
+            "Failed to verify {num_failed} of {} puzzle files.",
+            records.len()
+        );
+        Ok(())
+    }
+
+    /// Every file under the directory except golden solutions, with the kind of puzzle it holds, sorted by path so
+    /// that the results come in the same order every time.
+    fn puzzle_files(&self) -> Result<Vec<(String, PathBuf)>> {
+        let dir_kind = self
+            .dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(paths::kind_of_data_dir)
+            .filter(|kind| paths::is_kind(kind));
+        let pattern = self.dir.join("**").join("*");
+        let mut files = vec![];
+        for path in glob::glob(&pattern.to_string_lossy())? {
+            let path = path?;
+            if !path.is_file()
+                || path
+                    .extension()
+                    .is_some_and(|extension| extension == GOLDEN)
+            {
+                continue;
+            }
+            let kind = match &dir_kind {
+                Some(kind) => kind.clone(),
+                None => match game_dir(&self.dir, &path) {
+                    Some(game) => paths::kind_of_data_dir(game),
+                    None => continue,
+                },
+            };
+            files.push((kind, path));
+        }
+        files.sort_by(|(_, path), (_, other)| path.cmp(other));
+        Ok(files)
+    }
+}
+
+/// The name of the directory under `dir` that holds the file, such as `akari` for `data/akari/puzzles/ten01.txt`.
+fn game_dir<'a>(dir: &Path, path: &'a Path) -> Option<&'a str> {
+    let mut components = path.strip_prefix(dir).ok()?.components();
+    let game = components.next()?.as_os_str().to_str();
+    // Files directly in the data directory, such as the list of sources to fetch, belong to no game.
+    components.next().and(game)
+}
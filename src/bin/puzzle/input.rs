@@ -0,0 +1,19 @@
+use std::{
+    fs,
+    io::{self, Read},
+};
+
+use anyhow::{Context, Result};
+
+/// Reads a puzzle from the file at `path`, or from stdin if `path` is `-`.
+pub fn read_input(path: &str) -> Result<String> {
+    if path == "-" {
+        let mut string = String::new();
+        io::stdin()
+            .read_to_string(&mut string)
+            .context("Failed to read puzzle from stdin.")?;
+        Ok(string)
+    } else {
+        fs::read_to_string(path).with_context(|| format!("Failed to read puzzle file '{path}'."))
+    }
+}
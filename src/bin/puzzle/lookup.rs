@@ -0,0 +1,41 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use puzzles::{id::PuzzleId, solutions::SolutionIndex};
+
+use crate::{
+    action::{is_input, print_text},
+    input::read_input,
+    paths::Paths,
+};
+
+#[derive(Clone, Debug, Args)]
+pub struct Lookup {
+    /// Path to the puzzle, `-` for stdin, the puzzle itself, of any kind, or the id of the puzzle.
+    puzzle: String,
+}
+
+impl Lookup {
+    /// Prints the stored solution of the puzzle, without solving it.
+    pub fn run(self, paths: &Paths) -> Result<()> {
+        let path = paths.solution_index();
+        let index = SolutionIndex::load(&path)
+            .with_context(|| format!("Failed to load the solution index {path:?}."))?;
+        if let Ok(id) = self.puzzle.parse::<PuzzleId>() {
+            let (_, solution) = index
+                .find(id)
+                .with_context(|| format!("No stored solution for the puzzle with id {id}."))?;
+            print_text(solution);
+            return Ok(());
+        }
+        let input = if is_input(&self.puzzle) {
+            read_input(&self.puzzle)?
+        } else {
+            self.puzzle.clone()
+        };
+        let (_, solution) = index
+            .lookup(&input)
+            .with_context(|| format!("No stored solution for '{}'.", self.puzzle))?;
+        print_text(&solution);
+        Ok(())
+    }
+}
@@ -1,28 +1,313 @@
+mod action;
 mod camping;
+mod collection;
+mod convert;
+mod crossword_fill;
+mod dedupe;
+mod docs;
+#[cfg(feature = "net")]
+mod fetch;
+mod goldens;
+mod input;
+mod lookup;
+mod minesweeper;
+mod nonogram;
+mod output;
+mod pack;
+mod paths;
+mod play;
+mod progress;
+mod queens;
+mod rate;
+mod sliding;
+mod sokoban;
+mod stats;
 mod sudoku;
+mod threads;
+mod watch;
+mod wordsearch;
 
+use action::{Action, NoOptions};
 use anyhow::Result;
 use camping::Camping;
 use clap::{Parser, Subcommand};
+use collection::Collection;
+use convert::Convert;
+use crossword_fill::CrosswordFillOptions;
+use dedupe::Dedupe;
+use docs::{Completions, Man};
+#[cfg(feature = "net")]
+use fetch::Fetch;
+use goldens::VerifyGoldens;
+use lookup::Lookup;
+use minesweeper::MinesweeperOptions;
+use nonogram::NonogramOptions;
+use pack::PlayPack;
+use paths::Paths;
+use progress::ProgressArgs;
+use queens::QueensOptions;
+use rate::Rate;
+use sliding::SlidingOptions;
+use sokoban::SokobanOptions;
+use stats::Stats;
 use sudoku::Sudoku;
+use threads::Threads;
+use wordsearch::WordsearchGenerate;
+
+/// Counts allocations so that solve statistics can report them.
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOCATOR: puzzles::stats::CountingAllocator = puzzles::stats::CountingAllocator;
 
 #[derive(Clone, Debug, Subcommand)]
 pub enum Game {
+    /// Light up a grid with bulbs that see every white cell, placed next to the right number of numbered walls.
+    #[command(subcommand)]
+    Akari(Action),
+    /// Fill tanks with water from the bottom up to match the row and column counts.
+    #[command(subcommand)]
+    Aquarium(Action),
+    /// Place a fleet of ships in a grid from the row and column counts and the revealed cells.
+    #[command(subcommand)]
+    Battleship(Action),
+    /// Fill a grid with zeros and ones, balanced in every line, with no three alike in a row and no equal lines.
+    #[command(subcommand)]
+    Binairo(Action),
+    /// Place a tent next to each tree so that no two tents touch and every row and column has its number of tents.
     Camping(Camping),
+    /// Shade the cells outside a connected cave so that each number sees that many cave cells.
+    #[command(subcommand)]
+    Cave(Action),
+    /// Solve or rate a collection of puzzles of mixed kinds.
+    #[command(subcommand)]
+    Collection(Collection),
+    /// Print a shell completion script.
+    Completions(Completions),
+    /// Convert files of puzzles between the formats of their game, or collections between manifest formats.
+    Convert(Convert),
+    /// Fill a crossword grid from a list of words.
+    #[command(subcommand)]
+    CrosswordFill(Action<CrosswordFillOptions>),
+    /// Replace the letters of a sum with digits so that it adds up.
+    #[command(subcommand)]
+    Cryptarithm(Action),
+    /// Find puzzles that appear more than once across files, even rotated or reflected, and optionally remove them.
+    Dedupe(Dedupe),
+    /// Find the full set of dominoes that covers a grid of numbers.
+    #[command(subcommand)]
+    Dominosa(Action),
+    /// Download puzzles from the sources listed in the data directory.
+    #[cfg(feature = "net")]
+    Fetch(Fetch),
+    /// Shade cells so that each number counts the shaded cells around it, itself included.
+    #[command(subcommand)]
+    Fillapix(Action),
+    /// Divide a grid into regions whose numbers give their sizes, with no equal regions touching.
+    #[command(subcommand)]
+    Fillomino(Action),
+    /// Fill a Latin square that follows the inequalities between neighbouring cells.
+    #[command(subcommand)]
+    Futoshiki(Action),
+    /// Divide a grid into regions that are symmetric about their dots.
+    #[command(subcommand)]
+    Galaxies(Action),
+    /// Connect the islands with bridges, as many as their numbers say, into one group.
+    #[command(subcommand)]
+    Hashi(Action),
+    /// Shade cells so that rooms have their numbers of shaded cells and white runs cross at most one room border.
+    #[command(subcommand)]
+    Heyawake(Action),
+    /// Fill a grid with a path of consecutive numbers through neighbouring cells.
+    #[command(subcommand)]
+    Hidato(Action),
+    /// Shade cells so that no number repeats in a line and the white cells stay connected.
+    #[command(subcommand)]
+    Hitori(Action),
+    /// Fill a Latin square so that the digits in each room multiply to its product.
+    #[command(subcommand)]
+    Inshi(Action),
+    /// Shade cells so that the weights of the shaded cells in each line add up to its clue.
+    #[command(subcommand)]
+    Kakurasu(Action),
+    /// Fill a Latin square so that each cage gives its target with its operation.
+    #[command(subcommand)]
+    Kenken(Action),
+    /// Shade cells so that each number sees that many white cells, itself included.
+    #[command(subcommand)]
+    Kuromasu(Action),
+    /// Shade a tetromino in every region, with no equal tetrominoes touching and no 2x2 shaded square.
+    #[command(subcommand)]
+    Lits(Action),
+    /// Print the stored solution of a puzzle of any kind that has been solved before.
+    Lookup(Lookup),
+    /// Place magnets in the dominoes of a grid to match the counts of plus and minus poles in each line.
+    #[command(subcommand)]
+    Magnets(Action),
+    /// Print the manual page, or write one for every command to a directory.
+    Man(Man),
+    /// Draw a loop through every pearl, turning on the black ones and going straight on the white ones.
+    #[command(subcommand)]
+    Masyu(Action),
+    /// Find the cells that are certainly mines and the cells that are certainly safe.
+    #[command(subcommand)]
+    Minesweeper(Action<MinesweeperOptions>),
+    /// Color a grid from the runs of each row and column.
+    #[command(subcommand)]
+    Nonogram(Action<NonogramOptions>),
+    /// Shade two cells in every region so that the shaded cells form dominoes.
+    #[command(subcommand)]
+    Norinori(Action),
+    /// Connect the pairs of equal numbers with paths that do not cross.
+    #[command(subcommand)]
+    Numberlink(Action),
+    /// Shade a connected sea around islands whose numbers give their sizes.
+    #[command(subcommand)]
+    Nurikabe(Action),
+    /// Play the puzzles of a pack in order, continuing from where the last session stopped.
+    Play(PlayPack),
+    /// Place queens that cannot attack each other.
+    #[command(subcommand)]
+    Queens(Action<QueensOptions>),
+    /// Rate puzzles of any kind on the difficulty scale shared by every game.
+    Rate(Rate),
+    /// Fill rooms with the numbers up to their sizes, with equal numbers n cells apart in a line.
+    #[command(subcommand)]
+    RippleEffect(Action),
+    /// Place black triangles so that the white areas are all rectangles.
+    #[command(subcommand)]
+    Shakashaka(Action),
+    /// Divide a grid into rectangles that each hold one number, giving its area.
+    #[command(subcommand)]
+    Shikaku(Action),
+    /// Fill a Latin square of building heights that are seen from the edges as their clues say.
+    #[command(subcommand)]
+    Skyscrapers(Action),
+    /// Slide the tiles of a sliding puzzle into order in as few moves as possible.
+    #[command(subcommand)]
+    Sliding(Action<SlidingOptions>),
+    /// Push the boxes onto the goals.
+    #[command(subcommand)]
+    Sokoban(Action<SokobanOptions>),
+    /// Place stars so that every row, column and region has the same number, and no two stars touch.
+    #[command(subcommand)]
+    StarBattle(Action),
+    /// Keep a history of how the sudoku solver does on batches of grids, and compare runs from it.
+    #[command(subcommand)]
+    Stats(Stats),
+    /// Fill a grid with the digits so that no digit repeats in a row, column or box.
+    #[command(subcommand)]
     Sudoku(Sudoku),
+    /// Fill regions with the numbers up to their sizes so that no equal numbers touch.
+    #[command(subcommand)]
+    Suguru(Action),
+    /// Shade a connected wall around clues that give the runs of shaded cells around them.
+    #[command(subcommand)]
+    Tapa(Action),
+    /// Lay a railway from one edge to the other to match the counts of track in each line.
+    #[command(subcommand)]
+    Tracks(Action),
+    /// Check that solving the puzzles under a directory still gives their stored golden solutions.
+    VerifyGoldens(VerifyGoldens),
+    /// Find words hidden in a grid of letters.
+    #[command(subcommand)]
+    Wordsearch(Action<NoOptions, WordsearchGenerate>),
+    /// Shade cells as the arrows count them and draw a loop through every other cell.
+    #[command(subcommand)]
+    Yajilin(Action),
 }
 
+/// Solvers for various puzzles.
 #[derive(Clone, Debug, Parser)]
+#[command(name = "puzzle", version)]
 pub struct Cli {
     #[command(subcommand)]
     game: Game,
+    #[command(flatten)]
+    paths: Paths,
+    #[command(flatten)]
+    progress: ProgressArgs,
+    #[command(flatten)]
+    threads: Threads,
 }
 
 impl Cli {
     pub fn run(self) -> Result<()> {
         match self.game {
-            Game::Camping(camping) => camping.run()?,
-            Game::Sudoku(sudoku) => sudoku.run()?,
+            Game::Akari(action) => action.run::<puzzles::akari::Akari>(&self.paths)?,
+            Game::Aquarium(action) => action.run::<puzzles::aquarium::Aquarium>(&self.paths)?,
+            Game::Battleship(action) => {
+                action.run::<puzzles::battleship::Battleship>(&self.paths)?
+            }
+            Game::Binairo(action) => action.run::<puzzles::binairo::Binairo>(&self.paths)?,
+            Game::Camping(camping) => camping.run(&self.paths, &self.progress)?,
+            Game::Cave(action) => action.run::<puzzles::cave::Cave>(&self.paths)?,
+            Game::Collection(collection) => collection.run()?,
+            Game::Completions(completions) => completions.run()?,
+            Game::Convert(convert) => convert.run()?,
+            Game::CrosswordFill(action) => {
+                action.run::<puzzles::crossword_fill::CrosswordFill>(&self.paths)?
+            }
+            Game::Cryptarithm(action) => {
+                action.run::<puzzles::cryptarithm::Cryptarithm>(&self.paths)?
+            }
+            Game::Dedupe(dedupe) => dedupe.run()?,
+            Game::Dominosa(action) => action.run::<puzzles::dominosa::Dominosa>(&self.paths)?,
+            #[cfg(feature = "net")]
+            Game::Fetch(fetch) => fetch.run(&self.paths)?,
+            Game::Fillapix(action) => action.run::<puzzles::fillapix::Fillapix>(&self.paths)?,
+            Game::Fillomino(action) => action.run::<puzzles::fillomino::Fillomino>(&self.paths)?,
+            Game::Futoshiki(action) => action.run::<puzzles::futoshiki::Futoshiki>(&self.paths)?,
+            Game::Galaxies(action) => action.run::<puzzles::galaxies::Galaxies>(&self.paths)?,
+            Game::Hashi(action) => action.run::<puzzles::hashi::Hashi>(&self.paths)?,
+            Game::Heyawake(action) => action.run::<puzzles::heyawake::Heyawake>(&self.paths)?,
+            Game::Hidato(action) => action.run::<puzzles::hidato::Hidato>(&self.paths)?,
+            Game::Hitori(action) => action.run::<puzzles::hitori::Hitori>(&self.paths)?,
+            Game::Inshi(action) => action.run::<puzzles::inshi::Inshi>(&self.paths)?,
+            Game::Kakurasu(action) => action.run::<puzzles::kakurasu::Kakurasu>(&self.paths)?,
+            Game::Kenken(action) => action.run::<puzzles::kenken::Kenken>(&self.paths)?,
+            Game::Kuromasu(action) => action.run::<puzzles::kuromasu::Kuromasu>(&self.paths)?,
+            Game::Lits(action) => action.run::<puzzles::lits::Lits>(&self.paths)?,
+            Game::Lookup(lookup) => lookup.run(&self.paths)?,
+            Game::Magnets(action) => action.run::<puzzles::magnets::Magnets>(&self.paths)?,
+            Game::Man(man) => man.run()?,
+            Game::Masyu(action) => action.run::<puzzles::masyu::Masyu>(&self.paths)?,
+            Game::Minesweeper(action) => {
+                action.run::<puzzles::minesweeper::Minesweeper>(&self.paths)?
+            }
+            Game::Nonogram(action) => action.run::<puzzles::nonogram::Nonogram>(&self.paths)?,
+            Game::Norinori(action) => action.run::<puzzles::norinori::Norinori>(&self.paths)?,
+            Game::Numberlink(action) => {
+                action.run::<puzzles::numberlink::Numberlink>(&self.paths)?
+            }
+            Game::Nurikabe(action) => action.run::<puzzles::nurikabe::Nurikabe>(&self.paths)?,
+            Game::Play(play) => play.run(&self.paths)?,
+            Game::Queens(action) => action.run::<puzzles::queens::Queens>(&self.paths)?,
+            Game::Rate(rate) => rate.run()?,
+            Game::RippleEffect(action) => {
+                action.run::<puzzles::rippleeffect::RippleEffect>(&self.paths)?
+            }
+            Game::Shakashaka(action) => {
+                action.run::<puzzles::shakashaka::Shakashaka>(&self.paths)?
+            }
+            Game::Shikaku(action) => action.run::<puzzles::shikaku::Shikaku>(&self.paths)?,
+            Game::Skyscrapers(action) => {
+                action.run::<puzzles::skyscrapers::Skyscrapers>(&self.paths)?
+            }
+            Game::Sliding(action) => action.run::<puzzles::sliding::Sliding>(&self.paths)?,
+            Game::Sokoban(action) => action.run::<puzzles::sokoban::Sokoban>(&self.paths)?,
+            Game::StarBattle(action) => {
+                action.run::<puzzles::starbattle::StarBattle>(&self.paths)?
+            }
+            Game::Stats(stats) => stats.run(&self.paths, &self.progress)?,
+            Game::Sudoku(sudoku) => sudoku.run(&self.paths, &self.progress)?,
+            Game::Suguru(action) => action.run::<puzzles::suguru::Suguru>(&self.paths)?,
+            Game::Tapa(action) => action.run::<puzzles::tapa::Tapa>(&self.paths)?,
+            Game::Tracks(action) => action.run::<puzzles::tracks::Tracks>(&self.paths)?,
+            Game::VerifyGoldens(verify_goldens) => verify_goldens.run()?,
+            Game::Wordsearch(action) => {
+                action.run::<puzzles::wordsearch::Wordsearch>(&self.paths)?
+            }
+            Game::Yajilin(action) => action.run::<puzzles::yajilin::Yajilin>(&self.paths)?,
         }
         Ok(())
     }
@@ -30,6 +315,7 @@ impl Cli {
 
 pub fn main() -> Result<()> {
     let cli = Cli::parse();
-    cli.run()?;
+    // Everything the command does in parallel runs on this pool.
+    cli.threads.pool()?.install(|| cli.run())?;
     Ok(())
 }
@@ -0,0 +1,52 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+use clap::Args;
+use puzzles::{
+    location::Location,
+    minesweeper::{self, Cell, Minesweeper},
+};
+
+use crate::{action::SolveOptions, output::Record, paths::Paths};
+
+#[derive(Clone, Debug, Args)]
+pub struct MinesweeperOptions {
+    /// The total number of mines on the board.
+    #[arg(long)]
+    mines: Option<usize>,
+    /// Also print the chance of each undecided cell being a mine.
+    #[arg(long)]
+    probabilities: bool,
+}
+
+impl SolveOptions<Minesweeper> for MinesweeperOptions {
+    fn prepare(&self, board: minesweeper::Board, _paths: &Paths) -> Result<minesweeper::Board> {
+        Ok(match self.mines {
+            Some(mines) => board.with_mines(mines)?,
+            None => board,
+        })
+    }
+
+    fn solve(&self, id: &str, board: &minesweeper::Board) -> Option<Result<Vec<Record>>> {
+        self.probabilities
+            .then(|| solve_with_probabilities(id, board).map(|record| vec![record]))
+    }
+}
+
+fn solve_with_probabilities(id: &str, board: &minesweeper::Board) -> Result<Record> {
+    let Some(analysis) = minesweeper::analyze(board) else {
+        return Ok(Record::no_solution(id).with_text(format!(
+            "No arrangement of mines is consistent with '{id}'."
+        )));
+    };
+    let mut solution = analysis.to_string();
+    for loc in Location::grid_iter(board.dim()) {
+        if board.cell(loc) != Cell::Hidden || analysis.verdict(loc).is_some() {
+            continue;
+        }
+        if let Some(probability) = analysis.probability(loc) {
+            writeln!(solution, "{loc}: {:.1}%", probability * 100.)?;
+        }
+    }
+    Ok(Record::solved(id, solution))
+}
@@ -0,0 +1,26 @@
+use anyhow::Result;
+use clap::Args;
+use puzzles::nonogram::{self, Nonogram};
+
+use crate::{action::SolveOptions, output::Record};
+
+#[derive(Clone, Debug, Args)]
+pub struct NonogramOptions {
+    /// Draw the solution in color in the terminal.
+    #[arg(long)]
+    color: bool,
+}
+
+impl SolveOptions<Nonogram> for NonogramOptions {
+    fn solve(&self, id: &str, board: &nonogram::Board) -> Option<Result<Vec<Record>>> {
+        if !self.color {
+            return None;
+        }
+        Some(Ok(vec![match nonogram::solve(board) {
+            Some(solution) => {
+                Record::solved(id, solution.to_string()).with_text(solution.render_ansi())
+            }
+            None => Record::no_solution(id),
+        }]))
+    }
+}
@@ -0,0 +1,314 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::{self, IsTerminal, Write},
+    path::PathBuf,
+};
+
+use anyhow::{ensure, Context, Result};
+use clap::{Args, ValueEnum};
+use puzzles::{
+    difficulty::{Difficulty, Rating},
+    id::PuzzleId,
+    puzzle::Puzzle,
+    stats::SolveStats,
+};
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Messages meant for people.
+    #[default]
+    Text,
+    /// A JSON array with an object per result.
+    Json,
+    /// A header and then a line per result, with a column per statistic.
+    Csv,
+}
+
+#[derive(Clone, Debug, Args)]
+pub struct OutputArgs {
+    /// How to write the results.
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+    /// Write the results to a file instead of stdout.
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Solved,
+    NoSolution,
+    Rated,
+    Generated,
+    /// The solution matches the stored golden solution.
+    Matched,
+    /// The solution differs from the stored golden solution.
+    Differs,
+    /// The golden solution was written from the solution.
+    Blessed,
+    /// The puzzle is the same as an earlier one, written another way.
+    Duplicate,
+    Error,
+}
+
+/// The result of solving, rating or generating a single puzzle.
+#[derive(Clone, Debug, Serialize)]
+pub struct Record {
+    /// The puzzle argument, map name or seed the result is for.
+    pub id: String,
+    pub status: Status,
+    /// The id of the puzzle, which is the same in every run, for joining results with other outputs and the
+    /// solution index.
+    pub puzzle_id: Option<PuzzleId>,
+    /// The kind of puzzle, by the name [`puzzles::puzzle::visit`] knows it by, for rated puzzles.
+    pub kind: Option<String>,
+    /// How hard the puzzle is, for rated puzzles. The score it is graded from is the `rating` statistic.
+    pub difficulty: Option<Difficulty>,
+    /// The solution, or the puzzle for generated puzzles, in the game's text format.
+    pub solution: Option<String>,
+    pub stats: BTreeMap<&'static str, u64>,
+    /// What went wrong, for results with [`Status::Error`].
+    pub error: Option<String>,
+    /// What the text format prints, which is sometimes more than the solution.
+    #[serde(skip)]
+    pub text: String,
+}
+
+impl Record {
+    fn new(id: impl Into<String>, status: Status, solution: Option<String>, text: String) -> Self {
+        Self {
+            id: id.into(),
+            status,
+            puzzle_id: None,
+            kind: None,
+            difficulty: None,
+            solution: solution.map(|solution| solution.trim_end_matches('\n').to_string()),
+            stats: BTreeMap::new(),
+            error: None,
+            text,
+        }
+    }
+
+    pub fn solved(id: impl Into<String>, solution: String) -> Self {
+        Self::new(id, Status::Solved, Some(solution.clone()), solution)
+    }
+
+    pub fn no_solution(id: impl Into<String>) -> Self {
+        let id = id.into();
+        let text = format!("No solution found for '{id}'.");
+        Self::new(id, Status::NoSolution, None, text)
+    }
+
+    pub fn rated(id: impl Into<String>, kind: impl Into<String>, rating: Rating) -> Self {
+        Self {
+            kind: Some(kind.into()),
+            difficulty: Some(rating.difficulty),
+            ..Self::new(id, Status::Rated, None, rating.to_string())
+        }
+        .with_stat("rating", rating.score)
+    }
+
+    /// A generated puzzle, identified by its seed.
+    pub fn generated(seed: u64, puzzle: String) -> Self {
+        Self::new(
+            seed.to_string(),
+            Status::Generated,
+            Some(puzzle.clone()),
+            puzzle,
+        )
+        .with_stat("seed", seed)
+    }
+
+    /// A solution compared against its golden solution, listing the differences.
+    pub fn compared(id: impl Into<String>, differences: Vec<String>) -> Self {
+        let id = id.into();
+        if differences.is_empty() {
+            let text = format!("'{id}' matches its golden solution.");
+            return Self::new(id, Status::Matched, None, text);
+        }
+        let text = format!(
+            "'{id}' differs from its golden solution:\n  {}",
+            differences.join("\n  ")
+        );
+        Self {
+            error: Some(differences.join("\n")),
+            ..Self::new(id, Status::Differs, None, text)
+        }
+    }
+
+    pub fn blessed(id: impl Into<String>, solution: String) -> Self {
+        let id = id.into();
+        let text = format!("Wrote the golden solution of '{id}'.");
+        Self::new(id, Status::Blessed, Some(solution), text)
+    }
+
+    /// A puzzle that is the same as the `original` read before it.
+    pub fn duplicate(id: impl Into<String>, original: &str) -> Self {
+        let id = id.into();
+        let text = format!("'{id}' duplicates '{original}'.");
+        Self::new(id, Status::Duplicate, None, text)
+    }
+
+    /// A result made up of statistics, such as those of a whole set of puzzles, that counts as solved.
+    pub fn summary(id: impl Into<String>, text: String) -> Self {
+        Self::new(id, Status::Solved, None, text)
+    }
+
+    pub fn error(id: impl Into<String>, error: impl ToString) -> Self {
+        let id = id.into();
+        let error = error.to_string();
+        let text = format!("Error while solving '{id}': {error}");
+        Self {
+            error: Some(error),
+            ..Self::new(id, Status::Error, None, text)
+        }
+    }
+
+    pub fn with_stat(mut self, name: &'static str, value: u64) -> Self {
+        self.stats.insert(name, value);
+        self
+    }
+
+    /// Adds what solving cost as statistics.
+    pub fn with_solve_stats(self, stats: &SolveStats) -> Self {
+        stats
+            .named()
+            .into_iter()
+            .fold(self, |record, (name, value)| record.with_stat(name, value))
+    }
+
+    /// Adds how hard the puzzle is, with the score it is graded from as the `rating` statistic.
+    pub fn with_rating(mut self, rating: Rating) -> Self {
+        self.difficulty = Some(rating.difficulty);
+        self.with_stat("rating", rating.score)
+    }
+
+    pub fn with_puzzle_id(mut self, id: PuzzleId) -> Self {
+        self.puzzle_id = Some(id);
+        self
+    }
+
+    /// Identifies the puzzle the result is for by its [`PuzzleId`].
+    pub fn with_puzzle<P: Puzzle>(self, instance: &P::Instance) -> Self {
+        self.with_puzzle_id(PuzzleId::of::<P>(instance))
+    }
+
+    /// Replaces what the text format prints for the result.
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.text = text.into();
+        self
+    }
+}
+
+impl OutputArgs {
+    pub fn is_text(&self) -> bool {
+        self.format == Format::Text
+    }
+
+    /// Whether the text format is written to a terminal, which can show highlights.
+    pub fn is_terminal(&self) -> bool {
+        self.is_text() && self.output.is_none() && io::stdout().is_terminal()
+    }
+
+    /// Writes the records in the chosen format. The text format writes errors to stderr and everything else to the
+    /// output.
+    pub fn write(&self, records: &[Record]) -> Result<()> {
+        let mut writer: Box<dyn Write> = match &self.output {
+            Some(path) => Box::new(
+                File::create(path)
+                    .with_context(|| format!("Failed to create output file '{path:?}'."))?,
+            ),
+            None => Box::new(io::stdout().lock()),
+        };
+        match self.format {
+            Format::Text => {
+                for record in records {
+                    // Some texts end in a newline and others do not, but each should end up on its own lines.
+                    let text = record.text.strip_suffix('\n').unwrap_or(&record.text);
+                    if record.status == Status::Error {
+                        eprintln!("{text}");
+                    } else {
+                        writeln!(writer, "{text}")?;
+                    }
+                }
+            }
+            Format::Json => {
+                serde_json::to_writer_pretty(&mut writer, records)?;
+                writeln!(writer)?;
+            }
+            Format::Csv => write_csv(&mut writer, records)?,
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Fails if any puzzle went unsolved, so that scripts can tell from the exit code.
+pub fn ensure_solved(records: &[Record]) -> Result<()> {
+    let num_failed = records
+        .iter()
+        .filter(|record| matches!(record.status, Status::NoSolution | Status::Error))
+        .count();
+    ensure!(
+        num_failed == 0,
+        "Failed to solve {num_failed} of {} puzzles.",
+        records.len()
+    );
+    Ok(())
+}
+
+fn write_csv(writer: &mut impl Write, records: &[Record]) -> Result<()> {
+    let stats = records
+        .iter()
+        .flat_map(|record| record.stats.keys().copied())
+        .collect::<BTreeSet<_>>();
+    let header = [
+        "id",
+        "puzzle_id",
+        "status",
+        "solution",
+        "error",
+        "kind",
+        "difficulty",
+    ]
+    .into_iter()
+    .chain(stats.iter().copied())
+    .collect::<Vec<_>>();
+    writeln!(writer, "{}", header.join(","))?;
+    for record in records {
+        let status = serde_json::to_value(record.status)?;
+        let mut fields = vec![
+            csv_field(&record.id),
+            record
+                .puzzle_id
+                .map_or_else(String::new, |id| id.to_string()),
+            csv_field(status.as_str().unwrap_or_default()),
+            csv_field(record.solution.as_deref().unwrap_or_default()),
+            csv_field(record.error.as_deref().unwrap_or_default()),
+            csv_field(record.kind.as_deref().unwrap_or_default()),
+            record
+                .difficulty
+                .map_or_else(String::new, |difficulty| difficulty.to_string()),
+        ];
+        fields.extend(stats.iter().map(|stat| {
+            record
+                .stats
+                .get(stat)
+                .map_or_else(String::new, u64::to_string)
+        }));
+        writeln!(writer, "{}", fields.join(","))?;
+    }
+    Ok(())
+}
+
+/// Quotes a field if it contains characters that would otherwise end it.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,126 @@
+//! Plays the puzzles of a pack in order, across games, keeping the progress in the output directory so that the next
+//! session picks up at the first puzzle that has not been completed.
+
+use std::{fs, io, path::PathBuf};
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use puzzles::{
+    collection::{self, Entry, Progress},
+    puzzle::{self, Puzzle, Visitor},
+    sudoku,
+};
+
+use crate::{action::print_text, paths::Paths, play::play};
+
+#[derive(Clone, Debug, Args)]
+pub struct PlayPack {
+    /// The name of the pack, as listed in the manifest.
+    #[arg(long)]
+    pack: String,
+    /// The manifest of the collection holding the pack. Defaults to `packs.toml` in the data directory.
+    #[arg(long)]
+    collection: Option<PathBuf>,
+    /// Start the pack over, forgetting the puzzles completed before.
+    #[arg(long)]
+    reset: bool,
+}
+
+/// Shows the puzzle and reads solutions from stdin, each ending with an empty line, until one matches the solver's
+/// or the player gives up with an empty solution. Returns whether the puzzle was solved. Only the number of
+/// differences is shown, so that wrong solutions do not give the answer away.
+struct PlayText<'a>(&'a Entry);
+
+impl Visitor for PlayText<'_> {
+    type Output = Result<bool>;
+
+    fn visit<P: Puzzle>(self) -> Self::Output {
+        let instance = P::parse(&self.0.puzzle)?;
+        let Some(solution) = P::solve(&instance)? else {
+            bail!("Puzzle '{}' has no solution.", self.0.id);
+        };
+        print_text(&P::format_instance(&instance));
+        println!("Enter your solution, ending with an empty line, or only an empty line to stop.");
+        loop {
+            let answer = read_answer()?;
+            if answer.trim().is_empty() {
+                return Ok(false);
+            }
+            let differences = P::diff(&answer, &solution);
+            if differences.is_empty() {
+                return Ok(true);
+            }
+            println!(
+                "{} differences from the solution. Try again.",
+                differences.len()
+            );
+        }
+    }
+}
+
+/// Reads lines from stdin up to an empty line or the end of the input.
+fn read_answer() -> Result<String> {
+    let mut answer = String::new();
+    loop {
+        let mut line = String::new();
+        if io::stdin()
+            .read_line(&mut line)
+            .context("Failed to read solution from stdin.")?
+            == 0
+            || line.trim().is_empty()
+        {
+            return Ok(answer);
+        }
+        answer.push_str(&line);
+    }
+}
+
+impl PlayPack {
+    pub fn run(self, paths: &Paths) -> Result<()> {
+        let manifest = self
+            .collection
+            .unwrap_or_else(|| paths.data_dir().join("packs.toml"));
+        let collection = collection::load(&manifest)
+            .with_context(|| format!("Failed to load collection {manifest:?}."))?;
+        let puzzles = collection.pack(&self.pack)?;
+        let progress_path = paths.pack_progress(&self.pack);
+        let mut progress = if self.reset {
+            Progress::default()
+        } else {
+            Progress::load(&progress_path)?
+        };
+        while let Some(entry) = progress.next(&puzzles) {
+            let done = puzzles
+                .iter()
+                .filter(|entry| progress.completed.contains(&entry.id))
+                .count();
+            println!(
+                "Puzzle {} of {}: '{}', a {}.",
+                done + 1,
+                puzzles.len(),
+                entry.id,
+                entry.kind
+            );
+            // Sudokus have a player of their own, and every other game is played by entering the solution as text.
+            let solved = if entry.kind == sudoku::Sudoku::NAME {
+                play(sudoku::Sudoku::parse(&entry.puzzle)?)?
+            } else {
+                puzzle::visit(&entry.kind, PlayText(entry))??
+            };
+            if !solved {
+                println!(
+                    "Stopped at '{}'. Play the pack again to continue from there.",
+                    entry.id
+                );
+                return Ok(());
+            }
+            progress.completed.insert(entry.id.clone());
+            if let Some(dir) = progress_path.parent() {
+                fs::create_dir_all(dir).with_context(|| format!("Failed to create {dir:?}."))?;
+            }
+            progress.save(&progress_path)?;
+        }
+        println!("Completed every puzzle of pack '{}'.", self.pack);
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use puzzles::puzzle::{self, Puzzle, Visitor};
+
+/// Where puzzle data is read from and output written to. Relative paths are resolved against the working
+/// directory, so the defaults only work from the root of the repository.
+#[derive(Clone, Debug, Args)]
+pub struct Paths {
+    /// Directory to read puzzle data from.
+    #[arg(long, global = true, env = "PUZZLES_DATA_DIR", default_value = "data")]
+    data_dir: PathBuf,
+    /// Directory to write solutions and reports to.
+    #[arg(
+        long,
+        global = true,
+        env = "PUZZLES_OUTPUT_DIR",
+        default_value = "output"
+    )]
+    output_dir: PathBuf,
+}
+
+impl Paths {
+    /// The data directory itself, for files shared by every game.
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    /// The data directory of a game, such as `data/sudoku`.
+    pub fn data(&self, game: &str) -> PathBuf {
+        self.data_dir.join(game)
+    }
+
+    /// Where solutions are stored so that solving the same puzzle again only looks them up.
+    pub fn solution_index(&self) -> PathBuf {
+        self.output_dir.join("solutions.jsonl")
+    }
+
+    /// Where `puzzle stats record` keeps the summaries of its runs.
+    pub fn history(&self) -> PathBuf {
+        self.output_dir.join("history.jsonl")
+    }
+
+    /// Where the progress through a pack is kept between sessions.
+    pub fn pack_progress(&self, pack: &str) -> PathBuf {
+        self.output_dir.join("packs").join(format!("{pack}.json"))
+    }
+
+    /// The output directory of a game, such as `output/sudoku`.
+    pub fn output(&self, game: &str) -> PathBuf {
+        self.output_dir.join(game)
+    }
+}
+
+/// The data directory of a kind of puzzle, which is named after its module, such as `rippleeffect` for
+/// `ripple-effect`.
+#[cfg(feature = "net")]
+pub fn data_dir_name(kind: &str) -> String {
+    match kind {
+        "ripple-effect" => "rippleeffect".to_string(),
+        "star-battle" => "starbattle".to_string(),
+        kind => kind.replace('-', "_"),
+    }
+}
+
+/// The kind of puzzle whose data is in the directory of the given name, the reverse of [`data_dir_name`].
+pub fn kind_of_data_dir(name: &str) -> String {
+    match name {
+        "rippleeffect" => "ripple-effect".to_string(),
+        "starbattle" => "star-battle".to_string(),
+        name => name.replace('_', "-"),
+    }
+}
+
+/// Whether [`puzzle::visit`] knows the kind of puzzle.
+pub fn is_kind(kind: &str) -> bool {
+    puzzle::visit(kind, IsPuzzle).is_ok()
+}
+
+/// The game whose data directory the file is in, such as `sudoku` for `data/sudoku/grids/easy50.txt`.
+pub fn kind_of_path(path: &Path) -> Option<String> {
+    if !path.is_file() {
+        return None;
+    }
+    path.ancestors()
+        .skip(1)
+        .filter_map(|dir| dir.file_name()?.to_str())
+        .map(kind_of_data_dir)
+        .find(|kind| is_kind(kind))
+}
+
+/// Only checks that a kind of puzzle exists.
+struct IsPuzzle;
+
+impl Visitor for IsPuzzle {
+    type Output = ();
+
+    fn visit<P: Puzzle>(self) -> Self::Output {}
+}
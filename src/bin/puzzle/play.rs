@@ -0,0 +1,304 @@
+//! An interactive sudoku player for the terminal.
+
+use anyhow::Result;
+use puzzles::sudoku::{self, Board, BoardCell, CellValue, Hint, Location, ValueSet};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    DefaultTerminal, Frame,
+};
+
+const HELP: &str =
+    "arrows/hjkl move  1-9 enter  0/del clear  p pencil  u undo  r redo  ? hint  c check  q quit";
+
+/// What undo and redo restore.
+#[derive(Clone, Debug)]
+struct Snapshot {
+    board: Board,
+    marks: [ValueSet; 81],
+}
+
+struct Game {
+    givens: Board,
+    /// The solution of the givens, found the first time the player checks their progress.
+    solution: Option<Board>,
+    current: Snapshot,
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    /// The row and column of the cursor.
+    cursor: (u8, u8),
+    /// Whether digits toggle pencil marks instead of entering values.
+    pencil: bool,
+    message: String,
+}
+
+impl Game {
+    fn new(givens: Board) -> Self {
+        Self {
+            current: Snapshot {
+                board: givens.clone(),
+                marks: [ValueSet::NONE; 81],
+            },
+            givens,
+            solution: None,
+            undo: vec![],
+            redo: vec![],
+            cursor: (0, 0),
+            pencil: false,
+            message: String::new(),
+        }
+    }
+
+    fn cursor_location(&self) -> Location {
+        Location::new(self.cursor.0, self.cursor.1).expect("The cursor stays on the board.")
+    }
+
+    fn move_cursor(&mut self, rows: i8, cols: i8) {
+        let (row, col) = self.cursor;
+        self.cursor = (
+            (row as i8 + rows).rem_euclid(9) as u8,
+            (col as i8 + cols).rem_euclid(9) as u8,
+        );
+    }
+
+    /// Changes the current state, keeping the old one for undo.
+    fn edit(&mut self, change: impl FnOnce(&mut Snapshot)) {
+        let location = self.cursor_location();
+        if self.givens.get(location) != BoardCell::Empty {
+            self.message = "Givens cannot be changed.".to_string();
+            return;
+        }
+        self.undo.push(self.current.clone());
+        self.redo.clear();
+        change(&mut self.current);
+        self.message = if self.is_solved() {
+            "Solved!".to_string()
+        } else {
+            String::new()
+        };
+    }
+
+    fn enter(&mut self, value: CellValue) {
+        let location = self.cursor_location();
+        if self.pencil {
+            self.edit(|current| {
+                let marks = &mut current.marks[location.index()];
+                *marks = if marks.contains(value) {
+                    *marks - value
+                } else {
+                    *marks | ValueSet::from_value(value)
+                };
+            });
+        } else {
+            self.edit(|current| current.board.set(location, BoardCell::Value(value)));
+        }
+    }
+
+    fn clear(&mut self) {
+        let location = self.cursor_location();
+        self.edit(|current| {
+            current.board.set(location, BoardCell::Empty);
+            current.marks[location.index()] = ValueSet::NONE;
+        });
+    }
+
+    fn undo(&mut self) {
+        if let Some(previous) = self.undo.pop() {
+            self.redo
+                .push(std::mem::replace(&mut self.current, previous));
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(next) = self.redo.pop() {
+            self.undo.push(std::mem::replace(&mut self.current, next));
+        }
+    }
+
+    /// Places a value the solver can deduce, moving the cursor to it.
+    fn hint(&mut self) {
+        match sudoku::hint(&self.current.board) {
+            Ok(Some(Hint { location, value })) => {
+                self.cursor = location.to_row_col();
+                self.edit(|current| current.board.set(location, BoardCell::Value(value)));
+                if self.message.is_empty() {
+                    let (row, col) = location.to_row_col();
+                    self.message =
+                        format!("{value} follows in row {}, column {}.", row + 1, col + 1);
+                }
+            }
+            Ok(None) => self.message = "No value follows without guessing.".to_string(),
+            Err(_) => self.message = "The board has a mistake.".to_string(),
+        }
+    }
+
+    /// Tells how many entered values differ from the solution, moving the cursor to the first, without showing what
+    /// they should be.
+    fn check(&mut self) {
+        if self.solution.is_none() {
+            self.solution = sudoku::solve(&self.givens)
+                .ok()
+                .map(|(solution, _, _)| solution)
+                .filter(Board::finished);
+        }
+        let Some(solution) = &self.solution else {
+            self.message = "The sudoku has no solution to check against.".to_string();
+            return;
+        };
+        let mistakes = self.current.board.check_against(solution);
+        self.message = match mistakes.first() {
+            None => "No mistakes so far.".to_string(),
+            Some(&first) => {
+                self.cursor = first.to_row_col();
+                format!("{} values are wrong.", mistakes.len())
+            }
+        };
+    }
+
+    fn is_solved(&self) -> bool {
+        self.current.board.finished() && self.current.board.validate_all().is_empty()
+    }
+
+    /// Whether each cell holds a value that appears twice in one of its groups.
+    fn conflicts(&self) -> [bool; 81] {
+        let mut conflicts = [false; 81];
+        for error in self.current.board.validate_all() {
+            for location in error.group() {
+                if self.current.board.get(location) == BoardCell::Value(error.value()) {
+                    conflicts[location.index()] = true;
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Handles a key, returning whether to quit.
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return true,
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return true,
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1, 0),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1, 0),
+            KeyCode::Left | KeyCode::Char('h') => self.move_cursor(0, -1),
+            KeyCode::Right | KeyCode::Char('l') => self.move_cursor(0, 1),
+            KeyCode::Char('0') | KeyCode::Char('.') | KeyCode::Backspace | KeyCode::Delete => {
+                self.clear()
+            }
+            KeyCode::Char(c @ '1'..='9') => {
+                let value = c
+                    .to_digit(10)
+                    .and_then(|digit| std::num::NonZeroU8::new(digit as u8))
+                    .and_then(CellValue::new)
+                    .expect("Digits 1 to 9 are cell values.");
+                self.enter(value);
+            }
+            KeyCode::Char('p') => self.pencil = !self.pencil,
+            KeyCode::Char('u') => self.undo(),
+            KeyCode::Char('r') => self.redo(),
+            KeyCode::Char('?') => self.hint(),
+            KeyCode::Char('c') => self.check(),
+            _ => {}
+        }
+        false
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [grid_area, status_area, help_area] = Layout::vertical([
+            Constraint::Length(9 * 3 + 2),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        frame.render_widget(Paragraph::new(self.grid_lines()), grid_area);
+        let mode = if self.pencil { "pencil" } else { "value" };
+        let status = format!("[{mode}] {}", self.message);
+        frame.render_widget(Paragraph::new(status), status_area);
+        frame.render_widget(
+            Paragraph::new(HELP).style(Style::new().fg(Color::DarkGray)),
+            help_area,
+        );
+    }
+
+    /// Every cell is three lines of three characters, showing either its value in the middle or its pencil marks.
+    fn grid_lines(&self) -> Vec<Line<'static>> {
+        let conflicts = self.conflicts();
+        let mut lines = vec![];
+        for row in 0..9u8 {
+            if row > 0 && row % 3 == 0 {
+                lines.push(Line::from("─".repeat(9 * 4 + 2)));
+            }
+            for subline in 0..3u8 {
+                let mut spans = vec![];
+                for col in 0..9u8 {
+                    if col > 0 && col % 3 == 0 {
+                        spans.push(Span::raw("│"));
+                    }
+                    let location = Location::new(row, col).expect("Rows and columns are below 9.");
+                    let text = match self.current.board.get(location) {
+                        BoardCell::Value(value) if subline == 1 => format!(" {value} "),
+                        BoardCell::Value(_) => "   ".to_string(),
+                        BoardCell::Empty => (1..=3)
+                            .map(|offset| {
+                                let value = std::num::NonZeroU8::new(subline * 3 + offset)
+                                    .and_then(CellValue::new)
+                                    .expect("Marks are cell values.");
+                                let marks = self.current.marks[location.index()];
+                                if marks.contains(value) {
+                                    value.to_char()
+                                } else {
+                                    ' '
+                                }
+                            })
+                            .collect(),
+                    };
+                    spans.push(Span::styled(
+                        format!("{text} "),
+                        self.cell_style(location, &conflicts),
+                    ));
+                }
+                lines.push(Line::from(spans));
+            }
+        }
+        lines
+    }
+
+    fn cell_style(&self, location: Location, conflicts: &[bool; 81]) -> Style {
+        let mut style = if self.givens.get(location) != BoardCell::Empty {
+            Style::new().add_modifier(Modifier::BOLD)
+        } else if self.current.board.get(location) == BoardCell::Empty {
+            Style::new().fg(Color::DarkGray)
+        } else {
+            Style::new().fg(Color::Cyan)
+        };
+        if conflicts[location.index()] {
+            style = style.fg(Color::Red);
+        }
+        if location.to_row_col() == self.cursor {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        style
+    }
+}
+
+fn run(terminal: &mut DefaultTerminal, mut game: Game) -> Result<bool> {
+    loop {
+        terminal.draw(|frame| game.draw(frame))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && game.handle_key(key.code, key.modifiers) {
+                return Ok(game.is_solved());
+            }
+        }
+    }
+}
+
+/// Plays the board until the player quits, restoring the terminal afterwards even on errors. Returns whether the
+/// player solved it.
+pub fn play(board: Board) -> Result<bool> {
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, Game::new(board));
+    ratatui::restore();
+    result
+}
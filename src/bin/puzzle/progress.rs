@@ -0,0 +1,76 @@
+//! Progress bars for commands that solve many puzzles. They are drawn on stderr, so they do not mix with the results,
+//! and only when stderr is a terminal.
+
+use clap::Args;
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+#[derive(Clone, Debug, Args)]
+pub struct ProgressArgs {
+    /// Do not show progress bars.
+    #[arg(long, short, global = true)]
+    quiet: bool,
+}
+
+/// An overall bar, and a bar for each set of puzzles that is being solved.
+pub struct Progress {
+    bars: MultiProgress,
+    overall: ProgressBar,
+}
+
+impl Progress {
+    /// Starts the overall bar, counting up to the total number of puzzles.
+    pub fn new(args: &ProgressArgs, total: usize) -> Self {
+        let bars = if args.quiet {
+            MultiProgress::with_draw_target(ProgressDrawTarget::hidden())
+        } else {
+            MultiProgress::new()
+        };
+        let overall = bars.add(bar(total, "total"));
+        Self { bars, overall }
+    }
+
+    /// Adds a bar for a set of puzzles, which advances the overall bar along with its own.
+    pub fn set(&self, name: &str, len: usize) -> SetProgress {
+        let bar = self.bars.insert_before(&self.overall, bar(len, name));
+        SetProgress {
+            bar,
+            overall: self.overall.clone(),
+        }
+    }
+
+    /// Advances the overall bar by one puzzle, for puzzles that are not part of a set.
+    pub fn inc(&self) {
+        self.overall.inc(1);
+    }
+
+    /// Removes the bars, so that the results are printed on a clean terminal.
+    pub fn finish(&self) {
+        self.overall.finish_and_clear();
+        self.bars.clear().ok();
+    }
+}
+
+pub struct SetProgress {
+    bar: ProgressBar,
+    overall: ProgressBar,
+}
+
+impl SetProgress {
+    pub fn inc(&self) {
+        self.bar.inc(1);
+        self.overall.inc(1);
+    }
+
+    pub fn finish(&self) {
+        self.bar.finish_and_clear();
+    }
+}
+
+fn bar(len: usize, name: &str) -> ProgressBar {
+    let style = ProgressStyle::with_template("{msg:>20} [{bar:40}] {pos}/{len} ({eta} left)")
+        .expect("The progress bar template is valid.")
+        .progress_chars("=> ");
+    ProgressBar::new(len as u64)
+        .with_style(style)
+        .with_message(name.to_string())
+}
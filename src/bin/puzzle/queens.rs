@@ -0,0 +1,47 @@
+use anyhow::Result;
+use clap::Args;
+use puzzles::queens::{self, Queens};
+
+use crate::{action::SolveOptions, output::Record};
+
+#[derive(Clone, Debug, Args)]
+pub struct QueensOptions {
+    /// Print the number of solutions instead of a solution.
+    #[arg(long, conflicts_with = "all")]
+    count: bool,
+    /// Print every solution.
+    #[arg(long)]
+    all: bool,
+}
+
+impl SolveOptions<Queens> for QueensOptions {
+    /// With `all`, returns a result per solution, and the text format ends with the number of solutions.
+    fn solve(&self, id: &str, board: &queens::Board) -> Option<Result<Vec<Record>>> {
+        if self.count {
+            let num_solutions = queens::count_solutions(board, usize::MAX);
+            let record = match queens::solve(board) {
+                Some(solution) => Record::solved(id, solution.to_string()),
+                None => Record::no_solution(id),
+            };
+            Some(Ok(vec![record
+                .with_stat("solutions", num_solutions as u64)
+                .with_text(num_solutions.to_string())]))
+        } else if self.all {
+            let solutions = queens::solutions(board, usize::MAX);
+            let num_solutions = solutions.len();
+            let mut records = solutions
+                .into_iter()
+                .map(|solution| {
+                    Record::solved(id, solution.to_string()).with_text(format!("{solution}\n"))
+                })
+                .collect::<Vec<_>>();
+            match records.last_mut() {
+                Some(last) => last.text.push_str(&format!("{num_solutions} solutions.")),
+                None => records.push(Record::no_solution(id).with_text("0 solutions.")),
+            }
+            Some(Ok(records))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! Rates puzzles of any kind on the difficulty scale shared by every puzzle, so that files of different kinds can be
+//! rated together and give records of the same shape.
+
+use std::path::Path;
+
+use anyhow::{bail, ensure, Result};
+use clap::Args;
+use puzzles::{
+    difficulty::Rating,
+    id::PuzzleId,
+    puzzle::{self, Puzzle, Visitor},
+};
+
+use crate::{
+    action::is_input,
+    input::read_input,
+    output::{OutputArgs, Record, Status},
+    paths,
+};
+
+#[derive(Clone, Debug, Args)]
+pub struct Rate {
+    /// Paths to puzzle files, `-` for stdin, or puzzles themselves. Every puzzle in a file is rated.
+    #[arg(required = true)]
+    puzzles: Vec<String>,
+    /// The kind of puzzle, such as `sudoku`. By default, files under a game's data directory, such as
+    /// `data/sudoku/grids/easy50.txt`, are of that game, and other puzzles of the first kind that can rate them.
+    #[arg(long)]
+    kind: Option<String>,
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// Rates every puzzle in the input, with its id, failing if any cannot be read or rated.
+struct RateAll<'a>(&'a str);
+
+impl Visitor for RateAll<'_> {
+    type Output = puzzles::Result<Vec<(PuzzleId, Rating)>>;
+
+    fn visit<P: Puzzle>(self) -> Self::Output {
+        let instances = P::split(self.0)
+            .into_iter()
+            .map(|input| {
+                let instance = P::parse(input)?;
+                P::validate(&instance)?;
+                Ok(instance)
+            })
+            .collect::<puzzles::Result<Vec<_>>>()?;
+        instances
+            .iter()
+            .map(|instance| Ok((PuzzleId::of::<P>(instance), P::rating(instance)?)))
+            .collect()
+    }
+}
+
+impl Rate {
+    pub fn run(self) -> Result<()> {
+        if let Some(kind) = &self.kind {
+            if !paths::is_kind(kind) {
+                bail!("Unknown kind of puzzle '{kind}'.");
+            }
+        }
+        let mut records = vec![];
+        for arg in &self.puzzles {
+            match self.rate(arg) {
+                Ok((kind, ratings)) => {
+                    let single = ratings.len() == 1;
+                    records.extend(ratings.into_iter().enumerate().map(
+                        |(index, (puzzle_id, rating))| {
+                            let id = if single {
+                                arg.clone()
+                            } else {
+                                format!("{arg}[{index}]")
+                            };
+                            let text = format!("{id}: {kind}, {rating}");
+                            Record::rated(id, &kind, rating)
+                                .with_puzzle_id(puzzle_id)
+                                .with_text(text)
+                        },
+                    ));
+                }
+                Err(err) => records.push(Record::error(arg, format!("{err:#}"))),
+            }
+        }
+        self.output.write(&records)?;
+
+        let num_failed = records
+            .iter()
+            .filter(|record| record.status == Status::Error)
+            .count();
+        ensure!(
+            num_failed == 0,
+            "Failed to rate {num_failed} of {} puzzles.",
+            records.len()
+        );
+        Ok(())
+    }
+
+    /// Rates every puzzle the argument holds, returning the kind they were read as.
+    fn rate(&self, arg: &str) -> Result<(String, Vec<(PuzzleId, Rating)>)> {
+        let input = if is_input(arg) {
+            read_input(arg)?
+        } else {
+            arg.to_string()
+        };
+        if let Some(kind) = self
+            .kind
+            .clone()
+            .or_else(|| paths::kind_of_path(Path::new(arg)))
+        {
+            let ratings = puzzle::visit(&kind, RateAll(&input))??;
+            return Ok((kind, ratings));
+        }
+        puzzle::KINDS
+            .into_iter()
+            .find_map(|kind| {
+                let ratings = puzzle::visit(kind, RateAll(&input)).ok()?.ok()?;
+                Some((kind.to_string(), ratings))
+            })
+            .ok_or_else(|| {
+                anyhow::anyhow!("No kind of puzzle can rate '{arg}'. Give one with --kind.")
+            })
+    }
+}
@@ -0,0 +1,34 @@
+use anyhow::Result;
+use clap::Args;
+use puzzles::sliding::{self, Sliding};
+
+use crate::{action::SolveOptions, output::Record};
+
+#[derive(Clone, Debug, Args)]
+pub struct SlidingOptions {
+    /// The number of tiles in each pattern database, between 1 and 7. Chosen from the board size by default.
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=7))]
+    group_size: Option<u8>,
+}
+
+impl SolveOptions<Sliding> for SlidingOptions {
+    fn solve(&self, id: &str, board: &sliding::Board) -> Option<Result<Vec<Record>>> {
+        let group_size = self.group_size?;
+        if !board.is_solvable() {
+            return Some(Ok(vec![
+                Record::no_solution(id).with_text(format!("'{id}' cannot be solved."))
+            ]));
+        }
+        Some(Ok(vec![
+            match sliding::solve_with(board, group_size.into()) {
+                Some(solution) => {
+                    let moves = solution.moves().len();
+                    Record::solved(id, solution.to_string())
+                        .with_stat("moves", moves as u64)
+                        .with_text(format!("{moves} moves:\n{solution}"))
+                }
+                None => Record::no_solution(id),
+            },
+        ]))
+    }
+}
@@ -0,0 +1,26 @@
+use anyhow::Result;
+use clap::Args;
+use puzzles::sokoban::{self, Algorithm, Sokoban};
+
+use crate::{action::SolveOptions, output::Record};
+
+#[derive(Clone, Debug, Args)]
+pub struct SokobanOptions {
+    /// Search with IDA* instead of A*, which uses less memory on large levels.
+    #[arg(long)]
+    ida: bool,
+}
+
+impl SolveOptions<Sokoban> for SokobanOptions {
+    fn solve(&self, id: &str, board: &sokoban::Board) -> Option<Result<Vec<Record>>> {
+        if !self.ida {
+            return None;
+        }
+        Some(Ok(vec![
+            match sokoban::solve_with(board, Algorithm::IdaStar) {
+                Some(solution) => Record::solved(id, solution.to_string()),
+                None => Record::no_solution(id),
+            },
+        ]))
+    }
+}
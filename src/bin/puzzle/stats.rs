@@ -0,0 +1,196 @@
+//! Keeps a history of how the sudoku solver does on batches of grids, and compares runs from it, so that changes to
+//! the solver can be checked for making it slower or worse.
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use clap::Subcommand;
+use puzzles::history::{self, Change, History, RunSummary};
+
+use crate::{
+    output::{OutputArgs, Record},
+    paths::Paths,
+    progress::ProgressArgs,
+    sudoku,
+};
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum Stats {
+    /// Solve the sudoku grid sets, or a file of grids, and append a summary of each set to the history in the output
+    /// directory.
+    Record {
+        /// A file with one grid per line to solve instead of the grid sets of the data directory.
+        #[arg(long)]
+        grids: Option<PathBuf>,
+        /// A name for the run, such as a branch, to compare against later.
+        #[arg(long)]
+        label: Option<String>,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Compare the latest run in the history against an earlier one, set by set.
+    Diff {
+        /// The label or id of the run to compare against. Defaults to the run before the latest.
+        #[arg(long)]
+        baseline: Option<String>,
+        /// How much more of anything but solved grids counts as a regression, as a share of the baseline. Times
+        /// vary between runs, so small changes are not regressions.
+        #[arg(long, default_value_t = 0.1)]
+        tolerance: f64,
+        /// Fail if anything regressed, such as to stop a script.
+        #[arg(long)]
+        check: bool,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+}
+
+impl Stats {
+    pub fn run(self, paths: &Paths, progress: &ProgressArgs) -> Result<()> {
+        match self {
+            Stats::Record {
+                grids,
+                label,
+                output,
+            } => record(grids, label, paths, progress, &output),
+            Stats::Diff {
+                baseline,
+                tolerance,
+                check,
+                output,
+            } => diff(baseline, tolerance, check, paths, &output),
+        }
+    }
+}
+
+fn record(
+    grids: Option<PathBuf>,
+    label: Option<String>,
+    paths: &Paths,
+    progress: &ProgressArgs,
+    output: &OutputArgs,
+) -> Result<()> {
+    let run = history::new_run();
+    let results = match grids {
+        Some(file) => {
+            let (_, result, _) = sudoku::solve_grid_file(&file, false, paths, progress)?;
+            vec![result]
+        }
+        None => sudoku::solve_data_sets(paths, progress)?.0,
+    };
+    let budgets = sudoku::load_budgets(paths)?;
+    let (mut records, summaries) = results
+        .into_iter()
+        .map(|(record, set)| {
+            let config = history::config_hash(&budgets.for_set(&set.name))?;
+            Ok((record, RunSummary::new(run, label.clone(), config, &set)))
+        })
+        .collect::<Result<(Vec<_>, Vec<_>)>>()?;
+    let path = paths.history();
+    History::append(&path, &summaries)
+        .with_context(|| format!("Failed to record the run in {path:?}."))?;
+    let text = format!(
+        "Recorded {} sets as run {run} in {path:?}.",
+        summaries.len()
+    );
+    records.push(Record::summary("record", text).with_stat("run", run));
+    output.write(&records)
+}
+
+fn diff(
+    baseline: Option<String>,
+    tolerance: f64,
+    check: bool,
+    paths: &Paths,
+    output: &OutputArgs,
+) -> Result<()> {
+    let path = paths.history();
+    let history = History::load(&path)
+        .with_context(|| format!("Failed to read the run history {path:?}."))?;
+    let runs = history.runs();
+    let Some(&latest) = runs.last() else {
+        bail!("No runs have been recorded in {path:?}. Record one with `puzzle stats record`.");
+    };
+    let baseline = match baseline {
+        Some(name) => history
+            .find(&name)
+            .with_context(|| format!("No run with the label or id '{name}' in {path:?}."))?,
+        None => match runs.len().checked_sub(2) {
+            Some(index) => runs[index],
+            None => bail!("Only one run has been recorded in {path:?}, so there is nothing to compare it with."),
+        },
+    };
+    let (baseline_sets, latest_sets) = (history.run(baseline), history.run(latest));
+    let changes = history::compare(&baseline_sets, &latest_sets);
+    let mut records = vec![];
+    let mut datasets = changes
+        .iter()
+        .map(|change| change.dataset.as_str())
+        .collect::<Vec<_>>();
+    datasets.dedup();
+    for dataset in datasets {
+        let changes = changes
+            .iter()
+            .filter(|change| change.dataset == dataset)
+            .collect::<Vec<_>>();
+        let mut text = changes
+            .iter()
+            .map(|change| describe(change, tolerance))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let config = |sets: &[&RunSummary]| {
+            sets.iter()
+                .find(|set| set.dataset == dataset)
+                .map(|set| (set.version.clone(), set.config.clone()))
+        };
+        // Changes from a different config or version are expected, so they are pointed out.
+        if config(&baseline_sets) != config(&latest_sets) {
+            text.push_str(", with a different version or solver config");
+        }
+        records.push(changes.iter().fold(
+            Record::summary(dataset, format!("{dataset}: {text}.")),
+            |record, change| record.with_stat(change.metric.name(), change.latest),
+        ));
+    }
+    let regressions = changes
+        .iter()
+        .filter(|change| change.is_regression(tolerance))
+        .count();
+    let text = format!("{regressions} regressions in run {latest} against run {baseline}.");
+    records.push(
+        Record::summary("diff", text)
+            .with_stat("baseline", baseline)
+            .with_stat("latest", latest)
+            .with_stat("regressions", regressions as u64),
+    );
+    output.write(&records)?;
+    if check && regressions > 0 {
+        bail!(
+            "{regressions} metrics regressed by more than {:.0}%.",
+            tolerance * 100.0
+        );
+    }
+    Ok(())
+}
+
+/// The metric in the baseline and latest run, marked if it regressed.
+fn describe(change: &Change, tolerance: f64) -> String {
+    let value = |value: u64| {
+        if change.metric.is_time() {
+            format!("{:.3}s", value as f64 / 1e6)
+        } else {
+            value.to_string()
+        }
+    };
+    let mut text = format!(
+        "{} {} -> {} ({:+.0}%)",
+        change.metric.name(),
+        value(change.baseline),
+        value(change.latest),
+        change.relative() * 100.0
+    );
+    if change.is_regression(tolerance) {
+        text.push_str(" REGRESSED");
+    }
+    text
+}
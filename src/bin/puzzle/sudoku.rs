@@ -1,121 +1,413 @@
 use std::{
+    collections::BTreeMap,
     fs::{self, File},
-    io::Write,
+    io::{BufWriter, Write},
+    num::NonZeroU8,
     path::{Path, PathBuf},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use anyhow::{Context, Result};
-use puzzles::sudoku::{self, Board};
+use puzzles::{
+    cancel::CancelToken,
+    generate::{self, GeneratorConfig},
+    puzzle::Puzzle,
+    report::{Report, SetReport},
+    stats::SolveStats,
+    sudoku::{self, Board, Budget, Budgets, CellValue, GridFile, Location, Pair, Technique},
+};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
-fn data_dir() -> PathBuf {
-    PathBuf::from("data/sudoku")
-}
-
-fn output_dir() -> PathBuf {
-    PathBuf::from("output/sudoku")
-}
+use crate::{
+    action::{print_text, read_puzzle, Action, GenerateArgs},
+    input::read_input,
+    output::{OutputArgs, Record},
+    paths::Paths,
+    play::play,
+    progress::{Progress, ProgressArgs, SetProgress},
+};
 
-fn read_boards_from_lines<S: AsRef<str>>(
-    lines: impl Iterator<Item = S>,
-    empty_char: char,
-) -> Result<Vec<Board>> {
-    lines
-        .map(|line| Board::from_line(line.as_ref(), empty_char))
-        .collect::<Result<Vec<_>>>()
+fn load_grid_file(file: impl AsRef<Path>, mmap: bool) -> Result<GridFile> {
+    let file = file.as_ref();
+    let grid_file = if mmap {
+        GridFile::map(file)
+    } else {
+        GridFile::read(file)
+    };
+    grid_file.with_context(|| format!("Failed to read grid file '{file:?}'."))
 }
 
-fn load_grid_file(file: impl AsRef<Path>) -> Result<Vec<Board>> {
-    let file = file.as_ref();
-    let data_str = std::fs::read_to_string(file)
-        .with_context(|| format!("Failed to read grid file '{file:?}'."))?;
-    read_boards_from_lines(data_str.lines(), '.')
+/// The solver budgets of the sudoku data directory, or the default budget for every set if it has none.
+pub fn load_budgets(paths: &Paths) -> Result<Budgets> {
+    let path = paths.data("sudoku").join("budgets.toml");
+    if !path.exists() {
+        return Ok(Budgets::default());
+    }
+    Budgets::load(&path).with_context(|| format!("Failed to load solver budgets '{path:?}'."))
 }
 
-fn solve_set(name: &str, grids: Vec<Board>, solutions_dir: impl AsRef<Path>) -> Result<(u32, u32)> {
+/// Solves the grids one at a time as they are parsed from the file, so that only the grid being solved is held.
+/// Grids that would take the solver over the budget count as unsolved.
+fn solve_set(
+    name: &str,
+    grids: &GridFile,
+    budget: &Budget,
+    solutions_dir: impl AsRef<Path>,
+    progress: SetProgress,
+) -> Result<(Record, SetReport)> {
     let solution_path = solutions_dir.as_ref().join(name).with_extension("txt");
-    let mut solution_file = File::create(&solution_path)
-        .with_context(|| format!("Failed to create solution file '{solution_path:?}'."))?;
-    let mut num_solved = 0;
-    let mut num_set_steps = 0;
-    let mut num_set_guesses = 0;
-    for (index, grid) in grids.iter().enumerate() {
-        let (solution, num_steps, num_guesses) = sudoku::solve(grid)
-            .with_context(|| format!("Error while solving grid {index} in set {name}"))?;
+    let mut solution_file = BufWriter::new(
+        File::create(&solution_path)
+            .with_context(|| format!("Failed to create solution file '{solution_path:?}'."))?,
+    );
+    let mut report = SetReport::new(name);
+    // How many grids went over each budget, by the name of the budget.
+    let mut over_budget = BTreeMap::<&str, u64>::new();
+    for (index, grid) in grids.boards('.').enumerate() {
+        let grid = grid.with_context(|| format!("Error parsing grid {index} in set {name}"))?;
+        let start_time = Instant::now();
+        let result = sudoku::solve_with_budget(&grid, budget, &CancelToken::new());
+        let time = start_time.elapsed();
+        let (solution, stats) = match result {
+            Ok(result) => result,
+            Err(puzzles::Error::Sudoku(sudoku::Error::OverBudget(exceeded))) => {
+                *over_budget.entry(exceeded.name()).or_default() += 1;
+                (grid.clone(), SolveStats::default())
+            }
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("Error while solving grid {index} in set {name}"))
+            }
+        };
         let solved = solution.validate().with_context(|| {
             format!(
                 "Error validating solution for grid {index} in set {name}.\nSolution:\n{solution}Original board:\n{grid}"
             )
         })?.finished();
-        if solved {
-            num_solved += 1;
-            num_set_steps += num_steps;
-            num_set_guesses += num_guesses;
-        }
+        report.add(
+            solved,
+            time,
+            Technique::hardest(&grid, &stats).name(),
+            &stats,
+        );
         let solution_line = solution.to_pretty_string(Board::format_line, '.')?;
-        writeln!(solution_file, "{solution_line},{solved}")
+        writeln!(solution_file, "{solution_line},{solved},{}", grid.id())
             .with_context(|| format!("Failed to write solution for grid {index} in set {name}."))?;
+        progress.inc();
     }
-    let num_grids = grids.len();
+    solution_file
+        .flush()
+        .with_context(|| format!("Failed to write solutions for set {name}."))?;
+    progress.finish();
 
-    let percentage = num_solved as f64 / num_grids as f64 * 100.0;
-    println!("Solved {num_solved}/{num_grids} ({percentage:.0}%) {name} grids with {num_set_steps} steps and {num_set_guesses} guesses.",);
-    Ok((num_set_steps, num_set_guesses))
+    let SetReport {
+        puzzles,
+        solved,
+        stats,
+        ..
+    } = report;
+    let percentage = report.solve_rate() * 100.0;
+    let mut text = format!(
+        "Solved {solved}/{puzzles} ({percentage:.0}%) {name} grids with {} steps and {} guesses.",
+        stats.steps, stats.guesses
+    );
+    let num_over_budget = over_budget.values().sum::<u64>();
+    if num_over_budget > 0 {
+        let budgets = over_budget
+            .iter()
+            .map(|(budget, count)| format!("{budget}: {count}"))
+            .collect::<Vec<_>>();
+        text.push_str(&format!(
+            " {num_over_budget} went over budget ({}).",
+            budgets.join(", ")
+        ));
+    }
+    let record = Record::summary(name, text)
+        .with_stat("grids", puzzles)
+        .with_stat("solved", solved)
+        .with_stat("over_budget", num_over_budget)
+        .with_solve_stats(&stats);
+    Ok((record, report))
 }
 
-#[derive(Clone, Debug, clap::Args)]
-pub struct Sudoku {}
+/// Writes the report of the sets next to their solutions as Markdown and HTML. The text format prints the Markdown
+/// report in place of the records of the sets.
+fn write_report(
+    title: &str,
+    results: Vec<(Record, SetReport)>,
+    elapsed: Duration,
+    output_dir: &Path,
+    output: &OutputArgs,
+) -> Result<()> {
+    let (records, sets): (Vec<_>, Vec<_>) = results.into_iter().unzip();
+    let report = Report {
+        title: title.to_string(),
+        sets,
+        elapsed,
+        techniques: Technique::ALL.map(Technique::name).to_vec(),
+    };
+    let markdown = report.to_markdown();
+    for (extension, contents) in [("md", &markdown), ("html", &report.to_html())] {
+        let path = output_dir.join("report").with_extension(extension);
+        fs::write(&path, contents)
+            .with_context(|| format!("Failed to write report '{path:?}'."))?;
+    }
+    if output.is_text() {
+        output.write(&[Record::summary("report", markdown)])
+    } else {
+        output.write(&records)
+    }
+}
+
+#[derive(Clone, Debug, clap::Subcommand)]
+pub enum Sudoku {
+    #[command(flatten)]
+    Common(Action),
+    /// Solve every grid set in the data directory, writing the solutions to the output directory.
+    Sets {
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Solve every grid in a file with one grid per line, writing the solutions to the output directory. Grids are
+    /// parsed as they are solved, so files of any size can be solved.
+    Grids {
+        /// The grid file, named by its file stem in the output.
+        file: PathBuf,
+        /// Map the file into memory instead of reading it, for files of hundreds of megabytes. Needs the `mmap`
+        /// feature.
+        #[arg(long)]
+        mmap: bool,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Explain why a value cannot go in a cell, as the singles that lead from placing it there to a contradiction.
+    Explain {
+        /// Path to the sudoku, `-` for stdin, or the sudoku itself.
+        puzzle: String,
+        /// The row of the cell, from 1 to 9.
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=9))]
+        row: u8,
+        /// The column of the cell, from 1 to 9.
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=9))]
+        col: u8,
+        /// The value to rule out, from 1 to 9.
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=9))]
+        value: u8,
+    },
+    /// Write a certificate that the sudoku can be solved without guessing, as JSON listing every deduction from the
+    /// givens to the solution, which `puzzle sudoku verify` checks without solving the sudoku.
+    Certify {
+        /// Path to the sudoku, `-` for stdin, or the sudoku itself.
+        puzzle: String,
+    },
+    /// Check a certificate written by `puzzle sudoku certify`, printing the solution it leads to.
+    Verify {
+        /// Path to the certificate, or `-` for stdin.
+        certificate: String,
+    },
+    /// Generate sudokus together with their solutions and difficulties, one per line as
+    /// `puzzle,solution,difficulty,score`, so that they can be checked later without solving them again.
+    Pairs(GenerateArgs),
+    /// Play a sudoku in the terminal, generating one if none is given.
+    Play {
+        /// Path to the sudoku, `-` for stdin, or the sudoku itself.
+        puzzle: Option<String>,
+        /// The seed to generate a sudoku from. Random by default.
+        #[arg(long, conflicts_with = "puzzle")]
+        seed: Option<u64>,
+    },
+}
 
 impl Sudoku {
-    pub fn run(self) -> Result<()> {
-        let set_names = [
-            "qqwing_simple",
-            "qqwing_easy",
-            "qqwing_intermediate",
-            "qqwing_expert",
-            "easy50",
-            "top95",
-            "hardest",
-            "insane",
-            "blank",
-        ];
-
-        let grid_dir = data_dir().join("grids");
-
-        let sets: Vec<(&str, Vec<Board>)> = set_names
-            .iter()
-            .map(|&name| {
-                load_grid_file(grid_dir.join(name).with_extension("txt"))
-                    .with_context(|| format!("Error loading grid set {name}"))
-                    .map(|grids| (name, grids))
-            })
-            .collect::<Result<_>>()?;
-
-        let output_dir = output_dir();
-        let solutions_dir = output_dir.join("solutions");
-        fs::create_dir_all(&solutions_dir).with_context(|| {
-            format!("Failed to create solutions directory '{solutions_dir:?}'.")
-        })?;
+    pub fn run(self, paths: &Paths, progress: &ProgressArgs) -> Result<()> {
+        match self {
+            Sudoku::Common(action) => action.run::<sudoku::Sudoku>(paths),
+            Sudoku::Sets { output } => solve_sets(paths, progress, &output),
+            Sudoku::Grids { file, mmap, output } => {
+                solve_grids(&file, mmap, paths, progress, &output)
+            }
+            Sudoku::Explain {
+                puzzle,
+                row,
+                col,
+                value,
+            } => {
+                let board = read_puzzle::<sudoku::Sudoku>(&puzzle)?;
+                let location = Location::new(row - 1, col - 1)
+                    .expect("Rows and columns are checked to be in range.");
+                let value = NonZeroU8::new(value)
+                    .and_then(CellValue::new)
+                    .expect("Values are checked to be in range.");
+                print_text(&sudoku::explain(&board, location, value)?.to_string());
+                Ok(())
+            }
+            Sudoku::Certify { puzzle } => {
+                let certificate = sudoku::certify(&read_puzzle::<sudoku::Sudoku>(&puzzle)?)?;
+                print_text(&serde_json::to_string_pretty(&certificate)?);
+                Ok(())
+            }
+            Sudoku::Verify { certificate } => {
+                let certificate =
+                    serde_json::from_str::<sudoku::Certificate>(&read_input(&certificate)?)
+                        .with_context(|| format!("Failed to parse certificate '{certificate}'."))?;
+                let solution = sudoku::verify_certificate(&certificate)?;
+                println!(
+                    "The certificate holds: the sudoku is solved in {} steps using {} at most.",
+                    certificate.steps.len(),
+                    certificate.technique.name()
+                );
+                print_text(&sudoku::Sudoku::format(&solution));
+                Ok(())
+            }
+            Sudoku::Pairs(args) => {
+                let records =
+                    generate::generate_pairs::<sudoku::Sudoku>(&args.config(), args.count)?
+                        .into_iter()
+                        .map(|generated| {
+                            let seed = generated.seed;
+                            let pair = Pair::from(generated);
+                            let record = Record::generated(seed, pair.to_line())
+                                .with_puzzle::<sudoku::Sudoku>(&pair.puzzle);
+                            match pair.rating {
+                                Some(rating) => record.with_rating(rating),
+                                None => record,
+                            }
+                        })
+                        .collect::<Vec<_>>();
+                args.output.write(&records)
+            }
+            Sudoku::Play { puzzle, seed } => {
+                let board = match puzzle {
+                    Some(puzzle) => read_puzzle::<sudoku::Sudoku>(&puzzle)?,
+                    None => sudoku::Sudoku::generate(&GeneratorConfig::seeded(
+                        seed.unwrap_or_else(rand::random),
+                    ))?,
+                };
+                play(board)?;
+                Ok(())
+            }
+        }
+    }
+}
 
-        let start_time = Instant::now();
-        let (num_total_steps, num_total_guesses) = sets
-            .into_par_iter()
-            .map(|(name, grids)| solve_set(name, grids, solutions_dir.as_path()).unwrap())
-            .reduce(
-                || (0, 0),
-                |(total_steps, total_guesses), (set_steps, set_guesses)| {
-                    (total_steps + set_steps, total_guesses + set_guesses)
-                },
-            );
-        let elapsed = start_time.elapsed();
-        println!("{num_total_steps} total steps and {num_total_guesses} guesses used on successful solutions");
-        println!(
-            "Total time: {}s {}ms",
-            elapsed.as_secs(),
-            elapsed.subsec_millis()
-        );
+fn solve_sets(paths: &Paths, progress: &ProgressArgs, output: &OutputArgs) -> Result<()> {
+    let (results, elapsed) = solve_data_sets(paths, progress)?;
+    write_report(
+        "Sudoku grid sets",
+        results,
+        elapsed,
+        &paths.output("sudoku"),
+        output,
+    )
+}
 
-        Ok(())
-    }
+/// Solves every grid set in the data directory, returning the record and report of each set, and how long they
+/// took together.
+pub fn solve_data_sets(
+    paths: &Paths,
+    progress: &ProgressArgs,
+) -> Result<(Vec<(Record, SetReport)>, Duration)> {
+    let set_names = [
+        "qqwing_simple",
+        "qqwing_easy",
+        "qqwing_intermediate",
+        "qqwing_expert",
+        "easy50",
+        "top95",
+        "hardest",
+        "insane",
+        "blank",
+    ];
+
+    let grid_dir = paths.data("sudoku").join("grids");
+
+    let sets: Vec<(&str, GridFile)> = set_names
+        .iter()
+        .map(|&name| {
+            load_grid_file(grid_dir.join(name).with_extension("txt"), false)
+                .with_context(|| format!("Error loading grid set {name}"))
+                .map(|grids| (name, grids))
+        })
+        .collect::<Result<_>>()?;
+
+    let output_dir = paths.output("sudoku");
+    let solutions_dir = output_dir.join("solutions");
+    fs::create_dir_all(&solutions_dir)
+        .with_context(|| format!("Failed to create solutions directory '{solutions_dir:?}'."))?;
+
+    let progress = Progress::new(progress, sets.iter().map(|(_, grids)| grids.len()).sum());
+    // The bars are added up front so that they are shown in the order of the sets.
+    let sets = sets
+        .into_iter()
+        .map(|(name, grids)| {
+            let set_progress = progress.set(name, grids.len());
+            (name, grids, set_progress)
+        })
+        .collect::<Vec<_>>();
+    let budgets = load_budgets(paths)?;
+    let start_time = Instant::now();
+    let results = sets
+        .into_par_iter()
+        .map(|(name, grids, set_progress)| {
+            solve_set(
+                name,
+                &grids,
+                &budgets.for_set(name),
+                solutions_dir.as_path(),
+                set_progress,
+            )
+        })
+        .collect::<Result<Vec<_>>>();
+    let elapsed = start_time.elapsed();
+    progress.finish();
+    Ok((results?, elapsed))
+}
+
+fn solve_grids(
+    file: &Path,
+    mmap: bool,
+    paths: &Paths,
+    progress: &ProgressArgs,
+    output: &OutputArgs,
+) -> Result<()> {
+    let (name, result, elapsed) = solve_grid_file(file, mmap, paths, progress)?;
+    write_report(
+        &format!("Sudoku grids of {name}"),
+        vec![result],
+        elapsed,
+        &paths.output("sudoku"),
+        output,
+    )
+}
+
+/// Solves every grid in the file, returning the name of the set, its record and report, and how long it took.
+pub fn solve_grid_file(
+    file: &Path,
+    mmap: bool,
+    paths: &Paths,
+    progress: &ProgressArgs,
+) -> Result<(String, (Record, SetReport), Duration)> {
+    let grids = load_grid_file(file, mmap)?;
+    let name = file
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .with_context(|| format!("Grid file '{file:?}' has no name."))?;
+    let output_dir = paths.output("sudoku");
+    let solutions_dir = output_dir.join("solutions");
+    fs::create_dir_all(&solutions_dir)
+        .with_context(|| format!("Failed to create solutions directory '{solutions_dir:?}'."))?;
+    let budget = load_budgets(paths)?.for_set(name);
+    let num_grids = grids.len();
+    let progress = Progress::new(progress, num_grids);
+    let start_time = Instant::now();
+    let result = solve_set(
+        name,
+        &grids,
+        &budget,
+        &solutions_dir,
+        progress.set(name, num_grids),
+    );
+    let elapsed = start_time.elapsed();
+    progress.finish();
+    Ok((name.to_string(), result?, elapsed))
 }
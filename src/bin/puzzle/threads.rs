@@ -0,0 +1,30 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+/// How many threads commands that solve many puzzles may use.
+#[derive(Clone, Debug, Args)]
+pub struct Threads {
+    /// The number of threads to solve puzzles on. Defaults to the number of CPUs.
+    #[arg(long, global = true, env = "PUZZLES_THREADS")]
+    threads: Option<usize>,
+    /// Solve puzzles one at a time, on a single thread.
+    #[arg(long, global = true, conflicts_with = "threads")]
+    sequential: bool,
+}
+
+impl Threads {
+    /// A thread pool for the command to run in.
+    pub fn pool(&self) -> Result<ThreadPool> {
+        let num_threads = if self.sequential {
+            1
+        } else {
+            // Rayon picks the number of threads for 0.
+            self.threads.unwrap_or(0)
+        };
+        ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .context("Failed to create thread pool.")
+    }
+}
@@ -0,0 +1,78 @@
+//! Re-checks a puzzle every time its file changes, for a quick edit and check loop while designing puzzles.
+
+use std::{fs, path::Path, thread, time::Duration};
+
+use anyhow::{ensure, Result};
+use puzzles::{difficulty::Rating, puzzle::Puzzle, Error};
+
+/// How often to look at the modification time of the file. Polling works the same everywhere, including for editors
+/// that save by replacing the file.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Checks the puzzle in the file whenever it changes, until interrupted.
+pub fn watch<P: Puzzle>(path: &Path) -> Result<()> {
+    watch_with(path, check::<P>)
+}
+
+/// Like [`watch`], but describes the puzzle with `check`, for games that check puzzles their own way.
+pub fn watch_with(path: &Path, check: impl Fn(&str) -> String) -> Result<()> {
+    ensure!(path.is_file(), "Can only watch files, not '{path:?}'.");
+    println!("Watching {path:?}. Press Ctrl+C to stop.");
+    let mut last_modified = None;
+    loop {
+        let modified = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        if modified.is_some() && modified != last_modified {
+            last_modified = modified;
+            match fs::read_to_string(path) {
+                Ok(text) => println!("\n{}", check(&text)),
+                Err(err) => println!("\nFailed to read {path:?}: {err}"),
+            }
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Describes whether the puzzle is valid, whether its solution is unique, and how hard it is.
+fn check<P: Puzzle>(text: &str) -> String {
+    let instance = match P::parse(text).and_then(|instance| {
+        P::validate(&instance)?;
+        Ok(instance)
+    }) {
+        Ok(instance) => instance,
+        Err(err) => return format!("Invalid {}: {err}", P::NAME),
+    };
+    describe(
+        P::count_solutions(&instance, 2),
+        P::rating(&instance),
+        P::solve(&instance).map(|solution| solution.map(|solution| P::format(&solution))),
+    )
+}
+
+/// Describes a valid puzzle from its number of solutions, counted up to 2, its rating and its formatted solution.
+pub fn describe(
+    count: puzzles::Result<usize>,
+    rating: puzzles::Result<Rating>,
+    solution: puzzles::Result<Option<String>>,
+) -> String {
+    let mut lines = vec![];
+    match count {
+        Ok(0) => lines.push("No solution.".to_string()),
+        Ok(1) => lines.push("Unique solution.".to_string()),
+        Ok(_) => lines.push("Several solutions.".to_string()),
+        Err(Error::Unsupported { .. }) => lines.push("Uniqueness unknown.".to_string()),
+        Err(err) => lines.push(format!("Failed to count solutions: {err}")),
+    }
+    match rating {
+        Ok(rating) => lines.push(format!("Difficulty: {rating}")),
+        Err(Error::Unsupported { .. }) => {}
+        Err(err) => lines.push(format!("Failed to rate: {err}")),
+    }
+    match solution {
+        Ok(Some(solution)) => lines.push(solution.trim_end().to_string()),
+        Ok(None) => {}
+        Err(err) => lines.push(format!("Failed to solve: {err}")),
+    }
+    lines.join("\n")
+}
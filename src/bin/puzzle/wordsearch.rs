@@ -0,0 +1,43 @@
+use anyhow::Result;
+use clap::Args;
+use puzzles::wordsearch::{self, Wordsearch};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    action::GenerateCommand,
+    input::read_input,
+    output::{OutputArgs, Record},
+};
+
+/// Generates a grid in which every word appears exactly once, instead of a puzzle from the shared generator flags.
+#[derive(Clone, Debug, Args)]
+pub struct WordsearchGenerate {
+    /// Path to a list of words, one per line, or `-` for stdin.
+    words: String,
+    #[arg(long, default_value_t = 12)]
+    width: usize,
+    #[arg(long, default_value_t = 12)]
+    height: usize,
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    #[arg(long, default_value_t = 1000)]
+    attempts: usize,
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+impl GenerateCommand<Wordsearch> for WordsearchGenerate {
+    fn generate(self) -> Result<()> {
+        let words = read_input(&self.words)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let board =
+            wordsearch::generate(&words, (self.height, self.width), self.attempts, &mut rng)?;
+        self.output
+            .write(&[Record::generated(self.seed, board.to_string())])
+    }
+}
@@ -0,0 +1,31 @@
+mod board;
+pub use board::Board;
+mod solver;
+pub use solver::{count_solutions, solve, Solution};
+
+/// Binairo puzzles as a [`Puzzle`](crate::puzzle::Puzzle), in the text format of [`Board::parse`] or as an Unruly game
+/// ID or save file.
+pub struct Binairo;
+
+impl crate::puzzle::Puzzle for Binairo {
+    const NAME: &'static str = "binairo puzzle";
+
+    type Instance = Board;
+    type Solution = Solution;
+
+    fn parse(input: &str) -> crate::Result<Board> {
+        if input.contains(':') {
+            Board::parse_unruly(input)
+        } else {
+            Board::parse(input)
+        }
+    }
+
+    fn solve(board: &Board) -> crate::Result<Option<Solution>> {
+        Ok(solve(board))
+    }
+
+    fn count_solutions(board: &Board, limit: usize) -> crate::Result<usize> {
+        Ok(count_solutions(board, limit))
+    }
+}
@@ -0,0 +1,227 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+};
+
+/// A binairo puzzle: a grid to fill with zeros and ones such that no three adjacent cells in a line are equal,
+/// every row and column has as many zeros as ones, and no two rows or two columns are the same.
+///
+/// Unruly puzzles, also known as Tohu-wa-Vohu, are the same except that rows and columns may repeat unless
+/// `unique_lines` is set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    givens: Grid<Option<bool>>,
+    unique_lines: bool,
+}
+
+impl Board {
+    pub const MAX_SIZE: usize = 16;
+
+    pub fn new(givens: Grid<Option<bool>>, unique_lines: bool) -> Result<Self> {
+        let (height, width) = givens.dim();
+        for (name, length) in [("height", height), ("width", width)] {
+            ensure!(
+                length > 0 && length % 2 == 0,
+                Invalid,
+                "The {name} of a binairo grid must be even and positive, but is {length}."
+            );
+            ensure!(
+                length <= Self::MAX_SIZE,
+                Invalid,
+                "The {name} of a binairo grid can be at most {}, but is {length}.",
+                Self::MAX_SIZE
+            );
+        }
+        Ok(Self {
+            givens,
+            unique_lines,
+        })
+    }
+
+    /// Parses a grid of `0`, `1` and `.` for empty cells.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        let mut givens = Grid::from_elem((height, width), None);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.chars().count() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} characters in row {row_index}. Got '{row}'."
+                );
+            }
+            for (col_index, c) in row.chars().enumerate() {
+                givens[Location::new(row_index, col_index)] = match c {
+                    '0' => Some(false),
+                    '1' => Some(true),
+                    '.' => None,
+                    _ => bail!(
+                        Parse,
+                        "Invalid character '{c}' at ({row_index}, {col_index})."
+                    ),
+                };
+            }
+        }
+        Self::new(givens, true)
+    }
+
+    /// Parses an Unruly puzzle from Simon Tatham's Portable Puzzle Collection, either as a save file or as a game
+    /// ID such as `8x6:aacIDKbaeBBCe`.
+    ///
+    /// A `u` in the parameters, as in `8x8u`, requires unique rows and columns.
+    pub fn parse_unruly(string: impl AsRef<str>) -> Result<Self> {
+        let string = string.as_ref().trim();
+        let (params, desc) = if string.starts_with("SAVEFILE") {
+            let mut params = None;
+            let mut desc = None;
+            for line in string.lines() {
+                let mut fields = line.splitn(3, ':');
+                let (Some(key), Some(_), Some(value)) =
+                    (fields.next(), fields.next(), fields.next())
+                else {
+                    bail!(Parse, "Invalid save file line '{line}'.");
+                };
+                match key.trim() {
+                    "GAME" => ensure!(
+                        value == "Unruly",
+                        Parse,
+                        "Expected an Unruly save file, but got one for {value}."
+                    ),
+                    "PARAMS" => params = Some(value),
+                    "DESC" => desc = Some(value),
+                    _ => {}
+                }
+            }
+            (
+                params
+                    .ok_or_else(|| Error::Parse("The save file has no PARAMS line.".to_string()))?,
+                desc.ok_or_else(|| Error::Parse("The save file has no DESC line.".to_string()))?,
+            )
+        } else {
+            string.split_once(':').ok_or_else(|| {
+                Error::Parse("Expected an Unruly game ID such as '8x6:aacIDKbaeBBCe'.".to_string())
+            })?
+        };
+        let size_end = params
+            .find(|c: char| !c.is_ascii_digit() && c != 'x')
+            .unwrap_or(params.len());
+        let (width, height) = params[..size_end]
+            .split_once('x')
+            .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+            .ok_or_else(|| Error::Parse(format!("Invalid Unruly parameters '{params}'.")))?;
+        let unique_lines = params[size_end..].starts_with('u');
+        let mut givens = Grid::from_elem((height, width), None);
+        let cells = width * height;
+        // Every letter skips as many empty cells as its distance from `a` or `A` and then fills the next cell,
+        // with a zero for lowercase and a one for uppercase. The last letter may point past the end of the grid
+        // to encode trailing empty cells.
+        let mut index = 0;
+        for (position, c) in desc.chars().enumerate() {
+            let (skip, one) = match c {
+                'a'..='z' => (c as usize - 'a' as usize, false),
+                'A'..='Z' => (c as usize - 'A' as usize, true),
+                _ => bail!(
+                    Parse,
+                    "Invalid character '{c}' in Unruly description '{desc}'."
+                ),
+            };
+            index += skip;
+            if index < cells {
+                givens[Location::new(index / width, index % width)] = Some(one);
+            } else {
+                ensure!(
+                    index == cells && position + 1 == desc.chars().count(),
+                    Parse,
+                    "The Unruly description '{desc}' is longer than the {width}x{height} grid."
+                );
+            }
+            index += 1;
+        }
+        ensure!(
+            index >= cells,
+            Parse,
+            "The Unruly description '{desc}' is shorter than the {width}x{height} grid."
+        );
+        Self::new(givens, unique_lines)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("binairo", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.givens.dim()
+    }
+
+    pub fn given(&self, location: Location) -> Option<bool> {
+        self.givens[location]
+    }
+
+    /// Whether every row must differ from every other row, and every column from every other column.
+    pub fn unique_lines(&self) -> bool {
+        self.unique_lines
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.givens.rows() {
+            for &given in row {
+                let c = match given {
+                    Some(false) => '0',
+                    Some(true) => '1',
+                    None => '.',
+                };
+                write!(f, "{c}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_grids() {
+        let board = Board::parse(include_str!("../../data/binairo/puzzles/ten01.txt")).unwrap();
+        assert_eq!(board.dim(), (10, 10));
+        assert_eq!(board.given(Location::new(0, 0)), Some(true));
+        assert_eq!(board.given(Location::new(1, 3)), Some(false));
+        assert_eq!(board.given(Location::new(0, 1)), None);
+        assert!(board.unique_lines());
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        assert!(Board::parse("0.\n.2\n").is_err());
+        assert!(Board::parse("0.1\n...\n").is_err());
+    }
+
+    #[test]
+    fn parses_unruly_save_files_and_game_ids() {
+        let board =
+            Board::parse_unruly(include_str!("../../data/binairo/puzzles/unruly01.sav")).unwrap();
+        assert_eq!(board.dim(), (6, 8));
+        assert!(!board.unique_lines());
+        // `a` fills the first cell with a zero, `a` the next, then `c` skips two cells before a zero.
+        assert_eq!(board.given(Location::new(0, 0)), Some(false));
+        assert_eq!(board.given(Location::new(0, 1)), Some(false));
+        assert_eq!(board.given(Location::new(0, 2)), None);
+        assert_eq!(board.given(Location::new(0, 4)), Some(false));
+        assert_eq!(Board::parse_unruly("8x6dn:aacIDKbaeBBCe").unwrap(), board);
+        assert!(Board::parse_unruly("4x4u:Ap").unwrap().unique_lines());
+        assert!(Board::parse_unruly("4x4:aaaaaaaaaaaaaaaaaa").is_err());
+        assert!(Board::parse_unruly("4x4:a1").is_err());
+    }
+}
@@ -0,0 +1,240 @@
+use std::fmt::Display;
+
+use crate::{
+    grid::Grid,
+    location::Location,
+    search::{self, Contradiction, SearchState},
+    shading::{Shade, ShadeGrid},
+};
+
+use super::Board;
+
+/// A filled binairo grid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    ones: Grid<bool>,
+}
+
+impl Solution {
+    pub fn is_one(&self, location: Location) -> bool {
+        self.ones[location]
+    }
+}
+
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.ones.rows() {
+            for &one in row {
+                write!(f, "{}", if one { '1' } else { '0' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Every balanced line of the given length without three equal cells in a row,
+/// with bit `i` set if cell `i` is a one.
+fn line_patterns(length: usize) -> Vec<u32> {
+    (0u32..1 << length)
+        .filter(|pattern| pattern.count_ones() as usize * 2 == length)
+        .filter(|pattern| {
+            let zeros = !pattern & ((1 << length) - 1);
+            (0..length.saturating_sub(2)).all(|start| {
+                let window = 0b111 << start;
+                pattern & window != window && zeros & window != window
+            })
+        })
+        .collect()
+}
+
+/// The patterns of each line orientation, shared between all search states.
+struct Patterns {
+    rows: Vec<u32>,
+    cols: Vec<u32>,
+}
+
+/// Ones are shaded cells and zeros are unshaded cells.
+#[derive(Clone)]
+struct State<'a> {
+    patterns: &'a Patterns,
+    unique_lines: bool,
+    lines: &'a [Vec<Location>],
+    grid: ShadeGrid,
+}
+
+impl<'a> State<'a> {
+    fn new(
+        board: &Board,
+        patterns: &'a Patterns,
+        lines: &'a [Vec<Location>],
+    ) -> Result<Self, Contradiction> {
+        let mut grid = ShadeGrid::new(board.dim());
+        for loc in Location::grid_iter(board.dim()) {
+            if let Some(one) = board.given(loc) {
+                grid.set(loc, if one { Shade::Shaded } else { Shade::Unshaded })?;
+            }
+        }
+        Ok(Self {
+            patterns,
+            unique_lines: board.unique_lines(),
+            lines,
+            grid,
+        })
+    }
+
+    /// The decided cells of `line` as a mask of decided cells and a pattern of ones.
+    fn known(&self, line: &[Location]) -> (u32, u32) {
+        line.iter()
+            .enumerate()
+            .fold((0, 0), |(mask, ones), (index, &loc)| {
+                match self.grid.get(loc) {
+                    Shade::Unknown => (mask, ones),
+                    Shade::Shaded => (mask | 1 << index, ones | 1 << index),
+                    Shade::Unshaded => (mask | 1 << index, ones),
+                }
+            })
+    }
+
+    /// Decides every cell of `line` that is the same in all patterns that match the decided cells
+    /// and, if lines must be unique, differ from every complete line of the same orientation.
+    fn propagate_line(&mut self, line_index: usize) -> Result<bool, Contradiction> {
+        let line = &self.lines[line_index];
+        let height = self.grid.dim().0;
+        let (patterns, same_orientation) = if line_index < height {
+            (&self.patterns.rows, 0..height)
+        } else {
+            (&self.patterns.cols, height..self.lines.len())
+        };
+        let (mask, ones) = self.known(line);
+        let complete = (1 << line.len()) - 1;
+        let taken = same_orientation
+            .filter(|&other| self.unique_lines && other != line_index)
+            .map(|other| self.known(&self.lines[other]))
+            .filter(|&(other_mask, _)| other_mask == complete)
+            .map(|(_, other_ones)| other_ones)
+            .collect::<Vec<_>>();
+        let (always_one, always_zero) = patterns
+            .iter()
+            .filter(|&&pattern| pattern & mask == ones && !taken.contains(&pattern))
+            .fold(None, |acc, &pattern| {
+                let (always_one, always_zero) = acc.unwrap_or((complete, complete));
+                Some((always_one & pattern, always_zero & !pattern))
+            })
+            .ok_or(Contradiction)?;
+        let mut changed = false;
+        for (index, &loc) in line.iter().enumerate() {
+            if always_one & 1 << index != 0 {
+                changed |= self.grid.set(loc, Shade::Shaded)?;
+            } else if always_zero & 1 << index != 0 {
+                changed |= self.grid.set(loc, Shade::Unshaded)?;
+            }
+        }
+        Ok(changed)
+    }
+
+    fn into_solution(self) -> Solution {
+        Solution {
+            ones: self.grid.cells().map(|&shade| shade == Shade::Shaded),
+        }
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = false;
+            for line_index in 0..self.lines.len() {
+                changed |= self.propagate_line(line_index)?;
+            }
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(location) = self.grid.first_unknown() else {
+            return vec![];
+        };
+        self.grid
+            .branch_on(location)
+            .into_iter()
+            .map(|grid| Self {
+                grid,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+/// Every row followed by every column.
+fn lines((height, width): (usize, usize)) -> Vec<Vec<Location>> {
+    let rows = (0..height).map(|row| (0..width).map(|col| Location::new(row, col)).collect());
+    let cols = (0..width).map(|col| (0..height).map(|row| Location::new(row, col)).collect());
+    rows.chain(cols).collect()
+}
+
+fn with_state<T>(board: &Board, f: impl FnOnce(State) -> T) -> Option<T> {
+    let (height, width) = board.dim();
+    let patterns = Patterns {
+        rows: line_patterns(width),
+        cols: line_patterns(height),
+    };
+    let lines = lines(board.dim());
+    State::new(board, &patterns, &lines).ok().map(f)
+}
+
+pub fn solve(board: &Board) -> Option<Solution> {
+    with_state(board, |state| {
+        search::solve(state).map(State::into_solution)
+    })
+    .flatten()
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    with_state(board, |state| search::count_solutions(state, limit)).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+    use crate::{binairo::Binairo, puzzle::Puzzle};
+
+    fn follows_rules(board: &Board, solution: &Solution) -> bool {
+        let (height, width) = board.dim();
+        let rows = (0..height)
+            .map(|row| solution.ones.row(row).copied().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let cols = (0..width)
+            .map(|col| solution.ones.col(col).copied().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let lines_fit = |lines: &[Vec<bool>]| {
+            lines.iter().all(|line| {
+                line.iter().filter(|&&one| one).count() * 2 == line.len()
+                    && line.windows(3).all(|cells| !cells.iter().all_equal())
+            }) && (!board.unique_lines() || lines.iter().all_unique())
+        };
+        let givens_kept = Location::grid_iter(board.dim()).all(|loc| {
+            board
+                .given(loc)
+                .is_none_or(|given| given == solution.is_one(loc))
+        });
+        lines_fit(&rows) && lines_fit(&cols) && givens_kept
+    }
+
+    #[test]
+    fn solves_data_puzzles() {
+        for input in [
+            include_str!("../../data/binairo/puzzles/ten01.txt"),
+            include_str!("../../data/binairo/puzzles/unruly01.sav"),
+        ] {
+            let board = Binairo::parse(input).unwrap();
+            let solution = solve(&board).unwrap();
+            assert!(follows_rules(&board, &solution));
+            assert_eq!(count_solutions(&board, 2), 1);
+        }
+    }
+}
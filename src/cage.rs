@@ -0,0 +1,182 @@
+//! Cages: groups of cells whose values must combine to a target, as in kenken and similar puzzles.
+
+use std::fmt::{self, Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{latin::LatinSquare, location::Location, search::Contradiction, value_set::ValueSet};
+
+/// Cages with more possible fillings than this are left to the other deductions and to guessing.
+const MAX_FILLINGS: usize = 100_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Operation {
+    /// The single cell of the cage holds the target.
+    Given,
+    Add,
+    /// The difference between the two cells of the cage is the target.
+    Subtract,
+    Multiply,
+    /// The larger of the two cells of the cage divided by the smaller is the target.
+    Divide,
+}
+
+impl Operation {
+    pub fn symbol(self) -> Option<char> {
+        match self {
+            Operation::Given => None,
+            Operation::Add => Some('+'),
+            Operation::Subtract => Some('-'),
+            Operation::Multiply => Some('*'),
+            Operation::Divide => Some('/'),
+        }
+    }
+
+    /// Accepts both the ASCII symbols and the usual typeset ones.
+    pub fn from_symbol(symbol: char) -> Option<Self> {
+        match symbol {
+            '+' => Some(Operation::Add),
+            '-' | '−' => Some(Operation::Subtract),
+            '*' | 'x' | '×' => Some(Operation::Multiply),
+            '/' | '÷' => Some(Operation::Divide),
+            _ => None,
+        }
+    }
+
+    /// Whether `values` combine to `target`. Expects the number of values the operation requires.
+    pub fn evaluates_to(self, values: &[u8], target: u32) -> bool {
+        let values = values.iter().map(|&value| value as u32);
+        match self {
+            Operation::Given | Operation::Add => values.sum::<u32>() == target,
+            Operation::Multiply => values.product::<u32>() == target,
+            Operation::Subtract | Operation::Divide => {
+                let (min, max) = values.fold((u32::MAX, 0), |(min, max), value| {
+                    (min.min(value), max.max(value))
+                });
+                if self == Operation::Subtract {
+                    max - min == target
+                } else {
+                    max == min * target
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cage {
+    pub cells: Vec<Location>,
+    pub operation: Operation,
+    pub target: u32,
+}
+
+impl Cage {
+    /// Whether the operation of the cage allows its number of cells.
+    pub fn has_valid_size(&self) -> bool {
+        match self.operation {
+            Operation::Given => self.cells.len() == 1,
+            Operation::Subtract | Operation::Divide => self.cells.len() == 2,
+            Operation::Add | Operation::Multiply => !self.cells.is_empty(),
+        }
+    }
+
+    /// The values each cell of the cage can take in some filling that hits the target,
+    /// where `candidates` are the values currently possible in each cell.
+    /// Cells of the cage sharing a row or column never get the same value.
+    ///
+    /// Returns `None` if the cage has too many possible fillings to check.
+    pub fn support(&self, candidates: &[ValueSet]) -> Option<Vec<ValueSet>> {
+        debug_assert_eq!(candidates.len(), self.cells.len());
+        let fillings = candidates
+            .iter()
+            .try_fold(1usize, |product, cell| product.checked_mul(cell.len()))?;
+        if fillings > MAX_FILLINGS {
+            return None;
+        }
+        let mut search = CageSearch {
+            cage: self,
+            candidates,
+            values: Vec::with_capacity(self.cells.len()),
+            support: vec![ValueSet::NONE; self.cells.len()],
+        };
+        search.extend();
+        Some(search.support)
+    }
+
+    /// Removes every value from the cells of the cage in `square` that does not appear in any filling reaching
+    /// the target.
+    pub fn apply(&self, square: &mut LatinSquare) -> Result<bool, Contradiction> {
+        let candidates = self
+            .cells
+            .iter()
+            .map(|&loc| square.candidates(loc))
+            .collect::<Vec<_>>();
+        let mut changed = false;
+        if let Some(support) = self.support(&candidates) {
+            for (&loc, support) in self.cells.iter().zip(support) {
+                changed |= square.restrict(loc, support)?;
+            }
+        }
+        Ok(changed)
+    }
+}
+
+impl Display for Cage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.target)?;
+        if let Some(symbol) = self.operation.symbol() {
+            write!(f, "{symbol}")?;
+        }
+        Ok(())
+    }
+}
+
+struct CageSearch<'a> {
+    cage: &'a Cage,
+    candidates: &'a [ValueSet],
+    values: Vec<u8>,
+    support: Vec<ValueSet>,
+}
+
+impl CageSearch<'_> {
+    /// Whether the partial filling can still reach the target.
+    fn is_promising(&self) -> bool {
+        let values = self.values.iter().map(|&value| value as u32);
+        match self.cage.operation {
+            Operation::Given | Operation::Add => values.sum::<u32>() <= self.cage.target,
+            Operation::Multiply => self.cage.target.is_multiple_of(values.product()),
+            Operation::Subtract | Operation::Divide => true,
+        }
+    }
+
+    fn extend(&mut self) {
+        let index = self.values.len();
+        if index == self.cage.cells.len() {
+            if self
+                .cage
+                .operation
+                .evaluates_to(&self.values, self.cage.target)
+            {
+                for (support, &value) in self.support.iter_mut().zip(self.values.iter()) {
+                    support.insert(value);
+                }
+            }
+            return;
+        }
+        let cell = self.cage.cells[index];
+        let mut allowed = self.candidates[index];
+        for (&other, &value) in self.cage.cells.iter().zip(self.values.iter()) {
+            if other.row == cell.row || other.col == cell.col {
+                allowed.remove(value);
+            }
+        }
+        for value in allowed.iter() {
+            self.values.push(value);
+            if self.is_promising() {
+                self.extend();
+            }
+            self.values.pop();
+        }
+    }
+}
@@ -1,4 +1,149 @@
+mod generator;
 mod map;
-pub use map::{Map, MaybeTransposedMap, PlacementError, Tile, TransposedMap};
+pub use map::{
+    Error, InvalidMapError, Map, MaybeTransposedMap, PlacementError, Tile, TransposedMap,
+};
 mod solver;
-pub use solver::{presolve, solve, solve_step};
+pub(crate) use solver::Scratch;
+pub use solver::{
+    count_solutions, fill_tents, presolve, solve, solve_cancellable, solve_step, solve_traced,
+    solve_with, solve_with_stats, GuessOrder, SolverConfig,
+};
+mod trace;
+pub use trace::{Highlighted, StepKind, TraceStep};
+mod rules;
+pub use rules::Rules;
+
+/// Reads a map in its JSON form, checks and solves it, and returns the solved map as JSON, for the bindings to other
+/// languages, which pass maps as JSON strings.
+pub fn solve_json(json: &str) -> crate::Result<String> {
+    let map: Map = serde_json::from_str(json)?;
+    let solution = crate::puzzle::solve_valid::<Camping>(&map)?;
+    Ok(serde_json::to_string(&solution)?)
+}
+
+/// Camping maps as a [`Puzzle`](crate::puzzle::Puzzle), in the text format of [`Map::parse`], solved with the
+/// default rules.
+pub struct Camping;
+
+impl crate::puzzle::Puzzle for Camping {
+    const NAME: &'static str = "camping map";
+
+    type Instance = Map;
+    type Solution = Map;
+
+    fn parse(input: &str) -> crate::Result<Map> {
+        Map::parse_unchecked(input)
+    }
+
+    fn validate(map: &Map) -> crate::Result<()> {
+        map.check_feasible()?;
+        Ok(())
+    }
+
+    fn solve(map: &Map) -> crate::Result<Option<Map>> {
+        solve(map)
+    }
+
+    fn solve_reusing(
+        map: &Map,
+        scratch: &mut crate::puzzle::SolverScratch,
+    ) -> crate::Result<Option<Map>> {
+        solver::solve_reusing(map, &SolverConfig::default(), &mut scratch.camping)
+    }
+
+    /// The number of guesses the solver makes.
+    fn rate(map: &Map) -> crate::Result<u64> {
+        let (_, stats) = solve_with_stats(map, &SolverConfig::default())?;
+        Ok(stats.guesses)
+    }
+
+    /// The deductions settle most maps, so any guess makes a map harder than usual.
+    fn grade(num_guesses: u64) -> crate::difficulty::Difficulty {
+        crate::difficulty::Difficulty::grade(num_guesses, [1, 2, 5])
+    }
+
+    /// Never counts past 2, as [`count_solutions`].
+    fn count_solutions(map: &Map, limit: usize) -> crate::Result<usize> {
+        count_solutions(map, &SolverConfig::default(), limit)
+    }
+
+    fn isomorphic_form(map: &Map) -> String {
+        map.isomorphic_form()
+    }
+
+    /// The text format, and the JSON form of [`Map`]. There is no puzz.link format: the crate has no decoder for the
+    /// tents URLs of pzprjs, so reading one fails as it does for the other puzzles without [`Puzzle::parse_url`].
+    ///
+    /// [`Puzzle::parse_url`]: crate::puzzle::Puzzle::parse_url
+    fn formats() -> Vec<crate::puzzle::Format<Map>> {
+        vec![
+            crate::puzzle::Format::new("text", "txt", Self::parse, |map| Ok(map.to_string())),
+            crate::puzzle::Format::new(
+                "json",
+                "json",
+                |input| Ok(serde_json::from_str(input)?),
+                |map| Ok(serde_json::to_string_pretty(map)?),
+            ),
+        ]
+    }
+
+    /// `size` is the side length of the square map, 8 by default.
+    fn generate(config: &crate::generate::GeneratorConfig) -> crate::Result<Map> {
+        crate::generate::generate::<Self>(config)
+    }
+
+    fn generate_many(
+        config: &crate::generate::GeneratorConfig,
+        count: usize,
+    ) -> crate::Result<Vec<(u64, Map)>> {
+        crate::generate::generate_many::<Self>(config, count)
+    }
+
+    /// Compares tiles, falling back to comparing lines for maps of different sizes or requirements.
+    fn diff(expected: &str, solution: &Map) -> Vec<String> {
+        let Some((expected_map, locations)) =
+            Map::parse_unchecked(expected).ok().and_then(|expected| {
+                expected
+                    .diff(solution)
+                    .map(|locations| (expected, locations))
+            })
+        else {
+            return crate::puzzle::diff_lines(expected, &solution.to_string());
+        };
+        let tile =
+            |map: &Map, location| map.tiles().get(location).map_or(' ', |tile| tile.to_char());
+        locations
+            .into_iter()
+            .map(|location| {
+                format!(
+                    "Row {}, column {}: expected {}, found {}.",
+                    location.row + 1,
+                    location.col + 1,
+                    tile(&expected_map, location),
+                    tile(solution, location)
+                )
+            })
+            .collect()
+    }
+}
+
+impl crate::generate::Generator for Camping {
+    const DEFAULT_SIZE: usize = 8;
+    /// Candidates are quick to make but often have several solutions.
+    const ATTEMPTS: usize = 1000;
+
+    fn candidate(size: usize, rng: &mut rand::rngs::StdRng) -> crate::Result<Option<Map>> {
+        generator::candidate(size, rng).map(Some)
+    }
+
+    /// Clears the blocked tiles of a map that gives its solution away until it is as hard as asked, trying other
+    /// densities of trees when it is not.
+    fn candidate_for_difficulty(
+        size: usize,
+        difficulty: crate::difficulty::Difficulty,
+        rng: &mut rand::rngs::StdRng,
+    ) -> crate::Result<Option<(Map, Option<Map>)>> {
+        Ok(generator::targeted(size, difficulty, rng)?.map(|map| (map, None)))
+    }
+}
@@ -0,0 +1,185 @@
+use ndarray::Array1;
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    difficulty::Difficulty,
+    error::{bail, Result},
+    grid::Grid,
+    location::Location,
+    puzzle::Puzzle,
+};
+
+use super::{Camping, Map, Tile};
+
+/// Makes a square map by placing tents apart from each other, each with a tree beside it, and then clearing the
+/// tents. The map always has a solution, though not always a unique one.
+pub fn candidate(size: usize, rng: &mut impl Rng) -> Result<Map> {
+    let (mut tiles, row_requirements, col_requirements) = place_tents(size, USUAL_DENSITY, rng)?;
+    for location in tiles.locations() {
+        if tiles[location] == Tile::Tent {
+            tiles[location] = Tile::Free;
+        }
+    }
+    Ok(Map::new(tiles, row_requirements, col_requirements)?)
+}
+
+/// Tents per hundred tiles in maps of the usual density, which have about one tent to every five tiles.
+const USUAL_DENSITY: usize = 20;
+
+/// The densities targeting tries, as tents per hundred tiles, starting from the usual one.
+const DENSITIES: [usize; 4] = [USUAL_DENSITY, 16, 12, 24];
+
+/// Makes a square map with a unique solution that [`Camping`] grades to the difficulty, or `None` if this attempt
+/// found none.
+///
+/// Each density is tried in turn, placing the tents as for [`candidate`]. A map that already has the difficulty is
+/// taken as it is. Otherwise every tile without a tree or a tent starts out blocked, which gives the solution away,
+/// and the blocked tiles are cleared in a random order, each only if the map keeps its unique solution and is not
+/// rated harder than the difficulty. A map that is still too easy once no more can be cleared moves on to the next
+/// density.
+pub fn targeted(size: usize, difficulty: Difficulty, rng: &mut impl Rng) -> Result<Option<Map>> {
+    for density in DENSITIES {
+        let (mut tiles, row_requirements, col_requirements) = place_tents(size, density, rng)?;
+        let mut cleared = tiles.clone();
+        for location in cleared.locations() {
+            if cleared[location] == Tile::Tent {
+                cleared[location] = Tile::Free;
+            }
+        }
+        // The map without any blocked tiles is where clearing would end up if nothing stopped it.
+        let cleared = Map::new(cleared, row_requirements.clone(), col_requirements.clone())?;
+        if Camping::count_solutions(&cleared, 2)? == 1
+            && Camping::rating(&cleared)?.difficulty == difficulty
+        {
+            return Ok(Some(cleared));
+        }
+        let mut blocked = tiles
+            .locations()
+            .filter(|&location| tiles[location] == Tile::Free)
+            .collect::<Vec<_>>();
+        for location in tiles.locations() {
+            tiles[location] = match tiles[location] {
+                Tile::Free => Tile::Blocked,
+                Tile::Tent => Tile::Free,
+                tile => tile,
+            };
+        }
+        blocked.shuffle(rng);
+
+        let mut map = Map::new(
+            tiles.clone(),
+            row_requirements.clone(),
+            col_requirements.clone(),
+        )?;
+        let mut rating = Camping::rating(&map)?;
+        for location in blocked {
+            tiles[location] = Tile::Free;
+            let cleared = Map::new(
+                tiles.clone(),
+                row_requirements.clone(),
+                col_requirements.clone(),
+            )?;
+            if Camping::count_solutions(&cleared, 2)? != 1 {
+                tiles[location] = Tile::Blocked;
+                continue;
+            }
+            let cleared_rating = Camping::rating(&cleared)?;
+            if cleared_rating.difficulty > difficulty {
+                tiles[location] = Tile::Blocked;
+                continue;
+            }
+            map = cleared;
+            rating = cleared_rating;
+        }
+        if rating.difficulty == difficulty {
+            return Ok(Some(map));
+        }
+    }
+    Ok(None)
+}
+
+/// Places tents apart from each other, each with a tree beside it, until there are `density` tents per hundred tiles
+/// or no more fit. Returns the tiles with the tents still on them, and the requirements they meet.
+fn place_tents(
+    size: usize,
+    density: usize,
+    rng: &mut impl Rng,
+) -> Result<(Grid<Tile>, Array1<usize>, Array1<usize>)> {
+    if size < 2 {
+        bail!(
+            Generate,
+            "Camping maps are at least 2 by 2, not {size} by {size}."
+        );
+    }
+    let mut tiles = Grid::from_elem((size, size), Tile::Free);
+    let mut locations = tiles.locations().collect::<Vec<_>>();
+    locations.shuffle(rng);
+    let max_tents = size * size * density / 100;
+    let mut tents: Vec<Location> = vec![];
+    for location in locations {
+        if tents.len() == max_tents {
+            break;
+        }
+        if tiles[location] != Tile::Free
+            || tiles
+                .neighbors(location)
+                .into_iter()
+                .flatten()
+                .any(|(_, &tile)| tile == Tile::Tent)
+        {
+            continue;
+        }
+        let spots = tiles
+            .adjacents(location)
+            .into_iter()
+            .flatten()
+            .filter(|&(_, &tile)| tile == Tile::Free)
+            .map(|(spot, _)| spot)
+            .collect::<Vec<_>>();
+        let Some(&tree) = spots.choose(rng) else {
+            continue;
+        };
+        tiles[location] = Tile::Tent;
+        tiles[tree] = Tile::Tree;
+        tents.push(location);
+    }
+
+    let row_requirements = (0..size)
+        .map(|row| tents.iter().filter(|tent| tent.row == row).count())
+        .collect::<Array1<_>>();
+    let col_requirements = (0..size)
+        .map(|col| tents.iter().filter(|tent| tent.col == col).count())
+        .collect::<Array1<_>>();
+    Ok((tiles, row_requirements, col_requirements))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn targets_difficulties() {
+        let map = targeted(10, Difficulty::Medium, &mut StdRng::seed_from_u64(1))
+            .unwrap()
+            .unwrap();
+        assert_eq!(Camping::count_solutions(&map, 2).unwrap(), 1);
+        assert_eq!(
+            Camping::rating(&map).unwrap().difficulty,
+            Difficulty::Medium
+        );
+
+        let easy = |seed| {
+            targeted(8, Difficulty::Easy, &mut StdRng::seed_from_u64(seed))
+                .unwrap()
+                .unwrap()
+        };
+        assert_eq!(easy(0), easy(0));
+        assert_ne!(easy(0), easy(1));
+        assert_eq!(
+            Camping::rating(&easy(0)).unwrap().difficulty,
+            Difficulty::Easy
+        );
+    }
+}
@@ -1,12 +1,18 @@
-use std::{fmt::Display, fs, path};
+use std::{fmt::Display, ops::ControlFlow, path};
 
-use anyhow::{Context, Result};
 use itertools::Itertools;
-use ndarray::{Array1, Array2, ArrayView2, Axis};
+use ndarray::Array1;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::location::Location;
+use crate::{
+    error::{bail, ensure, read_to_string, Result},
+    grid::{Grid, Symmetry},
+    id::PuzzleId,
+    location::Location,
+};
+
+use super::Rules;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Tile {
@@ -14,6 +20,20 @@ pub enum Tile {
     Tent,
     Free,
     Blocked,
+    /// Outside the playable area, for maps that are not rectangular.
+    Void,
+}
+
+impl Tile {
+    pub fn to_char(self) -> char {
+        match self {
+            Tile::Tree => 'T',
+            Tile::Tent => 'X',
+            Tile::Free => ' ',
+            Tile::Blocked => '#',
+            Tile::Void => '-',
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, Error)]
@@ -58,6 +78,59 @@ pub enum InvalidMapError {
     TentNotAdjacentToTree { location: Location },
     #[error("Pair of neighbouring tents at locations {loc1} and {loc2}.")]
     NeighbouringTents { loc1: Location, loc2: Location },
+    #[error("Row requirements sum to {row_total}, but column requirements sum to {col_total}.")]
+    RequirementSumMismatch { row_total: usize, col_total: usize },
+    #[error("Map requires {required} tents, but only has {trees} trees.")]
+    TooFewTrees { trees: usize, required: usize },
+    #[error(
+        "Map requires {required} tents, but only {max} can be paired with trees on the other colour of a checkerboard."
+    )]
+    TooFewByParity { required: usize, max: usize },
+    #[error("Row {row_index} requires {required} tents, but at most {max} fit in a row.")]
+    RowRequirementTooLarge {
+        row_index: usize,
+        required: usize,
+        max: usize,
+    },
+    #[error("Column {col_index} requires {required} tents, but at most {max} fit in a column.")]
+    ColRequirementTooLarge {
+        col_index: usize,
+        required: usize,
+        max: usize,
+    },
+    #[error(
+        "Map is {height}x{width}, but has {rows} row requirements and {cols} column requirements."
+    )]
+    RequirementShapeMismatch {
+        height: usize,
+        width: usize,
+        rows: usize,
+        cols: usize,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Placement(#[from] PlacementError),
+    #[error(transparent)]
+    InvalidMap(#[from] InvalidMapError),
+    /// A solver step reported progress without changing the map, which is a bug in the solver. Only checked in debug
+    /// builds and with the `paranoid` feature.
+    #[error("A solver step reported a change, but left the map as it was.")]
+    FalseChange,
+}
+
+impl From<PlacementError> for crate::Error {
+    fn from(error: PlacementError) -> Self {
+        Error::from(error).into()
+    }
+}
+
+impl From<InvalidMapError> for crate::Error {
+    fn from(error: InvalidMapError) -> Self {
+        Error::from(error).into()
+    }
 }
 
 pub trait MaybeTransposedMap: Sized {
@@ -66,14 +139,15 @@ pub trait MaybeTransposedMap: Sized {
     fn height(&self) -> usize;
     fn width(&self) -> usize;
     fn in_bounds(&self, location: Location) -> bool;
-    fn tiles(&self) -> ArrayView2<Tile>;
+    /// The tiles of a row, from left to right.
+    fn row_tiles(&self, row_index: usize) -> impl Iterator<Item = Tile> + '_;
     fn row_requirements(&self) -> &Array1<usize>;
     fn col_requirements(&self) -> &Array1<usize>;
     fn get(&self, location: Location) -> Option<Tile>;
     fn adjacents(&self, location: Location) -> [Option<(Location, Tile)>; 4];
     fn neighbors(&self, location: Location) -> [Option<(Location, Tile)>; 8];
-    fn is_valid(&self) -> Result<(), InvalidMapError>;
-    fn is_complete(&self) -> bool;
+    fn is_valid(&self, rules: Rules) -> Result<(), InvalidMapError>;
+    fn is_complete(&self, rules: Rules) -> bool;
     fn add_tent(&mut self, location: Location) -> Result<(), PlacementError>;
     fn add_blocked(&mut self, location: Location) -> Result<(), PlacementError>;
     fn num_possible_row_tents(&self, row_index: usize) -> usize;
@@ -81,72 +155,229 @@ pub trait MaybeTransposedMap: Sized {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "MapFields")]
 pub struct Map {
-    tiles: Array2<Tile>,
+    tiles: Grid<Tile>,
     row_requirements: Array1<usize>,
     col_requirements: Array1<usize>,
+    #[serde(skip)]
+    counts: Counts,
+}
+
+/// The fields of a [`Map`] that are serialized, from which the counts are rebuilt.
+#[derive(Deserialize)]
+struct MapFields {
+    tiles: Grid<Tile>,
+    row_requirements: Array1<usize>,
+    col_requirements: Array1<usize>,
+}
+
+impl TryFrom<MapFields> for Map {
+    type Error = Error;
+
+    /// Rejects requirements that do not match the tiles, since the solver indexes them by row and column.
+    fn try_from(fields: MapFields) -> Result<Self, Error> {
+        let (height, width) = fields.tiles.dim();
+        let (rows, cols) = (fields.row_requirements.len(), fields.col_requirements.len());
+        if (rows, cols) != (height, width) {
+            return Err(InvalidMapError::RequirementShapeMismatch {
+                height,
+                width,
+                rows,
+                cols,
+            }
+            .into());
+        }
+        Ok(Self::from_parts(
+            fields.tiles,
+            fields.row_requirements,
+            fields.col_requirements,
+        ))
+    }
+}
+
+/// Counts kept up to date as tiles are placed, so that checking a map while solving need not scan it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Counts {
+    /// The rows followed by the columns.
+    lines: Vec<LineCounts>,
+    /// Tents without a tree next to them. Trees never move, so a tent is lonely from when it is placed.
+    lonely_tents: usize,
+    /// Pairs of tents next to each other horizontally or vertically.
+    adjacent_tents: usize,
+    /// Pairs of tents that only touch diagonally, which only break the rules if diagonal tents are not allowed.
+    diagonal_tents: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct LineCounts {
+    tents: usize,
+    /// Tiles that are free or hold a tent, which is the most tents the line can end up with.
+    open: usize,
+}
+
+impl Counts {
+    fn new(tiles: &Grid<Tile>) -> Self {
+        let (height, width) = tiles.dim();
+        let mut counts = Self {
+            lines: vec![LineCounts::default(); height + width],
+            ..Self::default()
+        };
+        for (location, &tile) in tiles.indexed_iter() {
+            if matches!(tile, Tile::Free | Tile::Tent) {
+                counts.line_counts(height, location, |line| line.open += 1);
+            }
+            if tile == Tile::Tent {
+                // Each pair is counted once, by the tent that comes last in row-major order.
+                counts.tent_placed(tiles, location, |other| {
+                    (other.row, other.col) < (location.row, location.col)
+                });
+            }
+        }
+        counts
+    }
+
+    /// Updates the counts of the row and column of the location.
+    fn line_counts(&mut self, height: usize, location: Location, f: impl Fn(&mut LineCounts)) {
+        f(&mut self.lines[location.row]);
+        f(&mut self.lines[height + location.col]);
+    }
+
+    /// Counts a tent at the location, pairing it with the tents next to it that `counted` accepts.
+    fn tent_placed(
+        &mut self,
+        tiles: &Grid<Tile>,
+        location: Location,
+        counted: impl Fn(Location) -> bool,
+    ) {
+        self.line_counts(tiles.height(), location, |line| line.tents += 1);
+        if !tiles
+            .adjacents(location)
+            .into_iter()
+            .flatten()
+            .any(|(_, &tile)| tile == Tile::Tree)
+        {
+            self.lonely_tents += 1;
+        }
+        for (other, _) in tiles
+            .neighbors(location)
+            .into_iter()
+            .flatten()
+            .filter(|&(other, &tile)| tile == Tile::Tent && counted(other))
+        {
+            if other.row == location.row || other.col == location.col {
+                self.adjacent_tents += 1;
+            } else {
+                self.diagonal_tents += 1;
+            }
+        }
+    }
+
+    /// Whether [`Map::visit_problems`] would find nothing.
+    fn is_valid(
+        &self,
+        row_requirements: &Array1<usize>,
+        col_requirements: &Array1<usize>,
+        rules: Rules,
+    ) -> bool {
+        self.lonely_tents == 0
+            && self.adjacent_tents == 0
+            && (rules.diagonal_tents_allowed || self.diagonal_tents == 0)
+            && row_requirements
+                .iter()
+                .chain(col_requirements.iter())
+                .zip(&self.lines)
+                .all(|(&required, line)| line.tents <= required && line.open >= required)
+    }
 }
 
 impl Map {
     pub fn new(
-        tiles: Array2<Tile>,
+        tiles: Grid<Tile>,
+        row_requirements: Array1<usize>,
+        col_requirements: Array1<usize>,
+    ) -> Result<Self, InvalidMapError> {
+        assert_eq!(tiles.height(), row_requirements.len());
+        assert_eq!(tiles.width(), col_requirements.len());
+        let map = Self::from_parts(tiles, row_requirements, col_requirements);
+        map.check_feasible()?;
+        Ok(map)
+    }
+
+    fn from_parts(
+        tiles: Grid<Tile>,
         row_requirements: Array1<usize>,
         col_requirements: Array1<usize>,
     ) -> Self {
-        assert_eq!(tiles.shape()[0], row_requirements.len());
-        assert_eq!(tiles.shape()[1], col_requirements.len());
+        let counts = Counts::new(&tiles);
         Self {
             tiles,
             row_requirements,
             col_requirements,
+            counts,
         }
     }
 
     pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let map = Self::parse_unchecked(string)?;
+        map.check_feasible()?;
+        Ok(map)
+    }
+
+    /// Parses a map without running `check_feasible`, so that broken maps can still be diagnosed.
+    pub fn parse_unchecked(string: impl AsRef<str>) -> Result<Self> {
         let string = string.as_ref();
         let mut lines = string.lines();
-        let line = lines.next().context("No first line.")?;
-        let (height, width): (&str, &str) = line.split(',').collect_tuple().with_context(|| {
-            format!("Expected two integers separated by a comma. Got '{line}'.")
-        })?;
-        let height = height
-            .parse::<usize>()
-            .with_context(|| format!("Expected a positive integer height. Got '{height}'.",))?;
-        let width = width
-            .parse::<usize>()
-            .with_context(|| format!("Expected a positive integer width. Got '{width}'.",))?;
-        let line = lines.next().context("No second line.")?;
+        let mut next_line = |name: &str| {
+            lines
+                .next()
+                .ok_or_else(|| crate::Error::Parse(format!("No {name} line.")))
+        };
+        let line = next_line("first")?;
+        let Some((height, width)) = line.split(',').collect_tuple() else {
+            bail!(
+                Parse,
+                "Expected two integers separated by a comma. Got '{line}'."
+            );
+        };
+        let Ok(height) = height.parse::<usize>() else {
+            bail!(Parse, "Expected a positive integer height. Got '{height}'.");
+        };
+        let Ok(width) = width.parse::<usize>() else {
+            bail!(Parse, "Expected a positive integer width. Got '{width}'.");
+        };
+        let line = next_line("second")?;
         let row_requirements = line
             .split(',')
             .map(|s| s.parse::<usize>())
             .collect::<Result<Array1<_>, _>>()
-            .with_context(|| {
-                format!(
+            .map_err(|_| {
+                crate::Error::Parse(format!(
                     "Expected {height} non-negative integers separated by commas. Got '{line}'.",
-                )
+                ))
             })?;
-        if row_requirements.len() != height {
-            return Err(anyhow::anyhow!(
-                "Expected {height} non-negative integers separated by commas. Got {len} integers.",
-                len = row_requirements.len()
-            ));
-        }
-        let line = lines.next().context("No third line.")?;
-        let col_requirements =
-            line.split(',')
-                .map(|s| s.parse::<usize>())
-                .collect::<Result<Array1<_>, _>>()
-                .with_context(|| {
-                    format!(
-                        "Expected {width} non-negative integers separated by commas. Got '{line}'.",
-                    )
-                })?;
-        if col_requirements.len() != width {
-            return Err(anyhow::anyhow!(
-                "Expected {width} non-negative integers separated by commas. Got {len} integers.",
-                len = col_requirements.len()
-            ));
-        }
+        ensure!(
+            row_requirements.len() == height,
+            Parse,
+            "Expected {height} non-negative integers separated by commas. Got {len} integers.",
+            len = row_requirements.len()
+        );
+        let line = next_line("third")?;
+        let col_requirements = line
+            .split(',')
+            .map(|s| s.parse::<usize>())
+            .collect::<Result<Array1<_>, _>>()
+            .map_err(|_| {
+                crate::Error::Parse(format!(
+                    "Expected {width} non-negative integers separated by commas. Got '{line}'.",
+                ))
+            })?;
+        ensure!(
+            col_requirements.len() == width,
+            Parse,
+            "Expected {width} non-negative integers separated by commas. Got {len} integers.",
+            len = col_requirements.len()
+        );
         let x = lines
             .flat_map(|line| {
                 line.chars().map(|c| match c {
@@ -154,164 +385,103 @@ impl Map {
                     'X' => Ok(Tile::Tent),
                     ' ' => Ok(Tile::Free),
                     '#' => Ok(Tile::Blocked),
-                    _ => Err(anyhow::anyhow!(
-                        "Expected 'T', 'X', ' ', or '#'. Got '{c}'.",
-                    )),
+                    '-' => Ok(Tile::Void),
+                    _ => Err(crate::Error::Parse(format!(
+                        "Expected 'T', 'X', ' ', '#', or '-'. Got '{c}'.",
+                    ))),
                 })
             })
             .collect::<Result<Vec<_>, _>>()?;
-        let tiles = Array2::from_shape_vec((height, width), x)
-            .with_context(|| "Dimensions of map must match dimensions given at start of file.")?;
+        let tiles = Grid::from_vec((height, width), x).ok_or_else(|| {
+            crate::Error::Parse(
+                "Dimensions of map must match dimensions given at start of file.".into(),
+            )
+        })?;
 
-        Ok(Self {
-            tiles,
-            row_requirements,
-            col_requirements,
-        })
+        Ok(Self::from_parts(tiles, row_requirements, col_requirements))
     }
 
     pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
-        let path = path.as_ref();
-        let string = fs::read_to_string(path)
-            .with_context(|| format!("Error reading map file from path {path:?}"))?;
-        Self::parse(string)
-    }
-
-    pub fn transpose(&mut self) -> TransposedMap {
-        TransposedMap { map: self }
-    }
-}
-
-impl Display for Map {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let (height, width) = self.dim();
-        writeln!(f, "{height},{width}")?;
-        writeln!(f, "{}", self.row_requirements().iter().join(","))?;
-        writeln!(f, "{}", self.col_requirements().iter().join(","))?;
-        writeln!(
-            f,
-            "{}",
-            self.tiles()
-                .axis_iter(Axis(0))
-                .map(|row| row
-                    .iter()
-                    .map(|&t| match t {
-                        Tile::Tree => 'T',
-                        Tile::Tent => 'X',
-                        Tile::Free => ' ',
-                        Tile::Blocked => '#',
-                    })
-                    .join(""))
-                .join("\n")
-        )
-    }
-}
-
-impl MaybeTransposedMap for Map {
-    fn map(&self) -> &Map {
-        self
-    }
-
-    fn dim(&self) -> (usize, usize) {
-        self.tiles().dim()
+        Self::parse(Self::read_file(path)?)
     }
 
-    fn height(&self) -> usize {
-        self.tiles().shape()[0]
-    }
-
-    fn width(&self) -> usize {
-        self.tiles().shape()[1]
-    }
-
-    fn in_bounds(&self, location: Location) -> bool {
-        let (height, width) = self.dim();
-        location.row < height && location.col < width
-    }
-
-    fn tiles(&self) -> ArrayView2<Tile> {
-        self.tiles.view()
-    }
-
-    fn row_requirements(&self) -> &Array1<usize> {
-        &self.row_requirements
-    }
-
-    fn col_requirements(&self) -> &Array1<usize> {
-        &self.col_requirements
+    pub fn from_file_unchecked(path: impl AsRef<path::Path>) -> Result<Self> {
+        Self::parse_unchecked(Self::read_file(path)?)
     }
 
-    fn get(&self, location: Location) -> Option<Tile> {
-        self.tiles.get((location.row, location.col)).copied()
-    }
-
-    fn adjacents(&self, location: Location) -> [Option<(Location, Tile)>; 4] {
-        location
-            .adjacents(self.dim())
-            .map(move |loc| loc.map(|loc| (loc, self.get(loc).unwrap())))
-    }
-
-    fn neighbors(&self, location: Location) -> [Option<(Location, Tile)>; 8] {
-        location.neighbors(self.dim()).map(move |loc| {
-            loc.map(|loc| (loc, self.get(loc).unwrap_or_else(|| panic!("{:?}", loc))))
-        })
+    fn read_file(path: impl AsRef<path::Path>) -> Result<String> {
+        read_to_string("map", path.as_ref())
     }
 
-    fn is_valid(&self) -> Result<(), InvalidMapError> {
+    /// Calls `visit` with every rule violation on the map, stopping early if `visit` breaks.
+    fn visit_problems<F>(&self, rules: Rules, mut visit: F) -> ControlFlow<InvalidMapError>
+    where
+        F: FnMut(InvalidMapError) -> ControlFlow<InvalidMapError>,
+    {
         // RULES:
         // 1. Each row and column must have no more than the correct number of tents and enough free spaces to reach the required amount.
-        // 2. Tents cannot be adjacent to each other, neither horizontally, vertically, nor diagonally.
+        // 2. Tents cannot be adjacent to each other, neither horizontally, vertically, nor diagonally
+        //    (unless the rules allow diagonal tents).
         // 3. Tents must be placed adjacent to trees, horizontally and vertically.
 
-        for (row_index, row) in self.tiles().axis_iter(Axis(0)).enumerate() {
+        for row_index in 0..self.height() {
             let requirement = self.row_requirements()[row_index];
-            let num_tents = row.iter().filter(|&&t| t == Tile::Tent).count();
-            let num_poss_tents = row
-                .iter()
+            let num_tents = self
+                .tiles
+                .row(row_index)
+                .filter(|&&t| t == Tile::Tent)
+                .count();
+            let num_poss_tents = self
+                .tiles
+                .row(row_index)
                 .filter(|&&t| t == Tile::Free || t == Tile::Tent)
                 .count();
             if num_tents > requirement {
-                return Err(InvalidMapError::TooManyTentsInRow {
+                visit(InvalidMapError::TooManyTentsInRow {
                     row_index,
                     placed: num_tents,
                     required: requirement,
-                });
+                })?;
             }
             if num_poss_tents < requirement {
-                return Err(InvalidMapError::TooFewPossibleTentsInRow {
+                visit(InvalidMapError::TooFewPossibleTentsInRow {
                     row_index,
                     possible: num_poss_tents,
                     required: requirement,
-                });
+                })?;
             }
         }
 
-        for (col_index, col) in self.tiles().axis_iter(Axis(1)).enumerate() {
+        for col_index in 0..self.width() {
             let requirement = self.col_requirements()[col_index];
-            let num_tents = col.iter().filter(|&&t| t == Tile::Tent).count();
-            let num_poss_tents = col
-                .iter()
+            let num_tents = self
+                .tiles
+                .col(col_index)
+                .filter(|&&t| t == Tile::Tent)
+                .count();
+            let num_poss_tents = self
+                .tiles
+                .col(col_index)
                 .filter(|&&t| t == Tile::Free || t == Tile::Tent)
                 .count();
             if num_tents > requirement {
-                return Err(InvalidMapError::TooManyTentsInCol {
+                visit(InvalidMapError::TooManyTentsInCol {
                     col_index,
                     placed: num_tents,
                     required: requirement,
-                });
+                })?;
             }
             if num_poss_tents < requirement {
-                return Err(InvalidMapError::TooFewPossibleTentsInCol {
+                visit(InvalidMapError::TooFewPossibleTentsInCol {
                     col_index,
                     possible: num_poss_tents,
                     required: requirement,
-                });
+                })?;
             }
         }
 
         // Iterate over all tiles
-        for ((row, col), &tile) in self.tiles().indexed_iter() {
-            let loc = Location::new(row, col);
+        for (loc, &tile) in self.tiles.indexed_iter() {
             match tile {
                 Tile::Tree => {}
                 Tile::Tent => {
@@ -321,34 +491,363 @@ impl MaybeTransposedMap for Map {
                         .flatten()
                         .any(|(_, t)| t == Tile::Tree)
                     {
-                        return Err(InvalidMapError::TentNotAdjacentToTree { location: loc });
+                        visit(InvalidMapError::TentNotAdjacentToTree { location: loc })?;
                     }
-                    if let Some((other_loc, _tile)) = self
-                        .neighbors(loc)
-                        .into_iter()
-                        .flatten()
-                        .find(|&(_, t)| t == Tile::Tent)
+                    // Each pair is reported once, by the tent that comes first in row-major order.
+                    for (other_loc, _tile) in
+                        rules.tent_neighbors(self, loc).filter(|&(other_loc, t)| {
+                            t == Tile::Tent && (other_loc.row, other_loc.col) > (loc.row, loc.col)
+                        })
                     {
-                        return Err(InvalidMapError::NeighbouringTents {
+                        visit(InvalidMapError::NeighbouringTents {
                             loc1: loc,
                             loc2: other_loc,
-                        });
+                        })?;
                     }
                 }
                 Tile::Free => {}
-                Tile::Blocked => {}
+                Tile::Blocked | Tile::Void => {}
+            }
+        }
+
+        ControlFlow::Continue(())
+    }
+
+    /// Checks that hold for any solvable map regardless of how far solving has progressed.
+    fn visit_global_problems<F>(&self, mut visit: F) -> ControlFlow<InvalidMapError>
+    where
+        F: FnMut(InvalidMapError) -> ControlFlow<InvalidMapError>,
+    {
+        let row_total: usize = self.row_requirements.sum();
+        let col_total: usize = self.col_requirements.sum();
+        if row_total != col_total {
+            visit(InvalidMapError::RequirementSumMismatch {
+                row_total,
+                col_total,
+            })?;
+        }
+
+        // Every tent is paired with a distinct tree.
+        let trees = self.tiles.iter().filter(|&&t| t == Tile::Tree).count();
+        let required = row_total.max(col_total);
+        if trees < required {
+            visit(InvalidMapError::TooFewTrees { trees, required })?;
+        }
+
+        // A tent and its tree are adjacent, so they lie on different colours of a checkerboard. The tents on each
+        // colour are then at most the trees on the other colour, and at most the tiles of their own colour that are
+        // next to a tree.
+        let mut colour_trees = [0; 2];
+        let mut colour_places = [0; 2];
+        for (loc, &tile) in self.tiles.indexed_iter() {
+            let colour = (loc.row + loc.col) % 2;
+            match tile {
+                Tile::Tree => colour_trees[colour] += 1,
+                Tile::Tent | Tile::Free
+                    if self
+                        .tiles
+                        .adjacents(loc)
+                        .into_iter()
+                        .flatten()
+                        .any(|(_, &tile)| tile == Tile::Tree) =>
+                {
+                    colour_places[colour] += 1
+                }
+                _ => {}
+            }
+        }
+        let max = colour_places[0].min(colour_trees[1]) + colour_places[1].min(colour_trees[0]);
+        if max < required {
+            visit(InvalidMapError::TooFewByParity { required, max })?;
+        }
+
+        // Tents cannot be next to each other, so at most every other tile in a line can hold one.
+        let (height, width) = self.tiles.dim();
+        let max = width.div_ceil(2);
+        for (row_index, &required) in self.row_requirements.iter().enumerate() {
+            if required > max {
+                visit(InvalidMapError::RowRequirementTooLarge {
+                    row_index,
+                    required,
+                    max,
+                })?;
+            }
+        }
+        let max = height.div_ceil(2);
+        for (col_index, &required) in self.col_requirements.iter().enumerate() {
+            if required > max {
+                visit(InvalidMapError::ColRequirementTooLarge {
+                    col_index,
+                    required,
+                    max,
+                })?;
+            }
+        }
+
+        ControlFlow::Continue(())
+    }
+
+    /// Cheap checks run when a map is constructed, catching maps that can never be solved
+    /// before they reach the search.
+    pub fn check_feasible(&self) -> Result<(), InvalidMapError> {
+        match self.visit_global_problems(ControlFlow::Break) {
+            ControlFlow::Continue(()) => Ok(()),
+            ControlFlow::Break(error) => Err(error),
+        }
+    }
+
+    /// Returns every problem with the map instead of only the first one found.
+    /// Includes global infeasibility checks that `is_valid` does not perform.
+    pub fn problems(&self, rules: Rules) -> Vec<InvalidMapError> {
+        let mut problems = vec![];
+        let mut push = |error| {
+            problems.push(error);
+            ControlFlow::Continue(())
+        };
+        _ = self.visit_global_problems(&mut push);
+        _ = self.visit_problems(rules, &mut push);
+        problems
+    }
+
+    /// Whether the row and column of the free location still have room for their tents, and no more tents than
+    /// required, once `tile` is placed there. Only looks at the counts, so it is cheap enough to check before every
+    /// guess.
+    pub fn fits_lines(&self, location: Location, tile: Tile) -> bool {
+        let height = self.tiles.height();
+        [
+            (
+                &self.counts.lines[location.row],
+                self.row_requirements[location.row],
+            ),
+            (
+                &self.counts.lines[height + location.col],
+                self.col_requirements[location.col],
+            ),
+        ]
+        .into_iter()
+        .all(|(line, required)| match tile {
+            Tile::Tent => line.tents < required,
+            Tile::Blocked => line.open > required,
+            _ => true,
+        })
+    }
+
+    pub fn tiles(&self) -> &Grid<Tile> {
+        &self.tiles
+    }
+
+    /// The id of the map, which is the same for every map with the same tiles and requirements.
+    pub fn id(&self) -> PuzzleId {
+        PuzzleId::of::<super::Camping>(self)
+    }
+
+    /// The locations of the tiles that differ between the maps, or `None` if their sizes or requirements differ.
+    pub fn diff(&self, other: &Map) -> Option<Vec<Location>> {
+        if self.tiles.dim() != other.tiles.dim()
+            || self.row_requirements != other.row_requirements
+            || self.col_requirements != other.col_requirements
+        {
+            return None;
+        }
+        Some(
+            self.tiles
+                .indexed_iter()
+                .filter(|&(location, tile)| other.tiles.get(location) != Some(tile))
+                .map(|(location, _)| location)
+                .collect(),
+        )
+    }
+
+    /// The map rotated or reflected by the symmetry, with its requirements moved along with the lines.
+    pub fn transformed(&self, symmetry: Symmetry) -> Map {
+        let line = |requirements: &Array1<usize>, flip: bool| {
+            let mut requirements = requirements.to_vec();
+            if flip {
+                requirements.reverse();
             }
+            Array1::from(requirements)
+        };
+        let rows = line(&self.row_requirements, symmetry.flip_rows);
+        let cols = line(&self.col_requirements, symmetry.flip_cols);
+        let (rows, cols) = if symmetry.transpose {
+            (cols, rows)
+        } else {
+            (rows, cols)
+        };
+        Self::from_parts(self.tiles.transformed(symmetry), rows, cols)
+    }
+
+    /// The map in a form shared by every map that is the same puzzle rotated or reflected: the smallest of the
+    /// transformed maps written as text.
+    pub fn isomorphic_form(&self) -> String {
+        Symmetry::ALL
+            .into_iter()
+            .map(|symmetry| self.transformed(symmetry).to_string())
+            .min()
+            .expect("There are always symmetries.")
+    }
+
+    /// The map inside a larger one with the given dimensions, with its top left corner at `at` and void around it.
+    /// The lines outside the map require no tents, so the larger map has the same solutions as the map, moved along.
+    pub fn embed(&self, into_dims: (usize, usize), at: Location) -> Result<Map> {
+        let (height, width) = self.dim();
+        ensure!(
+            at.row + height <= into_dims.0 && at.col + width <= into_dims.1,
+            Invalid,
+            "A {height} by {width} map at {at} does not fit in a {} by {} map.",
+            into_dims.0,
+            into_dims.1
+        );
+        let tiles = Grid::from_fn(into_dims, |location| {
+            location
+                .row
+                .checked_sub(at.row)
+                .zip(location.col.checked_sub(at.col))
+                .and_then(|inner| self.tiles.get(inner.into()))
+                .copied()
+                .unwrap_or(Tile::Void)
+        });
+        let line = |requirements: &Array1<usize>, len: usize, start: usize| {
+            (0..len)
+                .map(|index| {
+                    index
+                        .checked_sub(start)
+                        .and_then(|inner| requirements.get(inner))
+                        .copied()
+                        .unwrap_or(0)
+                })
+                .collect::<Array1<_>>()
+        };
+        let rows = line(&self.row_requirements, into_dims.0, at.row);
+        let cols = line(&self.col_requirements, into_dims.1, at.col);
+        Ok(Self::from_parts(tiles, rows, cols))
+    }
+
+    /// The maps side by side from left to right, aligned at the top and with `gap` void columns between them, such as
+    /// for a sheet of practice maps. Rows shared by several maps require the tents of each map added together, so
+    /// every combination of solutions to the maps solves the sheet, but the sheet may also have solutions that move
+    /// tents between maps in the same row. Without a gap, tents at the edge of one map can also use the trees of the
+    /// next.
+    pub fn concat(maps: &[Map], gap: usize) -> Map {
+        let height = maps.iter().map(|map| map.dim().0).max().unwrap_or(0);
+        let width =
+            maps.iter().map(|map| map.dim().1).sum::<usize>() + gap * maps.len().saturating_sub(1);
+        let mut tiles = Grid::from_elem((height, width), Tile::Void);
+        let mut row_requirements = Array1::zeros(height);
+        let mut col_requirements = Array1::zeros(width);
+        let mut start = 0;
+        for map in maps {
+            let (map_height, map_width) = map.dim();
+            for (location, &tile) in map.tiles.indexed_iter() {
+                tiles[Location::new(location.row, start + location.col)] = tile;
+            }
+            let mut rows = row_requirements.slice_mut(ndarray::s![..map_height]);
+            rows += &map.row_requirements;
+            col_requirements
+                .slice_mut(ndarray::s![start..start + map_width])
+                .assign(&map.col_requirements);
+            start += map_width + gap;
         }
+        Self::from_parts(tiles, row_requirements, col_requirements)
+    }
+
+    pub fn transpose(&mut self) -> TransposedMap<'_> {
+        TransposedMap { map: self }
+    }
+}
+
+impl Display for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (height, width) = self.dim();
+        writeln!(f, "{height},{width}")?;
+        writeln!(f, "{}", self.row_requirements().iter().join(","))?;
+        writeln!(f, "{}", self.col_requirements().iter().join(","))?;
+        writeln!(
+            f,
+            "{}",
+            (0..height)
+                .map(|row_index| self.tiles.row(row_index).map(|&t| t.to_char()).join(""))
+                .join("\n")
+        )
+    }
+}
+
+impl MaybeTransposedMap for Map {
+    fn map(&self) -> &Map {
+        self
+    }
+
+    fn dim(&self) -> (usize, usize) {
+        self.tiles.dim()
+    }
+
+    fn height(&self) -> usize {
+        self.tiles.height()
+    }
+
+    fn width(&self) -> usize {
+        self.tiles.width()
+    }
+
+    fn in_bounds(&self, location: Location) -> bool {
+        self.tiles.in_bounds(location)
+    }
+
+    fn row_tiles(&self, row_index: usize) -> impl Iterator<Item = Tile> + '_ {
+        self.tiles.row(row_index).copied()
+    }
+
+    fn row_requirements(&self) -> &Array1<usize> {
+        &self.row_requirements
+    }
+
+    fn col_requirements(&self) -> &Array1<usize> {
+        &self.col_requirements
+    }
+
+    fn get(&self, location: Location) -> Option<Tile> {
+        self.tiles.get(location).copied()
+    }
 
-        Ok(())
+    fn adjacents(&self, location: Location) -> [Option<(Location, Tile)>; 4] {
+        self.tiles
+            .adjacents(location)
+            .map(|adjacent| adjacent.map(|(loc, &tile)| (loc, tile)))
+    }
+
+    fn neighbors(&self, location: Location) -> [Option<(Location, Tile)>; 8] {
+        self.tiles
+            .neighbors(location)
+            .map(|neighbor| neighbor.map(|(loc, &tile)| (loc, tile)))
+    }
+
+    /// Answers from the counts kept as tiles are placed, and only scans the map to find the problem if there is one.
+    fn is_valid(&self, rules: Rules) -> Result<(), InvalidMapError> {
+        if self
+            .counts
+            .is_valid(&self.row_requirements, &self.col_requirements, rules)
+        {
+            debug_assert!(
+                self.visit_problems(rules, ControlFlow::Break).is_continue(),
+                "The counts of the map missed a problem."
+            );
+            return Ok(());
+        }
+        match self.visit_problems(rules, ControlFlow::Break) {
+            ControlFlow::Continue(()) => {
+                unreachable!("The counts of the map found a problem the scan did not.")
+            }
+            ControlFlow::Break(error) => Err(error),
+        }
     }
 
-    fn is_complete(&self) -> bool {
+    fn is_complete(&self, rules: Rules) -> bool {
         // RULES:
         // 1. No free tiles exist.
         // 2. Map must be valid.
 
-        self.tiles().iter().all(|&t| t != Tile::Free) && self.is_valid().is_ok()
+        // A line has no free tiles if every open tile holds a tent.
+        self.counts.lines.iter().all(|line| line.open == line.tents) && self.is_valid(rules).is_ok()
     }
 
     fn add_tent(&mut self, location: Location) -> Result<(), PlacementError> {
@@ -356,7 +855,8 @@ impl MaybeTransposedMap for Map {
             if tile != Tile::Free {
                 Err(PlacementError::NotFree { location, tile })
             } else {
-                self.tiles[(location.row, location.col)] = Tile::Tent;
+                self.tiles[location] = Tile::Tent;
+                self.counts.tent_placed(&self.tiles, location, |_| true);
                 Ok(())
             }
         } else {
@@ -369,7 +869,9 @@ impl MaybeTransposedMap for Map {
             if tile != Tile::Free {
                 Err(PlacementError::NotFree { location, tile })
             } else {
-                self.tiles[(location.row, location.col)] = Tile::Blocked;
+                self.tiles[location] = Tile::Blocked;
+                self.counts
+                    .line_counts(self.tiles.height(), location, |line| line.open -= 1);
                 Ok(())
             }
         } else {
@@ -381,7 +883,7 @@ impl MaybeTransposedMap for Map {
     fn num_possible_row_tents(&self, row_index: usize) -> usize {
         let mut total = 0;
         let mut prev = false;
-        for &tile in self.tiles().row(row_index) {
+        for &tile in self.tiles.row(row_index) {
             if prev {
                 prev = false;
             } else if tile == Tile::Free {
@@ -395,7 +897,7 @@ impl MaybeTransposedMap for Map {
     fn num_possible_col_tents(&self, col_index: usize) -> usize {
         let mut total = 0;
         let mut prev = false;
-        for &tile in self.tiles().column(col_index) {
+        for &tile in self.tiles.col(col_index) {
             if prev {
                 prev = false;
             } else if tile == Tile::Free {
@@ -437,10 +939,8 @@ impl<'a> MaybeTransposedMap for TransposedMap<'a> {
         self.map.in_bounds(location.transpose())
     }
 
-    fn tiles(&self) -> ArrayView2<Tile> {
-        let mut tiles = self.map.tiles();
-        tiles.swap_axes(0, 1);
-        tiles
+    fn row_tiles(&self, row_index: usize) -> impl Iterator<Item = Tile> + '_ {
+        self.map.tiles.transposed().row(row_index).copied()
     }
 
     fn row_requirements(&self) -> &Array1<usize> {
@@ -467,12 +967,12 @@ impl<'a> MaybeTransposedMap for TransposedMap<'a> {
             .map(|loc| loc.map(|(loc, t)| (loc.transpose(), t)))
     }
 
-    fn is_valid(&self) -> Result<(), InvalidMapError> {
-        self.map.is_valid()
+    fn is_valid(&self, rules: Rules) -> Result<(), InvalidMapError> {
+        self.map.is_valid(rules)
     }
 
-    fn is_complete(&self) -> bool {
-        self.map.is_complete()
+    fn is_complete(&self, rules: Rules) -> bool {
+        self.map.is_complete(rules)
     }
 
     fn add_tent(&mut self, location: Location) -> Result<(), PlacementError> {
@@ -491,3 +991,123 @@ impl<'a> MaybeTransposedMap for TransposedMap<'a> {
         self.map.num_possible_row_tents(col_index)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{camping::Camping, puzzle::Puzzle};
+
+    #[test]
+    fn finds_too_few_tents_by_parity() {
+        // Both trees can only pair with the tile between them, though there are as many trees as tents.
+        let map = Map::parse_unchecked("1,3\n2\n1,0,1\nT T\n").unwrap();
+        assert!(matches!(
+            map.problems(Rules::default())[..],
+            [
+                InvalidMapError::TooFewByParity {
+                    required: 2,
+                    max: 1
+                },
+                ..
+            ]
+        ));
+        assert!(matches!(
+            map.check_feasible(),
+            Err(InvalidMapError::TooFewByParity { .. })
+        ));
+        let map = Map::parse(include_str!("../../data/camping/maps/map01.txt")).unwrap();
+        assert!(map.check_feasible().is_ok());
+    }
+
+    #[test]
+    fn embeds_and_concatenates_maps() {
+        let map = Camping::parse(include_str!("../../data/camping/maps/map01.txt")).unwrap();
+        let solution = Camping::solve(&map).unwrap().unwrap();
+        let at = Location::new(2, 3);
+        let embedded = map.embed((12, 11), at).unwrap();
+        assert_eq!(embedded.dim(), (12, 11));
+        assert_eq!(embedded.tiles()[Location::new(0, 0)], Tile::Void);
+        assert_eq!(
+            Camping::solve(&embedded).unwrap(),
+            Some(solution.embed((12, 11), at).unwrap())
+        );
+        assert!(map.embed((8, 8), Location::new(0, 1)).is_err());
+
+        let other = Camping::parse(include_str!("../../data/camping/maps/map02.txt")).unwrap();
+        let other_solution = Camping::solve(&other).unwrap().unwrap();
+        let sheet = Map::concat(&[map.clone(), other.clone()], 1);
+        assert_eq!(sheet.dim().1, map.dim().1 + 1 + other.dim().1);
+        assert_eq!(
+            sheet.row_requirements().sum(),
+            map.row_requirements().sum() + other.row_requirements().sum()
+        );
+        let solved_sheet = Map::concat(&[solution, other_solution], 1);
+        assert!(solved_sheet.problems(Rules::default()).is_empty());
+        assert!(Camping::solve(&sheet).unwrap().is_some());
+    }
+
+    #[test]
+    fn solves_around_void_tiles() {
+        let text = include_str!("../../data/camping/maps/map25.txt");
+        let map = Map::parse(text).unwrap();
+        assert_eq!(Map::parse(map.to_string()).unwrap(), map);
+        let voids = map
+            .tiles()
+            .indexed_iter()
+            .filter(|&(_, &tile)| tile == Tile::Void)
+            .map(|(location, _)| location)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            voids,
+            [
+                Location::new(0, 0),
+                Location::new(0, 1),
+                Location::new(0, 7),
+                Location::new(6, 0),
+                Location::new(6, 1)
+            ]
+        );
+
+        let solution = Camping::solve(&map).unwrap().unwrap();
+        assert!(solution.is_complete(Rules::default()));
+        assert!(solution.problems(Rules::default()).is_empty());
+        assert!(voids
+            .iter()
+            .all(|&location| solution.get(location) == Some(Tile::Void)));
+        assert_eq!(Camping::count_solutions(&map, 2).unwrap(), 1);
+
+        // Nothing can be placed on a void tile, and a solution with a tent or tree there is not the solution.
+        let mut placed = map.clone();
+        assert!(matches!(
+            placed.add_tent(voids[0]),
+            Err(PlacementError::NotFree {
+                tile: Tile::Void,
+                ..
+            })
+        ));
+        assert!(placed.add_blocked(voids[0]).is_err());
+        let solved = solution.to_string();
+        for replacement in ["X-", "T-"] {
+            let answer = solved.replacen("--", replacement, 1);
+            assert_eq!(
+                Camping::diff(&answer, &solution),
+                [format!(
+                    "Row 1, column 1: expected {}, found -.",
+                    &replacement[..1]
+                )]
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_mismatched_requirements_when_deserializing() {
+        let map = Camping::parse(include_str!("../../data/camping/maps/map01.txt")).unwrap();
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(serde_json::from_str::<Map>(&json).unwrap(), map);
+
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["row_requirements"] = serde_json::to_value(Array1::from_elem(1, 1)).unwrap();
+        let error = serde_json::from_value::<Map>(value).unwrap_err();
+        assert!(error.to_string().contains("row requirements"), "{error}");
+    }
+}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+use crate::location::Location;
+
+use super::{map::MaybeTransposedMap, Tile};
+
+/// Variations on the rules of the puzzle.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rules {
+    /// Tents may touch diagonally, so only orthogonally adjacent tents are forbidden.
+    pub diagonal_tents_allowed: bool,
+}
+
+impl Rules {
+    /// Tiles that cannot hold a tent if `location` holds one.
+    pub fn tent_neighbors<M>(
+        self,
+        map: &M,
+        location: Location,
+    ) -> impl Iterator<Item = (Location, Tile)>
+    where
+        M: MaybeTransposedMap,
+    {
+        map.neighbors(location)
+            .into_iter()
+            .flatten()
+            .filter(move |&(loc, _)| {
+                !self.diagonal_tents_allowed || loc.row == location.row || loc.col == location.col
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camping::{count_solutions, solve_with, InvalidMapError, Map, SolverConfig};
+
+    #[test]
+    fn diagonal_variant_allows_touching_tents() {
+        let map = Map::parse(include_str!("../../data/camping/variants/diagonal01.txt")).unwrap();
+        let diagonal = Rules {
+            diagonal_tents_allowed: true,
+        };
+        let config = SolverConfig {
+            rules: diagonal,
+            ..SolverConfig::default()
+        };
+        let solution = solve_with(&map, &config).unwrap().unwrap();
+        assert!(solution.is_complete(diagonal));
+        assert!(solution.problems(diagonal).is_empty());
+        assert_eq!(count_solutions(&map, &config, 2).unwrap(), 1);
+
+        // The solution has tents that touch diagonally, which the usual rules reject.
+        assert!(!solution.is_complete(Rules::default()));
+        let problems = solution.problems(Rules::default());
+        assert!(!problems.is_empty());
+        assert!(problems.iter().all(
+            |problem| matches!(problem, InvalidMapError::NeighbouringTents { loc1, loc2 }
+                if loc1.row != loc2.row && loc1.col != loc2.col)
+        ));
+        assert!(solve_with(&map, &SolverConfig::default()).is_err());
+    }
+}
@@ -1,17 +1,27 @@
-use crate::location::{GridIter, Location};
+use std::cell::RefCell;
 
-use anyhow::{ensure, Context, Result};
+use smallvec::smallvec;
 
-use super::{map::MaybeTransposedMap, Map, Tile};
+use crate::{
+    cancel::CancelToken,
+    engine::{Constraint, Engine},
+    error::Result,
+    location::Location,
+    stats::{self, SolveStats},
+};
+
+use super::{
+    map::{Error, MaybeTransposedMap, PlacementError},
+    trace::{StepKind, TraceStep, Tracer},
+    Map, Rules, Tile,
+};
 fn block_row_if_finished<M>(map: &mut M, row_index: usize, requirement: usize) -> Result<bool>
 where
     M: MaybeTransposedMap,
 {
     let num_tents = map
-        .tiles()
-        .row(row_index)
-        .iter()
-        .filter(|&&tile| tile == Tile::Tent)
+        .row_tiles(row_index)
+        .filter(|&tile| tile == Tile::Tent)
         .count();
     if num_tents == requirement {
         let mut changed = false;
@@ -36,14 +46,11 @@ where
 
     for col_index in 0..width {
         let loc = Location::new(row_index, col_index);
-        let cur_tile = map
-            .get(loc)
-            .with_context(|| format!("Location {loc} is outside of the map."))?;
+        let cur_tile = map.get(loc).ok_or(PlacementError::OutOfBounds(loc))?;
         match cur_tile {
-            Tile::Tree | Tile::Blocked => {
+            Tile::Tree | Tile::Blocked | Tile::Void => {
                 if col_index - run_start > 0 {
-                    action(map, run_start, col_index)
-                        .with_context(|| format!("Error while processing run ending at {loc}."))?;
+                    action(map, run_start, col_index)?;
                 }
                 run_start = col_index + 1;
             }
@@ -55,30 +62,32 @@ where
         }
     }
     if run_start < width {
-        action(map, run_start, width).with_context(|| {
-            format!("Error while processing run at end of row {row_index} starting at {run_start}.")
-        })?;
+        action(map, run_start, width)?;
     }
     Ok(())
 }
 
-fn handle_row_runs<M>(map: &mut M, row_index: usize, requirement: usize) -> Result<bool>
+fn handle_row_runs<M>(
+    map: &mut M,
+    row_index: usize,
+    requirement: usize,
+    rules: Rules,
+) -> Result<bool>
 where
     M: MaybeTransposedMap,
 {
     let mut changed = false;
     let num_possible_row_tents = map.num_possible_row_tents(row_index);
     let num_cur_row_tents = map
-        .tiles()
-        .row(row_index)
-        .iter()
-        .filter(|&&tile| tile == Tile::Tent)
+        .row_tiles(row_index)
+        .filter(|&tile| tile == Tile::Tent)
         .count();
-    if num_possible_row_tents == requirement - num_cur_row_tents {
+    if num_possible_row_tents + num_cur_row_tents == requirement {
         run_iter(map, row_index, |map, run_start, run_end| {
             let run_length = run_end - run_start;
             // If the run is empty, there is really no run.
-            if run_length != 0 {
+            // With diagonal tents allowed, only the cells next to the tents placed below can be blocked.
+            if run_length != 0 && !rules.diagonal_tents_allowed {
                 // We know that at least every other cell in the run must be a tent.
                 // Therefore the adjacent cells can be blocked.
                 let block_locs = (run_start..run_end).flat_map(|block_col_index| {
@@ -93,10 +102,12 @@ where
                 for block_loc in block_locs {
                     changed |= map.add_blocked(block_loc).is_ok();
                 }
+            }
 
-                // If the run is odd, we can place tents every other cell in the run,
-                // and block the neighbouring cells we skipped above.
-                if run_length % 2 == 1 {
+            // If the run is odd, we can place tents every other cell in the run,
+            // and block the neighbouring cells we skipped above.
+            if run_length % 2 == 1 {
+                if !rules.diagonal_tents_allowed {
                     let block_locs = [
                         (row_index > 0 && run_start > 0)
                             .then(|| Location::new(row_index - 1, run_start - 1)),
@@ -109,21 +120,22 @@ where
                         // and we don't care about the error.
                         _ = map.add_blocked(block_loc)
                     }
-                    for (i, fill_col_index) in (run_start..run_end).enumerate() {
-                        let fill_loc = Location::new(row_index, fill_col_index);
-                        if i % 2 == 0 {
-                            map.add_tent(fill_loc)
-                            .with_context(|| format!("Failed to add tent. Expected position to be free. Location: {fill_loc}  Row: {row_index}"))?;
-                        } else {
-                            map.add_blocked(fill_loc).with_context(|| format!("Failed to add blocked. Expected position to be free. Location: {fill_loc}  Row: {row_index}"))?;
-                        }
+                }
+                for (i, fill_col_index) in (run_start..run_end).enumerate() {
+                    let fill_loc = Location::new(row_index, fill_col_index);
+                    if i % 2 == 0 {
+                        map.add_tent(fill_loc)?;
+                    } else {
+                        map.add_blocked(fill_loc)?;
                     }
-                    changed = true;
                 }
+                changed = true;
             }
             Ok(())
         })?;
-    } else if num_possible_row_tents == requirement - num_cur_row_tents + 1 {
+    } else if num_possible_row_tents + num_cur_row_tents == requirement + 1
+        && !rules.diagonal_tents_allowed
+    {
         // In this case we cannot place any tents, but we can block some tiles.
         // Specifically when there are two odd-length runs with a single cell between them.
         // Since at least one of the runs must be filled,
@@ -153,39 +165,144 @@ where
     Ok(changed)
 }
 
-fn handle_rows(map: &mut impl MaybeTransposedMap) -> Result<bool> {
-    let mut changed = false;
-    let row_requirements = map.row_requirements().clone();
-    for (row_index, requirement) in row_requirements.into_iter().enumerate() {
-        changed |= handle_row_runs(map, row_index, requirement)
-            .with_context(|| format!("Error while processing runs in row {row_index}."))?;
-        changed |= block_row_if_finished(map, row_index, requirement).with_context(|| {
-            format!("Error while checking whether row {row_index} was finished.")
-        })?;
+/// The rules on the number of tents in a row, or in a column if `transposed` is set.
+struct LineConstraint {
+    index: usize,
+    transposed: bool,
+    rules: Rules,
+    /// The width of the untransposed map, to number its tiles.
+    width: usize,
+    length: usize,
+}
+
+impl LineConstraint {
+    fn variable(&self, loc: Location) -> usize {
+        loc.row * self.width + loc.col
+    }
+
+    /// The locations of the line, in the untransposed map.
+    fn locations(&self, line_index: usize) -> impl Iterator<Item = Location> + '_ {
+        (0..self.length).map(move |i| {
+            let loc = Location::new(line_index, i);
+            if self.transposed {
+                loc.transpose()
+            } else {
+                loc
+            }
+        })
+    }
+
+    fn apply<M>(&self, map: &mut M) -> Result<()>
+    where
+        M: MaybeTransposedMap,
+    {
+        let requirement = map.row_requirements()[self.index];
+        handle_row_runs(map, self.index, requirement, self.rules)?;
+        block_row_if_finished(map, self.index, requirement)?;
+        Ok(())
     }
-    Ok(changed)
 }
 
-pub fn fill_tents(map: &mut Map) -> Result<bool> {
-    let mut changed = false;
-    let old_map = map.clone();
-    changed |= handle_rows(map).context("Error while filling tents in rows.")?;
-    changed |=
-        handle_rows(&mut map.transpose()).context("Error while filling tents in columns.")?;
-    assert_eq!(changed, old_map != *map);
-    Ok(changed)
+impl Constraint<Map> for LineConstraint {
+    fn variables(&self) -> Vec<usize> {
+        self.locations(self.index)
+            .map(|loc| self.variable(loc))
+            .collect()
+    }
+
+    fn propagate(&self, map: &mut Map, changed: &mut Vec<usize>) -> Result<()> {
+        // The runs of the line can block tiles in the lines on either side.
+        let touched = (self.index.saturating_sub(1)..=self.index + 1)
+            .flat_map(|line_index| self.locations(line_index))
+            .filter_map(|loc| Some((loc, map.get(loc)?)))
+            .collect::<Vec<_>>();
+        if self.transposed {
+            self.apply(&mut map.transpose())
+        } else {
+            self.apply(map)
+        }?;
+        changed.extend(
+            touched
+                .into_iter()
+                .filter(|&(loc, tile)| map.get(loc) != Some(tile))
+                .map(|(loc, _)| self.variable(loc)),
+        );
+        Ok(())
+    }
+}
+
+fn line_engine(map: &Map, rules: Rules) -> Engine<Map> {
+    let (height, width) = map.dim();
+    let mut engine = Engine::new(height * width);
+    for (transposed, count, length) in [(false, height, width), (true, width, height)] {
+        for index in 0..count {
+            engine.add(LineConstraint {
+                index,
+                transposed,
+                rules,
+                width,
+                length,
+            });
+        }
+    }
+    engine
+}
+
+/// Applies the rules on the number of tents in every row and column until they make no more progress.
+pub fn fill_tents(map: &mut Map, rules: Rules) -> Result<bool> {
+    with_line_engine(map, rules, |map, engine| engine.propagate(map))
 }
 
-pub fn presolve(map: &mut Map) -> Result<()> {
-    let old_map = map.clone();
+/// A line engine, with the size and rules of the maps it was built for.
+type CachedEngine = Option<((usize, usize), Rules, Engine<Map>)>;
+
+/// Buffers kept between solves, so that solving many maps on a thread does not build an engine and a stack for each.
+#[derive(Default)]
+pub(crate) struct Scratch {
+    /// The line engine of the last map solved.
+    engine: CachedEngine,
+    stack: Vec<(Map, GuessIter)>,
+}
+
+/// The line engine for maps of the size of `map`, built unless the cached one fits.
+fn cached_line_engine<'a>(
+    cached: &'a mut CachedEngine,
+    map: &Map,
+    rules: Rules,
+) -> &'a Engine<Map> {
+    if !matches!(cached, Some((dim, cached_rules, _)) if *dim == map.dim() && *cached_rules == rules)
+    {
+        *cached = Some((map.dim(), rules, line_engine(map, rules)));
+    }
+    &cached.as_ref().unwrap().2
+}
+
+thread_local! {
+    /// The line engine for [`fill_tents`] and [`solve_step`], which are called once per step by callers that solve a
+    /// map step by step, so that they do not build an engine for every step.
+    static LINE_ENGINE: RefCell<CachedEngine> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with the line engine of this thread for maps of the size of `map`.
+fn with_line_engine<T>(
+    map: &mut Map,
+    rules: Rules,
+    f: impl FnOnce(&mut Map, &Engine<Map>) -> T,
+) -> T {
+    LINE_ENGINE.with_borrow_mut(|cached| f(map, cached_line_engine(cached, map, rules)))
+}
+
+/// Whether solver steps check that the changes they report are real, which copies the map on every step.
+const CHECK_CHANGES: bool = cfg!(any(debug_assertions, feature = "paranoid"));
+
+pub fn presolve(map: &mut Map, rules: Rules) -> Result<()> {
+    let old_map = CHECK_CHANGES.then(|| map.clone());
     let mut changed = false;
     for loc in Location::grid_iter(map.dim()) {
         if map.get(loc) == Some(Tile::Free)
-            && (map
-                .neighbors(loc)
-                .into_iter()
-                .filter_map(|x| x.map(|(_, tile)| tile))
-                .any(|tile| tile == Tile::Tent)
+            && (rules
+                .tent_neighbors(map, loc)
+                .any(|(_, tile)| tile == Tile::Tent)
                 || !map
                     .adjacents(loc)
                     .into_iter()
@@ -198,59 +315,132 @@ pub fn presolve(map: &mut Map) -> Result<()> {
         }
     }
 
-    map.is_valid()
-        .with_context(|| format!("Invalid_map:\n{map}"))?;
-    if changed {
-        ensure!(*map != old_map, "`changed` is true but old_map == map.")
+    map.is_valid(rules)?;
+    if changed && old_map.as_ref() == Some(&*map) {
+        return Err(Error::FalseChange.into());
     }
     Ok(())
 }
 
-pub fn solve_step(map: &mut Map) -> Result<bool> {
-    let old_map = map.clone();
-    let changed = fill_tents(map).context("Error while filling tents.")?;
+/// Blocks every free tile that cannot hold a tent because of a tent next to it.
+fn block_tent_neighbors(map: &mut Map, rules: Rules) -> bool {
+    let mut changed = false;
+    for loc in Location::grid_iter(map.dim()) {
+        if map.get(loc) == Some(Tile::Tent) {
+            let neighbors = rules.tent_neighbors(map, loc).collect::<Vec<_>>();
+            for (neighbor_loc, _) in neighbors {
+                changed |= map.add_blocked(neighbor_loc).is_ok();
+            }
+        }
+    }
+    changed
+}
+
+pub fn solve_step(map: &mut Map, rules: Rules) -> Result<bool> {
+    with_line_engine(map, rules, |map, engine| step(map, rules, engine))
+}
+
+/// Like [`solve_step`], with the line engine for the map.
+fn step(map: &mut Map, rules: Rules, engine: &Engine<Map>) -> Result<bool> {
+    let old_map = CHECK_CHANGES.then(|| map.clone());
+    let mut changed = block_tent_neighbors(map, rules);
+    // The counts catch lines that can no longer meet their requirements, so wrong guesses fail before the lines
+    // are propagated.
+    map.is_valid(rules)?;
+    changed |= engine.propagate(map)?;
 
-    map.is_valid()
-        .with_context(|| format!("Invalid_map:\n{map}"))?;
-    if changed {
-        ensure!(old_map != *map, "`changed` is true map but old_map == map.")
+    map.is_valid(rules)?;
+    if changed && old_map.as_ref() == Some(&*map) {
+        return Err(Error::FalseChange.into());
     }
     Ok(changed)
 }
 
+/// How the solver picks the next cell to guess once deductions run dry.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GuessOrder {
+    /// Guess a tent and then a blocked tile in the free cell whose row or column has the least slack,
+    /// i.e. the fewest free tiles beyond the number of tents still needed.
+    #[default]
+    MostConstrained,
+    /// Try placing a tent in each free cell in row-major order.
+    Scan,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SolverConfig {
+    pub guess_order: GuessOrder,
+    pub rules: Rules,
+}
+
+fn row_slack(map: &impl MaybeTransposedMap, row_index: usize) -> usize {
+    let free = map
+        .row_tiles(row_index)
+        .filter(|&tile| tile == Tile::Free)
+        .count();
+    let tents = map
+        .row_tiles(row_index)
+        .filter(|&tile| tile == Tile::Tent)
+        .count();
+    let needed = map.row_requirements()[row_index].saturating_sub(tents);
+    free.saturating_sub(needed)
+}
+
+fn free_locations(map: &Map) -> impl Iterator<Item = Location> + '_ {
+    Location::grid_iter(map.dim()).filter(|&loc| map.get(loc) == Some(Tile::Free))
+}
+
 struct GuessIter {
-    location_iter: GridIter,
+    guesses: smallvec::IntoIter<[(Location, bool); 2]>,
 }
 
 impl GuessIter {
-    fn new(map: &Map) -> Self {
+    fn new(map: &mut Map, guess_order: GuessOrder) -> Self {
+        let guesses = match guess_order {
+            GuessOrder::MostConstrained => {
+                let row_slacks = (0..map.height())
+                    .map(|row_index| row_slack(map, row_index))
+                    .collect::<Vec<_>>();
+                let transposed = map.transpose();
+                let col_slacks = (0..transposed.height())
+                    .map(|col_index| row_slack(&transposed, col_index))
+                    .collect::<Vec<_>>();
+                free_locations(map)
+                    .min_by_key(|loc| row_slacks[loc.row].min(col_slacks[loc.col]))
+                    .map(|loc| smallvec![(loc, true), (loc, false)])
+                    .unwrap_or_default()
+            }
+            GuessOrder::Scan => free_locations(map).map(|loc| (loc, true)).collect(),
+        };
         Self {
-            location_iter: Location::grid_iter(map.dim()),
+            guesses: guesses.into_iter(),
         }
     }
 
-    fn next(&mut self, map: &Map) -> Option<(Location, bool)> {
-        for loc in &mut self.location_iter {
-            if map.get(loc) == Some(Tile::Free) {
-                return Some((loc, true));
-            }
-        }
-        None
+    /// The next guess that leaves the row and column of its location within their requirements. The others would
+    /// only fail after a whole step of deductions, so they are skipped without copying the map.
+    fn next_fitting(&mut self, map: &Map) -> Option<(Location, bool)> {
+        self.guesses
+            .find(|&(loc, tile)| map.fits_lines(loc, guess_tile(tile)))
     }
 }
 
-fn next_try(stack: &mut Vec<(Map, GuessIter)>) -> Option<Map> {
+fn apply_guess(map: &Map, loc: Location, tile: bool) -> Map {
+    let mut map = map.clone();
+    if tile {
+        map.add_tent(loc).expect("Expected to add tent.");
+    } else {
+        map.add_blocked(loc).expect("Expected to add blocked.");
+    }
+    map
+}
+
+fn next_try(stack: &mut Vec<(Map, GuessIter)>) -> Option<(Map, Location, bool)> {
     let mut new_map = None;
     while new_map.is_none() {
         if let Some((prev_map, mut guess_iter)) = stack.pop() {
-            if let Some((loc, tile)) = guess_iter.next(&prev_map) {
-                let mut map = prev_map.clone();
-                if tile {
-                    map.add_tent(loc).expect("Expected to add tent.");
-                } else {
-                    map.add_blocked(loc).expect("Expected to add blocked.");
-                }
-                new_map = Some(map);
+            if let Some((loc, tile)) = guess_iter.next_fitting(&prev_map) {
+                new_map = Some((apply_guess(&prev_map, loc, tile), loc, tile));
                 stack.push((prev_map, guess_iter));
             }
         } else {
@@ -260,36 +450,201 @@ fn next_try(stack: &mut Vec<(Map, GuessIter)>) -> Option<Map> {
     Some(new_map.unwrap())
 }
 
+fn guess_tile(tile: bool) -> Tile {
+    if tile {
+        Tile::Tent
+    } else {
+        Tile::Blocked
+    }
+}
+
+/// Backtracks to the next untried guess, recording it in the trace.
+fn backtrack(
+    stack: &mut Vec<(Map, GuessIter)>,
+    cur_map: &Map,
+    tracer: &mut Tracer,
+    stats: &mut SolveStats,
+) -> Option<Map> {
+    let (next_map, location, tile) = next_try(stack)?;
+    stats.clones += 1;
+    tracer.record(
+        StepKind::Backtrack {
+            location,
+            tile: guess_tile(tile),
+        },
+        cur_map,
+        &next_map,
+    );
+    Some(next_map)
+}
+
 pub fn solve(map: &Map) -> Result<Option<Map>> {
+    solve_with(map, &SolverConfig::default())
+}
+
+pub fn solve_with(map: &Map, config: &SolverConfig) -> Result<Option<Map>> {
+    solve_reusing(map, config, &mut Scratch::default())
+}
+
+/// Counts the solutions of the map under the config's rules, stopping once `limit` have been found. Finds a solution
+/// and then looks for another with each of its tents forbidden in turn, since any other solution lacks at least one of
+/// them. So it never counts past 2.
+pub fn count_solutions(map: &Map, config: &SolverConfig, limit: usize) -> Result<usize> {
+    let Some(solution) = solve_with(map, config)? else {
+        return Ok(0);
+    };
+    if limit <= 1 {
+        return Ok(1);
+    }
+    for (location, &tile) in solution.tiles().indexed_iter() {
+        if tile == Tile::Tent && map.tiles().get(location) == Some(&Tile::Free) {
+            let mut forbidden = map.clone();
+            forbidden.add_blocked(location).expect("The tile is free.");
+            // Forbidding a tent can make the map infeasible, which the solver reports as an invalid map.
+            match solve_with(&forbidden, config) {
+                Ok(Some(_)) => return Ok(2),
+                Ok(None) | Err(crate::Error::Camping(Error::InvalidMap(_))) => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+    Ok(1)
+}
+
+/// Like [`solve_with`], but keeps its buffers in `scratch` for the next solve.
+pub(crate) fn solve_reusing(
+    map: &Map,
+    config: &SolverConfig,
+    scratch: &mut Scratch,
+) -> Result<Option<Map>> {
+    solve_inner(
+        map,
+        config,
+        &mut Tracer::disabled(),
+        &CancelToken::new(),
+        &mut SolveStats::default(),
+        scratch,
+    )
+}
+
+/// Like [`solve_with`], but also returns what the solve cost.
+pub fn solve_with_stats(map: &Map, config: &SolverConfig) -> Result<(Option<Map>, SolveStats)> {
+    let mut stats = SolveStats::default();
+    let (solution, allocations) = stats::measure(|| {
+        solve_inner(
+            map,
+            config,
+            &mut Tracer::disabled(),
+            &CancelToken::new(),
+            &mut stats,
+            &mut Scratch::default(),
+        )
+    });
+    Ok((
+        solution?,
+        SolveStats {
+            allocations,
+            ..stats
+        },
+    ))
+}
+
+/// Like [`solve_with`], but checks `cancel` between steps and fails with [`crate::Error::Cancelled`] once it is
+/// cancelled.
+pub fn solve_cancellable(
+    map: &Map,
+    config: &SolverConfig,
+    cancel: &CancelToken,
+) -> Result<Option<Map>> {
+    solve_inner(
+        map,
+        config,
+        &mut Tracer::disabled(),
+        cancel,
+        &mut SolveStats::default(),
+        &mut Scratch::default(),
+    )
+}
+
+/// Solves the map while recording every step taken, for explaining how the solution was found.
+pub fn solve_traced(map: &Map, config: &SolverConfig) -> Result<(Option<Map>, Vec<TraceStep>)> {
+    let mut tracer = Tracer::enabled();
+    let solution = solve_inner(
+        map,
+        config,
+        &mut tracer,
+        &CancelToken::new(),
+        &mut SolveStats::default(),
+        &mut Scratch::default(),
+    )?;
+    Ok((solution, tracer.into_steps()))
+}
+
+fn solve_inner(
+    map: &Map,
+    config: &SolverConfig,
+    tracer: &mut Tracer,
+    cancel: &CancelToken,
+    stats: &mut SolveStats,
+    scratch: &mut Scratch,
+) -> Result<Option<Map>> {
     let mut map = map.clone();
-    presolve(&mut map).context("Error while presolving.")?;
-    let mut stack: Vec<(Map, GuessIter)> = vec![];
+    let before = tracer.snapshot(&map);
+    presolve(&mut map, config.rules)?;
+    tracer.record_changes(StepKind::Presolve, before, &map);
+    let Scratch { engine, stack } = scratch;
+    let engine = cached_line_engine(engine, &map, config.rules);
+    stack.clear();
 
     let mut cur_map = map;
 
     loop {
-        let changed = solve_step(&mut cur_map).context("Error while performing solve step.")?;
-        if cur_map.is_valid().is_err() {
-            cur_map = if let Some(next_map) = next_try(&mut stack) {
+        cancel.check()?;
+        let before = tracer.snapshot(&cur_map);
+        stats.steps += 1;
+        let changed = match step(&mut cur_map, config.rules, engine) {
+            Ok(changed) => changed,
+            // Deducing from a wrong guess runs into a contradiction, which only means that the guess was wrong.
+            Err(crate::Error::Camping(Error::InvalidMap(_) | Error::Placement(_)))
+                if !stack.is_empty() =>
+            {
+                cur_map = if let Some(next_map) = backtrack(stack, &cur_map, tracer, stats) {
+                    next_map
+                } else {
+                    return Ok(None);
+                };
+                continue;
+            }
+            Err(err) => return Err(err),
+        };
+        tracer.record_changes(StepKind::Deduction, before, &cur_map);
+        if cur_map.is_valid(config.rules).is_err() {
+            cur_map = if let Some(next_map) = backtrack(stack, &cur_map, tracer, stats) {
                 next_map
             } else {
                 return Ok(None);
             }
-        } else if cur_map.is_complete() {
+        } else if cur_map.is_complete(config.rules) {
             return Ok(Some(cur_map));
         } else if !changed {
-            let mut guess_iter = GuessIter::new(&cur_map);
-            if let Some((loc, tile)) = guess_iter.next(&cur_map) {
-                let mut map = cur_map.clone();
-                if tile {
-                    map.add_tent(loc).expect("Expected to add tent.");
-                } else {
-                    map.add_blocked(loc).expect("Expected to add blocked.");
-                }
+            let mut guess_iter = GuessIter::new(&mut cur_map, config.guess_order);
+            if let Some((loc, tile)) = guess_iter.next_fitting(&cur_map) {
+                let map = apply_guess(&cur_map, loc, tile);
+                stats.guesses += 1;
+                stats.clones += 1;
+                tracer.record(
+                    StepKind::Guess {
+                        location: loc,
+                        tile: guess_tile(tile),
+                    },
+                    &cur_map,
+                    &map,
+                );
                 stack.push((cur_map, guess_iter));
+                stats.reach_depth(stack.len());
                 cur_map = map;
             } else {
-                cur_map = if let Some(next_map) = next_try(&mut stack) {
+                cur_map = if let Some(next_map) = backtrack(stack, &cur_map, tracer, stats) {
                     next_map
                 } else {
                     return Ok(None);
@@ -298,3 +653,68 @@ pub fn solve(map: &Map) -> Result<Option<Map>> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{camping::Camping, puzzle::Puzzle};
+
+    /// The maps in the data directory, by name.
+    fn data_maps() -> Vec<(String, Map)> {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/data/camping/maps");
+        let mut maps = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| {
+                let path = entry.unwrap().path();
+                let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+                (name, Map::from_file(&path).unwrap())
+            })
+            .collect::<Vec<_>>();
+        maps.sort_by(|(name1, _), (name2, _)| name1.cmp(name2));
+        maps
+    }
+
+    #[test]
+    fn solves_json() {
+        let map = Map::parse(include_str!("../../data/camping/maps/map01.txt")).unwrap();
+        let solved = crate::camping::solve_json(&serde_json::to_string(&map).unwrap()).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Map>(&solved).unwrap(),
+            solve(&map).unwrap().unwrap()
+        );
+        assert!(crate::camping::solve_json("{}").is_err());
+    }
+
+    #[test]
+    fn guess_orders_find_the_same_solution() {
+        let config = |guess_order| SolverConfig {
+            guess_order,
+            ..SolverConfig::default()
+        };
+        let mut ambiguous = vec![];
+        let mut guessed = false;
+        for (name, map) in data_maps() {
+            let (scan, scan_stats) = solve_with_stats(&map, &config(GuessOrder::Scan)).unwrap();
+            let (constrained, constrained_stats) =
+                solve_with_stats(&map, &config(GuessOrder::MostConstrained)).unwrap();
+            let solution = scan.unwrap_or_else(|| panic!("{name} has no solution"));
+            assert!(solution.is_complete(Rules::default()), "{name}");
+            assert_eq!(constrained.as_ref(), Some(&solution), "{name}");
+            assert!(
+                constrained_stats.guesses <= scan_stats.guesses,
+                "{name}: {} guesses with the most constrained order, {} scanning",
+                constrained_stats.guesses,
+                scan_stats.guesses
+            );
+            guessed |= constrained_stats.guesses > 0;
+            if Camping::count_solutions(&map, 2).unwrap() != 1 {
+                ambiguous.push(name);
+            }
+        }
+        // Some maps need a guess, so that the orders are compared at all.
+        assert!(guessed);
+        // The rules only ask for a tree next to each tent, not a tree of its own, so these maps also have solutions
+        // where two tents share a tree. Both orders still pick the same solution.
+        assert_eq!(ambiguous, ["map03", "map04", "map05", "map10", "map21"]);
+    }
+}
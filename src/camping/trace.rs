@@ -0,0 +1,200 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::location::Location;
+
+use super::{map::MaybeTransposedMap, Map, Tile};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StepKind {
+    /// Blocking tiles that can never hold a tent before solving starts.
+    Presolve,
+    /// A pass of the row and column deduction rules.
+    Deduction,
+    /// A guess made when no deductions were possible.
+    Guess { location: Location, tile: Tile },
+    /// Undoing guesses that led to a contradiction and trying the next alternative.
+    Backtrack { location: Location, tile: Tile },
+}
+
+impl Display for StepKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            StepKind::Presolve => write!(f, "Presolve"),
+            StepKind::Deduction => write!(f, "Deduction"),
+            StepKind::Guess { location, tile } => write!(f, "Guess {tile:?} at {location}"),
+            StepKind::Backtrack { location, tile } => {
+                write!(f, "Backtrack and try {tile:?} at {location}")
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TraceStep {
+    pub kind: StepKind,
+    pub before: Map,
+    pub after: Map,
+}
+
+impl TraceStep {
+    pub fn changed_locations(&self) -> Vec<Location> {
+        Location::grid_iter(self.after.dim())
+            .filter(|&loc| self.before.get(loc) != self.after.get(loc))
+            .collect()
+    }
+}
+
+/// Prints the map before and after the step side by side.
+impl Display for TraceStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write(f, false)
+    }
+}
+
+/// A [`TraceStep`] printed with its changed tiles highlighted by ANSI escapes, for terminals.
+pub struct Highlighted<'a>(&'a TraceStep);
+
+impl Display for Highlighted<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.write(f, true)
+    }
+}
+
+impl TraceStep {
+    /// Prints like the step itself, but with the changed tiles highlighted.
+    pub fn highlighted(&self) -> Highlighted<'_> {
+        Highlighted(self)
+    }
+
+    fn write(&self, f: &mut Formatter<'_>, highlight: bool) -> fmt::Result {
+        const HIGHLIGHT: &str = "\x1b[7m";
+        const RESET: &str = "\x1b[0m";
+
+        let changed = self.changed_locations();
+        writeln!(f, "{} ({} tiles changed)", self.kind, changed.len())?;
+        let (height, width) = self.after.dim();
+        for row in 0..height {
+            for map in [&self.before, &self.after] {
+                write!(f, "|")?;
+                for col in 0..width {
+                    let loc = Location::new(row, col);
+                    let c = map.get(loc).unwrap().to_char();
+                    if highlight && std::ptr::eq(map, &self.after) && changed.contains(&loc) {
+                        write!(f, "{HIGHLIGHT}{c}{RESET}")?;
+                    } else {
+                        write!(f, "{c}")?;
+                    }
+                }
+                write!(f, "| {:<2}", map.row_requirements()[row])?;
+                if std::ptr::eq(map, &self.before) {
+                    write!(f, " -> ")?;
+                }
+            }
+            writeln!(f)?;
+        }
+        for map in [&self.before, &self.after] {
+            write!(f, " ")?;
+            for &requirement in map.col_requirements() {
+                write!(f, "{}", requirement % 10)?;
+            }
+            write!(f, "{:7}", "")?;
+        }
+        writeln!(f)
+    }
+}
+
+/// Collects trace steps while solving, doing nothing when disabled so the untraced solver pays no cost.
+pub(super) struct Tracer {
+    steps: Option<Vec<TraceStep>>,
+}
+
+impl Tracer {
+    pub fn enabled() -> Self {
+        Self {
+            steps: Some(vec![]),
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self { steps: None }
+    }
+
+    /// Clone of the map if tracing is enabled, for later use with `record_changes`.
+    pub fn snapshot(&self, map: &Map) -> Option<Map> {
+        self.steps.as_ref().map(|_| map.clone())
+    }
+
+    pub fn record(&mut self, kind: StepKind, before: &Map, after: &Map) {
+        if let Some(steps) = self.steps.as_mut() {
+            steps.push(TraceStep {
+                kind,
+                before: before.clone(),
+                after: after.clone(),
+            });
+        }
+    }
+
+    /// Records the step only if it changed the map.
+    pub fn record_changes(&mut self, kind: StepKind, before: Option<Map>, after: &Map) {
+        if let (Some(steps), Some(before)) = (self.steps.as_mut(), before) {
+            if before != *after {
+                steps.push(TraceStep {
+                    kind,
+                    before,
+                    after: after.clone(),
+                });
+            }
+        }
+    }
+
+    pub fn into_steps(self) -> Vec<TraceStep> {
+        self.steps.unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::camping::{solve, solve_traced, SolverConfig};
+
+    use super::*;
+
+    #[test]
+    fn traces_a_small_map() {
+        let map = Map::parse("2,3\n1,0\n0,1,0\nT  \n   \n").unwrap();
+        let (solution, steps) = solve_traced(&map, &SolverConfig::default()).unwrap();
+        let solution = solution.unwrap();
+        assert_eq!(solve(&map).unwrap().as_ref(), Some(&solution));
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].kind, StepKind::Presolve);
+        assert_eq!(steps[0].before, map);
+        assert_eq!(steps[1].kind, StepKind::Deduction);
+        assert_eq!(steps[1].after, solution);
+        assert_eq!(
+            steps[1].changed_locations(),
+            [Location::new(0, 1), Location::new(1, 0)]
+        );
+        assert_eq!(
+            steps[1].to_string(),
+            "Deduction (2 tiles changed)\n\
+             |T #| 1  -> |TX#| 1 \n\
+             | ##| 0  -> |###| 0 \n \
+             010        010       \n"
+        );
+        let highlighted = steps[1].highlighted().to_string();
+        assert!(
+            highlighted.contains("|T\x1b[7mX\x1b[0m#| 1"),
+            "{highlighted}"
+        );
+        assert!(!steps[1].to_string().contains('\x1b'));
+    }
+
+    #[test]
+    fn traces_guesses_to_the_solution() {
+        let map = Map::parse(include_str!("../../data/camping/maps/map03.txt")).unwrap();
+        let (solution, steps) = solve_traced(&map, &SolverConfig::default()).unwrap();
+        assert!(steps
+            .iter()
+            .any(|step| matches!(step.kind, StepKind::Guess { .. })));
+        assert_eq!(steps.last().map(|step| &step.after), solution.as_ref());
+    }
+}
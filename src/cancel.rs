@@ -0,0 +1,60 @@
+//! Cooperative cancellation of long running solvers. The solver checks a shared token between steps and gives up with
+//! [`Error::Cancelled`] once it is cancelled from elsewhere, such as when a request times out.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use crate::error::{Error, Result};
+
+/// A flag shared between a solver and whoever may cancel it. Clones share the flag.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Fails with [`Error::Cancelled`] if the token has been cancelled.
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            Err(Error::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{camping, sudoku};
+
+    #[test]
+    fn cancelled_solvers_give_up() {
+        let cancel = CancelToken::new();
+        let clone = cancel.clone();
+        let board = sudoku::Board::from_line(&".".repeat(81), '.').unwrap();
+        assert!(sudoku::solve_cancellable(&board, &cancel).is_ok());
+        clone.cancel();
+        assert!(matches!(
+            sudoku::solve_cancellable(&board, &cancel),
+            Err(Error::Cancelled)
+        ));
+        let map = camping::Map::parse(include_str!("../data/camping/maps/map01.txt")).unwrap();
+        assert!(matches!(
+            camping::solve_cancellable(&map, &camping::SolverConfig::default(), &cancel),
+            Err(Error::Cancelled)
+        ));
+    }
+}
@@ -0,0 +1,153 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+    puzzlink::{self, Clue, PuzzLink},
+};
+
+/// A cave puzzle: shade cells such that the unshaded cells form a single orthogonally connected cave and every
+/// group of shaded cells reaches the edge of the grid. Numbered cells are in the cave, and every number sees exactly
+/// that many cave cells along its row and column, counting itself and stopping at shaded cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    numbers: Grid<Option<u32>>,
+}
+
+impl Board {
+    pub fn new(numbers: Grid<Option<u32>>) -> Result<Self> {
+        let (height, width) = numbers.dim();
+        ensure!(
+            height > 0 && width > 0,
+            Invalid,
+            "Cave grids must have at least one cell."
+        );
+        let max = (height + width - 1) as u32;
+        if let Some(number) = numbers
+            .iter()
+            .flatten()
+            .find(|&&number| number == 0 || number > max)
+        {
+            bail!(
+                Invalid,
+                "Numbers must be between 1 and {max}, but got {number}."
+            );
+        }
+        Ok(Self { numbers })
+    }
+
+    /// Parses a grid of numbers with `.` for empty cells. Rows are either strings of digits and dots or,
+    /// for numbers above 9, whitespace separated numbers and dots.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let parse_token = |token: &str| -> Result<Option<u32>> {
+            match token {
+                "." => Ok(None),
+                _ => token
+                    .parse()
+                    .map(Some)
+                    .map_err(|_| Error::Parse(format!("Invalid cell '{token}'."))),
+            }
+        };
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                if line.contains(char::is_whitespace) {
+                    line.split_whitespace().map(parse_token).collect()
+                } else {
+                    line.chars()
+                        .map(|c| parse_token(c.encode_utf8(&mut [0; 4])))
+                        .collect::<Result<Vec<_>>>()
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        let mut numbers = Grid::from_elem((height, width), None);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} cells in row {row_index}. Got {}.",
+                    row.len()
+                );
+            }
+            for (col_index, number) in row.into_iter().enumerate() {
+                numbers[Location::new(row_index, col_index)] = number;
+            }
+        }
+        Self::new(numbers)
+    }
+
+    /// Imports a puzzle from a puzz.link or pzv.jp URL.
+    pub fn from_puzz_link(url: &str) -> Result<Self> {
+        let link = PuzzLink::parse(url)?;
+        link.expect_kind(&["cave", "bag"])?;
+        let clues = puzzlink::decode_number16(&link.body, link.width * link.height)?;
+        let mut numbers = Grid::from_elem((link.height, link.width), None);
+        for (index, clue) in clues.into_iter().enumerate() {
+            numbers[Location::new(index / link.width, index % link.width)] = match clue {
+                None => None,
+                Some(Clue::Number(number)) => Some(number),
+                Some(Clue::Unknown) => bail!(Parse, "Cells without a number are not supported."),
+            };
+        }
+        Self::new(numbers)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("cave", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.numbers.dim()
+    }
+
+    pub fn number(&self, location: Location) -> Option<u32> {
+        self.numbers[location]
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let wide = self.numbers.iter().flatten().any(|&number| number > 9);
+        for row in self.numbers.rows() {
+            for (index, number) in row.enumerate() {
+                if wide && index > 0 {
+                    write!(f, " ")?;
+                }
+                match number {
+                    Some(number) => write!(f, "{number}")?,
+                    None => write!(f, ".")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_text_and_puzz_links() {
+        let board = Board::parse(include_str!("../../data/cave/puzzles/eight01.txt")).unwrap();
+        assert_eq!(board.dim(), (8, 8));
+        assert_eq!(board.number(Location::new(3, 5)), Some(10));
+        assert_eq!(board.number(Location::new(0, 0)), None);
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        let link = "https://puzz.link/p?cave/8/8/z9j8iah7jbjah97jcj7jah";
+        assert_eq!(Board::from_puzz_link(link).unwrap(), board);
+        assert!(Board::from_puzz_link("https://puzz.link/p?cave/2/1/.g").is_err());
+        assert!(Board::parse("3.\n..\n").is_ok());
+        assert!(Board::parse("4.\n..\n").is_err());
+        assert!(Board::parse("1.\n.\n").is_err());
+    }
+}
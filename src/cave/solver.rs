@@ -0,0 +1,149 @@
+use crate::{
+    grid::Grid,
+    location::Location,
+    search::{self, Contradiction, SearchState},
+    shading::{Shade, ShadeGrid},
+};
+
+use super::Board;
+
+/// The cave is unshaded and the walls are shaded.
+#[derive(Clone, Debug)]
+struct State<'a> {
+    board: &'a Board,
+    grid: ShadeGrid,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board) -> Self {
+        let mut grid = ShadeGrid::new(board.dim());
+        for loc in Location::grid_iter(board.dim()) {
+            if board.number(loc).is_some() {
+                grid.set(loc, Shade::Unshaded)
+                    .expect("Only numbered cells have been set.");
+            }
+        }
+        Self { board, grid }
+    }
+
+    /// Every number must see exactly that many cave cells.
+    fn apply_numbers(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for loc in Location::grid_iter(self.grid.dim()) {
+            if let Some(number) = self.board.number(loc) {
+                // The number counts its own cell.
+                changed |= self.grid.apply_sight(loc, number as usize - 1)?;
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Every wall must reach the edge of the grid through cells outside the cave,
+    /// so undecided cells that cannot reach it are part of the cave.
+    fn apply_edge(&mut self) -> Result<bool, Contradiction> {
+        let dim = self.grid.dim();
+        let (height, width) = dim;
+        let mut reached = Grid::from_elem(dim, false);
+        let mut stack = vec![];
+        for loc in Location::grid_iter(dim) {
+            let on_edge =
+                loc.row == 0 || loc.col == 0 || loc.row + 1 == height || loc.col + 1 == width;
+            if on_edge && self.grid.get(loc) != Shade::Unshaded {
+                reached[loc] = true;
+                stack.push(loc);
+            }
+        }
+        while let Some(loc) = stack.pop() {
+            for adj in loc.adjacents(dim).into_iter().flatten() {
+                if !reached[adj] && self.grid.get(adj) != Shade::Unshaded {
+                    reached[adj] = true;
+                    stack.push(adj);
+                }
+            }
+        }
+        let mut changed = false;
+        for loc in Location::grid_iter(dim) {
+            if !reached[loc] {
+                changed |= self.grid.set(loc, Shade::Unshaded)?;
+            }
+        }
+        Ok(changed)
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.apply_numbers()?;
+            changed |= self.apply_edge()?;
+            changed |= self.grid.keep_connected(Shade::Unshaded)?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(location) = self.grid.first_unknown() else {
+            return vec![];
+        };
+        self.grid
+            .branch_on(location)
+            .into_iter()
+            .map(|grid| Self {
+                grid,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+/// Returns the solved grid with walls as `#` and the cave as `.`, or `None` if the puzzle has no solution.
+pub fn solve(board: &Board) -> Option<ShadeGrid> {
+    search::solve(State::new(board)).map(|state| state.grid)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    search::count_solutions(State::new(board), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{location::Direction, region};
+
+    fn follows_rules(board: &Board, grid: &ShadeGrid) -> bool {
+        let (height, width) = board.dim();
+        let dim = (height, width);
+        let numbers = Location::grid_iter(dim).all(|loc| {
+            board.number(loc).is_none_or(|number| {
+                let seen = Direction::ALL
+                    .into_iter()
+                    .map(|direction| {
+                        loc.ray(direction, dim)
+                            .take_while(|&other| !grid.is_shaded(other))
+                            .count()
+                    })
+                    .sum::<usize>();
+                !grid.is_shaded(loc) && seen + 1 == number as usize
+            })
+        });
+        let shaded = |loc| grid.is_shaded(loc);
+        let walls_reach_edge = region::components(dim, shaded).regions.iter().all(|wall| {
+            wall.iter().any(|loc| {
+                loc.row == 0 || loc.col == 0 || loc.row + 1 == height || loc.col + 1 == width
+            })
+        });
+        let cave = |loc| !grid.is_shaded(loc);
+        numbers && walls_reach_edge && region::is_connected(dim, cave, cave)
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/cave/puzzles/eight01.txt")).unwrap();
+        let grid = solve(&board).unwrap();
+        assert!(grid.is_complete());
+        assert!(follows_rules(&board, &grid));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
@@ -0,0 +1,308 @@
+//! Collections of puzzles of mixed kinds, described by a manifest in TOML or JSON. With the `zip` feature, the manifest
+//! can also be stored in a zip archive, as `manifest.toml`.
+//!
+//! A TOML manifest looks like
+//!
+//! ```toml
+//! name = "Weekend"
+//!
+//! [[puzzles]]
+//! id = "monday"
+//! kind = "cryptarithm"
+//! puzzle = "SEND+MORE=MONEY"
+//! difficulty = "easy"
+//! source = "Dudeney, 1924"
+//!
+//! [[packs]]
+//! name = "beginners"
+//! puzzles = ["monday"]
+//! ```
+//!
+//! Packs list puzzles of the collection by id, in the order to play them, and a player's progress through a pack is
+//! kept as a [`Progress`].
+
+use std::{collections::BTreeSet, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    difficulty::Difficulty,
+    error::{bail, read_to_string, write, Error, Result},
+};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Collection {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub puzzles: Vec<Entry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packs: Vec<Pack>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub id: String,
+    /// The kind of puzzle, by the name [`crate::puzzle::visit`] knows it by, such as `sudoku` or `ripple-effect`.
+    pub kind: String,
+    /// The puzzle in the text format of its kind.
+    pub puzzle: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<Difficulty>,
+    /// Where the puzzle comes from, such as a URL or a book.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+/// Puzzles of a collection to play in order, such as a campaign for beginners that moves between games.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pack {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The ids of the puzzles, in order.
+    pub puzzles: Vec<String>,
+}
+
+/// The puzzles of a pack that a player has completed, by id.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Progress {
+    pub completed: BTreeSet<String>,
+}
+
+impl Progress {
+    /// Reads the progress, starting afresh if the file does not exist yet.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_str(&read_to_string(
+            "pack progress",
+            path,
+        )?)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        write(
+            "pack progress",
+            path.as_ref(),
+            serde_json::to_string_pretty(self)?,
+        )
+    }
+
+    /// The first of the puzzles that has not been completed.
+    pub fn next<'a>(&self, puzzles: &[&'a Entry]) -> Option<&'a Entry> {
+        puzzles
+            .iter()
+            .copied()
+            .find(|entry| !self.completed.contains(&entry.id))
+    }
+}
+
+/// The name of the manifest inside zip archives.
+#[cfg(feature = "zip")]
+const MANIFEST: &str = "manifest.toml";
+
+enum Format {
+    Toml,
+    Json,
+    Zip,
+}
+
+impl Format {
+    fn of(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Ok(Format::Toml),
+            Some("json") => Ok(Format::Json),
+            Some("zip") => Ok(Format::Zip),
+            _ => bail!(
+                Parse,
+                "Collection {path:?} must end in .toml, .json or .zip."
+            ),
+        }
+    }
+}
+
+impl Collection {
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        toml::from_str(toml).map_err(|err| Error::Parse(err.to_string()))
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self)
+            .expect("Collections only hold strings, which TOML can always represent.")
+    }
+
+    /// The puzzles of the pack with the name, in order. Fails if there is no such pack, or if it lists a puzzle that
+    /// is not in the collection.
+    pub fn pack(&self, name: &str) -> Result<Vec<&Entry>> {
+        let Some(pack) = self.packs.iter().find(|pack| pack.name == name) else {
+            let names = self
+                .packs
+                .iter()
+                .map(|pack| pack.name.as_str())
+                .collect::<Vec<_>>();
+            bail!(
+                Parse,
+                "No pack named '{name}'. Expected one of {}.",
+                names.join(", ")
+            );
+        };
+        pack.puzzles
+            .iter()
+            .map(
+                |id| match self.puzzles.iter().find(|entry| entry.id == *id) {
+                    Some(entry) => Ok(entry),
+                    None => bail!(
+                        Parse,
+                        "Pack '{name}' lists puzzle '{id}', which is not in the collection."
+                    ),
+                },
+            )
+            .collect()
+    }
+}
+
+/// Reads a collection, in the format given by the extension of the path.
+pub fn load(path: impl AsRef<Path>) -> Result<Collection> {
+    let path = path.as_ref();
+    match Format::of(path)? {
+        Format::Toml => Collection::from_toml(&read_to_string("collection", path)?),
+        Format::Json => Ok(serde_json::from_str(&read_to_string("collection", path)?)?),
+        Format::Zip => load_zip(path),
+    }
+}
+
+/// Writes a collection, in the format given by the extension of the path.
+pub fn save(collection: &Collection, path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+    match Format::of(path)? {
+        Format::Toml => write("collection", path, collection.to_toml()),
+        Format::Json => write(
+            "collection",
+            path,
+            serde_json::to_string_pretty(collection)?,
+        ),
+        Format::Zip => save_zip(collection, path),
+    }
+}
+
+#[cfg(feature = "zip")]
+fn load_zip(path: &Path) -> Result<Collection> {
+    use std::{fs::File, io::Read};
+
+    let read_error = |source| Error::Read {
+        puzzle: "collection",
+        path: path.to_owned(),
+        source,
+    };
+    let file = File::open(path).map_err(read_error)?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|err| Error::Parse(format!("Invalid zip archive {path:?}: {err}")))?;
+    let mut manifest = archive
+        .by_name(MANIFEST)
+        .map_err(|err| Error::Parse(format!("Missing {MANIFEST} in {path:?}: {err}")))?;
+    let mut toml = String::new();
+    manifest.read_to_string(&mut toml).map_err(read_error)?;
+    Collection::from_toml(&toml)
+}
+
+#[cfg(feature = "zip")]
+fn save_zip(collection: &Collection, path: &Path) -> Result<()> {
+    use std::{fs::File, io::Write};
+
+    let write_error = |source| Error::Write {
+        puzzle: "collection",
+        path: path.to_owned(),
+        source,
+    };
+    let mut archive = zip::ZipWriter::new(File::create(path).map_err(write_error)?);
+    archive
+        .start_file(MANIFEST, zip::write::SimpleFileOptions::default())
+        .map_err(|err| write_error(err.into()))?;
+    archive
+        .write_all(collection.to_toml().as_bytes())
+        .map_err(write_error)?;
+    archive.finish().map_err(|err| write_error(err.into()))?;
+    Ok(())
+}
+
+#[cfg(not(feature = "zip"))]
+fn load_zip(_path: &Path) -> Result<Collection> {
+    Err(zip_unsupported("Reading zip archives"))
+}
+
+#[cfg(not(feature = "zip"))]
+fn save_zip(_collection: &Collection, _path: &Path) -> Result<()> {
+    Err(zip_unsupported("Writing zip archives"))
+}
+
+/// Zip archives need the `zip` feature.
+#[cfg(not(feature = "zip"))]
+fn zip_unsupported(action: &'static str) -> Error {
+    Error::Unsupported {
+        puzzle: "collection",
+        action,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        let toml = r#"
+            name = "Mixed"
+
+            [[puzzles]]
+            id = "money"
+            kind = "cryptarithm"
+            puzzle = "SEND+MORE=MONEY"
+            source = "Dudeney"
+
+            [[puzzles]]
+            id = "first"
+            kind = "sudoku"
+            puzzle = "..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3.."
+            difficulty = "easy"
+
+            [[packs]]
+            name = "beginners"
+            puzzles = ["first", "money"]
+        "#;
+        let collection = Collection::from_toml(toml).unwrap();
+        assert_eq!(collection.puzzles.len(), 2);
+        assert_eq!(collection.puzzles[1].difficulty, Some(Difficulty::Easy));
+        let pack = collection.pack("beginners").unwrap();
+        assert_eq!(
+            pack.iter()
+                .map(|entry| entry.id.as_str())
+                .collect::<Vec<_>>(),
+            ["first", "money"]
+        );
+        assert!(collection.pack("experts").is_err());
+        let mut progress = Progress::default();
+        assert_eq!(progress.next(&pack).unwrap().id, "first");
+        progress.completed.insert("first".to_string());
+        assert_eq!(progress.next(&pack).unwrap().id, "money");
+        assert_eq!(
+            Collection::from_toml(&collection.to_toml()).unwrap(),
+            collection
+        );
+
+        let dir = std::env::temp_dir();
+        let mut extensions = vec!["toml", "json"];
+        if cfg!(feature = "zip") {
+            extensions.push("zip");
+        }
+        for extension in extensions {
+            let path = dir.join(format!("puzzles-collection-test.{extension}"));
+            save(&collection, &path).unwrap();
+            assert_eq!(load(&path).unwrap(), collection);
+            std::fs::remove_file(path).unwrap();
+        }
+        assert!(matches!(load("collection.txt"), Err(Error::Parse(_))));
+    }
+}
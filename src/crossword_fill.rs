@@ -0,0 +1,58 @@
+mod board;
+pub use board::{Board, Cell, Slot};
+mod solver;
+pub use solver::{count_solutions, solve, Solution};
+
+/// A grid together with the words to fill it with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Crossword {
+    pub board: Board,
+    pub words: Vec<String>,
+}
+
+/// The grid, an empty line and then the words, one per line.
+impl std::fmt::Display for Crossword {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.board)?;
+        for word in self.words.iter() {
+            writeln!(f, "{word}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Crosswords as a [`Puzzle`](crate::puzzle::Puzzle), read as a grid in the text format of [`Board::parse`], an
+/// empty line and then the words, one per line.
+pub struct CrosswordFill;
+
+impl crate::puzzle::Puzzle for CrosswordFill {
+    const NAME: &'static str = "crossword";
+
+    type Instance = Crossword;
+    type Solution = Solution;
+
+    fn parse(input: &str) -> crate::Result<Crossword> {
+        let input = input.trim_start();
+        let (grid, words) = input
+            .split_once("\n\n")
+            .or_else(|| input.split_once("\r\n\r\n"))
+            .unwrap_or((input, ""));
+        Ok(Crossword {
+            board: Board::parse(grid)?,
+            words: words
+                .lines()
+                .map(str::trim)
+                .filter(|word| !word.is_empty())
+                .map(str::to_string)
+                .collect(),
+        })
+    }
+
+    fn solve(crossword: &Crossword) -> crate::Result<Option<Solution>> {
+        Ok(solve(&crossword.board, &crossword.words))
+    }
+
+    fn count_solutions(crossword: &Crossword, limit: usize) -> crate::Result<usize> {
+        Ok(count_solutions(&crossword.board, &crossword.words, limit))
+    }
+}
@@ -0,0 +1,146 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Result},
+    grid::Grid,
+    location::{Direction, Location},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cell {
+    Block,
+    Empty,
+    /// A letter that is given, always uppercase.
+    Letter(char),
+}
+
+/// A line of at least two open cells between blocks or edges, to be filled with a word.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Slot {
+    /// Either [`Direction::Right`] for across or [`Direction::Down`] for down.
+    pub direction: Direction,
+    pub cells: Vec<Location>,
+}
+
+/// A crossword grid to fill with words from a word list, such that every slot is a different word.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    cells: Grid<Cell>,
+    slots: Vec<Slot>,
+}
+
+impl Board {
+    pub fn new(cells: Grid<Cell>) -> Result<Self> {
+        let dim = cells.dim();
+        ensure!(
+            dim.0 > 0 && dim.1 > 0,
+            Invalid,
+            "Crossword grids cannot be empty."
+        );
+        for loc in Location::grid_iter(dim) {
+            if let Cell::Letter(letter) = cells[loc] {
+                ensure!(
+                    letter.is_ascii_uppercase(), Invalid,
+                    "The letter at {loc} must be an uppercase letter from A to Z, but is '{letter}'."
+                );
+            }
+        }
+        let mut slots = vec![];
+        for direction in [Direction::Right, Direction::Down] {
+            for start in Location::grid_iter(dim) {
+                let open = |loc: Location| cells[loc] != Cell::Block;
+                let before = start.step(direction.opposite(), dim);
+                if !open(start) || before.is_some_and(open) {
+                    continue;
+                }
+                let slot = std::iter::once(start)
+                    .chain(start.ray(direction, dim))
+                    .take_while(|&loc| open(loc))
+                    .collect::<Vec<_>>();
+                if slot.len() >= 2 {
+                    slots.push(Slot {
+                        direction,
+                        cells: slot,
+                    });
+                }
+            }
+        }
+        for loc in Location::grid_iter(dim) {
+            ensure!(
+                cells[loc] == Cell::Block || slots.iter().any(|slot| slot.cells.contains(&loc)),
+                Invalid,
+                "The open cell at {loc} is not part of any word."
+            );
+        }
+        Ok(Self { cells, slots })
+    }
+
+    /// Parses a grid of `#` for blocks, `.` for empty cells and letters for given letters.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        let mut cells = Grid::from_elem((height, width), Cell::Empty);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.chars().count() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} characters in row {row_index}. Got '{row}'."
+                );
+            }
+            for (col_index, c) in row.chars().enumerate() {
+                cells[Location::new(row_index, col_index)] = match c {
+                    '#' => Cell::Block,
+                    '.' => Cell::Empty,
+                    c if c.is_ascii_alphabetic() => Cell::Letter(c.to_ascii_uppercase()),
+                    _ => bail!(
+                        Parse,
+                        "Invalid character '{c}' at ({row_index}, {col_index})."
+                    ),
+                };
+            }
+        }
+        Self::new(cells)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("crossword", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.cells.dim()
+    }
+
+    pub fn cell(&self, location: Location) -> Cell {
+        self.cells[location]
+    }
+
+    /// Every across slot in reading order, followed by every down slot in reading order.
+    pub fn slots(&self) -> &[Slot] {
+        &self.slots
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.cells.rows() {
+            for cell in row {
+                let c = match cell {
+                    Cell::Block => '#',
+                    Cell::Empty => '.',
+                    Cell::Letter(letter) => *letter,
+                };
+                write!(f, "{c}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,292 @@
+use std::{collections::HashMap, fmt::Display};
+
+use crate::{
+    grid::Grid,
+    location::Location,
+    search::{self, Contradiction, SearchState},
+};
+
+use super::{Board, Cell};
+
+/// The bit of a letter from `A` to `Z` in a set of letters.
+fn letter_bit(letter: u8) -> u32 {
+    1 << (letter - b'A')
+}
+
+const ALL_LETTERS: u32 = (1 << 26) - 1;
+
+/// The words of the word list, uppercase and without duplicates, grouped by length.
+struct Dictionary {
+    by_length: HashMap<usize, Vec<Vec<u8>>>,
+}
+
+impl Dictionary {
+    /// Skips words with characters other than the letters from `A` to `Z`, ignoring case.
+    fn new(words: &[String]) -> Self {
+        let mut by_length: HashMap<usize, Vec<Vec<u8>>> = HashMap::new();
+        for word in words {
+            let word = word.trim().to_ascii_uppercase().into_bytes();
+            if word.is_empty() || !word.iter().all(u8::is_ascii_uppercase) {
+                continue;
+            }
+            let words = by_length.entry(word.len()).or_default();
+            if !words.contains(&word) {
+                words.push(word);
+            }
+        }
+        Self { by_length }
+    }
+
+    fn words(&self, length: usize) -> &[Vec<u8>] {
+        self.by_length.get(&length).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// The candidate words of every slot, as indices into the words of the slot's length, and the letters still
+/// possible in every cell.
+#[derive(Clone)]
+struct State<'a> {
+    board: &'a Board,
+    dictionary: &'a Dictionary,
+    domains: Vec<Vec<usize>>,
+    letters: Grid<u32>,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board, dictionary: &'a Dictionary) -> Self {
+        let domains = board
+            .slots()
+            .iter()
+            .map(|slot| (0..dictionary.words(slot.cells.len()).len()).collect())
+            .collect();
+        let letters = Grid::from_fn(board.dim(), |loc| match board.cell(loc) {
+            Cell::Block => 0,
+            Cell::Empty => ALL_LETTERS,
+            Cell::Letter(letter) => letter_bit(letter as u8),
+        });
+        Self {
+            board,
+            dictionary,
+            domains,
+            letters,
+        }
+    }
+
+    fn words(&self, slot: usize) -> &'a [Vec<u8>] {
+        self.dictionary.words(self.board.slots()[slot].cells.len())
+    }
+
+    /// Removes the candidates of every slot that do not fit the letters of its cells, and then restricts every
+    /// cell to the letters of the remaining candidates. Since every cell is shared by at most two slots, this makes
+    /// every pair of crossing slots arc consistent once nothing changes.
+    fn apply_slots(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for (index, slot) in self.board.slots().iter().enumerate() {
+            let words = self.words(index);
+            let masks = slot
+                .cells
+                .iter()
+                .map(|loc| self.letters[*loc])
+                .collect::<Vec<_>>();
+            let domain = &mut self.domains[index];
+            let before = domain.len();
+            domain.retain(|&word| {
+                words[word]
+                    .iter()
+                    .zip(masks.iter())
+                    .all(|(&letter, &mask)| mask & letter_bit(letter) != 0)
+            });
+            if domain.is_empty() {
+                return Err(Contradiction);
+            }
+            changed |= domain.len() != before;
+            for (position, loc) in slot.cells.iter().enumerate() {
+                let possible = domain
+                    .iter()
+                    .fold(0, |mask, &word| mask | letter_bit(words[word][position]));
+                let cell = &mut self.letters[*loc];
+                if *cell & possible != *cell {
+                    *cell &= possible;
+                    changed = true;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Removes the word of every decided slot from the candidates of the other slots of the same length.
+    fn apply_distinct(&mut self) -> Result<bool, Contradiction> {
+        let slots = self.board.slots();
+        let mut changed = false;
+        for index in 0..slots.len() {
+            let &[word] = self.domains[index].as_slice() else {
+                continue;
+            };
+            let length = slots[index].cells.len();
+            for (other, other_slot) in slots.iter().enumerate() {
+                if other == index || other_slot.cells.len() != length {
+                    continue;
+                }
+                let domain = &mut self.domains[other];
+                if let Some(position) = domain.iter().position(|&other| other == word) {
+                    domain.remove(position);
+                    if domain.is_empty() {
+                        return Err(Contradiction);
+                    }
+                    changed = true;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// How well `word` keeps the crossing slots of `slot` open: the product, over its letters, of the fraction of
+    /// the crossing slot's candidates with that letter in the shared cell. Cells without a crossing slot count as
+    /// fully open.
+    fn score(&self, slot: usize, word: &[u8]) -> f64 {
+        slot_crossings(self.board, slot)
+            .map(|(position, other, other_position)| {
+                let words = self.words(other);
+                let domain = &self.domains[other];
+                let matching = domain
+                    .iter()
+                    .filter(|&&candidate| words[candidate][other_position] == word[position])
+                    .count();
+                matching as f64 / domain.len() as f64
+            })
+            .product()
+    }
+
+    fn into_solution(self) -> Solution {
+        Solution {
+            letters: self
+                .letters
+                .map(|&mask| (mask != 0).then(|| (b'A' + mask.trailing_zeros() as u8) as char)),
+        }
+    }
+}
+
+/// The crossings of the slot with index `slot`, as its position of the shared cell, the index of the crossing slot
+/// and that slot's position of the shared cell.
+fn slot_crossings(board: &Board, slot: usize) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+    let slots = board.slots();
+    slots[slot]
+        .cells
+        .iter()
+        .enumerate()
+        .flat_map(move |(position, loc)| {
+            slots
+                .iter()
+                .enumerate()
+                .filter(move |&(other, _)| other != slot)
+                .filter_map(move |(other, other_slot)| {
+                    let other_position = other_slot.cells.iter().position(|cell| cell == loc)?;
+                    Some((position, other, other_position))
+                })
+        })
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let changed = self.apply_slots()? | self.apply_distinct()?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Fills the slot with the fewest candidates, trying first the words that leave the most candidates for the
+    /// crossing slots.
+    fn branches(&self) -> Vec<Self> {
+        let Some(slot) = (0..self.domains.len())
+            .filter(|&slot| self.domains[slot].len() > 1)
+            .min_by_key(|&slot| self.domains[slot].len())
+        else {
+            return vec![];
+        };
+        let words = self.words(slot);
+        let mut candidates = self.domains[slot]
+            .iter()
+            .map(|&word| (word, self.score(slot, &words[word])))
+            .collect::<Vec<_>>();
+        candidates.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        candidates
+            .into_iter()
+            .map(|(word, _)| {
+                let mut domains = self.domains.clone();
+                domains[slot] = vec![word];
+                Self {
+                    domains,
+                    ..self.clone()
+                }
+            })
+            .collect()
+    }
+}
+
+/// A filled grid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    letters: Grid<Option<char>>,
+}
+
+impl Solution {
+    /// The letter in a cell, or `None` for blocks.
+    pub fn letter(&self, location: Location) -> Option<char> {
+        self.letters[location]
+    }
+}
+
+/// Blocks are shown as `#`.
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.letters.rows() {
+            let row = row.map(|letter| letter.unwrap_or('#')).collect::<String>();
+            writeln!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Fills the grid with words from `words`, ignoring case. Words with characters other than letters are skipped.
+pub fn solve(board: &Board, words: &[String]) -> Option<Solution> {
+    let dictionary = Dictionary::new(words);
+    search::solve(State::new(board, &dictionary)).map(State::into_solution)
+}
+
+pub fn count_solutions(board: &Board, words: &[String], limit: usize) -> usize {
+    let dictionary = Dictionary::new(words);
+    search::count_solutions(State::new(board, &dictionary), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_ring() {
+        let board = Board::parse("...\n.#.\n...").unwrap();
+        let words = ["cat", "cow", "tie", "woe", "dog", "car", "tea", "pig"]
+            .map(str::to_string)
+            .to_vec();
+        let solution = solve(&board, &words).unwrap();
+        let used = board
+            .slots()
+            .iter()
+            .map(|slot| {
+                slot.cells
+                    .iter()
+                    .map(|&loc| solution.letter(loc).unwrap())
+                    .collect::<String>()
+                    .to_ascii_lowercase()
+            })
+            .collect::<Vec<_>>();
+        for (index, word) in used.iter().enumerate() {
+            assert!(words.contains(word), "{word} is not in the word list.");
+            assert!(!used[..index].contains(word), "{word} is used twice.");
+        }
+        let given = Board::parse("...\n.#.\n..E").unwrap();
+        assert_eq!(count_solutions(&given, &words, 10), 2);
+    }
+}
@@ -0,0 +1,35 @@
+mod board;
+pub use board::{Board, Operation};
+mod solver;
+pub use solver::{count_solutions, solve, Solution};
+
+/// Cryptarithms as a [`Puzzle`](crate::puzzle::Puzzle), in the text format of [`Board::parse`].
+pub struct Cryptarithm;
+
+impl crate::puzzle::Puzzle for Cryptarithm {
+    const NAME: &'static str = "cryptarithm puzzle";
+
+    type Instance = Board;
+    type Solution = Solution;
+
+    fn parse(input: &str) -> crate::Result<Board> {
+        Board::parse(input)
+    }
+
+    /// One equation per line.
+    fn split(input: &str) -> Vec<&str> {
+        input
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect()
+    }
+
+    fn solve(board: &Board) -> crate::Result<Option<Solution>> {
+        Ok(solve(board))
+    }
+
+    fn count_solutions(board: &Board, limit: usize) -> crate::Result<usize> {
+        Ok(count_solutions(board, limit))
+    }
+}
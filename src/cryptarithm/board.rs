@@ -0,0 +1,196 @@
+use std::{fmt::Display, path};
+
+use crate::error::{bail, ensure, read_to_string, Error, Result};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Operation {
+    pub fn to_char(self) -> char {
+        match self {
+            Operation::Add => '+',
+            Operation::Subtract => '-',
+            Operation::Multiply => '*',
+            Operation::Divide => '/',
+        }
+    }
+
+    fn from_char(c: char) -> Option<Self> {
+        Some(match c {
+            '+' => Operation::Add,
+            '-' => Operation::Subtract,
+            '*' => Operation::Multiply,
+            '/' => Operation::Divide,
+            _ => return None,
+        })
+    }
+}
+
+/// The most digits a number may have, so that the solver can work in `u128` without overflowing.
+const MAX_DIGITS: usize = 18;
+
+/// A cryptarithm: replace every letter by a digit, different letters by different digits, such that the equation
+/// holds and no number with more than one digit starts with 0.
+///
+/// Sums may have any number of terms and products any number of factors, while a subtraction or a division has
+/// exactly two operands. Divisions must come out even.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    /// The distinct letters in order of first appearance.
+    letters: Vec<char>,
+    /// The letters of every operand and of the result, as indices into `letters`, most significant first.
+    operands: Vec<Vec<usize>>,
+    result: Vec<usize>,
+    operation: Operation,
+}
+
+impl Board {
+    pub fn new(operands: Vec<String>, operation: Operation, result: String) -> Result<Self> {
+        ensure!(
+            operands.len() >= 2,
+            Invalid,
+            "Expected at least two operands, but got {}.",
+            operands.len()
+        );
+        if matches!(operation, Operation::Subtract | Operation::Divide) {
+            ensure!(
+                operands.len() == 2,
+                Invalid,
+                "A '{}' takes exactly two operands, but got {}.",
+                operation.to_char(),
+                operands.len()
+            );
+        }
+        let mut letters = vec![];
+        let mut word = |word: &str| -> Result<Vec<usize>> {
+            ensure!(
+                !word.is_empty(),
+                Invalid,
+                "Every number needs at least one letter."
+            );
+            ensure!(
+                word.chars().count() <= MAX_DIGITS,
+                Invalid,
+                "Numbers may have at most {MAX_DIGITS} digits. Got '{word}'."
+            );
+            word.chars()
+                .map(|c| {
+                    ensure!(
+                        c.is_alphabetic(),
+                        Invalid,
+                        "Invalid letter '{c}' in '{word}'."
+                    );
+                    Ok(match letters.iter().position(|&letter| letter == c) {
+                        Some(index) => index,
+                        None => {
+                            letters.push(c);
+                            letters.len() - 1
+                        }
+                    })
+                })
+                .collect()
+        };
+        let operands = operands
+            .iter()
+            .map(|operand| word(operand))
+            .collect::<Result<Vec<_>>>()?;
+        let result = word(&result)?;
+        ensure!(
+            letters.len() <= 10,
+            Invalid,
+            "There are only 10 digits, but the puzzle has {} letters.",
+            letters.len()
+        );
+        Ok(Self {
+            letters,
+            operands,
+            result,
+            operation,
+        })
+    }
+
+    /// Parses an equation such as `SEND+MORE=MONEY`, ignoring whitespace. The operation is one of `+`, `-`, `*`
+    /// and `/`.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let equation = string
+            .as_ref()
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>();
+        let Some((left, result)) = equation.split_once('=') else {
+            bail!(Parse, "Expected an equation with '='. Got '{equation}'.");
+        };
+        let operators = left
+            .chars()
+            .filter(|c| !c.is_alphabetic())
+            .collect::<Vec<_>>();
+        let &first = operators
+            .first()
+            .ok_or_else(|| Error::Parse(format!("Expected an operation in '{left}'.")))?;
+        let operation = Operation::from_char(first)
+            .ok_or_else(|| Error::Parse(format!("Invalid operation '{first}' in '{left}'.")))?;
+        ensure!(
+            operators.iter().all(|&c| c == first),
+            Parse,
+            "Mixing operations is not supported. Got '{left}'."
+        );
+        let operands = left.split(first).map(str::to_string).collect();
+        Self::new(operands, operation, result.to_string())
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("cryptarithm", path)?;
+        Self::parse(string)
+    }
+
+    /// The distinct letters in order of first appearance.
+    pub fn letters(&self) -> &[char] {
+        &self.letters
+    }
+
+    /// The letters of every operand as indices into [`Board::letters`], most significant first.
+    pub fn operands(&self) -> &[Vec<usize>] {
+        &self.operands
+    }
+
+    /// The letters of the result as indices into [`Board::letters`], most significant first.
+    pub fn result(&self) -> &[usize] {
+        &self.result
+    }
+
+    pub fn operation(&self) -> Operation {
+        self.operation
+    }
+
+    /// Writes the equation with every letter replaced by `symbol`.
+    pub(crate) fn write_with(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        symbol: impl Fn(usize) -> char,
+    ) -> std::fmt::Result {
+        let word = |word: &[usize]| {
+            word.iter()
+                .map(|&letter| symbol(letter))
+                .collect::<String>()
+        };
+        let operands = self
+            .operands
+            .iter()
+            .map(|operand| word(operand))
+            .collect::<Vec<_>>();
+        let operation = self.operation.to_char().to_string();
+        writeln!(f, "{}={}", operands.join(&operation), word(&self.result))
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_with(f, |letter| self.letters[letter])
+    }
+}
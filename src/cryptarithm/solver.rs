@@ -0,0 +1,260 @@
+use std::fmt::Display;
+
+use crate::search::{self, Contradiction, SearchState};
+
+use super::{Board, Operation};
+
+/// The puzzle rewritten without subtraction and division: the terms must add up to or multiply to the total.
+#[derive(Clone, Debug)]
+struct Layout {
+    product: bool,
+    /// Every word as letter indices, least significant first.
+    terms: Vec<Vec<usize>>,
+    total: Vec<usize>,
+    /// Letters that start a number with more than one digit and so cannot be 0.
+    leading: Vec<bool>,
+    /// The letters from the rightmost column to the leftmost, which is the order column sums are decided in.
+    order: Vec<usize>,
+}
+
+impl Layout {
+    fn new(board: &Board) -> Self {
+        let reversed = |word: &[usize]| word.iter().rev().copied().collect::<Vec<_>>();
+        let operands = board.operands().iter().map(|operand| reversed(operand));
+        let result = reversed(board.result());
+        // A - B = C is B + C = A, and A / B = C is B * C = A.
+        let (terms, total): (Vec<_>, _) = match board.operation() {
+            Operation::Add | Operation::Multiply => (operands.collect(), result),
+            Operation::Subtract | Operation::Divide => {
+                let mut operands = operands;
+                let minuend = operands.next().expect("Subtractions have two operands.");
+                (operands.chain([result]).collect(), minuend)
+            }
+        };
+        let mut leading = vec![false; board.letters().len()];
+        let mut order = vec![];
+        let words = terms.iter().chain([&total]).collect::<Vec<_>>();
+        for word in words.iter() {
+            if word.len() > 1 {
+                leading[word[word.len() - 1]] = true;
+            }
+        }
+        let longest = words.iter().map(|word| word.len()).max().unwrap_or(0);
+        for place in 0..longest {
+            for word in words.iter() {
+                if let Some(&letter) = word.get(place) {
+                    if !order.contains(&letter) {
+                        order.push(letter);
+                    }
+                }
+            }
+        }
+        Self {
+            product: board.operation() == Operation::Multiply
+                || board.operation() == Operation::Divide,
+            terms,
+            total,
+            leading,
+            order,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct State<'a> {
+    layout: &'a Layout,
+    digits: Vec<Option<u8>>,
+}
+
+impl<'a> State<'a> {
+    fn new(layout: &'a Layout) -> Self {
+        Self {
+            layout,
+            digits: vec![None; layout.leading.len()],
+        }
+    }
+
+    /// The value of the lowest `places` digits of `word`, or of all of it if it is shorter,
+    /// or `None` if one of them is undecided.
+    fn low(&self, word: &[usize], places: usize) -> Option<u128> {
+        word.iter()
+            .take(places)
+            .rev()
+            .try_fold(0, |value, &letter| {
+                Some(value * 10 + u128::from(self.digits[letter]?))
+            })
+    }
+
+    fn set(&mut self, letter: usize, digit: u8) -> Result<bool, Contradiction> {
+        match self.digits[letter] {
+            Some(current) if current == digit => Ok(false),
+            Some(_) => Err(Contradiction),
+            None if digit == 0 && self.layout.leading[letter] => Err(Contradiction),
+            None if self.digits.contains(&Some(digit)) => Err(Contradiction),
+            None => {
+                self.digits[letter] = Some(digit);
+                Ok(true)
+            }
+        }
+    }
+
+    /// Works through the columns from the right. Once the lowest `places` digits of every term are known,
+    /// so are the lowest `places` digits of the total, which decides its digit in that column.
+    fn apply_columns(&mut self) -> Result<bool, Contradiction> {
+        let layout = self.layout;
+        let longest = layout.terms.iter().map(Vec::len).max().unwrap_or(0);
+        let mut changed = false;
+        for places in 1..=longest.max(layout.total.len()) {
+            let modulus = 10u128.pow(places as u32);
+            let Some(lows) = layout
+                .terms
+                .iter()
+                .map(|term| self.low(term, places))
+                .collect::<Option<Vec<_>>>()
+            else {
+                break;
+            };
+            let value = if layout.product {
+                lows.iter().fold(1, |product, &low| product * low % modulus)
+            } else {
+                lows.iter().fold(0, |sum, &low| (sum + low) % modulus)
+            };
+            if places <= layout.total.len() {
+                if self.low(&layout.total, places - 1).is_none() {
+                    break;
+                }
+                let digit = (value / (modulus / 10) % 10) as u8;
+                changed |= self.set(layout.total[places - 1], digit)?;
+            } else if self.low(&layout.total, places) != Some(value) {
+                // The total has fewer digits than the terms, so it must be all of the low digits.
+                return Err(Contradiction);
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Fails if a letter has no digit left to take. Once every letter has a digit, checks the whole equation,
+    /// as the columns only fix the low digits.
+    fn check_digits(&self) -> Result<(), Contradiction> {
+        let layout = self.layout;
+        let undecided = (0..self.digits.len())
+            .filter(|&letter| self.digits[letter].is_none())
+            .collect::<Vec<_>>();
+        if !undecided.is_empty() {
+            let free = (0..10)
+                .filter(|&digit| !self.digits.contains(&Some(digit)))
+                .collect::<Vec<_>>();
+            if undecided.len() > free.len()
+                || undecided
+                    .iter()
+                    .any(|&letter| layout.leading[letter] && free == [0])
+            {
+                return Err(Contradiction);
+            }
+            return Ok(());
+        }
+        let value = |word: &[usize]| self.low(word, word.len()).expect("Every digit is known.");
+        let mut values = layout.terms.iter().map(|term| value(term));
+        let combined = if layout.product {
+            values.try_fold(1u128, |product, value| product.checked_mul(value))
+        } else {
+            values.try_fold(0u128, |sum, value| sum.checked_add(value))
+        };
+        if combined == Some(value(&layout.total)) {
+            Ok(())
+        } else {
+            Err(Contradiction)
+        }
+    }
+
+    fn into_solution(self, board: &Board) -> Solution {
+        Solution {
+            board: board.clone(),
+            digits: self
+                .digits
+                .into_iter()
+                .map(|digit| digit.expect("Every letter has a digit in a solution."))
+                .collect(),
+        }
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        while self.apply_columns()? {}
+        self.check_digits()
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(&letter) = self
+            .layout
+            .order
+            .iter()
+            .find(|&&letter| self.digits[letter].is_none())
+        else {
+            return vec![];
+        };
+        (0..10)
+            .filter_map(|digit| {
+                let mut state = self.clone();
+                state.set(letter, digit).ok()?;
+                Some(state)
+            })
+            .collect()
+    }
+}
+
+/// A digit for every letter of a cryptarithm.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    board: Board,
+    digits: Vec<u8>,
+}
+
+impl Solution {
+    /// The digit of `letter`, or `None` if the puzzle does not use it.
+    pub fn digit(&self, letter: char) -> Option<u8> {
+        let index = self.board.letters().iter().position(|&c| c == letter)?;
+        Some(self.digits[index])
+    }
+}
+
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.board
+            .write_with(f, |letter| char::from(b'0' + self.digits[letter]))
+    }
+}
+
+/// Returns the equation with every letter replaced by its digit, or `None` if the puzzle has no solution.
+pub fn solve(board: &Board) -> Option<Solution> {
+    let layout = Layout::new(board);
+    search::solve(State::new(&layout)).map(|state| state.into_solution(board))
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    let layout = Layout::new(board);
+    search::count_solutions(State::new(&layout), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn send_more_money() {
+        let board = Board::parse("SEND + MORE = MONEY").unwrap();
+        let solution = solve(&board).unwrap();
+        assert_eq!(solution.to_string(), "9567+1085=10652\n");
+        assert_eq!(solution.digit('M'), Some(1));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+
+    #[test]
+    fn division_is_a_product() {
+        let board = Board::parse("AB / C = D").unwrap();
+        let solution = solve(&board).unwrap();
+        let digit = |c| u32::from(solution.digit(c).unwrap());
+        assert_eq!(digit('A') * 10 + digit('B'), digit('C') * digit('D'));
+    }
+}
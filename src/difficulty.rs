@@ -0,0 +1,106 @@
+//! A difficulty scale shared by every kind of puzzle. Each kind rates puzzles by its own score, such as the number of
+//! guesses its solver makes, and grades those scores on the shared scale, so that puzzles of different kinds can be
+//! compared and reported the same way.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{bail, Error, Result};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+/// A score in the units of its kind of puzzle, with the difficulty it grades to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rating {
+    pub score: u64,
+    pub difficulty: Difficulty,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ];
+
+    /// Grades a score given the lowest scores that are medium, hard and expert.
+    pub fn grade(score: u64, thresholds: [u64; 3]) -> Self {
+        let index = thresholds
+            .iter()
+            .take_while(|&&threshold| score >= threshold)
+            .count();
+        Self::ALL[index]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let Some(difficulty) = Self::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.name().eq_ignore_ascii_case(s.trim()))
+        else {
+            bail!(
+                Parse,
+                "Unknown difficulty '{s}'. Expected easy, medium, hard or expert."
+            );
+        };
+        Ok(difficulty)
+    }
+}
+
+impl fmt::Display for Rating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.difficulty, self.score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grades_scores() {
+        let thresholds = [1, 5, 100];
+        let grades = [0, 1, 4, 5, 99, 100, 1000].map(|score| Difficulty::grade(score, thresholds));
+        assert_eq!(
+            grades,
+            [
+                Difficulty::Easy,
+                Difficulty::Medium,
+                Difficulty::Medium,
+                Difficulty::Hard,
+                Difficulty::Hard,
+                Difficulty::Expert,
+                Difficulty::Expert,
+            ]
+        );
+        assert_eq!("Hard".parse::<Difficulty>().unwrap(), Difficulty::Hard);
+        assert!("impossible".parse::<Difficulty>().is_err());
+    }
+}
@@ -0,0 +1,36 @@
+use crate::generate::GeneratorConfig;
+
+mod board;
+pub use board::Board;
+mod generator;
+pub use generator::generate;
+mod solver;
+pub use solver::{count_solutions, solve, Solution};
+
+/// Dominosa puzzles as a [`Puzzle`](crate::puzzle::Puzzle), in the text format of [`Board::parse`].
+pub struct Dominosa;
+
+impl crate::puzzle::Puzzle for Dominosa {
+    const NAME: &'static str = "dominosa puzzle";
+
+    type Instance = Board;
+    type Solution = Solution;
+
+    fn parse(input: &str) -> crate::Result<Board> {
+        Board::parse(input)
+    }
+
+    fn solve(board: &Board) -> crate::Result<Option<Solution>> {
+        Ok(solve(board))
+    }
+
+    fn count_solutions(board: &Board, limit: usize) -> crate::Result<usize> {
+        Ok(count_solutions(board, limit))
+    }
+
+    /// `size` is the highest number on the dominoes, 6 by default.
+    fn generate(config: &GeneratorConfig) -> crate::Result<Board> {
+        config.ensure_untargeted::<Self>()?;
+        generate(config.size.unwrap_or(6), 1000, &mut config.rng())
+    }
+}
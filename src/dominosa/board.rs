@@ -0,0 +1,134 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+};
+
+/// A dominosa puzzle: a grid of numbers from 0 to `max` that must be split into dominoes such that every pair of
+/// numbers appears on exactly one domino. The grid is `max + 1` rows by `max + 2` columns, or the other way around.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    max: usize,
+    numbers: Grid<usize>,
+}
+
+impl Board {
+    pub fn new(numbers: Grid<usize>) -> Result<Self> {
+        let (height, width) = numbers.dim();
+        let max = height.min(width).saturating_sub(1);
+        ensure!(
+            height.abs_diff(width) == 1 && height > 0, Invalid,
+            "Dominosa grids must have one more column than rows or the other way around, but got {height}x{width}."
+        );
+        for number in 0..=max {
+            let count = numbers.iter().filter(|&&other| other == number).count();
+            ensure!(
+                count == max + 2,
+                Invalid,
+                "Every number must appear {} times, but {number} appears {count} times.",
+                max + 2
+            );
+        }
+        if let Some(number) = numbers.iter().find(|&&number| number > max) {
+            bail!(Invalid, "Numbers must be at most {max}, but got {number}.");
+        }
+        Ok(Self { max, numbers })
+    }
+
+    /// Parses a grid of numbers. Rows are either strings of digits or, for numbers above 9,
+    /// whitespace separated numbers.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                if line.contains(char::is_whitespace) {
+                    line.split_whitespace()
+                        .map(|token| {
+                            token
+                                .parse::<usize>()
+                                .map_err(|_| Error::Parse(format!("Invalid number '{token}'.")))
+                        })
+                        .collect::<Result<Vec<_>>>()
+                } else {
+                    line.chars()
+                        .map(|c| {
+                            c.to_digit(10)
+                                .map(|digit| digit as usize)
+                                .ok_or_else(|| Error::Parse(format!("Invalid digit '{c}'.")))
+                        })
+                        .collect::<Result<Vec<_>>>()
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        let mut numbers = Grid::from_elem((height, width), 0);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} numbers in row {row_index}. Got {}.",
+                    row.len()
+                );
+            }
+            for (col_index, number) in row.into_iter().enumerate() {
+                numbers[Location::new(row_index, col_index)] = number;
+            }
+        }
+        Self::new(numbers)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("dominosa", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.numbers.dim()
+    }
+
+    /// The highest number on the dominoes.
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    pub fn number(&self, location: Location) -> usize {
+        self.numbers[location]
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let wide = self.max > 9;
+        for row in self.numbers.rows() {
+            let row = row.map(usize::to_string).collect::<Vec<_>>();
+            writeln!(f, "{}", row.join(if wide { " " } else { "" }))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_digits_and_wide_numbers() {
+        let board = Board::parse(include_str!("../../data/dominosa/puzzles/six01.txt")).unwrap();
+        assert_eq!(board.dim(), (7, 8));
+        assert_eq!(board.max(), 6);
+        assert_eq!(board.number(Location::new(0, 1)), 4);
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        let one = Board::parse("0 0\n").unwrap();
+        assert_eq!((one.dim(), one.max()), ((1, 2), 0));
+        assert!(Board::parse("000\n011\n").is_err());
+        assert!(Board::parse("00\n1\n").is_err());
+        assert!(Board::parse("0a\n").is_err());
+    }
+}
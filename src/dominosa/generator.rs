@@ -0,0 +1,92 @@
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    error::{bail, Result},
+    grid::Grid,
+    location::{Direction, Location},
+};
+
+use super::{count_solutions, Board};
+
+/// Covers the rest of the grid with randomly placed dominoes, always covering the first uncovered cell next,
+/// and backtracking when that cell cannot be covered. Returns whether the grid could be covered.
+fn random_tiling(
+    covered: &mut Grid<bool>,
+    dominoes: &mut Vec<[Location; 2]>,
+    rng: &mut impl Rng,
+) -> bool {
+    let dim = covered.dim();
+    let Some(start) = Location::grid_iter(dim).find(|&loc| !covered[loc]) else {
+        return true;
+    };
+    let mut directions = [Direction::Right, Direction::Down];
+    directions.shuffle(rng);
+    for direction in directions {
+        let Some(other) = start.step(direction, dim) else {
+            continue;
+        };
+        if covered[other] {
+            continue;
+        }
+        covered[start] = true;
+        covered[other] = true;
+        dominoes.push([start, other]);
+        if random_tiling(covered, dominoes, rng) {
+            return true;
+        }
+        covered[start] = false;
+        covered[other] = false;
+        dominoes.pop();
+    }
+    false
+}
+
+/// Generates a puzzle with numbers up to `max` and a unique solution, giving up after `attempts` tries.
+pub fn generate(max: usize, attempts: usize, rng: &mut impl Rng) -> Result<Board> {
+    let dim = (max + 1, max + 2);
+    for _ in 0..attempts {
+        let mut tiling = vec![];
+        if !random_tiling(&mut Grid::from_elem(dim, false), &mut tiling, rng) {
+            bail!(
+                Generate,
+                "A {}x{} grid cannot be tiled with dominoes.",
+                dim.0,
+                dim.1
+            );
+        }
+        let mut set = (0..=max)
+            .flat_map(|a| (a..=max).map(move |b| [a, b]))
+            .collect::<Vec<_>>();
+        set.shuffle(rng);
+        let mut numbers = Grid::from_elem(dim, 0);
+        for (cells, mut domino) in tiling.into_iter().zip(set) {
+            domino.shuffle(rng);
+            for (loc, number) in cells.into_iter().zip(domino) {
+                numbers[loc] = number;
+            }
+        }
+        let board = Board::new(numbers)?;
+        if count_solutions(&board, 2) == 1 {
+            return Ok(board);
+        }
+    }
+    bail!(
+        Generate,
+        "Failed to generate a unique puzzle up to {max} in {attempts} attempts."
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn generates_unique_puzzles() {
+        let board = generate(4, 100, &mut StdRng::seed_from_u64(0)).unwrap();
+        assert_eq!(board.max(), 4);
+        assert_eq!(board.dim(), (5, 6));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt::Display;
+
+use crate::{
+    exact_cover::ExactCover,
+    location::{Direction, Location},
+};
+
+use super::Board;
+
+/// The index of the domino with the numbers `a` and `b` in the set of dominoes up to `max`.
+fn domino_index(a: usize, b: usize, max: usize) -> usize {
+    let (low, high) = (a.min(b), a.max(b));
+    // The dominoes with a lower number below `low` come first.
+    low * (2 * max + 3 - low) / 2 + (high - low)
+}
+
+/// Every pair of adjacent cells as an exact cover problem, where the items are the cells and then the dominoes.
+fn problem(board: &Board) -> (ExactCover, Vec<[Location; 2]>) {
+    let dim = board.dim();
+    let max = board.max();
+    let num_cells = dim.0 * dim.1;
+    let num_dominoes = (max + 1) * (max + 2) / 2;
+    let mut problem = ExactCover::new(num_cells + num_dominoes);
+    let mut placements = vec![];
+    for loc in Location::grid_iter(dim) {
+        for direction in [Direction::Right, Direction::Down] {
+            let Some(other) = loc.step(direction, dim) else {
+                continue;
+            };
+            let domino = domino_index(board.number(loc), board.number(other), max);
+            problem.add_option(vec![
+                loc.row * dim.1 + loc.col,
+                other.row * dim.1 + other.col,
+                num_cells + domino,
+            ]);
+            placements.push([loc, other]);
+        }
+    }
+    (problem, placements)
+}
+
+/// The dominoes of a solved puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    board: Board,
+    /// The two cells of every domino, the top or left one first.
+    dominoes: Vec<[Location; 2]>,
+}
+
+impl Solution {
+    pub fn dominoes(&self) -> &[[Location; 2]] {
+        &self.dominoes
+    }
+}
+
+/// The numbers with `-` and `|` joining the two halves of every domino.
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (height, width) = self.board.dim();
+        let joined = |a: Location, b: Location| self.dominoes.contains(&[a, b]);
+        let cell_width = self.board.max().to_string().len();
+        for row in 0..height {
+            for col in 0..width {
+                let loc = Location::new(row, col);
+                write!(f, "{:>cell_width$}", self.board.number(loc))?;
+                if col + 1 < width {
+                    let right = Location::new(row, col + 1);
+                    write!(f, "{}", if joined(loc, right) { '-' } else { ' ' })?;
+                }
+            }
+            writeln!(f)?;
+            if row + 1 < height {
+                let lines = (0..width)
+                    .map(|col| {
+                        let (top, bottom) = (Location::new(row, col), Location::new(row + 1, col));
+                        let line = if joined(top, bottom) { "|" } else { " " };
+                        format!("{line:>cell_width$}")
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                writeln!(f, "{}", lines.trim_end())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn solve(board: &Board) -> Option<Solution> {
+    let (problem, placements) = problem(board);
+    let mut chosen = problem.solve()?;
+    chosen.sort();
+    Some(Solution {
+        board: board.clone(),
+        dominoes: chosen.into_iter().map(|index| placements[index]).collect(),
+    })
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    problem(board).0.count_solutions(limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    fn follows_rules(board: &Board, solution: &Solution) -> bool {
+        let dim = board.dim();
+        let mut covered = Grid::from_elem(dim, false);
+        let mut pairs = vec![];
+        for &[a, b] in solution.dominoes() {
+            if !a.adjacents(dim).contains(&Some(b)) || covered[a] || covered[b] {
+                return false;
+            }
+            covered[a] = true;
+            covered[b] = true;
+            let (a, b) = (board.number(a), board.number(b));
+            pairs.push((a.min(b), a.max(b)));
+        }
+        pairs.sort_unstable();
+        let all_pairs = (0..=board.max()).flat_map(|a| (a..=board.max()).map(move |b| (a, b)));
+        let all_covered = covered.iter().all(|&covered| covered);
+        all_covered && pairs.into_iter().eq(all_pairs)
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/dominosa/puzzles/six01.txt")).unwrap();
+        let solution = solve(&board).unwrap();
+        assert!(follows_rules(&board, &solution));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+
+    #[test]
+    fn domino_indices_are_a_bijection() {
+        let max = 4;
+        let mut indices = (0..=max)
+            .flat_map(|a| (a..=max).map(move |b| domino_index(b, a, max)))
+            .collect::<Vec<_>>();
+        indices.sort();
+        assert_eq!(indices, (0..15).collect::<Vec<_>>());
+    }
+}
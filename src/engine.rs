@@ -0,0 +1,206 @@
+//! A constraint propagation engine. Each rule of a puzzle is a constraint registered against the variables it reads,
+//! such as the cells of a row, and whenever a constraint changes a variable, every constraint reading that variable
+//! is scheduled to run again, until nothing changes. Solvers then only need to supply their constraints.
+
+use std::cell::Cell;
+
+use crate::error::Result;
+
+/// A rule of a puzzle over some of the variables of a state of type `S`, with variables identified by index.
+pub trait Constraint<S> {
+    /// The variables the constraint reads. It runs again whenever one of them changes.
+    fn variables(&self) -> Vec<usize>;
+
+    /// Narrows the variables of `state` using the rule, pushing every variable it changes to `changed`.
+    /// Fails if the rule cannot be satisfied.
+    fn propagate(&self, state: &mut S, changed: &mut Vec<usize>) -> Result<()>;
+}
+
+pub struct Engine<S> {
+    constraints: Vec<Box<dyn Constraint<S>>>,
+    /// The constraints reading each variable.
+    watchers: Vec<Vec<usize>>,
+    /// Kept between runs, so that propagating does not allocate once they have grown. Taken out while running.
+    buffers: Cell<Buffers>,
+}
+
+#[derive(Default)]
+struct Buffers {
+    queued: Vec<bool>,
+    changed: Vec<usize>,
+}
+
+impl<S> Engine<S> {
+    pub fn new(num_variables: usize) -> Self {
+        Self {
+            constraints: vec![],
+            watchers: vec![vec![]; num_variables],
+            buffers: Cell::default(),
+        }
+    }
+
+    /// Panics if the constraint reads a variable outside the engine.
+    pub fn add(&mut self, constraint: impl Constraint<S> + 'static) {
+        let index = self.constraints.len();
+        for variable in constraint.variables() {
+            self.watchers[variable].push(index);
+        }
+        self.constraints.push(Box::new(constraint));
+    }
+
+    /// The number of constraints.
+    pub fn len(&self) -> usize {
+        self.constraints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.constraints.is_empty()
+    }
+
+    /// Runs every constraint until none of them changes anything. Returns whether any variable changed.
+    pub fn propagate(&self, state: &mut S) -> Result<bool> {
+        Ok(self.run(state, 0..self.constraints.len())?.changed)
+    }
+
+    /// Like [`Engine::propagate`], but only starts with the constraints reading `variables`, for when the state was
+    /// already at a fixpoint before those variables changed.
+    pub fn propagate_changed(
+        &self,
+        state: &mut S,
+        variables: impl IntoIterator<Item = usize>,
+    ) -> Result<bool> {
+        Ok(self.run(state, self.watching(variables))?.changed)
+    }
+
+    /// Like [`Engine::propagate_changed`], but returns the number of rounds it took. Each round is a pass over the
+    /// scheduled constraints in the order they were added, like a sweep over every constraint would make.
+    pub fn count_rounds(
+        &self,
+        state: &mut S,
+        variables: impl IntoIterator<Item = usize>,
+    ) -> Result<u32> {
+        Ok(self.run(state, self.watching(variables))?.rounds)
+    }
+
+    /// The constraints reading any of the variables.
+    fn watching<'a>(
+        &'a self,
+        variables: impl IntoIterator<Item = usize> + 'a,
+    ) -> impl Iterator<Item = usize> + 'a {
+        variables
+            .into_iter()
+            .flat_map(|variable| self.watchers[variable].iter().copied())
+    }
+
+    fn run(&self, state: &mut S, constraints: impl IntoIterator<Item = usize>) -> Result<Run> {
+        let mut buffers = self.buffers.take();
+        let result = self.run_with(state, constraints, &mut buffers);
+        self.buffers.set(buffers);
+        result
+    }
+
+    fn run_with(
+        &self,
+        state: &mut S,
+        constraints: impl IntoIterator<Item = usize>,
+        buffers: &mut Buffers,
+    ) -> Result<Run> {
+        let Buffers { queued, changed } = buffers;
+        // A run that failed may have left constraints queued.
+        queued.clear();
+        queued.resize(self.constraints.len(), false);
+        changed.clear();
+        let mut num_queued = 0;
+        for constraint in constraints {
+            if !queued[constraint] {
+                queued[constraint] = true;
+                num_queued += 1;
+            }
+        }
+        let mut run = Run::default();
+        // A constraint scheduled by one before it in the round runs later in the same round, and one scheduled by
+        // itself or one after it runs in the next.
+        while num_queued > 0 {
+            run.rounds += 1;
+            for constraint in 0..self.constraints.len() {
+                if !queued[constraint] {
+                    continue;
+                }
+                queued[constraint] = false;
+                num_queued -= 1;
+                self.constraints[constraint].propagate(state, changed)?;
+                for variable in changed.drain(..) {
+                    run.changed = true;
+                    for &watcher in self.watchers[variable].iter() {
+                        if !queued[watcher] {
+                            queued[watcher] = true;
+                            num_queued += 1;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(run)
+    }
+}
+
+#[derive(Default)]
+struct Run {
+    changed: bool,
+    rounds: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lowers the second variable to at most the first.
+    struct AtMost(usize, usize);
+
+    impl Constraint<Vec<u32>> for AtMost {
+        fn variables(&self) -> Vec<usize> {
+            vec![self.0]
+        }
+
+        fn propagate(&self, state: &mut Vec<u32>, changed: &mut Vec<usize>) -> Result<()> {
+            if state[self.1] > state[self.0] {
+                state[self.1] = state[self.0];
+                changed.push(self.1);
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn changes_reach_fixpoint() {
+        let mut engine = Engine::new(4);
+        for variable in (0..3).rev() {
+            engine.add(AtMost(variable, variable + 1));
+        }
+        let mut state = vec![9; 4];
+        assert!(!engine.propagate(&mut state).unwrap());
+        state[0] = 2;
+        assert!(engine.propagate_changed(&mut state, [0]).unwrap());
+        assert_eq!(state, [2; 4]);
+        state[2] = 1;
+        assert!(engine.propagate_changed(&mut state, [2]).unwrap());
+        assert_eq!(state, [2, 2, 1, 1]);
+        // Every constraint schedules one added before it, so each round lowers one more variable.
+        state[0] = 0;
+        assert_eq!(engine.count_rounds(&mut state, [0]).unwrap(), 3);
+        assert_eq!(state, [0; 4]);
+        assert_eq!(engine.count_rounds(&mut state, [0]).unwrap(), 1);
+    }
+
+    #[test]
+    fn rounds_follow_order_of_constraints() {
+        let mut engine = Engine::new(4);
+        for variable in 0..3 {
+            engine.add(AtMost(variable, variable + 1));
+        }
+        let mut state = vec![9; 4];
+        state[0] = 0;
+        assert_eq!(engine.count_rounds(&mut state, [0]).unwrap(), 1);
+        assert_eq!(state, [0; 4]);
+    }
+}
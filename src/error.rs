@@ -0,0 +1,140 @@
+//! The errors of the library. Most puzzle modules only ever fail on input that is malformed or breaks the rules of
+//! the puzzle, which [`Error::Parse`] and [`Error::Invalid`] describe. Modules whose failures carry more structure
+//! have their own error types, which [`Error`] wraps. The shared solver modules say why a state breaks the rules
+//! with [`latin::Error`] and [`shading::Error`], which the depth first [search](crate::search) turns into its
+//! [`Contradiction`], as it only needs to know that a branch is dead.
+
+use std::{fs, io, path::Path, path::PathBuf};
+
+use thiserror::Error;
+
+use crate::{camping, latin, puzzlink, search::Contradiction, shading, sudoku};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Error reading {puzzle} file from path {path:?}")]
+    Read {
+        puzzle: &'static str,
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("Error writing {puzzle} file to path {path:?}")]
+    Write {
+        puzzle: &'static str,
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    /// The input is not in the format of the puzzle.
+    #[error("{0}")]
+    Parse(String),
+    /// The input is well formed, but does not describe a valid puzzle, such as a clue that is out of range.
+    #[error("{0}")]
+    Invalid(String),
+    /// A generator could not produce a puzzle, either because none fit the parameters or because it gave up.
+    #[error("{0}")]
+    Generate(String),
+    #[error("The {0} has no solution.")]
+    NoSolution(&'static str),
+    /// The solver was cancelled through a [`CancelToken`](crate::cancel::CancelToken) before it finished.
+    #[error("The solver was cancelled.")]
+    Cancelled,
+    /// The puzzle does not support an action, such as generating or rating.
+    #[error("{action} is not supported for {puzzle}s.")]
+    Unsupported {
+        puzzle: &'static str,
+        action: &'static str,
+    },
+    #[error(transparent)]
+    Contradiction(#[from] Contradiction),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Camping(#[from] camping::Error),
+    #[error(transparent)]
+    Latin(#[from] latin::Error),
+    #[error(transparent)]
+    PuzzLink(#[from] puzzlink::Error),
+    #[error(transparent)]
+    Shading(#[from] shading::Error),
+    #[error(transparent)]
+    Sudoku(#[from] sudoku::Error),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Reads the file of a puzzle, naming the puzzle in the error.
+pub(crate) fn read_to_string(puzzle: &'static str, path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|source| Error::Read {
+        puzzle,
+        path: path.to_owned(),
+        source,
+    })
+}
+
+/// Writes the file of a puzzle, naming the puzzle in the error.
+pub(crate) fn write(puzzle: &'static str, path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    fs::write(path, contents).map_err(|source| Error::Write {
+        puzzle,
+        path: path.to_owned(),
+        source,
+    })
+}
+
+/// Returns an error of the given kind, with a message formatted like [`format!`].
+macro_rules! bail {
+    ($kind:ident, $($arg:tt)+) => {
+        return Err($crate::error::Error::$kind(format!($($arg)+)))
+    };
+}
+
+/// Returns an error of the given kind unless the condition holds.
+macro_rules! ensure {
+    ($condition:expr, $kind:ident, $($arg:tt)+) => {
+        if !$condition {
+            $crate::error::bail!($kind, $($arg)+);
+        }
+    };
+}
+
+pub(crate) use {bail, ensure};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{akari, latin::LatinSquare, location::Location, shading::ShadeGrid};
+
+    #[test]
+    fn kinds() {
+        assert!(matches!(akari::Board::parse("..\n."), Err(Error::Parse(_))));
+        assert!(matches!(akari::Board::parse("5."), Err(Error::Invalid(_))));
+        assert!(matches!(
+            akari::Board::from_file("does/not/exist.txt"),
+            Err(Error::Read {
+                puzzle: "akari",
+                ..
+            })
+        ));
+        assert!(matches!(
+            crate::sudoku::Board::from_line("1", '.'),
+            Err(sudoku::Error::LineLength(1))
+        ));
+
+        let location = Location::new(0, 0);
+        let mut grid = ShadeGrid::new((1, 1));
+        grid.set(location, shading::Shade::Shaded).unwrap();
+        let shade = shading::Shade::Unshaded;
+        assert_eq!(
+            grid.set(location, shade),
+            Err(shading::Error::Decided { location, shade })
+        );
+        let mut square = LatinSquare::new(2);
+        square.assign(location, 1).unwrap();
+        let error = Error::from(square.assign(location, 2).unwrap_err());
+        assert!(matches!(
+            error,
+            Error::Latin(latin::Error::NoValuesLeft(at)) if at == location
+        ));
+    }
+}
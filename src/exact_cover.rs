@@ -0,0 +1,155 @@
+//! Exact cover: choosing options, each covering some items, such that every item is covered by exactly one
+//! chosen option. Puzzles that tile a grid with pieces, such as shikaku, map directly onto this.
+
+use crate::search::{self, Contradiction, SearchState};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExactCover {
+    num_items: usize,
+    options: Vec<Vec<usize>>,
+    /// The options covering each item.
+    item_options: Vec<Vec<usize>>,
+}
+
+impl ExactCover {
+    pub fn new(num_items: usize) -> Self {
+        Self {
+            num_items,
+            options: vec![],
+            item_options: vec![vec![]; num_items],
+        }
+    }
+
+    /// Adds an option covering `items`, returning its index.
+    ///
+    /// Panics if an item is out of range.
+    pub fn add_option(&mut self, items: Vec<usize>) -> usize {
+        let index = self.options.len();
+        for &item in items.iter() {
+            assert!(
+                item < self.num_items,
+                "Item {item} is out of range for {} items.",
+                self.num_items
+            );
+            self.item_options[item].push(index);
+        }
+        self.options.push(items);
+        index
+    }
+
+    pub fn options(&self) -> &[Vec<usize>] {
+        &self.options
+    }
+
+    /// The indices of the options of a solution, or `None` if there is none.
+    pub fn solve(&self) -> Option<Vec<usize>> {
+        search::solve(State::new(self)).map(|state| state.chosen)
+    }
+
+    /// Counts solutions, stopping once `limit` have been found.
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        search::count_solutions(State::new(self), limit)
+    }
+}
+
+#[derive(Clone)]
+struct State<'a> {
+    problem: &'a ExactCover,
+    covered: Vec<bool>,
+    /// Whether each option can still be chosen, that is it is not chosen and covers no covered item.
+    available: Vec<bool>,
+    chosen: Vec<usize>,
+}
+
+impl<'a> State<'a> {
+    fn new(problem: &'a ExactCover) -> Self {
+        Self {
+            problem,
+            covered: vec![false; problem.num_items],
+            available: vec![true; problem.options.len()],
+            chosen: vec![],
+        }
+    }
+
+    fn choose(&mut self, option: usize) {
+        for &item in self.problem.options[option].iter() {
+            self.covered[item] = true;
+            for &other in self.problem.item_options[item].iter() {
+                self.available[other] = false;
+            }
+        }
+        self.chosen.push(option);
+    }
+
+    fn available_options(&self, item: usize) -> impl Iterator<Item = usize> + '_ {
+        self.problem.item_options[item]
+            .iter()
+            .copied()
+            .filter(|&option| self.available[option])
+    }
+}
+
+impl SearchState for State<'_> {
+    /// Chooses the options that are the only way left to cover an item.
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = false;
+            for item in 0..self.problem.num_items {
+                if self.covered[item] {
+                    continue;
+                }
+                match self.available_options(item).collect::<Vec<_>>()[..] {
+                    [] => return Err(Contradiction),
+                    [option] => {
+                        self.choose(option);
+                        changed = true;
+                    }
+                    _ => {}
+                }
+            }
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(item) = (0..self.problem.num_items)
+            .filter(|&item| !self.covered[item])
+            .min_by_key(|&item| self.available_options(item).count())
+        else {
+            return vec![];
+        };
+        self.available_options(item)
+            .map(|option| {
+                let mut state = self.clone();
+                state.choose(option);
+                state
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn knuth_example() {
+        let mut problem = ExactCover::new(7);
+        for option in [
+            vec![2, 4, 5],
+            vec![0, 3, 6],
+            vec![1, 2, 5],
+            vec![0, 3],
+            vec![1, 6],
+            vec![3, 4, 6],
+        ] {
+            problem.add_option(option);
+        }
+        let mut solution = problem.solve().unwrap();
+        solution.sort();
+        assert_eq!(solution, vec![0, 3, 4]);
+        assert_eq!(problem.count_solutions(2), 1);
+    }
+}
@@ -0,0 +1,164 @@
+//! A C interface to the solvers, for embedding them in programs written in other languages.
+//! Strings go in as NUL terminated UTF-8 and come out in buffers owned by the caller, so no memory changes hands.
+
+use std::{
+    ffi::{c_char, c_int, CStr},
+    panic::{self, AssertUnwindSafe},
+    slice,
+};
+
+use crate::{
+    camping,
+    error::{Error, Result},
+    puzzle,
+    sudoku::Sudoku,
+};
+
+/// The puzzle was solved and the solution written to the output buffer.
+pub const PUZZLES_OK: c_int = 0;
+/// A pointer was null or the input was not UTF-8.
+pub const PUZZLES_INVALID_ARGUMENT: c_int = 1;
+/// The input could not be read as a puzzle, or breaks its rules.
+pub const PUZZLES_INVALID_PUZZLE: c_int = 2;
+/// The puzzle follows the rules but has no solution.
+pub const PUZZLES_NO_SOLUTION: c_int = 3;
+/// The output buffer cannot hold the solution. Its required length is still written.
+pub const PUZZLES_BUFFER_TOO_SMALL: c_int = 4;
+/// The solver panicked. The panic is caught so that it never unwinds into the caller.
+pub const PUZZLES_INTERNAL_ERROR: c_int = 5;
+
+fn error_code(err: &Error) -> c_int {
+    match err {
+        Error::NoSolution(_) => PUZZLES_NO_SOLUTION,
+        _ => PUZZLES_INVALID_PUZZLE,
+    }
+}
+
+/// Reads the input, solves it and writes the result, NUL terminated, to `out`. `out_len` holds the capacity of `out`
+/// on entry and the length of the result without the NUL on return. Panics are caught and reported as
+/// [`PUZZLES_INTERNAL_ERROR`], since unwinding out of an `extern "C"` function aborts the process.
+///
+/// # Safety
+///
+/// See [`puzzles_solve_sudoku`].
+unsafe fn solve_into(
+    input: *const c_char,
+    out: *mut c_char,
+    out_len: *mut usize,
+    solve: impl FnOnce(&str) -> Result<String>,
+) -> c_int {
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        if input.is_null() || out.is_null() || out_len.is_null() {
+            return PUZZLES_INVALID_ARGUMENT;
+        }
+        let Ok(input) = CStr::from_ptr(input).to_str() else {
+            return PUZZLES_INVALID_ARGUMENT;
+        };
+        let solution = match solve(input) {
+            Ok(solution) => solution,
+            Err(err) => return error_code(&err),
+        };
+        let capacity = *out_len;
+        *out_len = solution.len();
+        if solution.len() >= capacity {
+            return PUZZLES_BUFFER_TOO_SMALL;
+        }
+        let out = slice::from_raw_parts_mut(out.cast::<u8>(), capacity);
+        out[..solution.len()].copy_from_slice(solution.as_bytes());
+        out[solution.len()] = 0;
+        PUZZLES_OK
+    }))
+    .unwrap_or(PUZZLES_INTERNAL_ERROR)
+}
+
+/// Solves a sudoku given as an 81 character line with '.' for empty cells and writes the solution in the same format
+/// to `out`. `out_len` holds the capacity of `out` on entry and the length of the solution on return. Returns one of
+/// the `PUZZLES_` codes.
+///
+/// # Safety
+///
+/// `line` must be null or a NUL terminated string, `out` must be null or valid for writes of `*out_len` bytes, and
+/// `out_len` must be null or valid for reads and writes.
+#[no_mangle]
+pub unsafe extern "C" fn puzzles_solve_sudoku(
+    line: *const c_char,
+    out: *mut c_char,
+    out_len: *mut usize,
+) -> c_int {
+    solve_into(line, out, out_len, puzzle::solve_str::<Sudoku>)
+}
+
+/// Solves a camping map given in its JSON form and writes the solved map as JSON to `out`, like
+/// [`puzzles_solve_sudoku`].
+///
+/// # Safety
+///
+/// See [`puzzles_solve_sudoku`].
+#[no_mangle]
+pub unsafe extern "C" fn puzzles_solve_camping(
+    json: *const c_char,
+    out: *mut c_char,
+    out_len: *mut usize,
+) -> c_int {
+    solve_into(json, out, out_len, camping::solve_json)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ffi::CString, ptr};
+
+    use super::*;
+
+    fn solve_sudoku(line: &str, capacity: usize) -> (c_int, usize, String) {
+        let line = CString::new(line).unwrap();
+        let mut out = vec![0 as c_char; capacity];
+        let mut out_len = capacity;
+        let code = unsafe { puzzles_solve_sudoku(line.as_ptr(), out.as_mut_ptr(), &mut out_len) };
+        let solution = unsafe { CStr::from_ptr(out.as_ptr()) };
+        (code, out_len, solution.to_str().unwrap().to_string())
+    }
+
+    #[test]
+    fn solves_through_buffers() {
+        let line = include_str!("../data/sudoku/grids/easy50.txt")
+            .lines()
+            .next()
+            .unwrap();
+        let (code, len, solution) = solve_sudoku(line, 82);
+        assert_eq!((code, len), (PUZZLES_OK, 81));
+        assert!(!solution.contains('.'));
+        assert_eq!(solve_sudoku(line, 81).0, PUZZLES_BUFFER_TOO_SMALL);
+        assert_eq!(solve_sudoku("123", 82).0, PUZZLES_INVALID_PUZZLE);
+        let mut out_len = 0;
+        let code = unsafe { puzzles_solve_camping(ptr::null(), ptr::null_mut(), &mut out_len) };
+        assert_eq!(code, PUZZLES_INVALID_ARGUMENT);
+    }
+
+    #[test]
+    fn rejects_requirements_that_do_not_fit_the_tiles() {
+        let tiles = crate::grid::Grid::from_elem((2, 2), camping::Tile::Free);
+        let map = serde_json::json!({
+            "tiles": tiles,
+            "row_requirements": ndarray::Array1::from_elem(1, 0usize),
+            "col_requirements": ndarray::Array1::from_elem(2, 0usize),
+        });
+        let json = CString::new(map.to_string()).unwrap();
+        let mut out = vec![0 as c_char; 256];
+        let mut out_len = out.len();
+        let code = unsafe { puzzles_solve_camping(json.as_ptr(), out.as_mut_ptr(), &mut out_len) };
+        assert_eq!(code, PUZZLES_INVALID_PUZZLE);
+    }
+
+    #[test]
+    fn catches_solver_panics() {
+        let input = CString::new("").unwrap();
+        let mut out = vec![0 as c_char; 8];
+        let mut out_len = out.len();
+        let code = unsafe {
+            solve_into(input.as_ptr(), out.as_mut_ptr(), &mut out_len, |_| {
+                panic!("solver bug")
+            })
+        };
+        assert_eq!(code, PUZZLES_INTERNAL_ERROR);
+    }
+}
@@ -0,0 +1,26 @@
+mod board;
+pub use board::Board;
+mod solver;
+pub use solver::{count_solutions, solve};
+
+/// Fill-a-pix puzzles as a [`Puzzle`](crate::puzzle::Puzzle), in the text format of [`Board::parse`].
+pub struct Fillapix;
+
+impl crate::puzzle::Puzzle for Fillapix {
+    const NAME: &'static str = "fill-a-pix puzzle";
+
+    type Instance = Board;
+    type Solution = crate::shading::ShadeGrid;
+
+    fn parse(input: &str) -> crate::Result<Board> {
+        Board::parse(input)
+    }
+
+    fn solve(board: &Board) -> crate::Result<Option<crate::shading::ShadeGrid>> {
+        Ok(solve(board))
+    }
+
+    fn count_solutions(board: &Board, limit: usize) -> crate::Result<usize> {
+        Ok(count_solutions(board, limit))
+    }
+}
@@ -0,0 +1,132 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+};
+
+/// A fill-a-pix puzzle: shade cells such that every clue equals the number of shaded cells in the 3x3 block
+/// centred on it, counting the clue's own cell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    clues: Grid<Option<u32>>,
+}
+
+impl Board {
+    pub fn new(clues: Grid<Option<u32>>) -> Result<Self> {
+        let (height, width) = clues.dim();
+        ensure!(
+            height > 0 && width > 0,
+            Invalid,
+            "Fill-a-pix grids must have at least one cell."
+        );
+        for loc in Location::grid_iter(clues.dim()) {
+            let Some(clue) = clues[loc] else {
+                continue;
+            };
+            let size = block(clues.dim(), loc).len();
+            ensure!(
+                clue as usize <= size,
+                Invalid,
+                "The clue at ({}, {}) is {clue}, but its block only has {size} cells.",
+                loc.row,
+                loc.col
+            );
+        }
+        Ok(Self { clues })
+    }
+
+    /// Parses a grid of digits with `.` for cells without a clue.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        let mut clues = Grid::from_elem((height, width), None);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.chars().count() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} characters in row {row_index}. Got '{row}'."
+                );
+            }
+            for (col_index, c) in row.chars().enumerate() {
+                clues[Location::new(row_index, col_index)] = match c {
+                    '.' => None,
+                    _ => Some(c.to_digit(10).ok_or_else(|| {
+                        Error::Parse(format!(
+                            "Invalid character '{c}' at ({row_index}, {col_index})."
+                        ))
+                    })?),
+                };
+            }
+        }
+        Self::new(clues)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("fill-a-pix", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.clues.dim()
+    }
+
+    pub fn clue(&self, location: Location) -> Option<u32> {
+        self.clues[location]
+    }
+
+    /// The cells of the 3x3 block centred on `location` that lie inside the grid.
+    pub fn block(&self, location: Location) -> Vec<Location> {
+        block(self.dim(), location)
+    }
+}
+
+fn block(dim: (usize, usize), location: Location) -> Vec<Location> {
+    let mut block = location
+        .neighbors(dim)
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    block.push(location);
+    block
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.clues.rows() {
+            let row = row
+                .map(|clue| clue.map_or('.', |clue| char::from_digit(clue, 10).unwrap()))
+                .collect::<String>();
+            writeln!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_clues_and_blocks() {
+        let board = Board::parse(include_str!("../../data/fillapix/puzzles/ten01.txt")).unwrap();
+        assert_eq!(board.dim(), (10, 10));
+        assert_eq!(board.clue(Location::new(0, 0)), Some(2));
+        assert_eq!(board.clue(Location::new(0, 1)), None);
+        assert_eq!(board.block(Location::new(0, 0)).len(), 4);
+        assert_eq!(board.block(Location::new(0, 1)).len(), 6);
+        assert_eq!(board.block(Location::new(5, 5)).len(), 9);
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        assert!(Board::parse("5.\n..\n").is_err());
+        assert!(Board::parse("4.\n.\n").is_err());
+        assert!(Board::parse("4x\n..\n").is_err());
+    }
+}
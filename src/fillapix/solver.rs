@@ -0,0 +1,197 @@
+use crate::{
+    location::Location,
+    search::{self, Contradiction, SearchState},
+    shading::{Shade, ShadeGrid},
+};
+
+use super::Board;
+
+/// Exactly `target` of `cells` are shaded.
+#[derive(Debug)]
+struct Block {
+    cells: Vec<Location>,
+    target: usize,
+}
+
+/// The block of every clue and every pair of clues whose blocks overlap.
+#[derive(Debug)]
+struct Layout {
+    blocks: Vec<Block>,
+    overlaps: Vec<(usize, usize)>,
+}
+
+impl Layout {
+    fn new(board: &Board) -> Self {
+        let blocks = Location::grid_iter(board.dim())
+            .filter_map(|loc| {
+                let target = board.clue(loc)? as usize;
+                Some(Block {
+                    cells: board.block(loc),
+                    target,
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut overlaps = vec![];
+        for first in 0..blocks.len() {
+            for second in first + 1..blocks.len() {
+                if blocks[first]
+                    .cells
+                    .iter()
+                    .any(|cell| blocks[second].cells.contains(cell))
+                {
+                    overlaps.push((first, second));
+                }
+            }
+        }
+        Self { blocks, overlaps }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct State<'a> {
+    layout: &'a Layout,
+    grid: ShadeGrid,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &Board, layout: &'a Layout) -> Self {
+        Self {
+            layout,
+            grid: ShadeGrid::new(board.dim()),
+        }
+    }
+
+    /// Every block must have exactly its clue's number of shaded cells.
+    fn apply_blocks(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for block in self.layout.blocks.iter() {
+            changed |= self.grid.apply_count(&block.cells, block.target)?;
+        }
+        Ok(changed)
+    }
+
+    /// Two overlapping blocks bound how many shaded cells their shared part can hold: at least what either block
+    /// cannot fit in its own part, and at most what either still needs. Whatever those bounds leave for the
+    /// unshared parts may decide them, as when one block's undecided cells are a subset of the other's.
+    fn apply_overlaps(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for &(first, second) in self.layout.overlaps.iter() {
+            let (first, second) = (&self.layout.blocks[first], &self.layout.blocks[second]);
+            let needed = |block: &Block| {
+                let shaded = block
+                    .cells
+                    .iter()
+                    .filter(|&&cell| self.grid.is_shaded(cell))
+                    .count();
+                block.target.checked_sub(shaded).ok_or(Contradiction)
+            };
+            let undecided = |block: &Block, other: &Block, shared: bool| {
+                block
+                    .cells
+                    .iter()
+                    .copied()
+                    .filter(|&cell| {
+                        self.grid.get(cell) == Shade::Unknown
+                            && other.cells.contains(&cell) == shared
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let (first_needed, second_needed) = (needed(first)?, needed(second)?);
+            let first_only = undecided(first, second, false);
+            let second_only = undecided(second, first, false);
+            let shared = undecided(first, second, true);
+            let low = first_needed
+                .saturating_sub(first_only.len())
+                .max(second_needed.saturating_sub(second_only.len()));
+            let high = shared.len().min(first_needed).min(second_needed);
+            if low > high {
+                return Err(Contradiction);
+            }
+            let mut fill = Vec::new();
+            for (only, needed) in [(first_only, first_needed), (second_only, second_needed)] {
+                if needed == low {
+                    fill.extend(only.iter().map(|&cell| (cell, Shade::Unshaded)));
+                } else if needed - high == only.len() {
+                    fill.extend(only.iter().map(|&cell| (cell, Shade::Shaded)));
+                }
+            }
+            if low == shared.len() {
+                fill.extend(shared.iter().map(|&cell| (cell, Shade::Shaded)));
+            } else if high == 0 {
+                fill.extend(shared.iter().map(|&cell| (cell, Shade::Unshaded)));
+            }
+            for (cell, shade) in fill {
+                changed |= self.grid.set(cell, shade)?;
+            }
+        }
+        Ok(changed)
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.apply_blocks()?;
+            if !changed {
+                changed |= self.apply_overlaps()?;
+            }
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(location) = self.grid.first_unknown() else {
+            return vec![];
+        };
+        self.grid
+            .branch_on(location)
+            .into_iter()
+            .map(|grid| Self {
+                grid,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+/// Returns the solved grid with shaded cells as `#` and unshaded cells as `.`,
+/// or `None` if the puzzle has no solution.
+pub fn solve(board: &Board) -> Option<ShadeGrid> {
+    let layout = Layout::new(board);
+    search::solve(State::new(board, &layout)).map(|state| state.grid)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    let layout = Layout::new(board);
+    search::count_solutions(State::new(board, &layout), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follows_rules(board: &Board, grid: &ShadeGrid) -> bool {
+        let dim = board.dim();
+        Location::grid_iter(dim).all(|loc| {
+            board.clue(loc).is_none_or(|clue| {
+                let neighbors = loc.neighbors(dim).into_iter().flatten();
+                let shaded = neighbors
+                    .chain([loc])
+                    .filter(|&other| grid.is_shaded(other))
+                    .count();
+                shaded == clue as usize
+            })
+        })
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/fillapix/puzzles/ten01.txt")).unwrap();
+        let grid = solve(&board).unwrap();
+        assert!(grid.is_complete());
+        assert!(follows_rules(&board, &grid));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
@@ -0,0 +1,148 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+    value_set::ValueSet,
+};
+
+/// A fillomino puzzle: write a number in every cell such that every orthogonally connected region of equal numbers
+/// has as many cells as its number. Regions of the same size therefore never touch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    numbers: Grid<Option<u8>>,
+}
+
+impl Board {
+    pub fn new(numbers: Grid<Option<u8>>) -> Result<Self> {
+        let (height, width) = numbers.dim();
+        ensure!(
+            height > 0 && width > 0,
+            Invalid,
+            "Fillomino grids must have at least one cell."
+        );
+        if let Some(number) = numbers
+            .iter()
+            .flatten()
+            .find(|&&number| number == 0 || number > ValueSet::MAX_VALUE)
+        {
+            bail!(
+                Invalid,
+                "Numbers must be between 1 and {}, but got {number}.",
+                ValueSet::MAX_VALUE
+            );
+        }
+        Ok(Self { numbers })
+    }
+
+    /// Parses a grid of numbers with `.` for empty cells. Rows are either strings of digits and dots or,
+    /// for numbers above 9, whitespace separated numbers and dots.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let parse_token = |token: &str| -> Result<Option<u8>> {
+            match token {
+                "." => Ok(None),
+                _ => token
+                    .parse()
+                    .map(Some)
+                    .map_err(|_| Error::Parse(format!("Invalid cell '{token}'."))),
+            }
+        };
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                if line.contains(char::is_whitespace) {
+                    line.split_whitespace().map(parse_token).collect()
+                } else {
+                    line.chars()
+                        .map(|c| parse_token(c.encode_utf8(&mut [0; 4])))
+                        .collect::<Result<Vec<_>>>()
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        let mut numbers = Grid::from_elem((height, width), None);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} cells in row {row_index}. Got {}.",
+                    row.len()
+                );
+            }
+            for (col_index, number) in row.into_iter().enumerate() {
+                numbers[Location::new(row_index, col_index)] = number;
+            }
+        }
+        Self::new(numbers)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("fillomino", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.numbers.dim()
+    }
+
+    pub fn number(&self, location: Location) -> Option<u8> {
+        self.numbers[location]
+    }
+
+    /// The largest region size the solver considers: the largest given number, but at least 9.
+    pub fn max_size(&self) -> u8 {
+        self.numbers
+            .iter()
+            .flatten()
+            .copied()
+            .max()
+            .unwrap_or(0)
+            .max(9)
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let wide = self.numbers.iter().flatten().any(|&number| number > 9);
+        for row in self.numbers.rows() {
+            for (index, number) in row.enumerate() {
+                if wide && index > 0 {
+                    write!(f, " ")?;
+                }
+                match number {
+                    Some(number) => write!(f, "{number}")?,
+                    None => write!(f, ".")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_digits_and_wide_numbers() {
+        let board = Board::parse(include_str!("../../data/fillomino/puzzles/eight01.txt")).unwrap();
+        assert_eq!(board.dim(), (8, 8));
+        assert_eq!(board.number(Location::new(0, 0)), Some(4));
+        assert_eq!(board.number(Location::new(0, 1)), None);
+        assert_eq!(board.max_size(), 9);
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        let wide = Board::parse(". 12\n3 .\n").unwrap();
+        assert_eq!(wide.number(Location::new(0, 1)), Some(12));
+        assert_eq!(wide.max_size(), 12);
+        assert_eq!(Board::parse(wide.to_string()).unwrap(), wide);
+        assert!(Board::parse("0.\n..\n").is_err());
+        assert!(Board::parse("1.\n.\n").is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use std::{collections::VecDeque, fmt::Display};
+
+use crate::{
+    grid::Grid,
+    location::Location,
+    region,
+    search::{self, Contradiction, SearchState},
+    value_set::ValueSet,
+};
+
+use super::Board;
+
+/// The numbers of a solved puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    numbers: Grid<u8>,
+}
+
+impl Solution {
+    pub fn number(&self, location: Location) -> u8 {
+        self.numbers[location]
+    }
+}
+
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let wide = self.numbers.iter().any(|&number| number > 9);
+        for row in self.numbers.rows() {
+            let row = row.map(u8::to_string).collect::<Vec<_>>();
+            writeln!(f, "{}", row.join(if wide { " " } else { "" }))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct State {
+    cells: Grid<ValueSet>,
+}
+
+impl State {
+    fn new(board: &Board) -> Self {
+        let all = ValueSet::all(board.max_size());
+        let cells = Grid::from_fn(board.dim(), |loc| {
+            board.number(loc).map_or(all, ValueSet::from_value)
+        });
+        Self { cells }
+    }
+
+    fn candidates(&self, location: Location) -> ValueSet {
+        self.cells[location]
+    }
+
+    fn value(&self, location: Location) -> Option<u8> {
+        self.candidates(location).single()
+    }
+
+    fn restrict(&mut self, location: Location, allowed: ValueSet) -> Result<bool, Contradiction> {
+        let cell = &mut self.cells[location];
+        let restricted = *cell & allowed;
+        if restricted.is_empty() {
+            return Err(Contradiction);
+        }
+        let changed = restricted != *cell;
+        *cell = restricted;
+        Ok(changed)
+    }
+
+    /// The number of cells that can be reached from `start` through cells that can hold `number`, counting at most `number`.
+    fn reach(&self, start: &[Location], number: u8) -> usize {
+        let dim = self.cells.dim();
+        let mut visited = Grid::from_elem(dim, false);
+        for loc in start {
+            visited[*loc] = true;
+        }
+        let mut count = start.len();
+        let mut queue = start.iter().copied().collect::<VecDeque<_>>();
+        while let Some(loc) = queue.pop_front() {
+            if count >= number as usize {
+                break;
+            }
+            for adj in loc.adjacents(dim).into_iter().flatten() {
+                if !visited[adj] && self.candidates(adj).contains(number) {
+                    visited[adj] = true;
+                    count += 1;
+                    queue.push_back(adj);
+                }
+            }
+        }
+        count
+    }
+
+    /// Closes off complete regions, extends regions with only one way to grow,
+    /// and fails if a region is too large or cannot grow large enough.
+    fn apply_regions(&mut self) -> Result<bool, Contradiction> {
+        let dim = self.cells.dim();
+        let mut changed = false;
+        for number in 1..=ValueSet::MAX_VALUE {
+            let regions = region::components(dim, |loc| self.value(loc) == Some(number));
+            for region in regions.regions {
+                let size = region.len();
+                if size > number as usize {
+                    return Err(Contradiction);
+                }
+                let open = region::boundary(dim, &region)
+                    .into_iter()
+                    .filter(|&loc| self.candidates(loc).contains(number))
+                    .collect::<Vec<_>>();
+                if size == number as usize {
+                    for loc in region::boundary(dim, &region) {
+                        let allowed = self.candidates(loc) - ValueSet::from_value(number);
+                        changed |= self.restrict(loc, allowed)?;
+                    }
+                } else if self.reach(&region, number) < number as usize {
+                    return Err(Contradiction);
+                } else if let [loc] = open[..] {
+                    changed |= self.restrict(loc, ValueSet::from_value(number))?;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Removes the numbers an undecided cell cannot hold because no region of that size fits around it.
+    fn apply_space(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for loc in Location::grid_iter(self.cells.dim()) {
+            let candidates = self.candidates(loc);
+            if candidates.len() < 2 {
+                continue;
+            }
+            let fitting = candidates
+                .iter()
+                .filter(|&number| self.reach(&[loc], number) >= number as usize)
+                .collect();
+            changed |= self.restrict(loc, fitting)?;
+        }
+        Ok(changed)
+    }
+}
+
+impl SearchState for State {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.apply_regions()?;
+            changed |= self.apply_space()?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let dim = self.cells.dim();
+        // Prefer cells next to decided cells, as deciding those grows or closes off regions.
+        let Some(location) = Location::grid_iter(dim)
+            .filter(|&loc| self.candidates(loc).len() > 1)
+            .min_by_key(|&loc| {
+                let isolated = loc
+                    .adjacents(dim)
+                    .into_iter()
+                    .flatten()
+                    .all(|adj| self.value(adj).is_none());
+                (isolated, self.candidates(loc).len())
+            })
+        else {
+            return vec![];
+        };
+        self.candidates(location)
+            .iter()
+            .map(|number| {
+                let mut state = self.clone();
+                state.cells[location] = ValueSet::from_value(number);
+                state
+            })
+            .collect()
+    }
+}
+
+pub fn solve(board: &Board) -> Option<Solution> {
+    search::solve(State::new(board)).map(|state| Solution {
+        numbers: state
+            .cells
+            .map(|cell| cell.single().expect("Solved cells have a single number.")),
+    })
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    search::count_solutions(State::new(board), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follows_rules(board: &Board, solution: &Solution) -> bool {
+        let dim = board.dim();
+        let givens = Location::grid_iter(dim).all(|loc| {
+            board
+                .number(loc)
+                .is_none_or(|number| number == solution.number(loc))
+        });
+        let regions = (1..=board.max_size()).all(|number| {
+            region::components(dim, |loc| solution.number(loc) == number)
+                .regions
+                .iter()
+                .all(|region| region.len() == number as usize)
+        });
+        givens && regions
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/fillomino/puzzles/eight01.txt")).unwrap();
+        let solution = solve(&board).unwrap();
+        assert!(follows_rules(&board, &solution));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
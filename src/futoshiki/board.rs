@@ -0,0 +1,203 @@
+use std::{cmp::Ordering, fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+};
+
+/// The value in `smaller` must be less than the value in `larger`. The two cells are always adjacent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Inequality {
+    pub smaller: Location,
+    pub larger: Location,
+}
+
+/// A futoshiki puzzle: a Latin square with some values given and inequalities between some adjacent cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    givens: Grid<Option<u8>>,
+    inequalities: Vec<Inequality>,
+}
+
+impl Board {
+    pub const MAX_SIZE: usize = 9;
+
+    pub fn new(givens: Grid<Option<u8>>, inequalities: Vec<Inequality>) -> Result<Self> {
+        let (height, width) = givens.dim();
+        ensure!(
+            height == width,
+            Invalid,
+            "Futoshiki grids must be square, but got {height}x{width}."
+        );
+        let size = height;
+        ensure!(
+            (1..=Self::MAX_SIZE).contains(&size),
+            Invalid,
+            "Futoshiki grids can be at most {0}x{0}, but got {size}x{size}.",
+            Self::MAX_SIZE
+        );
+        if let Some(given) = givens
+            .iter()
+            .flatten()
+            .find(|&&given| given == 0 || given as usize > size)
+        {
+            bail!(
+                Invalid,
+                "The given value {given} is not between 1 and {size}."
+            );
+        }
+        for &Inequality { smaller, larger } in inequalities.iter() {
+            ensure!(
+                smaller.adjacents((size, size)).contains(&Some(larger)), Invalid,
+                "Inequalities must be between adjacent cells, but {smaller} and {larger} are not adjacent."
+            );
+        }
+        Ok(Self {
+            givens,
+            inequalities,
+        })
+    }
+
+    /// Parses the standard layout, where rows of cells alternate with rows of vertical inequalities.
+    ///
+    /// Cells are digits or `.` for empty cells and are separated by a space, `<` or `>`.
+    /// Below each cell, the line between two rows of cells has `^` if the cell is less than the one below it,
+    /// `v` if it is greater and a space otherwise.
+    /// ```text
+    /// . < .   .
+    ///         v
+    /// .   2   .
+    /// ^
+    /// . > .   .
+    /// ```
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let mut lines = string
+            .as_ref()
+            .lines()
+            .map(|line| line.trim_end().chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        while lines.last().is_some_and(Vec::is_empty) {
+            lines.pop();
+        }
+        ensure!(!lines.is_empty(), Parse, "The puzzle is empty.");
+        ensure!(
+            lines.len() % 2 == 1,
+            Parse,
+            "Expected an odd number of lines, but got {}.",
+            lines.len()
+        );
+        let size = lines.len() / 2 + 1;
+        let mut givens = Grid::from_elem((size, size), None);
+        let mut inequalities = vec![];
+        for (line_index, line) in lines.iter().enumerate() {
+            ensure!(
+                line.len() < 2 * size,
+                Parse,
+                "Line {line_index} is longer than {} characters.",
+                2 * size - 1
+            );
+            let row = line_index / 2;
+            for (char_index, &c) in line.iter().enumerate() {
+                let col = char_index / 2;
+                let location = Location::new(row, col);
+                match (line_index % 2 == 0, char_index % 2 == 0, c) {
+                    (_, _, ' ') | (true, true, '.') => {}
+                    (true, true, _) => {
+                        let value = c.to_digit(10).ok_or_else(|| {
+                            Error::Parse(format!("Invalid cell '{c}' at {location}."))
+                        })?;
+                        givens[Location::new(row, col)] = Some(value as u8);
+                    }
+                    (true, false, '<') => inequalities.push(Inequality {
+                        smaller: location,
+                        larger: Location::new(row, col + 1),
+                    }),
+                    (true, false, '>') => inequalities.push(Inequality {
+                        smaller: Location::new(row, col + 1),
+                        larger: location,
+                    }),
+                    (false, true, '^') => inequalities.push(Inequality {
+                        smaller: location,
+                        larger: Location::new(row + 1, col),
+                    }),
+                    (false, true, 'v') => inequalities.push(Inequality {
+                        smaller: Location::new(row + 1, col),
+                        larger: location,
+                    }),
+                    _ => bail!(
+                        Parse,
+                        "Unexpected character '{c}' at line {line_index}, column {char_index}."
+                    ),
+                }
+            }
+        }
+        Self::new(givens, inequalities)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("futoshiki", path)?;
+        Self::parse(string)
+    }
+
+    pub fn size(&self) -> usize {
+        self.givens.height()
+    }
+
+    pub fn given(&self, location: Location) -> Option<u8> {
+        self.givens[location]
+    }
+
+    pub fn inequalities(&self) -> &[Inequality] {
+        &self.inequalities
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let size = self.size();
+        let mut lines = vec![vec![' '; 2 * size - 1]; 2 * size - 1];
+        for loc in Location::grid_iter((size, size)) {
+            lines[2 * loc.row][2 * loc.col] =
+                self.given(loc).map_or('.', |value| (b'0' + value) as char);
+        }
+        for &Inequality { smaller, larger } in self.inequalities.iter() {
+            let (row, col) = (smaller.row + larger.row, smaller.col + larger.col);
+            lines[row][col] = match (larger.row.cmp(&smaller.row), larger.col.cmp(&smaller.col)) {
+                (_, Ordering::Greater) => '<',
+                (_, Ordering::Less) => '>',
+                (Ordering::Greater, _) => '^',
+                _ => 'v',
+            };
+        }
+        for line in lines {
+            writeln!(f, "{}", line.into_iter().collect::<String>().trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_inequalities_in_both_directions() {
+        let board = Board::parse(include_str!("../../data/futoshiki/puzzles/five01.txt")).unwrap();
+        assert_eq!(board.size(), 5);
+        assert_eq!(board.given(Location::new(0, 2)), Some(4));
+        // `>` in the second row and `v` below the third.
+        assert!(board.inequalities().contains(&Inequality {
+            smaller: Location::new(1, 1),
+            larger: Location::new(1, 0),
+        }));
+        assert!(board.inequalities().contains(&Inequality {
+            smaller: Location::new(3, 0),
+            larger: Location::new(2, 0),
+        }));
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        assert!(Board::parse(". .\n").is_err());
+        assert!(Board::parse(".x.\n\n. .\n").is_err());
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{
+    latin::LatinSquare,
+    location::Location,
+    search::{self, Contradiction, SearchState},
+    value_set::ValueSet,
+};
+
+use super::{Board, Inequality};
+
+/// Bounds both sides of an inequality by the extremes of the other side.
+fn apply_inequality(
+    square: &mut LatinSquare,
+    inequality: Inequality,
+) -> Result<bool, Contradiction> {
+    let size = square.size() as u8;
+    let Inequality { smaller, larger } = inequality;
+    let largest = square.candidates(larger).max().ok_or(Contradiction)?;
+    let mut changed = square.restrict(smaller, ValueSet::range(1, largest - 1))?;
+    let smallest = square.candidates(smaller).min().ok_or(Contradiction)?;
+    changed |= square.restrict(larger, ValueSet::range(smallest + 1, size))?;
+    Ok(changed)
+}
+
+#[derive(Clone, Debug)]
+struct State<'a> {
+    inequalities: &'a [Inequality],
+    square: LatinSquare,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board) -> Result<Self, Contradiction> {
+        let mut square = LatinSquare::new(board.size());
+        for loc in Location::grid_iter(square.dim()) {
+            if let Some(given) = board.given(loc) {
+                square.assign(loc, given)?;
+            }
+        }
+        Ok(Self {
+            inequalities: board.inequalities(),
+            square,
+        })
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.square.propagate()?;
+            for &inequality in self.inequalities {
+                changed |= apply_inequality(&mut self.square, inequality)?;
+            }
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(location) = self.square.branch_location() else {
+            return vec![];
+        };
+        self.square
+            .branch_on(location)
+            .into_iter()
+            .map(|square| Self {
+                square,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+/// Returns the solved grid, or `None` if the puzzle has no solution.
+pub fn solve(board: &Board) -> Option<LatinSquare> {
+    let state = State::new(board).ok()?;
+    search::solve(state).map(|state| state.square)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    State::new(board).map_or(0, |state| search::count_solutions(state, limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solves_data_puzzles() {
+        for input in [
+            include_str!("../../data/futoshiki/puzzles/five01.txt"),
+            include_str!("../../data/futoshiki/puzzles/seven01.txt"),
+        ] {
+            let board = Board::parse(input).unwrap();
+            let values = solve(&board).unwrap().values().unwrap();
+            assert!(crate::latin::is_latin(&values));
+            assert!(Location::grid_iter(values.dim())
+                .all(|loc| board.given(loc).is_none_or(|given| given == values[loc])));
+            assert!(board
+                .inequalities()
+                .iter()
+                .all(|inequality| values[inequality.smaller] < values[inequality.larger]));
+            assert_eq!(count_solutions(&board, 2), 1);
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Result},
+    grid::Grid,
+    location::Location,
+    puzzlink::PuzzLink,
+};
+
+/// A galaxies puzzle: divide the grid into orthogonally connected regions such that every region contains exactly
+/// one centre and is unchanged by a half turn around it.
+///
+/// Centres may sit in a cell, on an edge between two cells or on a corner between four cells, so they are given in
+/// doubled coordinates: the cell at `(row, col)` is at `(2 * row, 2 * col)`, and the positions in between are the
+/// edges and corners.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    dim: (usize, usize),
+    centers: Vec<Location>,
+}
+
+impl Board {
+    pub fn new(dim: (usize, usize), centers: Vec<Location>) -> Result<Self> {
+        let (height, width) = dim;
+        ensure!(
+            height > 0 && width > 0,
+            Invalid,
+            "Galaxies grids must have at least one cell."
+        );
+        ensure!(
+            !centers.is_empty(),
+            Invalid,
+            "There must be at least one centre."
+        );
+        let mut covered = Grid::from_elem(dim, false);
+        for &center in centers.iter() {
+            ensure!(
+                center.row < 2 * height - 1 && center.col < 2 * width - 1,
+                Invalid,
+                "The centre at ({}, {}) is outside the grid.",
+                center.row,
+                center.col
+            );
+            for cell in cells_at(center) {
+                ensure!(
+                    !covered[cell],
+                    Invalid,
+                    "More than one centre touches the cell at ({}, {}).",
+                    cell.row,
+                    cell.col
+                );
+                covered[cell] = true;
+            }
+        }
+        Ok(Self { dim, centers })
+    }
+
+    /// Parses a grid of positions in doubled coordinates, with `o` for centres and `.` for everything else.
+    /// A puzzle with `height` rows and `width` columns of cells has `2 * height - 1` rows of `2 * width - 1`
+    /// characters, where the characters in even rows and columns are the cells.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        ensure!(
+            height % 2 == 1 && width % 2 == 1,
+            Parse,
+            "Expected an odd number of rows and columns, but got {height}x{width}."
+        );
+        let mut centers = vec![];
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.chars().count() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} characters in row {row_index}. Got '{row}'."
+                );
+            }
+            for (col_index, c) in row.chars().enumerate() {
+                match c {
+                    '.' => {}
+                    'o' => centers.push(Location::new(row_index, col_index)),
+                    _ => bail!(
+                        Parse,
+                        "Invalid character '{c}' at ({row_index}, {col_index})."
+                    ),
+                }
+            }
+        }
+        Self::new((height.div_ceil(2), width.div_ceil(2)), centers)
+    }
+
+    /// Imports a puzzle from a puzz.link or pzv.jp URL.
+    pub fn from_puzz_link(url: &str) -> Result<Self> {
+        let link = PuzzLink::parse(url)?;
+        link.expect_kind(&["tentaisho"])?;
+        let (height, width) = (link.height, link.width);
+        ensure!(
+            height > 0 && width > 0,
+            Parse,
+            "Galaxies grids must have at least one cell."
+        );
+        let positions = (2 * height - 1) * (2 * width - 1);
+        let mut centers = vec![];
+        let mut position = 0;
+        for c in link.body.chars() {
+            if position >= positions {
+                break;
+            }
+            // A hexadecimal digit places a centre and skips up to seven positions after it, while the letters
+            // from `g` skip positions without a centre. The lowest bit only tells white and black centres apart.
+            match c {
+                '0'..='9' | 'a'..='f' => {
+                    let digit = c.to_digit(16).unwrap() as usize;
+                    centers.push(Location::new(
+                        position / (2 * width - 1),
+                        position % (2 * width - 1),
+                    ));
+                    position += (digit >> 1) + 1;
+                }
+                'g'..='z' => position += c.to_digit(36).unwrap() as usize - 15,
+                _ => bail!(Parse, "Unexpected character '{c}' in the URL."),
+            }
+        }
+        Self::new((height, width), centers)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("galaxies", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.dim
+    }
+
+    /// The centres in doubled coordinates.
+    pub fn centers(&self) -> &[Location] {
+        &self.centers
+    }
+
+    /// The one, two or four cells that the centre at index `galaxy` touches.
+    pub fn center_cells(&self, galaxy: usize) -> Vec<Location> {
+        cells_at(self.centers[galaxy])
+    }
+
+    /// The cell that `location` turns into under a half turn around the centre at index `galaxy`,
+    /// or `None` if that is outside the grid.
+    pub fn partner(&self, galaxy: usize, location: Location) -> Option<Location> {
+        let center = self.centers[galaxy];
+        let partner = Location::new(
+            center.row.checked_sub(location.row)?,
+            center.col.checked_sub(location.col)?,
+        );
+        (partner.row < self.dim.0 && partner.col < self.dim.1).then_some(partner)
+    }
+}
+
+/// The cells touched by a position in doubled coordinates.
+fn cells_at(center: Location) -> Vec<Location> {
+    let rows = [center.row / 2, center.row.div_ceil(2)];
+    let cols = [center.col / 2, center.col.div_ceil(2)];
+    let mut cells = vec![];
+    for row in rows {
+        for col in cols {
+            let cell = Location::new(row, col);
+            if !cells.contains(&cell) {
+                cells.push(cell);
+            }
+        }
+    }
+    cells
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (height, width) = self.dim;
+        for row in 0..2 * height - 1 {
+            let row = (0..2 * width - 1)
+                .map(|col| {
+                    if self.centers.contains(&Location::new(row, col)) {
+                        'o'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>();
+            writeln!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_text_and_puzz_links() {
+        let board = Board::parse(include_str!("../../data/galaxies/puzzles/ten01.txt")).unwrap();
+        assert_eq!(board.dim(), (10, 10));
+        assert_eq!(
+            board.centers()[..2],
+            [Location::new(0, 0), Location::new(0, 9)]
+        );
+        assert_eq!(
+            board.center_cells(1),
+            [Location::new(0, 4), Location::new(0, 5)]
+        );
+        assert_eq!(
+            board.partner(1, Location::new(0, 3)),
+            Some(Location::new(0, 6))
+        );
+        assert_eq!(board.partner(1, Location::new(1, 3)), None);
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        let link =
+            "https://puzz.link/p?tentaisho/10/10/egaeel0aaezh24eg2ezheke46ezq6egetei8eiei62e22ezl66aeu420";
+        assert_eq!(Board::from_puzz_link(link).unwrap(), board);
+        assert!(Board::from_puzz_link("https://puzz.link/p?tentaisho/2/2/0!").is_err());
+        assert!(Board::parse("o.\n..\n").is_err());
+        assert!(Board::parse("oo.\n...\n...\n").is_err());
+        assert!(Board::parse("o..\n...\n..x\n").is_err());
+    }
+}
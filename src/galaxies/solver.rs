@@ -0,0 +1,196 @@
+use std::fmt::Display;
+
+use crate::{
+    grid::Grid,
+    location::Location,
+    search::{self, Contradiction, SearchState},
+};
+
+use super::Board;
+
+/// The galaxy of every cell in a solved puzzle, as indices into the board's centres.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    galaxies: Grid<usize>,
+}
+
+impl Solution {
+    pub fn galaxy(&self, location: Location) -> usize {
+        self.galaxies[location]
+    }
+}
+
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const NAMES: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+        for row in self.galaxies.rows() {
+            let row = row
+                .map(|&galaxy| NAMES[galaxy % NAMES.len()] as char)
+                .collect::<String>();
+            writeln!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct State<'a> {
+    board: &'a Board,
+    /// The galaxies each cell may still belong to, in increasing order.
+    candidates: Grid<Vec<usize>>,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board) -> Self {
+        let mut candidates = Grid::from_fn(board.dim(), |loc| {
+            (0..board.centers().len())
+                .filter(|&galaxy| board.partner(galaxy, loc).is_some())
+                .collect()
+        });
+        for galaxy in 0..board.centers().len() {
+            for cell in board.center_cells(galaxy) {
+                candidates[cell] = vec![galaxy];
+            }
+        }
+        Self { board, candidates }
+    }
+
+    fn candidates(&self, location: Location) -> &[usize] {
+        &self.candidates[location]
+    }
+
+    /// Removes `galaxy` from the candidates of the cell at `location`, returning whether anything changed.
+    /// Fails if no candidates are left.
+    fn remove(&mut self, location: Location, galaxy: usize) -> Result<bool, Contradiction> {
+        let candidates = &mut self.candidates[location];
+        let Some(index) = candidates.iter().position(|&candidate| candidate == galaxy) else {
+            return Ok(false);
+        };
+        candidates.remove(index);
+        if candidates.is_empty() {
+            return Err(Contradiction);
+        }
+        Ok(true)
+    }
+
+    /// A cell can only belong to a galaxy if its partner under the half turn around that galaxy's centre can too.
+    fn apply_symmetry(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for loc in Location::grid_iter(self.board.dim()) {
+            // Cells too far from every centre start out with no candidates.
+            if self.candidates(loc).is_empty() {
+                return Err(Contradiction);
+            }
+            for galaxy in self.candidates(loc).to_vec() {
+                let symmetric = self
+                    .board
+                    .partner(galaxy, loc)
+                    .is_some_and(|partner| self.candidates(partner).contains(&galaxy));
+                if !symmetric {
+                    changed |= self.remove(loc, galaxy)?;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// A cell can only belong to a galaxy if it can be reached from the centre through cells that can belong to it.
+    fn apply_connectivity(&mut self) -> Result<bool, Contradiction> {
+        let dim = self.board.dim();
+        let mut changed = false;
+        for galaxy in 0..self.board.centers().len() {
+            let mut reached = Grid::from_elem(dim, false);
+            let mut stack = self.board.center_cells(galaxy);
+            for cell in stack.iter() {
+                reached[*cell] = true;
+            }
+            while let Some(loc) = stack.pop() {
+                for adj in loc.adjacents(dim).into_iter().flatten() {
+                    if !reached[adj] && self.candidates(adj).contains(&galaxy) {
+                        reached[adj] = true;
+                        stack.push(adj);
+                    }
+                }
+            }
+            for loc in Location::grid_iter(dim) {
+                if !reached[loc] {
+                    changed |= self.remove(loc, galaxy)?;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    fn into_solution(self) -> Solution {
+        Solution {
+            galaxies: self.candidates.map(|candidates| candidates[0]),
+        }
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.apply_symmetry()?;
+            changed |= self.apply_connectivity()?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(location) = Location::grid_iter(self.board.dim())
+            .filter(|&loc| self.candidates(loc).len() > 1)
+            .min_by_key(|&loc| self.candidates(loc).len())
+        else {
+            return vec![];
+        };
+        self.candidates(location)
+            .iter()
+            .map(|&galaxy| {
+                let mut candidates = self.candidates.clone();
+                candidates[location] = vec![galaxy];
+                Self {
+                    candidates,
+                    ..self.clone()
+                }
+            })
+            .collect()
+    }
+}
+
+pub fn solve(board: &Board) -> Option<Solution> {
+    search::solve(State::new(board)).map(State::into_solution)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    search::count_solutions(State::new(board), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::region;
+
+    fn follows_rules(board: &Board, solution: &Solution) -> bool {
+        let dim = board.dim();
+        (0..board.centers().len()).all(|galaxy| {
+            let inside = |loc| solution.galaxy(loc) == galaxy;
+            let symmetric = Location::grid_iter(dim)
+                .filter(|&loc| inside(loc))
+                .all(|loc| board.partner(galaxy, loc).is_some_and(inside));
+            board.center_cells(galaxy).into_iter().all(inside)
+                && symmetric
+                && region::is_connected(dim, inside, inside)
+        }) && Location::grid_iter(dim).all(|loc| solution.galaxy(loc) < board.centers().len())
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/galaxies/puzzles/ten01.txt")).unwrap();
+        let solution = solve(&board).unwrap();
+        assert!(follows_rules(&board, &solution));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
@@ -0,0 +1,301 @@
+//! Generating puzzles with the same parameters for every kind of puzzle. A kind of puzzle only has to make candidate
+//! instances by implementing [`Generator`], and [`generate`] seeds it, checks the candidates for uniqueness and
+//! difficulty, and retries until one fits. [`generate_many`] does the same for many instances across threads, and
+//! [`generate_pairs`] also returns their solutions and ratings.
+
+use rand::{rngs::StdRng, SeedableRng};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    difficulty::{Difficulty, Rating},
+    error::{Error, Result},
+    puzzle::Puzzle,
+};
+
+/// What to generate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GeneratorConfig {
+    /// The same seed always gives the same instance.
+    pub seed: u64,
+    /// The size of the instance, with a meaning that depends on the puzzle. `None` for a size that suits the puzzle.
+    pub size: Option<usize>,
+    /// Only accept instances that [`Puzzle::rating`] grades to this difficulty.
+    pub difficulty: Option<Difficulty>,
+    /// Only accept instances with exactly one solution.
+    pub unique: bool,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            size: None,
+            difficulty: None,
+            unique: true,
+        }
+    }
+}
+
+impl GeneratorConfig {
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            seed,
+            ..Self::default()
+        }
+    }
+
+    pub fn rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.seed)
+    }
+
+    /// Fails if a difficulty is asked for, for puzzles that generate without [`generate`] and cannot aim for one.
+    pub fn ensure_untargeted<P: Puzzle + ?Sized>(&self) -> Result<()> {
+        if self.difficulty.is_some() {
+            return Err(Error::Unsupported {
+                puzzle: P::NAME,
+                action: "Generating to a difficulty",
+            });
+        }
+        Ok(())
+    }
+}
+
+/// A kind of puzzle that can make candidate instances for [`generate`].
+pub trait Generator: Puzzle {
+    /// The size used when the config gives none.
+    const DEFAULT_SIZE: usize;
+    /// How many candidates to try before giving up.
+    const ATTEMPTS: usize = 100;
+
+    /// Makes an instance of the size that has a solution, or `None` if this attempt failed. Candidates need not have
+    /// a unique solution, since [`generate`] checks that.
+    fn candidate(size: usize, rng: &mut StdRng) -> Result<Option<Self::Instance>>;
+
+    /// Like [`Generator::candidate`], but also returns the solution if making the candidate found it, such as the
+    /// filled grid a puzzle was made from, so that it need not be solved again. By default, only makes the candidate.
+    #[allow(clippy::type_complexity)]
+    fn candidate_with_solution(
+        size: usize,
+        rng: &mut StdRng,
+    ) -> Result<Option<(Self::Instance, Option<Self::Solution>)>> {
+        Ok(Self::candidate(size, rng)?.map(|instance| (instance, None)))
+    }
+
+    /// Like [`Generator::candidate_with_solution`], for configs that ask for a difficulty. Generators that can steer
+    /// a candidate towards a difficulty override this, so that fewer candidates are thrown away. The candidate is
+    /// still checked against the config. By default, makes a candidate without regard to the difficulty.
+    #[allow(clippy::type_complexity)]
+    fn candidate_for_difficulty(
+        size: usize,
+        _difficulty: Difficulty,
+        rng: &mut StdRng,
+    ) -> Result<Option<(Self::Instance, Option<Self::Solution>)>> {
+        Self::candidate_with_solution(size, rng)
+    }
+}
+
+/// A generated instance with its solution, and its rating if the puzzle rates instances.
+pub struct Generated<P: Puzzle + ?Sized> {
+    /// The seed that generates the instance on its own.
+    pub seed: u64,
+    pub instance: P::Instance,
+    pub solution: P::Solution,
+    pub rating: Option<Rating>,
+}
+
+/// A candidate that fits the config, with what checking it found out.
+struct Candidate<G: Generator> {
+    seed: u64,
+    instance: G::Instance,
+    solution: Option<G::Solution>,
+    rating: Option<Rating>,
+}
+
+/// Generates an instance that fits the config, giving up after [`Generator::ATTEMPTS`] candidates. Checking
+/// uniqueness needs [`Puzzle::count_solutions`] and aiming for a difficulty needs [`Puzzle::rate`].
+pub fn generate<G: Generator>(config: &GeneratorConfig) -> Result<G::Instance> {
+    let size = config.size.unwrap_or(G::DEFAULT_SIZE);
+    for index in 0..G::ATTEMPTS {
+        if let Some(candidate) = try_candidate::<G>(config, size, index)? {
+            return Ok(candidate.instance);
+        }
+    }
+    Err(failed::<G>(config, G::ATTEMPTS))
+}
+
+/// Generates `count` instances that fit the config, each with the seed that [`generate`] gives it back for. The
+/// candidates are made, checked for uniqueness and graded in parallel, a batch at a time, until enough fit, so the
+/// instances are the same however many threads there are. Gives up after [`Generator::ATTEMPTS`] candidates per
+/// instance.
+pub fn generate_many<G>(config: &GeneratorConfig, count: usize) -> Result<Vec<(u64, G::Instance)>>
+where
+    G: Generator,
+    G::Instance: Send,
+{
+    let candidates = candidates::<G>(config, count)?;
+    Ok(candidates
+        .into_iter()
+        .map(|candidate| (candidate.seed, candidate.instance))
+        .collect())
+}
+
+/// Like [`generate_many`], but returns each instance with its solution and rating. Solutions that the generator
+/// kept while making the candidates are used as they are, and the others are solved for.
+pub fn generate_pairs<G>(config: &GeneratorConfig, count: usize) -> Result<Vec<Generated<G>>>
+where
+    G: Generator,
+    G::Instance: Send,
+{
+    candidates::<G>(config, count)?
+        .into_par_iter()
+        .map(|candidate| {
+            let solution = match candidate.solution {
+                Some(solution) => solution,
+                None => G::solve(&candidate.instance)?.ok_or(Error::NoSolution(G::NAME))?,
+            };
+            let rating = match candidate.rating {
+                Some(rating) => Some(rating),
+                None => G::rating(&candidate.instance).ok(),
+            };
+            Ok(Generated {
+                seed: candidate.seed,
+                instance: candidate.instance,
+                solution,
+                rating,
+            })
+        })
+        .collect()
+}
+
+fn candidates<G>(config: &GeneratorConfig, count: usize) -> Result<Vec<Candidate<G>>>
+where
+    G: Generator,
+    G::Instance: Send,
+{
+    let size = config.size.unwrap_or(G::DEFAULT_SIZE);
+    let max_candidates = count.saturating_mul(G::ATTEMPTS);
+    // Enough candidates to keep every thread busy, without making many more than are needed once few are left.
+    let batch_size = rayon::current_num_threads() * 4;
+    let mut candidates = Vec::with_capacity(count);
+    let mut next = 0;
+    while candidates.len() < count && next < max_candidates {
+        let end = (next + batch_size).min(max_candidates);
+        let batch = (next..end)
+            .into_par_iter()
+            .map(|index| try_candidate::<G>(config, size, index))
+            .collect::<Result<Vec<_>>>()?;
+        candidates.extend(batch.into_iter().flatten());
+        next = end;
+    }
+    if candidates.len() < count {
+        return Err(failed::<G>(config, max_candidates));
+    }
+    candidates.truncate(count);
+    Ok(candidates)
+}
+
+/// Makes the candidate with the index, seeded by the config's seed plus the index so that it can be made again on
+/// its own, and returns it if it fits the config.
+fn try_candidate<G: Generator>(
+    config: &GeneratorConfig,
+    size: usize,
+    index: usize,
+) -> Result<Option<Candidate<G>>> {
+    let seed = config.seed.wrapping_add(index as u64);
+    let rng = &mut StdRng::seed_from_u64(seed);
+    let candidate = match config.difficulty {
+        Some(difficulty) => G::candidate_for_difficulty(size, difficulty, rng)?,
+        None => G::candidate_with_solution(size, rng)?,
+    };
+    let Some((instance, solution)) = candidate else {
+        return Ok(None);
+    };
+    if config.unique && G::count_solutions(&instance, 2)? != 1 {
+        return Ok(None);
+    }
+    let mut rating = None;
+    if let Some(difficulty) = config.difficulty {
+        let candidate_rating = G::rating(&instance)?;
+        if candidate_rating.difficulty != difficulty {
+            return Ok(None);
+        }
+        rating = Some(candidate_rating);
+    }
+    Ok(Some(Candidate {
+        seed,
+        instance,
+        solution,
+        rating,
+    }))
+}
+
+fn failed<G: Generator>(config: &GeneratorConfig, attempts: usize) -> Error {
+    let unique = if config.unique { "unique " } else { "" };
+    let difficulty = config
+        .difficulty
+        .map_or_else(String::new, |difficulty| format!("{difficulty} "));
+    Error::Generate(format!(
+        "Failed to generate a {unique}{difficulty}{} in {attempts} attempts.",
+        G::NAME,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{camping::Camping, sudoku::Sudoku};
+
+    #[test]
+    fn generates_to_config() {
+        let config = GeneratorConfig::seeded(3);
+        let map = generate::<Camping>(&config).unwrap();
+        assert_eq!(Camping::count_solutions(&map, 2).unwrap(), 1);
+        assert_eq!(generate::<Camping>(&config).unwrap(), map);
+
+        let config = GeneratorConfig {
+            size: Some(6),
+            difficulty: Some(Difficulty::Easy),
+            ..config
+        };
+        let map = generate::<Camping>(&config).unwrap();
+        assert_eq!(map.tiles().dim(), (6, 6));
+        assert_eq!(Camping::rating(&map).unwrap().difficulty, Difficulty::Easy);
+
+        let board = generate::<Sudoku>(&GeneratorConfig::seeded(0)).unwrap();
+        assert_eq!(Sudoku::count_solutions(&board, 2).unwrap(), 1);
+        assert!(generate::<Sudoku>(&GeneratorConfig {
+            size: Some(4),
+            ..GeneratorConfig::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn generates_many_reproducibly() {
+        let config = GeneratorConfig {
+            seed: 5,
+            size: Some(6),
+            ..GeneratorConfig::default()
+        };
+        let maps = generate_many::<Camping>(&config, 4).unwrap();
+        assert_eq!(maps.len(), 4);
+        assert_eq!(maps[0].1, generate::<Camping>(&config).unwrap());
+        for (seed, map) in &maps {
+            let config = GeneratorConfig {
+                seed: *seed,
+                ..config
+            };
+            assert_eq!(&generate::<Camping>(&config).unwrap(), map);
+        }
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+        assert_eq!(
+            pool.install(|| generate_many::<Camping>(&config, 4))
+                .unwrap(),
+            maps
+        );
+    }
+}
@@ -0,0 +1,305 @@
+//! A rectangular grid of cells indexed by [`Location`], so puzzles do not each need their own bounds checks and
+//! neighbor lookups.
+
+use std::ops::{Index, IndexMut};
+
+use ndarray::{Array2, ArrayView2};
+use serde::{Deserialize, Serialize};
+
+use crate::location::{GridIter, Location};
+
+/// Serialized in the same form as the `ndarray` array it wraps.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Grid<T> {
+    cells: Array2<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_array(cells: Array2<T>) -> Self {
+        Self { cells }
+    }
+
+    pub fn from_elem(dim: (usize, usize), value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_array(Array2::from_elem(dim, value))
+    }
+
+    pub fn from_fn(dim: (usize, usize), mut f: impl FnMut(Location) -> T) -> Self {
+        Self::from_array(Array2::from_shape_fn(dim, |(row, col)| {
+            f(Location::new(row, col))
+        }))
+    }
+
+    /// Returns `None` unless `cells` holds exactly `height * width` cells, in row-major order.
+    pub fn from_vec(dim: (usize, usize), cells: Vec<T>) -> Option<Self> {
+        Array2::from_shape_vec(dim, cells)
+            .ok()
+            .map(Self::from_array)
+    }
+
+    /// The number of rows and columns.
+    pub fn dim(&self) -> (usize, usize) {
+        self.cells.dim()
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.nrows()
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.ncols()
+    }
+
+    pub fn in_bounds(&self, location: Location) -> bool {
+        location.row < self.height() && location.col < self.width()
+    }
+
+    pub fn get(&self, location: Location) -> Option<&T> {
+        self.cells.get((location.row, location.col))
+    }
+
+    pub fn get_mut(&mut self, location: Location) -> Option<&mut T> {
+        self.cells.get_mut((location.row, location.col))
+    }
+
+    /// Every location in the grid, in row-major order.
+    pub fn locations(&self) -> GridIter {
+        Location::grid_iter(self.dim())
+    }
+
+    /// Every cell, in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// Every cell with its location, in row-major order.
+    pub fn indexed_iter(&self) -> impl Iterator<Item = (Location, &T)> {
+        self.cells
+            .indexed_iter()
+            .map(|((row, col), cell)| (Location::new(row, col), cell))
+    }
+
+    /// The cells of a row, from left to right.
+    pub fn row(&self, row: usize) -> impl DoubleEndedIterator<Item = &T> {
+        self.cells.row(row).into_iter()
+    }
+
+    /// Every row, from top to bottom, as in [`Grid::row`].
+    pub fn rows(&self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &T>> {
+        (0..self.height()).map(|row| self.row(row))
+    }
+
+    /// The cells of a column, from top to bottom.
+    pub fn col(&self, col: usize) -> impl DoubleEndedIterator<Item = &T> {
+        self.cells.column(col).into_iter()
+    }
+
+    /// The cells at `locations`, which must all be in the grid.
+    pub fn region<'a>(
+        &'a self,
+        locations: impl IntoIterator<Item = Location> + 'a,
+    ) -> impl Iterator<Item = &'a T> {
+        locations.into_iter().map(|location| &self[location])
+    }
+
+    /// The orthogonally adjacent cells, in the order of [`Location::adjacents`].
+    pub fn adjacents(&self, location: Location) -> [Option<(Location, &T)>; 4] {
+        location
+            .adjacents(self.dim())
+            .map(|loc| loc.map(|loc| (loc, &self[loc])))
+    }
+
+    /// The orthogonally and diagonally adjacent cells, in the order of [`Location::neighbors`].
+    pub fn neighbors(&self, location: Location) -> [Option<(Location, &T)>; 8] {
+        location
+            .neighbors(self.dim())
+            .map(|loc| loc.map(|loc| (loc, &self[loc])))
+    }
+
+    /// A view of the grid with rows and columns swapped.
+    pub fn transposed(&self) -> Transposed<'_, T> {
+        Transposed { grid: self }
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::from_array(self.cells.map(f))
+    }
+
+    /// The cells as an `ndarray` view, for code that needs array operations.
+    pub fn view(&self) -> ArrayView2<'_, T> {
+        self.cells.view()
+    }
+
+    /// The grid rotated or reflected by the symmetry.
+    pub fn transformed(&self, symmetry: Symmetry) -> Self
+    where
+        T: Clone,
+    {
+        let dim = self.dim();
+        let mut cells = vec![None; dim.0 * dim.1];
+        let (_, width) = symmetry.dim(dim);
+        for (location, cell) in self.indexed_iter() {
+            let location = symmetry.apply(location, dim);
+            cells[location.row * width + location.col] = Some(cell.clone());
+        }
+        Grid::from_vec(symmetry.dim(dim), cells.into_iter().flatten().collect())
+            .expect("A symmetry moves every cell to a different cell of the grid.")
+    }
+}
+
+/// One of the eight ways to rotate and reflect a grid onto a grid of the same or transposed shape. Applied by first
+/// reversing the rows and columns that `flip_rows` and `flip_cols` ask for, and then transposing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Symmetry {
+    pub flip_rows: bool,
+    pub flip_cols: bool,
+    pub transpose: bool,
+}
+
+impl Symmetry {
+    pub const IDENTITY: Symmetry = Symmetry {
+        flip_rows: false,
+        flip_cols: false,
+        transpose: false,
+    };
+
+    /// Every symmetry, starting with [`Symmetry::IDENTITY`].
+    pub const ALL: [Symmetry; 8] = {
+        let mut all = [Self::IDENTITY; 8];
+        let mut index = 0;
+        while index < 8 {
+            all[index] = Symmetry {
+                flip_rows: index & 1 != 0,
+                flip_cols: index & 2 != 0,
+                transpose: index & 4 != 0,
+            };
+            index += 1;
+        }
+        all
+    };
+
+    /// The shape of a grid of shape `dim` after the symmetry.
+    pub fn dim(self, dim: (usize, usize)) -> (usize, usize) {
+        if self.transpose {
+            (dim.1, dim.0)
+        } else {
+            dim
+        }
+    }
+
+    /// Where the symmetry moves a location of a grid of shape `dim`.
+    pub fn apply(self, location: Location, dim: (usize, usize)) -> Location {
+        let (height, width) = dim;
+        let location = Location::new(
+            if self.flip_rows {
+                height - 1 - location.row
+            } else {
+                location.row
+            },
+            if self.flip_cols {
+                width - 1 - location.col
+            } else {
+                location.col
+            },
+        );
+        if self.transpose {
+            location.transpose()
+        } else {
+            location
+        }
+    }
+}
+
+impl<T> Index<Location> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, location: Location) -> &T {
+        &self.cells[(location.row, location.col)]
+    }
+}
+
+impl<T> IndexMut<Location> for Grid<T> {
+    fn index_mut(&mut self, location: Location) -> &mut T {
+        &mut self.cells[(location.row, location.col)]
+    }
+}
+
+/// A grid with rows and columns swapped, so that code written for rows also works for columns.
+#[derive(Clone, Copy, Debug)]
+pub struct Transposed<'a, T> {
+    grid: &'a Grid<T>,
+}
+
+impl<'a, T> Transposed<'a, T> {
+    pub fn dim(&self) -> (usize, usize) {
+        let (height, width) = self.grid.dim();
+        (width, height)
+    }
+
+    pub fn get(&self, location: Location) -> Option<&'a T> {
+        self.grid.get(location.transpose())
+    }
+
+    /// The cells of a row of the view, which is a column of the grid.
+    pub fn row(&self, row: usize) -> impl DoubleEndedIterator<Item = &'a T> {
+        self.grid.col(row)
+    }
+
+    /// The cells of a column of the view, which is a row of the grid.
+    pub fn col(&self, col: usize) -> impl DoubleEndedIterator<Item = &'a T> {
+        self.grid.row(col)
+    }
+}
+
+impl<T> Index<Location> for Transposed<'_, T> {
+    type Output = T;
+
+    fn index(&self, location: Location) -> &T {
+        &self.grid[location.transpose()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transposed_lines() {
+        let grid = Grid::from_fn((2, 3), |loc| loc.row * 10 + loc.col);
+        let transposed = grid.transposed();
+        assert_eq!(transposed.dim(), (3, 2));
+        assert_eq!(transposed.row(2).copied().collect::<Vec<_>>(), [2, 12]);
+        assert_eq!(transposed.col(1).copied().collect::<Vec<_>>(), [10, 11, 12]);
+        assert_eq!(transposed[Location::new(2, 1)], 12);
+        assert_eq!(transposed.get(Location::new(1, 2)), None);
+        assert_eq!(
+            grid.neighbors(Location::new(0, 0)).iter().flatten().count(),
+            3
+        );
+    }
+
+    #[test]
+    fn symmetries_are_distinct() {
+        let grid = Grid::from_fn((2, 3), |loc| loc.row * 10 + loc.col);
+        let transformed = Symmetry::ALL.map(|symmetry| grid.transformed(symmetry));
+        assert_eq!(transformed[0], grid);
+        for (index, grid) in transformed.iter().enumerate() {
+            assert!(!transformed[..index].contains(grid));
+        }
+        let rotated = Symmetry {
+            flip_rows: true,
+            flip_cols: false,
+            transpose: true,
+        };
+        assert_eq!(
+            grid.transformed(rotated)
+                .row(0)
+                .copied()
+                .collect::<Vec<_>>(),
+            [10, 0]
+        );
+    }
+}
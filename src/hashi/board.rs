@@ -0,0 +1,147 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+    puzzlink::{self, Clue, PuzzLink},
+};
+
+/// A hashiwokakero puzzle: islands that must be joined by horizontal and vertical bridges such that every island
+/// has as many bridges as its number, at most two bridges join any pair of islands, bridges do not cross,
+/// and all islands are connected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    islands: Grid<Option<u8>>,
+}
+
+impl Board {
+    pub fn new(islands: Grid<Option<u8>>) -> Result<Self> {
+        let (height, width) = islands.dim();
+        ensure!(
+            height > 0 && width > 0,
+            Invalid,
+            "Hashi grids must have at least one cell."
+        );
+        if let Some(number) = islands
+            .iter()
+            .flatten()
+            .find(|&&number| number == 0 || number > 8)
+        {
+            bail!(
+                Invalid,
+                "Island numbers must be between 1 and 8, but got {number}."
+            );
+        }
+        ensure!(
+            islands.iter().any(Option::is_some),
+            Invalid,
+            "The puzzle has no islands."
+        );
+        Ok(Self { islands })
+    }
+
+    /// Parses a grid of island numbers, with `.` for water.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        let mut islands = Grid::from_elem((height, width), None);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.chars().count() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} characters in row {row_index}. Got '{row}'."
+                );
+            }
+            for (col_index, c) in row.chars().enumerate() {
+                islands[Location::new(row_index, col_index)] = match c {
+                    '.' => None,
+                    _ => Some(c.to_digit(10).ok_or_else(|| {
+                        Error::Parse(format!(
+                            "Invalid character '{c}' at ({row_index}, {col_index})."
+                        ))
+                    })? as u8),
+                };
+            }
+        }
+        Self::new(islands)
+    }
+
+    /// Imports a puzzle from a puzz.link or pzv.jp URL.
+    pub fn from_puzz_link(url: &str) -> Result<Self> {
+        let link = PuzzLink::parse(url)?;
+        link.expect_kind(&["hashi", "bridges"])?;
+        let clues = puzzlink::decode_number16(&link.body, link.width * link.height)?;
+        let mut islands = Grid::from_elem((link.height, link.width), None);
+        for (index, clue) in clues.into_iter().enumerate() {
+            let location = Location::new(index / link.width, index % link.width);
+            islands[location] = match clue {
+                None => None,
+                Some(Clue::Number(number)) => {
+                    Some(u8::try_from(number).map_err(|_| {
+                        Error::Parse(format!("Island number {number} is too large."))
+                    })?)
+                }
+                Some(Clue::Unknown) => bail!(Parse, "Islands without a number are not supported."),
+            };
+        }
+        Self::new(islands)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("hashi", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.islands.dim()
+    }
+
+    /// The number of the island at `location`, if there is one.
+    pub fn island(&self, location: Location) -> Option<u8> {
+        self.islands[location]
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.islands.rows() {
+            for &island in row {
+                write!(
+                    f,
+                    "{}",
+                    island.map_or('.', |number| (b'0' + number) as char)
+                )?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_text_and_puzz_links() {
+        let board = Board::parse(include_str!("../../data/hashi/puzzles/nine01.txt")).unwrap();
+        assert_eq!(board.dim(), (9, 9));
+        assert_eq!(board.island(Location::new(3, 6)), Some(8));
+        assert_eq!(board.island(Location::new(0, 1)), None);
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        let link = "https://puzz.link/p?hashi/9/9/1h2h3p2p2g4h8g4p3h6g4r1h3h1p";
+        assert_eq!(Board::from_puzz_link(link).unwrap(), board);
+        assert!(Board::from_puzz_link("https://puzz.link/p?hashi/2/1/1.").is_err());
+        assert!(Board::from_puzz_link("https://puzz.link/p?lits/2/1/1g").is_err());
+        assert!(Board::parse("1.\n.\n").is_err());
+        assert!(Board::parse("1.\n.9\n").is_err());
+    }
+}
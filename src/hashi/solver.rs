@@ -0,0 +1,385 @@
+use std::fmt::Display;
+
+use crate::{
+    grid::Grid,
+    location::Location,
+    search::{self, Contradiction, SearchState},
+};
+
+use super::Board;
+
+/// A possible bridge between two islands that can see each other along a row or column.
+#[derive(Clone, Debug)]
+struct Edge {
+    /// Indices of the two islands, the top or left one first.
+    islands: [usize; 2],
+    /// The water cells the bridge passes over.
+    cells: Vec<Location>,
+}
+
+impl Edge {
+    fn is_horizontal(&self, islands: &[(Location, u8)]) -> bool {
+        islands[self.islands[0]].0.row == islands[self.islands[1]].0.row
+    }
+}
+
+/// The islands and possible bridges of a board, shared between all search states.
+struct Graph {
+    islands: Vec<(Location, u8)>,
+    edges: Vec<Edge>,
+    /// The edges touching each island.
+    island_edges: Vec<Vec<usize>>,
+    /// The edges crossing each edge.
+    crossings: Vec<Vec<usize>>,
+}
+
+impl Graph {
+    fn new(board: &Board) -> Self {
+        let dim = board.dim();
+        let islands = Location::grid_iter(dim)
+            .filter_map(|loc| board.island(loc).map(|number| (loc, number)))
+            .collect::<Vec<_>>();
+        let index_of = |loc: Location| islands.iter().position(|&(other, _)| other == loc);
+        let mut edges = vec![];
+        for (index, &(loc, _)) in islands.iter().enumerate() {
+            let rightwards = (loc.col + 1..dim.1).map(|col| Location::new(loc.row, col));
+            let downwards = (loc.row + 1..dim.0).map(|row| Location::new(row, loc.col));
+            for direction in [rightwards.collect::<Vec<_>>(), downwards.collect()] {
+                let mut cells = vec![];
+                for other in direction {
+                    if let Some(other_index) = index_of(other) {
+                        edges.push(Edge {
+                            islands: [index, other_index],
+                            cells,
+                        });
+                        break;
+                    }
+                    cells.push(other);
+                }
+            }
+        }
+        let island_edges = (0..islands.len())
+            .map(|island| {
+                (0..edges.len())
+                    .filter(|&edge| edges[edge].islands.contains(&island))
+                    .collect()
+            })
+            .collect();
+        let crossings = edges
+            .iter()
+            .map(|edge| {
+                (0..edges.len())
+                    .filter(|&other| {
+                        let other = &edges[other];
+                        edge.is_horizontal(&islands) != other.is_horizontal(&islands)
+                            && edge.cells.iter().any(|cell| other.cells.contains(cell))
+                    })
+                    .collect()
+            })
+            .collect();
+        Self {
+            islands,
+            edges,
+            island_edges,
+            crossings,
+        }
+    }
+}
+
+/// A bridge of one or two lines between the islands at `from` and `to`, where `from` is the top or left island.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bridge {
+    pub from: Location,
+    pub to: Location,
+    pub count: u8,
+}
+
+/// The bridges of a solved puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    islands: Grid<Option<u8>>,
+    bridges: Vec<Bridge>,
+}
+
+impl Solution {
+    pub fn bridges(&self) -> &[Bridge] {
+        &self.bridges
+    }
+}
+
+/// Islands are shown by their number, single bridges by `-` and `|` and double bridges by `=` and `H`.
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut chars = self
+            .islands
+            .map(|island| island.map_or('.', |number| (b'0' + number) as char));
+        for bridge in self.bridges.iter() {
+            let c = match (bridge.from.row == bridge.to.row, bridge.count) {
+                (true, 1) => '-',
+                (true, _) => '=',
+                (false, 1) => '|',
+                (false, _) => 'H',
+            };
+            let cells = if bridge.from.row == bridge.to.row {
+                (bridge.from.col + 1..bridge.to.col)
+                    .map(|col| Location::new(bridge.from.row, col))
+                    .collect::<Vec<_>>()
+            } else {
+                (bridge.from.row + 1..bridge.to.row)
+                    .map(|row| Location::new(row, bridge.from.col))
+                    .collect()
+            };
+            for cell in cells {
+                chars[cell] = c;
+            }
+        }
+        for row in chars.rows() {
+            writeln!(f, "{}", row.collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct State<'a> {
+    graph: &'a Graph,
+    /// The lowest and highest number of bridges still possible on each edge.
+    bounds: Vec<(u8, u8)>,
+}
+
+impl<'a> State<'a> {
+    fn new(graph: &'a Graph) -> Self {
+        let bounds = graph
+            .edges
+            .iter()
+            .map(|edge| {
+                let [a, b] = edge.islands.map(|island| graph.islands[island].1);
+                let mut max = a.min(b).min(2);
+                // Two islands that would use up all their bridges on each other would be cut off from the rest.
+                if a == b && a <= 2 && graph.islands.len() > 2 {
+                    max = max.min(a - 1);
+                }
+                (0, max)
+            })
+            .collect();
+        Self { graph, bounds }
+    }
+
+    fn restrict(&mut self, edge: usize, min: u8, max: u8) -> Result<bool, Contradiction> {
+        let (old_min, old_max) = self.bounds[edge];
+        let bounds = (old_min.max(min), old_max.min(max));
+        if bounds.0 > bounds.1 {
+            return Err(Contradiction);
+        }
+        self.bounds[edge] = bounds;
+        Ok(bounds != (old_min, old_max))
+    }
+
+    /// Bounds every edge of an island by how many bridges the other edges of the island can take.
+    fn apply_capacities(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for (island, &(_, number)) in self.graph.islands.iter().enumerate() {
+            let edges = &self.graph.island_edges[island];
+            let (min_sum, max_sum) = edges.iter().fold((0, 0), |(min_sum, max_sum), &edge| {
+                let (min, max) = self.bounds[edge];
+                (min_sum + min, max_sum + max)
+            });
+            if number < min_sum || number > max_sum {
+                return Err(Contradiction);
+            }
+            for &edge in edges {
+                let (min, max) = self.bounds[edge];
+                let others_max = max_sum - max;
+                let others_min = min_sum - min;
+                changed |=
+                    self.restrict(edge, number.saturating_sub(others_max), number - others_min)?;
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Bridges cannot cross, so a built bridge rules out every bridge crossing it.
+    fn apply_crossings(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for edge in 0..self.graph.edges.len() {
+            if self.bounds[edge].0 > 0 {
+                for &other in self.graph.crossings[edge].iter() {
+                    changed |= self.restrict(other, 0, 0)?;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Whether every island can reach every other island without using `skipped` or any edge that cannot be built.
+    fn is_connected_without(&self, skipped: Option<usize>) -> bool {
+        let mut reached = vec![false; self.graph.islands.len()];
+        reached[0] = true;
+        let mut stack = vec![0];
+        while let Some(island) = stack.pop() {
+            for &edge in self.graph.island_edges[island].iter() {
+                if Some(edge) == skipped || self.bounds[edge].1 == 0 {
+                    continue;
+                }
+                let [a, b] = self.graph.edges[edge].islands;
+                let other = if a == island { b } else { a };
+                if !reached[other] {
+                    reached[other] = true;
+                    stack.push(other);
+                }
+            }
+        }
+        reached.into_iter().all(|reached| reached)
+    }
+
+    /// Fails if the islands can no longer be connected, and builds at least one bridge on every edge
+    /// the islands cannot be connected without.
+    fn apply_connectivity(&mut self) -> Result<bool, Contradiction> {
+        if !self.is_connected_without(None) {
+            return Err(Contradiction);
+        }
+        let mut changed = false;
+        for edge in 0..self.graph.edges.len() {
+            if self.bounds[edge].0 == 0
+                && self.bounds[edge].1 > 0
+                && !self.is_connected_without(Some(edge))
+            {
+                changed |= self.restrict(edge, 1, 2)?;
+            }
+        }
+        Ok(changed)
+    }
+
+    fn into_solution(self, board: &Board) -> Solution {
+        let bridges = self
+            .graph
+            .edges
+            .iter()
+            .zip(self.bounds.iter())
+            .filter(|(_, &(count, _))| count > 0)
+            .map(|(edge, &(count, _))| Bridge {
+                from: self.graph.islands[edge.islands[0]].0,
+                to: self.graph.islands[edge.islands[1]].0,
+                count,
+            })
+            .collect();
+        Solution {
+            islands: Grid::from_fn(board.dim(), |loc| board.island(loc)),
+            bridges,
+        }
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.apply_capacities()?;
+            changed |= self.apply_crossings()?;
+            changed |= self.apply_connectivity()?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(edge) = (0..self.bounds.len()).find(|&edge| {
+            let (min, max) = self.bounds[edge];
+            min < max
+        }) else {
+            return vec![];
+        };
+        let (min, max) = self.bounds[edge];
+        (min..=max)
+            .rev()
+            .map(|count| {
+                let mut state = self.clone();
+                state.bounds[edge] = (count, count);
+                state
+            })
+            .collect()
+    }
+}
+
+pub fn solve(board: &Board) -> Option<Solution> {
+    let graph = Graph::new(board);
+    search::solve(State::new(&graph)).map(|state| state.into_solution(board))
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    let graph = Graph::new(board);
+    search::count_solutions(State::new(&graph), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follows_rules(board: &Board, solution: &Solution) -> bool {
+        let dim = board.dim();
+        let mut counts = Grid::from_elem(dim, 0);
+        let mut covered = Grid::from_elem(dim, false);
+        for bridge in solution.bridges() {
+            let Bridge { from, to, count } = *bridge;
+            if board.island(from).is_none()
+                || board.island(to).is_none()
+                || !(1..=2).contains(&count)
+            {
+                return false;
+            }
+            let cells = if from.row == to.row && from.col < to.col {
+                (from.col + 1..to.col)
+                    .map(|col| Location::new(from.row, col))
+                    .collect::<Vec<_>>()
+            } else if from.col == to.col && from.row < to.row {
+                (from.row + 1..to.row)
+                    .map(|row| Location::new(row, from.col))
+                    .collect()
+            } else {
+                return false;
+            };
+            // A cell under two bridges means they cross, and a cell with an island means the bridge passes it.
+            for cell in cells {
+                if covered[cell] || board.island(cell).is_some() {
+                    return false;
+                }
+                covered[cell] = true;
+            }
+            counts[from] += count;
+            counts[to] += count;
+        }
+        let islands = Location::grid_iter(dim)
+            .filter(|&loc| board.island(loc).is_some())
+            .collect::<Vec<_>>();
+        if islands
+            .iter()
+            .any(|&loc| board.island(loc) != Some(counts[loc]))
+        {
+            return false;
+        }
+        let mut reached = vec![islands[0]];
+        let mut stack = vec![islands[0]];
+        while let Some(loc) = stack.pop() {
+            for bridge in solution.bridges() {
+                let other = match loc {
+                    _ if bridge.from == loc => bridge.to,
+                    _ if bridge.to == loc => bridge.from,
+                    _ => continue,
+                };
+                if !reached.contains(&other) {
+                    reached.push(other);
+                    stack.push(other);
+                }
+            }
+        }
+        reached.len() == islands.len()
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/hashi/puzzles/nine01.txt")).unwrap();
+        let solution = solve(&board).unwrap();
+        assert!(follows_rules(&board, &solution));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
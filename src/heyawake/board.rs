@@ -0,0 +1,190 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+    puzzlink::{self, Clue, PuzzLink},
+    region,
+};
+
+/// A heyawake puzzle: a grid divided into rooms, some with the number of shaded cells they contain.
+/// Shaded cells may not be orthogonally adjacent, the unshaded cells must be connected, and no straight line of
+/// unshaded cells may cross two room borders.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    rooms: Grid<usize>,
+    room_cells: Vec<Vec<Location>>,
+    counts: Vec<Option<usize>>,
+}
+
+impl Board {
+    pub fn new(rooms: Grid<usize>, counts: Vec<Option<usize>>) -> Result<Self> {
+        let dim = rooms.dim();
+        ensure!(
+            dim.0 > 0 && dim.1 > 0,
+            Invalid,
+            "Heyawake grids must have at least one cell."
+        );
+        let mut room_cells = vec![vec![]; counts.len()];
+        for loc in Location::grid_iter(dim) {
+            let room = rooms[loc];
+            ensure!(
+                room < counts.len(),
+                Invalid,
+                "Cell {loc} is in room {room}, but there are only {} rooms.",
+                counts.len()
+            );
+            room_cells[room].push(loc);
+        }
+        for (room, (cells, &count)) in room_cells.iter().zip(counts.iter()).enumerate() {
+            ensure!(!cells.is_empty(), Invalid, "Room {room} has no cells.");
+            if let Some(count) = count {
+                ensure!(
+                    count <= cells.len(),
+                    Invalid,
+                    "Room {room} has {} cells, so it cannot have {count} shaded cells.",
+                    cells.len()
+                );
+            }
+        }
+        Ok(Self {
+            rooms,
+            room_cells,
+            counts,
+        })
+    }
+
+    /// Parses a grid of characters where each distinct character is a room, followed by a blank line and a line
+    /// such as `A 2` for every room with a number.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let mut lines = string.as_ref().lines().map(str::trim);
+        let rows = lines
+            .by_ref()
+            .skip_while(|line| line.is_empty())
+            .take_while(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let (rooms, num_rooms) = region::parse_regions(&rows)?;
+        let names = rows
+            .iter()
+            .flat_map(|row| row.chars())
+            .fold(vec![], |mut names, c| {
+                if !names.contains(&c) {
+                    names.push(c);
+                }
+                names
+            });
+        let mut counts = vec![None; num_rooms];
+        for line in lines.filter(|line| !line.is_empty()) {
+            let mut parts = line.split_whitespace();
+            let (Some(name), Some(count), None) = (parts.next(), parts.next(), parts.next()) else {
+                bail!(
+                    Parse,
+                    "Expected a room character and a number. Got '{line}'."
+                );
+            };
+            let mut name_chars = name.chars();
+            let (Some(name), None) = (name_chars.next(), name_chars.next()) else {
+                bail!(
+                    Parse,
+                    "Room names must be a single character. Got '{name}'."
+                );
+            };
+            let room = names
+                .iter()
+                .position(|&other| other == name)
+                .ok_or_else(|| {
+                    Error::Parse(format!("Room '{name}' does not appear in the grid."))
+                })?;
+            ensure!(
+                counts[room].is_none(),
+                Parse,
+                "Room '{name}' has more than one number."
+            );
+            counts[room] = Some(count.parse().map_err(|_| {
+                Error::Parse(format!("Invalid number '{count}' for room '{name}'."))
+            })?);
+        }
+        Self::new(rooms, counts)
+    }
+
+    /// Imports a puzzle from a puzz.link or pzv.jp URL.
+    pub fn from_puzz_link(url: &str) -> Result<Self> {
+        let link = PuzzLink::parse(url)?;
+        link.expect_kind(&["heyawake", "heyawa"])?;
+        let (rooms, num_rooms, rest) = puzzlink::decode_rooms(&link.body, link.width, link.height)?;
+        let counts = puzzlink::decode_number16(rest, num_rooms)?
+            .into_iter()
+            .map(|clue| match clue {
+                None | Some(Clue::Unknown) => None,
+                Some(Clue::Number(number)) => Some(number as usize),
+            })
+            .collect();
+        Self::new(rooms, counts)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("heyawake", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.rooms.dim()
+    }
+
+    pub fn room(&self, location: Location) -> usize {
+        self.rooms[location]
+    }
+
+    /// The cells of each room.
+    pub fn rooms(&self) -> &[Vec<Location>] {
+        &self.room_cells
+    }
+
+    /// The number of shaded cells in each room, if given.
+    pub fn counts(&self) -> &[Option<usize>] {
+        &self.counts
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const NAMES: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+        let name = |room: usize| NAMES[room % NAMES.len()] as char;
+        for row in self.rooms.rows() {
+            writeln!(f, "{}", row.map(|&room| name(room)).collect::<String>())?;
+        }
+        if self.counts.iter().any(Option::is_some) {
+            writeln!(f)?;
+            for (room, count) in self.counts.iter().enumerate() {
+                if let Some(count) = count {
+                    writeln!(f, "{} {count}", name(room))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_text_and_puzz_links() {
+        let board = Board::parse(include_str!("../../data/heyawake/puzzles/eight01.txt")).unwrap();
+        assert_eq!(board.dim(), (8, 8));
+        assert_eq!(board.rooms().len(), 16);
+        assert_eq!(board.room(Location::new(1, 0)), 2);
+        assert_eq!(board.counts()[2], Some(1));
+        assert_eq!(board.counts()[0], None);
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        let link = "https://puzz.link/p?heyawake/8/8/09ufjstfbho0vo0e05n01vfgh1g3q";
+        assert_eq!(Board::from_puzz_link(link).unwrap(), board);
+        assert!(Board::from_puzz_link("https://puzz.link/p?heyawake/8/8/09uf").is_err());
+        assert!(Board::parse("AB\nAB\n\nC 1\n").is_err());
+        assert!(Board::parse("AB\nAB\n\nA 3\n").is_err());
+        assert!(Board::parse("AB\nAB\n\nA 1\nA 0\n").is_err());
+    }
+}
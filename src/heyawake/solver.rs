@@ -0,0 +1,173 @@
+use crate::{
+    location::Location,
+    search::{self, Contradiction, SearchState},
+    shading::{Shade, ShadeGrid},
+};
+
+use super::Board;
+
+/// Every shortest straight line of cells that crosses two room borders, each of which needs a shaded cell.
+fn spans(board: &Board) -> Vec<Vec<Location>> {
+    let (height, width) = board.dim();
+    let rows = (0..height).map(|row| (0..width).map(|col| Location::new(row, col)).collect());
+    let cols = (0..width).map(|col| (0..height).map(|row| Location::new(row, col)).collect());
+    let mut spans = vec![];
+    for line in rows.chain(cols).collect::<Vec<Vec<_>>>() {
+        // The index of the first cell after each border.
+        let borders = (1..line.len())
+            .filter(|&index| board.room(line[index - 1]) != board.room(line[index]))
+            .collect::<Vec<_>>();
+        for pair in borders.windows(2) {
+            spans.push(line[pair[0] - 1..=pair[1]].to_vec());
+        }
+    }
+    spans
+}
+
+/// Shaded cells are the blacked out cells of the puzzle.
+#[derive(Clone, Debug)]
+struct State<'a> {
+    board: &'a Board,
+    spans: &'a [Vec<Location>],
+    grid: ShadeGrid,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board, spans: &'a [Vec<Location>]) -> Self {
+        Self {
+            board,
+            spans,
+            grid: ShadeGrid::new(board.dim()),
+        }
+    }
+
+    /// Shaded cells cannot be orthogonally adjacent.
+    fn apply_adjacency(&mut self) -> Result<bool, Contradiction> {
+        let dim = self.grid.dim();
+        let mut changed = false;
+        for loc in Location::grid_iter(dim) {
+            if self.grid.is_shaded(loc) {
+                for adj in loc.adjacents(dim).into_iter().flatten() {
+                    changed |= self.grid.set(adj, Shade::Unshaded)?;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Shades the last undecided cell of a span whose other cells are all unshaded.
+    fn apply_spans(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for span in self.spans {
+            if span.iter().any(|&loc| self.grid.is_shaded(loc)) {
+                continue;
+            }
+            let unknown = span
+                .iter()
+                .filter(|&&loc| self.grid.get(loc) == Shade::Unknown)
+                .collect::<Vec<_>>();
+            match unknown[..] {
+                [] => return Err(Contradiction),
+                [&loc] => changed |= self.grid.set(loc, Shade::Shaded)?,
+                _ => {}
+            }
+        }
+        Ok(changed)
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.apply_adjacency()?;
+            for (cells, count) in self.board.rooms().iter().zip(self.board.counts()) {
+                if let Some(count) = count {
+                    changed |= self.grid.apply_count(cells, *count)?;
+                }
+            }
+            changed |= self.apply_spans()?;
+            changed |= self.grid.keep_connected(Shade::Unshaded)?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(location) = self.grid.first_unknown() else {
+            return vec![];
+        };
+        self.grid
+            .branch_on(location)
+            .into_iter()
+            .map(|grid| Self {
+                grid,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+/// Returns the solved grid with shaded cells as `#` and unshaded cells as `.`, or `None` if the puzzle has no solution.
+pub fn solve(board: &Board) -> Option<ShadeGrid> {
+    let spans = spans(board);
+    search::solve(State::new(board, &spans)).map(|state| state.grid)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    let spans = spans(board);
+    search::count_solutions(State::new(board, &spans), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::region;
+
+    fn follows_rules(board: &Board, grid: &ShadeGrid) -> bool {
+        let (height, width) = board.dim();
+        let dim = (height, width);
+        let counts = board
+            .rooms()
+            .iter()
+            .zip(board.counts())
+            .all(|(cells, count)| {
+                count.is_none_or(|count| {
+                    cells.iter().filter(|&&loc| grid.is_shaded(loc)).count() == count
+                })
+            });
+        let apart = Location::grid_iter(dim)
+            .filter(|&loc| grid.is_shaded(loc))
+            .all(|loc| {
+                loc.adjacents(dim)
+                    .into_iter()
+                    .flatten()
+                    .all(|adj| !grid.is_shaded(adj))
+            });
+        let rows = (0..height).map(|row| {
+            (0..width)
+                .map(|col| Location::new(row, col))
+                .collect::<Vec<_>>()
+        });
+        let cols = (0..width).map(|col| (0..height).map(|row| Location::new(row, col)).collect());
+        let spans = rows.chain(cols).all(|line| {
+            line.split(|&loc| grid.is_shaded(loc)).all(|run| {
+                run.windows(2)
+                    .filter(|pair| board.room(pair[0]) != board.room(pair[1]))
+                    .count()
+                    <= 1
+            })
+        });
+        let unshaded = |loc| !grid.is_shaded(loc);
+        counts && apart && spans && region::is_connected(dim, unshaded, unshaded)
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/heyawake/puzzles/eight01.txt")).unwrap();
+        let grid = solve(&board).unwrap();
+        assert!(grid.is_complete());
+        assert!(follows_rules(&board, &grid));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
@@ -0,0 +1,148 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cell {
+    /// A cell outside the puzzle's shape.
+    Blocked,
+    /// A cell to be numbered, with its number if it is given.
+    Open(Option<u32>),
+}
+
+/// A hidato puzzle: number the open cells from 1 up to the number of open cells such that every pair of consecutive
+/// numbers is in cells that touch, also diagonally. The open cells may form any shape.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    cells: Grid<Cell>,
+    open: usize,
+}
+
+impl Board {
+    pub fn new(cells: Grid<Cell>) -> Result<Self> {
+        let open = cells.iter().filter(|&&cell| cell != Cell::Blocked).count();
+        ensure!(
+            open > 0,
+            Invalid,
+            "Hidato puzzles must have at least one open cell."
+        );
+        let mut given = vec![false; open + 1];
+        for (location, &cell) in cells.indexed_iter() {
+            let Cell::Open(Some(number)) = cell else {
+                continue;
+            };
+            ensure!(
+                (1..=open as u32).contains(&number),
+                Invalid,
+                "The number {number} at {location} is not between 1 and the {open} open cells."
+            );
+            ensure!(
+                !given[number as usize],
+                Invalid,
+                "The number {number} is given more than once."
+            );
+            given[number as usize] = true;
+        }
+        Ok(Self { cells, open })
+    }
+
+    /// Parses rows of whitespace separated cells, where `.` is an open cell, `#` is a blocked cell and numbers are
+    /// given.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| line.split_whitespace().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        let mut cells = Grid::from_elem((height, width), Cell::Blocked);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} cells in row {row_index}. Got {}.",
+                    row.len()
+                );
+            }
+            for (col_index, token) in row.into_iter().enumerate() {
+                cells[Location::new(row_index, col_index)] = match token {
+                    "." => Cell::Open(None),
+                    "#" => Cell::Blocked,
+                    _ => Cell::Open(Some(token.parse().map_err(|_| {
+                        Error::Parse(format!(
+                            "Invalid cell '{token}' at ({row_index}, {col_index})."
+                        ))
+                    })?)),
+                };
+            }
+        }
+        Self::new(cells)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("hidato", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.cells.dim()
+    }
+
+    pub fn cell(&self, location: Location) -> Cell {
+        self.cells[location]
+    }
+
+    /// The number of open cells, which is also the largest number.
+    pub fn open(&self) -> usize {
+        self.open
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self.open.to_string().len();
+        for row in self.cells.rows() {
+            let row = row
+                .map(|cell| {
+                    let token = match cell {
+                        Cell::Blocked => "#".to_string(),
+                        Cell::Open(None) => ".".to_string(),
+                        Cell::Open(Some(number)) => number.to_string(),
+                    };
+                    format!("{token:>width$}")
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_open_blocked_and_given_cells() {
+        let board = Board::parse(include_str!("../../data/hidato/puzzles/seven01.txt")).unwrap();
+        assert_eq!(board.dim(), (7, 7));
+        assert_eq!(board.open(), 44);
+        assert_eq!(board.cell(Location::new(0, 0)), Cell::Blocked);
+        assert_eq!(board.cell(Location::new(0, 1)), Cell::Open(None));
+        assert_eq!(board.cell(Location::new(1, 6)), Cell::Open(Some(15)));
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        assert!(Board::parse("1 .\n. 5\n").is_err());
+        assert!(Board::parse("1 .\n. 1\n").is_err());
+        assert!(Board::parse("# #\n# #\n").is_err());
+        assert!(Board::parse("1 x\n. .\n").is_err());
+    }
+}
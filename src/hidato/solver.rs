@@ -0,0 +1,231 @@
+use std::fmt::Display;
+
+use crate::{
+    grid::Grid,
+    location::Location,
+    search::{self, Contradiction, SearchState},
+};
+
+use super::{Board, Cell};
+
+/// The numbers of a solved puzzle, with `None` for blocked cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    numbers: Grid<Option<u32>>,
+}
+
+impl Solution {
+    pub fn number(&self, location: Location) -> Option<u32> {
+        self.numbers[location]
+    }
+}
+
+/// Blocked cells are shown as `#`.
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let max = self.numbers.iter().flatten().max().copied().unwrap_or(0);
+        let width = max.to_string().len();
+        for row in self.numbers.rows() {
+            let row = row
+                .map(|number| {
+                    let token = number.map_or("#".to_string(), |number| number.to_string());
+                    format!("{token:>width$}")
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The number of king moves between two cells, which is the least number of steps between their numbers.
+fn distance(first: Location, second: Location) -> usize {
+    first
+        .row
+        .abs_diff(second.row)
+        .max(first.col.abs_diff(second.col))
+}
+
+#[derive(Clone, Debug)]
+struct State<'a> {
+    board: &'a Board,
+    /// The cells each number may still go in, where the number `n` is at index `n - 1`.
+    places: Vec<Vec<Location>>,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board) -> Self {
+        let mut places = vec![vec![]; board.open()];
+        let mut open = vec![];
+        for loc in Location::grid_iter(board.dim()) {
+            match board.cell(loc) {
+                Cell::Blocked => {}
+                Cell::Open(None) => open.push(loc),
+                Cell::Open(Some(number)) => places[number as usize - 1] = vec![loc],
+            }
+        }
+        for places in places.iter_mut().filter(|places| places.is_empty()) {
+            places.clone_from(&open);
+        }
+        Self { board, places }
+    }
+
+    /// Keeps only the places of the number at `index` for which `keep` holds, returning whether anything changed.
+    /// Fails if no places are left.
+    fn retain(
+        &mut self,
+        index: usize,
+        keep: impl Fn(Location) -> bool,
+    ) -> Result<bool, Contradiction> {
+        let places = &mut self.places[index];
+        let before = places.len();
+        places.retain(|&loc| keep(loc));
+        if places.is_empty() {
+            return Err(Contradiction);
+        }
+        Ok(places.len() != before)
+    }
+
+    /// A cell taken by a number cannot hold any other, and a cell only one number can go in holds that number.
+    fn apply_cells(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for index in 0..self.places.len() {
+            if let [place] = self.places[index][..] {
+                for other in (0..self.places.len()).filter(|&other| other != index) {
+                    changed |= self.retain(other, |loc| loc != place)?;
+                }
+            }
+        }
+        let mut numbers = Grid::from_elem(self.board.dim(), vec![]);
+        for (index, places) in self.places.iter().enumerate() {
+            for loc in places {
+                numbers[*loc].push(index);
+            }
+        }
+        for loc in Location::grid_iter(self.board.dim()) {
+            match (self.board.cell(loc), &numbers[loc][..]) {
+                (Cell::Blocked, _) => {}
+                (_, []) => return Err(Contradiction),
+                (_, &[index]) => changed |= self.retain(index, |other| other == loc)?,
+                _ => {}
+            }
+        }
+        Ok(changed)
+    }
+
+    /// A number can only be as far from a placed number as the gap between them allows.
+    fn apply_gaps(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for index in 0..self.places.len() {
+            let [place] = self.places[index][..] else {
+                continue;
+            };
+            for other in 0..self.places.len() {
+                let gap = index.abs_diff(other);
+                changed |= self.retain(other, |loc| distance(loc, place) <= gap)?;
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Every number must touch a place of the number before it and of the number after it.
+    fn apply_neighbors(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for index in 0..self.places.len() {
+            let neighbors = [index.checked_sub(1), Some(index + 1)]
+                .into_iter()
+                .flatten()
+                .filter_map(|neighbor| self.places.get(neighbor))
+                .cloned()
+                .collect::<Vec<_>>();
+            changed |= self.retain(index, |loc| {
+                neighbors
+                    .iter()
+                    .all(|places| places.iter().any(|&other| distance(loc, other) == 1))
+            })?;
+        }
+        Ok(changed)
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.apply_cells()?;
+            changed |= self.apply_gaps()?;
+            changed |= self.apply_neighbors()?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(index) = (0..self.places.len())
+            .filter(|&index| self.places[index].len() > 1)
+            .min_by_key(|&index| self.places[index].len())
+        else {
+            return vec![];
+        };
+        self.places[index]
+            .iter()
+            .map(|&place| {
+                let mut state = self.clone();
+                state.places[index] = vec![place];
+                state
+            })
+            .collect()
+    }
+}
+
+pub fn solve(board: &Board) -> Option<Solution> {
+    search::solve(State::new(board)).map(|state| {
+        let mut numbers = Grid::from_elem(board.dim(), None);
+        for (index, places) in state.places.iter().enumerate() {
+            numbers[places[0]] = Some(index as u32 + 1);
+        }
+        Solution { numbers }
+    })
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    search::count_solutions(State::new(board), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follows_rules(board: &Board, solution: &Solution) -> bool {
+        let dim = board.dim();
+        let mut places = vec![None; board.open() + 1];
+        for loc in Location::grid_iter(dim) {
+            match (board.cell(loc), solution.number(loc)) {
+                (Cell::Blocked, None) => {}
+                (Cell::Open(given), Some(number)) if given.is_none_or(|given| given == number) => {
+                    match places.get_mut(number as usize) {
+                        Some(place @ None) if number > 0 => *place = Some(loc),
+                        _ => return false,
+                    }
+                }
+                _ => return false,
+            }
+        }
+        // Every number from 1 up is placed once, so the places are consecutive when each touches the next.
+        places[1..].windows(2).all(|pair| {
+            let (Some(a), Some(b)) = (pair[0], pair[1]) else {
+                return false;
+            };
+            a.neighbors(dim).contains(&Some(b))
+        })
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/hidato/puzzles/seven01.txt")).unwrap();
+        let solution = solve(&board).unwrap();
+        assert!(follows_rules(&board, &solution));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
@@ -0,0 +1,308 @@
+//! A history of batch runs, such as solving the sudoku grid sets, so that changes to the solvers can be checked for
+//! whether they made solving slower or worse over time.
+//!
+//! Each run appends a [`RunSummary`] per set to a file of JSON lines, which is never rewritten, so that the history
+//! can be kept across branches and merged by concatenating files. Runs are compared set by set with [`compare`].
+
+use std::{
+    io::{ErrorKind, Write},
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{Error, Result},
+    report::SetReport,
+};
+
+/// How one set of puzzles fared in a run.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunSummary {
+    /// The milliseconds since the Unix epoch when the run started, which every set of the run shares.
+    pub run: u64,
+    /// A name for the run, such as the branch or change it was made on, for picking it as a baseline.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub dataset: String,
+    /// The version of this crate that solved the set.
+    pub version: String,
+    /// The hash of the solver config the set was solved with, by [`config_hash`], so that runs with different
+    /// configs are not mistaken for a change in the solver.
+    pub config: String,
+    pub puzzles: u64,
+    pub solved: u64,
+    pub steps: u64,
+    pub guesses: u64,
+    pub total_micros: u64,
+    pub median_micros: u64,
+    pub p90_micros: u64,
+    pub slowest_micros: u64,
+}
+
+impl RunSummary {
+    pub fn new(run: u64, label: Option<String>, config: String, set: &SetReport) -> Self {
+        let micros = |duration: Duration| duration.as_micros().try_into().unwrap_or(u64::MAX);
+        Self {
+            run,
+            label,
+            dataset: set.name.clone(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            config,
+            puzzles: set.puzzles,
+            solved: set.solved,
+            steps: set.stats.steps,
+            guesses: set.stats.guesses,
+            total_micros: micros(set.total_time()),
+            median_micros: micros(set.time_quantile(0.5)),
+            p90_micros: micros(set.time_quantile(0.9)),
+            slowest_micros: micros(set.time_quantile(1.0)),
+        }
+    }
+
+    /// The metrics that runs are compared by.
+    fn metrics(&self) -> [(Metric, u64); 7] {
+        [
+            (Metric::Solved, self.solved),
+            (Metric::Steps, self.steps),
+            (Metric::Guesses, self.guesses),
+            (Metric::Total, self.total_micros),
+            (Metric::Median, self.median_micros),
+            (Metric::P90, self.p90_micros),
+            (Metric::Slowest, self.slowest_micros),
+        ]
+    }
+}
+
+/// The id of a run starting now.
+pub fn new_run() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_millis().try_into().unwrap_or(u64::MAX))
+}
+
+/// The first 64 bits of the BLAKE3 hash of the config written as JSON, as 16 lowercase hex digits.
+pub fn config_hash(config: &impl Serialize) -> Result<String> {
+    let hash = blake3::hash(serde_json::to_string(config)?.as_bytes());
+    Ok(hash.as_bytes()[..8]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+/// Every run summary recorded, in the order they were recorded.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct History {
+    pub summaries: Vec<RunSummary>,
+}
+
+impl History {
+    /// Reads the history at the path, or returns an empty one if there is none yet.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(source) => {
+                return Err(Error::Read {
+                    puzzle: "run history",
+                    path: path.to_owned(),
+                    source,
+                })
+            }
+        };
+        let summaries = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<serde_json::Result<_>>()?;
+        Ok(Self { summaries })
+    }
+
+    /// Appends the summaries to the history at the path, creating it if needed.
+    pub fn append(path: impl AsRef<Path>, summaries: &[RunSummary]) -> Result<()> {
+        let path = path.as_ref();
+        let write_error = |source| Error::Write {
+            puzzle: "run history",
+            path: path.to_owned(),
+            source,
+        };
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(write_error)?;
+        }
+        let mut text = String::new();
+        for summary in summaries {
+            text.push_str(&serde_json::to_string(summary)?);
+            text.push('\n');
+        }
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(text.as_bytes()))
+            .map_err(write_error)
+    }
+
+    /// The ids of the runs, from the first recorded to the latest.
+    pub fn runs(&self) -> Vec<u64> {
+        let mut runs = vec![];
+        for summary in &self.summaries {
+            if !runs.contains(&summary.run) {
+                runs.push(summary.run);
+            }
+        }
+        runs
+    }
+
+    /// The summaries of the sets of the run.
+    pub fn run(&self, run: u64) -> Vec<&RunSummary> {
+        self.summaries
+            .iter()
+            .filter(|summary| summary.run == run)
+            .collect()
+    }
+
+    /// The latest run with the label, or the run with the id if the name is a number.
+    pub fn find(&self, name: &str) -> Option<u64> {
+        self.runs().into_iter().rev().find(|&run| {
+            name.parse() == Ok(run)
+                || self
+                    .run(run)
+                    .iter()
+                    .any(|summary| summary.label.as_deref() == Some(name))
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+    Solved,
+    Steps,
+    Guesses,
+    Total,
+    Median,
+    P90,
+    Slowest,
+}
+
+impl Metric {
+    pub fn name(self) -> &'static str {
+        match self {
+            Metric::Solved => "solved",
+            Metric::Steps => "steps",
+            Metric::Guesses => "guesses",
+            Metric::Total => "total_time",
+            Metric::Median => "median_time",
+            Metric::P90 => "p90_time",
+            Metric::Slowest => "slowest_time",
+        }
+    }
+
+    /// Whether the metric is a time in microseconds.
+    pub fn is_time(self) -> bool {
+        matches!(
+            self,
+            Metric::Total | Metric::Median | Metric::P90 | Metric::Slowest
+        )
+    }
+}
+
+/// A metric of a set in a baseline run and in a later one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    pub dataset: String,
+    pub metric: Metric,
+    pub baseline: u64,
+    pub latest: u64,
+}
+
+impl Change {
+    /// The change from the baseline as a share of it, such as 0.1 for 10% more. 0 when both are 0.
+    pub fn relative(&self) -> f64 {
+        if self.baseline == 0 {
+            return if self.latest == 0 { 0.0 } else { f64::INFINITY };
+        }
+        (self.latest as f64 - self.baseline as f64) / self.baseline as f64
+    }
+
+    /// Whether the latest run did worse: solved fewer puzzles, or needed more than `tolerance` more of anything else,
+    /// as a share of the baseline. Times are noisy, so a tolerance of some percent suits them.
+    pub fn is_regression(&self, tolerance: f64) -> bool {
+        match self.metric {
+            Metric::Solved => self.latest < self.baseline,
+            _ => self.relative() > tolerance,
+        }
+    }
+}
+
+/// Compares every metric of the sets that both runs solved, in the order of the latest run.
+pub fn compare(baseline: &[&RunSummary], latest: &[&RunSummary]) -> Vec<Change> {
+    latest
+        .iter()
+        .filter_map(|latest| {
+            let baseline = baseline
+                .iter()
+                .find(|baseline| baseline.dataset == latest.dataset)?;
+            Some(baseline.metrics().into_iter().zip(latest.metrics()).map(
+                |((metric, baseline), (_, new))| Change {
+                    dataset: latest.dataset.clone(),
+                    metric,
+                    baseline,
+                    latest: new,
+                },
+            ))
+        })
+        .flatten()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::SolveStats;
+
+    fn summary(run: u64, label: &str, millis: u64, solved: bool) -> RunSummary {
+        let mut set = SetReport::new("easy50");
+        let stats = SolveStats {
+            steps: 10,
+            ..SolveStats::default()
+        };
+        set.add(solved, Duration::from_millis(millis), "groups", &stats);
+        RunSummary::new(
+            run,
+            Some(label.to_string()),
+            config_hash(&"default").unwrap(),
+            &set,
+        )
+    }
+
+    #[test]
+    fn compares_runs_from_the_history() {
+        let dir = std::env::temp_dir().join(format!("puzzles-history-{}", std::process::id()));
+        let path = dir.join("history.jsonl");
+        History::append(&path, &[summary(1, "main", 10, true)]).unwrap();
+        History::append(&path, &[summary(2, "slower", 20, true)]).unwrap();
+        History::append(&path, &[summary(3, "unsolved", 10, false)]).unwrap();
+        let history = History::load(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(history.runs(), [1, 2, 3]);
+        assert_eq!(history.find("main"), Some(1));
+        assert_eq!(history.find("2"), Some(2));
+        assert_eq!(history.find("unknown"), None);
+
+        let regressions = |baseline, latest| {
+            compare(&history.run(baseline), &history.run(latest))
+                .into_iter()
+                .filter(|change| change.is_regression(0.1))
+                .map(|change| change.metric)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(regressions(1, 1), []);
+        assert!(regressions(1, 2).contains(&Metric::Median));
+        assert!(!regressions(1, 2).contains(&Metric::Steps));
+        assert!(regressions(2, 1).is_empty());
+        assert!(regressions(1, 3).contains(&Metric::Solved));
+    }
+}
@@ -0,0 +1,134 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+};
+
+/// A hitori puzzle: a grid of numbers where some duplicates must be shaded such that no number appears twice
+/// unshaded in a row or column, no two shaded cells are orthogonally adjacent,
+/// and the unshaded cells are orthogonally connected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    numbers: Grid<u32>,
+}
+
+impl Board {
+    pub fn new(numbers: Grid<u32>) -> Result<Self> {
+        let (height, width) = numbers.dim();
+        ensure!(
+            height > 0 && width > 0,
+            Invalid,
+            "Hitori grids must have at least one cell."
+        );
+        Ok(Self { numbers })
+    }
+
+    /// Parses a grid of numbers. Rows are either strings of digits or, for numbers above 9,
+    /// whitespace separated numbers.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                if line.contains(char::is_whitespace) {
+                    line.split_whitespace()
+                        .map(|token| {
+                            token
+                                .parse::<u32>()
+                                .map_err(|_| Error::Parse(format!("Invalid number '{token}'.")))
+                        })
+                        .collect::<Result<Vec<_>>>()
+                } else {
+                    line.chars()
+                        .map(|c| {
+                            c.to_digit(10)
+                                .ok_or_else(|| Error::Parse(format!("Invalid digit '{c}'.")))
+                        })
+                        .collect::<Result<Vec<_>>>()
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        let mut numbers = Grid::from_elem((height, width), 0);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} numbers in row {row_index}. Got {}.",
+                    row.len()
+                );
+            }
+            for (col_index, number) in row.into_iter().enumerate() {
+                numbers[Location::new(row_index, col_index)] = number;
+            }
+        }
+        Self::new(numbers)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("hitori", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.numbers.dim()
+    }
+
+    pub fn number(&self, location: Location) -> u32 {
+        self.numbers[location]
+    }
+
+    /// The other cells in the same row or column with the same number as the cell at `location`.
+    pub fn duplicates(&self, location: Location) -> impl Iterator<Item = Location> + '_ {
+        let (height, width) = self.dim();
+        let number = self.number(location);
+        let row = (0..width).map(move |col| Location::new(location.row, col));
+        let col = (0..height).map(move |row| Location::new(row, location.col));
+        row.chain(col)
+            .filter(move |&other| other != location && self.number(other) == number)
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let wide = self.numbers.iter().any(|&number| number > 9);
+        for row in self.numbers.rows() {
+            for (index, number) in row.enumerate() {
+                if wide && index > 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "{number}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_digits_and_wide_numbers() {
+        let board = Board::parse(include_str!("../../data/hitori/puzzles/eight01.txt")).unwrap();
+        assert_eq!(board.dim(), (8, 8));
+        assert_eq!(board.number(Location::new(0, 1)), 5);
+        assert_eq!(
+            board.duplicates(Location::new(0, 0)).collect::<Vec<_>>(),
+            [Location::new(7, 0)]
+        );
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        let wide = Board::parse("10 2\n2 10\n").unwrap();
+        assert_eq!(wide.number(Location::new(1, 1)), 10);
+        assert_eq!(Board::parse(wide.to_string()).unwrap(), wide);
+        assert!(Board::parse("12\n3\n").is_err());
+        assert!(Board::parse("1a\n23\n").is_err());
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{
+    location::Location,
+    search::{self, Contradiction, SearchState},
+    shading::{Shade, ShadeGrid},
+};
+
+use super::Board;
+
+#[derive(Clone, Debug)]
+struct State<'a> {
+    board: &'a Board,
+    grid: ShadeGrid,
+}
+
+impl<'a> State<'a> {
+    /// Only duplicates are ever shaded, so every cell without one starts out unshaded.
+    fn new(board: &'a Board) -> Result<Self, Contradiction> {
+        let mut grid = ShadeGrid::new(board.dim());
+        for loc in Location::grid_iter(board.dim()) {
+            if board.duplicates(loc).next().is_none() {
+                grid.set(loc, Shade::Unshaded)?;
+            }
+        }
+        Ok(Self { board, grid })
+    }
+
+    /// Shaded cells cannot touch and an unshaded number forces its duplicates to be shaded.
+    fn apply_local_rules(&mut self) -> Result<bool, Contradiction> {
+        let dim = self.grid.dim();
+        let mut changed = false;
+        for loc in Location::grid_iter(dim) {
+            match self.grid.get(loc) {
+                Shade::Shaded => {
+                    for adj in loc.adjacents(dim).into_iter().flatten() {
+                        changed |= self.grid.set(adj, Shade::Unshaded)?;
+                    }
+                }
+                Shade::Unshaded => {
+                    for duplicate in self.board.duplicates(loc) {
+                        changed |= self.grid.set(duplicate, Shade::Shaded)?;
+                    }
+                }
+                Shade::Unknown => {}
+            }
+        }
+        Ok(changed)
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.apply_local_rules()?;
+            changed |= self.grid.keep_connected(Shade::Unshaded)?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(location) = self.grid.first_unknown() else {
+            return vec![];
+        };
+        self.grid
+            .branch_on(location)
+            .into_iter()
+            .map(|grid| Self {
+                grid,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+/// Returns the solved grid with shaded cells as `#` and unshaded cells as `.`,
+/// or `None` if the puzzle has no solution.
+pub fn solve(board: &Board) -> Option<ShadeGrid> {
+    let state = State::new(board).ok()?;
+    search::solve(state).map(|state| state.grid)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    State::new(board).map_or(0, |state| search::count_solutions(state, limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::region;
+
+    fn follows_rules(board: &Board, grid: &ShadeGrid) -> bool {
+        let dim = board.dim();
+        let unique = Location::grid_iter(dim)
+            .filter(|&loc| !grid.is_shaded(loc))
+            .all(|loc| board.duplicates(loc).all(|other| grid.is_shaded(other)));
+        let apart = Location::grid_iter(dim)
+            .filter(|&loc| grid.is_shaded(loc))
+            .all(|loc| {
+                loc.adjacents(dim)
+                    .into_iter()
+                    .flatten()
+                    .all(|adj| !grid.is_shaded(adj))
+            });
+        let unshaded = |loc| !grid.is_shaded(loc);
+        unique && apart && region::is_connected(dim, unshaded, unshaded)
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/hitori/puzzles/eight01.txt")).unwrap();
+        let grid = solve(&board).unwrap();
+        assert!(grid.is_complete());
+        assert!(follows_rules(&board, &grid));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
@@ -0,0 +1,164 @@
+//! Identifiers of puzzles that are the same across runs and machines, so that solutions, statistics and traces of
+//! the same puzzle can be joined however they were produced.
+//!
+//! An id hashes the kind of puzzle with its canonical form, which is the instance written by
+//! [`Puzzle::format_instance`], so the same puzzle gets the same id however it was written, and puzzles of different
+//! kinds that happen to be written alike do not share one.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{Error, Result},
+    puzzle::{self, Puzzle},
+};
+
+/// The first 64 bits of the BLAKE3 hash of a puzzle, written as 16 lowercase hex digits so that it fits in file
+/// names.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PuzzleId([u8; 8]);
+
+impl PuzzleId {
+    /// The id of the puzzle of the kind, by the name [`puzzle::visit`] knows it by, with the canonical form.
+    pub fn new(kind: &str, canonical: &str) -> Self {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(kind.as_bytes());
+        // Separates the kind from the puzzle, since neither contains a null byte.
+        hasher.update(&[0]);
+        hasher.update(canonical.as_bytes());
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&hasher.finalize().as_bytes()[..8]);
+        Self(bytes)
+    }
+
+    pub fn of<P: Puzzle>(instance: &P::Instance) -> Self {
+        Self::new(puzzle::kind::<P>(), &P::format_instance(instance))
+    }
+
+    /// An id shared by every instance that is the same puzzle written another way, by
+    /// [`Puzzle::isomorphic_form`]. Differs from [`PuzzleId::of`] unless the instance is already in that form.
+    pub fn up_to_isomorphism<P: Puzzle>(instance: &P::Instance) -> Self {
+        Self::new(puzzle::kind::<P>(), &P::isomorphic_form(instance))
+    }
+}
+
+impl fmt::Display for PuzzleId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PuzzleId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let invalid = || Error::Parse(format!("Expected a puzzle id of 16 hex digits. Got '{s}'."));
+        if s.len() != 16 || !s.is_ascii() {
+            return Err(invalid());
+        }
+        let mut bytes = [0; 8];
+        for (byte, digits) in bytes.iter_mut().zip(s.as_bytes().chunks(2)) {
+            let digits = std::str::from_utf8(digits).map_err(|_| invalid())?;
+            *byte = u8::from_str_radix(digits, 16).map_err(|_| invalid())?;
+        }
+        Ok(Self(bytes))
+    }
+}
+
+impl TryFrom<String> for PuzzleId {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<PuzzleId> for String {
+    fn from(id: PuzzleId) -> Self {
+        id.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{camping::Camping, sudoku::Sudoku};
+
+    #[test]
+    fn identifies_puzzles_however_written() {
+        let line = include_str!("../data/sudoku/grids/easy50.txt")
+            .lines()
+            .next()
+            .unwrap();
+        let grid = line
+            .as_bytes()
+            .chunks(9)
+            .map(|row| std::str::from_utf8(row).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let board = Sudoku::parse(line).unwrap();
+        let id = PuzzleId::of::<Sudoku>(&board);
+        assert_eq!(PuzzleId::of::<Sudoku>(&Sudoku::parse(&grid).unwrap()), id);
+        assert_eq!(
+            PuzzleId::new("sudoku", &Sudoku::format_instance(&board)),
+            id
+        );
+        assert_ne!(
+            PuzzleId::new("kenken", &Sudoku::format_instance(&board)),
+            id
+        );
+
+        let text = id.to_string();
+        assert_eq!(text.len(), 16);
+        assert_eq!(text.parse::<PuzzleId>().unwrap(), id);
+        assert_eq!(serde_json::to_string(&id).unwrap(), format!("\"{text}\""));
+        assert!("not an id".parse::<PuzzleId>().is_err());
+        assert!("0123456789abcdeg".parse::<PuzzleId>().is_err());
+    }
+
+    #[test]
+    fn identifies_rotated_and_relabeled_puzzles_alike() {
+        let line = include_str!("../data/sudoku/grids/easy50.txt")
+            .lines()
+            .next()
+            .unwrap();
+        // Rotates a quarter turn and swaps the values 1 and 2.
+        let rotated = (0..81)
+            .map(
+                |index| match line.as_bytes()[(8 - index % 9) * 9 + index / 9] {
+                    b'1' => '2',
+                    b'2' => '1',
+                    byte => char::from(byte),
+                },
+            )
+            .collect::<String>();
+        let board = Sudoku::parse(line).unwrap();
+        let rotated = Sudoku::parse(&rotated).unwrap();
+        assert_ne!(
+            PuzzleId::of::<Sudoku>(&rotated),
+            PuzzleId::of::<Sudoku>(&board)
+        );
+        assert_eq!(
+            PuzzleId::up_to_isomorphism::<Sudoku>(&rotated),
+            PuzzleId::up_to_isomorphism::<Sudoku>(&board)
+        );
+
+        let map = Camping::parse(include_str!("../data/camping/maps/map01.txt")).unwrap();
+        let solution = Camping::solve(&map).unwrap().unwrap();
+        let forms = crate::grid::Symmetry::ALL.map(|symmetry| {
+            let transformed = map.transformed(symmetry);
+            assert_eq!(
+                Camping::solve(&transformed).unwrap(),
+                Some(solution.transformed(symmetry))
+            );
+            PuzzleId::up_to_isomorphism::<Camping>(&transformed)
+        });
+        assert!(forms.iter().all(|&id| id == forms[0]));
+    }
+}
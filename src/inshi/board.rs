@@ -0,0 +1,228 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    cage::{Cage, Operation},
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+    puzzlink::{self, Clue, PuzzLink},
+    region,
+};
+
+/// An inshi no heya puzzle: a Latin square divided into rooms one cell wide, some with the product of the numbers
+/// in the room.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    rooms: Grid<usize>,
+    room_cells: Vec<Vec<Location>>,
+    products: Vec<Option<u32>>,
+}
+
+impl Board {
+    pub const MAX_SIZE: usize = 9;
+
+    pub fn new(rooms: Grid<usize>, products: Vec<Option<u32>>) -> Result<Self> {
+        let (height, width) = rooms.dim();
+        ensure!(
+            height == width && (1..=Self::MAX_SIZE).contains(&height), Invalid,
+            "Inshi no heya grids must be square and between 1x1 and {0}x{0}, but got {height}x{width}.",
+            Self::MAX_SIZE
+        );
+        let mut room_cells = vec![vec![]; products.len()];
+        for loc in Location::grid_iter(rooms.dim()) {
+            let room = rooms[loc];
+            ensure!(
+                room < products.len(),
+                Invalid,
+                "Cell {loc} is in room {room}, but there are only {} rooms.",
+                products.len()
+            );
+            room_cells[room].push(loc);
+        }
+        for (room, cells) in room_cells.iter().enumerate() {
+            ensure!(!cells.is_empty(), Invalid, "Room {room} has no cells.");
+            let first = cells[0];
+            let straight = cells.iter().all(|loc| loc.row == first.row)
+                || cells.iter().all(|loc| loc.col == first.col);
+            ensure!(
+                straight
+                    && region::is_connected(
+                        rooms.dim(),
+                        |loc| rooms[loc] == room,
+                        |loc| rooms[loc] == room
+                    ),
+                Invalid,
+                "Room {room} is not a single row or column of cells."
+            );
+        }
+        Ok(Self {
+            rooms,
+            room_cells,
+            products,
+        })
+    }
+
+    /// Parses a grid of characters where each distinct character is a room, followed by a blank line and a line
+    /// such as `A 12` for every room with a product.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let mut lines = string.as_ref().lines().map(str::trim);
+        let rows = lines
+            .by_ref()
+            .skip_while(|line| line.is_empty())
+            .take_while(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let (rooms, num_rooms) = region::parse_regions(&rows)?;
+        let names = rows
+            .iter()
+            .flat_map(|row| row.chars())
+            .fold(vec![], |mut names, c| {
+                if !names.contains(&c) {
+                    names.push(c);
+                }
+                names
+            });
+        let mut products = vec![None; num_rooms];
+        for line in lines.filter(|line| !line.is_empty()) {
+            let mut parts = line.split_whitespace();
+            let (Some(name), Some(product), None) = (parts.next(), parts.next(), parts.next())
+            else {
+                bail!(
+                    Parse,
+                    "Expected a room character and a number. Got '{line}'."
+                );
+            };
+            let mut name_chars = name.chars();
+            let (Some(name), None) = (name_chars.next(), name_chars.next()) else {
+                bail!(
+                    Parse,
+                    "Room names must be a single character. Got '{name}'."
+                );
+            };
+            let room = names
+                .iter()
+                .position(|&other| other == name)
+                .ok_or_else(|| {
+                    Error::Parse(format!("Room '{name}' does not appear in the grid."))
+                })?;
+            ensure!(
+                products[room].is_none(),
+                Parse,
+                "Room '{name}' has more than one number."
+            );
+            products[room] = Some(product.parse().map_err(|_| {
+                Error::Parse(format!("Invalid number '{product}' for room '{name}'."))
+            })?);
+        }
+        Self::new(rooms, products)
+    }
+
+    /// Imports a puzzle from a puzz.link or pzv.jp URL.
+    pub fn from_puzz_link(url: &str) -> Result<Self> {
+        let link = PuzzLink::parse(url)?;
+        link.expect_kind(&["inshi"])?;
+        let (rooms, num_rooms, rest) = puzzlink::decode_rooms(&link.body, link.width, link.height)?;
+        let products = puzzlink::decode_number16(rest, num_rooms)?
+            .into_iter()
+            .map(|clue| match clue {
+                None | Some(Clue::Unknown) => None,
+                Some(Clue::Number(number)) => Some(number),
+            })
+            .collect();
+        Self::new(rooms, products)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("inshi", path)?;
+        Self::parse(string)
+    }
+
+    pub fn size(&self) -> usize {
+        self.rooms.height()
+    }
+
+    pub fn room(&self, location: Location) -> usize {
+        self.rooms[location]
+    }
+
+    /// The cells of each room.
+    pub fn rooms(&self) -> &[Vec<Location>] {
+        &self.room_cells
+    }
+
+    /// The product of the numbers in each room, if given.
+    pub fn products(&self) -> &[Option<u32>] {
+        &self.products
+    }
+
+    /// A multiplication cage for every room with a product.
+    pub fn cages(&self) -> Vec<Cage> {
+        self.room_cells
+            .iter()
+            .zip(self.products.iter())
+            .filter_map(|(cells, &product)| {
+                Some(Cage {
+                    cells: cells.clone(),
+                    operation: Operation::Multiply,
+                    target: product?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const NAMES: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+        let name = |room: usize| NAMES[room % NAMES.len()] as char;
+        for row in self.rooms.rows() {
+            writeln!(f, "{}", row.map(|&room| name(room)).collect::<String>())?;
+        }
+        if self.products.iter().any(Option::is_some) {
+            writeln!(f)?;
+            for (room, product) in self.products.iter().enumerate() {
+                if let Some(product) = product {
+                    writeln!(f, "{} {product}", name(room))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rooms_and_products() {
+        let board = Board::parse(include_str!("../../data/inshi/puzzles/six01.txt")).unwrap();
+        assert_eq!(board.size(), 6);
+        assert_eq!(board.rooms().len(), 19);
+        assert_eq!(
+            board.room(Location::new(0, 0)),
+            board.room(Location::new(2, 0))
+        );
+        assert_eq!(
+            board.rooms()[3],
+            vec![
+                Location::new(1, 1),
+                Location::new(1, 2),
+                Location::new(1, 3)
+            ]
+        );
+        assert_eq!(board.products()[3], Some(90));
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        assert_eq!(
+            Board::from_puzz_link(
+                "https://puzz.link/p?inshi/6/6/kivuurfnsv6f-28c-1e-5a23c4231-1e-14-12ac861"
+            )
+            .unwrap(),
+            board
+        );
+        assert!(Board::parse("AB\nBA\n").is_err());
+        assert!(Board::parse("AB\nAB\n\nC 2\n").is_err());
+        assert!(Board::parse("AB\nAB\n\nA x\n").is_err());
+        assert!(Board::parse("ABC\nABC\n").is_err());
+    }
+}
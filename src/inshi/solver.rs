@@ -0,0 +1,84 @@
+use crate::{
+    cage::Cage,
+    latin::LatinSquare,
+    search::{self, Contradiction, SearchState},
+};
+
+use super::Board;
+
+#[derive(Clone, Debug)]
+struct State<'a> {
+    cages: &'a [Cage],
+    square: LatinSquare,
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.square.propagate()?;
+            for cage in self.cages {
+                changed |= cage.apply(&mut self.square)?;
+            }
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(location) = self.square.branch_location() else {
+            return vec![];
+        };
+        self.square
+            .branch_on(location)
+            .into_iter()
+            .map(|square| Self {
+                square,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+/// Returns the solved grid, or `None` if the puzzle has no solution.
+pub fn solve(board: &Board) -> Option<LatinSquare> {
+    let cages = board.cages();
+    let state = State {
+        cages: &cages,
+        square: LatinSquare::new(board.size()),
+    };
+    search::solve(state).map(|state| state.square)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    let cages = board.cages();
+    let state = State {
+        cages: &cages,
+        square: LatinSquare::new(board.size()),
+    };
+    search::count_solutions(state, limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/inshi/puzzles/six01.txt")).unwrap();
+        let values = solve(&board).unwrap().values().unwrap();
+        assert!(crate::latin::is_latin(&values));
+        assert!(board
+            .rooms()
+            .iter()
+            .zip(board.products())
+            .all(|(cells, &product)| product.is_none_or(|product| {
+                cells
+                    .iter()
+                    .map(|&loc| u32::from(values[loc]))
+                    .product::<u32>()
+                    == product
+            })));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
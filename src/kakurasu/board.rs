@@ -0,0 +1,115 @@
+use std::{fmt::Display, path};
+
+use crate::error::{ensure, read_to_string, Error, Result};
+
+/// A kakurasu puzzle: shade cells such that the numbers outside the grid are the sums of the shaded cells' weights
+/// in each line. A cell weighs its column number, counting from 1, towards its row's sum, and its row number
+/// towards its column's sum.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    row_sums: Vec<Option<usize>>,
+    col_sums: Vec<Option<usize>>,
+}
+
+impl Board {
+    pub fn new(row_sums: Vec<Option<usize>>, col_sums: Vec<Option<usize>>) -> Result<Self> {
+        let (height, width) = (row_sums.len(), col_sums.len());
+        ensure!(
+            height > 0 && width > 0,
+            Invalid,
+            "Kakurasu grids must have at least one cell."
+        );
+        ensure!(
+            row_sums
+                .iter()
+                .flatten()
+                .all(|&sum| sum <= width * (width + 1) / 2)
+                && col_sums
+                    .iter()
+                    .flatten()
+                    .all(|&sum| sum <= height * (height + 1) / 2),
+            Invalid,
+            "Sums cannot be larger than the total weight of their line."
+        );
+        Ok(Self { row_sums, col_sums })
+    }
+
+    /// Parses a puzzle given as a line of row sums and a line of column sums, separated by commas with `?` for a
+    /// line without a sum.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let mut lines = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty());
+        let mut sums = |name: &str| -> Result<Vec<Option<usize>>> {
+            let line = lines
+                .next()
+                .ok_or_else(|| Error::Parse(format!("No line of {name}.")))?;
+            line.split(',')
+                .map(|sum| match sum.trim() {
+                    "?" => Ok(None),
+                    sum => sum.parse::<usize>().map(Some),
+                })
+                .collect::<Result<_, _>>()
+                .map_err(|_| {
+                    Error::Parse(format!(
+                        "Expected {name} separated by commas. Got '{line}'."
+                    ))
+                })
+        };
+        let row_sums = sums("row sums")?;
+        let col_sums = sums("column sums")?;
+        Self::new(row_sums, col_sums)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("kakurasu", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        (self.row_sums.len(), self.col_sums.len())
+    }
+
+    pub fn row_sums(&self) -> &[Option<usize>] {
+        &self.row_sums
+    }
+
+    pub fn col_sums(&self) -> &[Option<usize>] {
+        &self.col_sums
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sums = |sums: &[Option<usize>]| {
+            sums.iter()
+                .map(|sum| sum.map_or("?".to_string(), |sum| sum.to_string()))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        writeln!(f, "{}", sums(&self.row_sums))?;
+        writeln!(f, "{}", sums(&self.col_sums))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sums() {
+        let board = Board::parse(include_str!("../../data/kakurasu/puzzles/seven01.txt")).unwrap();
+        assert_eq!(board.dim(), (7, 7));
+        assert_eq!(board.row_sums()[0], Some(16));
+        assert_eq!(board.col_sums()[6], Some(14));
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        let unknown = Board::parse("?,3\n1,?\n").unwrap();
+        assert_eq!(unknown.row_sums(), [None, Some(3)]);
+        assert!(Board::parse("4,3\n1,?\n").is_err());
+        assert!(Board::parse("1,x\n1,1\n").is_err());
+        assert!(Board::parse("1,1\n").is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{
+    location::Location,
+    search::{self, Contradiction, SearchState},
+    shading::{self, Shade, ShadeGrid},
+};
+
+use super::Board;
+
+/// Whether some of `weights` add up to exactly `target`.
+fn reachable(weights: impl Iterator<Item = usize>, target: usize) -> bool {
+    let mut sums = vec![false; target + 1];
+    sums[0] = true;
+    for weight in weights {
+        for sum in (weight..=target).rev() {
+            sums[sum] |= sums[sum - weight];
+        }
+    }
+    sums[target]
+}
+
+#[derive(Clone, Debug)]
+struct State<'a> {
+    /// Every row and column with a sum, with its cells in order of increasing weight.
+    lines: &'a [(Vec<Location>, usize)],
+    grid: ShadeGrid,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &Board, lines: &'a [(Vec<Location>, usize)]) -> Self {
+        Self {
+            lines,
+            grid: ShadeGrid::new(board.dim()),
+        }
+    }
+
+    /// The shaded cells of every line must weigh exactly its sum. An undecided cell is shaded if the other undecided
+    /// cells cannot make up the rest of the sum without it, and unshaded if they cannot with it.
+    fn apply_sums(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for (cells, sum) in self.lines.iter() {
+            let weighted = || {
+                cells
+                    .iter()
+                    .enumerate()
+                    .map(|(index, &loc)| (loc, index + 1))
+            };
+            let shaded = weighted()
+                .filter(|&(loc, _)| self.grid.is_shaded(loc))
+                .map(|(_, weight)| weight)
+                .sum::<usize>();
+            let rest = sum.checked_sub(shaded).ok_or(Contradiction)?;
+            let unknown = weighted()
+                .filter(|&(loc, _)| self.grid.get(loc) == Shade::Unknown)
+                .collect::<Vec<_>>();
+            if !reachable(unknown.iter().map(|&(_, weight)| weight), rest) {
+                return Err(Contradiction);
+            }
+            for &(loc, weight) in unknown.iter() {
+                let others = || {
+                    unknown
+                        .iter()
+                        .filter(move |&&(other, _)| other != loc)
+                        .map(|&(_, weight)| weight)
+                };
+                if !reachable(others(), rest) {
+                    changed |= self.grid.set(loc, Shade::Shaded)?;
+                } else if weight > rest || !reachable(others(), rest - weight) {
+                    changed |= self.grid.set(loc, Shade::Unshaded)?;
+                }
+            }
+        }
+        Ok(changed)
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        while self.apply_sums()? {}
+        Ok(())
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(location) = self.grid.first_unknown() else {
+            return vec![];
+        };
+        self.grid
+            .branch_on(location)
+            .into_iter()
+            .map(|grid| Self {
+                grid,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+/// Returns the solved grid with shaded cells as `#` and unshaded cells as `.`, or `None` if the puzzle has no solution.
+pub fn solve(board: &Board) -> Option<ShadeGrid> {
+    let lines = shading::counted_lines(board.row_sums(), board.col_sums());
+    search::solve(State::new(board, &lines)).map(|state| state.grid)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    let lines = shading::counted_lines(board.row_sums(), board.col_sums());
+    search::count_solutions(State::new(board, &lines), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follows_rules(board: &Board, grid: &ShadeGrid) -> bool {
+        let (height, width) = board.dim();
+        let rows = (0..height).all(|row| {
+            board.row_sums()[row].is_none_or(|sum| {
+                let weights = (0..width).filter(|&col| grid.is_shaded(Location::new(row, col)));
+                weights.map(|col| col + 1).sum::<usize>() == sum
+            })
+        });
+        let cols = (0..width).all(|col| {
+            board.col_sums()[col].is_none_or(|sum| {
+                let weights = (0..height).filter(|&row| grid.is_shaded(Location::new(row, col)));
+                weights.map(|row| row + 1).sum::<usize>() == sum
+            })
+        });
+        rows && cols
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/kakurasu/puzzles/seven01.txt")).unwrap();
+        let grid = solve(&board).unwrap();
+        assert!(grid.is_complete());
+        assert!(follows_rules(&board, &grid));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
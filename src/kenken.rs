@@ -0,0 +1,26 @@
+mod board;
+pub use board::Board;
+mod solver;
+pub use solver::{count_solutions, solve};
+
+/// Kenken puzzles as a [`Puzzle`](crate::puzzle::Puzzle), in the text format of [`Board::parse`].
+pub struct Kenken;
+
+impl crate::puzzle::Puzzle for Kenken {
+    const NAME: &'static str = "kenken puzzle";
+
+    type Instance = Board;
+    type Solution = crate::latin::LatinSquare;
+
+    fn parse(input: &str) -> crate::Result<Board> {
+        Board::parse(input)
+    }
+
+    fn solve(board: &Board) -> crate::Result<Option<crate::latin::LatinSquare>> {
+        Ok(solve(board))
+    }
+
+    fn count_solutions(board: &Board, limit: usize) -> crate::Result<usize> {
+        Ok(count_solutions(board, limit))
+    }
+}
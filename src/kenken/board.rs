@@ -0,0 +1,210 @@
+use std::{fmt::Display, path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cage::{Cage, Operation},
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+};
+
+/// A kenken puzzle: a Latin square split into cages, where the values in each cage must combine to the
+/// cage's target using the cage's operation.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Board {
+    size: usize,
+    cages: Vec<Cage>,
+}
+
+#[derive(Deserialize)]
+struct BoardData {
+    size: usize,
+    cages: Vec<Cage>,
+}
+
+impl Board {
+    pub const MAX_SIZE: usize = 9;
+
+    pub fn new(size: usize, cages: Vec<Cage>) -> Result<Self> {
+        ensure!(
+            (1..=Self::MAX_SIZE).contains(&size),
+            Invalid,
+            "Kenken grids must be between 1x1 and {0}x{0}, but got {size}x{size}.",
+            Self::MAX_SIZE
+        );
+        let mut covered = Grid::from_elem((size, size), false);
+        for cage in cages.iter() {
+            ensure!(
+                cage.has_valid_size(),
+                Invalid,
+                "A cage with target {cage} cannot have {} cells.",
+                cage.cells.len()
+            );
+            ensure!(cage.target > 0, Invalid, "Cage targets must be positive.");
+            for &loc in cage.cells.iter() {
+                ensure!(
+                    loc.row < size && loc.col < size,
+                    Invalid,
+                    "Cage cell {loc} is outside the {size}x{size} grid."
+                );
+                let covered = &mut covered[loc];
+                ensure!(!*covered, Invalid, "Cell {loc} is in more than one cage.");
+                *covered = true;
+            }
+        }
+        if let Some(loc) = Location::grid_iter((size, size)).find(|&loc| !covered[loc]) {
+            bail!(Invalid, "Cell {loc} is not in any cage.");
+        }
+        Ok(Self { size, cages })
+    }
+
+    /// Parses either the JSON form of a board or the text form.
+    ///
+    /// The text form is a grid where each cage is marked by its own character, followed by an empty line and
+    /// a line per cage with its character and its target, followed by the operation unless the cage is a single cell.
+    /// ```text
+    /// AAB
+    /// CDB
+    /// CDD
+    ///
+    /// A 3+
+    /// B 1-
+    /// C 5+
+    /// D 3*
+    /// ```
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let string = string.as_ref();
+        if string.trim_start().starts_with('{') {
+            let data: BoardData = serde_json::from_str(string)
+                .map_err(|_| Error::Parse("Invalid kenken board JSON.".into()))?;
+            return Self::new(data.size, data.cages);
+        }
+        let mut lines = string.lines().map(str::trim);
+        let rows = lines
+            .by_ref()
+            .skip_while(|line| line.is_empty())
+            .take_while(|line| !line.is_empty())
+            .map(|line| line.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let size = rows.len();
+        let mut cages: Vec<(char, Cage)> = vec![];
+        for (row_index, row) in rows.iter().enumerate() {
+            ensure!(
+                row.len() == size,
+                Parse,
+                "Expected {size} characters in row {row_index}. Got '{}'.",
+                row.iter().collect::<String>()
+            );
+            for (col_index, &c) in row.iter().enumerate() {
+                let loc = Location::new(row_index, col_index);
+                match cages.iter_mut().find(|(id, _)| *id == c) {
+                    Some((_, cage)) => cage.cells.push(loc),
+                    None => cages.push((
+                        c,
+                        Cage {
+                            cells: vec![loc],
+                            operation: Operation::Given,
+                            target: 0,
+                        },
+                    )),
+                }
+            }
+        }
+        let mut defined = vec![false; cages.len()];
+        for line in lines.filter(|line| !line.is_empty()) {
+            let mut parts = line.split_whitespace();
+            let (Some(id), Some(clue), None) = (parts.next(), parts.next(), parts.next()) else {
+                bail!(Parse, "Expected a cage character and a clue. Got '{line}'.");
+            };
+            let mut id_chars = id.chars();
+            let (Some(id), None) = (id_chars.next(), id_chars.next()) else {
+                bail!(
+                    Parse,
+                    "Cage identifiers must be a single character. Got '{id}'."
+                );
+            };
+            let index = cages
+                .iter()
+                .position(|&(other, _)| other == id)
+                .ok_or_else(|| Error::Parse(format!("Cage '{id}' does not appear in the grid.")))?;
+            ensure!(
+                !defined[index],
+                Parse,
+                "Cage '{id}' is defined more than once."
+            );
+            defined[index] = true;
+            let cage = &mut cages[index].1;
+            let mut clue_chars = clue.chars();
+            let operation = clue_chars.next_back().and_then(Operation::from_symbol);
+            let target = match operation {
+                Some(_) => clue_chars.as_str(),
+                None => clue,
+            };
+            cage.target = target
+                .parse()
+                .map_err(|_| Error::Parse(format!("Invalid target '{target}' for cage '{id}'.")))?;
+            cage.operation = operation.unwrap_or(Operation::Given);
+        }
+        if let Some(index) = defined.iter().position(|&defined| !defined) {
+            bail!(Parse, "Cage '{}' has no clue.", cages[index].0);
+        }
+        Self::new(size, cages.into_iter().map(|(_, cage)| cage).collect())
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("kenken", path)?;
+        Self::parse(string)
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn cages(&self) -> &[Cage] {
+        &self.cages
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ids = ('A'..='Z').chain('a'..='z').chain('0'..='9');
+        let mut grid = Grid::from_elem((self.size, self.size), '?');
+        for (cage, id) in self.cages.iter().zip(ids.clone()) {
+            for &loc in cage.cells.iter() {
+                grid[loc] = id;
+            }
+        }
+        for row in grid.rows() {
+            writeln!(f, "{}", row.collect::<String>())?;
+        }
+        writeln!(f)?;
+        for (cage, id) in self.cages.iter().zip(ids) {
+            writeln!(f, "{id} {cage}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_text_and_json() {
+        let board = Board::parse(include_str!("../../data/kenken/puzzles/six01.txt")).unwrap();
+        assert_eq!((board.size(), board.cages().len()), (6, 15));
+        assert_eq!(board.cages()[1].operation, Operation::Multiply);
+        assert_eq!(board.cages()[1].target, 288);
+        assert_eq!(board.cages()[13].operation, Operation::Given);
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+
+        let board = Board::parse(include_str!("../../data/kenken/puzzles/three01.json")).unwrap();
+        assert_eq!((board.size(), board.cages().len()), (3, 4));
+        assert_eq!(board.cages()[1].operation, Operation::Subtract);
+
+        assert!(Board::parse("AB\nAB\n\nA 3+\n").is_err());
+        assert!(Board::parse("AA\nAB\n\nA 3+\nB 1\nC 2\n").is_err());
+    }
+}
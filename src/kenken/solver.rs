@@ -0,0 +1,83 @@
+use crate::{
+    cage::Cage,
+    latin::LatinSquare,
+    search::{self, Contradiction, SearchState},
+};
+
+use super::Board;
+
+#[derive(Clone, Debug)]
+struct State<'a> {
+    cages: &'a [Cage],
+    square: LatinSquare,
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.square.propagate()?;
+            for cage in self.cages {
+                changed |= cage.apply(&mut self.square)?;
+            }
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(location) = self.square.branch_location() else {
+            return vec![];
+        };
+        self.square
+            .branch_on(location)
+            .into_iter()
+            .map(|square| Self {
+                square,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+fn initial_state(board: &Board) -> State<'_> {
+    State {
+        cages: board.cages(),
+        square: LatinSquare::new(board.size()),
+    }
+}
+
+/// Returns the solved grid, or `None` if the puzzle has no solution.
+pub fn solve(board: &Board) -> Option<LatinSquare> {
+    search::solve(initial_state(board)).map(|state| state.square)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    search::count_solutions(initial_state(board), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solves_data_puzzles() {
+        for input in [
+            include_str!("../../data/kenken/puzzles/six01.txt"),
+            include_str!("../../data/kenken/puzzles/three01.json"),
+        ] {
+            let board = Board::parse(input).unwrap();
+            let values = solve(&board).unwrap().values().unwrap();
+            assert!(crate::latin::is_latin(&values));
+            assert!(board.cages().iter().all(|cage| {
+                let cage_values = cage
+                    .cells
+                    .iter()
+                    .map(|&loc| values[loc])
+                    .collect::<Vec<_>>();
+                cage.operation.evaluates_to(&cage_values, cage.target)
+            }));
+            assert_eq!(count_solutions(&board, 2), 1);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{
+    location::Location,
+    search::{self, Contradiction, SearchState},
+    shading::{Shade, ShadeGrid},
+};
+
+use super::Board;
+
+#[derive(Clone, Debug)]
+struct State<'a> {
+    board: &'a Board,
+    grid: ShadeGrid,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board) -> Self {
+        let mut grid = ShadeGrid::new(board.dim());
+        for loc in Location::grid_iter(board.dim()) {
+            if board.number(loc).is_some() {
+                grid.set(loc, Shade::Unshaded)
+                    .expect("Only numbered cells have been set.");
+            }
+        }
+        Self { board, grid }
+    }
+
+    /// Shaded cells cannot be orthogonally adjacent.
+    fn apply_adjacency(&mut self) -> Result<bool, Contradiction> {
+        let dim = self.grid.dim();
+        let mut changed = false;
+        for loc in Location::grid_iter(dim) {
+            if self.grid.is_shaded(loc) {
+                for adj in loc.adjacents(dim).into_iter().flatten() {
+                    changed |= self.grid.set(adj, Shade::Unshaded)?;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Every number must see exactly that many cells.
+    fn apply_numbers(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for loc in Location::grid_iter(self.grid.dim()) {
+            if let Some(number) = self.board.number(loc) {
+                // The number counts its own cell.
+                changed |= self.grid.apply_sight(loc, number as usize - 1)?;
+            }
+        }
+        Ok(changed)
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.apply_adjacency()?;
+            changed |= self.apply_numbers()?;
+            changed |= self.grid.keep_connected(Shade::Unshaded)?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(location) = self.grid.first_unknown() else {
+            return vec![];
+        };
+        self.grid
+            .branch_on(location)
+            .into_iter()
+            .map(|grid| Self {
+                grid,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+/// Returns the solved grid with shaded cells as `#` and unshaded cells as `.`, or `None` if the puzzle has no solution.
+pub fn solve(board: &Board) -> Option<ShadeGrid> {
+    search::solve(State::new(board)).map(|state| state.grid)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    search::count_solutions(State::new(board), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{location::Direction, region};
+
+    fn follows_rules(board: &Board, grid: &ShadeGrid) -> bool {
+        let dim = board.dim();
+        let numbers = Location::grid_iter(dim).all(|loc| {
+            board.number(loc).is_none_or(|number| {
+                let seen = Direction::ALL
+                    .into_iter()
+                    .map(|direction| {
+                        loc.ray(direction, dim)
+                            .take_while(|&other| !grid.is_shaded(other))
+                            .count()
+                    })
+                    .sum::<usize>();
+                !grid.is_shaded(loc) && seen + 1 == number as usize
+            })
+        });
+        let apart = Location::grid_iter(dim)
+            .filter(|&loc| grid.is_shaded(loc))
+            .all(|loc| {
+                loc.adjacents(dim)
+                    .into_iter()
+                    .flatten()
+                    .all(|adj| !grid.is_shaded(adj))
+            });
+        let unshaded = |loc| !grid.is_shaded(loc);
+        numbers && apart && region::is_connected(dim, unshaded, unshaded)
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/kuromasu/puzzles/eight01.txt")).unwrap();
+        let grid = solve(&board).unwrap();
+        assert!(grid.is_complete());
+        assert!(follows_rules(&board, &grid));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
@@ -0,0 +1,192 @@
+//! Latin squares: square grids where every row and column contains each value from 1 to the size exactly once.
+//! This is the shared core of puzzles such as skyscrapers, futoshiki and kenken, which add their own constraints
+//! on top.
+
+use std::fmt::{self, Display, Formatter};
+
+use thiserror::Error;
+
+use crate::{grid::Grid, location::Location, search::Contradiction, value_set::ValueSet};
+
+/// Why a Latin square breaks its rules.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Error)]
+pub enum Error {
+    #[error("No values are left for the cell at {0}.")]
+    NoValuesLeft(Location),
+    /// The value has no cell left in line `line` of [`LatinSquare::lines`].
+    #[error("Value {value} has no cell left in line {line}.")]
+    NoCellLeft { value: u8, line: usize },
+}
+
+impl From<Error> for Contradiction {
+    fn from(_: Error) -> Self {
+        Contradiction
+    }
+}
+
+/// A partially filled Latin square, tracking the values still possible in each cell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LatinSquare {
+    cells: Grid<ValueSet>,
+}
+
+impl LatinSquare {
+    pub fn new(size: usize) -> Self {
+        assert!(
+            size > 0 && size <= ValueSet::MAX_VALUE as usize,
+            "Latin squares must have a size between 1 and {}.",
+            ValueSet::MAX_VALUE
+        );
+        Self {
+            cells: Grid::from_elem((size, size), ValueSet::all(size as u8)),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.cells.height()
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.cells.dim()
+    }
+
+    pub fn candidates(&self, location: Location) -> ValueSet {
+        self.cells[location]
+    }
+
+    pub fn value(&self, location: Location) -> Option<u8> {
+        self.candidates(location).single()
+    }
+
+    /// Removes every candidate of the cell at `location` not in `allowed`, returning whether anything changed.
+    pub fn restrict(&mut self, location: Location, allowed: ValueSet) -> Result<bool, Error> {
+        let cell = &mut self.cells[location];
+        let restricted = *cell & allowed;
+        if restricted.is_empty() {
+            Err(Error::NoValuesLeft(location))
+        } else if restricted == *cell {
+            Ok(false)
+        } else {
+            *cell = restricted;
+            Ok(true)
+        }
+    }
+
+    pub fn assign(&mut self, location: Location, value: u8) -> Result<bool, Error> {
+        self.restrict(location, ValueSet::from_value(value))
+    }
+
+    /// Every row followed by every column.
+    pub fn lines(&self) -> Vec<Vec<Location>> {
+        let size = self.size();
+        let rows = (0..size).map(|row| (0..size).map(|col| Location::new(row, col)).collect());
+        let cols = (0..size).map(|col| (0..size).map(|row| Location::new(row, col)).collect());
+        rows.chain(cols).collect()
+    }
+
+    /// Removes values already placed in a line from the other cells of the line,
+    /// and places values that only have one possible cell left in a line.
+    fn propagate_line(&mut self, line_id: usize, line: &[Location]) -> Result<bool, Error> {
+        let mut changed = false;
+        for &loc in line {
+            if let Some(value) = self.value(loc) {
+                for &other in line.iter().filter(|&&other| other != loc) {
+                    changed |= self.restrict(other, self.all() - ValueSet::from_value(value))?;
+                }
+            }
+        }
+        for value in self.all().iter() {
+            let mut cells = line
+                .iter()
+                .filter(|&&loc| self.candidates(loc).contains(value));
+            match (cells.next(), cells.next()) {
+                (None, _) => {
+                    return Err(Error::NoCellLeft {
+                        value,
+                        line: line_id,
+                    })
+                }
+                (Some(&loc), None) => changed |= self.assign(loc, value)?,
+                _ => {}
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Applies the Latin square rules to every line until nothing changes.
+    /// Returns whether anything changed at all.
+    pub fn propagate(&mut self) -> Result<bool, Error> {
+        let lines = self.lines();
+        let mut changed = false;
+        loop {
+            let mut changed_this_pass = false;
+            for (line_id, line) in lines.iter().enumerate() {
+                changed_this_pass |= self.propagate_line(line_id, line)?;
+            }
+            if !changed_this_pass {
+                return Ok(changed);
+            }
+            changed = true;
+        }
+    }
+
+    /// The undecided cell with the fewest candidates, if any.
+    pub fn branch_location(&self) -> Option<Location> {
+        Location::grid_iter(self.dim())
+            .filter(|&loc| self.candidates(loc).len() > 1)
+            .min_by_key(|&loc| self.candidates(loc).len())
+    }
+
+    /// Squares with each candidate of the cell at `location` placed in turn.
+    pub fn branch_on(&self, location: Location) -> Vec<Self> {
+        self.candidates(location)
+            .iter()
+            .map(|value| {
+                let mut square = self.clone();
+                square.cells[location] = ValueSet::from_value(value);
+                square
+            })
+            .collect()
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.cells.iter().all(|cell| cell.len() == 1)
+    }
+
+    /// The placed values, or `None` if some cell is still undecided.
+    pub fn values(&self) -> Option<Grid<u8>> {
+        self.is_solved()
+            .then(|| self.cells.map(|cell| cell.single().unwrap()))
+    }
+
+    fn all(&self) -> ValueSet {
+        ValueSet::all(self.size() as u8)
+    }
+}
+
+/// Whether every row and column holds each value from 1 to the size exactly once, for checking solutions in tests.
+#[cfg(test)]
+pub(crate) fn is_latin(values: &Grid<u8>) -> bool {
+    use itertools::Itertools;
+
+    let size = values.height() as u8;
+    (0..values.height()).all(|i| {
+        values.row(i).copied().sorted().eq(1..=size) && values.col(i).copied().sorted().eq(1..=size)
+    })
+}
+
+impl Display for LatinSquare {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for row in self.cells.rows() {
+            for cell in row {
+                let c = cell
+                    .single()
+                    .and_then(|value| char::from_digit(value.into(), 36))
+                    .map_or('.', |c| c.to_ascii_uppercase());
+                write!(f, "{c}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
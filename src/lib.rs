@@ -1,3 +1,74 @@
+pub mod akari;
+pub mod aquarium;
+pub mod battleship;
+pub mod binairo;
+pub mod cage;
 pub mod camping;
+pub mod cancel;
+pub mod cave;
+pub mod collection;
+pub mod crossword_fill;
+pub mod cryptarithm;
+pub mod difficulty;
+pub mod dominosa;
+pub mod engine;
+pub mod error;
+pub mod exact_cover;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fillapix;
+pub mod fillomino;
+pub mod futoshiki;
+pub mod galaxies;
+pub mod generate;
+pub mod grid;
+pub mod hashi;
+pub mod heyawake;
+pub mod hidato;
+pub mod history;
+pub mod hitori;
+pub mod id;
+pub mod inshi;
+pub mod kakurasu;
+pub mod kenken;
+pub mod kuromasu;
+pub mod latin;
+pub mod lits;
 pub mod location;
+pub mod loop_grid;
+pub mod magnets;
+pub mod masyu;
+pub mod minesweeper;
+pub mod nonogram;
+pub mod norinori;
+pub mod numberlink;
+pub mod nurikabe;
+pub mod pattern_database;
+pub mod polyomino;
+pub mod puzzle;
+pub mod puzzlink;
+pub mod queens;
+pub mod region;
+pub mod report;
+pub mod rippleeffect;
+pub mod search;
+pub mod shading;
+pub mod shakashaka;
+pub mod shikaku;
+pub mod skyscrapers;
+pub mod sliding;
+pub mod sokoban;
+pub mod solutions;
+pub mod starbattle;
+pub mod stats;
 pub mod sudoku;
+pub mod suguru;
+pub mod tapa;
+pub mod tracks;
+pub mod value_set;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod wordsearch;
+pub mod yajilin;
+
+pub use error::{Error, Result};
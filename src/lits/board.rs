@@ -0,0 +1,128 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{ensure, read_to_string, Result},
+    grid::Grid,
+    location::Location,
+    puzzlink::{self, PuzzLink},
+    region,
+};
+
+/// A LITS puzzle: a grid divided into regions. Each region must contain exactly one tetromino of shaded cells,
+/// tetrominoes of the same shape may not touch across a region border, no 2x2 block may be entirely shaded,
+/// and all shaded cells must be connected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    regions: Grid<usize>,
+    region_cells: Vec<Vec<Location>>,
+}
+
+impl Board {
+    pub fn new(regions: Grid<usize>, num_regions: usize) -> Result<Self> {
+        let dim = regions.dim();
+        ensure!(
+            dim.0 > 0 && dim.1 > 0,
+            Invalid,
+            "LITS grids must have at least one cell."
+        );
+        let mut region_cells = vec![vec![]; num_regions];
+        for loc in Location::grid_iter(dim) {
+            let region = regions[loc];
+            ensure!(
+                region < num_regions,
+                Invalid,
+                "Cell {loc} is in region {region}, but there are only {num_regions} regions."
+            );
+            region_cells[region].push(loc);
+        }
+        for (region, cells) in region_cells.iter().enumerate() {
+            ensure!(
+                cells.len() >= 4,
+                Invalid,
+                "Region {region} has {} cells, which is too few for a tetromino.",
+                cells.len()
+            );
+            ensure!(
+                region::is_connected(dim, |loc| cells.contains(&loc), |loc| cells.contains(&loc)),
+                Invalid,
+                "Region {region} is not connected."
+            );
+        }
+        Ok(Self {
+            regions,
+            region_cells,
+        })
+    }
+
+    /// Parses a grid of characters where each distinct character is a region.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let (regions, num_regions) = region::parse_regions(&rows)?;
+        Self::new(regions, num_regions)
+    }
+
+    /// Imports a puzzle from a puzz.link or pzv.jp URL.
+    pub fn from_puzz_link(url: &str) -> Result<Self> {
+        let link = PuzzLink::parse(url)?;
+        link.expect_kind(&["lits"])?;
+        let (regions, num_regions, _) =
+            puzzlink::decode_rooms(&link.body, link.width, link.height)?;
+        Self::new(regions, num_regions)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("LITS", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.regions.dim()
+    }
+
+    pub fn region(&self, location: Location) -> usize {
+        self.regions[location]
+    }
+
+    /// The cells of each region.
+    pub fn regions(&self) -> &[Vec<Location>] {
+        &self.region_cells
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const NAMES: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+        for row in self.regions.rows() {
+            let row = row
+                .map(|&region| NAMES[region % NAMES.len()] as char)
+                .collect::<String>();
+            writeln!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_text_and_puzz_links() {
+        let board = Board::parse(include_str!("../../data/lits/puzzles/six01.txt")).unwrap();
+        assert_eq!(board.dim(), (6, 6));
+        assert_eq!(board.regions().len(), 6);
+        assert_eq!(board.region(Location::new(1, 2)), 2);
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        let link = "https://puzz.link/p?lits/6/6/4ckde04dqo5rl";
+        assert_eq!(Board::from_puzz_link(link).unwrap(), board);
+        assert!(Board::from_puzz_link("https://puzz.link/p?heyawake/6/6/4ckde04dqo5rl").is_err());
+        assert!(Board::parse("AAB\nABB\n").is_err());
+        assert!(Board::parse("AAAB\nBBBA\n").is_err());
+    }
+}
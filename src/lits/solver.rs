@@ -0,0 +1,294 @@
+use std::fmt::Display;
+
+use crate::{
+    grid::Grid,
+    location::Location,
+    polyomino::{self, Tetromino},
+    search::{self, Contradiction, SearchState},
+    shading::{Shade, ShadeGrid},
+};
+
+use super::Board;
+
+/// A tetromino that fits inside a region.
+#[derive(Clone, Debug)]
+struct Placement {
+    shape: Tetromino,
+    cells: Vec<Location>,
+}
+
+impl Placement {
+    fn touches(&self, other: &Placement, dim: (usize, usize)) -> bool {
+        self.cells.iter().any(|loc| {
+            loc.adjacents(dim)
+                .into_iter()
+                .flatten()
+                .any(|adj| other.cells.contains(&adj))
+        })
+    }
+}
+
+/// The possible placements of every region and which regions border each other, shared between all search states.
+struct Layout {
+    placements: Vec<Vec<Placement>>,
+    neighbors: Vec<Vec<usize>>,
+}
+
+impl Layout {
+    fn new(board: &Board) -> Self {
+        let dim = board.dim();
+        let placements = board
+            .regions()
+            .iter()
+            .map(|cells| {
+                polyomino::placements(dim, cells, 4)
+                    .into_iter()
+                    .filter_map(|cells| {
+                        let shape = Tetromino::classify(&cells)?;
+                        // A square is a shaded 2x2 block.
+                        (shape != Tetromino::O).then_some(Placement { shape, cells })
+                    })
+                    .collect()
+            })
+            .collect();
+        let neighbors = board
+            .regions()
+            .iter()
+            .enumerate()
+            .map(|(region, cells)| {
+                let mut neighbors = cells
+                    .iter()
+                    .flat_map(|loc| loc.adjacents(dim).into_iter().flatten())
+                    .map(|adj| board.region(adj))
+                    .filter(|&other| other != region)
+                    .collect::<Vec<_>>();
+                neighbors.sort_unstable();
+                neighbors.dedup();
+                neighbors
+            })
+            .collect();
+        Self {
+            placements,
+            neighbors,
+        }
+    }
+}
+
+/// The tetromino placed in every region of a solved puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    shapes: Grid<Option<Tetromino>>,
+}
+
+impl Solution {
+    /// The shape of the tetromino covering `location`, if it is shaded.
+    pub fn shape(&self, location: Location) -> Option<Tetromino> {
+        self.shapes[location]
+    }
+}
+
+/// Shaded cells are shown by the letter of their tetromino and unshaded cells by `.`.
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.shapes.rows() {
+            let row = row
+                .map(|shape| shape.map_or('.', Tetromino::to_char))
+                .collect::<String>();
+            writeln!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The tetrominoes are shaded.
+#[derive(Clone)]
+struct State<'a> {
+    board: &'a Board,
+    layout: &'a Layout,
+    grid: ShadeGrid,
+    /// The placements still possible in each region.
+    candidates: Vec<Vec<usize>>,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board, layout: &'a Layout) -> Self {
+        Self {
+            board,
+            layout,
+            grid: ShadeGrid::new(board.dim()),
+            candidates: layout
+                .placements
+                .iter()
+                .map(|placements| (0..placements.len()).collect())
+                .collect(),
+        }
+    }
+
+    /// Removes placements that disagree with the grid, then shades the cells every remaining placement covers
+    /// and unshades the cells none of them do.
+    fn apply_placements(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for (region, cells) in self.board.regions().iter().enumerate() {
+            let placements = &self.layout.placements[region];
+            let grid = &self.grid;
+            let before = self.candidates[region].len();
+            self.candidates[region].retain(|&index| {
+                let placement = &placements[index].cells;
+                placement
+                    .iter()
+                    .all(|&loc| grid.get(loc) != Shade::Unshaded)
+                    && cells
+                        .iter()
+                        .all(|loc| !grid.is_shaded(*loc) || placement.contains(loc))
+            });
+            changed |= self.candidates[region].len() != before;
+            let candidates = &self.candidates[region];
+            if candidates.is_empty() {
+                return Err(Contradiction);
+            }
+            for &loc in cells {
+                let covered = candidates
+                    .iter()
+                    .filter(|&&index| placements[index].cells.contains(&loc))
+                    .count();
+                if covered == candidates.len() {
+                    changed |= self.grid.set(loc, Shade::Shaded)?;
+                } else if covered == 0 {
+                    changed |= self.grid.set(loc, Shade::Unshaded)?;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Removes placements that would touch a tetromino of the same shape in a neighboring region
+    /// whatever that region ends up with.
+    fn apply_shapes(&mut self) -> Result<bool, Contradiction> {
+        let dim = self.board.dim();
+        let mut changed = false;
+        for region in 0..self.candidates.len() {
+            for &other in self.layout.neighbors[region].iter() {
+                let placements = &self.layout.placements[region];
+                let others = &self.layout.placements[other];
+                let other_candidates = self.candidates[other].clone();
+                let before = self.candidates[region].len();
+                self.candidates[region].retain(|&index| {
+                    let placement = &placements[index];
+                    other_candidates.iter().any(|&other_index| {
+                        let other = &others[other_index];
+                        other.shape != placement.shape || !other.touches(placement, dim)
+                    })
+                });
+                if self.candidates[region].is_empty() {
+                    return Err(Contradiction);
+                }
+                changed |= self.candidates[region].len() != before;
+            }
+        }
+        Ok(changed)
+    }
+
+    fn into_solution(self) -> Solution {
+        let mut shapes = Grid::from_elem(self.board.dim(), None);
+        for (region, candidates) in self.candidates.iter().enumerate() {
+            let placement = &self.layout.placements[region][candidates[0]];
+            for loc in placement.cells.iter() {
+                shapes[*loc] = Some(placement.shape);
+            }
+        }
+        Solution { shapes }
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.apply_placements()?;
+            changed |= self.apply_shapes()?;
+            changed |= self.grid.apply_no_pools(Shade::Shaded)?;
+            changed |= self.grid.keep_connected(Shade::Shaded)?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(region) = (0..self.candidates.len())
+            .filter(|&region| self.candidates[region].len() > 1)
+            .min_by_key(|&region| self.candidates[region].len())
+        else {
+            return vec![];
+        };
+        self.candidates[region]
+            .iter()
+            .map(|&index| {
+                let mut state = self.clone();
+                state.candidates[region] = vec![index];
+                state
+            })
+            .collect()
+    }
+}
+
+pub fn solve(board: &Board) -> Option<Solution> {
+    let layout = Layout::new(board);
+    search::solve(State::new(board, &layout)).map(State::into_solution)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    let layout = Layout::new(board);
+    search::count_solutions(State::new(board, &layout), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::region;
+
+    fn follows_rules(board: &Board, solution: &Solution) -> bool {
+        let (height, width) = board.dim();
+        let dim = (height, width);
+        let shaded = |loc| solution.shape(loc).is_some();
+        let tetrominoes = board.regions().iter().all(|cells| {
+            let shaded = cells
+                .iter()
+                .copied()
+                .filter(|&loc| shaded(loc))
+                .collect::<Vec<_>>();
+            shaded.len() == 4
+                && region::is_connected(
+                    dim,
+                    |loc| shaded.contains(&loc),
+                    |loc| shaded.contains(&loc),
+                )
+                && shaded
+                    .iter()
+                    .all(|&loc| solution.shape(loc) == Tetromino::classify(&shaded))
+        });
+        let same_shapes_apart = Location::grid_iter(dim)
+            .filter(|&loc| shaded(loc))
+            .all(|loc| {
+                loc.adjacents(dim).into_iter().flatten().all(|adj| {
+                    board.region(adj) == board.region(loc)
+                        || solution.shape(adj) != solution.shape(loc)
+                })
+            });
+        let no_pools = (1..height).all(|row| {
+            (1..width).all(|col| {
+                !(row - 1..=row)
+                    .flat_map(|row| [col - 1, col].map(|col| Location::new(row, col)))
+                    .all(shaded)
+            })
+        });
+        tetrominoes && same_shapes_apart && no_pools && region::is_connected(dim, shaded, shaded)
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/lits/puzzles/six01.txt")).unwrap();
+        let solution = solve(&board).unwrap();
+        assert!(follows_rules(&board, &solution));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
@@ -49,6 +49,60 @@ impl Location {
     pub const fn grid_iter(map_dim: (usize, usize)) -> GridIter {
         GridIter::new(map_dim)
     }
+
+    /// The adjacent location in `direction`, if it is inside the map.
+    pub fn step(self, direction: Direction, map_dim: (usize, usize)) -> Option<Location> {
+        let Self { row, col } = self;
+        let (max_row, max_col) = map_dim;
+        match direction {
+            Direction::Up => (row > 0).then(|| Location::new(row - 1, col)),
+            Direction::Right => (col < max_col - 1).then(|| Location::new(row, col + 1)),
+            Direction::Down => (row < max_row - 1).then(|| Location::new(row + 1, col)),
+            Direction::Left => (col > 0).then(|| Location::new(row, col - 1)),
+        }
+    }
+
+    /// The locations from this one (exclusive) to the edge of the map in `direction`, nearest first.
+    pub fn ray(
+        self,
+        direction: Direction,
+        map_dim: (usize, usize),
+    ) -> impl Iterator<Item = Location> {
+        std::iter::successors(self.step(direction, map_dim), move |&loc| {
+            loc.step(direction, map_dim)
+        })
+    }
+}
+
+/// One of the four orthogonal directions, in the same order as [`Location::adjacents`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub const fn opposite(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Right => Direction::Left,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+        }
+    }
+
+    pub const fn is_horizontal(self) -> bool {
+        matches!(self, Direction::Right | Direction::Left)
+    }
 }
 
 impl From<(usize, usize)> for Location {
@@ -0,0 +1,282 @@
+//! A single closed loop along the edges between orthogonally adjacent nodes of a grid. The nodes are the cell
+//! centres in puzzles such as masyu and yajilin, and the corners of the cells in puzzles such as slitherlink.
+
+use crate::{
+    grid::Grid,
+    location::{Direction, Location},
+    search::Contradiction,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoopGrid {
+    dim: (usize, usize),
+    /// Whether the loop uses the edge from each node to the node to its right.
+    horizontal: Grid<Option<bool>>,
+    /// Whether the loop uses the edge from each node to the node below it.
+    vertical: Grid<Option<bool>>,
+}
+
+impl LoopGrid {
+    pub fn new(dim: (usize, usize)) -> Self {
+        let (height, width) = dim;
+        Self {
+            dim,
+            horizontal: Grid::from_elem((height, width.saturating_sub(1)), None),
+            vertical: Grid::from_elem((height.saturating_sub(1), width), None),
+        }
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.dim
+    }
+
+    fn edge_mut(&mut self, location: Location, direction: Direction) -> Option<&mut Option<bool>> {
+        let other = location.step(direction, self.dim)?;
+        let edge = Location::new(location.row.min(other.row), location.col.min(other.col));
+        Some(if direction.is_horizontal() {
+            &mut self.horizontal[edge]
+        } else {
+            &mut self.vertical[edge]
+        })
+    }
+
+    /// Whether the loop uses the edge from `location` in `direction`, if that is decided.
+    /// Edges leaving the grid are never used.
+    pub fn line(&self, location: Location, direction: Direction) -> Option<bool> {
+        let Some(other) = location.step(direction, self.dim) else {
+            return Some(false);
+        };
+        let edge = Location::new(location.row.min(other.row), location.col.min(other.col));
+        if direction.is_horizontal() {
+            self.horizontal[edge]
+        } else {
+            self.vertical[edge]
+        }
+    }
+
+    /// Decides whether the loop uses the edge from `location` in `direction`, returning whether anything changed.
+    /// Fails if the edge is already decided the other way.
+    pub fn set_line(
+        &mut self,
+        location: Location,
+        direction: Direction,
+        line: bool,
+    ) -> Result<bool, Contradiction> {
+        let Some(edge) = self.edge_mut(location, direction) else {
+            return if line { Err(Contradiction) } else { Ok(false) };
+        };
+        match *edge {
+            Some(current) if current == line => Ok(false),
+            Some(_) => Err(Contradiction),
+            None => {
+                *edge = Some(line);
+                Ok(true)
+            }
+        }
+    }
+
+    /// The number of edges from `location` the loop is known to use.
+    pub fn lines(&self, location: Location) -> usize {
+        Direction::ALL
+            .into_iter()
+            .filter(|&direction| self.line(location, direction) == Some(true))
+            .count()
+    }
+
+    /// The directions from `location` whose edges are not decided yet.
+    pub fn undecided(&self, location: Location) -> impl Iterator<Item = Direction> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter(move |&direction| self.line(location, direction).is_none())
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.horizontal
+            .iter()
+            .chain(self.vertical.iter())
+            .all(Option::is_some)
+    }
+
+    /// Makes every node have either no lines or two, where nodes for which `on_loop` holds must have two.
+    pub fn apply_degrees(
+        &mut self,
+        on_loop: impl Fn(Location) -> bool,
+    ) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for loc in Location::grid_iter(self.dim) {
+            let lines = self.lines(loc);
+            let undecided = self.undecided(loc).collect::<Vec<_>>();
+            let fill = match (lines, undecided.len()) {
+                (3.., _) => return Err(Contradiction),
+                (2, _) => false,
+                (1, 0) => return Err(Contradiction),
+                (1, 1) => true,
+                (0, count) if on_loop(loc) && count < 2 => return Err(Contradiction),
+                (0, 2) if on_loop(loc) => true,
+                // A single undecided edge would be a dead end.
+                (0, 1) => false,
+                _ => continue,
+            };
+            for direction in undecided {
+                changed |= self.set_line(loc, direction, fill)?;
+            }
+        }
+        Ok(changed)
+    }
+
+    /// The index of the chain of lines each node is part of, or `None` for nodes with no lines.
+    fn chains(&self) -> (Grid<Option<usize>>, usize) {
+        let mut labels = Grid::from_elem(self.dim, None);
+        let mut count = 0;
+        for start in Location::grid_iter(self.dim) {
+            if labels[start].is_some() || self.lines(start) == 0 {
+                continue;
+            }
+            labels[start] = Some(count);
+            let mut stack = vec![start];
+            while let Some(loc) = stack.pop() {
+                for direction in Direction::ALL {
+                    if self.line(loc, direction) != Some(true) {
+                        continue;
+                    }
+                    let next = loc
+                        .step(direction, self.dim)
+                        .expect("Lines stay in the grid.");
+                    if labels[next].is_none() {
+                        labels[next] = Some(count);
+                        stack.push(next);
+                    }
+                }
+            }
+            count += 1;
+        }
+        (labels, count)
+    }
+
+    /// Keeps the lines from closing into more than one loop. A closed loop must be the only chain of lines and pass
+    /// through every node for which `on_loop` holds, in which case every other edge is left out.
+    /// An edge that would close a chain into a loop too early is left out.
+    pub fn apply_single_loop(
+        &mut self,
+        on_loop: impl Fn(Location) -> bool,
+    ) -> Result<bool, Contradiction> {
+        let dim = self.dim;
+        let (labels, count) = self.chains();
+        let label = |loc: Location| labels[loc];
+        // Whether closing the lines into a loop would give the whole solution.
+        let completes =
+            count == 1 && Location::grid_iter(dim).all(|loc| !on_loop(loc) || label(loc).is_some());
+        let closed = (0..count).any(|chain| {
+            Location::grid_iter(dim).all(|loc| label(loc) != Some(chain) || self.lines(loc) == 2)
+        });
+        let mut changed = false;
+        if closed {
+            if !completes {
+                return Err(Contradiction);
+            }
+            for loc in Location::grid_iter(dim) {
+                for direction in self.undecided(loc).collect::<Vec<_>>() {
+                    changed |= self.set_line(loc, direction, false)?;
+                }
+            }
+            return Ok(changed);
+        }
+        if completes {
+            return Ok(false);
+        }
+        for loc in Location::grid_iter(dim) {
+            let Some(chain) = label(loc) else {
+                continue;
+            };
+            for direction in [Direction::Right, Direction::Down] {
+                let Some(other) = loc.step(direction, dim) else {
+                    continue;
+                };
+                if self.line(loc, direction).is_none() && label(other) == Some(chain) {
+                    changed |= self.set_line(loc, direction, false)?;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// An undecided edge to branch on, preferring edges at the ends of chains.
+    pub fn branch_edge(&self) -> Option<(Location, Direction)> {
+        let mut undecided = Location::grid_iter(self.dim)
+            .flat_map(|loc| self.undecided(loc).map(move |direction| (loc, direction)));
+        Location::grid_iter(self.dim)
+            .filter(|&loc| self.lines(loc) == 1)
+            .find_map(|loc| self.undecided(loc).next().map(|direction| (loc, direction)))
+            .or_else(|| undecided.next())
+    }
+
+    /// The two grids obtained by using and leaving out the edge from `location` in `direction`.
+    pub fn branch_on(&self, location: Location, direction: Direction) -> [Self; 2] {
+        [true, false].map(|line| {
+            let mut grid = self.clone();
+            *grid
+                .edge_mut(location, direction)
+                .expect("Branch edges stay in the grid.") = Some(line);
+            grid
+        })
+    }
+
+    /// The nodes of the loop in order if the lines form a single closed loop, for checking solutions in tests.
+    #[cfg(test)]
+    pub(crate) fn loop_nodes(&self) -> Option<Vec<Location>> {
+        let nodes = Location::grid_iter(self.dim)
+            .filter(|&loc| self.lines(loc) > 0)
+            .collect::<Vec<_>>();
+        if !self.is_complete() || nodes.iter().any(|&loc| self.lines(loc) != 2) {
+            return None;
+        }
+        let start = *nodes.first()?;
+        let mut path = vec![start];
+        let mut previous = None;
+        let mut current = start;
+        loop {
+            let direction = Direction::ALL.into_iter().find(|&direction| {
+                self.line(current, direction) == Some(true)
+                    && Some(direction.opposite()) != previous
+            })?;
+            current = current.step(direction, self.dim)?;
+            previous = Some(direction);
+            if current == start {
+                break;
+            }
+            path.push(current);
+        }
+        (path.len() == nodes.len()).then_some(path)
+    }
+
+    /// Draws the nodes with `node` and the lines between them with `-` and `|`.
+    pub fn render(&self, node: impl Fn(Location) -> char) -> String {
+        let (height, width) = self.dim;
+        let mut string = String::new();
+        for row in 0..height {
+            for col in 0..width {
+                let loc = Location::new(row, col);
+                string.push(node(loc));
+                if col + 1 < width {
+                    let line = self.line(loc, Direction::Right) == Some(true);
+                    string.push(if line { '-' } else { ' ' });
+                }
+            }
+            string.push('\n');
+            if row + 1 < height {
+                let lines = (0..width)
+                    .map(
+                        |col| match self.line(Location::new(row, col), Direction::Down) {
+                            Some(true) => "|",
+                            _ => " ",
+                        },
+                    )
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                string.push_str(lines.trim_end());
+                string.push('\n');
+            }
+        }
+        string
+    }
+}
@@ -0,0 +1,223 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::{Direction, Location},
+};
+
+/// A magnets puzzle: a grid tiled with dominoes, each of which is either a magnet with a positive and a negative
+/// pole or neutral. Poles of the same kind may not be orthogonally adjacent, and the numbers outside the grid say
+/// how many positive and negative poles each row and column has.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    /// The other half of the domino each cell is part of.
+    partners: Grid<Location>,
+    row_plus: Vec<Option<usize>>,
+    row_minus: Vec<Option<usize>>,
+    col_plus: Vec<Option<usize>>,
+    col_minus: Vec<Option<usize>>,
+}
+
+impl Board {
+    pub fn new(
+        partners: Grid<Location>,
+        [row_plus, row_minus, col_plus, col_minus]: [Vec<Option<usize>>; 4],
+    ) -> Result<Self> {
+        let (height, width) = partners.dim();
+        ensure!(
+            height > 0 && width > 0,
+            Invalid,
+            "Magnets grids must have at least one cell."
+        );
+        ensure!(
+            row_plus.len() == height && row_minus.len() == height,
+            Invalid,
+            "Expected {height} row counts of each pole."
+        );
+        ensure!(
+            col_plus.len() == width && col_minus.len() == width,
+            Invalid,
+            "Expected {width} column counts of each pole."
+        );
+        for loc in Location::grid_iter((height, width)) {
+            let partner = partners[loc];
+            ensure!(
+                Direction::ALL
+                    .into_iter()
+                    .any(|direction| loc.step(direction, (height, width)) == Some(partner))
+                    && partners[partner] == loc,
+                Invalid,
+                "The domino at {loc} is not two adjacent cells."
+            );
+        }
+        Ok(Self {
+            partners,
+            row_plus,
+            row_minus,
+            col_plus,
+            col_minus,
+        })
+    }
+
+    /// Parses a puzzle given as four lines of counts separated by commas, with `?` for a line without a count:
+    /// the positive and then negative poles of each row, followed by the positive and then negative poles of each
+    /// column. Then comes the grid of dominoes, where `L` and `R` are the left and right halves of a horizontal
+    /// domino and `T` and `B` are the top and bottom halves of a vertical domino.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let mut lines = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty());
+        let mut counts = |name: &str| -> Result<Vec<Option<usize>>> {
+            let line = lines
+                .next()
+                .ok_or_else(|| Error::Parse(format!("No line of {name}.")))?;
+            line.split(',')
+                .map(|count| match count.trim() {
+                    "?" => Ok(None),
+                    count => count.parse::<usize>().map(Some),
+                })
+                .collect::<Result<_, _>>()
+                .map_err(|_| {
+                    Error::Parse(format!(
+                        "Expected {name} separated by commas. Got '{line}'."
+                    ))
+                })
+        };
+        let counts = [
+            counts("positive row counts")?,
+            counts("negative row counts")?,
+            counts("positive column counts")?,
+            counts("negative column counts")?,
+        ];
+        let rows = lines.collect::<Vec<_>>();
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        let mut partners = Grid::from_elem((height, width), Location::new(0, 0));
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.chars().count() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} characters in row {row_index}. Got '{row}'."
+                );
+            }
+            for (col_index, c) in row.chars().enumerate() {
+                let direction = match c {
+                    'L' => Direction::Right,
+                    'R' => Direction::Left,
+                    'T' => Direction::Down,
+                    'B' => Direction::Up,
+                    _ => bail!(
+                        Parse,
+                        "Invalid character '{c}' at ({row_index}, {col_index})."
+                    ),
+                };
+                let loc = Location::new(row_index, col_index);
+                // Halves pointing out of the grid are caught when checking the dominoes.
+                partners[Location::new(row_index, col_index)] =
+                    loc.step(direction, (height, width)).unwrap_or(loc);
+            }
+        }
+        Self::new(partners, counts)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("magnets", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.partners.dim()
+    }
+
+    /// The other half of the domino at `location`.
+    pub fn partner(&self, location: Location) -> Location {
+        self.partners[location]
+    }
+
+    /// The two cells of every domino, the top or left one first.
+    pub fn dominoes(&self) -> Vec<[Location; 2]> {
+        Location::grid_iter(self.dim())
+            .filter_map(|loc| {
+                let partner = self.partner(loc);
+                (loc.row < partner.row || loc.col < partner.col).then_some([loc, partner])
+            })
+            .collect()
+    }
+
+    pub fn row_plus(&self) -> &[Option<usize>] {
+        &self.row_plus
+    }
+
+    pub fn row_minus(&self) -> &[Option<usize>] {
+        &self.row_minus
+    }
+
+    pub fn col_plus(&self) -> &[Option<usize>] {
+        &self.col_plus
+    }
+
+    pub fn col_minus(&self) -> &[Option<usize>] {
+        &self.col_minus
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let counts = [
+            &self.row_plus,
+            &self.row_minus,
+            &self.col_plus,
+            &self.col_minus,
+        ];
+        for counts in counts {
+            let counts = counts
+                .iter()
+                .map(|count| count.map_or("?".to_string(), |count| count.to_string()))
+                .collect::<Vec<_>>();
+            writeln!(f, "{}", counts.join(","))?;
+        }
+        for row in 0..self.dim().0 {
+            for col in 0..self.dim().1 {
+                let loc = Location::new(row, col);
+                let partner = self.partner(loc);
+                let c = if partner.col > col {
+                    'L'
+                } else if partner.col < col {
+                    'R'
+                } else if partner.row > row {
+                    'T'
+                } else {
+                    'B'
+                };
+                write!(f, "{c}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_counts_and_dominoes() {
+        let board = Board::parse(include_str!("../../data/magnets/puzzles/six01.txt")).unwrap();
+        assert_eq!(board.dim(), (6, 6));
+        assert_eq!(board.row_plus()[..2], [Some(3), None]);
+        assert_eq!(board.col_minus()[3], Some(2));
+        assert_eq!(board.partner(Location::new(0, 0)), Location::new(1, 0));
+        assert_eq!(board.partner(Location::new(0, 3)), Location::new(0, 2));
+        assert_eq!(board.dominoes().len(), 18);
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        assert!(Board::parse("?\n?\n?,?\n?,?\nLL\n").is_err());
+        assert!(Board::parse("?\n?\n?,?\n?,?\nRL\n").is_err());
+        assert!(Board::parse("?\n?\n?,?\n?,?\nLX\n").is_err());
+        assert!(Board::parse("?\n?\n?\n?,?\nLR\n").is_err());
+    }
+}
@@ -0,0 +1,258 @@
+use std::fmt::Display;
+
+use crate::{
+    grid::Grid,
+    location::Location,
+    search::{self, Contradiction, SearchState},
+    shading::{self, Shade, ShadeGrid},
+};
+
+use super::Board;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pole {
+    Plus,
+    Minus,
+}
+
+impl Pole {
+    pub fn to_char(self) -> char {
+        match self {
+            Pole::Plus => '+',
+            Pole::Minus => '-',
+        }
+    }
+}
+
+/// The poles of a solved puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    poles: Grid<Option<Pole>>,
+}
+
+impl Solution {
+    /// The pole at `location`, or `None` if it is part of a neutral domino.
+    pub fn pole(&self, location: Location) -> Option<Pole> {
+        self.poles[location]
+    }
+}
+
+/// Poles are shown as `+` and `-` and neutral cells as `.`.
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.poles.rows() {
+            let row = row
+                .map(|pole| pole.map_or('.', Pole::to_char))
+                .collect::<String>();
+            writeln!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Every row and column with a count of positive poles, and every one with a count of negative poles.
+#[derive(Debug)]
+struct Lines {
+    plus: Vec<(Vec<Location>, usize)>,
+    minus: Vec<(Vec<Location>, usize)>,
+}
+
+impl Lines {
+    fn new(board: &Board) -> Self {
+        Self {
+            plus: shading::counted_lines(board.row_plus(), board.col_plus()),
+            minus: shading::counted_lines(board.row_minus(), board.col_minus()),
+        }
+    }
+}
+
+/// Makes the cell at `location` and its `partner` agree between the grids of the two kinds of pole.
+fn link(
+    grid: &ShadeGrid,
+    other: &mut ShadeGrid,
+    location: Location,
+    partner: Location,
+) -> Result<bool, Contradiction> {
+    let mut changed = false;
+    if grid.is_shaded(location) {
+        changed |= other.set(location, Shade::Unshaded)?;
+    }
+    if grid.get(location) != Shade::Unknown {
+        changed |= other.set(partner, grid.get(location))?;
+    }
+    Ok(changed)
+}
+
+/// The positive poles are shaded in `plus` and the negative poles in `minus`.
+#[derive(Clone, Debug)]
+struct State<'a> {
+    board: &'a Board,
+    lines: &'a Lines,
+    plus: ShadeGrid,
+    minus: ShadeGrid,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board, lines: &'a Lines) -> Self {
+        Self {
+            board,
+            lines,
+            plus: ShadeGrid::new(board.dim()),
+            minus: ShadeGrid::new(board.dim()),
+        }
+    }
+
+    /// A cell has at most one pole, and a domino is either neutral or has one pole of each kind,
+    /// so a cell is positive exactly when its partner is negative.
+    fn apply_dominoes(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for loc in Location::grid_iter(self.board.dim()) {
+            let partner = self.board.partner(loc);
+            changed |= link(&self.plus, &mut self.minus, loc, partner)?;
+            changed |= link(&self.minus, &mut self.plus, loc, partner)?;
+        }
+        Ok(changed)
+    }
+
+    /// Poles of the same kind cannot be orthogonally adjacent.
+    fn apply_repulsion(&mut self) -> Result<bool, Contradiction> {
+        let dim = self.board.dim();
+        let mut changed = false;
+        for grid in [&mut self.plus, &mut self.minus] {
+            for loc in Location::grid_iter(dim) {
+                if grid.is_shaded(loc) {
+                    for adj in loc.adjacents(dim).into_iter().flatten() {
+                        changed |= grid.set(adj, Shade::Unshaded)?;
+                    }
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    fn into_solution(self) -> Solution {
+        let poles = Grid::from_fn(self.board.dim(), |loc| {
+            if self.plus.is_shaded(loc) {
+                Some(Pole::Plus)
+            } else if self.minus.is_shaded(loc) {
+                Some(Pole::Minus)
+            } else {
+                None
+            }
+        });
+        Solution { poles }
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.apply_dominoes()?;
+            changed |= self.apply_repulsion()?;
+            for (line, count) in self.lines.plus.iter() {
+                changed |= self.plus.apply_count(line, *count)?;
+            }
+            for (line, count) in self.lines.minus.iter() {
+                changed |= self.minus.apply_count(line, *count)?;
+            }
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some([first, _]) = self.board.dominoes().into_iter().find(|&[first, _]| {
+            self.plus.get(first) == Shade::Unknown || self.minus.get(first) == Shade::Unknown
+        }) else {
+            return vec![];
+        };
+        // The first cell of the domino is positive, negative or neutral.
+        [(true, false), (false, true), (false, false)]
+            .into_iter()
+            .filter_map(|(plus, minus)| {
+                let mut state = self.clone();
+                let shade = |shaded| {
+                    if shaded {
+                        Shade::Shaded
+                    } else {
+                        Shade::Unshaded
+                    }
+                };
+                state.plus.set(first, shade(plus)).ok()?;
+                state.minus.set(first, shade(minus)).ok()?;
+                Some(state)
+            })
+            .collect()
+    }
+}
+
+pub fn solve(board: &Board) -> Option<Solution> {
+    let lines = Lines::new(board);
+    search::solve(State::new(board, &lines)).map(State::into_solution)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    let lines = Lines::new(board);
+    search::count_solutions(State::new(board, &lines), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follows_rules(board: &Board, solution: &Solution) -> bool {
+        let (height, width) = board.dim();
+        let dim = (height, width);
+        let dominoes =
+            board
+                .dominoes()
+                .into_iter()
+                .all(|[a, b]| match (solution.pole(a), solution.pole(b)) {
+                    (None, None) => true,
+                    (Some(a), Some(b)) => a != b,
+                    _ => false,
+                });
+        let apart = Location::grid_iter(dim).all(|loc| {
+            solution.pole(loc).is_none_or(|pole| {
+                loc.adjacents(dim)
+                    .into_iter()
+                    .flatten()
+                    .all(|adj| solution.pole(adj) != Some(pole))
+            })
+        });
+        let counts = [
+            (Pole::Plus, board.row_plus(), board.col_plus()),
+            (Pole::Minus, board.row_minus(), board.col_minus()),
+        ]
+        .into_iter()
+        .all(|(pole, rows, cols)| {
+            let rows_match = (0..height).all(|row| {
+                rows[row].is_none_or(|count| {
+                    (0..width)
+                        .filter(|&col| solution.pole(Location::new(row, col)) == Some(pole))
+                        .count()
+                        == count
+                })
+            });
+            let cols_match = (0..width).all(|col| {
+                cols[col].is_none_or(|count| {
+                    (0..height)
+                        .filter(|&row| solution.pole(Location::new(row, col)) == Some(pole))
+                        .count()
+                        == count
+                })
+            });
+            rows_match && cols_match
+        });
+        dominoes && apart && counts
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/magnets/puzzles/six01.txt")).unwrap();
+        let solution = solve(&board).unwrap();
+        assert!(follows_rules(&board, &solution));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
@@ -0,0 +1,148 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Result},
+    grid::Grid,
+    location::Location,
+    puzzlink::{self, PuzzLink},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pearl {
+    /// The loop goes straight through and turns in the cell before or after.
+    White,
+    /// The loop turns and goes straight through the cells before and after.
+    Black,
+}
+
+impl Pearl {
+    pub fn to_char(self) -> char {
+        match self {
+            Pearl::White => 'W',
+            Pearl::Black => 'B',
+        }
+    }
+}
+
+/// A masyu puzzle: draw a single loop through the centres of cells that passes through every pearl.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    pearls: Grid<Option<Pearl>>,
+}
+
+impl Board {
+    pub fn new(pearls: Grid<Option<Pearl>>) -> Result<Self> {
+        let (height, width) = pearls.dim();
+        ensure!(
+            height > 1 && width > 1,
+            Invalid,
+            "Masyu grids must be at least 2x2, but got {height}x{width}."
+        );
+        ensure!(
+            pearls.iter().any(Option::is_some),
+            Invalid,
+            "The puzzle has no pearls."
+        );
+        Ok(Self { pearls })
+    }
+
+    /// Parses a grid with `W` for white pearls, `B` for black pearls and `.` for empty cells.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        let mut pearls = Grid::from_elem((height, width), None);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.chars().count() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} characters in row {row_index}. Got '{row}'."
+                );
+            }
+            for (col_index, c) in row.chars().enumerate() {
+                pearls[Location::new(row_index, col_index)] = match c {
+                    '.' => None,
+                    'W' | 'w' => Some(Pearl::White),
+                    'B' | 'b' => Some(Pearl::Black),
+                    _ => bail!(
+                        Parse,
+                        "Invalid character '{c}' at ({row_index}, {col_index})."
+                    ),
+                };
+            }
+        }
+        Self::new(pearls)
+    }
+
+    /// Imports a puzzle from a puzz.link or pzv.jp URL.
+    pub fn from_puzz_link(url: &str) -> Result<Self> {
+        let link = PuzzLink::parse(url)?;
+        link.expect_kind(&["mashu", "masyu"])?;
+        let circles = puzzlink::decode_circles(&link.body, link.width * link.height)?;
+        let pearls = Grid::from_vec(
+            (link.height, link.width),
+            circles
+                .into_iter()
+                .map(|circle| match circle {
+                    1 => Some(Pearl::White),
+                    2 => Some(Pearl::Black),
+                    _ => None,
+                })
+                .collect(),
+        )
+        .expect("The URL has one circle per cell.");
+        Self::new(pearls)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("masyu", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.pearls.dim()
+    }
+
+    pub fn pearl(&self, location: Location) -> Option<Pearl> {
+        self.pearls[location]
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.pearls.rows() {
+            for &pearl in row {
+                write!(f, "{}", pearl.map_or('.', Pearl::to_char))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_text_and_puzz_links() {
+        let board = Board::parse(include_str!("../../data/masyu/puzzles/eight01.txt")).unwrap();
+        assert_eq!(board.dim(), (5, 8));
+        assert_eq!(board.pearl(Location::new(0, 4)), Some(Pearl::Black));
+        assert_eq!(board.pearl(Location::new(1, 0)), Some(Pearl::White));
+        assert_eq!(board.pearl(Location::new(0, 0)), None);
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        let link = "https://puzz.link/p?mashu/8/5/061c10019c090i";
+        assert_eq!(Board::from_puzz_link(link).unwrap(), board);
+        assert!(Board::from_puzz_link("https://puzz.link/p?mashu/8/5/061c").is_err());
+        assert!(Board::from_puzz_link("https://puzz.link/p?hashi/8/5/061c10019c090i").is_err());
+        assert!(Board::parse("W.\n.X\n").is_err());
+        assert!(Board::parse("W\n.\n").is_err());
+    }
+}
@@ -0,0 +1,210 @@
+use std::fmt::Display;
+
+use crate::{
+    location::{Direction, Location},
+    loop_grid::LoopGrid,
+    search::{self, Contradiction, SearchState},
+};
+
+use super::{Board, Pearl};
+
+/// The loop of a solved puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    board: Board,
+    grid: LoopGrid,
+}
+
+impl Solution {
+    /// Whether the loop leaves the cell at `location` in `direction`.
+    pub fn line(&self, location: Location, direction: Direction) -> bool {
+        self.grid.line(location, direction) == Some(true)
+    }
+}
+
+/// Pearls are shown as `W` and `B`, other cells as `.` and the loop with `-` and `|` between the cells.
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rendered = self
+            .grid
+            .render(|loc| self.board.pearl(loc).map_or('.', Pearl::to_char));
+        write!(f, "{rendered}")
+    }
+}
+
+#[derive(Clone)]
+struct State<'a> {
+    board: &'a Board,
+    grid: LoopGrid,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board) -> Self {
+        Self {
+            board,
+            grid: LoopGrid::new(board.dim()),
+        }
+    }
+
+    /// The loop goes straight through a white pearl and does not go straight through both of its neighbours on the loop.
+    fn apply_white(&mut self, location: Location) -> Result<bool, Contradiction> {
+        let dim = self.grid.dim();
+        let mut changed = false;
+        for (axis, other_axis) in [
+            (
+                [Direction::Left, Direction::Right],
+                [Direction::Up, Direction::Down],
+            ),
+            (
+                [Direction::Up, Direction::Down],
+                [Direction::Left, Direction::Right],
+            ),
+        ] {
+            let lines = axis.map(|direction| self.grid.line(location, direction));
+            // Going straight along one axis rules out the other, and the loop must go along one of them.
+            let along = if lines.contains(&Some(true)) {
+                Some(true)
+            } else if lines.contains(&Some(false)) {
+                Some(false)
+            } else {
+                None
+            };
+            if let Some(along) = along {
+                for direction in axis {
+                    changed |= self.grid.set_line(location, direction, along)?;
+                }
+                for direction in other_axis {
+                    changed |= self.grid.set_line(location, direction, !along)?;
+                }
+            }
+            if along == Some(true) {
+                let ends = axis.map(|direction| {
+                    location
+                        .step(direction, dim)
+                        .expect("Lines stay in the grid.")
+                });
+                let straight = [0, 1].map(|index| self.grid.line(ends[index], axis[index]));
+                for index in 0..2 {
+                    if straight[index] == Some(true) {
+                        changed |= self
+                            .grid
+                            .set_line(ends[1 - index], axis[1 - index], false)?;
+                    }
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// The loop turns on a black pearl and goes straight through the next cell in both directions it leaves in.
+    fn apply_black(&mut self, location: Location) -> Result<bool, Contradiction> {
+        let dim = self.grid.dim();
+        let mut changed = false;
+        for direction in Direction::ALL {
+            let next = location.step(direction, dim);
+            let possible = next.is_some_and(|next| {
+                self.grid.line(location, direction) != Some(false)
+                    && self.grid.line(next, direction) != Some(false)
+            });
+            if !possible {
+                changed |= self.grid.set_line(location, direction, false)?;
+                changed |= self.grid.set_line(location, direction.opposite(), true)?;
+            }
+            if self.grid.line(location, direction) == Some(true) {
+                let next = next.expect("Lines stay in the grid.");
+                changed |= self.grid.set_line(location, direction.opposite(), false)?;
+                changed |= self.grid.set_line(next, direction, true)?;
+            }
+        }
+        Ok(changed)
+    }
+
+    fn apply_pearls(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for loc in Location::grid_iter(self.board.dim()) {
+            changed |= match self.board.pearl(loc) {
+                Some(Pearl::White) => self.apply_white(loc)?,
+                Some(Pearl::Black) => self.apply_black(loc)?,
+                None => false,
+            };
+        }
+        Ok(changed)
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        let board = self.board;
+        let on_loop = |loc: Location| board.pearl(loc).is_some();
+        loop {
+            let mut changed = self.apply_pearls()?;
+            changed |= self.grid.apply_degrees(on_loop)?;
+            changed |= self.grid.apply_single_loop(on_loop)?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some((location, direction)) = self.grid.branch_edge() else {
+            return vec![];
+        };
+        self.grid
+            .branch_on(location, direction)
+            .into_iter()
+            .map(|grid| Self {
+                grid,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+pub fn solve(board: &Board) -> Option<Solution> {
+    search::solve(State::new(board)).map(|state| Solution {
+        board: board.clone(),
+        grid: state.grid,
+    })
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    search::count_solutions(State::new(board), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follows_rules(board: &Board, solution: &Solution) -> bool {
+        let Some(path) = solution.grid.loop_nodes() else {
+            return false;
+        };
+        let len = path.len();
+        let straight = |index: usize| {
+            let (before, after) = (path[(index + len - 1) % len], path[(index + 1) % len]);
+            before.row == after.row || before.col == after.col
+        };
+        let pearls = Location::grid_iter(board.dim())
+            .filter(|&loc| board.pearl(loc).is_some())
+            .all(|loc| path.contains(&loc));
+        pearls
+            && (0..len).all(|index| match board.pearl(path[index]) {
+                None => true,
+                Some(Pearl::White) => {
+                    straight(index) && !(straight(index + len - 1) && straight(index + 1))
+                }
+                Some(Pearl::Black) => {
+                    !straight(index) && straight(index + len - 1) && straight(index + 1)
+                }
+            })
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/masyu/puzzles/eight01.txt")).unwrap();
+        let solution = solve(&board).unwrap();
+        assert!(follows_rules(&board, &solution));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
@@ -0,0 +1,22 @@
+mod board;
+pub use board::{Board, Cell};
+mod solver;
+pub use solver::{analyze, Analysis};
+
+/// Minesweeper boards as a [`Puzzle`](crate::puzzle::Puzzle), in the text format of [`Board::parse`].
+pub struct Minesweeper;
+
+impl crate::puzzle::Puzzle for Minesweeper {
+    const NAME: &'static str = "minesweeper board";
+
+    type Instance = Board;
+    type Solution = Analysis;
+
+    fn parse(input: &str) -> crate::Result<Board> {
+        Board::parse(input)
+    }
+
+    fn solve(board: &Board) -> crate::Result<Option<Analysis>> {
+        Ok(analyze(board))
+    }
+}
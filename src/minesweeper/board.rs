@@ -0,0 +1,154 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cell {
+    Hidden,
+    /// A hidden cell already known to be a mine.
+    Flagged,
+    /// A revealed cell showing the number of mines among its eight neighbours.
+    Revealed(u8),
+}
+
+impl Cell {
+    pub fn to_char(self) -> char {
+        match self {
+            Cell::Hidden => '.',
+            Cell::Flagged => 'F',
+            Cell::Revealed(number) => (b'0' + number) as char,
+        }
+    }
+}
+
+/// A partially revealed minesweeper board, optionally with the total number of mines.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    cells: Grid<Cell>,
+    mines: Option<usize>,
+}
+
+impl Board {
+    pub fn new(cells: Grid<Cell>, mines: Option<usize>) -> Result<Self> {
+        let (height, width) = cells.dim();
+        ensure!(
+            height > 0 && width > 0,
+            Invalid,
+            "Minesweeper boards must have at least one cell."
+        );
+        if let Some(number) = cells.iter().find_map(|&cell| match cell {
+            Cell::Revealed(number) if number > 8 => Some(number),
+            _ => None,
+        }) {
+            bail!(
+                Invalid,
+                "Revealed numbers must be at most 8, but got {number}."
+            );
+        }
+        if let Some(mines) = mines {
+            let flagged = cells.iter().filter(|&&cell| cell == Cell::Flagged).count();
+            let hidden = cells.iter().filter(|&&cell| cell == Cell::Hidden).count();
+            ensure!(
+                (flagged..=flagged + hidden).contains(&mines),
+                Invalid,
+                "There must be between {flagged} and {} mines, but got {mines}.",
+                flagged + hidden
+            );
+        }
+        Ok(Self { cells, mines })
+    }
+
+    /// Parses a grid with `.` or `?` for hidden cells, `F` or `*` for flagged mines and digits for revealed cells.
+    /// The total number of mines is left unknown.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        let mut cells = Grid::from_elem((height, width), Cell::Hidden);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.chars().count() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} characters in row {row_index}. Got '{row}'."
+                );
+            }
+            for (col_index, c) in row.chars().enumerate() {
+                cells[Location::new(row_index, col_index)] = match c {
+                    '.' | '?' => Cell::Hidden,
+                    'F' | '*' => Cell::Flagged,
+                    _ => Cell::Revealed(c.to_digit(10).ok_or_else(|| {
+                        Error::Parse(format!(
+                            "Invalid character '{c}' at ({row_index}, {col_index})."
+                        ))
+                    })? as u8),
+                };
+            }
+        }
+        Self::new(cells, None)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("minesweeper", path)?;
+        Self::parse(string)
+    }
+
+    /// The same board with the total number of mines known.
+    pub fn with_mines(self, mines: usize) -> Result<Self> {
+        Self::new(self.cells, Some(mines))
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.cells.dim()
+    }
+
+    pub fn cell(&self, location: Location) -> Cell {
+        self.cells[location]
+    }
+
+    pub fn mines(&self) -> Option<usize> {
+        self.mines
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.cells.rows() {
+            writeln!(f, "{}", row.map(|cell| cell.to_char()).collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hidden_flagged_and_revealed_cells() {
+        let board =
+            Board::parse(include_str!("../../data/minesweeper/boards/twelve01.txt")).unwrap();
+        assert_eq!(board.dim(), (12, 12));
+        assert_eq!(board.cell(Location::new(0, 8)), Cell::Revealed(1));
+        assert_eq!(board.cell(Location::new(0, 0)), Cell::Hidden);
+        assert_eq!(board.mines(), None);
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        let flagged = Board::parse("?*\nF1\n").unwrap();
+        assert_eq!(flagged.cell(Location::new(0, 0)), Cell::Hidden);
+        assert_eq!(flagged.cell(Location::new(0, 1)), Cell::Flagged);
+        assert_eq!(flagged.cell(Location::new(1, 0)), Cell::Flagged);
+        assert!(flagged.clone().with_mines(1).is_err());
+        assert!(flagged.with_mines(3).is_ok());
+        assert!(Board::parse("9.\n..\n").is_err());
+        assert!(Board::parse("..\n.\n").is_err());
+    }
+}
@@ -0,0 +1,448 @@
+use std::fmt::Display;
+
+use crate::{grid::Grid, location::Location};
+
+use super::{Board, Cell};
+
+/// Gives up on subset reasoning if it derives more constraints than this, leaving the rest to the exhaustive search.
+const MAX_CONSTRAINTS: usize = 2_000;
+
+/// Exactly `mines` of `cells` are mines.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Constraint {
+    /// Sorted in row-major order.
+    cells: Vec<Location>,
+    mines: usize,
+}
+
+impl Constraint {
+    fn is_subset_of(&self, other: &Self) -> bool {
+        self.cells.iter().all(|cell| other.cells.contains(cell))
+    }
+}
+
+/// Whether each cell is known to be a mine (`Some(true)`) or known to be safe (`Some(false)`).
+type Known = Grid<Option<bool>>;
+
+/// Marks the cell at `location`, failing if it is already known to be the opposite.
+fn mark(known: &mut Known, location: Location, mine: bool) -> Option<bool> {
+    match known[location] {
+        Some(known) if known == mine => Some(false),
+        Some(_) => None,
+        None => {
+            known[location] = Some(mine);
+            Some(true)
+        }
+    }
+}
+
+/// The constraints given by the revealed numbers on the cells that are not known yet.
+/// Returns `None` if a number cannot be satisfied.
+fn constraints(board: &Board, known: &Known) -> Option<Vec<Constraint>> {
+    let dim = board.dim();
+    let mut constraints = vec![];
+    for loc in Location::grid_iter(dim) {
+        let Cell::Revealed(number) = board.cell(loc) else {
+            continue;
+        };
+        let neighbors = loc.neighbors(dim).into_iter().flatten();
+        let (mut cells, mut mines) = (vec![], 0);
+        for neighbor in neighbors {
+            match known[neighbor] {
+                Some(true) => mines += 1,
+                Some(false) => {}
+                None => cells.push(neighbor),
+            }
+        }
+        let mines = (number as usize).checked_sub(mines)?;
+        if mines > cells.len() {
+            return None;
+        }
+        if !cells.is_empty() {
+            cells.sort_by_key(|cell| (cell.row, cell.col));
+            constraints.push(Constraint { cells, mines });
+        }
+    }
+    Some(constraints)
+}
+
+/// Decides what simple counting can: constraints with no mines or only mines left,
+/// and the differences between constraints where one contains the other.
+fn apply_subsets(board: &Board, known: &mut Known) -> Option<()> {
+    loop {
+        let mut constraints = constraints(board, known)?;
+        let mut index = 0;
+        while index < constraints.len() && constraints.len() < MAX_CONSTRAINTS {
+            for other in 0..constraints.len() {
+                let (small, large) = (&constraints[index], &constraints[other]);
+                if small.cells.len() >= large.cells.len() || !small.is_subset_of(large) {
+                    continue;
+                }
+                let difference = Constraint {
+                    cells: large
+                        .cells
+                        .iter()
+                        .copied()
+                        .filter(|cell| !small.cells.contains(cell))
+                        .collect(),
+                    mines: large.mines.checked_sub(small.mines)?,
+                };
+                if difference.mines > difference.cells.len() {
+                    return None;
+                }
+                if !constraints.contains(&difference) {
+                    constraints.push(difference);
+                }
+            }
+            index += 1;
+        }
+        let mut changed = false;
+        for constraint in constraints {
+            if constraint.mines == 0 || constraint.mines == constraint.cells.len() {
+                for &cell in constraint.cells.iter() {
+                    changed |= mark(known, cell, constraint.mines > 0)?;
+                }
+            }
+        }
+        if !changed {
+            return Some(());
+        }
+    }
+}
+
+/// For a group of cells linked by constraints, how many arrangements of mines there are with each number of mines,
+/// and how many of those have a mine in each cell.
+struct Tally {
+    arrangements: Vec<f64>,
+    cell_mines: Vec<Vec<f64>>,
+}
+
+/// Exhaustively enumerates the arrangements of mines in `cells` that satisfy `constraints`.
+struct Enumeration<'a> {
+    constraints: &'a [Constraint],
+    /// The constraints each cell is part of.
+    cell_constraints: Vec<Vec<usize>>,
+    /// The mines placed and cells left undecided in each constraint.
+    placed: Vec<usize>,
+    open: Vec<usize>,
+    mines: Vec<bool>,
+    tally: Tally,
+}
+
+impl<'a> Enumeration<'a> {
+    fn run(cells: &[Location], constraints: &'a [Constraint]) -> Tally {
+        let cell_constraints = cells
+            .iter()
+            .map(|cell| {
+                (0..constraints.len())
+                    .filter(|&index| constraints[index].cells.contains(cell))
+                    .collect()
+            })
+            .collect();
+        let mut enumeration = Self {
+            constraints,
+            cell_constraints,
+            placed: vec![0; constraints.len()],
+            open: constraints
+                .iter()
+                .map(|constraint| constraint.cells.len())
+                .collect(),
+            mines: vec![],
+            tally: Tally {
+                arrangements: vec![0.; cells.len() + 1],
+                cell_mines: vec![vec![0.; cells.len() + 1]; cells.len()],
+            },
+        };
+        enumeration.visit();
+        enumeration.tally
+    }
+
+    fn visit(&mut self) {
+        let cell = self.mines.len();
+        if cell == self.cell_constraints.len() {
+            let count = self.mines.iter().filter(|&&mine| mine).count();
+            self.tally.arrangements[count] += 1.;
+            for (cell, &mine) in self.mines.iter().enumerate() {
+                if mine {
+                    self.tally.cell_mines[cell][count] += 1.;
+                }
+            }
+            return;
+        }
+        for mine in [false, true] {
+            let mut consistent = true;
+            for &index in self.cell_constraints[cell].iter() {
+                self.open[index] -= 1;
+                self.placed[index] += mine as usize;
+                let constraint = &self.constraints[index];
+                consistent &= self.placed[index] <= constraint.mines
+                    && self.placed[index] + self.open[index] >= constraint.mines;
+            }
+            if consistent {
+                self.mines.push(mine);
+                self.visit();
+                self.mines.pop();
+            }
+            for &index in self.cell_constraints[cell].iter() {
+                self.open[index] += 1;
+                self.placed[index] -= mine as usize;
+            }
+        }
+    }
+}
+
+/// Splits the cells of `constraints` into groups that share no constraints, along with the constraints of each group.
+fn components(constraints: Vec<Constraint>) -> Vec<(Vec<Location>, Vec<Constraint>)> {
+    let mut components: Vec<(Vec<Location>, Vec<Constraint>)> = vec![];
+    for constraint in constraints {
+        let (linked, mut rest): (Vec<_>, Vec<_>) = components
+            .into_iter()
+            .partition(|(cells, _)| constraint.cells.iter().any(|cell| cells.contains(cell)));
+        let mut merged = linked.into_iter().fold(
+            (vec![], vec![]),
+            |(mut cells, mut constraints), (other_cells, other_constraints)| {
+                cells.extend(other_cells);
+                constraints.extend(other_constraints);
+                (cells, constraints)
+            },
+        );
+        for &cell in constraint.cells.iter() {
+            if !merged.0.contains(&cell) {
+                merged.0.push(cell);
+            }
+        }
+        merged.1.push(constraint);
+        rest.push(merged);
+        components = rest;
+    }
+    components
+}
+
+fn binomial(n: usize, k: usize) -> f64 {
+    if k > n {
+        return 0.;
+    }
+    let k = k.min(n - k);
+    (0..k).fold(1., |product, i| product * (n - i) as f64 / (i + 1) as f64)
+}
+
+fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut result = vec![0.; a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            result[i + j] += x * y;
+        }
+    }
+    result
+}
+
+/// A cell is provably safe if no consistent arrangement has a mine in it, and provably a mine if every one does.
+fn verdict(mine_weight: f64, safe_weight: f64) -> Option<bool> {
+    if mine_weight == 0. {
+        Some(false)
+    } else if safe_weight == 0. {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// What can be deduced about every cell of a board.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Analysis {
+    board: Board,
+    verdicts: Known,
+    probabilities: Grid<Option<f64>>,
+}
+
+impl Analysis {
+    /// Whether the cell at `location` is provably a mine (`Some(true)`) or provably safe (`Some(false)`).
+    /// Revealed cells are safe and flagged cells are mines.
+    pub fn verdict(&self, location: Location) -> Option<bool> {
+        self.verdicts[location]
+    }
+
+    /// The chance that the cell at `location` is a mine, if every arrangement of mines consistent with the board is
+    /// equally likely. Hidden cells away from the revealed numbers only get an estimate if the number of mines is known.
+    pub fn probability(&self, location: Location) -> Option<f64> {
+        self.probabilities[location]
+    }
+}
+
+/// Revealed and flagged cells are shown as on the board, provable mines as `*`, provably safe cells as `o`
+/// and undecided cells as `.`.
+impl Display for Analysis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for loc in Location::grid_iter(self.board.dim()) {
+            let c = match (self.board.cell(loc), self.verdict(loc)) {
+                (Cell::Hidden, Some(true)) => '*',
+                (Cell::Hidden, Some(false)) => 'o',
+                (cell, _) => cell.to_char(),
+            };
+            write!(f, "{c}")?;
+            if loc.col + 1 == self.board.dim().1 {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Finds the hidden cells that are provably mines or provably safe and estimates the chance of the rest being mines.
+///
+/// Returns `None` if no arrangement of mines is consistent with the board.
+pub fn analyze(board: &Board) -> Option<Analysis> {
+    let dim = board.dim();
+    let mut known = Grid::from_fn(dim, |loc| match board.cell(loc) {
+        Cell::Hidden => None,
+        Cell::Flagged => Some(true),
+        Cell::Revealed(_) => Some(false),
+    });
+    apply_subsets(board, &mut known)?;
+    let components = components(constraints(board, &known)?)
+        .into_iter()
+        .map(|(cells, constraints)| {
+            let tally = Enumeration::run(&cells, &constraints);
+            (cells, tally)
+        })
+        .collect::<Vec<_>>();
+    let interior = Location::grid_iter(dim)
+        .filter(|&loc| {
+            known[loc].is_none() && components.iter().all(|(cells, _)| !cells.contains(&loc))
+        })
+        .collect::<Vec<_>>();
+    // The weight of an arrangement of the frontier with `count` mines is the number of ways to place the rest
+    // of the mines in the interior, or just one if the total is unknown.
+    let remaining = board
+        .mines()
+        .map(|mines| mines.checked_sub(known.iter().filter(|&&cell| cell == Some(true)).count()));
+    let weight = |count: usize, interior: usize| match remaining {
+        None => 1.,
+        Some(None) => 0.,
+        Some(Some(remaining)) => remaining
+            .checked_sub(count)
+            .map_or(0., |rest| binomial(interior, rest)),
+    };
+    let distribution = |skipped: Option<usize>| {
+        components
+            .iter()
+            .enumerate()
+            .filter(|&(index, _)| Some(index) != skipped)
+            .fold(vec![1.], |distribution, (_, (_, tally))| {
+                convolve(&distribution, &tally.arrangements)
+            })
+    };
+    let weigh = |mines: &[f64], others: &[f64], interior: usize| -> f64 {
+        mines
+            .iter()
+            .enumerate()
+            .map(|(count, &mines)| {
+                mines
+                    * others
+                        .iter()
+                        .enumerate()
+                        .map(|(other, &arrangements)| {
+                            arrangements * weight(count + other, interior)
+                        })
+                        .sum::<f64>()
+            })
+            .sum()
+    };
+
+    let total = weigh(&[1.], &distribution(None), interior.len());
+    if total == 0. {
+        return None;
+    }
+    let mut probabilities = known.map(|cell| cell.map(|mine| if mine { 1. } else { 0. }));
+    for (index, (cells, tally)) in components.iter().enumerate() {
+        let others = if remaining.is_some() {
+            distribution(Some(index))
+        } else {
+            vec![1.]
+        };
+        let component_total = weigh(&tally.arrangements, &others, interior.len());
+        for (&cell, cell_mines) in cells.iter().zip(tally.cell_mines.iter()) {
+            let cell_safes = tally
+                .arrangements
+                .iter()
+                .zip(cell_mines.iter())
+                .map(|(arrangements, mines)| arrangements - mines)
+                .collect::<Vec<_>>();
+            let mine_weight = weigh(cell_mines, &others, interior.len());
+            let safe_weight = weigh(&cell_safes, &others, interior.len());
+            known[cell] = verdict(mine_weight, safe_weight);
+            probabilities[cell] = Some(mine_weight / component_total);
+        }
+    }
+    if remaining.is_some() && !interior.is_empty() {
+        let frontier = distribution(None);
+        let mine_weight = weigh(&[0., 1.], &frontier, interior.len() - 1);
+        let safe_weight = weigh(&[1.], &frontier, interior.len() - 1);
+        for &cell in interior.iter() {
+            known[cell] = verdict(mine_weight, safe_weight);
+            probabilities[cell] = Some(mine_weight / total);
+        }
+    }
+    Some(Analysis {
+        board: board.clone(),
+        verdicts: known,
+        probabilities,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every arrangement of mines puts exactly a revealed cell's number of mines around it, so the chances
+    /// around it must add up to the number, and the verdicts must agree with the chances.
+    fn follows_rules(board: &Board, analysis: &Analysis) -> bool {
+        let dim = board.dim();
+        let numbers = Location::grid_iter(dim).all(|loc| match board.cell(loc) {
+            Cell::Revealed(number) => {
+                let expected = loc
+                    .neighbors(dim)
+                    .into_iter()
+                    .flatten()
+                    .map(|neighbor| analysis.probability(neighbor))
+                    .sum::<Option<f64>>();
+                expected.is_some_and(|expected| (expected - number as f64).abs() < 1e-9)
+            }
+            _ => true,
+        });
+        let verdicts = Location::grid_iter(dim).all(|loc| match analysis.probability(loc) {
+            Some(probability) => {
+                (0. ..=1.).contains(&probability)
+                    && match analysis.verdict(loc) {
+                        Some(mine) => probability == if mine { 1. } else { 0. },
+                        None => 0. < probability && probability < 1.,
+                    }
+            }
+            None => analysis.verdict(loc).is_none(),
+        });
+        numbers && verdicts
+    }
+
+    #[test]
+    fn one_two_one() {
+        let board = Board::parse("...\n121").unwrap();
+        let analysis = analyze(&board).unwrap();
+        let verdicts = (0..3)
+            .map(|col| analysis.verdict(Location::new(0, col)))
+            .collect::<Vec<_>>();
+        assert_eq!(verdicts, vec![Some(true), Some(false), Some(true)]);
+    }
+
+    #[test]
+    fn analyzes_data_board() {
+        let board =
+            Board::parse(include_str!("../../data/minesweeper/boards/twelve01.txt")).unwrap();
+        let analysis = analyze(&board).unwrap();
+        assert!(follows_rules(&board, &analysis));
+        assert_eq!(analysis.verdict(Location::new(3, 4)), Some(true));
+        let board = board.with_mines(20).unwrap();
+        let analysis = analyze(&board).unwrap();
+        assert!(follows_rules(&board, &analysis));
+        assert!(analysis.probability(Location::new(11, 0)).is_some());
+    }
+}
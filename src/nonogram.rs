@@ -0,0 +1,27 @@
+mod board;
+pub use board::{Board, Clue, Color};
+mod line;
+mod solver;
+pub use solver::{count_solutions, solve, Solution};
+
+/// Nonograms as a [`Puzzle`](crate::puzzle::Puzzle), in the text format of [`Board::parse`].
+pub struct Nonogram;
+
+impl crate::puzzle::Puzzle for Nonogram {
+    const NAME: &'static str = "nonogram";
+
+    type Instance = Board;
+    type Solution = Solution;
+
+    fn parse(input: &str) -> crate::Result<Board> {
+        Board::parse(input)
+    }
+
+    fn solve(board: &Board) -> crate::Result<Option<Solution>> {
+        Ok(solve(board))
+    }
+
+    fn count_solutions(board: &Board, limit: usize) -> crate::Result<usize> {
+        Ok(count_solutions(board, limit))
+    }
+}
@@ -0,0 +1,316 @@
+use std::{fmt::Display, path};
+
+use crate::error::{bail, ensure, read_to_string, Error, Result};
+
+/// A color that cells can be filled with. Cells that are not filled are the background.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Color {
+    pub name: String,
+    pub rgb: [u8; 3],
+    /// The character the color is written as, both in clues and in solutions.
+    pub symbol: char,
+}
+
+impl Color {
+    /// The color of plain black and white nonograms.
+    pub fn black() -> Self {
+        Self {
+            name: "black".to_string(),
+            rgb: [0, 0, 0],
+            symbol: '#',
+        }
+    }
+}
+
+/// A run of `length` consecutive cells of one color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Clue {
+    pub length: usize,
+    /// The index of the color in [`Board::colors`].
+    pub color: usize,
+}
+
+/// A nonogram: fill cells such that the runs of filled cells in every row and column, in order, are those given by
+/// its clues. Runs of the same color must be separated by at least one background cell, while runs of different
+/// colors may touch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    colors: Vec<Color>,
+    rows: Vec<Vec<Clue>>,
+    columns: Vec<Vec<Clue>>,
+}
+
+impl Board {
+    /// The most colors a puzzle may use besides the background.
+    pub const MAX_COLORS: usize = 31;
+
+    pub fn new(colors: Vec<Color>, rows: Vec<Vec<Clue>>, columns: Vec<Vec<Clue>>) -> Result<Self> {
+        ensure!(
+            (1..=Self::MAX_COLORS).contains(&colors.len()),
+            Invalid,
+            "Nonograms must have between 1 and {} colors, but got {}.",
+            Self::MAX_COLORS,
+            colors.len()
+        );
+        for (index, color) in colors.iter().enumerate() {
+            ensure!(
+                colors[..index]
+                    .iter()
+                    .all(|other| other.symbol != color.symbol),
+                Invalid,
+                "More than one color has the symbol '{}'.",
+                color.symbol
+            );
+        }
+        for (kind, lines, length) in [
+            ("row", &rows, columns.len()),
+            ("column", &columns, rows.len()),
+        ] {
+            for (index, line) in lines.iter().enumerate() {
+                for clue in line {
+                    ensure!(
+                        clue.length > 0,
+                        Invalid,
+                        "The clues of {kind} {index} include an empty run."
+                    );
+                    ensure!(
+                        clue.color < colors.len(),
+                        Invalid,
+                        "The clues of {kind} {index} use color {}, but there are only {} colors.",
+                        clue.color,
+                        colors.len()
+                    );
+                }
+                let needed = line.iter().map(|clue| clue.length).sum::<usize>()
+                    + line
+                        .windows(2)
+                        .filter(|pair| pair[0].color == pair[1].color)
+                        .count();
+                ensure!(
+                    needed <= length,
+                    Invalid,
+                    "The clues of {kind} {index} need {needed} cells, but it only has {length}."
+                );
+            }
+        }
+        for (color, Color { name, .. }) in colors.iter().enumerate() {
+            let total = |lines: &[Vec<Clue>]| {
+                lines
+                    .iter()
+                    .flatten()
+                    .filter(|clue| clue.color == color)
+                    .map(|clue| clue.length)
+                    .sum::<usize>()
+            };
+            ensure!(
+                total(&rows) == total(&columns),
+                Invalid,
+                "The row clues have {} {name} cells, but the column clues have {}.",
+                total(&rows),
+                total(&columns)
+            );
+        }
+        Ok(Self {
+            colors,
+            rows,
+            columns,
+        })
+    }
+
+    /// Parses the `.non` format: a `width` and a `height` line, then a `rows` line followed by a line of clues for
+    /// every row and a `columns` line followed by a line of clues for every column. Other lines, such as `title`,
+    /// are skipped.
+    ///
+    /// Clues are separated by commas or whitespace, and a row with no clues is written as `0` or left empty.
+    /// For colored puzzles, every color is declared before the clues by a line such as `color red #ff0000 r`,
+    /// giving its name, its value and its symbol, and the symbol follows the length of every clue, as in `3r`.
+    /// A clue without a symbol has the first color, which is black if no colors are declared.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let mut lines = string.as_ref().lines().map(str::trim);
+        let mut width = None;
+        let mut height = None;
+        let mut colors = vec![];
+        let mut rows = None;
+        let mut columns = None;
+        while let Some(line) = lines.next() {
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some(keyword @ ("width" | "height")) => {
+                    let size = words
+                        .next()
+                        .and_then(|size| size.parse().ok())
+                        .ok_or_else(|| Error::Parse(format!("Invalid {keyword} line '{line}'.")))?;
+                    if keyword == "width" {
+                        width = Some(size);
+                    } else {
+                        height = Some(size);
+                    }
+                }
+                Some("color") => colors.push(parse_color(line)?),
+                Some(keyword @ ("rows" | "columns")) => {
+                    if colors.is_empty() {
+                        colors.push(Color::black());
+                    }
+                    let (size, kind) = if keyword == "rows" {
+                        (
+                            height.ok_or_else(|| {
+                                Error::Parse("The height must be given before the rows.".into())
+                            })?,
+                            "row",
+                        )
+                    } else {
+                        (
+                            width.ok_or_else(|| {
+                                Error::Parse("The width must be given before the columns.".into())
+                            })?,
+                            "column",
+                        )
+                    };
+                    let clues = (0..size)
+                        .map(|index| {
+                            let line = lines.next().ok_or_else(|| {
+                                Error::Parse(format!("Missing clues for {kind} {index}."))
+                            })?;
+                            parse_clues(line, &colors).map_err(|_| {
+                                Error::Parse(format!("Invalid clues for {kind} {index}."))
+                            })
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    if keyword == "rows" {
+                        rows = Some(clues);
+                    } else {
+                        columns = Some(clues);
+                    }
+                }
+                _ => {}
+            }
+        }
+        let rows = rows.ok_or_else(|| Error::Parse("The puzzle has no row clues.".into()))?;
+        let columns =
+            columns.ok_or_else(|| Error::Parse("The puzzle has no column clues.".into()))?;
+        Self::new(colors, rows, columns)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("nonogram", path)?;
+        Self::parse(string)
+    }
+
+    /// The number of rows and columns.
+    pub fn dim(&self) -> (usize, usize) {
+        (self.rows.len(), self.columns.len())
+    }
+
+    pub fn colors(&self) -> &[Color] {
+        &self.colors
+    }
+
+    /// The clues of every row, from left to right.
+    pub fn rows(&self) -> &[Vec<Clue>] {
+        &self.rows
+    }
+
+    /// The clues of every column, from top to bottom.
+    pub fn columns(&self) -> &[Vec<Clue>] {
+        &self.columns
+    }
+}
+
+/// Parses a line such as `color red #ff0000 r`.
+fn parse_color(line: &str) -> Result<Color> {
+    let words = line.split_whitespace().collect::<Vec<_>>();
+    let [_, name, rgb, symbol] = words[..] else {
+        bail!(
+            Parse,
+            "Expected a color line such as 'color red #ff0000 r'. Got '{line}'."
+        );
+    };
+    let hex = rgb.trim_start_matches('#');
+    ensure!(
+        hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()),
+        Parse,
+        "Invalid color value '{rgb}' in '{line}'."
+    );
+    let rgb = [0, 2, 4].map(|start| u8::from_str_radix(&hex[start..start + 2], 16).unwrap());
+    let mut chars = symbol.chars();
+    let (Some(symbol), None) = (chars.next(), chars.next()) else {
+        bail!(
+            Parse,
+            "The symbol of a color must be a single character. Got '{symbol}'."
+        );
+    };
+    ensure!(
+        !symbol.is_ascii_digit() && symbol != ',' && symbol != '.',
+        Parse,
+        "The symbol '{symbol}' cannot be used for a color."
+    );
+    Ok(Color {
+        name: name.to_string(),
+        rgb,
+        symbol,
+    })
+}
+
+/// Parses a line of clues such as `1,3r,2`.
+fn parse_clues(line: &str, colors: &[Color]) -> Result<Vec<Clue>> {
+    if line == "0" {
+        return Ok(vec![]);
+    }
+    line.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            let split = item
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(item.len());
+            let (length, symbol) = item.split_at(split);
+            let length = length
+                .parse()
+                .map_err(|_| Error::Parse(format!("Invalid clue '{item}'.")))?;
+            let color = match symbol {
+                "" => 0,
+                _ => colors
+                    .iter()
+                    .position(|color| color.symbol.to_string() == symbol)
+                    .ok_or_else(|| {
+                        Error::Parse(format!("Unknown color '{symbol}' in clue '{item}'."))
+                    })?,
+            };
+            Ok(Clue { length, color })
+        })
+        .collect()
+}
+
+/// Writes the puzzle in the `.non` format.
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (height, width) = self.dim();
+        writeln!(f, "width {width}")?;
+        writeln!(f, "height {height}")?;
+        let colored = self.colors != [Color::black()];
+        if colored {
+            for Color { name, rgb, symbol } in self.colors.iter() {
+                let [r, g, b] = rgb;
+                writeln!(f, "color {name} #{r:02x}{g:02x}{b:02x} {symbol}")?;
+            }
+        }
+        for (keyword, lines) in [("rows", &self.rows), ("columns", &self.columns)] {
+            writeln!(f, "{keyword}")?;
+            for line in lines {
+                let clues = line
+                    .iter()
+                    .map(|clue| match colored {
+                        true => format!("{}{}", clue.length, self.colors[clue.color].symbol),
+                        false => clue.length.to_string(),
+                    })
+                    .collect::<Vec<_>>();
+                if clues.is_empty() {
+                    writeln!(f, "0")?;
+                } else {
+                    writeln!(f, "{}", clues.join(","))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
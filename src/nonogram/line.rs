@@ -0,0 +1,118 @@
+//! Deductions on a single row or column, which is where all the information of a nonogram lies.
+
+use super::Clue;
+
+/// The bit of the background in a set of possible cell values.
+pub(super) const BACKGROUND: u32 = 1;
+
+/// The bit of `color` in a set of possible cell values.
+pub(super) fn color_bit(color: usize) -> u32 {
+    1 << (color + 1)
+}
+
+/// Narrows the possible values of every cell of a line to those it has in some placement of the clues that fits
+/// the line, or returns `None` if no placement fits.
+///
+/// Works on positions `(run, cell)`: the first `run` runs are placed before `cell` and the next run may start at
+/// `cell`. Every placement is a path of background cells and runs from `(0, 0)` to `(runs, cells)`, so a value is
+/// possible for a cell exactly when a step giving it that value lies on such a path.
+pub(super) fn solve_line(clues: &[Clue], cells: &[u32]) -> Option<Vec<u32>> {
+    let (runs, length) = (clues.len(), cells.len());
+    let background = |cell: usize| cells[cell] & BACKGROUND != 0;
+    // The number of cells before each cell that may have the color of each run, to check runs in constant time.
+    let fits = clues
+        .iter()
+        .map(|clue| {
+            let mut counts = vec![0; length + 1];
+            for (cell, &value) in cells.iter().enumerate() {
+                counts[cell + 1] = counts[cell] + usize::from(value & color_bit(clue.color) != 0);
+            }
+            counts
+        })
+        .collect::<Vec<_>>();
+    // Where placing run `run` at `start` leads, if it fits. A run followed by one of the same color needs a
+    // background cell after it.
+    let place = |run: usize, start: usize| -> Option<usize> {
+        let end = start + clues[run].length;
+        if end > length || fits[run][end] - fits[run][start] != clues[run].length {
+            return None;
+        }
+        let gap = run + 1 < runs && clues[run + 1].color == clues[run].color;
+        match gap {
+            true if end < length && background(end) => Some(end + 1),
+            true => None,
+            false => Some(end),
+        }
+    };
+    let mut forward = vec![vec![false; length + 1]; runs + 1];
+    forward[0][0] = true;
+    for run in 0..=runs {
+        for cell in 0..=length {
+            if !forward[run][cell] {
+                continue;
+            }
+            if cell < length && background(cell) {
+                forward[run][cell + 1] = true;
+            }
+            if let Some(next) = (run < runs).then(|| place(run, cell)).flatten() {
+                forward[run + 1][next] = true;
+            }
+        }
+    }
+    let mut backward = vec![vec![false; length + 1]; runs + 1];
+    backward[runs][length] = true;
+    for run in (0..=runs).rev() {
+        for cell in (0..length).rev() {
+            backward[run][cell] = (background(cell) && backward[run][cell + 1])
+                || (run < runs && place(run, cell).is_some_and(|next| backward[run + 1][next]));
+        }
+    }
+    if !backward[0][0] {
+        return None;
+    }
+    let mut possible = vec![0; length];
+    for run in 0..=runs {
+        for cell in 0..length {
+            if !forward[run][cell] {
+                continue;
+            }
+            if background(cell) && backward[run][cell + 1] {
+                possible[cell] |= BACKGROUND;
+            }
+            if run == runs {
+                continue;
+            }
+            if let Some(next) = place(run, cell).filter(|&next| backward[run + 1][next]) {
+                let end = cell + clues[run].length;
+                for value in &mut possible[cell..end] {
+                    *value |= color_bit(clues[run].color);
+                }
+                if next > end {
+                    possible[end] |= BACKGROUND;
+                }
+            }
+        }
+    }
+    Some(possible)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_colors_need_gaps() {
+        let unknown = BACKGROUND | color_bit(0) | color_bit(1);
+        let clue = |length, color| Clue { length, color };
+        // Two runs of two of the same color fill five cells with a gap, so only the middle is decided.
+        let possible = solve_line(&[clue(2, 0), clue(2, 0)], &[unknown; 5]).unwrap();
+        assert_eq!(possible[2], BACKGROUND);
+        // Runs of different colors may touch, so they need not leave a gap.
+        let possible = solve_line(&[clue(2, 0), clue(2, 1)], &[unknown; 4]).unwrap();
+        assert_eq!(
+            possible,
+            [color_bit(0), color_bit(0), color_bit(1), color_bit(1)]
+        );
+        assert_eq!(solve_line(&[clue(3, 0), clue(2, 0)], &[unknown; 5]), None);
+    }
+}
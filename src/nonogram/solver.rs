@@ -0,0 +1,145 @@
+use std::fmt::Display;
+
+use crate::{
+    grid::Grid,
+    location::Location,
+    search::{self, Contradiction, SearchState},
+};
+
+use super::{
+    line::{self, BACKGROUND},
+    Board, Color,
+};
+
+/// The possible values of every cell as bit sets, as used by [`line::solve_line`].
+#[derive(Clone, Debug)]
+struct State<'a> {
+    board: &'a Board,
+    cells: Grid<u32>,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board) -> Self {
+        let all =
+            (0..board.colors().len()).fold(BACKGROUND, |all, color| all | line::color_bit(color));
+        Self {
+            board,
+            cells: Grid::from_elem(board.dim(), all),
+        }
+    }
+
+    /// Solves every row and then every column on its own.
+    fn apply_lines(&mut self) -> Result<bool, Contradiction> {
+        let board = self.board;
+        let (height, width) = board.dim();
+        let rows = board.rows().iter().enumerate().map(|(row, clues)| {
+            let cells = (0..width).map(move |col| Location::new(row, col));
+            (clues, cells.collect::<Vec<_>>())
+        });
+        let columns = board.columns().iter().enumerate().map(|(col, clues)| {
+            let cells = (0..height).map(move |row| Location::new(row, col));
+            (clues, cells.collect::<Vec<_>>())
+        });
+        let mut changed = false;
+        for (clues, cells) in rows.chain(columns) {
+            let current = cells.iter().map(|&loc| self.cells[loc]).collect::<Vec<_>>();
+            let possible = line::solve_line(clues, &current).ok_or(Contradiction)?;
+            if possible != current {
+                for (loc, value) in cells.into_iter().zip(possible) {
+                    self.cells[loc] = value;
+                }
+                changed = true;
+            }
+        }
+        Ok(changed)
+    }
+
+    fn into_solution(self) -> Solution {
+        Solution {
+            colors: self.board.colors().to_vec(),
+            cells: self
+                .cells
+                .map(|&value| (value != BACKGROUND).then(|| value.trailing_zeros() as usize - 1)),
+        }
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        while self.apply_lines()? {}
+        Ok(())
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(location) =
+            Location::grid_iter(self.board.dim()).find(|&loc| self.cells[loc].count_ones() > 1)
+        else {
+            return vec![];
+        };
+        let value = self.cells[location];
+        (0..u32::BITS)
+            .map(|bit| 1 << bit)
+            .filter(|&option| value & option != 0)
+            .map(|option| {
+                let mut cells = self.cells.clone();
+                cells[location] = option;
+                Self {
+                    cells,
+                    ..self.clone()
+                }
+            })
+            .collect()
+    }
+}
+
+/// The color of every cell, or `None` for the background.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    colors: Vec<Color>,
+    cells: Grid<Option<usize>>,
+}
+
+impl Solution {
+    pub fn color(&self, location: Location) -> Option<&Color> {
+        self.cells[location].map(|color| &self.colors[color])
+    }
+
+    /// The solution drawn with 24-bit color escape codes for terminals, two characters to a cell.
+    pub fn render_ansi(&self) -> String {
+        let mut string = String::new();
+        for row in self.cells.rows() {
+            for cell in row {
+                match cell {
+                    Some(color) => {
+                        let [r, g, b] = self.colors[*color].rgb;
+                        string.push_str(&format!("\x1b[48;2;{r};{g};{b}m  \x1b[0m"));
+                    }
+                    None => string.push_str("  "),
+                }
+            }
+            string.push('\n');
+        }
+        string
+    }
+}
+
+/// Every cell is shown as the symbol of its color, or `.` for the background.
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.cells.rows() {
+            let row = row
+                .map(|cell| cell.map_or('.', |color| self.colors[color].symbol))
+                .collect::<String>();
+            writeln!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+pub fn solve(board: &Board) -> Option<Solution> {
+    search::solve(State::new(board)).map(State::into_solution)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    search::count_solutions(State::new(board), limit)
+}
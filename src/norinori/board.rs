@@ -0,0 +1,124 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{ensure, read_to_string, Result},
+    grid::Grid,
+    location::Location,
+    puzzlink::{self, PuzzLink},
+    region,
+};
+
+/// A norinori puzzle: a grid divided into regions. Shade exactly two cells in each region such that every shaded
+/// cell is orthogonally adjacent to exactly one other shaded cell, so that the shaded cells form dominoes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    regions: Grid<usize>,
+    region_cells: Vec<Vec<Location>>,
+}
+
+impl Board {
+    pub fn new(regions: Grid<usize>, num_regions: usize) -> Result<Self> {
+        let dim = regions.dim();
+        ensure!(
+            dim.0 > 0 && dim.1 > 0,
+            Invalid,
+            "Norinori grids must have at least one cell."
+        );
+        let mut region_cells = vec![vec![]; num_regions];
+        for loc in Location::grid_iter(dim) {
+            let region = regions[loc];
+            ensure!(
+                region < num_regions,
+                Invalid,
+                "Cell {loc} is in region {region}, but there are only {num_regions} regions."
+            );
+            region_cells[region].push(loc);
+        }
+        for (region, cells) in region_cells.iter().enumerate() {
+            ensure!(
+                cells.len() >= 2,
+                Invalid,
+                "Region {region} has {} cells, but needs two shaded cells.",
+                cells.len()
+            );
+        }
+        Ok(Self {
+            regions,
+            region_cells,
+        })
+    }
+
+    /// Parses a grid of characters where each distinct character is a region.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let (regions, num_regions) = region::parse_regions(&rows)?;
+        Self::new(regions, num_regions)
+    }
+
+    /// Imports a puzzle from a puzz.link or pzv.jp URL.
+    pub fn from_puzz_link(url: &str) -> Result<Self> {
+        let link = PuzzLink::parse(url)?;
+        link.expect_kind(&["norinori"])?;
+        let (regions, num_regions, _) =
+            puzzlink::decode_rooms(&link.body, link.width, link.height)?;
+        Self::new(regions, num_regions)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("norinori", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.regions.dim()
+    }
+
+    pub fn region(&self, location: Location) -> usize {
+        self.regions[location]
+    }
+
+    /// The cells of each region.
+    pub fn regions(&self) -> &[Vec<Location>] {
+        &self.region_cells
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const NAMES: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+        for row in self.regions.rows() {
+            let row = row
+                .map(|&region| NAMES[region % NAMES.len()] as char)
+                .collect::<String>();
+            writeln!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_text_and_puzz_links() {
+        let board = Board::parse(include_str!("../../data/norinori/puzzles/eight01.txt")).unwrap();
+        assert_eq!(board.dim(), (8, 8));
+        assert_eq!(board.regions().len(), 14);
+        assert_eq!(
+            board.region(Location::new(7, 0)),
+            board.region(Location::new(5, 1))
+        );
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        let link = "https://puzz.link/p?norinori/8/8/515eplht5k30su770rm65uc0t";
+        assert_eq!(Board::from_puzz_link(link).unwrap(), board);
+        assert!(Board::from_puzz_link("https://puzz.link/p?norinori/8/8/515e").is_err());
+        assert!(Board::parse("AB\nAA\n").is_err());
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{
+    location::Location,
+    search::{self, Contradiction, SearchState},
+    shading::{Shade, ShadeGrid},
+};
+
+use super::Board;
+
+#[derive(Clone, Debug)]
+struct State<'a> {
+    board: &'a Board,
+    grid: ShadeGrid,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board) -> Self {
+        Self {
+            board,
+            grid: ShadeGrid::new(board.dim()),
+        }
+    }
+
+    /// Every region has exactly two shaded cells.
+    fn apply_regions(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for region in self.board.regions() {
+            changed |= self.grid.apply_count(region, 2)?;
+        }
+        Ok(changed)
+    }
+
+    /// Every shaded cell pairs up with exactly one adjacent shaded cell. A shaded cell with a partner closes off
+    /// its other neighbours, one with a single way left to find a partner takes it, and an undecided cell that
+    /// would have two partners or none is unshaded.
+    fn apply_dominoes(&mut self) -> Result<bool, Contradiction> {
+        let dim = self.grid.dim();
+        let mut changed = false;
+        for loc in Location::grid_iter(dim) {
+            let adjacents = loc.adjacents(dim).into_iter().flatten().collect::<Vec<_>>();
+            let with = |shade| {
+                adjacents
+                    .iter()
+                    .copied()
+                    .filter(|&adj| self.grid.get(adj) == shade)
+                    .collect::<Vec<_>>()
+            };
+            let (shaded, unknown) = (with(Shade::Shaded), with(Shade::Unknown));
+            match self.grid.get(loc) {
+                Shade::Shaded => match (shaded.len(), &unknown[..]) {
+                    (0, []) => return Err(Contradiction),
+                    (0, &[partner]) => changed |= self.grid.set(partner, Shade::Shaded)?,
+                    (0, _) => {}
+                    (1, _) => {
+                        for adj in unknown {
+                            changed |= self.grid.set(adj, Shade::Unshaded)?;
+                        }
+                    }
+                    _ => return Err(Contradiction),
+                },
+                Shade::Unknown if shaded.len() > 1 || shaded.len() + unknown.len() == 0 => {
+                    changed |= self.grid.set(loc, Shade::Unshaded)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(changed)
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.apply_regions()?;
+            changed |= self.apply_dominoes()?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(location) = self.grid.first_unknown() else {
+            return vec![];
+        };
+        self.grid
+            .branch_on(location)
+            .into_iter()
+            .map(|grid| Self {
+                grid,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+/// Returns the solved grid with shaded cells as `#` and unshaded cells as `.`, or `None` if the puzzle has no solution.
+pub fn solve(board: &Board) -> Option<ShadeGrid> {
+    search::solve(State::new(board)).map(|state| state.grid)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    search::count_solutions(State::new(board), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follows_rules(board: &Board, grid: &ShadeGrid) -> bool {
+        let dim = board.dim();
+        let regions = board
+            .regions()
+            .iter()
+            .all(|cells| cells.iter().filter(|&&loc| grid.is_shaded(loc)).count() == 2);
+        let dominoes = Location::grid_iter(dim)
+            .filter(|&loc| grid.is_shaded(loc))
+            .all(|loc| {
+                let shaded = loc
+                    .adjacents(dim)
+                    .into_iter()
+                    .flatten()
+                    .filter(|&adj| grid.is_shaded(adj));
+                shaded.count() == 1
+            });
+        regions && dominoes
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/norinori/puzzles/eight01.txt")).unwrap();
+        let grid = solve(&board).unwrap();
+        assert!(grid.is_complete());
+        assert!(follows_rules(&board, &grid));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
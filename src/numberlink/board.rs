@@ -0,0 +1,169 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+    puzzlink::{self, Clue, PuzzLink},
+};
+
+/// A numberlink puzzle: connect every pair of equal labels with a path through orthogonally adjacent cells such
+/// that paths do not cross and together fill the grid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    endpoints: Grid<Option<char>>,
+    /// The labels in order of first appearance, with the two endpoints of each.
+    pairs: Vec<(char, [Location; 2])>,
+}
+
+impl Board {
+    /// The most pairs a puzzle can have.
+    pub const MAX_PAIRS: usize = 64;
+
+    pub fn new(endpoints: Grid<Option<char>>) -> Result<Self> {
+        let (height, width) = endpoints.dim();
+        ensure!(
+            height > 0 && width > 0,
+            Invalid,
+            "Numberlink grids must have at least one cell."
+        );
+        let mut found: Vec<(char, Vec<Location>)> = vec![];
+        for loc in Location::grid_iter(endpoints.dim()) {
+            let Some(label) = endpoints[loc] else {
+                continue;
+            };
+            match found.iter_mut().find(|(other, _)| *other == label) {
+                Some((_, locations)) => locations.push(loc),
+                None => found.push((label, vec![loc])),
+            }
+        }
+        let pairs = found
+            .into_iter()
+            .map(|(label, locations)| match locations[..] {
+                [first, second] => Ok((label, [first, second])),
+                _ => bail!(
+                    Invalid,
+                    "Label '{label}' must appear exactly twice, but appears {} times.",
+                    locations.len()
+                ),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        ensure!(!pairs.is_empty(), Invalid, "The puzzle has no pairs.");
+        ensure!(
+            pairs.len() <= Self::MAX_PAIRS,
+            Invalid,
+            "Puzzles can have at most {} pairs, but got {}.",
+            Self::MAX_PAIRS,
+            pairs.len()
+        );
+        Ok(Self { endpoints, pairs })
+    }
+
+    /// Parses a grid with `.` for empty cells and any other letter or digit for an endpoint.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        let mut endpoints = Grid::from_elem((height, width), None);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.chars().count() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} characters in row {row_index}. Got '{row}'."
+                );
+            }
+            for (col_index, c) in row.chars().enumerate() {
+                endpoints[Location::new(row_index, col_index)] = match c {
+                    '.' => None,
+                    _ if c.is_ascii_alphanumeric() => Some(c),
+                    _ => bail!(
+                        Parse,
+                        "Invalid character '{c}' at ({row_index}, {col_index})."
+                    ),
+                };
+            }
+        }
+        Self::new(endpoints)
+    }
+
+    /// Imports a puzzle from a puzz.link or pzv.jp URL. Numbers are written as base 36 digits.
+    pub fn from_puzz_link(url: &str) -> Result<Self> {
+        let link = PuzzLink::parse(url)?;
+        link.expect_kind(&["numlin", "numberlink"])?;
+        let clues = puzzlink::decode_number16(&link.body, link.width * link.height)?;
+        let mut endpoints = Grid::from_elem((link.height, link.width), None);
+        for (index, clue) in clues.into_iter().enumerate() {
+            let location = Location::new(index / link.width, index % link.width);
+            endpoints[location] = match clue {
+                None => None,
+                Some(Clue::Number(number)) => Some(
+                    char::from_digit(number, 36)
+                        .ok_or_else(|| Error::Parse(format!("Number {number} is too large.")))?,
+                ),
+                Some(Clue::Unknown) => {
+                    bail!(Parse, "Endpoints without a number are not supported.")
+                }
+            };
+        }
+        Self::new(endpoints)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("numberlink", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.endpoints.dim()
+    }
+
+    pub fn endpoint(&self, location: Location) -> Option<char> {
+        self.endpoints[location]
+    }
+
+    pub fn pairs(&self) -> &[(char, [Location; 2])] {
+        &self.pairs
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.endpoints.rows() {
+            for &endpoint in row {
+                write!(f, "{}", endpoint.unwrap_or('.'))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_text_and_puzz_links() {
+        let board =
+            Board::parse(include_str!("../../data/numberlink/puzzles/seven01.txt")).unwrap();
+        assert_eq!(board.dim(), (7, 7));
+        assert_eq!(board.endpoint(Location::new(0, 1)), Some('1'));
+        assert_eq!(board.pairs().len(), 7);
+        assert_eq!(
+            board.pairs()[0],
+            ('1', [Location::new(0, 1), Location::new(1, 0)])
+        );
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        let link = "https://puzz.link/p?numlin/7/7/g1g233g1n5i4i4j5n7h72g6g6h";
+        assert_eq!(Board::from_puzz_link(link).unwrap(), board);
+        assert!(Board::from_puzz_link("https://puzz.link/p?numlin/2/1/1.").is_err());
+        assert!(Board::parse("a.\n.b\n").is_err());
+        assert!(Board::parse("a.\n.#\n").is_err());
+    }
+}
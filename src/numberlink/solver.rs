@@ -0,0 +1,294 @@
+use std::fmt::Display;
+
+use crate::{
+    grid::Grid,
+    location::{Direction, Location},
+    loop_grid::LoopGrid,
+    search::{self, Contradiction, SearchState},
+};
+
+use super::Board;
+
+/// The paths of a solved puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    labels: Grid<char>,
+    grid: LoopGrid,
+}
+
+impl Solution {
+    /// The label of the path through the cell at `location`.
+    pub fn label(&self, location: Location) -> char {
+        self.labels[location]
+    }
+
+    /// Whether the path through the cell at `location` leaves it in `direction`.
+    pub fn line(&self, location: Location, direction: Direction) -> bool {
+        self.grid.line(location, direction) == Some(true)
+    }
+}
+
+/// Every cell is shown by the label of its path, with the paths drawn with `-` and `|` between the cells.
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rendered = self.grid.render(|loc| self.label(loc));
+        write!(f, "{rendered}")
+    }
+}
+
+/// A bit for each pair whose path may pass through a cell.
+type Pairs = u64;
+
+#[derive(Clone)]
+struct State<'a> {
+    board: &'a Board,
+    pairs: Grid<Pairs>,
+    grid: LoopGrid,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board) -> Self {
+        let all = Pairs::MAX >> (Pairs::BITS as usize - board.pairs().len());
+        let mut pairs = Grid::from_elem(board.dim(), all);
+        for (index, (_, endpoints)) in board.pairs().iter().enumerate() {
+            for loc in endpoints {
+                pairs[*loc] = 1 << index;
+            }
+        }
+        Self {
+            board,
+            pairs,
+            grid: LoopGrid::new(board.dim()),
+        }
+    }
+
+    fn restrict(&mut self, location: Location, allowed: Pairs) -> Result<bool, Contradiction> {
+        let cell = &mut self.pairs[location];
+        let restricted = *cell & allowed;
+        if restricted == 0 {
+            return Err(Contradiction);
+        }
+        let changed = restricted != *cell;
+        *cell = restricted;
+        Ok(changed)
+    }
+
+    /// Endpoints have one line and every other cell has two.
+    fn apply_degrees(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for loc in Location::grid_iter(self.grid.dim()) {
+            let needed = if self.board.endpoint(loc).is_some() {
+                1
+            } else {
+                2
+            };
+            let lines = self.grid.lines(loc);
+            let undecided = self.grid.undecided(loc).collect::<Vec<_>>();
+            if lines > needed || lines + undecided.len() < needed {
+                return Err(Contradiction);
+            }
+            if lines == needed || lines + undecided.len() == needed {
+                for direction in undecided {
+                    changed |= self.grid.set_line(loc, direction, lines < needed)?;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Cells joined by a line belong to the same pair, and cells that share no pair cannot be joined.
+    fn apply_pairs(&mut self) -> Result<bool, Contradiction> {
+        let dim = self.grid.dim();
+        let mut changed = false;
+        for loc in Location::grid_iter(dim) {
+            for direction in [Direction::Right, Direction::Down] {
+                let Some(other) = loc.step(direction, dim) else {
+                    continue;
+                };
+                let shared = self.pairs[loc] & self.pairs[other];
+                match self.grid.line(loc, direction) {
+                    Some(true) => {
+                        changed |= self.restrict(loc, shared)?;
+                        changed |= self.restrict(other, shared)?;
+                    }
+                    None if shared == 0 => {
+                        changed |= self.grid.set_line(loc, direction, false)?;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Fails on closed loops and leaves out every edge that would close one.
+    fn apply_no_loops(&mut self) -> Result<bool, Contradiction> {
+        let dim = self.grid.dim();
+        let mut chains = Grid::from_elem(dim, usize::MAX);
+        let mut changed = false;
+        for start in Location::grid_iter(dim) {
+            if chains[start] != usize::MAX {
+                continue;
+            }
+            let chain = start.row * dim.1 + start.col;
+            chains[start] = chain;
+            let mut stack = vec![start];
+            let mut closed = true;
+            while let Some(loc) = stack.pop() {
+                closed &= self.grid.lines(loc) == 2 && self.board.endpoint(loc).is_none();
+                for direction in Direction::ALL {
+                    if self.grid.line(loc, direction) != Some(true) {
+                        continue;
+                    }
+                    let next = loc.step(direction, dim).expect("Lines stay in the grid.");
+                    if chains[next] == usize::MAX {
+                        chains[next] = chain;
+                        stack.push(next);
+                    }
+                }
+            }
+            if closed {
+                return Err(Contradiction);
+            }
+        }
+        for loc in Location::grid_iter(dim) {
+            for direction in [Direction::Right, Direction::Down] {
+                let Some(other) = loc.step(direction, dim) else {
+                    continue;
+                };
+                if self.grid.line(loc, direction).is_none() && chains[loc] == chains[other] {
+                    changed |= self.grid.set_line(loc, direction, false)?;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Removes each pair from the cells its path can no longer reach, failing if its endpoints cannot be joined.
+    fn apply_reachability(&mut self) -> Result<bool, Contradiction> {
+        let dim = self.grid.dim();
+        let mut changed = false;
+        for (index, (_, [start, end])) in self.board.pairs().iter().enumerate() {
+            let bit = 1 << index;
+            let mut reached = Grid::from_elem(dim, false);
+            reached[*start] = true;
+            let mut stack = vec![*start];
+            while let Some(loc) = stack.pop() {
+                for direction in Direction::ALL {
+                    let Some(next) = loc.step(direction, dim) else {
+                        continue;
+                    };
+                    if !reached[next]
+                        && self.grid.line(loc, direction) != Some(false)
+                        && self.pairs[next] & bit != 0
+                    {
+                        reached[next] = true;
+                        stack.push(next);
+                    }
+                }
+            }
+            if !reached[*end] {
+                return Err(Contradiction);
+            }
+            for loc in Location::grid_iter(dim) {
+                if !reached[loc] {
+                    changed |= self.restrict(loc, !bit)?;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    fn into_solution(self) -> Solution {
+        let pairs = self.board.pairs();
+        Solution {
+            labels: self
+                .pairs
+                .map(|&bits| pairs[bits.trailing_zeros() as usize].0),
+            grid: self.grid,
+        }
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.apply_degrees()?;
+            changed |= self.apply_pairs()?;
+            changed |= self.apply_no_loops()?;
+            changed |= self.apply_reachability()?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some((location, direction)) = self.grid.branch_edge() else {
+            return vec![];
+        };
+        self.grid
+            .branch_on(location, direction)
+            .into_iter()
+            .map(|grid| Self {
+                grid,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+pub fn solve(board: &Board) -> Option<Solution> {
+    search::solve(State::new(board)).map(State::into_solution)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    search::count_solutions(State::new(board), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follows_rules(board: &Board, solution: &Solution) -> bool {
+        let dim = board.dim();
+        let lines = |loc: Location| {
+            Direction::ALL
+                .into_iter()
+                .filter(move |&direction| solution.line(loc, direction))
+        };
+        let degrees = Location::grid_iter(dim)
+            .all(|loc| lines(loc).count() == if board.endpoint(loc).is_some() { 1 } else { 2 });
+        let mut visited = 0;
+        for &(label, [start, end]) in board.pairs() {
+            let (mut previous, mut current) = (None, start);
+            loop {
+                if solution.label(current) != label {
+                    return false;
+                }
+                visited += 1;
+                if current == end {
+                    break;
+                }
+                let Some(direction) =
+                    lines(current).find(|&direction| Some(direction.opposite()) != previous)
+                else {
+                    return false;
+                };
+                previous = Some(direction);
+                current = current.step(direction, dim).unwrap();
+            }
+        }
+        // Without loops apart from the paths, the paths visit every cell.
+        degrees && visited == dim.0 * dim.1
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board =
+            Board::parse(include_str!("../../data/numberlink/puzzles/seven01.txt")).unwrap();
+        let solution = solve(&board).unwrap();
+        assert!(follows_rules(&board, &solution));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
@@ -0,0 +1,225 @@
+use std::collections::VecDeque;
+
+use crate::{
+    grid::Grid,
+    location::Location,
+    region::{self, Components},
+    search::{self, Contradiction, SearchState},
+    shading::{Shade, ShadeGrid},
+};
+
+use super::Board;
+
+/// Sea cells are shaded and island cells are unshaded.
+#[derive(Clone, Debug)]
+struct State<'a> {
+    board: &'a Board,
+    grid: ShadeGrid,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board) -> Self {
+        let mut grid = ShadeGrid::new(board.dim());
+        for loc in Location::grid_iter(board.dim()) {
+            if board.number(loc).is_some() {
+                grid.set(loc, Shade::Unshaded)
+                    .expect("Only numbered cells have been set.");
+            }
+        }
+        Self { board, grid }
+    }
+
+    /// The islands of the grid together with the number of each island, failing if an island has more than one.
+    fn islands(&self) -> Result<(Components, Vec<Option<u32>>), Contradiction> {
+        let islands =
+            region::components(self.grid.dim(), |loc| self.grid.get(loc) == Shade::Unshaded);
+        let numbers = islands
+            .regions
+            .iter()
+            .map(|island| {
+                let mut numbers = island.iter().filter_map(|&loc| self.board.number(loc));
+                let number = numbers.next();
+                match numbers.next() {
+                    Some(_) => Err(Contradiction),
+                    None => Ok(number),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok((islands, numbers))
+    }
+
+    /// Closes off complete islands, extends unfinished islands with only one way to grow,
+    /// and keeps numbered islands from merging.
+    fn apply_islands(&mut self) -> Result<bool, Contradiction> {
+        let dim = self.grid.dim();
+        let (islands, numbers) = self.islands()?;
+        let mut changed = false;
+        for (island, &number) in islands.regions.iter().zip(numbers.iter()) {
+            let open = region::boundary(dim, island)
+                .into_iter()
+                .filter(|&loc| self.grid.get(loc) == Shade::Unknown)
+                .collect::<Vec<_>>();
+            let size = island.len() as u32;
+            match number {
+                Some(number) if size > number => return Err(Contradiction),
+                Some(number) if size == number => {
+                    for loc in open {
+                        changed |= self.grid.set(loc, Shade::Shaded)?;
+                    }
+                }
+                _ if open.is_empty() => return Err(Contradiction),
+                _ if open.len() == 1 => {
+                    changed |= self.grid.set(open[0], Shade::Unshaded)?;
+                }
+                _ => {}
+            }
+        }
+        for loc in Location::grid_iter(dim) {
+            if self.grid.get(loc) != Shade::Unknown {
+                continue;
+            }
+            let mut numbered = loc
+                .adjacents(dim)
+                .into_iter()
+                .flatten()
+                .filter_map(|adj| islands.label(adj))
+                .filter(|&label| numbers[label].is_some());
+            if let Some(first) = numbered.next() {
+                if numbered.any(|label| label != first) {
+                    changed |= self.grid.set(loc, Shade::Shaded)?;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Shades every undecided cell that no numbered island can grow far enough to reach.
+    fn apply_reachability(&mut self) -> Result<bool, Contradiction> {
+        let dim = self.grid.dim();
+        let (islands, numbers) = self.islands()?;
+        let mut reachable = Grid::from_elem(dim, false);
+        for (label, island) in islands.regions.iter().enumerate() {
+            let Some(number) = numbers[label] else {
+                continue;
+            };
+            let budget = number as usize - island.len().min(number as usize);
+            // Growing next to another numbered island would merge the two.
+            let touches_other = |loc: Location| {
+                loc.adjacents(dim).into_iter().flatten().any(|adj| {
+                    islands
+                        .label(adj)
+                        .is_some_and(|other| other != label && numbers[other].is_some())
+                })
+            };
+            let mut visited = Grid::from_elem(dim, false);
+            let mut queue = island.iter().map(|&loc| (loc, 0)).collect::<VecDeque<_>>();
+            while let Some((loc, distance)) = queue.pop_front() {
+                if distance >= budget {
+                    continue;
+                }
+                for adj in loc.adjacents(dim).into_iter().flatten() {
+                    if self.grid.get(adj) == Shade::Unknown && !visited[adj] && !touches_other(adj)
+                    {
+                        visited[adj] = true;
+                        reachable[adj] = true;
+                        queue.push_back((adj, distance + 1));
+                    }
+                }
+            }
+        }
+        let mut changed = false;
+        for loc in Location::grid_iter(dim) {
+            if self.grid.get(loc) == Shade::Unknown && !reachable[loc] {
+                changed |= self.grid.set(loc, Shade::Shaded)?;
+            }
+        }
+        Ok(changed)
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.grid.apply_no_pools(Shade::Shaded)?;
+            changed |= self.apply_islands()?;
+            changed |= self.apply_reachability()?;
+            changed |= self.grid.keep_connected(Shade::Shaded)?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        // Prefer growing an island, as that tends to decide the most.
+        let dim = self.grid.dim();
+        let Some(location) = Location::grid_iter(dim)
+            .find(|&loc| {
+                self.grid.get(loc) == Shade::Unknown
+                    && loc
+                        .adjacents(dim)
+                        .into_iter()
+                        .flatten()
+                        .any(|adj| self.grid.get(adj) == Shade::Unshaded)
+            })
+            .or_else(|| self.grid.first_unknown())
+        else {
+            return vec![];
+        };
+        self.grid
+            .branch_on(location)
+            .into_iter()
+            .map(|grid| Self {
+                grid,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+/// Returns the solved grid with sea as `#` and islands as `.`, or `None` if the puzzle has no solution.
+pub fn solve(board: &Board) -> Option<ShadeGrid> {
+    search::solve(State::new(board)).map(|state| state.grid)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    search::count_solutions(State::new(board), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follows_rules(board: &Board, grid: &ShadeGrid) -> bool {
+        let (height, width) = board.dim();
+        let dim = (height, width);
+        let sea = |loc| grid.is_shaded(loc);
+        let no_pools = (1..height).all(|row| {
+            (1..width).all(|col| {
+                !(row - 1..=row)
+                    .flat_map(|row| [col - 1, col].map(|col| Location::new(row, col)))
+                    .all(sea)
+            })
+        });
+        let islands = region::components(dim, |loc| !sea(loc))
+            .regions
+            .into_iter()
+            .all(|island| {
+                let numbers = island
+                    .iter()
+                    .filter_map(|&loc| board.number(loc))
+                    .collect::<Vec<_>>();
+                numbers == [island.len() as u32]
+            });
+        no_pools && islands && region::is_connected(dim, sea, sea)
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/nurikabe/puzzles/seven01.txt")).unwrap();
+        let grid = solve(&board).unwrap();
+        assert!(grid.is_complete());
+        assert!(follows_rules(&board, &grid));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
@@ -0,0 +1,76 @@
+//! Pattern databases: the exact cost of solving a simplified puzzle in which only some of the pieces are told apart,
+//! for every arrangement of those pieces. Looked up during a search, they give lower bounds on the cost of solving
+//! the full puzzle that are far tighter than distance-based estimates.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
+
+#[derive(Clone, Debug)]
+pub struct PatternDatabase<K> {
+    costs: HashMap<K, u32>,
+}
+
+impl<K: Clone + Eq + Hash> PatternDatabase<K> {
+    /// Finds the cheapest way to reach every abstract state from one of `goals`, where `moves` lists the states one
+    /// move away from a state together with the cost of that move, which must be 0 or 1.
+    ///
+    /// Moves must be reversible at the same cost, so that the cost found is also the cost of solving from the state.
+    pub fn build(
+        goals: impl IntoIterator<Item = K>,
+        mut moves: impl FnMut(&K) -> Vec<(K, u32)>,
+    ) -> Self {
+        let mut costs = HashMap::new();
+        let mut queue = VecDeque::new();
+        for goal in goals {
+            costs.insert(goal.clone(), 0);
+            queue.push_back((goal, 0));
+        }
+        while let Some((state, cost)) = queue.pop_front() {
+            if costs[&state] < cost {
+                continue;
+            }
+            for (next, step) in moves(&state) {
+                assert!(step <= 1, "Pattern database moves must cost 0 or 1.");
+                let next_cost = cost + step;
+                if costs.get(&next).is_some_and(|&known| known <= next_cost) {
+                    continue;
+                }
+                costs.insert(next.clone(), next_cost);
+                if step == 0 {
+                    queue.push_front((next, next_cost));
+                } else {
+                    queue.push_back((next, next_cost));
+                }
+            }
+        }
+        Self { costs }
+    }
+
+    /// Merges abstract states that `project` maps to the same key, keeping the lowest cost.
+    pub fn project<P: Clone + Eq + Hash>(&self, project: impl Fn(&K) -> P) -> PatternDatabase<P> {
+        let mut costs = HashMap::new();
+        for (state, &cost) in self.costs.iter() {
+            costs
+                .entry(project(state))
+                .and_modify(|known: &mut u32| *known = (*known).min(cost))
+                .or_insert(cost);
+        }
+        PatternDatabase { costs }
+    }
+
+    /// The cost of solving from `state`, or `None` if the goals cannot be reached from it.
+    pub fn cost(&self, state: &K) -> Option<u32> {
+        self.costs.get(state).copied()
+    }
+
+    /// The number of abstract states from which the goals can be reached.
+    pub fn len(&self) -> usize {
+        self.costs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.costs.is_empty()
+    }
+}
@@ -0,0 +1,158 @@
+//! Polyominoes: orthogonally connected groups of cells, such as the tetrominoes placed in LITS.
+
+use std::{collections::HashSet, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+
+use crate::location::Location;
+
+/// Every orthogonally connected group of `size` cells from `cells`, each sorted in row-major order.
+pub fn placements(dim: (usize, usize), cells: &[Location], size: usize) -> Vec<Vec<Location>> {
+    let key = |group: &[Location]| {
+        let mut key = group
+            .iter()
+            .map(|loc| (loc.row, loc.col))
+            .collect::<Vec<_>>();
+        key.sort_unstable();
+        key
+    };
+    let mut seen = HashSet::new();
+    let mut groups = cells.iter().map(|&loc| vec![loc]).collect::<Vec<_>>();
+    for _ in 1..size {
+        let mut grown = vec![];
+        for group in groups {
+            for loc in group.iter() {
+                for adj in loc.adjacents(dim).into_iter().flatten() {
+                    if !cells.contains(&adj) || group.contains(&adj) {
+                        continue;
+                    }
+                    let mut next = group.clone();
+                    next.push(adj);
+                    if seen.insert(key(&next)) {
+                        grown.push(next);
+                    }
+                }
+            }
+        }
+        groups = grown;
+    }
+    groups
+        .into_iter()
+        .map(|group| {
+            key(&group)
+                .into_iter()
+                .map(|(row, col)| Location::new(row, col))
+                .collect()
+        })
+        .collect()
+}
+
+/// The shape of `cells` moved to the top left corner and put in a canonical rotation and reflection,
+/// so that two groups of cells have the same shape exactly when their normal forms are equal.
+pub fn normal_form(cells: &[Location]) -> Vec<(isize, isize)> {
+    let points = cells
+        .iter()
+        .map(|loc| (loc.row as isize, loc.col as isize))
+        .collect::<Vec<_>>();
+    // Four rotations, each with and without a reflection.
+    let transform = |symmetry: usize, (mut r, mut c): (isize, isize)| {
+        if symmetry >= 4 {
+            c = -c;
+        }
+        for _ in 0..symmetry % 4 {
+            (r, c) = (c, -r);
+        }
+        (r, c)
+    };
+    (0..8)
+        .map(|symmetry| {
+            let moved = points
+                .iter()
+                .map(|&point| transform(symmetry, point))
+                .collect::<Vec<_>>();
+            let min_row = moved.iter().map(|&(r, _)| r).min().unwrap_or(0);
+            let min_col = moved.iter().map(|&(_, c)| c).min().unwrap_or(0);
+            let mut shape = moved
+                .into_iter()
+                .map(|(r, c)| (r - min_row, c - min_col))
+                .collect::<Vec<_>>();
+            shape.sort_unstable();
+            shape
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+/// The five tetrominoes, counting rotations and reflections as the same.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Tetromino {
+    I,
+    L,
+    O,
+    S,
+    T,
+}
+
+impl Tetromino {
+    pub const ALL: [Self; 5] = [Self::I, Self::L, Self::O, Self::S, Self::T];
+
+    /// The cells of the tetromino in one orientation.
+    fn cells(self) -> [Location; 4] {
+        let cells = match self {
+            Self::I => [(0, 0), (0, 1), (0, 2), (0, 3)],
+            Self::L => [(0, 0), (1, 0), (2, 0), (2, 1)],
+            Self::O => [(0, 0), (0, 1), (1, 0), (1, 1)],
+            Self::S => [(0, 1), (0, 2), (1, 0), (1, 1)],
+            Self::T => [(0, 0), (0, 1), (0, 2), (1, 1)],
+        };
+        cells.map(|(row, col)| Location::new(row, col))
+    }
+
+    /// The tetromino formed by `cells`, if they form one.
+    pub fn classify(cells: &[Location]) -> Option<Self> {
+        let shape = normal_form(cells);
+        Self::ALL
+            .into_iter()
+            .find(|tetromino| normal_form(&tetromino.cells()) == shape)
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Self::I => 'I',
+            Self::L => 'L',
+            Self::O => 'O',
+            Self::S => 'S',
+            Self::T => 'T',
+        }
+    }
+}
+
+impl Display for Tetromino {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_tetromino_in_a_square() {
+        let dim = (4, 4);
+        let cells = Location::grid_iter(dim).collect::<Vec<_>>();
+        let placements = placements(dim, &cells, 4);
+        let count = |tetromino| {
+            placements
+                .iter()
+                .filter(|placement| Tetromino::classify(placement) == Some(tetromino))
+                .count()
+        };
+        assert_eq!(count(Tetromino::I), 8);
+        assert_eq!(count(Tetromino::O), 9);
+        assert_eq!(count(Tetromino::L), 48);
+        assert_eq!(count(Tetromino::S), 24);
+        assert_eq!(count(Tetromino::T), 24);
+        assert_eq!(placements.len(), 113);
+    }
+}
@@ -0,0 +1,634 @@
+//! A common interface to the puzzle modules, so that code that only reads, checks, solves and writes puzzles can be
+//! written once for every kind of puzzle.
+
+use std::{borrow::Borrow, fmt::Display};
+
+use rayon::{prelude::*, ThreadPool};
+
+use crate::{
+    difficulty::{Difficulty, Rating},
+    error::{Error, Result},
+    generate::GeneratorConfig,
+};
+
+/// A kind of puzzle. Implemented by a marker type in each puzzle module, such as [`crate::sudoku::Sudoku`].
+///
+/// Only reading and solving are required. The other actions default to the usual text format, or to
+/// [`Error::Unsupported`] for puzzles without a way to do them.
+pub trait Puzzle {
+    /// The name of the puzzle, as used in messages.
+    const NAME: &'static str;
+
+    /// An unsolved puzzle. Instances are shared between threads when solving many at once.
+    type Instance: Display + Sync;
+    type Solution: Display + Send;
+
+    /// Reads an instance from the puzzle's usual text format.
+    fn parse(input: &str) -> Result<Self::Instance>;
+
+    /// Splits input holding several instances into the text of each, such as when reading many puzzles from stdin.
+    /// By default, instances are separated by lines of `---`.
+    fn split(input: &str) -> Vec<&str> {
+        split_blocks(input)
+    }
+
+    /// Reads an instance from a URL of an online puzzle editor, such as puzz.link.
+    fn parse_url(_url: &str) -> Result<Self::Instance> {
+        Err(unsupported::<Self>("Reading URLs"))
+    }
+
+    /// Fails if the instance breaks the rules of the puzzle, before any solving.
+    fn validate(_instance: &Self::Instance) -> Result<()> {
+        Ok(())
+    }
+
+    /// Returns `None` if the instance has no solution.
+    fn solve(instance: &Self::Instance) -> Result<Option<Self::Solution>>;
+
+    /// Like [`Puzzle::solve`], but may keep buffers in `scratch` for the next solve. By default, ignores it.
+    fn solve_reusing(
+        instance: &Self::Instance,
+        _scratch: &mut SolverScratch,
+    ) -> Result<Option<Self::Solution>> {
+        Self::solve(instance)
+    }
+
+    /// How hard the instance is for the solver, where higher is harder. Only comparable between instances of the
+    /// same puzzle.
+    fn rate(_instance: &Self::Instance) -> Result<u64> {
+        Err(unsupported::<Self>("Rating"))
+    }
+
+    /// Grades a score from [`Puzzle::rate`] on the difficulty scale shared by every puzzle. The default suits scores
+    /// that count the guesses the solver makes.
+    fn grade(score: u64) -> Difficulty {
+        Difficulty::grade(score, [1, 5, 100])
+    }
+
+    /// Rates the instance and grades the score.
+    fn rating(instance: &Self::Instance) -> Result<Rating> {
+        let score = Self::rate(instance)?;
+        Ok(Rating {
+            score,
+            difficulty: Self::grade(score),
+        })
+    }
+
+    /// Counts the solutions of the instance, stopping once `limit` have been found, such as for checking that a
+    /// puzzle has exactly one.
+    fn count_solutions(_instance: &Self::Instance, _limit: usize) -> Result<usize> {
+        Err(unsupported::<Self>("Counting solutions"))
+    }
+
+    /// Generates an instance that fits the config. Puzzles that implement [`Generator`](crate::generate::Generator)
+    /// can do this by [`generate`](crate::generate::generate).
+    fn generate(_config: &GeneratorConfig) -> Result<Self::Instance> {
+        Err(unsupported::<Self>("Generating"))
+    }
+
+    /// Generates `count` instances that fit the config, each with the seed that [`Puzzle::generate`] gives it back
+    /// for. By default, generates one from each seed counting up from the config's, one after another. Puzzles that
+    /// implement [`Generator`](crate::generate::Generator) can do this in parallel by
+    /// [`generate_many`](crate::generate::generate_many).
+    fn generate_many(config: &GeneratorConfig, count: usize) -> Result<Vec<(u64, Self::Instance)>> {
+        (0..count as u64)
+            .map(|offset| {
+                let config = GeneratorConfig {
+                    seed: config.seed.wrapping_add(offset),
+                    ..*config
+                };
+                Ok((config.seed, Self::generate(&config)?))
+            })
+            .collect()
+    }
+
+    /// Writes a solution in the puzzle's usual text format.
+    fn format(solution: &Self::Solution) -> String {
+        solution.to_string()
+    }
+
+    /// Writes an instance in the format read by [`Puzzle::parse`].
+    fn format_instance(instance: &Self::Instance) -> String {
+        instance.to_string()
+    }
+
+    /// Writes an instance in a form shared by every instance that is the same puzzle written another way, such as
+    /// rotated or reflected, so that duplicates can be found. By default, the same as [`Puzzle::format_instance`].
+    fn isomorphic_form(instance: &Self::Instance) -> String {
+        Self::format_instance(instance)
+    }
+
+    /// The formats instances can be converted between. The first is the text format of [`Puzzle::parse`], and by
+    /// default the only other is puzz.link URLs, which can only be read.
+    fn formats() -> Vec<Format<Self::Instance>> {
+        vec![
+            Format::new("text", "txt", Self::parse, |instance| {
+                Ok(Self::format_instance(instance))
+            }),
+            Format::new("puzz.link", "txt", Self::parse_url, |_| {
+                Err(unsupported::<Self>("Writing puzz.link URLs"))
+            })
+            .single_line(),
+        ]
+    }
+
+    /// Describes how a solution differs from an expected one in the text format, such as a stored golden solution.
+    /// Empty if they are the same. By default, compares the formatted solution line by line.
+    fn diff(expected: &str, solution: &Self::Solution) -> Vec<String> {
+        diff_lines(expected, &Self::format(solution))
+    }
+}
+
+/// A format that instances of a puzzle can be read from and written in, as listed by [`Puzzle::formats`].
+pub struct Format<I> {
+    pub name: &'static str,
+    /// The extension of files in the format, without the dot.
+    pub extension: &'static str,
+    /// Every instance takes a single line, so files hold one instance per line rather than separating them by lines
+    /// of `---`.
+    pub single_line: bool,
+    read: fn(&str) -> Result<I>,
+    write: fn(&I) -> Result<String>,
+}
+
+impl<I> Format<I> {
+    pub fn new(
+        name: &'static str,
+        extension: &'static str,
+        read: fn(&str) -> Result<I>,
+        write: fn(&I) -> Result<String>,
+    ) -> Self {
+        Self {
+            name,
+            extension,
+            single_line: false,
+            read,
+            write,
+        }
+    }
+
+    pub fn single_line(self) -> Self {
+        Self {
+            single_line: true,
+            ..self
+        }
+    }
+
+    pub fn read(&self, input: &str) -> Result<I> {
+        (self.read)(input)
+    }
+
+    pub fn write(&self, instance: &I) -> Result<String> {
+        (self.write)(instance)
+    }
+
+    /// Splits input holding several instances into the text of each.
+    pub fn split<'a>(&self, input: &'a str) -> Vec<&'a str> {
+        if self.single_line {
+            input
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect()
+        } else {
+            split_blocks(input)
+        }
+    }
+
+    /// Joins the written instances into the contents of a file, the reverse of [`Format::split`].
+    pub fn join(&self, texts: &[String]) -> String {
+        let separator = if self.single_line { "\n" } else { "\n---\n" };
+        let texts = texts
+            .iter()
+            .map(|text| text.trim_end_matches('\n'))
+            .collect::<Vec<_>>();
+        texts.join(separator) + "\n"
+    }
+}
+
+/// Finds a format of the puzzle by name.
+pub fn find_format<P: Puzzle>(name: &str) -> Result<Format<P::Instance>> {
+    let mut formats = P::formats();
+    let Some(index) = formats.iter().position(|format| format.name == name) else {
+        let names = formats.iter().map(|format| format.name).collect::<Vec<_>>();
+        return Err(Error::Parse(format!(
+            "Unknown format '{name}' for {}s. Expected one of {}.",
+            P::NAME,
+            names.join(", ")
+        )));
+    };
+    Ok(formats.swap_remove(index))
+}
+
+/// Splits input into the blocks between lines of `---`, skipping blocks that are only whitespace.
+pub fn split_blocks(input: &str) -> Vec<&str> {
+    let mut blocks = vec![];
+    let mut start = 0;
+    let mut offset = 0;
+    for line in input.split_inclusive('\n') {
+        if line.trim() == "---" {
+            blocks.push(&input[start..offset]);
+            start = offset + line.len();
+        }
+        offset += line.len();
+    }
+    blocks.push(&input[start..]);
+    blocks.retain(|block| !block.trim().is_empty());
+    blocks
+}
+
+/// Compares two texts line by line, ignoring trailing whitespace, and describes every line that differs.
+pub fn diff_lines(expected: &str, actual: &str) -> Vec<String> {
+    fn lines(text: &str) -> Vec<&str> {
+        text.trim_end().lines().map(str::trim_end).collect()
+    }
+    let (expected, actual) = (lines(expected), lines(actual));
+    let describe =
+        |line: Option<&&str>| line.map_or("nothing".to_string(), |line| format!("'{line}'"));
+    (0..expected.len().max(actual.len()))
+        .filter(|&index| expected.get(index) != actual.get(index))
+        .map(|index| {
+            format!(
+                "Line {}: expected {}, found {}.",
+                index + 1,
+                describe(expected.get(index)),
+                describe(actual.get(index))
+            )
+        })
+        .collect()
+}
+
+fn unsupported<P: Puzzle + ?Sized>(action: &'static str) -> Error {
+    Error::Unsupported {
+        puzzle: P::NAME,
+        action,
+    }
+}
+
+/// Validates and solves an instance, failing if it has no solution.
+pub fn solve_valid<P: Puzzle>(instance: &P::Instance) -> Result<P::Solution> {
+    P::validate(instance)?;
+    match P::solve(instance)? {
+        Some(solution) => Ok(solution),
+        None => Err(Error::NoSolution(P::NAME)),
+    }
+}
+
+/// Parses, validates and solves a puzzle, returning the formatted solution.
+pub fn solve_str<P: Puzzle>(input: &str) -> Result<String> {
+    let instance = P::parse(input)?;
+    Ok(P::format(&solve_valid::<P>(&instance)?))
+}
+
+/// Code that is generic over the kind of puzzle, for choosing the kind at runtime with [`visit`].
+pub trait Visitor {
+    type Output;
+
+    fn visit<P: Puzzle>(self) -> Self::Output;
+}
+
+/// The name of every kind of puzzle [`visit`] knows.
+pub const KINDS: [&str; 43] = [
+    "akari",
+    "aquarium",
+    "battleship",
+    "binairo",
+    "camping",
+    "cave",
+    "crossword-fill",
+    "cryptarithm",
+    "dominosa",
+    "fillapix",
+    "fillomino",
+    "futoshiki",
+    "galaxies",
+    "hashi",
+    "heyawake",
+    "hidato",
+    "hitori",
+    "inshi",
+    "kakurasu",
+    "kenken",
+    "kuromasu",
+    "lits",
+    "magnets",
+    "masyu",
+    "minesweeper",
+    "nonogram",
+    "norinori",
+    "numberlink",
+    "nurikabe",
+    "queens",
+    "ripple-effect",
+    "shakashaka",
+    "shikaku",
+    "skyscrapers",
+    "sliding",
+    "sokoban",
+    "star-battle",
+    "sudoku",
+    "suguru",
+    "tapa",
+    "tracks",
+    "wordsearch",
+    "yajilin",
+];
+
+/// The name [`visit`] knows the kind of puzzle by, such as `ripple-effect`.
+pub fn kind<P: Puzzle>() -> &'static str {
+    struct Name;
+
+    impl Visitor for Name {
+        type Output = &'static str;
+
+        fn visit<P: Puzzle>(self) -> &'static str {
+            P::NAME
+        }
+    }
+
+    KINDS
+        .into_iter()
+        .find(|kind| visit(kind, Name).is_ok_and(|name| name == P::NAME))
+        .expect("Every kind of puzzle is in KINDS.")
+}
+
+/// Runs the visitor with the kind of puzzle of the given name, which is the name of its module in kebab case, such as
+/// `sudoku` or `ripple-effect`. Fails for unknown names.
+pub fn visit<V: Visitor>(kind: &str, visitor: V) -> Result<V::Output> {
+    Ok(match kind {
+        "akari" => visitor.visit::<crate::akari::Akari>(),
+        "aquarium" => visitor.visit::<crate::aquarium::Aquarium>(),
+        "battleship" => visitor.visit::<crate::battleship::Battleship>(),
+        "binairo" => visitor.visit::<crate::binairo::Binairo>(),
+        "camping" => visitor.visit::<crate::camping::Camping>(),
+        "cave" => visitor.visit::<crate::cave::Cave>(),
+        "crossword-fill" => visitor.visit::<crate::crossword_fill::CrosswordFill>(),
+        "cryptarithm" => visitor.visit::<crate::cryptarithm::Cryptarithm>(),
+        "dominosa" => visitor.visit::<crate::dominosa::Dominosa>(),
+        "fillapix" => visitor.visit::<crate::fillapix::Fillapix>(),
+        "fillomino" => visitor.visit::<crate::fillomino::Fillomino>(),
+        "futoshiki" => visitor.visit::<crate::futoshiki::Futoshiki>(),
+        "galaxies" => visitor.visit::<crate::galaxies::Galaxies>(),
+        "hashi" => visitor.visit::<crate::hashi::Hashi>(),
+        "heyawake" => visitor.visit::<crate::heyawake::Heyawake>(),
+        "hidato" => visitor.visit::<crate::hidato::Hidato>(),
+        "hitori" => visitor.visit::<crate::hitori::Hitori>(),
+        "inshi" => visitor.visit::<crate::inshi::Inshi>(),
+        "kakurasu" => visitor.visit::<crate::kakurasu::Kakurasu>(),
+        "kenken" => visitor.visit::<crate::kenken::Kenken>(),
+        "kuromasu" => visitor.visit::<crate::kuromasu::Kuromasu>(),
+        "lits" => visitor.visit::<crate::lits::Lits>(),
+        "magnets" => visitor.visit::<crate::magnets::Magnets>(),
+        "masyu" => visitor.visit::<crate::masyu::Masyu>(),
+        "minesweeper" => visitor.visit::<crate::minesweeper::Minesweeper>(),
+        "nonogram" => visitor.visit::<crate::nonogram::Nonogram>(),
+        "norinori" => visitor.visit::<crate::norinori::Norinori>(),
+        "numberlink" => visitor.visit::<crate::numberlink::Numberlink>(),
+        "nurikabe" => visitor.visit::<crate::nurikabe::Nurikabe>(),
+        "queens" => visitor.visit::<crate::queens::Queens>(),
+        "ripple-effect" => visitor.visit::<crate::rippleeffect::RippleEffect>(),
+        "shakashaka" => visitor.visit::<crate::shakashaka::Shakashaka>(),
+        "shikaku" => visitor.visit::<crate::shikaku::Shikaku>(),
+        "skyscrapers" => visitor.visit::<crate::skyscrapers::Skyscrapers>(),
+        "sliding" => visitor.visit::<crate::sliding::Sliding>(),
+        "sokoban" => visitor.visit::<crate::sokoban::Sokoban>(),
+        "star-battle" => visitor.visit::<crate::starbattle::StarBattle>(),
+        "sudoku" => visitor.visit::<crate::sudoku::Sudoku>(),
+        "suguru" => visitor.visit::<crate::suguru::Suguru>(),
+        "tapa" => visitor.visit::<crate::tapa::Tapa>(),
+        "tracks" => visitor.visit::<crate::tracks::Tracks>(),
+        "wordsearch" => visitor.visit::<crate::wordsearch::Wordsearch>(),
+        "yajilin" => visitor.visit::<crate::yajilin::Yajilin>(),
+        _ => return Err(Error::Parse(format!("Unknown kind of puzzle '{kind}'."))),
+    })
+}
+
+/// Buffers that solvers keep between solves, so that solving many instances in a row allocates less. Only used by the
+/// puzzles that override [`Puzzle::solve_reusing`].
+#[derive(Default)]
+pub struct SolverScratch {
+    pub(crate) sudoku: crate::sudoku::Scratch,
+    pub(crate) camping: crate::camping::Scratch,
+}
+
+/// Solves many instances in parallel, returning the results in the same order. Runs on `pool` if given, and
+/// otherwise on the current thread pool, which is rayon's global one unless called from [`ThreadPool::install`].
+/// Each thread reuses a [`SolverScratch`] for the instances it solves.
+pub fn solve_all<P, I>(
+    instances: &[I],
+    pool: Option<&ThreadPool>,
+) -> Vec<Result<Option<P::Solution>>>
+where
+    P: Puzzle,
+    I: Borrow<P::Instance> + Sync,
+{
+    let solve = || {
+        instances
+            .par_iter()
+            .map_init(SolverScratch::default, |scratch, instance| {
+                P::solve_reusing(instance.borrow(), scratch)
+            })
+            .collect()
+    };
+    match pool {
+        Some(pool) => pool.install(solve),
+        None => solve(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{camping::Camping, sudoku::Sudoku};
+
+    #[test]
+    fn splits_blocks() {
+        assert_eq!(split_blocks("a\nb\n---\n\nc\n--- \n"), ["a\nb\n", "\nc\n"]);
+        assert_eq!(split_blocks("a\n\nb"), ["a\n\nb"]);
+        assert!(split_blocks("---\n\n").is_empty());
+    }
+
+    #[test]
+    fn diffs_solutions() {
+        assert!(diff_lines("ab\ncd \n", "ab\ncd").is_empty());
+        assert_eq!(
+            diff_lines("ab\ncd", "ab\nce\nf"),
+            [
+                "Line 2: expected 'cd', found 'ce'.",
+                "Line 3: expected nothing, found 'f'."
+            ]
+        );
+        let line = include_str!("../data/sudoku/grids/easy50.txt")
+            .lines()
+            .next()
+            .unwrap();
+        let solution = solve_valid::<Sudoku>(&Sudoku::parse(line).unwrap()).unwrap();
+        let golden = Sudoku::format(&solution);
+        assert!(Sudoku::diff(&golden, &solution).is_empty());
+        let changed = format!(
+            "{}{}",
+            if golden.starts_with('1') { '2' } else { '1' },
+            &golden[1..]
+        );
+        assert_eq!(Sudoku::diff(&changed, &solution).len(), 1);
+    }
+
+    #[test]
+    fn counts_solutions() {
+        let unique = include_str!("../data/sudoku/grids/easy50.txt")
+            .lines()
+            .next()
+            .unwrap();
+        let blank = include_str!("../data/sudoku/grids/blank.txt");
+        let count = |input: &str| Sudoku::count_solutions(&Sudoku::parse(input).unwrap(), 2);
+        assert_eq!(count(unique).unwrap(), 1);
+        assert_eq!(count(blank).unwrap(), 2);
+        let map =
+            crate::camping::Map::parse(include_str!("../data/camping/maps/map01.txt")).unwrap();
+        assert_eq!(Camping::count_solutions(&map, 2).unwrap(), 1);
+        let stars = include_str!("../data/starbattle/puzzles/six01.txt");
+        let stars = crate::starbattle::StarBattle::parse(stars).unwrap();
+        assert_eq!(
+            crate::starbattle::StarBattle::count_solutions(&stars, 2).unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn converts_between_formats() {
+        fn convert<P: Puzzle>(text: &str, from: &str, to: &str) -> String {
+            let from = find_format::<P>(from).unwrap();
+            let to = find_format::<P>(to).unwrap();
+            let texts = from
+                .split(text)
+                .into_iter()
+                .map(|part| to.write(&from.read(part).unwrap()).unwrap())
+                .collect::<Vec<_>>();
+            to.join(&texts)
+        }
+
+        let lines = include_str!("../data/sudoku/grids/easy50.txt")
+            .lines()
+            .take(2)
+            .map(|line| format!("{line}\n"))
+            .collect::<String>();
+        let json = convert::<Sudoku>(&lines, "line", "json");
+        let sdk = convert::<Sudoku>(&json, "json", "sdk");
+        assert_eq!(convert::<Sudoku>(&sdk, "sdk", "line"), lines);
+        assert!(find_format::<Sudoku>("pdf").is_err());
+
+        let text = include_str!("../data/camping/maps/map01.txt");
+        let json = convert::<Camping>(text, "text", "json");
+        assert_eq!(
+            Camping::parse(&convert::<Camping>(&json, "json", "text")).unwrap(),
+            Camping::parse(text).unwrap()
+        );
+        assert!(find_format::<Camping>("puzz.link").is_err());
+        assert!(matches!(
+            Camping::parse_url("https://puzz.link/p?tents/6/6/"),
+            Err(Error::Unsupported { .. })
+        ));
+    }
+
+    #[test]
+    fn reuses_scratch_between_instances() {
+        let mut scratch = SolverScratch::default();
+        for line in include_str!("../data/sudoku/grids/qqwing_expert.txt")
+            .lines()
+            .take(3)
+        {
+            let board = Sudoku::parse(line).unwrap();
+            let solution = Sudoku::solve_reusing(&board, &mut scratch)
+                .unwrap()
+                .unwrap();
+            assert_eq!(
+                Sudoku::format(&solution),
+                Sudoku::format(&Sudoku::solve(&board).unwrap().unwrap())
+            );
+        }
+        for text in [
+            include_str!("../data/camping/maps/map01.txt"),
+            include_str!("../data/camping/maps/map02.txt"),
+        ] {
+            let map = Camping::parse(text).unwrap();
+            assert_eq!(
+                Camping::solve_reusing(&map, &mut scratch).unwrap(),
+                Camping::solve(&map).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn visits_by_name() {
+        struct Name;
+
+        impl Visitor for Name {
+            type Output = &'static str;
+
+            fn visit<P: Puzzle>(self) -> &'static str {
+                P::NAME
+            }
+        }
+
+        assert_eq!(visit("sudoku", Name).unwrap(), Sudoku::NAME);
+        assert_eq!(
+            visit("ripple-effect", Name).unwrap(),
+            crate::rippleeffect::RippleEffect::NAME
+        );
+        assert!(matches!(visit("chess", Name), Err(Error::Parse(_))));
+        for kind in KINDS {
+            assert!(visit(kind, Name).is_ok(), "Cannot visit {kind}.");
+        }
+        assert_eq!(kind::<crate::rippleeffect::RippleEffect>(), "ripple-effect");
+        assert_eq!(kind::<crate::starbattle::StarBattle>(), "star-battle");
+    }
+
+    #[test]
+    fn solves_sudoku_lines() {
+        let line = include_str!("../data/sudoku/grids/top95.txt")
+            .lines()
+            .next()
+            .unwrap();
+        let solution = solve_str::<Sudoku>(line).unwrap();
+        assert_eq!(solution.len(), 81);
+        assert!(!solution.contains('.'));
+        let duplicate = format!("11{}", &line[2..]);
+        assert!(solve_str::<Sudoku>(&duplicate).is_err());
+    }
+
+    #[test]
+    fn splits_lines() {
+        let lines = include_str!("../data/sudoku/grids/easy50.txt");
+        let boards = Sudoku::split(lines)
+            .into_iter()
+            .map(|line| Sudoku::parse(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(boards.len(), lines.lines().count());
+    }
+
+    #[test]
+    fn solve_all_uses_pool() {
+        let boards = include_str!("../data/sudoku/grids/easy50.txt")
+            .lines()
+            .take(8)
+            .map(|line| Sudoku::parse(line).unwrap())
+            .collect::<Vec<_>>();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let solutions = solve_all::<Sudoku, _>(&boards, Some(&pool));
+        assert_eq!(solutions.len(), boards.len());
+        assert!(solutions
+            .iter()
+            .all(|solution| solution.as_ref().unwrap().is_some()));
+    }
+
+    #[test]
+    fn generate_unsupported() {
+        assert!(matches!(
+            crate::akari::Akari::generate(&GeneratorConfig::default()),
+            Err(Error::Unsupported { .. })
+        ));
+    }
+}
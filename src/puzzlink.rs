@@ -0,0 +1,348 @@
+//! Decoding of puzzle URLs from puzz.link and pzv.jp, which share the pzprjs encoding.
+
+use thiserror::Error;
+
+use crate::{
+    grid::Grid,
+    location::{Direction, Location},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum Error {
+    #[error("The URL has no {0}.")]
+    Missing(&'static str),
+    #[error("Invalid {0} in URL.")]
+    InvalidDimension(&'static str),
+    #[error("Expected a {expected} URL, but got a '{kind}' URL.")]
+    WrongKind { expected: String, kind: String },
+    #[error("The URL ends in the middle of a number.")]
+    UnexpectedEnd,
+    #[error("Invalid number '{0}'.")]
+    InvalidNumber(String),
+    #[error("Invalid arrow in the URL.")]
+    InvalidArrow,
+    #[error("Unexpected character '{0}' in the URL.")]
+    UnexpectedCharacter(char),
+    #[error("The URL has {found} cells, but the puzzle has {expected}.")]
+    CellCount { found: usize, expected: usize },
+    #[error("The URL needs {expected} characters of borders, but has {found}.")]
+    Borders { found: usize, expected: usize },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The parts of a puzzle URL such as `https://puzz.link/p?hashi/7/7/4g3h...`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PuzzLink {
+    pub kind: String,
+    pub width: usize,
+    pub height: usize,
+    pub body: String,
+}
+
+impl PuzzLink {
+    pub fn parse(url: &str) -> Result<Self> {
+        let query = url.split_once('?').map_or(url, |(_, query)| query).trim();
+        let mut parts = query.split('/');
+        let kind = parts.next().ok_or(Error::Missing("puzzle type"))?;
+        // Some puzzle types put flags such as `v:` before the dimensions.
+        let mut parts = parts.skip_while(|part| part.ends_with(':'));
+        let width = parts
+            .next()
+            .ok_or(Error::Missing("width"))?
+            .parse()
+            .map_err(|_| Error::InvalidDimension("width"))?;
+        let height = parts
+            .next()
+            .ok_or(Error::Missing("height"))?
+            .parse()
+            .map_err(|_| Error::InvalidDimension("height"))?;
+        let body = parts.collect::<Vec<_>>().join("/");
+        Ok(Self {
+            kind: kind.to_string(),
+            width,
+            height,
+            body,
+        })
+    }
+
+    /// Fails unless the puzzle type is one of `kinds`.
+    pub fn expect_kind(&self, kinds: &[&str]) -> Result<()> {
+        if kinds.contains(&self.kind.as_str()) {
+            Ok(())
+        } else {
+            Err(Error::WrongKind {
+                expected: kinds.join(" or "),
+                kind: self.kind.clone(),
+            })
+        }
+    }
+}
+
+/// A number clue in a cell, where the number may be hidden.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Clue {
+    Number(u32),
+    Unknown,
+}
+
+/// Decodes the common encoding of one optional number per cell, in row-major order.
+pub fn decode_number16(body: &str, num_cells: usize) -> Result<Vec<Option<Clue>>> {
+    let mut cells = vec![None; num_cells];
+    let chars = body.chars().collect::<Vec<_>>();
+    let hex = |start: usize, len: usize| -> Result<u32> {
+        let digits = chars
+            .get(start..start + len)
+            .ok_or(Error::UnexpectedEnd)?
+            .iter()
+            .collect::<String>();
+        u32::from_str_radix(&digits, 16).map_err(|_| Error::InvalidNumber(digits))
+    };
+    let mut cell = 0;
+    let mut index = 0;
+    while index < chars.len() && cell < num_cells {
+        let c = chars[index];
+        match c {
+            '0'..='9' | 'a'..='f' => cells[cell] = Some(Clue::Number(hex(index, 1)?)),
+            '-' => {
+                cells[cell] = Some(Clue::Number(hex(index + 1, 2)?));
+                index += 2;
+            }
+            '+' => {
+                cells[cell] = Some(Clue::Number(hex(index + 1, 3)?));
+                index += 3;
+            }
+            '.' => cells[cell] = Some(Clue::Unknown),
+            'g'..='z' => cell += c.to_digit(36).unwrap() as usize - 16,
+            _ => return Err(Error::UnexpectedCharacter(c)),
+        }
+        cell += 1;
+        index += 1;
+    }
+    Ok(cells)
+}
+
+/// Decodes the encoding of up to two kinds of circle per cell, three cells to a base 27 digit.
+/// Each cell is 0 for no circle, 1 for a white circle or 2 for a black circle.
+pub fn decode_circles(body: &str, num_cells: usize) -> Result<Vec<u8>> {
+    let mut cells = Vec::with_capacity(num_cells);
+    for c in body.chars() {
+        let digit = c.to_digit(27).ok_or(Error::UnexpectedCharacter(c))?;
+        for place in [9, 3, 1] {
+            if cells.len() < num_cells {
+                cells.push((digit / place % 3) as u8);
+            }
+        }
+    }
+    if cells.len() != num_cells {
+        return Err(Error::CellCount {
+            found: cells.len(),
+            expected: num_cells,
+        });
+    }
+    Ok(cells)
+}
+
+/// A number clue that may point in a direction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ArrowClue {
+    pub direction: Option<Direction>,
+    pub number: Clue,
+}
+
+/// Decodes the encoding of one optional arrow clue per cell, in row-major order.
+pub fn decode_arrow_numbers(body: &str, num_cells: usize) -> Result<Vec<Option<ArrowClue>>> {
+    let mut cells = vec![None; num_cells];
+    let chars = body.chars().collect::<Vec<_>>();
+    let hex = |start: usize, len: usize| -> Result<Clue> {
+        let digits = chars
+            .get(start..start + len)
+            .ok_or(Error::UnexpectedEnd)?
+            .iter()
+            .collect::<String>();
+        if digits == "." {
+            return Ok(Clue::Unknown);
+        }
+        u32::from_str_radix(&digits, 16)
+            .map(Clue::Number)
+            .map_err(|_| Error::InvalidNumber(digits))
+    };
+    let direction = |digit: u32| match digit {
+        1 => Some(Direction::Up),
+        2 => Some(Direction::Down),
+        3 => Some(Direction::Left),
+        4 => Some(Direction::Right),
+        _ => None,
+    };
+    let mut cell = 0;
+    let mut index = 0;
+    while index < chars.len() && cell < num_cells {
+        let c = chars[index];
+        match c {
+            '0'..='4' => {
+                cells[cell] = Some(ArrowClue {
+                    direction: direction(c.to_digit(10).unwrap()),
+                    number: hex(index + 1, 1)?,
+                });
+                index += 1;
+            }
+            '5'..='9' => {
+                cells[cell] = Some(ArrowClue {
+                    direction: direction(c.to_digit(10).unwrap() - 5),
+                    number: hex(index + 1, 2)?,
+                });
+                index += 2;
+            }
+            '-' => {
+                let digit = hex(index + 1, 1)?;
+                let Clue::Number(digit) = digit else {
+                    return Err(Error::InvalidArrow);
+                };
+                cells[cell] = Some(ArrowClue {
+                    direction: direction(digit),
+                    number: hex(index + 2, 3)?,
+                });
+                index += 4;
+            }
+            'a'..='z' => cell += c.to_digit(36).unwrap() as usize - 10,
+            _ => return Err(Error::UnexpectedCharacter(c)),
+        }
+        cell += 1;
+        index += 1;
+    }
+    Ok(cells)
+}
+
+/// Decodes the borders between cells into rooms, numbered in order of their first cell in row-major order.
+/// The borders are a bit per pair of horizontally adjacent cells followed by a bit per pair of vertically
+/// adjacent cells, five bits to a base 32 digit.
+///
+/// Returns the room of each cell, the number of rooms and the rest of the body.
+pub fn decode_rooms(body: &str, width: usize, height: usize) -> Result<(Grid<usize>, usize, &str)> {
+    let vertical = width.saturating_sub(1) * height;
+    let horizontal = width * height.saturating_sub(1);
+    let digits = vertical.div_ceil(5) + horizontal.div_ceil(5);
+    if body.len() < digits {
+        return Err(Error::Borders {
+            found: body.len(),
+            expected: digits,
+        });
+    }
+    let (borders, rest) = body.split_at(digits);
+    let bits = |digits: &str, count: usize| -> Result<Vec<bool>> {
+        let mut bits = Vec::with_capacity(count);
+        for c in digits.chars() {
+            let digit = c.to_digit(32).ok_or(Error::UnexpectedCharacter(c))?;
+            for place in [16, 8, 4, 2, 1] {
+                if bits.len() < count {
+                    bits.push(digit & place != 0);
+                }
+            }
+        }
+        Ok(bits)
+    };
+    let (vertical_digits, horizontal_digits) = borders.split_at(vertical.div_ceil(5));
+    let right_borders = bits(vertical_digits, vertical)?;
+    let down_borders = bits(horizontal_digits, horizontal)?;
+    let dim = (height, width);
+    let mut rooms = Grid::from_elem(dim, usize::MAX);
+    let mut count = 0;
+    for start in Location::grid_iter(dim) {
+        if rooms[start] != usize::MAX {
+            continue;
+        }
+        rooms[start] = count;
+        let mut stack = vec![start];
+        while let Some(loc) = stack.pop() {
+            let Location { row, col } = loc;
+            let neighbors = [
+                (col + 1 < width && !right_borders[row * (width - 1) + col])
+                    .then(|| Location::new(row, col + 1)),
+                (col > 0 && !right_borders[row * (width - 1) + col - 1])
+                    .then(|| Location::new(row, col - 1)),
+                (row + 1 < height && !down_borders[row * width + col])
+                    .then(|| Location::new(row + 1, col)),
+                (row > 0 && !down_borders[(row - 1) * width + col])
+                    .then(|| Location::new(row - 1, col)),
+            ];
+            for next in neighbors.into_iter().flatten() {
+                if rooms[next] == usize::MAX {
+                    rooms[next] = count;
+                    stack.push(next);
+                }
+            }
+        }
+        count += 1;
+    }
+    Ok((rooms, count, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_url() {
+        let link = PuzzLink::parse("https://puzz.link/p?hashi/9/7/1h2").unwrap();
+        assert_eq!(link.kind, "hashi");
+        assert_eq!((link.width, link.height), (9, 7));
+        assert_eq!(link.body, "1h2");
+    }
+
+    #[test]
+    fn decode_skips_and_long_numbers() {
+        let cells = decode_number16("1h-1a.g", 8).unwrap();
+        assert_eq!(
+            cells,
+            vec![
+                Some(Clue::Number(1)),
+                None,
+                None,
+                Some(Clue::Number(26)),
+                Some(Clue::Unknown),
+                None,
+                None,
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_circles_in_threes() {
+        let cells = decode_circles("5i", 5).unwrap();
+        assert_eq!(cells, vec![0, 1, 2, 2, 0]);
+    }
+
+    #[test]
+    fn decode_arrows_with_numbers() {
+        let cells = decode_arrow_numbers("12b400.", 5).unwrap();
+        assert_eq!(
+            cells,
+            vec![
+                Some(ArrowClue {
+                    direction: Some(Direction::Up),
+                    number: Clue::Number(2),
+                }),
+                None,
+                None,
+                Some(ArrowClue {
+                    direction: Some(Direction::Right),
+                    number: Clue::Number(0),
+                }),
+                Some(ArrowClue {
+                    direction: None,
+                    number: Clue::Unknown,
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_rooms_splits_on_borders() {
+        // A 2x2 grid split into a left and a right room: one vertical border in each row and no horizontal ones.
+        let (rooms, count, rest) = decode_rooms("o01", 2, 2).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(rooms, Grid::from_vec((2, 2), vec![0, 1, 0, 1]).unwrap());
+        assert_eq!(rest, "1");
+    }
+}
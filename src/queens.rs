@@ -0,0 +1,30 @@
+mod board;
+pub use board::Board;
+mod solver;
+pub use solver::{count_solutions, solutions, solve, Solution};
+
+/// Queens puzzles as a [`Puzzle`](crate::puzzle::Puzzle), in the text format of [`Board::parse`] or as the size of
+/// an empty board.
+pub struct Queens;
+
+impl crate::puzzle::Puzzle for Queens {
+    const NAME: &'static str = "queens puzzle";
+
+    type Instance = Board;
+    type Solution = Solution;
+
+    fn parse(input: &str) -> crate::Result<Board> {
+        match input.trim().parse() {
+            Ok(n) => Board::empty(n),
+            Err(_) => Board::parse(input),
+        }
+    }
+
+    fn solve(board: &Board) -> crate::Result<Option<Solution>> {
+        Ok(solve(board))
+    }
+
+    fn count_solutions(board: &Board, limit: usize) -> crate::Result<usize> {
+        Ok(count_solutions(board, limit))
+    }
+}
@@ -0,0 +1,111 @@
+use std::{fmt::Display, path};
+
+use crate::error::{bail, ensure, read_to_string, Result};
+
+/// An N-queens puzzle: place a queen in every row of an `n` by `n` board such that no two queens share a column
+/// or a diagonal. Some queens may be placed already.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    /// The column of the queen in each row, if it is given.
+    queens: Vec<Option<usize>>,
+}
+
+impl Board {
+    pub fn new(queens: Vec<Option<usize>>) -> Result<Self> {
+        let n = queens.len();
+        ensure!(n > 0, Invalid, "The board must have at least one row.");
+        let placed = queens
+            .iter()
+            .enumerate()
+            .filter_map(|(row, &col)| Some((row, col?)))
+            .collect::<Vec<_>>();
+        for (index, &(row, col)) in placed.iter().enumerate() {
+            ensure!(
+                col < n,
+                Invalid,
+                "The queen in row {row} is in column {col}, but the board only has {n} columns."
+            );
+            for &(other_row, other_col) in &placed[..index] {
+                ensure!(
+                    col != other_col && row - other_row != col.abs_diff(other_col), Invalid,
+                    "The queens at ({other_row}, {other_col}) and ({row}, {col}) attack each other."
+                );
+            }
+        }
+        Ok(Self { queens })
+    }
+
+    /// An empty `n` by `n` board.
+    pub fn empty(n: usize) -> Result<Self> {
+        Self::new(vec![None; n])
+    }
+
+    /// Parses a square grid where `Q` is a queen and `.` is an empty square.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let n = rows.len();
+        let mut queens = vec![None; n];
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.chars().count() != n {
+                bail!(
+                    Parse,
+                    "Expected {n} characters in row {row_index}. Got '{row}'."
+                );
+            }
+            for (col_index, c) in row.chars().enumerate() {
+                match c {
+                    '.' => {}
+                    'Q' if queens[row_index].is_none() => queens[row_index] = Some(col_index),
+                    'Q' => bail!(Parse, "Row {row_index} has more than one queen."),
+                    _ => bail!(
+                        Parse,
+                        "Invalid character '{c}' at ({row_index}, {col_index})."
+                    ),
+                }
+            }
+        }
+        Self::new(queens)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("queens", path)?;
+        Self::parse(string)
+    }
+
+    /// The number of rows and columns.
+    pub fn size(&self) -> usize {
+        self.queens.len()
+    }
+
+    /// The column of the given queen in `row`, if there is one.
+    pub fn queen(&self, row: usize) -> Option<usize> {
+        self.queens[row]
+    }
+}
+
+/// Writes a grid with a `Q` in the column of each row's queen.
+pub(super) fn write_queens(
+    f: &mut std::fmt::Formatter<'_>,
+    queens: impl Iterator<Item = Option<usize>>,
+    n: usize,
+) -> std::fmt::Result {
+    for queen in queens {
+        let row = (0..n)
+            .map(|col| if queen == Some(col) { 'Q' } else { '.' })
+            .collect::<String>();
+        writeln!(f, "{row}")?;
+    }
+    Ok(())
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_queens(f, self.queens.iter().copied(), self.size())
+    }
+}
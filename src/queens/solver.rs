@@ -0,0 +1,215 @@
+use std::{fmt::Display, ops::ControlFlow};
+
+use crate::search::{self, Contradiction, SearchState};
+
+use super::{board::write_queens, Board};
+
+#[derive(Clone, Debug)]
+struct State {
+    queens: Vec<Option<usize>>,
+    /// Whether each column, each diagonal `row + col` and each anti-diagonal `row + n - 1 - col` holds a queen.
+    columns: Vec<bool>,
+    diagonals: Vec<bool>,
+    anti_diagonals: Vec<bool>,
+}
+
+impl State {
+    fn new(board: &Board) -> Self {
+        let n = board.size();
+        let mut state = Self {
+            queens: vec![None; n],
+            columns: vec![false; n],
+            diagonals: vec![false; 2 * n - 1],
+            anti_diagonals: vec![false; 2 * n - 1],
+        };
+        for row in 0..n {
+            if let Some(col) = board.queen(row) {
+                state
+                    .place(row, col)
+                    .expect("The board checks that no given queens attack each other.");
+            }
+        }
+        state
+    }
+
+    fn is_free(&self, row: usize, col: usize) -> bool {
+        let n = self.queens.len();
+        !self.columns[col] && !self.diagonals[row + col] && !self.anti_diagonals[row + n - 1 - col]
+    }
+
+    fn place(&mut self, row: usize, col: usize) -> Result<(), Contradiction> {
+        if self.queens[row].is_some() || !self.is_free(row, col) {
+            return Err(Contradiction);
+        }
+        let n = self.queens.len();
+        self.queens[row] = Some(col);
+        self.columns[col] = true;
+        self.diagonals[row + col] = true;
+        self.anti_diagonals[row + n - 1 - col] = true;
+        Ok(())
+    }
+
+    /// The free columns of every row without a queen.
+    fn options(&self) -> impl Iterator<Item = (usize, Vec<usize>)> + '_ {
+        let n = self.queens.len();
+        (0..n)
+            .filter(|&row| self.queens[row].is_none())
+            .map(move |row| (row, (0..n).filter(|&col| self.is_free(row, col)).collect()))
+    }
+
+    /// Places the queen of every row with only one free column.
+    fn apply_rows(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for (row, columns) in self.options().collect::<Vec<_>>() {
+            match columns[..] {
+                [] => return Err(Contradiction),
+                [col] => {
+                    self.place(row, col)?;
+                    changed = true;
+                }
+                _ => {}
+            }
+        }
+        Ok(changed)
+    }
+}
+
+impl SearchState for State {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        while self.apply_rows()? {}
+        Ok(())
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some((row, columns)) = self.options().min_by_key(|(_, columns)| columns.len()) else {
+            return vec![];
+        };
+        columns
+            .into_iter()
+            .map(|col| {
+                let mut state = self.clone();
+                state.place(row, col).expect("Only free columns are tried.");
+                state
+            })
+            .collect()
+    }
+}
+
+/// A queen in every row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    queens: Vec<usize>,
+}
+
+impl Solution {
+    fn from_state(state: State) -> Self {
+        Self {
+            queens: state
+                .queens
+                .into_iter()
+                .map(|queen| queen.expect("Every row has a queen in a solution."))
+                .collect(),
+        }
+    }
+
+    /// The column of the queen in `row`.
+    pub fn queen(&self, row: usize) -> usize {
+        self.queens[row]
+    }
+}
+
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_queens(
+            f,
+            self.queens.iter().map(|&col| Some(col)),
+            self.queens.len(),
+        )
+    }
+}
+
+/// Places the queens of an empty `n` by `n` board without searching, following the explicit solution of Hoffman,
+/// Loessi and Moore. Every size but 2 and 3 has a solution.
+fn construct(n: usize) -> Option<Solution> {
+    if n == 2 || n == 3 {
+        return None;
+    }
+    // Columns counted from 1, as in the construction.
+    let evens = (2..=n).step_by(2);
+    let odds = (1..=n).step_by(2);
+    let columns: Vec<usize> = match n % 6 {
+        // Swaps 1 and 3 and moves 5 to the end of the odd columns.
+        2 => evens
+            .chain([3, 1])
+            .chain((7..=n).step_by(2))
+            .chain([5])
+            .collect(),
+        // Moves 2 to the end of the even columns and 1 and 3 to the end of the odd columns.
+        3 => (4..=n)
+            .step_by(2)
+            .chain([2])
+            .chain((5..=n).step_by(2))
+            .chain([1, 3])
+            .collect(),
+        _ => evens.chain(odds).collect(),
+    };
+    Some(Solution {
+        queens: columns.into_iter().map(|col| col - 1).collect(),
+    })
+}
+
+/// Solves an empty board by construction, as searching slows down badly beyond a few dozen rows, and any other
+/// board by search.
+pub fn solve(board: &Board) -> Option<Solution> {
+    let n = board.size();
+    if (0..n).all(|row| board.queen(row).is_none()) {
+        return construct(n);
+    }
+    search::solve(State::new(board)).map(Solution::from_state)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    search::count_solutions(State::new(board), limit)
+}
+
+/// Lists solutions in the order they are found, stopping once `limit` have been found.
+pub fn solutions(board: &Board, limit: usize) -> Vec<Solution> {
+    let mut solutions = vec![];
+    search::for_each_solution(State::new(board), |state| {
+        solutions.push(Solution::from_state(state));
+        if solutions.len() >= limit {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+    solutions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eight_queens() {
+        let board = Board::empty(8).unwrap();
+        assert_eq!(count_solutions(&board, usize::MAX), 92);
+        let completion = Board::parse(format!("Q.......\n{}", "........\n".repeat(7))).unwrap();
+        assert_eq!(solutions(&completion, usize::MAX).len(), 4);
+    }
+
+    #[test]
+    fn constructs_empty_boards() {
+        for n in 1..=200 {
+            let board = Board::empty(n).unwrap();
+            let Some(solution) = solve(&board) else {
+                assert!(n == 2 || n == 3, "No solution for {n} queens.");
+                continue;
+            };
+            let queens = (0..n).map(|row| Some(solution.queen(row))).collect();
+            Board::new(queens)
+                .unwrap_or_else(|err| panic!("Invalid solution for {n} queens: {err}"));
+        }
+        assert_eq!(count_solutions(&Board::empty(3).unwrap(), usize::MAX), 0);
+    }
+}
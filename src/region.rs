@@ -0,0 +1,119 @@
+//! Orthogonally connected regions of cells in a grid, as needed by puzzles where the unshaded cells, the sea,
+//! an island or a piece must form a single connected group.
+
+use std::collections::VecDeque;
+
+use crate::{
+    error::{bail, Result},
+    grid::Grid,
+    location::Location,
+};
+
+/// The orthogonally connected regions of a set of cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Components {
+    /// The index of the region of each cell in the set.
+    pub labels: Grid<Option<usize>>,
+    pub regions: Vec<Vec<Location>>,
+}
+
+impl Components {
+    pub fn label(&self, location: Location) -> Option<usize> {
+        self.labels[location]
+    }
+}
+
+/// Splits the cells for which `included` holds into orthogonally connected regions.
+pub fn components(dim: (usize, usize), included: impl Fn(Location) -> bool) -> Components {
+    let mut labels = Grid::from_elem(dim, None);
+    let mut regions = vec![];
+    for start in Location::grid_iter(dim) {
+        if labels[start].is_some() || !included(start) {
+            continue;
+        }
+        let label = regions.len();
+        labels[start] = Some(label);
+        let mut region = vec![];
+        let mut queue = VecDeque::from([start]);
+        while let Some(loc) = queue.pop_front() {
+            region.push(loc);
+            for adj in loc.adjacents(dim).into_iter().flatten() {
+                if labels[adj].is_none() && included(adj) {
+                    labels[adj] = Some(label);
+                    queue.push_back(adj);
+                }
+            }
+        }
+        regions.push(region);
+    }
+    Components { labels, regions }
+}
+
+/// Every cell outside `region` that is orthogonally adjacent to a cell in it, without duplicates.
+pub fn boundary(dim: (usize, usize), region: &[Location]) -> Vec<Location> {
+    let mut boundary = vec![];
+    for loc in region {
+        for adj in loc.adjacents(dim).into_iter().flatten() {
+            if !region.contains(&adj) && !boundary.contains(&adj) {
+                boundary.push(adj);
+            }
+        }
+    }
+    boundary
+}
+
+/// Whether every cell for which `required` holds can reach every other such cell
+/// through orthogonally adjacent cells for which `passable` holds.
+///
+/// Required cells must also be passable. Returns true if there are no required cells.
+pub fn is_connected(
+    dim: (usize, usize),
+    passable: impl Fn(Location) -> bool,
+    required: impl Fn(Location) -> bool,
+) -> bool {
+    let Some(start) = Location::grid_iter(dim).find(|&loc| required(loc)) else {
+        return true;
+    };
+    let mut visited = Grid::from_elem(dim, false);
+    visited[start] = true;
+    let mut stack = vec![start];
+    while let Some(loc) = stack.pop() {
+        for adj in loc.adjacents(dim).into_iter().flatten() {
+            if !visited[adj] && passable(adj) {
+                visited[adj] = true;
+                stack.push(adj);
+            }
+        }
+    }
+    Location::grid_iter(dim).all(|loc| !required(loc) || visited[loc])
+}
+
+/// Parses a grid of characters where each distinct character marks a region, as used to lay out the irregular
+/// regions of puzzles such as star battle and suguru. Regions are numbered in order of first appearance.
+///
+/// Returns the region of each cell and the number of regions.
+pub fn parse_regions(rows: &[&str]) -> Result<(Grid<usize>, usize)> {
+    let height = rows.len();
+    let width = rows.first().map_or(0, |row| row.chars().count());
+    let mut letters = vec![];
+    let mut regions = Grid::from_elem((height, width), 0);
+    for (row_index, row) in rows.iter().enumerate() {
+        if row.chars().count() != width {
+            bail!(
+                Parse,
+                "Expected {width} characters in row {row_index}. Got '{row}'."
+            );
+        }
+        for (col_index, c) in row.chars().enumerate() {
+            let region = match letters.iter().position(|&letter| letter == c) {
+                Some(region) => region,
+                None => {
+                    letters.push(c);
+                    letters.len() - 1
+                }
+            };
+            regions[Location::new(row_index, col_index)] = region;
+        }
+    }
+    Ok((regions, letters.len()))
+}
@@ -0,0 +1,344 @@
+//! Reports on solving whole sets of puzzles, such as the sudoku grid sets: how many were solved, how long they took,
+//! which techniques they needed and how many guesses, per set. Reports render as Markdown or as a standalone HTML
+//! page.
+
+use std::{collections::BTreeMap, fmt::Write, time::Duration};
+
+use crate::stats::SolveStats;
+
+/// The upper ends of the ranges that guess counts are grouped into, with a last group for everything above.
+const GUESS_BUCKETS: [u64; 4] = [0, 1, 9, 99];
+
+/// The results of solving one set of puzzles.
+#[derive(Clone, Debug, Default)]
+pub struct SetReport {
+    pub name: String,
+    pub puzzles: u64,
+    pub solved: u64,
+    /// The costs of the solved puzzles, added up.
+    pub stats: SolveStats,
+    /// How long each puzzle took to solve, solved or not.
+    times: Vec<Duration>,
+    /// How many solved puzzles needed each technique as their hardest.
+    techniques: BTreeMap<&'static str, u64>,
+    /// How many solved puzzles fall in each range of [`GUESS_BUCKETS`], and above it.
+    guesses: [u64; GUESS_BUCKETS.len() + 1],
+}
+
+impl SetReport {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Adds a puzzle that took `time` to solve. The technique and costs only count for solved puzzles.
+    pub fn add(
+        &mut self,
+        solved: bool,
+        time: Duration,
+        technique: &'static str,
+        stats: &SolveStats,
+    ) {
+        self.puzzles += 1;
+        self.times.push(time);
+        if solved {
+            self.solved += 1;
+            self.stats.add(stats);
+            *self.techniques.entry(technique).or_default() += 1;
+            let bucket = GUESS_BUCKETS
+                .iter()
+                .take_while(|&&bucket| stats.guesses > bucket)
+                .count();
+            self.guesses[bucket] += 1;
+        }
+    }
+
+    /// The share of the puzzles that were solved, from 0 to 1. 1 for a set without puzzles.
+    pub fn solve_rate(&self) -> f64 {
+        if self.puzzles == 0 {
+            1.0
+        } else {
+            self.solved as f64 / self.puzzles as f64
+        }
+    }
+
+    /// The time that the share `quantile` of the puzzles were solved within, such as 0.5 for the median.
+    pub fn time_quantile(&self, quantile: f64) -> Duration {
+        let mut times = self.times.clone();
+        times.sort_unstable();
+        let Some(last) = times.len().checked_sub(1) else {
+            return Duration::ZERO;
+        };
+        times[(last as f64 * quantile.clamp(0.0, 1.0)).round() as usize]
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.times.iter().sum()
+    }
+
+    /// The number of solved puzzles that needed each technique as their hardest, by technique name.
+    pub fn techniques(&self) -> &BTreeMap<&'static str, u64> {
+        &self.techniques
+    }
+
+    /// The number of solved puzzles in each range of guesses, labelled like `2-9`.
+    pub fn guess_distribution(&self) -> Vec<(String, u64)> {
+        let mut lower = 0;
+        let mut distribution = GUESS_BUCKETS
+            .iter()
+            .zip(self.guesses)
+            .map(|(&upper, count)| {
+                let label = if lower == upper {
+                    upper.to_string()
+                } else {
+                    format!("{lower}-{upper}")
+                };
+                lower = upper + 1;
+                (label, count)
+            })
+            .collect::<Vec<_>>();
+        distribution.push((format!("{lower}+"), self.guesses[GUESS_BUCKETS.len()]));
+        distribution
+    }
+}
+
+/// The reports of several sets, solved together in `elapsed`.
+#[derive(Clone, Debug)]
+pub struct Report {
+    pub title: String,
+    pub sets: Vec<SetReport>,
+    pub elapsed: Duration,
+    /// The techniques in the order to show them, such as from the cheapest to the most costly. Techniques that the
+    /// sets needed but that are not listed come after these.
+    pub techniques: Vec<&'static str>,
+}
+
+impl Report {
+    /// The summary table, with a header row and then a row per set.
+    fn summary_table(&self) -> Vec<Vec<String>> {
+        let header = [
+            "Set",
+            "Solved",
+            "Rate",
+            "Median",
+            "90th percentile",
+            "Slowest",
+            "Steps",
+            "Guesses",
+        ]
+        .map(str::to_string)
+        .to_vec();
+        let rows = self.sets.iter().map(|set| {
+            vec![
+                set.name.clone(),
+                format!("{}/{}", set.solved, set.puzzles),
+                format!("{:.0}%", set.solve_rate() * 100.0),
+                format_duration(set.time_quantile(0.5)),
+                format_duration(set.time_quantile(0.9)),
+                format_duration(set.time_quantile(1.0)),
+                set.stats.steps.to_string(),
+                set.stats.guesses.to_string(),
+            ]
+        });
+        std::iter::once(header).chain(rows).collect()
+    }
+
+    /// Every technique that any set needed, in the order of [`Report::techniques`].
+    fn technique_names(&self) -> Vec<&'static str> {
+        let mut names = self
+            .techniques
+            .iter()
+            .copied()
+            .filter(|technique| {
+                self.sets
+                    .iter()
+                    .any(|set| set.techniques.contains_key(technique))
+            })
+            .collect::<Vec<_>>();
+        for set in &self.sets {
+            for &technique in set.techniques.keys() {
+                if !names.contains(&technique) {
+                    names.push(technique);
+                }
+            }
+        }
+        names
+    }
+
+    /// Tables of the techniques and guesses per set, each with a header row.
+    fn distribution_tables(&self) -> [(&'static str, Vec<Vec<String>>); 2] {
+        let techniques = self.technique_names();
+        let mut technique_rows = vec![std::iter::once("Set")
+            .chain(techniques.iter().copied())
+            .map(str::to_string)
+            .collect::<Vec<_>>()];
+        let mut guess_rows = vec![];
+        for set in &self.sets {
+            technique_rows.push(
+                std::iter::once(set.name.clone())
+                    .chain(techniques.iter().map(|technique| {
+                        set.techniques
+                            .get(technique)
+                            .copied()
+                            .unwrap_or_default()
+                            .to_string()
+                    }))
+                    .collect(),
+            );
+            let distribution = set.guess_distribution();
+            if guess_rows.is_empty() {
+                guess_rows.push(
+                    std::iter::once("Set".to_string())
+                        .chain(distribution.iter().map(|(label, _)| label.clone()))
+                        .collect(),
+                );
+            }
+            guess_rows.push(
+                std::iter::once(set.name.clone())
+                    .chain(distribution.iter().map(|(_, count)| count.to_string()))
+                    .collect(),
+            );
+        }
+        [
+            ("Hardest technique needed", technique_rows),
+            ("Guesses", guess_rows),
+        ]
+    }
+
+    /// The total steps and guesses of the solved puzzles and the time everything took.
+    fn totals(&self) -> String {
+        let steps = self.sets.iter().map(|set| set.stats.steps).sum::<u64>();
+        let guesses = self.sets.iter().map(|set| set.stats.guesses).sum::<u64>();
+        format!(
+            "{steps} total steps and {guesses} guesses used on successful solutions in {}.",
+            format_duration(self.elapsed)
+        )
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("# {}\n\n{}\n\n", self.title, self.totals());
+        markdown_table(&mut markdown, &self.summary_table());
+        for (title, rows) in self.distribution_tables() {
+            if rows.len() > 1 {
+                markdown.push_str(&format!("\n## {title}\n\n"));
+                markdown_table(&mut markdown, &rows);
+            }
+        }
+        markdown
+    }
+
+    /// A standalone page with the same tables as [`Report::to_markdown`].
+    pub fn to_html(&self) -> String {
+        let title = escape_html(&self.title);
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n<body>\n\
+             <h1>{title}</h1>\n<p>{}</p>\n",
+            escape_html(&self.totals())
+        );
+        html_table(&mut html, &self.summary_table());
+        for (title, rows) in self.distribution_tables() {
+            if rows.len() > 1 {
+                html.push_str(&format!("<h2>{}</h2>\n", escape_html(title)));
+                html_table(&mut html, &rows);
+            }
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3}s", duration.as_secs_f64())
+}
+
+/// Writes the rows as a table, the first of them as the header.
+fn markdown_table(markdown: &mut String, rows: &[Vec<String>]) {
+    let Some((header, rows)) = rows.split_first() else {
+        return;
+    };
+    let line = |markdown: &mut String, cells: &[String]| {
+        _ = writeln!(markdown, "| {} |", cells.join(" | "));
+    };
+    line(markdown, header);
+    line(markdown, &vec!["---".to_string(); header.len()]);
+    for row in rows {
+        line(markdown, row);
+    }
+}
+
+/// Writes the rows as a table, the first of them as the header.
+fn html_table(html: &mut String, rows: &[Vec<String>]) {
+    let Some((header, rows)) = rows.split_first() else {
+        return;
+    };
+    let line = |html: &mut String, tag: &str, cells: &[String]| {
+        html.push_str("<tr>");
+        for cell in cells {
+            _ = write!(html, "<{tag}>{}</{tag}>", escape_html(cell));
+        }
+        html.push_str("</tr>\n");
+    };
+    html.push_str("<table>\n");
+    line(html, "th", header);
+    for row in rows {
+        line(html, "td", row);
+    }
+    html.push_str("</table>\n");
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregates_sets() {
+        let mut set = SetReport::new("easy");
+        for (guesses, millis) in [(0, 1), (0, 2), (3, 5), (120, 40)] {
+            let stats = SolveStats {
+                steps: 1,
+                guesses,
+                ..SolveStats::default()
+            };
+            let technique = if guesses == 0 { "groups" } else { "guesses" };
+            set.add(true, Duration::from_millis(millis), technique, &stats);
+        }
+        set.add(
+            false,
+            Duration::from_millis(3),
+            "guesses",
+            &SolveStats::default(),
+        );
+        assert_eq!((set.solved, set.puzzles), (4, 5));
+        assert_eq!(set.solve_rate(), 0.8);
+        assert_eq!(set.time_quantile(0.5), Duration::from_millis(3));
+        assert_eq!(set.time_quantile(1.0), Duration::from_millis(40));
+        assert_eq!(set.techniques()["groups"], 2);
+        assert_eq!(
+            set.guess_distribution(),
+            [("0", 2), ("1", 0), ("2-9", 1), ("10-99", 0), ("100+", 1)]
+                .map(|(label, count)| (label.to_string(), count))
+        );
+
+        let report = Report {
+            title: "Sudoku <sets>".to_string(),
+            sets: vec![set],
+            elapsed: Duration::from_millis(51),
+            techniques: vec!["ghosts", "guesses", "groups"],
+        };
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("| easy | 4/5 | 80% |"));
+        assert!(markdown.contains("| Set | guesses | groups |"));
+        let html = report.to_html();
+        assert!(html.contains("<title>Sudoku &lt;sets&gt;</title>"));
+        assert!(html.contains("<td>4/5</td>"));
+    }
+}
@@ -0,0 +1,70 @@
+use std::ops::ControlFlow;
+
+use thiserror::Error;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Error)]
+#[error("The puzzle state is contradictory.")]
+pub struct Contradiction;
+
+/// A partially solved puzzle that can be solved by depth first search.
+pub trait SearchState: Clone {
+    /// Applies deductions until no more can be made.
+    /// Must return an error if the state breaks any rule, also when the state is complete.
+    fn propagate(&mut self) -> Result<(), Contradiction>;
+
+    /// States to try, in order, that together cover every solution reachable from this state.
+    ///
+    /// Returns an empty vector when there is nothing left to decide, in which case the state is a solution.
+    fn branches(&self) -> Vec<Self>;
+}
+
+/// Calls `visit` with every solution reachable from `state`, stopping early if `visit` breaks.
+pub fn for_each_solution<S, F>(state: S, mut visit: F)
+where
+    S: SearchState,
+    F: FnMut(S) -> ControlFlow<()>,
+{
+    let mut stack = vec![state];
+    while let Some(mut state) = stack.pop() {
+        if state.propagate().is_err() {
+            continue;
+        }
+        let branches = state.branches();
+        if branches.is_empty() {
+            if visit(state).is_break() {
+                return;
+            }
+        } else {
+            stack.extend(branches.into_iter().rev());
+        }
+    }
+}
+
+pub fn solve<S>(state: S) -> Option<S>
+where
+    S: SearchState,
+{
+    let mut solution = None;
+    for_each_solution(state, |state| {
+        solution = Some(state);
+        ControlFlow::Break(())
+    });
+    solution
+}
+
+/// Counts solutions, stopping once `limit` have been found.
+pub fn count_solutions<S>(state: S, limit: usize) -> usize
+where
+    S: SearchState,
+{
+    let mut count = 0;
+    for_each_solution(state, |_| {
+        count += 1;
+        if count >= limit {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+    count
+}
@@ -0,0 +1,291 @@
+//! Grids where every cell is either shaded or unshaded, as used by the many puzzles that boil down to
+//! deciding which cells to shade (or where to put stars, bulbs, mines, ...).
+
+use std::fmt::{self, Display, Formatter};
+
+use thiserror::Error;
+
+use crate::{
+    grid::Grid,
+    location::{Direction, Location},
+    region,
+    search::Contradiction,
+};
+
+/// Why a shading breaks a rule of its puzzle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Error)]
+pub enum Error {
+    #[error("The cell at {location} cannot be {shade:?}, as it is already decided the other way.")]
+    Decided { location: Location, shade: Shade },
+    #[error("{target} cells must be shaded, but {shaded} are shaded and {unknown} undecided.")]
+    Count {
+        target: usize,
+        shaded: usize,
+        unknown: usize,
+    },
+    /// The 2x2 block with its top left cell at the location has the same shade throughout.
+    #[error("The 2x2 block at {0} is all {1:?}.")]
+    Pool(Location, Shade),
+    #[error("{target} cells must be seen from {location}, but between {min} and {max} can be.")]
+    Sight {
+        location: Location,
+        target: usize,
+        min: usize,
+        max: usize,
+    },
+    #[error("The {0:?} cells are cut apart.")]
+    Disconnected(Shade),
+}
+
+impl From<Error> for Contradiction {
+    fn from(_: Error) -> Self {
+        Contradiction
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Shade {
+    Unknown,
+    Shaded,
+    Unshaded,
+}
+
+impl Shade {
+    pub fn to_char(self) -> char {
+        match self {
+            Shade::Unknown => '?',
+            Shade::Shaded => '#',
+            Shade::Unshaded => '.',
+        }
+    }
+
+    /// Shaded for unshaded and the other way around. Unknown stays unknown.
+    pub fn opposite(self) -> Self {
+        match self {
+            Shade::Unknown => Shade::Unknown,
+            Shade::Shaded => Shade::Unshaded,
+            Shade::Unshaded => Shade::Shaded,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShadeGrid {
+    cells: Grid<Shade>,
+}
+
+impl ShadeGrid {
+    pub fn new(dim: (usize, usize)) -> Self {
+        Self {
+            cells: Grid::from_elem(dim, Shade::Unknown),
+        }
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.cells.dim()
+    }
+
+    pub fn cells(&self) -> &Grid<Shade> {
+        &self.cells
+    }
+
+    pub fn get(&self, location: Location) -> Shade {
+        self.cells[location]
+    }
+
+    pub fn is_shaded(&self, location: Location) -> bool {
+        self.get(location) == Shade::Shaded
+    }
+
+    /// Sets the cell at `location` to `shade`, returning whether anything changed.
+    /// Fails if the cell is already decided the other way.
+    pub fn set(&mut self, location: Location, shade: Shade) -> Result<bool, Error> {
+        let cell = &mut self.cells[location];
+        if *cell == shade {
+            Ok(false)
+        } else if *cell == Shade::Unknown {
+            *cell = shade;
+            Ok(true)
+        } else {
+            Err(Error::Decided { location, shade })
+        }
+    }
+
+    pub fn first_unknown(&self) -> Option<Location> {
+        Location::grid_iter(self.dim()).find(|&loc| self.get(loc) == Shade::Unknown)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.cells.iter().all(|&shade| shade != Shade::Unknown)
+    }
+
+    /// Enforces that exactly `target` of `locations` are shaded.
+    /// Shades or unshades the remaining unknown cells if the count forces it.
+    pub fn apply_count(&mut self, locations: &[Location], target: usize) -> Result<bool, Error> {
+        let shaded = locations.iter().filter(|&&loc| self.is_shaded(loc)).count();
+        let unknown = locations
+            .iter()
+            .filter(|&&loc| self.get(loc) == Shade::Unknown)
+            .count();
+        if shaded > target || shaded + unknown < target {
+            return Err(Error::Count {
+                target,
+                shaded,
+                unknown,
+            });
+        }
+        let fill = if shaded == target {
+            Shade::Unshaded
+        } else if shaded + unknown == target {
+            Shade::Shaded
+        } else {
+            return Ok(false);
+        };
+        let mut changed = false;
+        for &loc in locations {
+            if self.get(loc) == Shade::Unknown {
+                changed |= self.set(loc, fill)?;
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Enforces that no 2x2 block is entirely `shade`, giving the last undecided cell of a block the opposite shade
+    /// once the other three have `shade`.
+    pub fn apply_no_pools(&mut self, shade: Shade) -> Result<bool, Error> {
+        let (height, width) = self.dim();
+        let mut changed = false;
+        for row in 0..height.saturating_sub(1) {
+            for col in 0..width.saturating_sub(1) {
+                let block = [(0, 0), (0, 1), (1, 0), (1, 1)]
+                    .map(|(dr, dc)| Location::new(row + dr, col + dc));
+                let count = block.iter().filter(|&&loc| self.get(loc) == shade).count();
+                if count == 4 {
+                    return Err(Error::Pool(Location::new(row, col), shade));
+                }
+                if count == 3 {
+                    for &loc in block.iter() {
+                        if self.get(loc) == Shade::Unknown {
+                            changed |= self.set(loc, shade.opposite())?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// How many cells are seen from `location` looking in `direction`, where the view stops at the first shaded
+    /// cell or the edge of the grid. Returns the number of cells certainly seen and the number that may be seen.
+    pub fn sight(&self, location: Location, direction: Direction) -> (usize, usize) {
+        let ray = || location.ray(direction, self.dim()).map(|loc| self.get(loc));
+        let min = ray().take_while(|&shade| shade == Shade::Unshaded).count();
+        let max = ray().take_while(|&shade| shade != Shade::Shaded).count();
+        (min, max)
+    }
+
+    /// Enforces that exactly `target` cells are seen from `location` along its row and column, not counting the
+    /// cell itself. Each direction must see at least what the other directions cannot make up for, and once a
+    /// direction sees all that the others leave for it, its view is blocked.
+    pub fn apply_sight(&mut self, location: Location, target: usize) -> Result<bool, Error> {
+        let dim = self.dim();
+        let sights = Direction::ALL.map(|direction| self.sight(location, direction));
+        let min_sum = sights.iter().map(|&(min, _)| min).sum::<usize>();
+        let max_sum = sights.iter().map(|&(_, max)| max).sum::<usize>();
+        if target < min_sum || target > max_sum {
+            return Err(Error::Sight {
+                location,
+                target,
+                min: min_sum,
+                max: max_sum,
+            });
+        }
+        let mut changed = false;
+        for (direction, (min, max)) in Direction::ALL.into_iter().zip(sights) {
+            let at_least = target.saturating_sub(max_sum - max);
+            let at_most = target - (min_sum - min);
+            for cell in location.ray(direction, dim).take(at_least) {
+                changed |= self.set(cell, Shade::Unshaded)?;
+            }
+            if min.max(at_least) == at_most {
+                if let Some(cell) = location.ray(direction, dim).nth(at_most) {
+                    changed |= self.set(cell, Shade::Shaded)?;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Whether the cells with `shade` can still all be joined through cells that are not the opposite shade.
+    pub fn can_connect(&self, shade: Shade) -> bool {
+        let blocking = shade.opposite();
+        region::is_connected(
+            self.dim(),
+            |loc| self.get(loc) != blocking,
+            |loc| self.get(loc) == shade,
+        )
+    }
+
+    /// Gives `shade` to every undecided cell that would cut the cells with `shade` apart if it got the opposite shade.
+    /// Fails if the cells with `shade` are already cut apart.
+    pub fn keep_connected(&mut self, shade: Shade) -> Result<bool, Error> {
+        if !self.can_connect(shade) {
+            return Err(Error::Disconnected(shade));
+        }
+        let blocking = shade.opposite();
+        let mut changed = false;
+        for loc in Location::grid_iter(self.dim()) {
+            if self.get(loc) != Shade::Unknown {
+                continue;
+            }
+            let cuts = !region::is_connected(
+                self.dim(),
+                |other| other != loc && self.get(other) != blocking,
+                |other| self.get(other) == shade,
+            );
+            if cuts {
+                changed |= self.set(loc, shade)?;
+            }
+        }
+        Ok(changed)
+    }
+
+    /// The two grids obtained by shading and unshading the cell at `location`.
+    pub fn branch_on(&self, location: Location) -> [Self; 2] {
+        [Shade::Shaded, Shade::Unshaded].map(|shade| {
+            let mut grid = self.clone();
+            grid.cells[location] = shade;
+            grid
+        })
+    }
+}
+
+impl Display for ShadeGrid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for row in self.cells.rows() {
+            for &shade in row {
+                write!(f, "{}", shade.to_char())?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Every row and then every column that has a count, together with its count,
+/// for puzzles where the numbers outside the grid say how many cells of each line are shaded.
+pub fn counted_lines(
+    row_counts: &[Option<usize>],
+    col_counts: &[Option<usize>],
+) -> Vec<(Vec<Location>, usize)> {
+    let (height, width) = (row_counts.len(), col_counts.len());
+    let rows = row_counts.iter().enumerate().filter_map(|(row, &count)| {
+        let cells = (0..width).map(|col| Location::new(row, col)).collect();
+        Some((cells, count?))
+    });
+    let cols = col_counts.iter().enumerate().filter_map(|(col, &count)| {
+        let cells = (0..height).map(|row| Location::new(row, col)).collect();
+        Some((cells, count?))
+    });
+    rows.chain(cols).collect()
+}
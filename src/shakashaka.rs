@@ -0,0 +1,30 @@
+mod board;
+pub use board::{Board, Cell};
+mod solver;
+pub use solver::{count_solutions, solve, Piece, Solution};
+
+/// Shakashaka puzzles as a [`Puzzle`](crate::puzzle::Puzzle), in the text format of [`Board::parse`].
+pub struct Shakashaka;
+
+impl crate::puzzle::Puzzle for Shakashaka {
+    const NAME: &'static str = "shakashaka puzzle";
+
+    type Instance = Board;
+    type Solution = Solution;
+
+    fn parse(input: &str) -> crate::Result<Board> {
+        Board::parse(input)
+    }
+
+    fn parse_url(url: &str) -> crate::Result<Board> {
+        Board::from_puzz_link(url)
+    }
+
+    fn solve(board: &Board) -> crate::Result<Option<Solution>> {
+        Ok(solve(board))
+    }
+
+    fn count_solutions(board: &Board, limit: usize) -> crate::Result<usize> {
+        Ok(count_solutions(board, limit))
+    }
+}
@@ -0,0 +1,145 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+    puzzlink::{self, Clue, PuzzLink},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cell {
+    White,
+    /// A black cell, optionally with the number of triangles in the orthogonally adjacent cells.
+    Black(Option<u32>),
+}
+
+impl Cell {
+    pub fn to_char(self) -> char {
+        match self {
+            Cell::White => '.',
+            Cell::Black(None) => '#',
+            Cell::Black(Some(number)) => char::from_digit(number, 10).unwrap(),
+        }
+    }
+}
+
+/// A shakashaka puzzle: place triangles covering half of some white cells, cut along a diagonal, such that every
+/// area left white is a rectangle, upright or turned 45 degrees. Numbers on black cells count the triangles in the
+/// orthogonally adjacent cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    cells: Grid<Cell>,
+}
+
+impl Board {
+    pub fn new(cells: Grid<Cell>) -> Result<Self> {
+        let (height, width) = cells.dim();
+        ensure!(
+            height > 0 && width > 0,
+            Invalid,
+            "Shakashaka grids must have at least one cell."
+        );
+        if let Some(number) = cells.iter().find_map(|&cell| match cell {
+            Cell::Black(Some(number)) if number > 4 => Some(number),
+            _ => None,
+        }) {
+            bail!(Invalid, "Numbers must be at most 4, but got {number}.");
+        }
+        Ok(Self { cells })
+    }
+
+    /// Parses a grid with `.` for white cells, `#` for black cells and digits for numbered black cells.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        let mut cells = Grid::from_elem((height, width), Cell::White);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.chars().count() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} characters in row {row_index}. Got '{row}'."
+                );
+            }
+            for (col_index, c) in row.chars().enumerate() {
+                cells[Location::new(row_index, col_index)] = match c {
+                    '.' => Cell::White,
+                    '#' => Cell::Black(None),
+                    _ => Cell::Black(Some(c.to_digit(10).ok_or_else(|| {
+                        Error::Parse(format!(
+                            "Invalid character '{c}' at ({row_index}, {col_index})."
+                        ))
+                    })?)),
+                };
+            }
+        }
+        Self::new(cells)
+    }
+
+    /// Imports a puzzle from a puzz.link or pzv.jp URL.
+    pub fn from_puzz_link(url: &str) -> Result<Self> {
+        let link = PuzzLink::parse(url)?;
+        link.expect_kind(&["shakashaka"])?;
+        let clues = puzzlink::decode_number16(&link.body, link.width * link.height)?;
+        let mut cells = Grid::from_elem((link.height, link.width), Cell::White);
+        for (index, clue) in clues.into_iter().enumerate() {
+            cells[Location::new(index / link.width, index % link.width)] = match clue {
+                None => Cell::White,
+                Some(Clue::Number(number)) => Cell::Black(Some(number)),
+                Some(Clue::Unknown) => Cell::Black(None),
+            };
+        }
+        Self::new(cells)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("shakashaka", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.cells.dim()
+    }
+
+    pub fn cell(&self, location: Location) -> Cell {
+        self.cells[location]
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.cells.rows() {
+            writeln!(f, "{}", row.map(|cell| cell.to_char()).collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_text_and_puzz_links() {
+        let board =
+            Board::parse(include_str!("../../data/shakashaka/puzzles/eight01.txt")).unwrap();
+        assert_eq!(board.dim(), (8, 8));
+        assert_eq!(board.cell(Location::new(0, 4)), Cell::Black(None));
+        assert_eq!(board.cell(Location::new(1, 4)), Cell::Black(Some(1)));
+        assert_eq!(board.cell(Location::new(0, 0)), Cell::White);
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        let link = "https://puzz.link/p?shakashaka/8/8/j.g.k1l.k.k3.j.k.1m....i.j";
+        assert_eq!(Board::from_puzz_link(link).unwrap(), board);
+        assert!(Board::from_puzz_link("https://puzz.link/p?shakashaka/2/1/5g").is_err());
+        assert!(Board::parse("5.\n..\n").is_err());
+        assert!(Board::parse("..\n.\n").is_err());
+        assert!(Board::parse(".x\n..\n").is_err());
+    }
+}
@@ -0,0 +1,417 @@
+use std::fmt::Display;
+
+use crate::{
+    grid::Grid,
+    location::Location,
+    search::{self, Contradiction, SearchState},
+};
+
+use super::{Board, Cell};
+
+/// The quarters of a cell, cut along both diagonals, named by the edge they lie on.
+const TOP: u8 = 1;
+const RIGHT: u8 = 2;
+const BOTTOM: u8 = 4;
+const LEFT: u8 = 8;
+const SOLID: u8 = TOP | RIGHT | BOTTOM | LEFT;
+
+/// What a white cell holds: nothing, or a triangle shading the half of the cell with the named corner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Piece {
+    Empty,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Piece {
+    pub const ALL: [Self; 5] = [
+        Self::Empty,
+        Self::TopLeft,
+        Self::TopRight,
+        Self::BottomLeft,
+        Self::BottomRight,
+    ];
+
+    /// The triangles are drawn as the letter whose bowl is in the shaded corner.
+    pub fn to_char(self) -> char {
+        match self {
+            Piece::Empty => '.',
+            Piece::TopLeft => 'q',
+            Piece::TopRight => 'p',
+            Piece::BottomLeft => 'd',
+            Piece::BottomRight => 'b',
+        }
+    }
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+
+    /// The shaded quarters of the cell.
+    fn shaded(self) -> u8 {
+        match self {
+            Piece::Empty => 0,
+            Piece::TopLeft => TOP | LEFT,
+            Piece::TopRight => TOP | RIGHT,
+            Piece::BottomLeft => BOTTOM | LEFT,
+            Piece::BottomRight => BOTTOM | RIGHT,
+        }
+    }
+}
+
+/// The piece in every white cell of a solved puzzle, with `None` for black cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    pieces: Grid<Option<Piece>>,
+}
+
+impl Solution {
+    pub fn piece(&self, location: Location) -> Option<Piece> {
+        self.pieces[location]
+    }
+}
+
+/// Black cells are shown as `#`.
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.pieces.rows() {
+            let row = row
+                .map(|piece| piece.map_or('#', Piece::to_char))
+                .collect::<String>();
+            writeln!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether the white areas around a corner of the grid can belong to rectangles, given the shaded quarters of the
+/// cells to its top left, top right, bottom left and bottom right.
+///
+/// The quarters touching the corner split the space around it into eight 45 degree sectors. Every run of white
+/// sectors between shaded ones must make up a right angle or a straight edge, since anything else would be a corner
+/// that no rectangle has. A white area whose corners all pass this test is a rectangle.
+fn fits_corner([top_left, top_right, bottom_left, bottom_right]: [u8; 4]) -> bool {
+    let shaded = [
+        top_right & LEFT,
+        top_right & BOTTOM,
+        bottom_right & TOP,
+        bottom_right & LEFT,
+        bottom_left & RIGHT,
+        bottom_left & TOP,
+        top_left & BOTTOM,
+        top_left & RIGHT,
+    ]
+    .map(|quarter| quarter != 0);
+    let Some(start) = shaded.iter().position(|&shaded| shaded) else {
+        return true;
+    };
+    let mut run = 0;
+    for offset in 1..=shaded.len() {
+        if shaded[(start + offset) % shaded.len()] {
+            if ![0, 2, 4].contains(&run) {
+                return false;
+            }
+            run = 0;
+        } else {
+            run += 1;
+        }
+    }
+    true
+}
+
+#[derive(Clone, Debug)]
+struct State<'a> {
+    board: &'a Board,
+    /// The pieces each white cell may still hold, one bit per piece. Unused for black cells.
+    pieces: Grid<u8>,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board) -> Self {
+        let all = Piece::ALL.iter().fold(0, |bits, piece| bits | piece.bit());
+        Self {
+            board,
+            pieces: Grid::from_elem(board.dim(), all),
+        }
+    }
+
+    fn is_white(&self, location: Location) -> bool {
+        self.board.cell(location) == Cell::White
+    }
+
+    fn options(&self, location: Location) -> impl Iterator<Item = Piece> + '_ {
+        let bits = self.pieces[location];
+        Piece::ALL
+            .into_iter()
+            .filter(move |piece| bits & piece.bit() != 0)
+    }
+
+    /// Keeps only the pieces in `bits` at `location`, returning whether anything changed.
+    /// Fails if no pieces are left.
+    fn restrict(&mut self, location: Location, bits: u8) -> Result<bool, Contradiction> {
+        let pieces = &mut self.pieces[location];
+        let restricted = *pieces & bits;
+        if restricted == 0 {
+            return Err(Contradiction);
+        }
+        let changed = restricted != *pieces;
+        *pieces = restricted;
+        Ok(changed)
+    }
+
+    /// Every number must count exactly that many triangles among the adjacent white cells.
+    fn apply_numbers(&mut self) -> Result<bool, Contradiction> {
+        let dim = self.board.dim();
+        let empty = Piece::Empty.bit();
+        let mut changed = false;
+        for loc in Location::grid_iter(dim) {
+            let Cell::Black(Some(number)) = self.board.cell(loc) else {
+                continue;
+            };
+            let adjacent = loc
+                .adjacents(dim)
+                .into_iter()
+                .flatten()
+                .filter(|&adj| self.is_white(adj))
+                .collect::<Vec<_>>();
+            let bits = |adj: Location| self.pieces[adj];
+            let triangles = adjacent
+                .iter()
+                .filter(|&&adj| bits(adj) & empty == 0)
+                .count();
+            let undecided = adjacent
+                .iter()
+                .copied()
+                .filter(|&adj| bits(adj) & empty != 0 && bits(adj) != empty)
+                .collect::<Vec<_>>();
+            let number = number as usize;
+            if triangles > number || triangles + undecided.len() < number {
+                return Err(Contradiction);
+            }
+            let keep = if triangles == number {
+                empty
+            } else if triangles + undecided.len() == number {
+                !empty
+            } else {
+                continue;
+            };
+            for adj in undecided {
+                changed |= self.restrict(adj, keep)?;
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Keeps only the pieces that fit some choice of pieces in the other cells around each corner of the grid.
+    fn apply_corners(&mut self) -> Result<bool, Contradiction> {
+        let (height, width) = self.board.dim();
+        let mut changed = false;
+        for row in 0..=height {
+            for col in 0..=width {
+                let cells = [(0, 0), (0, 1), (1, 0), (1, 1)].map(|(dr, dc)| {
+                    let cell =
+                        Location::new((row + dr).checked_sub(1)?, (col + dc).checked_sub(1)?);
+                    (cell.row < height && cell.col < width && self.is_white(cell)).then_some(cell)
+                });
+                // `None` stands for a solid black cell or the outside of the grid.
+                let choices = cells.map(|cell| match cell {
+                    Some(cell) => self.options(cell).map(Some).collect(),
+                    None => vec![None],
+                });
+                let mut supported = [0; 4];
+                for &top_left in choices[0].iter() {
+                    for &top_right in choices[1].iter() {
+                        for &bottom_left in choices[2].iter() {
+                            for &bottom_right in choices[3].iter() {
+                                let pieces = [top_left, top_right, bottom_left, bottom_right];
+                                if !fits_corner(
+                                    pieces.map(|piece| piece.map_or(SOLID, Piece::shaded)),
+                                ) {
+                                    continue;
+                                }
+                                for (supported, piece) in supported.iter_mut().zip(pieces) {
+                                    *supported |= piece.map_or(0, Piece::bit);
+                                }
+                            }
+                        }
+                    }
+                }
+                for (cell, supported) in cells.into_iter().zip(supported) {
+                    if let Some(cell) = cell {
+                        changed |= self.restrict(cell, supported)?;
+                    }
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    fn into_solution(self) -> Solution {
+        let pieces = Grid::from_fn(self.board.dim(), |location| {
+            self.is_white(location)
+                .then(|| self.options(location).next().unwrap())
+        });
+        Solution { pieces }
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.apply_numbers()?;
+            changed |= self.apply_corners()?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(location) = Location::grid_iter(self.board.dim())
+            .filter(|&loc| self.is_white(loc) && self.options(loc).nth(1).is_some())
+            .min_by_key(|&loc| self.options(loc).count())
+        else {
+            return vec![];
+        };
+        self.options(location)
+            .map(|piece| {
+                let mut pieces = self.pieces.clone();
+                pieces[location] = piece.bit();
+                Self {
+                    pieces,
+                    ..self.clone()
+                }
+            })
+            .collect()
+    }
+}
+
+pub fn solve(board: &Board) -> Option<Solution> {
+    search::solve(State::new(board)).map(State::into_solution)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    search::count_solutions(State::new(board), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::location::Direction;
+
+    #[test]
+    fn corners_allow_only_right_angles_and_edges() {
+        let [empty, top_left, top_right] =
+            [Piece::Empty, Piece::TopLeft, Piece::TopRight].map(Piece::shaded);
+        // Inside a white area, at the corner of an upright rectangle, and along its edge.
+        assert!(fits_corner([empty; 4]));
+        assert!(fits_corner([SOLID, SOLID, SOLID, empty]));
+        assert!(fits_corner([SOLID, SOLID, empty, empty]));
+        // The top corner of a turned rectangle below the edge of an upright one.
+        assert!(fits_corner([empty, empty, top_left, top_right]));
+        // A white area wrapped around a black cell.
+        assert!(!fits_corner([SOLID, empty, empty, empty]));
+        // A triangle next to an empty cell leaves a 45 degree angle.
+        assert!(!fits_corner([SOLID, SOLID, top_left, empty]));
+    }
+
+    /// Every white area is a rectangle exactly when it fills its bounding box, either upright or turned 45 degrees.
+    /// The areas are made of the white quarters of the cells, measured in doubled coordinates.
+    fn white_areas_are_rectangles(solution: &Solution) -> bool {
+        let dim = solution.pieces.dim();
+        let quarters = [TOP, RIGHT, BOTTOM, LEFT];
+        let white = |loc: Location, quarter: u8| {
+            solution
+                .piece(loc)
+                .is_some_and(|piece| piece.shaded() & quarter == 0)
+        };
+        let mut seen = Grid::from_elem(dim, 0u8);
+        for start in Location::grid_iter(dim) {
+            for quarter in quarters {
+                if !white(start, quarter) || seen[start] & quarter != 0 {
+                    continue;
+                }
+                seen[start] |= quarter;
+                let mut stack = vec![(start, quarter)];
+                let mut corners = vec![];
+                let mut area = 0;
+                while let Some((loc, quarter)) = stack.pop() {
+                    let (x, y) = (2 * loc.col as isize, 2 * loc.row as isize);
+                    let edge = match quarter {
+                        TOP => [(x, y), (x + 2, y)],
+                        RIGHT => [(x + 2, y), (x + 2, y + 2)],
+                        BOTTOM => [(x, y + 2), (x + 2, y + 2)],
+                        _ => [(x, y), (x, y + 2)],
+                    };
+                    corners.extend(edge);
+                    corners.push((x + 1, y + 1));
+                    // Each quarter covers one unit of area in doubled coordinates.
+                    area += 1;
+                    let across = match quarter {
+                        TOP => loc.step(Direction::Up, dim).map(|other| (other, BOTTOM)),
+                        RIGHT => loc.step(Direction::Right, dim).map(|other| (other, LEFT)),
+                        BOTTOM => loc.step(Direction::Down, dim).map(|other| (other, TOP)),
+                        _ => loc.step(Direction::Left, dim).map(|other| (other, RIGHT)),
+                    };
+                    let beside = quarters
+                        .into_iter()
+                        .filter(|&other| {
+                            other != quarter && other != (quarter << 2 | quarter >> 2) & SOLID
+                        })
+                        .map(|other| (loc, other));
+                    for (next, next_quarter) in beside.chain(across) {
+                        if white(next, next_quarter) && seen[next] & next_quarter == 0 {
+                            seen[next] |= next_quarter;
+                            stack.push((next, next_quarter));
+                        }
+                    }
+                }
+                let extent = |values: Vec<isize>| {
+                    values.iter().max().unwrap() - values.iter().min().unwrap()
+                };
+                let upright = extent(corners.iter().map(|&(x, _)| x).collect())
+                    * extent(corners.iter().map(|&(_, y)| y).collect());
+                let turned = extent(corners.iter().map(|&(x, y)| x + y).collect())
+                    * extent(corners.iter().map(|&(x, y)| x - y).collect())
+                    / 2;
+                if area != upright && area != turned {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    fn follows_rules(board: &Board, solution: &Solution) -> bool {
+        let dim = board.dim();
+        let cells = Location::grid_iter(dim).all(|loc| match board.cell(loc) {
+            Cell::White => solution.piece(loc).is_some(),
+            Cell::Black(number) => {
+                let triangles = loc
+                    .adjacents(dim)
+                    .into_iter()
+                    .flatten()
+                    .filter(|&adj| {
+                        solution
+                            .piece(adj)
+                            .is_some_and(|piece| piece != Piece::Empty)
+                    })
+                    .count();
+                solution.piece(loc).is_none()
+                    && number.is_none_or(|number| triangles == number as usize)
+            }
+        });
+        cells && white_areas_are_rectangles(solution)
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board =
+            Board::parse(include_str!("../../data/shakashaka/puzzles/eight01.txt")).unwrap();
+        let solution = solve(&board).unwrap();
+        assert!(follows_rules(&board, &solution));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
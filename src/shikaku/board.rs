@@ -0,0 +1,136 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+};
+
+/// A shikaku puzzle: divide the grid into rectangles such that every rectangle contains exactly one number,
+/// which is equal to its area.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    numbers: Grid<Option<u32>>,
+}
+
+impl Board {
+    pub fn new(numbers: Grid<Option<u32>>) -> Result<Self> {
+        let (height, width) = numbers.dim();
+        ensure!(
+            height > 0 && width > 0,
+            Invalid,
+            "Shikaku grids must have at least one cell."
+        );
+        ensure!(
+            numbers.iter().flatten().all(|&number| number > 0),
+            Invalid,
+            "Numbers must be positive."
+        );
+        let area = numbers.iter().flatten().sum::<u32>() as usize;
+        ensure!(
+            area == height * width,
+            Invalid,
+            "The numbers must add up to the {} cells of the grid, but add up to {area}.",
+            height * width
+        );
+        Ok(Self { numbers })
+    }
+
+    /// Parses a grid of numbers with `.` for empty cells. Rows are either strings of digits and dots or,
+    /// for numbers above 9, whitespace separated numbers and dots.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let parse_token = |token: &str| -> Result<Option<u32>> {
+            match token {
+                "." => Ok(None),
+                _ => token
+                    .parse()
+                    .map(Some)
+                    .map_err(|_| Error::Parse(format!("Invalid cell '{token}'."))),
+            }
+        };
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                if line.contains(char::is_whitespace) {
+                    line.split_whitespace().map(parse_token).collect()
+                } else {
+                    line.chars()
+                        .map(|c| parse_token(c.encode_utf8(&mut [0; 4])))
+                        .collect::<Result<Vec<_>>>()
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        let mut numbers = Grid::from_elem((height, width), None);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} cells in row {row_index}. Got {}.",
+                    row.len()
+                );
+            }
+            for (col_index, number) in row.into_iter().enumerate() {
+                numbers[Location::new(row_index, col_index)] = number;
+            }
+        }
+        Self::new(numbers)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("shikaku", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.numbers.dim()
+    }
+
+    pub fn number(&self, location: Location) -> Option<u32> {
+        self.numbers[location]
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let wide = self.numbers.iter().flatten().any(|&number| number > 9);
+        for row in self.numbers.rows() {
+            for (index, number) in row.enumerate() {
+                if wide && index > 0 {
+                    write!(f, " ")?;
+                }
+                match number {
+                    Some(number) => write!(f, "{number}")?,
+                    None => write!(f, ".")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_digits_and_wide_numbers() {
+        let board = Board::parse(include_str!("../../data/shikaku/puzzles/ten01.txt")).unwrap();
+        assert_eq!(board.dim(), (10, 10));
+        assert_eq!(board.number(Location::new(0, 3)), Some(4));
+        assert_eq!(board.number(Location::new(0, 0)), None);
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        let wide = Board::parse(". . . . . . 12\n. . . . . . .\n2 . . . . . .\n").unwrap_err();
+        assert!(matches!(wide, Error::Invalid(_)));
+        let wide = Board::parse(". . . . . 10\n. . . . . .\n. . . . . 8\n").unwrap();
+        assert_eq!(wide.number(Location::new(0, 5)), Some(10));
+        assert_eq!(Board::parse(wide.to_string()).unwrap(), wide);
+        assert!(Board::parse("4.\n.\n").is_err());
+    }
+}
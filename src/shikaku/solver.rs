@@ -0,0 +1,153 @@
+use std::fmt::Display;
+
+use crate::{exact_cover::ExactCover, grid::Grid, location::Location};
+
+use super::Board;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rectangle {
+    pub top_left: Location,
+    pub height: usize,
+    pub width: usize,
+}
+
+impl Rectangle {
+    pub fn cells(self) -> impl Iterator<Item = Location> {
+        let Location { row, col } = self.top_left;
+        (row..row + self.height)
+            .flat_map(move |row| (col..col + self.width).map(move |col| Location::new(row, col)))
+    }
+}
+
+/// Every rectangle of the given area that contains `clue` and no other number.
+fn candidates(board: &Board, clue: Location, area: usize) -> Vec<Rectangle> {
+    let (rows, cols) = board.dim();
+    let mut candidates = vec![];
+    for height in (1..=area).filter(|height| area.is_multiple_of(*height)) {
+        let width = area / height;
+        if height > rows || width > cols {
+            continue;
+        }
+        let top_rows = clue.row.saturating_sub(height - 1)..=clue.row.min(rows - height);
+        for top in top_rows {
+            let left_cols = clue.col.saturating_sub(width - 1)..=clue.col.min(cols - width);
+            for left in left_cols {
+                let rectangle = Rectangle {
+                    top_left: Location::new(top, left),
+                    height,
+                    width,
+                };
+                if rectangle
+                    .cells()
+                    .all(|loc| loc == clue || board.number(loc).is_none())
+                {
+                    candidates.push(rectangle);
+                }
+            }
+        }
+    }
+    candidates
+}
+
+/// The rectangles of a board as an exact cover problem, where the items are the cells and then the numbers.
+fn problem(board: &Board) -> (ExactCover, Vec<Rectangle>) {
+    let dim = board.dim();
+    let clues = Location::grid_iter(dim)
+        .filter_map(|loc| board.number(loc).map(|number| (loc, number as usize)))
+        .collect::<Vec<_>>();
+    let num_cells = dim.0 * dim.1;
+    let mut problem = ExactCover::new(num_cells + clues.len());
+    let mut rectangles = vec![];
+    for (index, &(clue, area)) in clues.iter().enumerate() {
+        for rectangle in candidates(board, clue, area) {
+            let mut items = rectangle
+                .cells()
+                .map(|loc| loc.row * dim.1 + loc.col)
+                .collect::<Vec<_>>();
+            items.push(num_cells + index);
+            problem.add_option(items);
+            rectangles.push(rectangle);
+        }
+    }
+    (problem, rectangles)
+}
+
+/// The rectangles of a solved puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    dim: (usize, usize),
+    rectangles: Vec<Rectangle>,
+}
+
+impl Solution {
+    pub fn rectangles(&self) -> &[Rectangle] {
+        &self.rectangles
+    }
+}
+
+/// Every cell is shown by a letter or digit naming its rectangle. Names repeat after 62 rectangles.
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const NAMES: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+        let mut names = Grid::from_elem(self.dim, '.');
+        for (index, rectangle) in self.rectangles.iter().enumerate() {
+            for loc in rectangle.cells() {
+                names[loc] = NAMES[index % NAMES.len()] as char;
+            }
+        }
+        for row in names.rows() {
+            writeln!(f, "{}", row.collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+pub fn solve(board: &Board) -> Option<Solution> {
+    let (problem, rectangles) = problem(board);
+    let mut chosen = problem.solve()?;
+    chosen.sort();
+    Some(Solution {
+        dim: board.dim(),
+        rectangles: chosen.into_iter().map(|index| rectangles[index]).collect(),
+    })
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    problem(board).0.count_solutions(limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follows_rules(board: &Board, solution: &Solution) -> bool {
+        let (height, width) = board.dim();
+        let mut covered = Grid::from_elem((height, width), 0);
+        for &rectangle in solution.rectangles() {
+            let Location { row, col } = rectangle.top_left;
+            if row + rectangle.height > height || col + rectangle.width > width {
+                return false;
+            }
+            let numbers = rectangle
+                .cells()
+                .filter_map(|loc| board.number(loc))
+                .collect::<Vec<_>>();
+            if numbers != [(rectangle.height * rectangle.width) as u32] {
+                return false;
+            }
+            for loc in rectangle.cells() {
+                covered[loc] += 1;
+            }
+        }
+        let all_once = covered.iter().all(|&count| count == 1);
+        all_once
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/shikaku/puzzles/ten01.txt")).unwrap();
+        let solution = solve(&board).unwrap();
+        assert!(follows_rules(&board, &solution));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
@@ -0,0 +1,244 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+};
+
+/// The clues around a skyscrapers grid, each given in reading order.
+/// A clue is the number of buildings visible from that side of the row or column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Clues {
+    pub top: Vec<Option<u8>>,
+    pub bottom: Vec<Option<u8>>,
+    pub left: Vec<Option<u8>>,
+    pub right: Vec<Option<u8>>,
+}
+
+/// A row or column as seen from its front clue, together with the clues at both ends.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SightLine {
+    pub cells: Vec<Location>,
+    pub front: Option<u8>,
+    pub back: Option<u8>,
+}
+
+/// A skyscrapers puzzle: a Latin square of building heights where the clues around the edge
+/// give how many buildings can be seen from that direction, as taller buildings hide shorter ones behind them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    clues: Clues,
+    givens: Grid<Option<u8>>,
+}
+
+impl Board {
+    pub const MIN_SIZE: usize = 4;
+    pub const MAX_SIZE: usize = 9;
+
+    pub fn new(clues: Clues, givens: Grid<Option<u8>>) -> Result<Self> {
+        let (height, width) = givens.dim();
+        ensure!(
+            height == width,
+            Invalid,
+            "Skyscrapers grids must be square, but got {height}x{width}."
+        );
+        let size = height;
+        ensure!(
+            (Self::MIN_SIZE..=Self::MAX_SIZE).contains(&size),
+            Invalid,
+            "Skyscrapers grids must be between {}x{} and {}x{}, but got {size}x{size}.",
+            Self::MIN_SIZE,
+            Self::MIN_SIZE,
+            Self::MAX_SIZE,
+            Self::MAX_SIZE
+        );
+        for (side, side_clues) in [
+            ("top", &clues.top),
+            ("bottom", &clues.bottom),
+            ("left", &clues.left),
+            ("right", &clues.right),
+        ] {
+            ensure!(
+                side_clues.len() == size,
+                Invalid,
+                "Expected {size} {side} clues, but got {}.",
+                side_clues.len()
+            );
+            if let Some(clue) = side_clues
+                .iter()
+                .flatten()
+                .find(|&&clue| clue == 0 || clue as usize > size)
+            {
+                bail!(
+                    Invalid,
+                    "The {side} clue {clue} is not between 1 and {size}."
+                );
+            }
+        }
+        if let Some(given) = givens
+            .iter()
+            .flatten()
+            .find(|&&given| given == 0 || given as usize > size)
+        {
+            bail!(
+                Invalid,
+                "The given height {given} is not between 1 and {size}."
+            );
+        }
+        Ok(Self { clues, givens })
+    }
+
+    /// Parses a grid surrounded by its clues, with `.` for missing clues and empty cells.
+    /// The corners are ignored. A 4x4 puzzle looks like
+    /// ```text
+    /// ...3..
+    /// ......
+    /// ......
+    /// .....2
+    /// 2.....
+    /// ..3.3.
+    /// ```
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| line.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        ensure!(
+            rows.len() >= 3,
+            Parse,
+            "Expected at least 3 lines. Got {}.",
+            rows.len()
+        );
+        let size = rows.len() - 2;
+        let parse_char = |c: char, row: usize, col: usize| -> Result<Option<u8>> {
+            match c {
+                '.' => Ok(None),
+                _ => c
+                    .to_digit(10)
+                    .map(|digit| Some(digit as u8))
+                    .ok_or_else(|| {
+                        Error::Parse(format!("Invalid character '{c}' at ({row}, {col})."))
+                    }),
+            }
+        };
+        let mut clues = Clues {
+            top: Vec::with_capacity(size),
+            bottom: Vec::with_capacity(size),
+            left: Vec::with_capacity(size),
+            right: Vec::with_capacity(size),
+        };
+        let mut givens = Grid::from_elem((size, size), None);
+        for (row_index, row) in rows.iter().enumerate() {
+            if row.len() != size + 2 {
+                bail!(
+                    Parse,
+                    "Expected {} characters in line {row_index}. Got '{}'.",
+                    size + 2,
+                    row.iter().collect::<String>()
+                );
+            }
+            for (col_index, &c) in row.iter().enumerate() {
+                let is_row_border = row_index == 0 || row_index == size + 1;
+                let is_col_border = col_index == 0 || col_index == size + 1;
+                if is_row_border && is_col_border {
+                    continue;
+                }
+                let value = parse_char(c, row_index, col_index)?;
+                if row_index == 0 {
+                    clues.top.push(value);
+                } else if row_index == size + 1 {
+                    clues.bottom.push(value);
+                } else if col_index == 0 {
+                    clues.left.push(value);
+                } else if col_index == size + 1 {
+                    clues.right.push(value);
+                } else {
+                    givens[Location::new(row_index - 1, col_index - 1)] = value;
+                }
+            }
+        }
+        Self::new(clues, givens)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("skyscrapers", path)?;
+        Self::parse(string)
+    }
+
+    pub fn size(&self) -> usize {
+        self.givens.height()
+    }
+
+    pub fn clues(&self) -> &Clues {
+        &self.clues
+    }
+
+    pub fn given(&self, location: Location) -> Option<u8> {
+        self.givens[location]
+    }
+
+    /// Every column seen from the top and every row seen from the left.
+    pub fn sight_lines(&self) -> Vec<SightLine> {
+        let size = self.size();
+        let cols = (0..size).map(|col| SightLine {
+            cells: (0..size).map(|row| Location::new(row, col)).collect(),
+            front: self.clues.top[col],
+            back: self.clues.bottom[col],
+        });
+        let rows = (0..size).map(|row| SightLine {
+            cells: (0..size).map(|col| Location::new(row, col)).collect(),
+            front: self.clues.left[row],
+            back: self.clues.right[row],
+        });
+        cols.chain(rows).collect()
+    }
+}
+
+fn clue_char(clue: Option<u8>) -> char {
+    clue.map_or('.', |clue| (b'0' + clue) as char)
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let write_border = |f: &mut std::fmt::Formatter<'_>, clues: &[Option<u8>]| {
+            write!(f, ".")?;
+            for &clue in clues {
+                write!(f, "{}", clue_char(clue))?;
+            }
+            writeln!(f, ".")
+        };
+        write_border(f, &self.clues.top)?;
+        for (row_index, row) in self.givens.rows().enumerate() {
+            write!(f, "{}", clue_char(self.clues.left[row_index]))?;
+            for &given in row {
+                write!(f, "{}", clue_char(given))?;
+            }
+            writeln!(f, "{}", clue_char(self.clues.right[row_index]))?;
+        }
+        write_border(f, &self.clues.bottom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_clues_around_the_grid() {
+        let board = Board::parse(include_str!("../../data/skyscrapers/puzzles/six01.txt")).unwrap();
+        assert_eq!(board.size(), 6);
+        assert_eq!(
+            board.clues().top,
+            [None, Some(2), None, Some(4), Some(2), None]
+        );
+        assert_eq!(board.clues().left[0], Some(2));
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        assert!(Board::parse("..\n..\n").is_err());
+        assert!(Board::parse("......\n.x....\n......\n......\n......\n......\n").is_err());
+    }
+}
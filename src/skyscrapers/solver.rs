@@ -0,0 +1,241 @@
+use crate::{
+    latin::LatinSquare,
+    location::Location,
+    search::{self, Contradiction, SearchState},
+    value_set::ValueSet,
+};
+
+use super::{Board, SightLine};
+
+/// Lines with more possible arrangements than this are left to the cheaper deductions and to guessing.
+const MAX_ARRANGEMENTS: usize = 20_000;
+
+/// The number of buildings visible when looking along `heights` from the front.
+fn visible(heights: impl IntoIterator<Item = u8>) -> u8 {
+    let mut tallest = 0;
+    let mut count = 0;
+    for height in heights {
+        if height > tallest {
+            tallest = height;
+            count += 1;
+        }
+    }
+    count
+}
+
+/// Restricts the cells of `cells` using only the clue at the front.
+///
+/// With `clue` buildings visible, the building `k` cells from the front has at least `clue - 1 - k` taller
+/// buildings behind it, so it can be at most `size - clue + 1 + k` tall.
+fn apply_clue_bounds(
+    square: &mut LatinSquare,
+    cells: &[Location],
+    clue: u8,
+) -> Result<bool, Contradiction> {
+    let size = cells.len() as u8;
+    let mut changed = false;
+    if clue == 1 {
+        changed |= square.assign(cells[0], size)?;
+    }
+    for (distance, &loc) in cells.iter().enumerate() {
+        let max = (size + 1 + distance as u8).saturating_sub(clue).min(size);
+        changed |= square.restrict(loc, ValueSet::range(1, max))?;
+    }
+    Ok(changed)
+}
+
+struct LineSearch<'a> {
+    candidates: &'a [ValueSet],
+    front: Option<u8>,
+    back: Option<u8>,
+    heights: Vec<u8>,
+    support: Vec<ValueSet>,
+}
+
+impl LineSearch<'_> {
+    fn extend(&mut self, used: ValueSet, tallest: u8, seen: u8) {
+        let index = self.heights.len();
+        if index == self.candidates.len() {
+            if self.front.is_none_or(|front| front == seen)
+                && self
+                    .back
+                    .is_none_or(|back| back == visible(self.heights.iter().rev().copied()))
+            {
+                for (support, &height) in self.support.iter_mut().zip(self.heights.iter()) {
+                    support.insert(height);
+                }
+            }
+            return;
+        }
+        let remaining = self.candidates.len() - index - 1;
+        for height in (self.candidates[index] - used).iter() {
+            let tallest = tallest.max(height);
+            let seen = seen + u8::from(height == tallest);
+            if let Some(front) = self.front {
+                // At most every remaining building taller than the current tallest can still come into view.
+                let taller = (ValueSet::range(tallest + 1, ValueSet::MAX_VALUE) - used).len();
+                if seen > front || (seen as usize + remaining.min(taller)) < front as usize {
+                    continue;
+                }
+            }
+            self.heights.push(height);
+            self.extend(used | ValueSet::from_value(height), tallest, seen);
+            self.heights.pop();
+        }
+    }
+}
+
+/// The heights each cell of a line can take in some arrangement that satisfies both clues,
+/// or `None` if the line has too many arrangements to check.
+fn line_support(
+    candidates: &[ValueSet],
+    front: Option<u8>,
+    back: Option<u8>,
+) -> Option<Vec<ValueSet>> {
+    let arrangements = candidates
+        .iter()
+        .try_fold(1usize, |product, cell| product.checked_mul(cell.len()))?;
+    if arrangements > MAX_ARRANGEMENTS {
+        return None;
+    }
+    let mut search = LineSearch {
+        candidates,
+        front,
+        back,
+        heights: Vec::with_capacity(candidates.len()),
+        support: vec![ValueSet::NONE; candidates.len()],
+    };
+    search.extend(ValueSet::NONE, 0, 0);
+    Some(search.support)
+}
+
+/// Removes every height that cannot appear in an arrangement of the line matching both of its clues.
+fn apply_visibility(square: &mut LatinSquare, line: &SightLine) -> Result<bool, Contradiction> {
+    let mut changed = false;
+    if let Some(front) = line.front {
+        changed |= apply_clue_bounds(square, &line.cells, front)?;
+    }
+    if let Some(back) = line.back {
+        let reversed = line.cells.iter().rev().copied().collect::<Vec<_>>();
+        changed |= apply_clue_bounds(square, &reversed, back)?;
+    }
+    let candidates = line
+        .cells
+        .iter()
+        .map(|&loc| square.candidates(loc))
+        .collect::<Vec<_>>();
+    if let Some(support) = line_support(&candidates, line.front, line.back) {
+        for (&loc, support) in line.cells.iter().zip(support) {
+            changed |= square.restrict(loc, support)?;
+        }
+    }
+    Ok(changed)
+}
+
+#[derive(Clone, Debug)]
+struct State<'a> {
+    lines: &'a [SightLine],
+    square: LatinSquare,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &Board, lines: &'a [SightLine]) -> Result<Self, Contradiction> {
+        let mut square = LatinSquare::new(board.size());
+        for loc in Location::grid_iter(square.dim()) {
+            if let Some(given) = board.given(loc) {
+                square.assign(loc, given)?;
+            }
+        }
+        Ok(Self { lines, square })
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.square.propagate()?;
+            for line in self.lines {
+                changed |= apply_visibility(&mut self.square, line)?;
+            }
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(location) = self.square.branch_location() else {
+            return vec![];
+        };
+        self.square
+            .branch_on(location)
+            .into_iter()
+            .map(|square| Self {
+                square,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+/// Returns the solved grid, or `None` if the puzzle has no solution.
+pub fn solve(board: &Board) -> Option<LatinSquare> {
+    let lines = board.sight_lines();
+    let state = State::new(board, &lines).ok()?;
+    search::solve(state).map(|state| state.square)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    let lines = board.sight_lines();
+    State::new(board, &lines).map_or(0, |state| search::count_solutions(state, limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visible_counts_taller_buildings() {
+        assert_eq!(visible([1, 2, 3, 4]), 4);
+        assert_eq!(visible([4, 3, 2, 1]), 1);
+        assert_eq!(visible([2, 1, 4, 3]), 2);
+    }
+
+    #[test]
+    fn line_support_uses_both_clues() {
+        let all = ValueSet::all(4);
+        // Seeing 3 from the front and 2 from the back of a 4 cell line forces the 4 into the third cell.
+        let support = line_support(&[all; 4], Some(3), Some(2)).unwrap();
+        assert_eq!(support[2], ValueSet::from_value(4));
+        assert!(!support[0].contains(4));
+        assert!(!support[0].contains(3));
+    }
+
+    #[test]
+    fn solves_data_puzzles() {
+        for input in [
+            include_str!("../../data/skyscrapers/puzzles/six01.txt"),
+            include_str!("../../data/skyscrapers/puzzles/nine01.txt"),
+        ] {
+            let board = Board::parse(input).unwrap();
+            let values = solve(&board).unwrap().values().unwrap();
+            assert!(crate::latin::is_latin(&values));
+            assert!(Location::grid_iter(values.dim())
+                .all(|loc| board.given(loc).is_none_or(|given| given == values[loc])));
+            for line in board.sight_lines() {
+                let heights = line
+                    .cells
+                    .iter()
+                    .map(|&loc| values[loc])
+                    .collect::<Vec<_>>();
+                assert!(line
+                    .front
+                    .is_none_or(|clue| clue == visible(heights.iter().copied())));
+                assert!(line
+                    .back
+                    .is_none_or(|clue| clue == visible(heights.into_iter().rev())));
+            }
+            assert_eq!(count_solutions(&board, 2), 1);
+        }
+    }
+}
@@ -0,0 +1,22 @@
+mod board;
+pub use board::Board;
+mod solver;
+pub use solver::{solve, solve_with, Move, Solution};
+
+/// Sliding puzzles as a [`Puzzle`](crate::puzzle::Puzzle), in the text format of [`Board::parse`].
+pub struct Sliding;
+
+impl crate::puzzle::Puzzle for Sliding {
+    const NAME: &'static str = "sliding puzzle";
+
+    type Instance = Board;
+    type Solution = Solution;
+
+    fn parse(input: &str) -> crate::Result<Board> {
+        Board::parse(input)
+    }
+
+    fn solve(board: &Board) -> crate::Result<Option<Solution>> {
+        Ok(solve(board))
+    }
+}
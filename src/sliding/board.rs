@@ -0,0 +1,160 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+};
+
+/// A sliding tile puzzle such as the 15 puzzle: tiles numbered from 1 slide into the one empty square until they are
+/// in order, row by row, with the empty square last.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    /// The tile on each square, with 0 for the empty square.
+    tiles: Grid<u32>,
+}
+
+impl Board {
+    /// The most squares a board may have, so that a square fits in a byte.
+    pub const MAX_SQUARES: usize = 256;
+
+    pub fn new(tiles: Grid<u32>) -> Result<Self> {
+        let (height, width) = tiles.dim();
+        ensure!(
+            height >= 2 && width >= 2,
+            Invalid,
+            "Sliding puzzles must be at least 2x2, but got {height}x{width}."
+        );
+        let squares = height * width;
+        ensure!(
+            squares <= Self::MAX_SQUARES,
+            Invalid,
+            "Sliding puzzles may have at most {} squares, but got {squares}.",
+            Self::MAX_SQUARES
+        );
+        let mut seen = vec![false; squares];
+        for (location, &tile) in tiles.indexed_iter() {
+            ensure!(
+                (tile as usize) < squares,
+                Invalid,
+                "The tile {tile} at {location} is not between 1 and {}.",
+                squares - 1
+            );
+            ensure!(
+                !seen[tile as usize],
+                Invalid,
+                "The tile {tile} appears more than once."
+            );
+            seen[tile as usize] = true;
+        }
+        Ok(Self { tiles })
+    }
+
+    /// The solved board of the given dimensions.
+    pub fn solved(dim: (usize, usize)) -> Result<Self> {
+        let squares = dim.0 * dim.1;
+        Self::new(Grid::from_fn(dim, |loc| {
+            ((loc.row * dim.1 + loc.col + 1) % squares) as u32
+        }))
+    }
+
+    /// Parses rows of whitespace separated tiles, where `.` or `0` is the empty square.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| line.split_whitespace().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        let mut tiles = Grid::from_elem((height, width), 0);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} tiles in row {row_index}. Got {}.",
+                    row.len()
+                );
+            }
+            for (col_index, token) in row.into_iter().enumerate() {
+                tiles[Location::new(row_index, col_index)] = match token {
+                    "." => 0,
+                    _ => token.parse().map_err(|_| {
+                        Error::Parse(format!(
+                            "Invalid tile '{token}' at ({row_index}, {col_index})."
+                        ))
+                    })?,
+                };
+            }
+        }
+        Self::new(tiles)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("sliding puzzle", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.tiles.dim()
+    }
+
+    /// The tile on `location`, or `None` for the empty square.
+    pub fn tile(&self, location: Location) -> Option<u32> {
+        let tile = self.tiles[location];
+        (tile != 0).then_some(tile)
+    }
+
+    pub fn empty(&self) -> Location {
+        Location::grid_iter(self.dim())
+            .find(|&loc| self.tile(loc).is_none())
+            .expect("Boards have an empty square.")
+    }
+
+    /// Whether the tiles can be put in order. Every move swaps the empty square with a tile, so the parity of the
+    /// permutation of the squares changes with every move, as does the parity of the empty square's distance from
+    /// its place.
+    pub fn is_solvable(&self) -> bool {
+        let (height, width) = self.dim();
+        let tiles = self.tiles.iter().copied().collect::<Vec<_>>();
+        let mut visited = vec![false; tiles.len()];
+        // A permutation is odd exactly when its number of squares minus its number of cycles is.
+        let mut cycles = 0;
+        for start in 0..tiles.len() {
+            if visited[start] {
+                continue;
+            }
+            cycles += 1;
+            let mut square = start;
+            while !visited[square] {
+                visited[square] = true;
+                // The tile on `square` belongs on the square before the one numbered by it, and the empty square last.
+                square = (tiles[square] as usize + tiles.len() - 1) % tiles.len();
+            }
+        }
+        let empty = self.empty();
+        let distance = (height - 1 - empty.row) + (width - 1 - empty.col);
+        (tiles.len() - cycles) % 2 == distance % 2
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (height, width) = self.dim();
+        let digits = (height * width - 1).to_string().len();
+        for row in self.tiles.rows() {
+            let row = row
+                .map(|&tile| match tile {
+                    0 => format!("{:>digits$}", "."),
+                    _ => format!("{tile:>digits$}"),
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
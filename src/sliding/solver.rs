@@ -0,0 +1,278 @@
+use std::fmt::Display;
+
+use crate::{
+    location::{Direction, Location},
+    pattern_database::PatternDatabase,
+};
+
+use super::Board;
+
+/// The most abstract states a pattern database is built over when the group size is chosen automatically.
+const MAX_DATABASE_STATES: usize = 1_000_000;
+
+/// Positions of up to eight squares, a byte each.
+fn pack(squares: impl IntoIterator<Item = usize>) -> u64 {
+    squares
+        .into_iter()
+        .enumerate()
+        .fold(0, |packed, (index, square)| {
+            packed | (square as u64) << (8 * index)
+        })
+}
+
+fn unpack(packed: u64, index: usize) -> usize {
+    (packed >> (8 * index) & 0xff) as usize
+}
+
+/// A group of tiles with the fewest moves of those tiles needed to put them in place from every arrangement.
+/// As a move only moves one tile, the moves needed by disjoint groups add up to a lower bound for the whole board.
+#[derive(Clone, Debug)]
+struct Group {
+    tiles: Vec<u32>,
+    database: PatternDatabase<u64>,
+}
+
+impl Group {
+    fn new(dim: (usize, usize), tiles: Vec<u32>) -> Self {
+        let squares = dim.0 * dim.1;
+        let cell = |location: Location| location.row * dim.1 + location.col;
+        let location = |square: usize| Location::new(square / dim.1, square % dim.1);
+        // The tiles of the group followed by the empty square.
+        let goal = pack(
+            tiles
+                .iter()
+                .map(|&tile| tile as usize - 1)
+                .chain([squares - 1]),
+        );
+        let count = tiles.len();
+        let database = PatternDatabase::build([goal], |&state| {
+            let empty = unpack(state, count);
+            location(empty)
+                .adjacents(dim)
+                .into_iter()
+                .flatten()
+                .map(|next| {
+                    let next = cell(next);
+                    let moved = (0..count).find(|&index| unpack(state, index) == next);
+                    let squares = (0..=count).map(|index| match index {
+                        _ if index == count => next,
+                        _ if Some(index) == moved => empty,
+                        _ => unpack(state, index),
+                    });
+                    // Moving a tile from another group costs nothing here, as that group counts it.
+                    (pack(squares), u32::from(moved.is_some()))
+                })
+                .collect()
+        })
+        .project(|&state| state & !(0xff << (8 * count)));
+        Self { tiles, database }
+    }
+
+    fn moves_needed(&self, squares: &[usize]) -> u32 {
+        let state = pack(self.tiles.iter().map(|&tile| squares[tile as usize]));
+        self.database
+            .cost(&state)
+            .expect("Every arrangement of a group can be put in place.")
+    }
+}
+
+/// One move: the tile next to the empty square in a direction slides the opposite way into it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Move {
+    pub tile: u32,
+    /// The direction the tile slides in.
+    pub direction: Direction,
+}
+
+impl Move {
+    pub fn to_char(self) -> char {
+        match self.direction {
+            Direction::Up => 'U',
+            Direction::Right => 'R',
+            Direction::Down => 'D',
+            Direction::Left => 'L',
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Search<'a> {
+    dim: (usize, usize),
+    groups: &'a [Group],
+    /// The square of every tile, and of the empty square as tile 0.
+    squares: Vec<usize>,
+    /// The tile on every square.
+    tiles: Vec<u32>,
+    path: Vec<Move>,
+}
+
+impl Search<'_> {
+    fn estimate(&self) -> u32 {
+        self.groups
+            .iter()
+            .map(|group| group.moves_needed(&self.squares))
+            .sum()
+    }
+
+    /// Searches for a solution within `bound` moves in total, returning the smallest estimate over the bound
+    /// otherwise, or `None` if there is nothing left to search.
+    fn search(&mut self, bound: u32) -> Result<(), Option<u32>> {
+        let estimate = self.path.len() as u32 + self.estimate();
+        if estimate > bound {
+            return Err(Some(estimate));
+        }
+        if estimate == self.path.len() as u32 {
+            return Ok(());
+        }
+        let empty = self.squares[0];
+        let location = Location::new(empty / self.dim.1, empty % self.dim.1);
+        let mut next_bound = None;
+        for direction in Direction::ALL {
+            // Sliding the previous tile straight back is never useful.
+            if self
+                .path
+                .last()
+                .is_some_and(|last| last.direction == direction)
+            {
+                continue;
+            }
+            let Some(from) = location.step(direction, self.dim) else {
+                continue;
+            };
+            let from = from.row * self.dim.1 + from.col;
+            let tile = self.tiles[from];
+            self.slide(from, empty);
+            self.path.push(Move {
+                tile,
+                direction: direction.opposite(),
+            });
+            match self.search(bound) {
+                Ok(()) => return Ok(()),
+                Err(Some(estimate)) => {
+                    next_bound = Some(
+                        next_bound.map_or(estimate, |next_bound: u32| next_bound.min(estimate)),
+                    );
+                }
+                Err(None) => {}
+            }
+            self.path.pop();
+            self.slide(empty, from);
+        }
+        Err(next_bound)
+    }
+
+    /// Slides the tile on `from` to the empty square `to`.
+    fn slide(&mut self, from: usize, to: usize) {
+        let tile = self.tiles[from];
+        self.tiles.swap(from, to);
+        self.squares[tile as usize] = to;
+        self.squares[0] = from;
+    }
+}
+
+/// The moves that solve a sliding puzzle in as few moves as possible.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    moves: Vec<Move>,
+}
+
+impl Solution {
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+}
+
+/// The direction each tile slides in, as `U`, `R`, `D` or `L`.
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let moves = self
+            .moves
+            .iter()
+            .map(|step| step.to_char())
+            .collect::<String>();
+        writeln!(f, "{moves}")
+    }
+}
+
+/// Returns a solution with the fewest moves, or `None` if the puzzle has no solution.
+///
+/// Uses pattern databases of groups as large as can be built over at most a million arrangements.
+pub fn solve(board: &Board) -> Option<Solution> {
+    let squares = board.dim().0 * board.dim().1;
+    // The ways to place the tiles of a group and the empty square.
+    let arrangements = |group_size: usize| {
+        (squares - group_size..=squares)
+            .try_fold(1usize, |product, factor| product.checked_mul(factor))
+            .filter(|&product| product <= MAX_DATABASE_STATES)
+    };
+    let group_size = (1..=(squares - 1).min(7))
+        .take_while(|&group_size| arrangements(group_size).is_some())
+        .last()
+        .unwrap_or(1);
+    solve_with(board, group_size)
+}
+
+/// Returns a solution with the fewest moves using pattern databases of `group_size` tiles each,
+/// or `None` if the puzzle has no solution. Larger groups give a faster search but take longer to build.
+///
+/// # Panics
+/// If `group_size` is not between 1 and 7.
+pub fn solve_with(board: &Board, group_size: usize) -> Option<Solution> {
+    assert!(
+        (1..=7).contains(&group_size),
+        "Groups must have between 1 and 7 tiles, but got {group_size}."
+    );
+    if !board.is_solvable() {
+        return None;
+    }
+    let dim = board.dim();
+    let tiles = (1..(dim.0 * dim.1) as u32).collect::<Vec<_>>();
+    let groups = tiles
+        .chunks(group_size)
+        .map(|tiles| Group::new(dim, tiles.to_vec()))
+        .collect::<Vec<_>>();
+    let tiles = Location::grid_iter(dim)
+        .map(|loc| board.tile(loc).unwrap_or(0))
+        .collect::<Vec<_>>();
+    let mut squares = vec![0; tiles.len()];
+    for (square, &tile) in tiles.iter().enumerate() {
+        squares[tile as usize] = square;
+    }
+    let mut search = Search {
+        dim,
+        groups: &groups,
+        squares,
+        tiles,
+        path: vec![],
+    };
+    let mut bound = search.estimate();
+    loop {
+        match search.search(bound) {
+            Ok(()) => return Some(Solution { moves: search.path }),
+            Err(Some(next_bound)) => bound = next_bound,
+            Err(None) => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solvability() {
+        let board = Board::parse("1 2 3\n4 5 6\n8 7 .").unwrap();
+        assert!(!board.is_solvable());
+        assert_eq!(solve_with(&board, 4), None);
+        let board = Board::parse("1 2 3\n4 5 6\n7 . 8").unwrap();
+        assert!(board.is_solvable());
+    }
+
+    #[test]
+    fn group_sizes_agree() {
+        let board = Board::parse("8 6 7\n2 5 4\n3 . 1").unwrap();
+        let lengths =
+            [2, 4, 7].map(|group_size| solve_with(&board, group_size).unwrap().moves().len());
+        assert_eq!(lengths, [31; 3]);
+    }
+}
@@ -0,0 +1,23 @@
+mod board;
+pub use board::{Board, Tile};
+mod deadlock;
+mod solver;
+pub use solver::{solve, solve_with, Algorithm, Move, Solution};
+
+/// Sokoban levels as a [`Puzzle`](crate::puzzle::Puzzle), in the text format of [`Board::parse`].
+pub struct Sokoban;
+
+impl crate::puzzle::Puzzle for Sokoban {
+    const NAME: &'static str = "sokoban level";
+
+    type Instance = Board;
+    type Solution = Solution;
+
+    fn parse(input: &str) -> crate::Result<Board> {
+        Board::parse(input)
+    }
+
+    fn solve(board: &Board) -> crate::Result<Option<Solution>> {
+        Ok(solve(board))
+    }
+}
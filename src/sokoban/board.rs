@@ -0,0 +1,158 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tile {
+    Wall,
+    Floor,
+    /// A floor square that a box must end on.
+    Goal,
+}
+
+/// A sokoban level: the player walks around the warehouse pushing boxes, one at a time and never pulling them,
+/// until every box is on a goal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    tiles: Grid<Tile>,
+    boxes: Vec<Location>,
+    player: Location,
+}
+
+impl Board {
+    pub fn new(tiles: Grid<Tile>, boxes: Vec<Location>, player: Location) -> Result<Self> {
+        let dim = tiles.dim();
+        let is_open =
+            |loc: Location| loc.row < dim.0 && loc.col < dim.1 && tiles[loc] != Tile::Wall;
+        ensure!(
+            is_open(player),
+            Invalid,
+            "The player at {player} is not on a floor square."
+        );
+        for (index, &loc) in boxes.iter().enumerate() {
+            ensure!(
+                is_open(loc),
+                Invalid,
+                "The box at {loc} is not on a floor square."
+            );
+            ensure!(
+                !boxes[..index].contains(&loc) && loc != player,
+                Invalid,
+                "The square {loc} holds more than one box or the player and a box."
+            );
+        }
+        ensure!(
+            !boxes.is_empty(),
+            Invalid,
+            "Sokoban levels must have at least one box."
+        );
+        let goals = tiles.iter().filter(|&&tile| tile == Tile::Goal).count();
+        ensure!(
+            goals == boxes.len(),
+            Invalid,
+            "The level has {} boxes but {goals} goals.",
+            boxes.len()
+        );
+        Ok(Self {
+            tiles,
+            boxes,
+            player,
+        })
+    }
+
+    /// Parses a level in the standard XSB format, where `#` is a wall, ` `, `-` and `_` are floor, `.` is a goal,
+    /// `$` is a box, `*` is a box on a goal, `@` is the player and `+` is the player on a goal.
+    /// Rows may have different lengths and blank lines and comments starting with `;` are skipped.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.trim_start().is_empty() && !line.trim_start().starts_with(';'))
+            .collect::<Vec<_>>();
+        let height = rows.len();
+        let width = rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut tiles = Grid::from_elem((height, width), Tile::Floor);
+        let mut boxes = vec![];
+        let mut player = None;
+        for (row_index, row) in rows.into_iter().enumerate() {
+            for (col_index, c) in row.chars().enumerate() {
+                let loc = Location::new(row_index, col_index);
+                tiles[Location::new(row_index, col_index)] = match c {
+                    '#' => Tile::Wall,
+                    ' ' | '-' | '_' | '$' | '@' => Tile::Floor,
+                    '.' | '*' | '+' => Tile::Goal,
+                    _ => bail!(
+                        Parse,
+                        "Invalid character '{c}' at ({row_index}, {col_index})."
+                    ),
+                };
+                match c {
+                    '$' | '*' => boxes.push(loc),
+                    '@' | '+' if player.is_some() => {
+                        bail!(Parse, "The level has more than one player.")
+                    }
+                    '@' | '+' => player = Some(loc),
+                    _ => {}
+                }
+            }
+        }
+        let player = player.ok_or_else(|| Error::Parse("The level has no player.".into()))?;
+        Self::new(tiles, boxes, player)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("sokoban", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.tiles.dim()
+    }
+
+    pub fn tile(&self, location: Location) -> Tile {
+        self.tiles[location]
+    }
+
+    pub fn boxes(&self) -> &[Location] {
+        &self.boxes
+    }
+
+    pub fn player(&self) -> Location {
+        self.player
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (row_index, row) in self.tiles.rows().enumerate() {
+            let row = row
+                .enumerate()
+                .map(|(col_index, &tile)| {
+                    let loc = Location::new(row_index, col_index);
+                    let has_box = self.boxes.contains(&loc);
+                    match (tile, has_box, loc == self.player) {
+                        (Tile::Wall, _, _) => '#',
+                        (Tile::Floor, true, _) => '$',
+                        (Tile::Floor, _, true) => '@',
+                        (Tile::Floor, _, _) => ' ',
+                        (Tile::Goal, true, _) => '*',
+                        (Tile::Goal, _, true) => '+',
+                        (Tile::Goal, _, _) => '.',
+                    }
+                })
+                .collect::<String>();
+            writeln!(f, "{}", row.trim_end())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+//! Positions from which the level can no longer be solved, so that the search never has to look past them.
+
+use std::collections::VecDeque;
+
+use crate::location::Direction;
+
+use super::solver::Level;
+
+/// The fewest pushes that take a box from each square to the nearest goal, ignoring the other boxes,
+/// or `None` for the simple deadlock squares from which no push sequence reaches a goal.
+///
+/// Found by pulling boxes backwards from the goals: a box reaches a square if the player can stand beyond it.
+pub(super) fn push_distances(level: &Level) -> Vec<Option<u32>> {
+    let mut distances = (0..level.cells())
+        .map(|cell| level.is_goal(cell).then_some(0))
+        .collect::<Vec<_>>();
+    let mut queue = (0..level.cells())
+        .filter(|&cell| level.is_goal(cell))
+        .collect::<VecDeque<_>>();
+    while let Some(cell) = queue.pop_front() {
+        let distance = distances[cell].expect("Only squares with a distance are queued.");
+        for direction in Direction::ALL {
+            // The box was pushed from `from` in `direction` by a player standing one step further back.
+            let Some(from) = level.step(cell, direction.opposite()) else {
+                continue;
+            };
+            if distances[from].is_none() && level.step(from, direction.opposite()).is_some() {
+                distances[from] = Some(distance + 1);
+                queue.push_back(from);
+            }
+        }
+    }
+    distances
+}
+
+/// Whether the box just pushed to `cell` is part of a group of boxes that can never move again,
+/// where at least one of the boxes is off its goal.
+///
+/// A box is stuck along an axis if a wall is on either side of it, if both sides are simple deadlock squares,
+/// or if a box on either side is itself stuck. While checking a neighbouring box, the box being checked counts as a
+/// wall, which keeps the check from going in circles.
+pub(super) fn is_freeze_deadlock(level: &Level, boxes: &[usize], cell: usize) -> bool {
+    let mut walls = vec![];
+    let mut frozen = vec![];
+    is_frozen(level, boxes, cell, &mut walls, &mut frozen)
+        && frozen.iter().any(|&cell| !level.is_goal(cell))
+}
+
+fn is_frozen(
+    level: &Level,
+    boxes: &[usize],
+    cell: usize,
+    walls: &mut Vec<usize>,
+    frozen: &mut Vec<usize>,
+) -> bool {
+    let found = frozen.len();
+    walls.push(cell);
+    let stuck = [Direction::Left, Direction::Up]
+        .into_iter()
+        .all(|direction| {
+            let sides =
+                [direction, direction.opposite()].map(|direction| level.step(cell, direction));
+            let is_wall = |side: Option<usize>| side.is_none_or(|side| walls.contains(&side));
+            if sides.into_iter().any(is_wall) {
+                return true;
+            }
+            if sides.into_iter().flatten().all(|side| !level.is_live(side)) {
+                return true;
+            }
+            sides.into_iter().flatten().any(|side| {
+                boxes.binary_search(&side).is_ok() && is_frozen(level, boxes, side, walls, frozen)
+            })
+        });
+    walls.pop();
+    if stuck {
+        frozen.push(cell);
+    } else {
+        frozen.truncate(found);
+    }
+    stuck
+}
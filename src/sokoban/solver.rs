@@ -0,0 +1,399 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    fmt::Display,
+};
+
+use crate::location::{Direction, Location};
+
+use super::{deadlock, Board, Tile};
+
+/// The fixed parts of a level, with squares numbered in row-major order.
+#[derive(Clone, Debug)]
+pub(super) struct Level {
+    dim: (usize, usize),
+    walls: Vec<bool>,
+    goals: Vec<bool>,
+    distances: Vec<Option<u32>>,
+}
+
+impl Level {
+    fn new(board: &Board) -> Self {
+        let tiles = Location::grid_iter(board.dim())
+            .map(|loc| board.tile(loc))
+            .collect::<Vec<_>>();
+        let mut level = Self {
+            dim: board.dim(),
+            walls: tiles.iter().map(|&tile| tile == Tile::Wall).collect(),
+            goals: tiles.iter().map(|&tile| tile == Tile::Goal).collect(),
+            distances: vec![],
+        };
+        level.distances = deadlock::push_distances(&level);
+        level
+    }
+
+    pub(super) fn cells(&self) -> usize {
+        self.walls.len()
+    }
+
+    fn cell(&self, location: Location) -> usize {
+        location.row * self.dim.1 + location.col
+    }
+
+    fn location(&self, cell: usize) -> Location {
+        Location::new(cell / self.dim.1, cell % self.dim.1)
+    }
+
+    /// The square next to `cell` in `direction`, if it is not a wall.
+    pub(super) fn step(&self, cell: usize, direction: Direction) -> Option<usize> {
+        let next = self.cell(self.location(cell).step(direction, self.dim)?);
+        (!self.walls[next]).then_some(next)
+    }
+
+    pub(super) fn is_goal(&self, cell: usize) -> bool {
+        self.goals[cell]
+    }
+
+    /// Whether a box on `cell` can still be pushed to a goal.
+    pub(super) fn is_live(&self, cell: usize) -> bool {
+        self.distances[cell].is_some()
+    }
+
+    /// The squares the player can walk to without pushing a box, each with the direction it was first reached in.
+    fn reachable(&self, boxes: &[usize], player: usize) -> Vec<Option<Option<Direction>>> {
+        let mut reached = vec![None; self.cells()];
+        reached[player] = Some(None);
+        let mut queue = VecDeque::from([player]);
+        while let Some(cell) = queue.pop_front() {
+            for direction in Direction::ALL {
+                let Some(next) = self.step(cell, direction) else {
+                    continue;
+                };
+                if reached[next].is_none() && boxes.binary_search(&next).is_err() {
+                    reached[next] = Some(Some(direction));
+                    queue.push_back(next);
+                }
+            }
+        }
+        reached
+    }
+
+    /// The walk from `player` to `target` without pushing a box, which must exist.
+    fn walk(&self, boxes: &[usize], player: usize, target: usize) -> Vec<Direction> {
+        let reached = self.reachable(boxes, player);
+        let mut walk = vec![];
+        let mut cell = target;
+        while let Some(Some(direction)) = reached[cell] {
+            walk.push(direction);
+            cell = self
+                .step(cell, direction.opposite())
+                .expect("The walk came from there.");
+        }
+        walk.reverse();
+        walk
+    }
+}
+
+/// A position counted up to where the player stands within the area it can walk around in,
+/// which is represented by its first square.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Position {
+    /// Sorted.
+    boxes: Vec<usize>,
+    player: usize,
+}
+
+/// A push of the box on `from` in `direction`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Push {
+    from: usize,
+    direction: Direction,
+}
+
+impl Position {
+    fn new(level: &Level, mut boxes: Vec<usize>, player: usize) -> Self {
+        boxes.sort_unstable();
+        let player = level
+            .reachable(&boxes, player)
+            .iter()
+            .position(Option::is_some)
+            .expect("The player can reach its own square.");
+        Self { boxes, player }
+    }
+
+    fn is_solved(&self, level: &Level) -> bool {
+        self.boxes.iter().all(|&cell| level.is_goal(cell))
+    }
+
+    /// A lower bound on the pushes left: every box still has to get to some goal.
+    fn heuristic(&self, level: &Level) -> u32 {
+        self.boxes
+            .iter()
+            .map(|&cell| level.distances[cell].expect("Boxes are never pushed onto dead squares."))
+            .sum()
+    }
+
+    /// Every push the player can make that does not lead to a deadlock, with the position it leads to.
+    fn successors(&self, level: &Level) -> Vec<(Push, Position)> {
+        let reached = level.reachable(&self.boxes, self.player);
+        let mut successors = vec![];
+        for (index, &from) in self.boxes.iter().enumerate() {
+            for direction in Direction::ALL {
+                let Some(behind) = level.step(from, direction.opposite()) else {
+                    continue;
+                };
+                let Some(to) = level.step(from, direction) else {
+                    continue;
+                };
+                if reached[behind].is_none()
+                    || !level.is_live(to)
+                    || self.boxes.binary_search(&to).is_ok()
+                {
+                    continue;
+                }
+                let mut boxes = self.boxes.clone();
+                boxes[index] = to;
+                let position = Position::new(level, boxes, from);
+                if deadlock::is_freeze_deadlock(level, &position.boxes, to) {
+                    continue;
+                }
+                successors.push((Push { from, direction }, position));
+            }
+        }
+        successors
+    }
+}
+
+/// The search algorithm used to find a solution with the fewest pushes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Algorithm {
+    /// Best first search, which keeps every position it has seen in memory.
+    #[default]
+    AStar,
+    /// Iterative deepening on the estimated number of pushes, which uses less memory but revisits positions.
+    IdaStar,
+}
+
+fn a_star(level: &Level, start: Position) -> Option<Vec<Push>> {
+    // Every position seen, with the position and push it was first reached from.
+    let mut nodes: Vec<(Position, Option<(usize, Push)>)> = vec![(start.clone(), None)];
+    let mut best = HashMap::from([(start.clone(), 0)]);
+    // Among positions with the same estimate, the one with the most pushes made is the closest to a solution.
+    let mut queue = BinaryHeap::from([Reverse((start.heuristic(level), Reverse(0), 0))]);
+    while let Some(Reverse((_, Reverse(pushes), node))) = queue.pop() {
+        let position = nodes[node].0.clone();
+        if best[&position] < pushes {
+            continue;
+        }
+        if position.is_solved(level) {
+            let mut path = vec![];
+            let mut node = node;
+            while let Some((parent, push)) = nodes[node].1 {
+                path.push(push);
+                node = parent;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for (push, next) in position.successors(level) {
+            let pushes = pushes + 1;
+            match best.entry(next.clone()) {
+                Entry::Occupied(entry) if *entry.get() <= pushes => continue,
+                Entry::Occupied(mut entry) => {
+                    entry.insert(pushes);
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(pushes);
+                }
+            }
+            let estimate = pushes + next.heuristic(level);
+            nodes.push((next, Some((node, push))));
+            queue.push(Reverse((estimate, Reverse(pushes), nodes.len() - 1)));
+        }
+    }
+    None
+}
+
+fn ida_star(level: &Level, start: Position) -> Option<Vec<Push>> {
+    /// Searches for a solution within `bound` pushes in total, returning the smallest estimate over the bound
+    /// otherwise. Positions already reached with as few pushes during this iteration are skipped.
+    fn search(
+        level: &Level,
+        position: &Position,
+        pushes: u32,
+        bound: u32,
+        path: &mut Vec<Push>,
+        seen: &mut HashMap<Position, u32>,
+    ) -> Result<(), Option<u32>> {
+        let estimate = pushes + position.heuristic(level);
+        if estimate > bound {
+            return Err(Some(estimate));
+        }
+        if position.is_solved(level) {
+            return Ok(());
+        }
+        match seen.get(position) {
+            Some(&seen_pushes) if seen_pushes <= pushes => return Err(None),
+            _ => {
+                seen.insert(position.clone(), pushes);
+            }
+        }
+        let mut next_bound = None;
+        for (push, next) in position.successors(level) {
+            path.push(push);
+            match search(level, &next, pushes + 1, bound, path, seen) {
+                Ok(()) => return Ok(()),
+                Err(Some(estimate)) => {
+                    next_bound = Some(
+                        next_bound.map_or(estimate, |next_bound: u32| next_bound.min(estimate)),
+                    );
+                }
+                Err(None) => {}
+            }
+            path.pop();
+        }
+        Err(next_bound)
+    }
+
+    let mut bound = start.heuristic(level);
+    loop {
+        let mut path = vec![];
+        match search(level, &start, 0, bound, &mut path, &mut HashMap::new()) {
+            Ok(()) => return Some(path),
+            Err(Some(next_bound)) => bound = next_bound,
+            Err(None) => return None,
+        }
+    }
+}
+
+/// One step of the player, which is a push if it moves a box.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Move {
+    pub direction: Direction,
+    pub push: bool,
+}
+
+impl Move {
+    /// The move in LURD notation: the direction's letter, in upper case for a push.
+    pub fn to_char(self) -> char {
+        let c = match self.direction {
+            Direction::Up => 'u',
+            Direction::Right => 'r',
+            Direction::Down => 'd',
+            Direction::Left => 'l',
+        };
+        if self.push {
+            c.to_ascii_uppercase()
+        } else {
+            c
+        }
+    }
+}
+
+/// The moves that solve a level with the fewest pushes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    moves: Vec<Move>,
+}
+
+impl Solution {
+    fn from_pushes(level: &Level, board: &Board, pushes: Vec<Push>) -> Self {
+        let mut boxes = board
+            .boxes()
+            .iter()
+            .map(|&loc| level.cell(loc))
+            .collect::<Vec<_>>();
+        boxes.sort_unstable();
+        let mut player = level.cell(board.player());
+        let mut moves = vec![];
+        for Push { from, direction } in pushes {
+            let behind = level
+                .step(from, direction.opposite())
+                .expect("The player stood behind the box.");
+            moves.extend(
+                level
+                    .walk(&boxes, player, behind)
+                    .into_iter()
+                    .map(|direction| Move {
+                        direction,
+                        push: false,
+                    }),
+            );
+            moves.push(Move {
+                direction,
+                push: true,
+            });
+            let index = boxes
+                .binary_search(&from)
+                .expect("A box is on the pushed square.");
+            boxes[index] = level.step(from, direction).expect("The box moved there.");
+            boxes.sort_unstable();
+            player = from;
+        }
+        Self { moves }
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    pub fn pushes(&self) -> usize {
+        self.moves.iter().filter(|step| step.push).count()
+    }
+}
+
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let moves = self
+            .moves
+            .iter()
+            .map(|step| step.to_char())
+            .collect::<String>();
+        writeln!(f, "{moves}")
+    }
+}
+
+/// Returns a solution with the fewest pushes using A*, or `None` if the level has no solution.
+pub fn solve(board: &Board) -> Option<Solution> {
+    solve_with(board, Algorithm::default())
+}
+
+/// Returns a solution with the fewest pushes, or `None` if the level has no solution.
+pub fn solve_with(board: &Board, algorithm: Algorithm) -> Option<Solution> {
+    let level = Level::new(board);
+    let boxes = board
+        .boxes()
+        .iter()
+        .map(|&loc| level.cell(loc))
+        .collect::<Vec<_>>();
+    if boxes.iter().any(|&cell| !level.is_live(cell)) {
+        return None;
+    }
+    let start = Position::new(&level, boxes, level.cell(board.player()));
+    let pushes = match algorithm {
+        Algorithm::AStar => a_star(&level, start),
+        Algorithm::IdaStar => ida_star(&level, start),
+    }?;
+    Some(Solution::from_pushes(&level, board, pushes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn both_searches_find_the_fewest_pushes() {
+        let board = Board::parse("#######\n#.  @ #\n#  $  #\n# $ . #\n#     #\n#######\n").unwrap();
+        for algorithm in [Algorithm::AStar, Algorithm::IdaStar] {
+            assert_eq!(solve_with(&board, algorithm).unwrap().pushes(), 5);
+        }
+    }
+
+    #[test]
+    fn boxes_in_corners_are_dead() {
+        let board = Board::parse("#####\n#$ .#\n#  @#\n#####\n").unwrap();
+        let level = Level::new(&board);
+        assert!(!level.is_live(level.cell(Location::new(1, 1))));
+        assert_eq!(solve(&board), None);
+    }
+}
@@ -0,0 +1,233 @@
+//! A flat index of solved puzzles, so that solutions can be looked up instead of found again, across runs.
+//!
+//! Puzzles are keyed by their kind and canonical form, which is the instance written by [`Puzzle::format_instance`],
+//! so the same puzzle is found however it was written. The index is stored as JSON lines sorted by key, so that it
+//! changes little between runs and diffs well. Each line also holds the [`PuzzleId`] of its puzzle, so that other
+//! outputs can be joined with it.
+
+use std::{collections::BTreeMap, io::ErrorKind, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{write, Error, Result},
+    id::PuzzleId,
+    puzzle::{self, Puzzle, Visitor},
+};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SolutionIndex {
+    /// The formatted solutions by kind and canonical form.
+    solutions: BTreeMap<(String, String), String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Line {
+    /// Only written for other tools to join on, since it is recomputed from the kind and puzzle. Indexes written
+    /// before puzzles had ids have none.
+    #[serde(default)]
+    id: Option<PuzzleId>,
+    kind: String,
+    puzzle: String,
+    solution: String,
+}
+
+/// Reads any kind of puzzle and looks it up.
+struct Lookup<'a> {
+    index: &'a SolutionIndex,
+    input: &'a str,
+}
+
+impl Visitor for Lookup<'_> {
+    type Output = Option<String>;
+
+    fn visit<P: Puzzle>(self) -> Option<String> {
+        let instance = P::parse(self.input).ok()?;
+        self.index.get::<P>(&instance).map(str::to_string)
+    }
+}
+
+impl SolutionIndex {
+    /// Reads the index at the path, or returns an empty one if there is none yet.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(source) => {
+                return Err(Error::Read {
+                    puzzle: "solution index",
+                    path: path.to_owned(),
+                    source,
+                })
+            }
+        };
+        let mut index = Self::default();
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            let Line {
+                kind,
+                puzzle,
+                solution,
+                ..
+            } = serde_json::from_str(line)?;
+            index.solutions.insert((kind, puzzle), solution);
+        }
+        Ok(index)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|source| Error::Write {
+                puzzle: "solution index",
+                path: dir.to_owned(),
+                source,
+            })?;
+        }
+        let mut text = String::new();
+        for ((kind, puzzle), solution) in &self.solutions {
+            let line = Line {
+                id: Some(PuzzleId::new(kind, puzzle)),
+                kind: kind.clone(),
+                puzzle: puzzle.clone(),
+                solution: solution.clone(),
+            };
+            text.push_str(&serde_json::to_string(&line)?);
+            text.push('\n');
+        }
+        write("solution index", path, text)
+    }
+
+    pub fn len(&self) -> usize {
+        self.solutions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.solutions.is_empty()
+    }
+
+    /// The stored solution of the instance, formatted by [`Puzzle::format`].
+    pub fn get<P: Puzzle>(&self, instance: &P::Instance) -> Option<&str> {
+        self.get_variant::<P>(instance, None)
+    }
+
+    pub fn insert<P: Puzzle>(&mut self, instance: &P::Instance, solution: &P::Solution) {
+        self.insert_variant::<P>(instance, None, solution);
+    }
+
+    /// Like [`SolutionIndex::get`], but for the instance solved under variant rules such as `diagonal`, whose
+    /// solutions are kept apart from those under the usual rules.
+    pub fn get_variant<P: Puzzle>(
+        &self,
+        instance: &P::Instance,
+        variant: Option<&str>,
+    ) -> Option<&str> {
+        self.solutions
+            .get(&Self::key::<P>(instance, variant))
+            .map(String::as_str)
+    }
+
+    pub fn insert_variant<P: Puzzle>(
+        &mut self,
+        instance: &P::Instance,
+        variant: Option<&str>,
+        solution: &P::Solution,
+    ) {
+        self.solutions
+            .insert(Self::key::<P>(instance, variant), P::format(solution));
+    }
+
+    /// Solutions under variant rules are stored under their own kind, such as `camping:diagonal`, so that
+    /// [`SolutionIndex::lookup`] only finds solutions under the usual rules.
+    fn key<P: Puzzle>(instance: &P::Instance, variant: Option<&str>) -> (String, String) {
+        let kind = puzzle::kind::<P>();
+        let kind = match variant {
+            Some(variant) => format!("{kind}:{variant}"),
+            None => kind.to_string(),
+        };
+        (kind, P::format_instance(instance))
+    }
+
+    /// Finds the solution of the puzzle with the id, returning its kind with it.
+    pub fn find(&self, id: PuzzleId) -> Option<(&str, &str)> {
+        self.solutions
+            .iter()
+            .find(|((kind, puzzle), _)| PuzzleId::new(kind, puzzle) == id)
+            .map(|((kind, _), solution)| (kind.as_str(), solution.as_str()))
+    }
+
+    /// Finds the solution of a puzzle of any kind, returning the kind with it. Tries every kind the index holds
+    /// solutions of, so the input needs to be a puzzle that one of them reads.
+    pub fn lookup(&self, input: &str) -> Option<(&'static str, String)> {
+        puzzle::KINDS
+            .into_iter()
+            .filter(|kind| self.solutions.keys().any(|(stored, _)| stored == kind))
+            .find_map(|kind| {
+                let lookup = Lookup { index: self, input };
+                Some((kind, puzzle::visit(kind, lookup).ok()??))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        camping::{solve_with, Camping, Rules, SolverConfig},
+        sudoku::Sudoku,
+    };
+
+    #[test]
+    fn finds_solutions_however_written() {
+        let line = include_str!("../data/sudoku/grids/easy50.txt")
+            .lines()
+            .next()
+            .unwrap();
+        let board = Sudoku::parse(line).unwrap();
+        let solution = puzzle::solve_valid::<Sudoku>(&board).unwrap();
+        let mut index = SolutionIndex::default();
+        assert!(index.get::<Sudoku>(&board).is_none());
+        index.insert::<Sudoku>(&board, &solution);
+
+        let grid = line
+            .as_bytes()
+            .chunks(9)
+            .map(|row| std::str::from_utf8(row).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let expected = Sudoku::format(&solution);
+        assert_eq!(index.lookup(&grid), Some(("sudoku", expected)));
+        assert_eq!(index.lookup("SEND+MORE=MONEY"), None);
+        assert_eq!(
+            index.find(board.id()),
+            Some(("sudoku", Sudoku::format(&solution).as_str()))
+        );
+
+        let path = std::env::temp_dir().join("puzzles-solution-index-test.jsonl");
+        index.save(&path).unwrap();
+        assert_eq!(SolutionIndex::load(&path).unwrap(), index);
+        std::fs::remove_file(&path).unwrap();
+        assert!(SolutionIndex::load(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn keeps_variant_solutions_apart() {
+        let input = include_str!("../data/camping/variants/diagonal01.txt");
+        let map = Camping::parse(input).unwrap();
+        let config = SolverConfig {
+            rules: Rules {
+                diagonal_tents_allowed: true,
+            },
+            ..SolverConfig::default()
+        };
+        let solution = solve_with(&map, &config).unwrap().unwrap();
+        let mut index = SolutionIndex::default();
+        index.insert_variant::<Camping>(&map, Some("diagonal"), &solution);
+        assert_eq!(
+            index.get_variant::<Camping>(&map, Some("diagonal")),
+            Some(Camping::format(&solution).as_str())
+        );
+        assert!(index.get::<Camping>(&map).is_none());
+        assert!(index.lookup(input).is_none());
+    }
+}
@@ -0,0 +1,135 @@
+use std::{fmt::Display, path};
+
+use itertools::Itertools;
+
+use crate::{
+    error::{ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+    region,
+};
+
+/// A star battle puzzle: a square grid split into as many regions as it has rows.
+/// Every row, column and region must contain exactly `stars` stars, and no two stars may touch,
+/// not even diagonally.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    stars: usize,
+    regions: Grid<usize>,
+}
+
+impl Board {
+    pub fn new(stars: usize, regions: Grid<usize>) -> Result<Self> {
+        let (height, width) = regions.dim();
+        ensure!(
+            height == width,
+            Invalid,
+            "Star battle grids must be square, but got {height}x{width}."
+        );
+        let num_regions = regions.iter().unique().count();
+        ensure!(
+            num_regions == height,
+            Invalid,
+            "A {height}x{height} grid must have {height} regions, but has {num_regions}."
+        );
+        ensure!(
+            regions.iter().all(|&region| region < height),
+            Invalid,
+            "Region indices must be less than {height}."
+        );
+        ensure!(
+            stars > 0,
+            Invalid,
+            "Puzzle must require at least one star per unit."
+        );
+        Ok(Self { stars, regions })
+    }
+
+    /// Parses a puzzle given as the number of stars on the first line followed by a grid of letters,
+    /// where each distinct letter is a region.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let string = string.as_ref();
+        let mut lines = string.lines();
+        let line = lines
+            .next()
+            .ok_or_else(|| Error::Parse("No first line.".into()))?;
+        let stars = line.trim().parse::<usize>().map_err(|_| {
+            Error::Parse(format!(
+                "Expected a positive number of stars. Got '{line}'."
+            ))
+        })?;
+        let rows = lines
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let (regions, _) = region::parse_regions(&rows)?;
+        Self::new(stars, regions)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("star battle", path)?;
+        Self::parse(string)
+    }
+
+    pub fn size(&self) -> usize {
+        self.regions.height()
+    }
+
+    pub fn stars(&self) -> usize {
+        self.stars
+    }
+
+    pub fn region(&self, location: Location) -> usize {
+        self.regions[location]
+    }
+
+    /// Every row, column and region, each of which must contain exactly `stars` stars.
+    pub fn units(&self) -> Vec<Vec<Location>> {
+        let size = self.size();
+        let rows = (0..size).map(|row| (0..size).map(|col| Location::new(row, col)).collect());
+        let cols = (0..size).map(|col| (0..size).map(|row| Location::new(row, col)).collect());
+        let regions = (0..size).map(|region| {
+            Location::grid_iter((size, size))
+                .filter(|&loc| self.region(loc) == region)
+                .collect()
+        });
+        rows.chain(cols).chain(regions).collect()
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.stars)?;
+        for row in self.regions.rows() {
+            for &region in row {
+                write!(f, "{}", (b'A' + region as u8) as char)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_regions_and_stars() {
+        let board = Board::parse(include_str!("../../data/starbattle/puzzles/six01.txt")).unwrap();
+        assert_eq!((board.size(), board.stars()), (6, 1));
+        assert_eq!(
+            board.region(Location::new(0, 4)),
+            board.region(Location::new(0, 5))
+        );
+        assert_ne!(
+            board.region(Location::new(0, 0)),
+            board.region(Location::new(0, 3))
+        );
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        assert!(Board::parse("1\nABC\nABC\n").is_err());
+        assert!(Board::parse("1\nAB\nCC\n").is_err());
+        assert!(Board::parse("x\nAB\nBA\n").is_err());
+    }
+}
@@ -0,0 +1,69 @@
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    error::{bail, Result},
+    grid::Grid,
+    location::Location,
+};
+
+use super::{count_solutions, Board};
+
+/// Places one star in each row and column such that no two stars touch.
+fn random_stars(size: usize, rng: &mut impl Rng) -> Option<Vec<usize>> {
+    let mut cols = (0..size).collect::<Vec<_>>();
+    for _ in 0..10_000 {
+        cols.shuffle(rng);
+        if cols.windows(2).all(|pair| pair[0].abs_diff(pair[1]) > 1) {
+            return Some(cols);
+        }
+    }
+    None
+}
+
+/// Grows one region around each star by repeatedly adding a random neighbouring cell to a random region.
+fn grow_regions(size: usize, star_cols: &[usize], rng: &mut impl Rng) -> Grid<usize> {
+    let mut regions = Grid::from_elem((size, size), usize::MAX);
+    for (row, &col) in star_cols.iter().enumerate() {
+        regions[Location::new(row, col)] = row;
+    }
+    let mut unassigned = size * size - size;
+    while unassigned > 0 {
+        let frontier = Location::grid_iter((size, size))
+            .filter(|loc| regions[*loc] == usize::MAX)
+            .flat_map(|loc| {
+                let regions = &regions;
+                loc.adjacents((size, size))
+                    .into_iter()
+                    .flatten()
+                    .filter(move |adj| regions[*adj] != usize::MAX)
+                    .map(move |adj| (loc, regions[adj]))
+            })
+            .collect::<Vec<_>>();
+        let &(loc, region) = frontier
+            .choose(rng)
+            .expect("Regions always border an unassigned cell until the grid is full.");
+        regions[loc] = region;
+        unassigned -= 1;
+    }
+    regions
+}
+
+/// Generates a one star puzzle with a unique solution, giving up after `attempts` tries.
+pub fn generate(size: usize, attempts: usize, rng: &mut impl Rng) -> Result<Board> {
+    if size < 4 {
+        bail!(Generate, "There are no one star puzzles smaller than 4x4.");
+    }
+    for _ in 0..attempts {
+        let Some(star_cols) = random_stars(size, rng) else {
+            continue;
+        };
+        let board = Board::new(1, grow_regions(size, &star_cols, rng))?;
+        if count_solutions(&board, 2) == 1 {
+            return Ok(board);
+        }
+    }
+    bail!(
+        Generate,
+        "Failed to generate a unique {size}x{size} puzzle in {attempts} attempts."
+    )
+}
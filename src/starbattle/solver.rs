@@ -0,0 +1,153 @@
+use std::fmt::Display;
+
+use crate::{
+    grid::Grid,
+    location::Location,
+    search::{self, Contradiction, SearchState},
+    shading::{Shade, ShadeGrid},
+};
+
+use super::Board;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    stars: Grid<bool>,
+}
+
+impl Solution {
+    pub fn is_star(&self, location: Location) -> bool {
+        self.stars[location]
+    }
+}
+
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.stars.rows() {
+            for &star in row {
+                write!(f, "{}", if star { '*' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct State<'a> {
+    board: &'a Board,
+    units: &'a [Vec<Location>],
+    grid: ShadeGrid,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board, units: &'a [Vec<Location>]) -> Self {
+        let size = board.size();
+        Self {
+            board,
+            units,
+            grid: ShadeGrid::new((size, size)),
+        }
+    }
+
+    /// Stars cannot touch, so every neighbour of a star is empty.
+    fn block_neighbors(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        let dim = self.grid.dim();
+        for loc in Location::grid_iter(dim) {
+            if self.grid.is_shaded(loc) {
+                for neighbor in loc.neighbors(dim).into_iter().flatten() {
+                    changed |= self.grid.set(neighbor, Shade::Unshaded)?;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    fn into_solution(self) -> Solution {
+        Solution {
+            stars: self.grid.cells().map(|&shade| shade == Shade::Shaded),
+        }
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.block_neighbors()?;
+            for unit in self.units {
+                changed |= self.grid.apply_count(unit, self.board.stars())?;
+            }
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        // Branch on the first undecided cell of the unit with the fewest undecided cells.
+        let Some(location) = self
+            .units
+            .iter()
+            .filter_map(|unit| {
+                let unknowns = unit
+                    .iter()
+                    .filter(|&&loc| self.grid.get(loc) == Shade::Unknown)
+                    .collect::<Vec<_>>();
+                unknowns.first().map(|&&loc| (unknowns.len(), loc))
+            })
+            .min_by_key(|&(len, _)| len)
+            .map(|(_, loc)| loc)
+        else {
+            return vec![];
+        };
+        self.grid
+            .branch_on(location)
+            .into_iter()
+            .map(|grid| Self {
+                grid,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+pub fn solve(board: &Board) -> Option<Solution> {
+    let units = board.units();
+    search::solve(State::new(board, &units)).map(State::into_solution)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    let units = board.units();
+    search::count_solutions(State::new(board, &units), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks the rules directly: every unit has its stars and no two stars touch.
+    fn follows_rules(board: &Board, solution: &Solution) -> bool {
+        let dim = (board.size(), board.size());
+        let units_filled = board
+            .units()
+            .iter()
+            .all(|unit| unit.iter().filter(|&&loc| solution.is_star(loc)).count() == board.stars());
+        let apart = Location::grid_iter(dim)
+            .filter(|&loc| solution.is_star(loc))
+            .all(|loc| {
+                loc.neighbors(dim)
+                    .into_iter()
+                    .flatten()
+                    .all(|neighbor| !solution.is_star(neighbor))
+            });
+        units_filled && apart
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/starbattle/puzzles/six01.txt")).unwrap();
+        let solution = solve(&board).unwrap();
+        assert!(follows_rules(&board, &solution));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
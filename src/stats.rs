@@ -0,0 +1,223 @@
+//! What a solve cost, beyond its answer, so that regressions in the backtracking solvers show up in benchmarks.
+//!
+//! The solvers that backtrack count their steps, guesses and the copies of their state they make to guess. Counting
+//! allocations needs the `alloc-stats` feature and [`CountingAllocator`] installed as the global allocator, since
+//! only the allocator sees them.
+
+use serde::Serialize;
+
+#[cfg(feature = "alloc-stats")]
+pub use counting::CountingAllocator;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct SolveStats {
+    /// Rounds of deductions, each of which may place several values.
+    pub steps: u64,
+    pub guesses: u64,
+    /// Copies of the whole solver state made to guess or backtrack, which are the costly part of backtracking.
+    pub clones: u64,
+    /// The most guesses that were open at once, which is how many states the solver held.
+    pub max_depth: u64,
+    /// `None` unless allocations are counted.
+    pub allocations: Option<AllocStats>,
+}
+
+/// The allocations made on the solving thread.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct AllocStats {
+    pub allocations: u64,
+    pub bytes: u64,
+    /// The most bytes held at once, beyond those held when solving started.
+    pub peak_bytes: u64,
+}
+
+impl SolveStats {
+    /// The statistics by name, leaving out allocations if they were not counted.
+    pub fn named(&self) -> Vec<(&'static str, u64)> {
+        let mut stats = vec![
+            ("steps", self.steps),
+            ("guesses", self.guesses),
+            ("clones", self.clones),
+            ("max_depth", self.max_depth),
+        ];
+        if let Some(allocations) = self.allocations {
+            stats.extend([
+                ("allocations", allocations.allocations),
+                ("allocated_bytes", allocations.bytes),
+                ("peak_bytes", allocations.peak_bytes),
+            ]);
+        }
+        stats
+    }
+
+    /// Adds the costs of another solve, such as for a whole set of puzzles. Depths and peaks are the largest of either.
+    pub fn add(&mut self, other: &SolveStats) {
+        self.steps += other.steps;
+        self.guesses += other.guesses;
+        self.clones += other.clones;
+        self.max_depth = self.max_depth.max(other.max_depth);
+        self.allocations = match (self.allocations, other.allocations) {
+            (Some(ours), Some(theirs)) => Some(AllocStats {
+                allocations: ours.allocations + theirs.allocations,
+                bytes: ours.bytes + theirs.bytes,
+                peak_bytes: ours.peak_bytes.max(theirs.peak_bytes),
+            }),
+            (ours, theirs) => ours.or(theirs),
+        };
+    }
+
+    /// Notes that the solver holds this many guesses.
+    pub(crate) fn reach_depth(&mut self, depth: usize) {
+        self.max_depth = self.max_depth.max(depth as u64);
+    }
+}
+
+/// Runs `f`, counting the allocations it makes on this thread. Returns `None` for the counts unless
+/// [`CountingAllocator`] is the global allocator.
+#[cfg(feature = "alloc-stats")]
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    counting::measure(f)
+}
+
+/// Runs `f`. Allocations are only counted with the `alloc-stats` feature.
+#[cfg(not(feature = "alloc-stats"))]
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    (f(), None)
+}
+
+#[cfg(feature = "alloc-stats")]
+mod counting {
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        cell::Cell,
+        sync::atomic::{AtomicBool, Ordering},
+    };
+
+    use super::AllocStats;
+
+    /// The system allocator, counting the allocations of each thread for [`super::measure`]. Install it in a binary
+    /// with `#[global_allocator]`.
+    pub struct CountingAllocator;
+
+    /// Whether [`CountingAllocator`] has allocated anything, which it has if it is the global allocator.
+    static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+    #[derive(Clone, Copy)]
+    struct Counts {
+        allocations: u64,
+        bytes: u64,
+        held: u64,
+        peak: u64,
+    }
+
+    thread_local! {
+        // Initialized without allocating, since it is used from inside the allocator.
+        static COUNTS: Cell<Counts> = const {
+            Cell::new(Counts {
+                allocations: 0,
+                bytes: 0,
+                held: 0,
+                peak: 0,
+            })
+        };
+    }
+
+    fn update(f: impl FnOnce(&mut Counts)) {
+        // Threads that are shutting down have lost their counts, and their allocations are not measured anyway.
+        _ = COUNTS.try_with(|counts| {
+            let mut updated = counts.get();
+            f(&mut updated);
+            counts.set(updated);
+        });
+    }
+
+    fn allocated(counts: &mut Counts, size: usize) {
+        counts.allocations += 1;
+        counts.bytes += size as u64;
+        counts.held += size as u64;
+        counts.peak = counts.peak.max(counts.held);
+    }
+
+    // Memory freed on another thread than it was allocated on makes the held bytes of either thread inexact, which
+    // only matters for the peak.
+    fn freed(counts: &mut Counts, size: usize) {
+        counts.held = counts.held.saturating_sub(size as u64);
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            INSTALLED.store(true, Ordering::Relaxed);
+            update(|counts| allocated(counts, layout.size()));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            update(|counts| freed(counts, layout.size()));
+            System.dealloc(ptr, layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            update(|counts| {
+                freed(counts, layout.size());
+                allocated(counts, new_size);
+            });
+            System.realloc(ptr, layout, new_size)
+        }
+    }
+
+    pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+        let before = COUNTS.with(|counts| {
+            let before = counts.get();
+            counts.set(Counts {
+                peak: before.held,
+                ..before
+            });
+            before
+        });
+        let result = f();
+        let after = COUNTS.with(|counts| {
+            let after = counts.get();
+            // Keep the peak of an enclosing measurement.
+            counts.set(Counts {
+                peak: after.peak.max(before.peak),
+                ..after
+            });
+            after
+        });
+        let stats = INSTALLED.load(Ordering::Relaxed).then(|| AllocStats {
+            allocations: after.allocations - before.allocations,
+            bytes: after.bytes - before.bytes,
+            peak_bytes: after.peak.saturating_sub(before.held),
+        });
+        (result, stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{cancel::CancelToken, sudoku};
+
+    #[cfg(feature = "alloc-stats")]
+    #[global_allocator]
+    static ALLOCATOR: super::CountingAllocator = super::CountingAllocator;
+
+    #[test]
+    fn counts_backtracking() {
+        let line = include_str!("../data/sudoku/grids/qqwing_expert.txt")
+            .lines()
+            .next()
+            .unwrap();
+        let board = sudoku::Board::from_line(line, '.').unwrap();
+        let (solution, stats) = sudoku::solve_with_stats(&board, &CancelToken::new()).unwrap();
+        let (expected, steps, guesses) = sudoku::solve(&board).unwrap();
+        assert_eq!(solution.to_string(), expected.to_string());
+        assert_eq!((stats.steps, stats.guesses), (steps.into(), guesses.into()));
+        assert_eq!(stats.clones, stats.guesses);
+        assert!(stats.max_depth > 0 && stats.max_depth <= stats.guesses);
+        assert_eq!(stats.allocations.is_some(), cfg!(feature = "alloc-stats"));
+        assert!(stats
+            .allocations
+            .is_none_or(|allocations| allocations.allocations > 0));
+        assert_eq!(stats.named()[3], ("max_depth", stats.max_depth));
+    }
+}
@@ -1,7 +1,180 @@
 mod board;
+mod budget;
+mod certificate;
+mod completion;
+mod error;
+mod explain;
+mod formats;
+mod generator;
+mod grid_file;
 mod location_set;
+mod pair;
 mod solver;
 mod value_set;
 
-pub use board::Board;
-pub use solver::solve;
+pub use board::{Board, BoardCell, CellValue, InvalidBoardError, Location};
+pub use budget::{Budget, Budgets, Exceeded};
+pub use certificate::{certify, verify_certificate, Certificate, Step};
+pub use completion::{complete, complete_random, FEW_GIVENS};
+pub use error::Error;
+pub use explain::{explain, Contradiction, Deduction, Explanation, Reason};
+pub use generator::{generate, generate_cancellable, generate_with_solution, minimal_subpuzzles};
+pub use grid_file::GridFile;
+pub use pair::Pair;
+pub(crate) use solver::Scratch;
+pub use solver::{
+    count_solutions, hint, rate, sample_solutions, solve, solve_cancellable, solve_with_budget,
+    solve_with_stats, Hint, SolveState, Technique,
+};
+pub use value_set::ValueSet;
+
+use rand::rngs::StdRng;
+
+use crate::generate::{Generator, GeneratorConfig};
+
+/// Sudokus as a [`Puzzle`](crate::puzzle::Puzzle), read as either an 81 character line or a grid, with '.' for
+/// empty cells, and written as a line.
+pub struct Sudoku;
+
+impl crate::puzzle::Puzzle for Sudoku {
+    const NAME: &'static str = "sudoku";
+
+    type Instance = Board;
+    type Solution = Board;
+
+    fn parse(input: &str) -> crate::Result<Board> {
+        let input = input.trim();
+        if input.lines().count() == 1 {
+            Ok(Board::from_line(input, '.')?)
+        } else {
+            Ok(Board::from_grid(&format!("{input}\n"), '.')?)
+        }
+    }
+
+    /// One sudoku per line if the input starts with a line, and otherwise grids separated by lines of `---`.
+    fn split(input: &str) -> Vec<&str> {
+        let lines = input.lines().map(str::trim).filter(|line| !line.is_empty());
+        if lines
+            .clone()
+            .next()
+            .is_some_and(|line| line.chars().count() == 81)
+        {
+            lines.collect()
+        } else {
+            crate::puzzle::split_blocks(input)
+        }
+    }
+
+    fn validate(board: &Board) -> crate::Result<()> {
+        board.validate().map_err(Error::from)?;
+        Ok(())
+    }
+
+    fn solve(board: &Board) -> crate::Result<Option<Board>> {
+        Self::solve_reusing(board, &mut crate::puzzle::SolverScratch::default())
+    }
+
+    fn solve_reusing(
+        board: &Board,
+        scratch: &mut crate::puzzle::SolverScratch,
+    ) -> crate::Result<Option<Board>> {
+        let (solution, _, _) = solver::solve_reusing(board, &mut scratch.sudoku)?;
+        Ok(solution
+            .validate()
+            .map_err(Error::from)?
+            .finished()
+            .then_some(solution))
+    }
+
+    /// The number of guesses the solver makes.
+    fn rate(board: &Board) -> crate::Result<u64> {
+        Ok(rate(board)?.into())
+    }
+
+    /// Calibrated on the qqwing sets, where easy sudokus need no guesses and expert ones a few, while the hardest
+    /// known sudokus need hundreds.
+    fn grade(num_guesses: u64) -> crate::difficulty::Difficulty {
+        crate::difficulty::Difficulty::grade(num_guesses, [1, 3, 100])
+    }
+
+    fn count_solutions(board: &Board, limit: usize) -> crate::Result<usize> {
+        Ok(count_solutions(board, limit))
+    }
+
+    fn generate(config: &GeneratorConfig) -> crate::Result<Board> {
+        crate::generate::generate::<Self>(config)
+    }
+
+    fn generate_many(config: &GeneratorConfig, count: usize) -> crate::Result<Vec<(u64, Board)>> {
+        crate::generate::generate_many::<Self>(config, count)
+    }
+
+    fn format(solution: &Board) -> String {
+        let mut string = String::new();
+        solution
+            .format_line(&mut string, '.')
+            .expect("Writing to a string cannot fail.");
+        string
+    }
+
+    /// Compares cells, so the expected solution can be either a line or a grid.
+    fn diff(expected: &str, solution: &Board) -> Vec<String> {
+        let Ok(expected) = Self::parse(expected) else {
+            return crate::puzzle::diff_lines(expected, &Self::format(solution));
+        };
+        expected
+            .diff(solution)
+            .into_iter()
+            .map(|loc| {
+                let (row, col) = loc.to_row_col();
+                format!(
+                    "Row {}, column {}: expected {}, found {}.",
+                    row + 1,
+                    col + 1,
+                    expected.get(loc).to_char('.'),
+                    solution.get(loc).to_char('.')
+                )
+            })
+            .collect()
+    }
+
+    fn format_instance(board: &Board) -> String {
+        Self::format(board)
+    }
+
+    fn isomorphic_form(board: &Board) -> String {
+        board.isomorphic_form()
+    }
+
+    fn formats() -> Vec<crate::puzzle::Format<Board>> {
+        formats::formats()
+    }
+}
+
+impl Generator for Sudoku {
+    const DEFAULT_SIZE: usize = 9;
+
+    /// Candidates are minimal, so no given can be removed without losing uniqueness.
+    fn candidate(size: usize, rng: &mut StdRng) -> crate::Result<Option<Board>> {
+        crate::error::ensure!(
+            size == 9,
+            Invalid,
+            "Sudokus are always 9 by 9, not {size} by {size}."
+        );
+        generate(rng).map(Some)
+    }
+
+    /// Keeps the filled grid the givens were removed from, which is the solution since candidates are unique.
+    fn candidate_with_solution(
+        size: usize,
+        rng: &mut StdRng,
+    ) -> crate::Result<Option<(Board, Option<Board>)>> {
+        crate::error::ensure!(
+            size == 9,
+            Invalid,
+            "Sudokus are always 9 by 9, not {size} by {size}."
+        );
+        let (board, solution) = generate_with_solution(rng)?;
+        Ok(Some((board, Some(solution))))
+    }
+}
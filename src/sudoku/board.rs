@@ -1,9 +1,17 @@
-use anyhow::{bail, Context, Result};
+use std::{
+    fmt::{Display, Formatter, Write},
+    num::NonZeroU8,
+};
 use thiserror::Error;
-use std::{ fmt::{Display, Formatter, Write}, num::NonZeroU8};
 
+use crate::{grid::Symmetry, id::PuzzleId, location::Location as GridLocation};
 
-use super::{location_set, solver::{Cell, SolveState}, value_set::ValueSet};
+use super::{
+    error::Error as SudokuError,
+    location_set,
+    solver::{Cell, SolveState},
+    value_set::ValueSet,
+};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Location {
@@ -79,6 +87,16 @@ impl Location {
     pub const fn index(self) -> usize {
         self.index as usize
     }
+
+    /// The indices in [`location_set::GROUPS`] of the row, column and block of the location.
+    pub const fn groups(self) -> [usize; 3] {
+        let (row, col) = self.to_row_col();
+        [
+            row as usize,
+            9 + col as usize,
+            18 + (row / 3 * 3 + col / 3) as usize,
+        ]
+    }
 }
 
 impl Display for Location {
@@ -151,7 +169,35 @@ pub enum InvalidBoardError {
     #[error("Column {col_index} has duplicate value {value}")]
     DuplicateColumnValue { col_index: usize, value: CellValue },
     #[error("Block {block_index} has duplicate value {value}")]
-    DuplicateBlockValue { block_index: usize, value: CellValue },
+    DuplicateBlockValue {
+        block_index: usize,
+        value: CellValue,
+    },
+}
+
+impl InvalidBoardError {
+    /// The locations of the row, column or block with the duplicate value.
+    pub fn group(&self) -> [Location; 9] {
+        match *self {
+            InvalidBoardError::DuplicateRowValue { row_index, .. } => {
+                Location::row(row_index as u8)
+            }
+            InvalidBoardError::DuplicateColumnValue { col_index, .. } => {
+                Location::col(col_index as u8)
+            }
+            InvalidBoardError::DuplicateBlockValue { block_index, .. } => {
+                Location::block(block_index as u8)
+            }
+        }
+    }
+
+    pub fn value(&self) -> CellValue {
+        match *self {
+            InvalidBoardError::DuplicateRowValue { value, .. }
+            | InvalidBoardError::DuplicateColumnValue { value, .. }
+            | InvalidBoardError::DuplicateBlockValue { value, .. } => value,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -169,9 +215,46 @@ impl Board {
         }
     }
 
-    pub fn from_line(line: &str, empty_char: char) -> Result<Self> {
+    /// The id of the board, which is the same for every board with the same cells.
+    pub fn id(&self) -> PuzzleId {
+        PuzzleId::of::<super::Sudoku>(self)
+    }
+
+    /// The board as a line, in a form shared by every board that is the same sudoku rotated, reflected or with its
+    /// values renamed: the smallest line over the eight symmetries of the grid, with the values of each renamed to
+    /// count up in the order they first appear.
+    pub fn isomorphic_form(&self) -> String {
+        Symmetry::ALL
+            .into_iter()
+            .map(|symmetry| {
+                let mut cells = [BoardCell::Empty; 81];
+                for (index, &cell) in self.cells.iter().enumerate() {
+                    let to = symmetry.apply(GridLocation::new(index / 9, index % 9), (9, 9));
+                    cells[to.row * 9 + to.col] = cell;
+                }
+                let mut names = [None; 9];
+                let mut next = 0;
+                cells
+                    .iter()
+                    .map(|&cell| match cell {
+                        BoardCell::Empty => '.',
+                        BoardCell::Value(value) => {
+                            let name = names[usize::from(value) - 1].get_or_insert_with(|| {
+                                next += 1;
+                                next
+                            });
+                            char::from(b'0' + *name)
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .min()
+            .expect("There are always symmetries.")
+    }
+
+    pub fn from_line(line: &str, empty_char: char) -> Result<Self, SudokuError> {
         if line.len() != 81 {
-            bail!("Line must be exactly 81 characters long, but is {}. Line: '{line}'", line.len());
+            return Err(SudokuError::LineLength(line.len()));
         }
         let cells = line
             .chars()
@@ -179,46 +262,55 @@ impl Board {
             .map(|(index, c)| {
                 Ok(match c {
                     c if c == empty_char => BoardCell::Empty,
-                    c => {
-                        if let Some(digit) = c.to_digit(10) {
-                            BoardCell::Value(CellValue::new(NonZeroU8::new(digit.try_into().unwrap()).with_context(|| 
-                                format!("Invalid digit '{digit}' at index {index} in line '{line}'. '0' is not a valid character"))?
-                            ).unwrap())
-                        } else {
-                            bail!("Invalid character '{c}' at index {index} in line '{line}'.")
+                    c => match c.to_digit(10).and_then(|digit| NonZeroU8::new(digit as u8)) {
+                        Some(digit) => BoardCell::Value(CellValue::new(digit).unwrap()),
+                        None => {
+                            return Err(SudokuError::InvalidCharacter {
+                                c,
+                                location: Location::from_index(index).unwrap(),
+                            })
                         }
-                    }
+                    },
                 })
             })
-            .collect::<Result<Vec<_>>>()?;
+            .collect::<Result<Vec<_>, _>>()?;
         let cells: [BoardCell; 81] = cells.try_into().unwrap();
         Ok(Self { cells })
     }
 
-    pub fn from_grid(grid: &str, empty_char: char) -> Result<Self> {
+    pub fn from_grid(grid: &str, empty_char: char) -> Result<Self, SudokuError> {
         if grid.len() != 90 {
-            bail!("Grid must be exactly 90 characters long (81 for grid, 9 for newlines), but is {}. Grid: '{grid}'", grid.len());
+            return Err(SudokuError::GridLength(grid.len()));
         }
 
-        let cells: Vec<BoardCell> = grid.lines().enumerate().flat_map(|(row_index, line)|{
-            if line.len() != 9 {
-                bail!("Line must be exactly 9 characters long, but is {}. Line: '{line}'", line.len());
-            }
-            Ok(line.chars().enumerate().map(move |(col_index, c)| {
-                Ok(match c {
-                    c if c == empty_char => BoardCell::Empty,
-                    c => {
-                        if let Some(digit) = c.to_digit(10) {
-                            BoardCell::Value(CellValue::new(NonZeroU8::new(digit.try_into().unwrap()).with_context(|| 
-                                format!("Invalid digit '{digit}' at row {row_index}, column {col_index} in grid '{grid}'. '0' is not a valid character")
-                            )?).unwrap())
-                        } else {
-                            bail!("Invalid character '{c}' at row {row_index}, column {col_index} in grid '{grid}'.")
-                        }
-                    }
-                })
-            }))
-        }).flatten().collect::<Result<_>>()?;
+        let cells: Vec<BoardCell> = grid
+            .lines()
+            .enumerate()
+            .flat_map(|(row_index, line)| {
+                if line.len() != 9 {
+                    return Err(SudokuError::RowLength {
+                        row_index,
+                        length: line.len(),
+                    });
+                }
+                Ok(line.chars().enumerate().map(move |(col_index, c)| {
+                    Ok(match c {
+                        c if c == empty_char => BoardCell::Empty,
+                        c => match c.to_digit(10).and_then(|digit| NonZeroU8::new(digit as u8)) {
+                            Some(digit) => BoardCell::Value(CellValue::new(digit).unwrap()),
+                            None => {
+                                return Err(SudokuError::InvalidCharacter {
+                                    c,
+                                    location: Location::new(row_index as u8, col_index as u8)
+                                        .unwrap(),
+                                })
+                            }
+                        },
+                    })
+                }))
+            })
+            .flatten()
+            .collect::<Result<_, _>>()?;
         let cells: [BoardCell; 81] = cells.try_into().unwrap();
         Ok(Self { cells })
     }
@@ -271,14 +363,48 @@ impl Board {
         self.cells[loc.index()]
     }
 
+    pub fn set(&mut self, loc: Location, cell: BoardCell) {
+        self.cells[loc.index()] = cell;
+    }
+
+    /// The locations of the cells that differ between the boards.
+    pub fn diff(&self, other: &Board) -> Vec<Location> {
+        (0..81)
+            .filter_map(Location::from_index)
+            .filter(|&loc| self.get(loc) != other.get(loc))
+            .collect()
+    }
+
+    /// The locations of the values on the board that differ from the known solution, such as mistakes a player made.
+    /// Empty cells are not mistakes, so the solution is not revealed.
+    pub fn check_against(&self, solution: &Board) -> Vec<Location> {
+        (0..81)
+            .filter_map(Location::from_index)
+            .filter(|&loc| {
+                matches!(self.get(loc), BoardCell::Value(_)) && self.get(loc) != solution.get(loc)
+            })
+            .collect()
+    }
+
     pub fn validate(&self) -> Result<&Self, InvalidBoardError> {
+        match self.validate_all().into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(self),
+        }
+    }
+
+    /// Like [`Board::validate`], but finds every duplicate instead of stopping at the first, such as for showing
+    /// every conflict to a player.
+    pub fn validate_all(&self) -> Vec<InvalidBoardError> {
+        let mut errors = vec![];
+
         // Validate rows
         for (row_index, row) in location_set::ROWS.into_iter().enumerate() {
             let mut values = ValueSet::NONE;
             for cell in row.into_iter().map(|location| self.get(location)) {
                 if let BoardCell::Value(value) = cell {
                     if values.contains(value) {
-                        return Err(InvalidBoardError::DuplicateRowValue { row_index, value });
+                        errors.push(InvalidBoardError::DuplicateRowValue { row_index, value });
                     }
                     values |= ValueSet::from_value(value);
                 }
@@ -291,7 +417,7 @@ impl Board {
             for cell in col.into_iter().map(|location| self.get(location)) {
                 if let BoardCell::Value(value) = cell {
                     if values.contains(value) {
-                        return Err(InvalidBoardError::DuplicateColumnValue { col_index, value });
+                        errors.push(InvalidBoardError::DuplicateColumnValue { col_index, value });
                     }
                     values |= ValueSet::from_value(value);
                 }
@@ -304,14 +430,22 @@ impl Board {
             for cell in block.into_iter().map(|location| self.get(location)) {
                 if let BoardCell::Value(value) = cell {
                     if values.contains(value) {
-                        return Err(InvalidBoardError::DuplicateBlockValue { block_index, value });
+                        errors.push(InvalidBoardError::DuplicateBlockValue { block_index, value });
                     }
                     values |= ValueSet::from_value(value);
                 }
             }
         }
 
-        Ok(self)
+        errors
+    }
+
+    /// The number of cells with a value.
+    pub fn givens(&self) -> usize {
+        self.cells
+            .iter()
+            .filter(|&&cell| cell != BoardCell::Empty)
+            .count()
     }
 
     pub fn finished(&self) -> bool {
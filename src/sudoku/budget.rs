@@ -0,0 +1,169 @@
+//! Limits on how much work the solver may spend on a sudoku, by difficulty, so that a set of simple sudokus is not
+//! given the same room as the hardest known ones. Profiles are read from TOML like
+//!
+//! ```toml
+//! [budgets.easy]
+//! max_steps = 50
+//! max_guesses = 0
+//! techniques = ["groups", "box_line"]
+//!
+//! [sets]
+//! qqwing_simple = "easy"
+//! ```
+//!
+//! where difficulties without a profile get the [default](Budget::default) budget.
+
+use std::{collections::BTreeMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    difficulty::Difficulty,
+    error::{read_to_string, Error, Result},
+};
+
+use super::Technique;
+
+/// The most the solver may do before it gives up on a sudoku.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Budget {
+    pub max_steps: u32,
+    #[serde(default = "unlimited")]
+    pub max_guesses: u32,
+    /// The techniques the solver may use. Groups are always used, since the other techniques build on them.
+    #[serde(default = "all_techniques")]
+    pub techniques: Vec<Technique>,
+}
+
+fn unlimited() -> u32 {
+    u32::MAX
+}
+
+fn all_techniques() -> Vec<Technique> {
+    Technique::ALL.to_vec()
+}
+
+/// The budget that tripped when the solver gave up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Error)]
+pub enum Exceeded {
+    #[error("The solver took more than {0} steps.")]
+    Steps(u32),
+    #[error("The solver needed more than {0} guesses.")]
+    Guesses(u32),
+    #[error("The solver needed {}, which the budget does not allow.", .0.name())]
+    Technique(Technique),
+}
+
+impl Exceeded {
+    /// The name of the budget, such as for counting how often each tripped.
+    pub fn name(self) -> &'static str {
+        match self {
+            Exceeded::Steps(_) => "steps",
+            Exceeded::Guesses(_) => "guesses",
+            Exceeded::Technique(_) => "techniques",
+        }
+    }
+}
+
+/// As much as the solver always had: a thousand steps, with every technique and any number of guesses.
+impl Default for Budget {
+    fn default() -> Self {
+        Self {
+            max_steps: 1000,
+            max_guesses: unlimited(),
+            techniques: all_techniques(),
+        }
+    }
+}
+
+impl Budget {
+    pub fn allows(&self, technique: Technique) -> bool {
+        technique == Technique::Groups || self.techniques.contains(&technique)
+    }
+}
+
+/// Budgets by difficulty, and the difficulty of each named set of sudokus.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Budgets {
+    #[serde(default)]
+    pub budgets: BTreeMap<Difficulty, Budget>,
+    #[serde(default)]
+    pub sets: BTreeMap<String, Difficulty>,
+}
+
+impl Budgets {
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        toml::from_str(toml).map_err(|err| Error::Parse(err.to_string()))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_toml(&read_to_string("sudoku budget", path.as_ref())?)
+    }
+
+    /// The budget of the difficulty, or the default if it has none.
+    pub fn for_difficulty(&self, difficulty: Difficulty) -> Budget {
+        self.budgets.get(&difficulty).cloned().unwrap_or_default()
+    }
+
+    /// The budget of the difficulty of the set, or the default if the set has no difficulty.
+    pub fn for_set(&self, name: &str) -> Budget {
+        self.sets
+            .get(name)
+            .map_or_else(Budget::default, |&difficulty| {
+                self.for_difficulty(difficulty)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cancel::CancelToken,
+        sudoku::{solve_with_budget, Board, Error as SudokuError},
+    };
+
+    fn over_budget(line: &str, budget: &Budget) -> Option<Exceeded> {
+        let board = Board::from_line(line, '.').unwrap();
+        match solve_with_budget(&board, budget, &CancelToken::new()) {
+            Ok(_) => None,
+            Err(Error::Sudoku(SudokuError::OverBudget(exceeded))) => Some(exceeded),
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    #[test]
+    fn trips_the_budget_it_goes_over() {
+        let budgets = Budgets::from_toml(include_str!("../../data/sudoku/budgets.toml")).unwrap();
+        let easy = budgets.for_set("qqwing_simple");
+        assert_eq!(easy, budgets.for_difficulty(Difficulty::Easy));
+        assert!(!easy.allows(Technique::Guesses));
+        assert_eq!(budgets.for_set("unknown"), Budget::default());
+
+        let simple = include_str!("../../data/sudoku/grids/qqwing_simple.txt")
+            .lines()
+            .next()
+            .unwrap();
+        assert_eq!(over_budget(simple, &easy), None);
+        let expert = include_str!("../../data/sudoku/grids/qqwing_expert.txt")
+            .lines()
+            .next()
+            .unwrap();
+        assert_eq!(
+            over_budget(expert, &easy),
+            Some(Exceeded::Technique(Technique::Guesses))
+        );
+        let few_guesses = Budget {
+            max_guesses: 1,
+            ..Budget::default()
+        };
+        assert_eq!(
+            over_budget(expert, &few_guesses),
+            Some(Exceeded::Guesses(1))
+        );
+        assert_eq!(over_budget(expert, &budgets.for_set("qqwing_expert")), None);
+    }
+}
@@ -0,0 +1,359 @@
+//! Certificates that a sudoku can be solved by logic alone, so that claims about how hard a sudoku is can be checked
+//! without running the solver. A certificate lists every deduction from the givens to the solution, each with what it
+//! follows from, and [`verify_certificate`] replays them, checking each against the candidates the ones before it
+//! leave.
+//!
+//! Cells are numbered from 0 to 80 row by row, and groups by their index in [`GROUPS`]: the rows from 0, the columns
+//! from 9 and the blocks from 18. Certificates are written as JSON like
+//!
+//! ```json
+//! {"puzzle": "..3.2.6..", "technique": "groups", "steps": [{"step": "naked_single", "cell": 0, "value": 4}]}
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{ensure, Result},
+    puzzle::Puzzle,
+};
+
+use super::{
+    board::{BoardCell, CellValue, Location},
+    error::Error,
+    location_set::{LocationSet, GROUPS},
+    value_set::ValueSet,
+    Board, Sudoku, Technique,
+};
+
+/// A deduction, and what it follows from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum Step {
+    /// Every other value is ruled out in the cell.
+    NakedSingle { cell: usize, value: u8 },
+    /// The value is ruled out in every other cell of the group.
+    HiddenSingle {
+        group: usize,
+        cell: usize,
+        value: u8,
+    },
+    /// The cells of `from` that the value can go in all lie in `to`, where one of them is a block and the other a
+    /// row or column, so the value is ruled out in the rest of `to`.
+    BoxLine { from: usize, to: usize, value: u8 },
+}
+
+impl Step {
+    /// The technique of the solver that makes the deduction.
+    pub fn technique(self) -> Technique {
+        match self {
+            Step::NakedSingle { .. } | Step::HiddenSingle { .. } => Technique::Groups,
+            Step::BoxLine { .. } => Technique::BoxLine,
+        }
+    }
+}
+
+/// The deductions that solve a sudoku without guessing.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Certificate {
+    /// The sudoku as a line, with '.' for empty cells.
+    pub puzzle: String,
+    /// The hardest technique the steps may use, which is what the certificate claims about the sudoku.
+    pub technique: Technique,
+    pub steps: Vec<Step>,
+}
+
+/// Solves the sudoku by singles, and box/line reductions where the singles run out, recording every deduction. Fails
+/// with [`Error::NeedsGuesses`] if the sudoku cannot be solved without guessing, and with [`Error::NoValuesLeft`] if
+/// the deductions show it has no solution.
+pub fn certify(board: &Board) -> crate::Result<Certificate> {
+    board.validate().map_err(Error::from)?;
+    let mut candidates = Candidates::new(board);
+    let mut steps = vec![];
+    while let Some(step) = candidates.next_step() {
+        candidates
+            .apply(step)
+            .expect("Steps are only found where they hold.");
+        steps.push(step);
+    }
+    if let Some(location) = candidates.empty().next() {
+        return Err(if candidates.possible(location).is_empty() {
+            Error::NoValuesLeft
+        } else {
+            Error::NeedsGuesses
+        }
+        .into());
+    }
+    Ok(Certificate {
+        puzzle: Sudoku::format(board),
+        technique: steps
+            .iter()
+            .map(|step| step.technique())
+            .max()
+            .unwrap_or(Technique::Groups),
+        steps,
+    })
+}
+
+/// Replays the steps of the certificate from its sudoku, checking that each follows from the candidates left by the
+/// ones before it and uses no harder technique than the certificate claims, and that together they fill the board.
+/// Since every step is forced, the solution is then the only one. Returns the solution.
+pub fn verify_certificate(certificate: &Certificate) -> crate::Result<Board> {
+    let board = Board::from_line(&certificate.puzzle, '.')?;
+    board.validate().map_err(Error::from)?;
+    let mut candidates = Candidates::new(&board);
+    for (index, &step) in certificate.steps.iter().enumerate() {
+        ensure!(
+            step.technique() <= certificate.technique,
+            Invalid,
+            "Step {index} uses {}, which is harder than the {} the certificate claims.",
+            step.technique().name(),
+            certificate.technique.name()
+        );
+        candidates.apply(step).map_err(|reason| {
+            crate::Error::Invalid(format!("Step {index} does not hold: {reason}"))
+        })?;
+    }
+    let empty = candidates.empty().count();
+    ensure!(empty == 0, Invalid, "The steps leave {empty} cells empty.");
+    Ok(candidates.board)
+}
+
+/// The values placed so far, and the candidates ruled out by box/line reductions.
+struct Candidates {
+    board: Board,
+    /// The values placed in each group of [`GROUPS`].
+    used: [ValueSet; 27],
+    removed: [ValueSet; 81],
+}
+
+impl Candidates {
+    fn new(board: &Board) -> Self {
+        let mut candidates = Self {
+            board: board.clone(),
+            used: [ValueSet::NONE; 27],
+            removed: [ValueSet::NONE; 81],
+        };
+        for location in (0..81).filter_map(Location::from_index) {
+            if let BoardCell::Value(value) = board.get(location) {
+                candidates.place(location, value);
+            }
+        }
+        candidates
+    }
+
+    fn empty(&self) -> impl Iterator<Item = Location> + '_ {
+        (0..81)
+            .filter_map(Location::from_index)
+            .filter(|&location| self.board.get(location) == BoardCell::Empty)
+    }
+
+    fn place(&mut self, location: Location, value: CellValue) {
+        self.board.set(location, BoardCell::Value(value));
+        for group_id in location.groups() {
+            self.used[group_id] |= ValueSet::from_value(value);
+        }
+    }
+
+    /// The values that can still go in the cell, which are none if it is filled.
+    fn possible(&self, location: Location) -> ValueSet {
+        if self.board.get(location) != BoardCell::Empty {
+            return ValueSet::NONE;
+        }
+        let used = location
+            .groups()
+            .into_iter()
+            .fold(ValueSet::NONE, |used, group_id| used | self.used[group_id]);
+        ValueSet::ALL - used - self.removed[location.index()]
+    }
+
+    /// The cells of the group that the value can still go in.
+    fn places(&self, group_id: usize, value: CellValue) -> LocationSet {
+        GROUPS[group_id]
+            .into_iter()
+            .filter(|&location| self.possible(location).contains(value))
+            .collect()
+    }
+
+    /// Whether the value can only go where the groups, a block and a line, share cells, so that it can be ruled out
+    /// in the rest of `to`.
+    fn confined(&self, from: usize, to: usize, value: CellValue) -> bool {
+        let places = self.places(from, value);
+        (from < 18) != (to < 18)
+            && !places.is_empty()
+            && (GROUPS[from] & GROUPS[to]).is_superset(places)
+    }
+
+    /// The first deduction left, preferring singles over box/line reductions.
+    fn next_step(&self) -> Option<Step> {
+        for location in self.empty() {
+            if let Some(value) = self.possible(location).single() {
+                return Some(Step::NakedSingle {
+                    cell: location.index(),
+                    value: value.into(),
+                });
+            }
+        }
+        for group_id in 0..GROUPS.len() {
+            for value in ValueSet::ALL.iter() {
+                if let Some(location) = self.places(group_id, value).single() {
+                    return Some(Step::HiddenSingle {
+                        group: group_id,
+                        cell: location.index(),
+                        value: value.into(),
+                    });
+                }
+            }
+        }
+        for (from, to) in (0..27).flat_map(|from| (0..27).map(move |to| (from, to))) {
+            for value in ValueSet::ALL.iter() {
+                let rest = GROUPS[to] - GROUPS[from];
+                if self.confined(from, to, value)
+                    && rest
+                        .into_iter()
+                        .any(|loc| self.possible(loc).contains(value))
+                {
+                    return Some(Step::BoxLine {
+                        from,
+                        to,
+                        value: value.into(),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Checks that the step follows from the candidates, and applies it. Returns why it does not follow otherwise.
+    fn apply(&mut self, step: Step) -> Result<(), String> {
+        match step {
+            Step::NakedSingle { cell, value } => {
+                let (location, value) = (location(cell)?, cell_value(value)?);
+                if self.possible(location) != ValueSet::from_value(value) {
+                    return Err(format!(
+                        "{value} is not the only value left in cell {cell}."
+                    ));
+                }
+                self.place(location, value);
+            }
+            Step::HiddenSingle { group, cell, value } => {
+                let (location, value) = (location(cell)?, cell_value(value)?);
+                if self.places(group_id(group)?, value) != LocationSet::from_location(location) {
+                    return Err(format!(
+                        "Cell {cell} is not the only place left for {value} in group {group}."
+                    ));
+                }
+                self.place(location, value);
+            }
+            Step::BoxLine { from, to, value } => {
+                let value = cell_value(value)?;
+                if !self.confined(group_id(from)?, group_id(to)?, value) {
+                    return Err(format!(
+                        "{value} is not confined to the cells group {from} shares with group {to}."
+                    ));
+                }
+                for location in GROUPS[to] - GROUPS[from] {
+                    self.removed[location.index()] |= ValueSet::from_value(value);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn location(cell: usize) -> Result<Location, String> {
+    Location::from_index(cell).ok_or_else(|| format!("There is no cell {cell}."))
+}
+
+fn cell_value(value: u8) -> Result<CellValue, String> {
+    std::num::NonZeroU8::new(value)
+        .and_then(CellValue::new)
+        .ok_or_else(|| format!("{value} is not a sudoku value."))
+}
+
+fn group_id(group: usize) -> Result<usize, String> {
+    if group < GROUPS.len() {
+        Ok(group)
+    } else {
+        Err(format!("There is no group {group}."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cancel::CancelToken, sudoku::solve_with_stats};
+
+    fn board(set: &str, index: usize) -> Board {
+        let line = set.lines().nth(index).unwrap();
+        Board::from_line(line, '.').unwrap()
+    }
+
+    #[test]
+    fn certifies_sudokus_solved_without_guesses() {
+        let sets = [
+            include_str!("../../data/sudoku/grids/qqwing_simple.txt"),
+            include_str!("../../data/sudoku/grids/easy50.txt"),
+            include_str!("../../data/sudoku/grids/qqwing_intermediate.txt"),
+        ];
+        let mut techniques = vec![];
+        for board in sets
+            .iter()
+            .flat_map(|set| (0..10).map(|index| board(set, index)))
+        {
+            let (solution, stats) = solve_with_stats(&board, &CancelToken::new()).unwrap();
+            let Ok(certificate) = certify(&board) else {
+                assert!(stats.guesses > 0);
+                continue;
+            };
+            assert_eq!(certificate.technique, Technique::hardest(&board, &stats));
+            let json = serde_json::to_string(&certificate).unwrap();
+            let read = serde_json::from_str::<Certificate>(&json).unwrap();
+            assert!(verify_certificate(&read)
+                .unwrap()
+                .diff(&solution)
+                .is_empty());
+            if certificate.technique == Technique::BoxLine {
+                let understated = Certificate {
+                    technique: Technique::Groups,
+                    ..certificate.clone()
+                };
+                assert!(verify_certificate(&understated).is_err());
+            }
+            techniques.push(certificate.technique);
+        }
+        assert!(
+            techniques.contains(&Technique::Groups) && techniques.contains(&Technique::BoxLine)
+        );
+    }
+
+    #[test]
+    fn rejects_false_certificates() {
+        let hardest = board(include_str!("../../data/sudoku/grids/hardest.txt"), 1);
+        assert!(matches!(
+            certify(&hardest),
+            Err(crate::Error::Sudoku(Error::NeedsGuesses))
+        ));
+
+        let sudoku = board(include_str!("../../data/sudoku/grids/easy50.txt"), 0);
+        let certificate = certify(&sudoku).unwrap();
+        assert!(verify_certificate(&certificate).is_ok());
+
+        let mut unfinished = certificate.clone();
+        unfinished.steps.pop();
+        assert!(verify_certificate(&unfinished).is_err());
+
+        let mut wrong = certificate.clone();
+        let Step::NakedSingle { cell, value } = wrong.steps[0] else {
+            panic!("the first step is not a naked single")
+        };
+        wrong.steps[0] = Step::NakedSingle {
+            cell,
+            value: value % 9 + 1,
+        };
+        assert!(verify_certificate(&wrong).is_err());
+
+        let mut guessed = certificate;
+        guessed.puzzle = Sudoku::format(&hardest);
+        assert!(verify_certificate(&guessed).is_err());
+    }
+}
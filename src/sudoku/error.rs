@@ -0,0 +1,35 @@
+use thiserror::Error;
+
+use super::{
+    board::{CellValue, InvalidBoardError, Location},
+    budget::Exceeded,
+    value_set::ValueSet,
+};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Error)]
+pub enum Error {
+    #[error("Line must be exactly 81 characters long, but is {0}.")]
+    LineLength(usize),
+    #[error("Grid must be exactly 90 characters long (81 for grid, 9 for newlines), but is {0}.")]
+    GridLength(usize),
+    #[error("Row {row_index} must be exactly 9 characters long, but is {length}.")]
+    RowLength { row_index: usize, length: usize },
+    #[error("Invalid character '{c}' at {location}.")]
+    InvalidCharacter { c: char, location: Location },
+    #[error(transparent)]
+    InvalidBoard(#[from] InvalidBoardError),
+    #[error("Duplicate value {value} in group {group_id}.")]
+    DuplicateValue { group_id: usize, value: CellValue },
+    #[error("No possible values left for cell.")]
+    NoValuesLeft,
+    #[error("Cell value {value} is not possible according to value set {values}.")]
+    ImpossibleValue { value: CellValue, values: ValueSet },
+    #[error("Location {0} is not empty.")]
+    NotEmpty(Location),
+    /// The sudoku cannot be solved by logic alone, so it has no [certificate](super::certify).
+    #[error("The sudoku cannot be solved without guessing.")]
+    NeedsGuesses,
+    /// The solver gave up, as it would have gone over its [`Budget`](super::Budget).
+    #[error(transparent)]
+    OverBudget(#[from] Exceeded),
+}
@@ -0,0 +1,380 @@
+//! Explains why a value cannot go in a cell, as the chain of singles that leads from placing it there to a
+//! contradiction, for tutoring tools that show players where they went wrong.
+//!
+//! Only the singles that the groups technique of the solver finds are used, so a candidate that needs box/line
+//! reductions or guesses to rule out is reported as possible.
+
+use std::fmt::{self, Display, Formatter};
+
+use super::{
+    board::{BoardCell, CellValue, Location},
+    error::Error,
+    location_set::GROUPS,
+    value_set::ValueSet,
+    Board,
+};
+
+/// A value placed while explaining, and why.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Deduction {
+    pub location: Location,
+    pub value: CellValue,
+    pub reason: Reason,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Reason {
+    /// The value is on the board.
+    Given,
+    /// The value is placed to show that it leads to a contradiction.
+    Assumed,
+    /// Every other value is ruled out in the cell.
+    NakedSingle,
+    /// The value is ruled out in every other cell of the group with the index in [`GROUPS`].
+    HiddenSingle { group_id: usize },
+}
+
+/// What the deductions end in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Contradiction {
+    /// Every value is ruled out in the cell.
+    NoValues(Location),
+    /// The value is ruled out in every cell of the group with the index in [`GROUPS`].
+    NoPlace { group_id: usize, value: CellValue },
+    /// The value is placed twice in the group with the index in [`GROUPS`].
+    Duplicate { group_id: usize, value: CellValue },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Explanation {
+    /// No chain of singles rules the value out. It may still be wrong, but showing so needs harder techniques.
+    Possible,
+    /// The deductions, starting with the assumed value, that lead to the contradiction. Each only depends on the
+    /// board and the deductions before it.
+    Impossible {
+        deductions: Vec<Deduction>,
+        contradiction: Contradiction,
+    },
+}
+
+/// Finds a short chain of singles that shows that `value` cannot go at `location`. Every single is applied at once,
+/// round by round, so the contradiction is reached in as few rounds as possible, and only the deductions it depends
+/// on are kept. Fails if the cell is not empty.
+pub fn explain(board: &Board, location: Location, value: CellValue) -> crate::Result<Explanation> {
+    if board.get(location) != BoardCell::Empty {
+        return Err(Error::NotEmpty(location).into());
+    }
+    let mut chain = Chain::new(board);
+    chain.push(location, value, Reason::Assumed);
+    loop {
+        if let Some((contradiction, depends_on)) = chain.contradiction() {
+            return Ok(chain.explain(contradiction, depends_on));
+        }
+        if !chain.round() {
+            return Ok(Explanation::Possible);
+        }
+    }
+}
+
+/// Every value placed so far, in the order they were placed.
+struct Chain {
+    deductions: Vec<Deduction>,
+    /// The index in `deductions` of the value placed in each cell.
+    placed: [Option<usize>; 81],
+}
+
+impl Chain {
+    fn new(board: &Board) -> Self {
+        let mut chain = Self {
+            deductions: vec![],
+            placed: [None; 81],
+        };
+        for (index, &cell) in board.cells().iter().enumerate() {
+            if let BoardCell::Value(value) = cell {
+                chain.push(Location::from_index(index).unwrap(), value, Reason::Given);
+            }
+        }
+        chain
+    }
+
+    fn push(&mut self, location: Location, value: CellValue, reason: Reason) {
+        self.placed[location.index()] = Some(self.deductions.len());
+        self.deductions.push(Deduction {
+            location,
+            value,
+            reason,
+        });
+    }
+
+    /// The first deduction that rules `value` out at `location`: a different value in the cell, or the same value
+    /// elsewhere in one of its groups.
+    fn ruled_out_by(&self, location: Location, value: CellValue) -> Option<usize> {
+        let in_cell =
+            self.placed[location.index()].filter(|&index| self.deductions[index].value != value);
+        let in_groups = location
+            .groups()
+            .into_iter()
+            .flat_map(|group_id| GROUPS[group_id])
+            .filter(|&other| other != location)
+            .filter_map(|other| self.placed[other.index()])
+            .filter(|&index| self.deductions[index].value == value);
+        in_cell.into_iter().chain(in_groups).min()
+    }
+
+    fn possible_values(&self, location: Location) -> ValueSet {
+        ValueSet::ALL
+            .iter()
+            .filter(|&value| self.ruled_out_by(location, value).is_none())
+            .collect()
+    }
+
+    /// Finds a contradiction, with the deductions it depends on.
+    fn contradiction(&self) -> Option<(Contradiction, Vec<usize>)> {
+        for (group_id, group) in GROUPS.into_iter().enumerate() {
+            for value in ValueSet::ALL.iter() {
+                let placed = group
+                    .into_iter()
+                    .filter_map(|location| self.placed[location.index()])
+                    .filter(|&index| self.deductions[index].value == value)
+                    .collect::<Vec<_>>();
+                if placed.len() > 1 {
+                    return Some((Contradiction::Duplicate { group_id, value }, placed));
+                }
+                if placed.is_empty() {
+                    let depends_on = group
+                        .into_iter()
+                        .map(|location| self.ruled_out_by(location, value))
+                        .collect::<Option<Vec<_>>>();
+                    if let Some(depends_on) = depends_on {
+                        return Some((Contradiction::NoPlace { group_id, value }, depends_on));
+                    }
+                }
+            }
+        }
+        (0..81)
+            .filter_map(Location::from_index)
+            .find_map(|location| {
+                if self.placed[location.index()].is_some() {
+                    return None;
+                }
+                let depends_on = ValueSet::ALL
+                    .iter()
+                    .map(|value| self.ruled_out_by(location, value))
+                    .collect::<Option<Vec<_>>>()?;
+                Some((Contradiction::NoValues(location), depends_on))
+            })
+    }
+
+    /// Places every single that follows from the values placed so far. Returns whether any did.
+    fn round(&mut self) -> bool {
+        let mut singles = vec![];
+        for location in (0..81).filter_map(Location::from_index) {
+            if self.placed[location.index()].is_none() {
+                if let Some(value) = self.possible_values(location).single() {
+                    singles.push((location, value, Reason::NakedSingle));
+                }
+            }
+        }
+        for (group_id, group) in GROUPS.into_iter().enumerate() {
+            for value in ValueSet::ALL.iter() {
+                let mut places = group.into_iter().filter(|&location| {
+                    self.placed[location.index()].is_none()
+                        && self.ruled_out_by(location, value).is_none()
+                });
+                if let (Some(location), None) = (places.next(), places.next()) {
+                    singles.push((location, value, Reason::HiddenSingle { group_id }));
+                }
+            }
+        }
+        let mut placed_any = false;
+        for (location, value, reason) in singles {
+            // A cell can be a single for two values at once, which the next round finds to be a contradiction.
+            if self.placed[location.index()].is_none() {
+                self.push(location, value, reason);
+                placed_any = true;
+            }
+        }
+        placed_any
+    }
+
+    /// The deductions that `index` was placed because of.
+    fn depends_on(&self, index: usize) -> Vec<usize> {
+        let Deduction {
+            location,
+            value,
+            reason,
+        } = self.deductions[index];
+        let ruled_out = |location: Location, value: CellValue| {
+            self.ruled_out_by(location, value)
+                .expect("A single only follows from values that are ruled out.")
+        };
+        match reason {
+            Reason::Given | Reason::Assumed => vec![],
+            Reason::NakedSingle => ValueSet::ALL
+                .iter()
+                .filter(|&other| other != value)
+                .map(|other| ruled_out(location, other))
+                .collect(),
+            Reason::HiddenSingle { group_id } => GROUPS[group_id]
+                .into_iter()
+                .filter(|&other| other != location)
+                .map(|other| ruled_out(other, value))
+                .collect(),
+        }
+    }
+
+    /// Keeps the deductions the contradiction depends on, leaving out the values on the board.
+    fn explain(&self, contradiction: Contradiction, depends_on: Vec<usize>) -> Explanation {
+        let mut needed = vec![false; self.deductions.len()];
+        let mut stack = depends_on;
+        while let Some(index) = stack.pop() {
+            if !needed[index] {
+                needed[index] = true;
+                stack.extend(self.depends_on(index));
+            }
+        }
+        let deductions = self
+            .deductions
+            .iter()
+            .zip(needed)
+            .filter(|(deduction, needed)| *needed && deduction.reason != Reason::Given)
+            .map(|(&deduction, _)| deduction)
+            .collect::<Vec<_>>();
+        let assumed = self
+            .deductions
+            .iter()
+            .find(|deduction| deduction.reason == Reason::Assumed)
+            .copied()
+            .expect("Explaining always starts by assuming a value.");
+        // The contradiction might not need the assumed value when the board contradicts itself, but it is where the
+        // chain starts.
+        let deductions = match deductions.first() {
+            Some(first) if first.reason == Reason::Assumed => deductions,
+            _ => std::iter::once(assumed).chain(deductions).collect(),
+        };
+        Explanation::Impossible {
+            deductions,
+            contradiction,
+        }
+    }
+}
+
+/// The name of the group with the index in [`GROUPS`], counting from 1 like the rows and columns of a location are
+/// shown to players.
+fn group_name(group_id: usize) -> String {
+    match group_id {
+        0..9 => format!("row {}", group_id + 1),
+        9..18 => format!("column {}", group_id - 8),
+        _ => format!("block {}", group_id - 17),
+    }
+}
+
+fn cell_name(location: Location) -> String {
+    let (row, col) = location.to_row_col();
+    format!("row {}, column {}", row + 1, col + 1)
+}
+
+impl Display for Deduction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let cell = cell_name(self.location);
+        let value = self.value;
+        match self.reason {
+            Reason::Given => write!(f, "{value} is given at {cell}."),
+            Reason::Assumed => write!(f, "Suppose {value} goes at {cell}."),
+            Reason::NakedSingle => write!(f, "Then only {value} fits at {cell}."),
+            Reason::HiddenSingle { group_id } => {
+                write!(
+                    f,
+                    "Then {value} only fits in {} at {cell}.",
+                    group_name(group_id)
+                )
+            }
+        }
+    }
+}
+
+impl Display for Contradiction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            Contradiction::NoValues(location) => {
+                write!(f, "Then nothing fits at {}.", cell_name(location))
+            }
+            Contradiction::NoPlace { group_id, value } => {
+                write!(f, "Then {value} fits nowhere in {}.", group_name(group_id))
+            }
+            Contradiction::Duplicate { group_id, value } => {
+                write!(f, "Then {} has {value} twice.", group_name(group_id))
+            }
+        }
+    }
+}
+
+/// One line per deduction, ending with the contradiction.
+impl Display for Explanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Explanation::Possible => write!(f, "No chain of singles rules the value out."),
+            Explanation::Impossible {
+                deductions,
+                contradiction,
+            } => {
+                for deduction in deductions {
+                    writeln!(f, "{deduction}")?;
+                }
+                write!(f, "{contradiction}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::solve;
+
+    #[test]
+    fn explains_wrong_candidates() {
+        let line = include_str!("../../data/sudoku/grids/easy50.txt")
+            .lines()
+            .next()
+            .unwrap();
+        let board = Board::from_line(line, '.').unwrap();
+        let (solution, _, _) = solve(&board).unwrap();
+        let location = (0..81)
+            .filter_map(Location::from_index)
+            .find(|&loc| board.get(loc) == BoardCell::Empty)
+            .unwrap();
+        let BoardCell::Value(right) = solution.get(location) else {
+            panic!("solution has an empty cell")
+        };
+        assert_eq!(
+            explain(&board, location, right).unwrap(),
+            Explanation::Possible
+        );
+
+        for wrong in ValueSet::ALL.iter().filter(|&value| value != right) {
+            let Explanation::Impossible { deductions, .. } =
+                explain(&board, location, wrong).unwrap()
+            else {
+                panic!("{wrong} at {location} is not ruled out");
+            };
+            assert_eq!(
+                deductions[0],
+                Deduction {
+                    location,
+                    value: wrong,
+                    reason: Reason::Assumed
+                }
+            );
+            assert!(deductions[1..]
+                .iter()
+                .all(|deduction| deduction.reason != Reason::Assumed));
+        }
+
+        let given = (0..81)
+            .filter_map(Location::from_index)
+            .find(|&loc| board.get(loc) != BoardCell::Empty)
+            .unwrap();
+        assert!(explain(&board, given, right).is_err());
+    }
+}
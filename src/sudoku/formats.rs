@@ -0,0 +1,72 @@
+use crate::{
+    error::{ensure, Result},
+    puzzle::{Format, Puzzle},
+};
+
+use super::{Board, BoardCell, Location, Sudoku};
+
+/// Lines of 81 characters, grids of nine lines, SadMan Sudoku files, which are grids that may start with comment
+/// lines such as `#A Author`, and JSON arrays of nine rows of numbers, with 0 for empty cells.
+pub fn formats() -> Vec<Format<Board>> {
+    vec![
+        Format::new("line", "txt", Sudoku::parse, |board| {
+            Ok(Sudoku::format_instance(board))
+        })
+        .single_line(),
+        Format::new("grid", "txt", Sudoku::parse, |board| Ok(grid(board))),
+        Format::new("sdk", "sdk", read_sdk, |board| Ok(grid(board))),
+        Format::new("json", "json", read_json, write_json),
+    ]
+}
+
+fn grid(board: &Board) -> String {
+    let mut string = String::new();
+    board
+        .format_compact_grid(&mut string, '.')
+        .expect("Writing to a string cannot fail.");
+    string
+}
+
+fn read_sdk(input: &str) -> Result<Board> {
+    let grid = input
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Sudoku::parse(&grid)
+}
+
+fn read_json(input: &str) -> Result<Board> {
+    let rows: Vec<Vec<u8>> = serde_json::from_str(input)?;
+    ensure!(
+        rows.len() == 9 && rows.iter().all(|row| row.len() == 9),
+        Parse,
+        "Expected nine rows of nine numbers."
+    );
+    let line = rows
+        .iter()
+        .flatten()
+        .map(|&value| match value {
+            0 => Ok('.'),
+            1..=9 => Ok(char::from(b'0' + value)),
+            _ => Err(crate::Error::Parse(format!(
+                "Expected numbers from 0 to 9. Got {value}."
+            ))),
+        })
+        .collect::<Result<String>>()?;
+    Sudoku::parse(&line)
+}
+
+fn write_json(board: &Board) -> Result<String> {
+    let rows = (0..81)
+        .filter_map(Location::from_index)
+        .map(|location| match board.get(location) {
+            BoardCell::Empty => 0,
+            BoardCell::Value(value) => u8::from(value),
+        })
+        .collect::<Vec<_>>()
+        .chunks(9)
+        .map(<[u8]>::to_vec)
+        .collect::<Vec<_>>();
+    Ok(serde_json::to_string(&rows)?)
+}
@@ -0,0 +1,191 @@
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    cancel::CancelToken,
+    difficulty::Rating,
+    error::{bail, ensure},
+    puzzle::Puzzle,
+};
+
+use super::{
+    board::{BoardCell, Location},
+    completion::complete_random,
+    solver::count_solutions,
+    Board, Sudoku,
+};
+
+/// Generates a sudoku with a unique solution, from which no given can be removed without losing uniqueness.
+pub fn generate(rng: &mut impl Rng) -> crate::Result<Board> {
+    generate_with_solution(rng).map(|(board, _)| board)
+}
+
+/// Like [`generate`], but checks `cancel` before removing each given and fails with [`crate::Error::Cancelled`] once
+/// it is cancelled.
+pub fn generate_cancellable(rng: &mut impl Rng, cancel: &CancelToken) -> crate::Result<Board> {
+    generate_inner(rng, cancel).map(|(board, _)| board)
+}
+
+/// Like [`generate`], but also returns the solution, which is the filled grid the givens are removed from.
+pub fn generate_with_solution(rng: &mut impl Rng) -> crate::Result<(Board, Board)> {
+    generate_inner(rng, &CancelToken::new())
+}
+
+fn generate_inner(rng: &mut impl Rng, cancel: &CancelToken) -> crate::Result<(Board, Board)> {
+    let blank = Board::from_line(&".".repeat(81), '.')?;
+    let Some((solution, _)) = complete_random(&blank, rng) else {
+        bail!(Generate, "Failed to complete a blank sudoku.");
+    };
+
+    let mut board = solution.clone();
+    let mut locations = (0..81).filter_map(Location::from_index).collect::<Vec<_>>();
+    locations.shuffle(rng);
+    for location in locations {
+        cancel.check()?;
+        let cell = board.get(location);
+        board.set(location, BoardCell::Empty);
+        if count_solutions(&board, 2) != 1 {
+            board.set(location, cell);
+        }
+    }
+    Ok((board, solution))
+}
+
+/// Finds up to `limit` minimal sub-puzzles of the board, each with its rating: the subsets of its givens that still
+/// have a unique solution, but lose it if any more are removed. The board may be a filled grid or a puzzle, but must
+/// have a unique solution itself.
+///
+/// Givens are removed in the order of their locations, so each subset is found once. Removing givens never makes
+/// a solution unique again, so a given that cannot be removed is not tried again further down.
+pub fn minimal_subpuzzles(board: &Board, limit: usize) -> crate::Result<Vec<(Board, Rating)>> {
+    ensure!(
+        count_solutions(board, 2) == 1,
+        Invalid,
+        "Only a sudoku with a unique solution has minimal sub-puzzles."
+    );
+    let givens = (0..81)
+        .filter_map(Location::from_index)
+        .filter(|&location| board.get(location) != BoardCell::Empty)
+        .collect::<Vec<_>>();
+    let mut subpuzzles = vec![];
+    explore(&mut board.clone(), &givens, limit, &mut subpuzzles);
+    subpuzzles
+        .into_iter()
+        .map(|subpuzzle| {
+            let rating = Sudoku::rating(&subpuzzle)?;
+            Ok((subpuzzle, rating))
+        })
+        .collect()
+}
+
+/// Removes each of the candidates in turn, and then the candidates after it, keeping the board when no given can be
+/// removed at all.
+fn explore(board: &mut Board, candidates: &[Location], limit: usize, subpuzzles: &mut Vec<Board>) {
+    let removable = candidates
+        .iter()
+        .copied()
+        .filter(|&location| is_removable(board, location))
+        .collect::<Vec<_>>();
+    if removable.is_empty() {
+        // Givens before the candidates were skipped rather than removed, and may still be removable.
+        let minimal = (0..81).filter_map(Location::from_index).all(|location| {
+            board.get(location) == BoardCell::Empty || !is_removable(board, location)
+        });
+        if minimal {
+            subpuzzles.push(board.clone());
+        }
+        return;
+    }
+    for (index, &location) in removable.iter().enumerate() {
+        if subpuzzles.len() >= limit {
+            return;
+        }
+        let cell = board.get(location);
+        board.set(location, BoardCell::Empty);
+        explore(board, &removable[index + 1..], limit, subpuzzles);
+        board.set(location, cell);
+    }
+}
+
+fn is_removable(board: &mut Board, location: Location) -> bool {
+    let cell = board.get(location);
+    board.set(location, BoardCell::Empty);
+    let unique = count_solutions(board, 2) == 1;
+    board.set(location, cell);
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::sudoku::solve;
+
+    #[test]
+    fn cancelled_generation_gives_up() {
+        let cancel = CancelToken::new();
+        let board = generate_cancellable(&mut StdRng::seed_from_u64(0), &cancel).unwrap();
+        assert!(generate(&mut StdRng::seed_from_u64(0))
+            .unwrap()
+            .diff(&board)
+            .is_empty());
+        cancel.cancel();
+        assert!(matches!(
+            generate_cancellable(&mut StdRng::seed_from_u64(0), &cancel),
+            Err(crate::Error::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn generates_minimal_unique_sudokus() {
+        let (board, solution) = generate_with_solution(&mut StdRng::seed_from_u64(0)).unwrap();
+        assert_eq!(count_solutions(&board, 2), 1);
+        assert!(solve(&board).unwrap().0.diff(&solution).is_empty());
+        assert!(generate(&mut StdRng::seed_from_u64(0))
+            .unwrap()
+            .diff(&board)
+            .is_empty());
+        let givens = (0..81)
+            .filter_map(Location::from_index)
+            .filter(|&location| board.get(location) != BoardCell::Empty)
+            .collect::<Vec<_>>();
+        assert!(givens.len() < 40);
+        for &location in givens.iter().take(5) {
+            let mut removed = board.clone();
+            removed.set(location, BoardCell::Empty);
+            assert!(count_solutions(&removed, 2) > 1);
+        }
+    }
+
+    #[test]
+    fn finds_distinct_minimal_subpuzzles() {
+        let line = include_str!("../../data/sudoku/grids/qqwing_simple.txt")
+            .lines()
+            .next()
+            .unwrap();
+        let board = Board::from_line(line, '.').unwrap();
+        let (solution, _, _) = solve(&board).unwrap();
+        let subpuzzles = minimal_subpuzzles(&board, 5).unwrap();
+        assert!(!subpuzzles.is_empty() && subpuzzles.len() <= 5);
+        let mut lines = vec![];
+        for (subpuzzle, rating) in &subpuzzles {
+            assert_eq!(count_solutions(subpuzzle, 2), 1);
+            assert!(solve(subpuzzle).unwrap().0.diff(&solution).is_empty());
+            assert!(subpuzzle.check_against(&board).is_empty());
+            assert_eq!(*rating, Sudoku::rating(subpuzzle).unwrap());
+            let minimal = (0..81).filter_map(Location::from_index).all(|location| {
+                let mut removed = subpuzzle.clone();
+                removed.set(location, BoardCell::Empty);
+                subpuzzle.get(location) == BoardCell::Empty || count_solutions(&removed, 2) > 1
+            });
+            assert!(minimal);
+            lines.push(Sudoku::format(subpuzzle));
+        }
+        lines.sort();
+        lines.dedup();
+        assert_eq!(lines.len(), subpuzzles.len());
+
+        assert_eq!(minimal_subpuzzles(&solution, 1).unwrap().len(), 1);
+        assert!(minimal_subpuzzles(&Board::from_line(&".".repeat(81), '.').unwrap(), 1).is_err());
+    }
+}
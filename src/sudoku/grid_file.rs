@@ -0,0 +1,125 @@
+//! Files of sudokus with one grid per line, such as the grid sets in the data directory. Lines are read in place,
+//! so that even files of hundreds of megabytes can be mapped into memory with the `mmap` feature instead of copied.
+
+use std::{fs, path::Path};
+
+use crate::error::{Error, Result};
+
+use super::Board;
+
+/// The contents of a grid file, either read into memory or mapped.
+pub struct GridFile {
+    bytes: Bytes,
+}
+
+enum Bytes {
+    Read(Vec<u8>),
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
+}
+
+impl GridFile {
+    /// Reads the whole file into memory.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).map_err(|source| read_error(path, source))?;
+        Ok(Self {
+            bytes: Bytes::Read(bytes),
+        })
+    }
+
+    /// Maps the file into memory, so that only the pages of the lines being read need to be loaded. The file must
+    /// not change while it is mapped.
+    #[cfg(feature = "mmap")]
+    pub fn map(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = fs::File::open(path).map_err(|source| read_error(path, source))?;
+        // SAFETY: The map is only read, and the caller promises that the file does not change while it is mapped.
+        let map =
+            unsafe { memmap2::Mmap::map(&file) }.map_err(|source| read_error(path, source))?;
+        Ok(Self {
+            bytes: Bytes::Mapped(map),
+        })
+    }
+
+    /// Mapping files needs the `mmap` feature.
+    #[cfg(not(feature = "mmap"))]
+    pub fn map(_path: impl AsRef<Path>) -> Result<Self> {
+        Err(Error::Unsupported {
+            puzzle: "sudoku",
+            action: "Memory mapping grid files",
+        })
+    }
+
+    fn bytes(&self) -> &[u8] {
+        match &self.bytes {
+            Bytes::Read(bytes) => bytes,
+            #[cfg(feature = "mmap")]
+            Bytes::Mapped(map) => map,
+        }
+    }
+
+    /// The lines of the file that are not blank, trimmed, without copying them. A line that is not UTF-8 fails on
+    /// its own.
+    pub fn lines(&self) -> impl Iterator<Item = Result<&str>> + '_ {
+        self.bytes()
+            .split(|&byte| byte == b'\n')
+            .map(<[u8]>::trim_ascii)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                std::str::from_utf8(line)
+                    .map_err(|err| Error::Parse(format!("Grid line is not UTF-8: {err}")))
+            })
+    }
+
+    /// The number of grids in the file, found without parsing them.
+    pub fn len(&self) -> usize {
+        self.lines().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines().next().is_none()
+    }
+
+    /// The grid on each line, with `empty_char` for empty cells, parsed as they are iterated.
+    pub fn boards(&self, empty_char: char) -> impl Iterator<Item = Result<Board>> + '_ {
+        self.lines()
+            .map(move |line| Ok(Board::from_line(line?, empty_char)?))
+    }
+}
+
+fn read_error(path: &Path, source: std::io::Error) -> Error {
+    Error::Read {
+        puzzle: "sudoku",
+        path: path.to_owned(),
+        source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_lines_in_place() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/data/sudoku/grids/easy50.txt");
+        let grid_file = GridFile::read(path).unwrap();
+        let expected = fs::read_to_string(path).unwrap();
+        assert_eq!(grid_file.len(), expected.lines().count());
+        let boards = grid_file.boards('.').collect::<Result<Vec<_>>>().unwrap();
+        let first = Board::from_line(expected.lines().next().unwrap(), '.').unwrap();
+        assert_eq!(boards[0].cells(), first.cells());
+        if cfg!(feature = "mmap") {
+            let mapped = GridFile::map(path).unwrap();
+            assert!(mapped
+                .lines()
+                .map(Result::unwrap)
+                .eq(grid_file.lines().map(Result::unwrap)));
+        } else {
+            assert!(matches!(
+                GridFile::map(path),
+                Err(Error::Unsupported { .. })
+            ));
+        }
+    }
+}
@@ -1,123 +1,105 @@
-use std::{
-    iter::Enumerate,
-    marker::PhantomData,
-    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not, Sub},
-};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not, Sub};
 
 use array_concat::concat_arrays;
-use bitvec::{array::BitArray, bitarr, order::Lsb0};
 
 use super::board::Location;
 
+/// A set of the 81 locations of a board, as the lowest 81 bits of a `u128` indexed by [`Location::index`].
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct LocationSet {
-    set: BitArray<[u8; 11]>,
+    set: u128,
 }
 
 impl LocationSet {
-    pub const LAST: Self = {
-        let mut data = [0; 11];
-        data[10] = 0b11111110;
-        Self {
-            set: BitArray {
-                _ord: PhantomData::<_>,
-                data,
-            },
-        }
+    /// The bits above the 81 locations, which are never set.
+    pub const LAST: Self = Self {
+        set: !((1 << 81) - 1),
     };
 
-    pub const NONE: Self = Self {
-        set: bitarr![const u8, Lsb0; 0; 81],
-    };
+    pub const NONE: Self = Self { set: 0 };
 
     pub fn from_location(loc: Location) -> Self {
-        let mut result = Self::NONE;
-        result.set.set(loc.index(), true);
-        result
+        Self {
+            set: 1 << loc.index(),
+        }
     }
 
     pub const fn row(row_index: u8) -> Self {
-        let mut result = Self::NONE;
-        let start_index = row_index * 9;
-        let end_index = start_index + 9;
-        let mut cur_index = start_index;
-        while cur_index < end_index {
-            let byte_index = cur_index / 8;
-            let bit_index = cur_index % 8;
-            result.set.data[byte_index as usize] |= 1 << bit_index;
-            cur_index += 1;
+        Self {
+            set: 0x1FF << (row_index * 9),
         }
-        result
     }
 
     pub const fn col(col_index: u8) -> Self {
         let mut result = Self::NONE;
         let mut cur_index = col_index;
         while cur_index < 81 {
-            let byte_index = cur_index / 8;
-            let bit_index = cur_index % 8;
-            result.set.data[byte_index as usize] |= 1 << bit_index;
+            result.set |= 1 << cur_index;
             cur_index += 9;
         }
         result
     }
 
     pub const fn block(grid_index: u8) -> Self {
-        let mut result = Self::NONE;
-        let start_row = grid_index / 3 * 3;
-        let start_col = grid_index % 3 * 3;
-        let end_row = start_row + 3;
-        let end_col = start_col + 3;
-        let mut cur_row = start_row;
-        while cur_row < end_row {
-            let mut cur_col = start_col;
-            while cur_col < end_col {
-                let index = cur_row * 9 + cur_col;
-                let byte_index = index / 8;
-                let bit_index = index % 8;
-                result.set.data[byte_index as usize] |= 1 << bit_index;
-                cur_col += 1;
-            }
-            cur_row += 1;
+        // The three cells at the start of each of the three rows of the block.
+        const CORNER: u128 = 0b111 | 0b111 << 9 | 0b111 << 18;
+        Self {
+            set: CORNER << (grid_index / 3 * 27 + grid_index % 3 * 3),
         }
-        result
     }
 
     pub fn count(self) -> usize {
-        self.set.count_ones()
+        self.set.count_ones() as usize
     }
 
     pub fn is_superset(self, other: Self) -> bool {
-        (self.set & other.set) == other.set
+        self.set & other.set == other.set
     }
 
-    pub fn iter(self) -> LocationSetIter {
-        LocationSetIter {
-            iter: self.set.into_iter().enumerate(),
+    pub fn is_empty(self) -> bool {
+        self.set == 0
+    }
+
+    /// The location in the set, if there is exactly one.
+    pub fn single(self) -> Option<Location> {
+        if self.set.is_power_of_two() {
+            Location::from_index(self.set.trailing_zeros() as usize)
+        } else {
+            None
         }
     }
+
+    pub fn iter(self) -> LocationSetIter {
+        LocationSetIter { set: self.set }
+    }
 }
 
+/// The locations of a [`LocationSet`] by index, found by clearing the lowest set bit.
 #[derive(Clone, Debug)]
 pub struct LocationSetIter {
-    iter: Enumerate<bitvec::array::IntoIter<[u8; 11], Lsb0>>,
+    set: u128,
 }
 
 impl Iterator for LocationSetIter {
     type Item = Location;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut result = None;
-        while result.is_none() {
-            let (index, value) = self.iter.next()?;
-            if value {
-                result = Some(Location::from_index(index).unwrap());
-            }
+        if self.set == 0 {
+            return None;
         }
-        result
+        let index = self.set.trailing_zeros() as usize;
+        self.set &= self.set - 1;
+        Some(Location::from_index(index).unwrap())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.set.count_ones() as usize;
+        (len, Some(len))
     }
 }
 
+impl ExactSizeIterator for LocationSetIter {}
+
 impl IntoIterator for LocationSet {
     type Item = Location;
     type IntoIter = LocationSetIter;
@@ -131,7 +113,7 @@ impl FromIterator<Location> for LocationSet {
     fn from_iter<I: IntoIterator<Item = Location>>(iter: I) -> Self {
         let mut result = Self::NONE;
         for loc in iter {
-            result.set.set(loc.index(), true);
+            result |= Self::from_location(loc);
         }
         result
     }
@@ -151,9 +133,7 @@ impl Sub<Location> for LocationSet {
     type Output = Self;
 
     fn sub(self, rhs: Location) -> Self::Output {
-        Self {
-            set: self.set & !Self::from_location(rhs).set,
-        }
+        self - Self::from_location(rhs)
     }
 }
 
@@ -253,7 +233,7 @@ mod tests {
                 9,
                 "Row set iter {i} does not have 9 locations."
             );
-            for (j, (set_loc, array_loc)) in loc_set.iter().zip(loc_array.into_iter()).enumerate() {
+            for (j, (set_loc, array_loc)) in loc_set.iter().zip(loc_array).enumerate() {
                 assert_eq!(set_loc, array_loc, "Set location {set_loc} does not match array location {array_loc} for row {i} and index {j}.");
             }
         }
@@ -270,7 +250,7 @@ mod tests {
                 9,
                 "Col set iter {i} does not have 9 locations."
             );
-            for (j, (set_loc, array_loc)) in loc_set.iter().zip(loc_array.into_iter()).enumerate() {
+            for (j, (set_loc, array_loc)) in loc_set.iter().zip(loc_array).enumerate() {
                 assert_eq!(set_loc, array_loc, "Set location {set_loc} does not match array location {array_loc} for column {i} and index {j}.");
             }
         }
@@ -292,7 +272,7 @@ mod tests {
                 "Block set iter {i} does not have 9 locations."
             );
 
-            for (j, (set_loc, array_loc)) in loc_set.iter().zip(loc_array.into_iter()).enumerate() {
+            for (j, (set_loc, array_loc)) in loc_set.iter().zip(loc_array).enumerate() {
                 assert_eq!(set_loc, array_loc, "Set location {set_loc} does not match array location {array_loc} for block {i} and index {j}.");
             }
         }
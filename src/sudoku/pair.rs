@@ -0,0 +1,121 @@
+//! Sudokus written together with their solutions, one per line as `puzzle,solution,difficulty,score`, so that a file
+//! of generated sudokus can be checked later without solving them again. The difficulty and score are left out for
+//! sudokus that were not rated.
+
+use crate::{
+    difficulty::Rating,
+    error::{ensure, Result},
+    generate::Generated,
+    puzzle::Puzzle,
+};
+
+use super::{Board, Sudoku};
+
+#[derive(Clone, Debug)]
+pub struct Pair {
+    pub puzzle: Board,
+    pub solution: Board,
+    pub rating: Option<Rating>,
+}
+
+impl Pair {
+    pub fn from_line(line: &str) -> Result<Self> {
+        let fields = line.trim().split(',').map(str::trim).collect::<Vec<_>>();
+        ensure!(
+            matches!(fields.len(), 2 | 4),
+            Parse,
+            "Expected 'puzzle,solution' or 'puzzle,solution,difficulty,score'. Got '{line}'."
+        );
+        let rating = match fields[2..] {
+            [difficulty, score] => Some(Rating {
+                difficulty: difficulty.parse()?,
+                score: score.parse().map_err(|_| {
+                    crate::Error::Parse(format!("Expected a score. Got '{score}'."))
+                })?,
+            }),
+            _ => None,
+        };
+        Ok(Self {
+            puzzle: Board::from_line(fields[0], '.')?,
+            solution: Board::from_line(fields[1], '.')?,
+            rating,
+        })
+    }
+
+    pub fn to_line(&self) -> String {
+        let mut line = format!(
+            "{},{}",
+            Sudoku::format(&self.puzzle),
+            Sudoku::format(&self.solution)
+        );
+        if let Some(rating) = self.rating {
+            line.push_str(&format!(",{},{}", rating.difficulty, rating.score));
+        }
+        line
+    }
+
+    /// Reads a file of pairs, skipping empty lines.
+    pub fn read_all(input: &str) -> Result<Vec<Self>> {
+        input
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(Self::from_line)
+            .collect()
+    }
+}
+
+impl From<Generated<Sudoku>> for Pair {
+    fn from(generated: Generated<Sudoku>) -> Self {
+        Self {
+            puzzle: generated.instance,
+            solution: generated.solution,
+            rating: generated.rating,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        difficulty::Difficulty,
+        generate::{generate_pairs, GeneratorConfig},
+        sudoku::solve,
+    };
+
+    #[test]
+    fn keeps_solutions_through_generation() {
+        let config = GeneratorConfig {
+            difficulty: Some(Difficulty::Easy),
+            ..GeneratorConfig::default()
+        };
+        let pairs = generate_pairs::<Sudoku>(&config, 2).unwrap();
+        assert_eq!(pairs.len(), 2);
+        for generated in pairs {
+            let pair = Pair::from(generated);
+            assert!(solve(&pair.puzzle)
+                .unwrap()
+                .0
+                .diff(&pair.solution)
+                .is_empty());
+            assert_eq!(
+                pair.rating.map(|rating| rating.difficulty),
+                Some(Difficulty::Easy)
+            );
+
+            let read = Pair::from_line(&pair.to_line()).unwrap();
+            assert_eq!(read.to_line(), pair.to_line());
+            assert_eq!(read.rating, pair.rating);
+        }
+
+        let line = include_str!("../../data/sudoku/grids/easy50.txt")
+            .lines()
+            .next()
+            .unwrap();
+        let (solution, _, _) = solve(&Board::from_line(line, '.').unwrap()).unwrap();
+        let unrated = Pair::read_all(&format!("{line},{}\n\n", Sudoku::format(&solution))).unwrap();
+        assert_eq!(unrated.len(), 1);
+        assert_eq!(unrated[0].rating, None);
+        assert!(Pair::from_line(&format!("{line},{line},easy")).is_err());
+    }
+}
@@ -1,10 +1,21 @@
-use anyhow::{bail, ensure, Context, Result};
-use itertools::Itertools;
+use std::collections::HashSet;
 
-use crate::sudoku::location_set::LocationSet;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cancel::CancelToken,
+    engine::{Constraint, Engine},
+    id::PuzzleId,
+    stats::{self, SolveStats},
+    sudoku::location_set::LocationSet,
+};
 
 use super::{
     board::{BoardCell, CellValue, Location},
+    budget::{Budget, Exceeded},
+    completion::{complete, FEW_GIVENS},
+    error::Error,
     location_set::GROUPS,
     value_set::ValueSet,
     Board,
@@ -39,16 +50,27 @@ impl Cell {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SolveState {
     cells: [Cell; 81],
+    /// The values placed in each group of [`GROUPS`], kept up to date as values are placed so that no pass over the
+    /// groups needs to look at their cells to find them.
+    used: [ValueSet; 27],
+    /// The cells that changed since the groups were last propagated, so that only the groups holding them need to be
+    /// restricted again.
+    dirty: LocationSet,
 }
 
 impl SolveState {
-    fn from_board(board: &Board) -> Self {
-        Self {
-            cells: board.cells().map(|cell| match cell {
-                BoardCell::Value(value) => Cell::Value(value),
-                BoardCell::Empty => Cell::Empty(ValueSet::ALL),
-            }),
+    pub fn from_board(board: &Board) -> Self {
+        let mut state = Self {
+            cells: [Cell::Empty(ValueSet::ALL); 81],
+            used: [ValueSet::NONE; 27],
+            dirty: !LocationSet::NONE,
+        };
+        for (index, &cell) in board.cells().iter().enumerate() {
+            if let BoardCell::Value(value) = cell {
+                state.place(Location::from_index(index).unwrap(), value);
+            }
         }
+        state
     }
 
     pub fn cells(&self) -> &[Cell; 81] {
@@ -59,25 +81,45 @@ impl SolveState {
         self.cells[location.index()]
     }
 
-    fn get_mut(&mut self, location: Location) -> &mut Cell {
-        &mut self.cells[location.index()]
+    /// Places the value, noting it as used in the groups of the location.
+    fn place(&mut self, location: Location, value: CellValue) {
+        self.cells[location.index()] = Cell::Value(value);
+        self.dirty |= LocationSet::from_location(location);
+        for group_id in location.groups() {
+            self.used[group_id] |= ValueSet::from_value(value);
+        }
+    }
+
+    /// The values placed in the group with the index in [`GROUPS`].
+    pub fn used_values(&self, group_id: usize) -> ValueSet {
+        self.used[group_id]
     }
 
-    fn free_values(&self, locations: LocationSet) -> ValueSet {
-        !locations
-            .into_iter()
-            .filter_map(|location| Cell::value(self.get(location)))
-            .collect::<ValueSet>()
+    /// The values not yet placed in the group with the index in [`GROUPS`].
+    pub fn free_values(&self, group_id: usize) -> ValueSet {
+        !self.used[group_id]
     }
 
-    fn validate(&self) -> Result<()> {
+    /// The cells of `group` that each value is possible in, by value, found in one pass over the group.
+    pub fn candidates(&self, group: LocationSet) -> [LocationSet; 9] {
+        let mut candidates = [LocationSet::NONE; 9];
+        for loc in group {
+            let cell = LocationSet::from_location(loc);
+            for value in self.get(loc).possible_values().iter() {
+                candidates[usize::from(value) - 1] |= cell;
+            }
+        }
+        candidates
+    }
+
+    fn validate(&self) -> Result<(), Error> {
         for (group_id, &group) in GROUPS.iter().enumerate() {
             let mut values = ValueSet::NONE;
             for loc in group {
                 let cell = self.get(loc);
                 if let Some(value) = cell.value() {
                     if values.contains(value) {
-                        bail!("Duplicate value {value} in group {group_id}.");
+                        return Err(Error::DuplicateValue { group_id, value });
                     } else {
                         values |= ValueSet::from_value(value);
                     }
@@ -87,97 +129,97 @@ impl SolveState {
         Ok(())
     }
 
-    fn restrict(cell: &mut Cell, values: ValueSet) -> Result<bool> {
-        match *cell {
+    fn restrict(&mut self, location: Location, values: ValueSet) -> Result<bool, Error> {
+        match self.get(location) {
             Cell::Empty(mut value_set) => {
                 let start_value_set = value_set;
                 value_set &= values;
                 if value_set == ValueSet::NONE {
-                    bail!("No possible values left for cell.");
+                    Err(Error::NoValuesLeft)
                 } else if let Some(single) = value_set.single() {
-                    *cell = Cell::Value(single);
+                    self.place(location, single);
                     Ok(true)
                 } else {
-                    *cell = Cell::Empty(value_set);
-                    Ok(start_value_set != value_set)
+                    self.cells[location.index()] = Cell::Empty(value_set);
+                    let changed = start_value_set != value_set;
+                    if changed {
+                        self.dirty |= LocationSet::from_location(location);
+                    }
+                    Ok(changed)
                 }
             }
             Cell::Value(value) => {
                 if values.contains(value) {
                     Ok(false)
                 } else {
-                    bail!("Cell value {value} is not possible according to value set {values}.");
+                    Err(Error::ImpossibleValue { value, values })
                 }
             }
         }
     }
 
-    fn restrict_cells(&mut self) -> Result<bool> {
-        let mut changed = false;
-        let start_state = self.clone();
-        for group in GROUPS {
-            let free_values = self.free_values(group);
-            for loc in group {
-                let cell = self.get_mut(loc);
-                changed |= cell.is_empty()
-                    && Self::restrict(cell, free_values).with_context(|| {
-                        format!("Error while restricting cell {loc} to values {free_values}.")
-                    })?;
+    /// Removes the values placed in `group` from its other cells, and places every value that only fits in one of
+    /// its cells. Returns the cells that changed.
+    fn restrict_group(&mut self, group_id: usize) -> Result<LocationSet, Error> {
+        let group = GROUPS[group_id];
+        let free_values = self.free_values(group_id);
+        let mut changed = LocationSet::NONE;
+        for loc in group {
+            if self.get(loc).is_empty() && self.restrict(loc, free_values)? {
+                changed |= LocationSet::from_location(loc);
             }
-            let free_values = self.free_values(group);
-            for value in free_values.iter() {
-                if let Ok((loc, cell)) = group
-                    .into_iter()
-                    .map(|loc| (loc, self.get(loc)))
-                    .filter(|&(_, cell)| cell.possible_values().contains(value))
-                    .exactly_one()
-                {
-                    assert!(cell.value().is_none());
-                    *self.get_mut(loc) = Cell::Value(value);
-                    changed = true;
-                }
-            }
-        }
-        if changed {
-            assert_ne!(self, &start_state, "State should have changed.");
-        } else {
-            assert_eq!(self, &start_state, "State should not have changed.");
         }
-        Ok(changed)
-    }
-    fn ghosts(&mut self) -> Result<bool> {
-        let mut ghosts: Vec<(CellValue, LocationSet)> = vec![];
-
-        for group in GROUPS {
-            for value in ValueSet::ALL.iter() {
-                let locations = group
-                    .into_iter()
-                    .filter(|&loc| self.get(loc).possible_values().contains(value))
-                    .collect::<LocationSet>();
-                if locations.count() == 2 || locations.count() == 3 {
-                    for loc in locations {
-                        ensure!(self.get(loc).is_empty(), "Location {loc} is not empty.")
-                    }
-                    ghosts.push((value, locations));
-                }
+        let free_values = self.free_values(group_id);
+        let candidates = self.candidates(group);
+        // The cells placed below no longer hold the other values they were candidates for.
+        let mut placed = LocationSet::NONE;
+        for value in free_values.iter() {
+            if let Some(loc) = (candidates[usize::from(value) - 1] - placed).single() {
+                debug_assert!(self.get(loc).value().is_none());
+                self.place(loc, value);
+                placed |= LocationSet::from_location(loc);
             }
         }
+        Ok(changed | placed)
+    }
 
+    /// Restricts every row, column and block once, without propagating the changes like the solver does.
+    pub fn restrict_groups(&mut self) -> Result<(), Error> {
+        (0..GROUPS.len()).try_for_each(|group_id| self.restrict_group(group_id).map(|_| ()))
+    }
+
+    /// Box/line reduction, both ways. When the cells of a block that a value can go in all lie in one row or column,
+    /// the value is removed from the rest of that line, and when the cells of a row or column that a value can go in
+    /// all lie in one block, the value is removed from the rest of the block. A block and a line share up to three
+    /// cells, so these are the values with one to three places left. Returns whether any cell changed.
+    pub fn box_line_reductions(&mut self) -> Result<bool, Error> {
         let mut changed = false;
-        for group in GROUPS {
-            for &(ghost_value, locations) in ghosts.iter() {
-                if group.is_superset(locations) {
-                    for loc in group - locations {
-                        let cell = self.get_mut(loc);
-                        if cell.is_empty() {
-                            changed |= Self::restrict(cell, !ValueSet::from_value(ghost_value))
-                                .with_context(|| format!("Error while restricting cell {loc} with ghost of value {ghost_value}."))?;
+        for (block_id, &block) in GROUPS.iter().enumerate().skip(18) {
+            for (line_id, &line) in GROUPS.iter().enumerate().take(18) {
+                let shared = block & line;
+                if shared.is_empty() {
+                    continue;
+                }
+                // Restricting only removes candidates, so candidates found before it still hold every place left.
+                let (in_block, in_line) = (self.candidates(block), self.candidates(line));
+                for value in (self.free_values(block_id) & self.free_values(line_id)).iter() {
+                    let index = usize::from(value) - 1;
+                    for (places, rest) in [
+                        (in_block[index], line - block),
+                        (in_line[index], block - line),
+                    ] {
+                        if places.is_empty() || !shared.is_superset(places) {
+                            continue;
+                        }
+                        for loc in rest {
+                            if self.get(loc).is_empty() {
+                                changed |= self.restrict(loc, !ValueSet::from_value(value))?;
+                            }
                         }
                     }
                 }
             }
         }
-
         Ok(changed)
     }
 
@@ -197,84 +239,623 @@ impl SolveState {
             })
             .min_by_key(|(_, len)| *len)
             .map(|(index, _)| Location::from_index(index).unwrap())?;
-        let value = self.get(location).possible_values().iter().next().unwrap();
+        let value = self.get(location).possible_values().min().unwrap();
         Some((location, value))
     }
 }
 
-fn try_solve_guess(solve_state: &mut SolveState) -> Result<u32> {
+/// The rule that every row, column and block holds each value once.
+struct GroupConstraint {
+    /// The index of the group in [`GROUPS`].
+    group_id: usize,
+}
+
+impl Constraint<SolveState> for GroupConstraint {
+    fn variables(&self) -> Vec<usize> {
+        GROUPS[self.group_id]
+            .into_iter()
+            .map(Location::index)
+            .collect()
+    }
+
+    fn propagate(&self, state: &mut SolveState, changed: &mut Vec<usize>) -> crate::Result<()> {
+        changed.extend(
+            state
+                .restrict_group(self.group_id)?
+                .into_iter()
+                .map(Location::index),
+        );
+        Ok(())
+    }
+}
+
+/// Buffers kept between solves, so that solving many sudokus on a thread does not build an engine and a stack for
+/// each.
+#[derive(Default)]
+pub(crate) struct Scratch {
+    engine: Option<Engine<SolveState>>,
+    stack: Vec<(SolveState, Location, CellValue)>,
+}
+
+fn group_engine() -> Engine<SolveState> {
+    let mut engine = Engine::new(81);
+    for group_id in 0..GROUPS.len() {
+        engine.add(GroupConstraint { group_id });
+    }
+    engine
+}
+
+/// Restricts the groups holding the cells that changed since the last propagation, and in turn the groups of every cell
+/// that changes, until nothing changes. Returns the rounds of restrictions it took, each of which restricts the groups
+/// the round before it changed a cell of.
+fn propagate(engine: &Engine<SolveState>, solve_state: &mut SolveState) -> crate::Result<u32> {
+    let dirty = solve_state.dirty;
+    let rounds = engine.count_rounds(solve_state, dirty.into_iter().map(Location::index))?;
+    solve_state.dirty = LocationSet::NONE;
+    Ok(rounds)
+}
+
+/// Deduces as much as the groups, and box/line reductions if `box_line` is set, allow. Returns the steps it took, which
+/// are the rounds of group restrictions, so that a step is still one pass over the groups that may change, as it was
+/// before the groups were propagated by the engine.
+fn try_solve_guess(
+    engine: &Engine<SolveState>,
+    solve_state: &mut SolveState,
+    box_line: bool,
+) -> crate::Result<u32> {
     let mut steps = 0;
-    while solve_state.restrict_cells().with_context(|| {
-        format!(
-            "Error during restrict cells step. Partial solution:\n{}",
-            Board::from_solve_state(solve_state)
-        )
-    })? || solve_state.ghosts().with_context(|| {
-        format!(
-            "Error during ghosts step. Partial solution:\n{}",
-            Board::from_solve_state(solve_state)
-        )
-    })? {
-        steps += 1;
+    loop {
+        // The engine runs the group constraints to a fixpoint, so reductions only need to be looked for afterwards.
+        steps += propagate(engine, solve_state)?;
+        if !box_line || !solve_state.box_line_reductions()? {
+            return Ok(steps);
+        }
     }
-    steps += 1;
-    Ok(steps)
 }
 
 fn handle_error(
     stack: &mut Vec<(SolveState, Location, CellValue)>,
-    error: anyhow::Error,
-) -> Result<SolveState> {
+    error: crate::Error,
+) -> crate::Result<SolveState> {
     if let Some((mut prev_state, guess_loc, guess_value)) = stack.pop() {
-        let guess_cell = prev_state.get_mut(guess_loc);
-        SolveState::restrict(guess_cell, !ValueSet::from_value(guess_value)).with_context(
-            || format!("Error updating on faulty guess at {guess_loc} with value {guess_value}."),
-        )?;
+        prev_state.restrict(guess_loc, !ValueSet::from_value(guess_value))?;
         Ok(prev_state)
     } else {
-        bail!(error)
+        Err(error)
     }
 }
 
-pub fn solve(board: &Board) -> Result<(Board, u32, u32)> {
-    let mut stack: Vec<(SolveState, Location, CellValue)> = Vec::with_capacity(81);
+/// A value that follows from a board without guessing.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Hint {
+    pub location: Location,
+    pub value: CellValue,
+}
+
+/// Finds a value to place on the board without guessing. Values that follow from the groups alone are preferred over
+/// those that need box/line reductions. Returns `None` if nothing follows, and fails if the board contradicts itself.
+pub fn hint(board: &Board) -> crate::Result<Option<Hint>> {
+    let engine = group_engine();
+    let start_state = SolveState::from_board(board);
+    let mut state = start_state.clone();
+    propagate(&engine, &mut state)?;
+    let mut placed = new_values(&start_state, &state);
+    if placed.is_none() {
+        try_solve_guess(&engine, &mut state, true)?;
+        placed = new_values(&start_state, &state);
+    }
+    Ok(placed)
+}
+
+fn new_values(before: &SolveState, after: &SolveState) -> Option<Hint> {
+    before
+        .cells
+        .iter()
+        .zip(after.cells.iter())
+        .enumerate()
+        .find_map(|(index, (before, after))| match (before, after) {
+            (Cell::Empty(_), &Cell::Value(value)) => Some(Hint {
+                location: Location::from_index(index)?,
+                value,
+            }),
+            _ => None,
+        })
+}
+
+/// The hardest technique a solve needed, from the cheapest to the most costly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Technique {
+    /// Restricting rows, columns and blocks by the values placed in them.
+    Groups,
+    /// Box/line reduction, as [`SolveState::box_line_reductions`] does it. Budgets written before it was generalized
+    /// call it ghosts.
+    #[serde(rename = "box_line", alias = "ghosts")]
+    BoxLine,
+    Guesses,
+}
+
+impl Technique {
+    pub const ALL: [Technique; 3] = [Technique::Groups, Technique::BoxLine, Technique::Guesses];
+
+    /// Tells the technique a solve of the board needed from its statistics. The steps do not tell whether box/line
+    /// reductions changed anything, so a solve without guesses is checked by solving the board with the groups alone.
+    pub fn hardest(board: &Board, stats: &SolveStats) -> Self {
+        let groups = Budget {
+            max_guesses: 0,
+            techniques: vec![Technique::Groups],
+            ..Budget::default()
+        };
+        if stats.guesses > 0 {
+            Technique::Guesses
+        } else if solve_inner(
+            board,
+            &groups,
+            &CancelToken::new(),
+            &mut SolveStats::default(),
+            &mut Scratch::default(),
+        )
+        .is_ok()
+        {
+            Technique::Groups
+        } else {
+            Technique::BoxLine
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Technique::Groups => "groups",
+            Technique::BoxLine => "box_line",
+            Technique::Guesses => "guesses",
+        }
+    }
+}
 
+/// Counts the solutions of the board by trying every value of every guess, stopping once `limit` have been found.
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    // No sudoku with fewer than 17 givens has a unique solution, so completing a nearly empty board once shows that it
+    // has at least two, which is all a check for uniqueness asks, where enumerating them could take very long.
+    if (1..=2).contains(&limit) && board.givens() <= FEW_GIVENS && complete(board).is_some() {
+        return limit;
+    }
+    let engine = group_engine();
+    let mut stack = vec![SolveState::from_board(board)];
+    let mut count = 0;
+    while let Some(mut state) = stack.pop() {
+        if propagate(&engine, &mut state).is_err() {
+            continue;
+        }
+        if let Some((guess_loc, guess_value)) = state.guess() {
+            let mut other_state = state.clone();
+            if other_state
+                .restrict(guess_loc, !ValueSet::from_value(guess_value))
+                .is_ok()
+            {
+                stack.push(other_state);
+            }
+            state.place(guess_loc, guess_value);
+            stack.push(state);
+        } else if state.validate().is_ok() {
+            count += 1;
+            if count >= limit {
+                break;
+            }
+        }
+    }
+    count
+}
+
+/// Samples up to `n` distinct solutions of the board, such as filled grids to seed a generator with. Each solution is
+/// found by a search of its own that tries the values of every guess in a random order, so that the solutions spread
+/// over the completions of the board instead of differing in a few cells, though they are not sampled exactly
+/// uniformly. A search skips the solutions found before it, so fewer than `n` are returned only if the board has
+/// fewer solutions. Fails if the board breaks the rules.
+pub fn sample_solutions(board: &Board, n: usize, seed: u64) -> crate::Result<Vec<Board>> {
+    board.validate().map_err(Error::from)?;
+    let engine = group_engine();
+    let rng = &mut StdRng::seed_from_u64(seed);
+    let mut found = HashSet::new();
+    let mut solutions = vec![];
+    while solutions.len() < n {
+        let Some(solution) = sample_solution(&engine, board, rng, &found) else {
+            break;
+        };
+        found.insert(solution.id());
+        solutions.push(solution);
+    }
+    Ok(solutions)
+}
+
+/// Searches for a solution that is not in `found`, trying the values of each guess in a random order.
+fn sample_solution(
+    engine: &Engine<SolveState>,
+    board: &Board,
+    rng: &mut impl Rng,
+    found: &HashSet<PuzzleId>,
+) -> Option<Board> {
+    let mut stack = vec![SolveState::from_board(board)];
+    while let Some(mut state) = stack.pop() {
+        if propagate(engine, &mut state).is_err() {
+            continue;
+        }
+        if let Some((guess_loc, _)) = state.guess() {
+            let values = state
+                .get(guess_loc)
+                .possible_values()
+                .iter()
+                .collect::<Vec<_>>();
+            let &guess_value = values
+                .choose(rng)
+                .expect("Guesses are only made in cells with values left.");
+            let mut other_state = state.clone();
+            if other_state
+                .restrict(guess_loc, !ValueSet::from_value(guess_value))
+                .is_ok()
+            {
+                stack.push(other_state);
+            }
+            state.place(guess_loc, guess_value);
+            stack.push(state);
+        } else if state.validate().is_ok() {
+            let solution = Board::from_solve_state(&state);
+            if !found.contains(&solution.id()) {
+                return Some(solution);
+            }
+        }
+    }
+    None
+}
+
+pub fn solve(board: &Board) -> crate::Result<(Board, u32, u32)> {
+    solve_cancellable(board, &CancelToken::new())
+}
+
+/// Like [`solve`], but checks `cancel` between steps and fails with [`crate::Error::Cancelled`] once it is cancelled.
+pub fn solve_cancellable(board: &Board, cancel: &CancelToken) -> crate::Result<(Board, u32, u32)> {
+    solve_inner(
+        board,
+        &Budget::default(),
+        cancel,
+        &mut SolveStats::default(),
+        &mut Scratch::default(),
+    )
+}
+
+/// Like [`solve`], but keeps its buffers in `scratch` for the next solve.
+pub(crate) fn solve_reusing(
+    board: &Board,
+    scratch: &mut Scratch,
+) -> crate::Result<(Board, u32, u32)> {
+    solve_inner(
+        board,
+        &Budget::default(),
+        &CancelToken::new(),
+        &mut SolveStats::default(),
+        scratch,
+    )
+}
+
+/// The guesses the solver needs for the board, which tell how hard it is. Unlike [`solve`], this searches nearly empty
+/// boards like any other, since the guesses of completing them in order are not comparable to those of the solver.
+pub fn rate(board: &Board) -> crate::Result<u32> {
+    let (_, _, num_guesses) = search(
+        board,
+        &Budget::default(),
+        &CancelToken::new(),
+        &mut SolveStats::default(),
+        &mut Scratch::default(),
+    )?;
+    Ok(num_guesses)
+}
+
+/// Like [`solve_cancellable`], but returns what the solve cost instead of only its steps and guesses.
+pub fn solve_with_stats(board: &Board, cancel: &CancelToken) -> crate::Result<(Board, SolveStats)> {
+    solve_with_budget(board, &Budget::default(), cancel)
+}
+
+/// Like [`solve_with_stats`], but within the budget instead of the default one. Fails with [`Error::OverBudget`],
+/// telling which budget tripped, if the solve would go over it.
+pub fn solve_with_budget(
+    board: &Board,
+    budget: &Budget,
+    cancel: &CancelToken,
+) -> crate::Result<(Board, SolveStats)> {
+    let mut stats = SolveStats::default();
+    let (result, allocations) =
+        stats::measure(|| solve_inner(board, budget, cancel, &mut stats, &mut Scratch::default()));
+    let (solution, num_steps, num_guesses) = result?;
+    Ok((
+        solution,
+        SolveStats {
+            steps: num_steps.into(),
+            guesses: num_guesses.into(),
+            allocations,
+            ..stats
+        },
+    ))
+}
+
+/// Counts clones and depth in `stats`, and returns the steps and guesses.
+fn solve_inner(
+    board: &Board,
+    budget: &Budget,
+    cancel: &CancelToken,
+    stats: &mut SolveStats,
+    scratch: &mut Scratch,
+) -> crate::Result<(Board, u32, u32)> {
+    // Propagating and picking the best cell for every guess is wasted on nearly empty boards, which are completed in
+    // order instead, unless that takes more guesses than the budget allows or gives up.
+    if board.givens() <= FEW_GIVENS && budget.allows(Technique::Guesses) && budget.max_steps > 0 {
+        cancel.check()?;
+        if let Some((solution, num_guesses)) =
+            complete(board).filter(|&(_, guesses)| guesses <= budget.max_guesses)
+        {
+            return Ok((solution, 1, num_guesses));
+        }
+    }
+    search(board, budget, cancel, stats, scratch)
+}
+
+/// Like [`solve_inner`], but without completing nearly empty boards in order.
+fn search(
+    board: &Board,
+    budget: &Budget,
+    cancel: &CancelToken,
+    stats: &mut SolveStats,
+    scratch: &mut Scratch,
+) -> crate::Result<(Board, u32, u32)> {
+    let Scratch { engine, stack } = scratch;
+    stack.clear();
+    stack.reserve(81);
+
+    let engine = engine.get_or_insert_with(group_engine);
     let mut cur_state = SolveState::from_board(board);
     let mut num_steps = 0;
     let mut num_guesses = 0;
+    let box_line = budget.allows(Technique::BoxLine);
 
-    while num_steps < 1000 {
-        match try_solve_guess(&mut cur_state) {
+    while num_steps < budget.max_steps {
+        cancel.check()?;
+        match try_solve_guess(engine, &mut cur_state, box_line) {
             Ok(new_steps) => num_steps += new_steps,
             Err(error) => {
-                cur_state = handle_error(&mut stack, error)?;
+                cur_state = handle_error(stack, error)?;
             }
         }
 
         if let Some((guess_loc, guess_value)) = cur_state.guess() {
+            if !budget.allows(Technique::Guesses) {
+                return Err(Error::from(Exceeded::Technique(Technique::Guesses)).into());
+            }
+            if num_guesses >= budget.max_guesses {
+                return Err(Error::from(Exceeded::Guesses(budget.max_guesses)).into());
+            }
             num_guesses += 1;
             let mut guess_state = cur_state.clone();
-            let guess_cell = guess_state.get_mut(guess_loc);
-            *guess_cell = Cell::Value(guess_value);
+            stats.clones += 1;
+            guess_state.place(guess_loc, guess_value);
             stack.push((cur_state, guess_loc, guess_value));
+            stats.reach_depth(stack.len());
             cur_state = guess_state;
         } else {
             match cur_state.validate() {
                 Ok(()) => return Ok((Board::from_solve_state(&cur_state), num_steps, num_guesses)),
                 Err(error) => {
-                    cur_state = handle_error(&mut stack, error)?;
+                    cur_state = handle_error(stack, error.into())?;
                 }
             }
         }
     }
-    Ok((
-        Board::from_solve_state(
-            stack
-                .first()
-                .map(|(state, _, _)| state)
-                .unwrap_or(&cur_state),
-        ),
-        num_steps,
-        num_guesses,
-    ))
+    Err(Error::from(Exceeded::Steps(budget.max_steps)).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hints_agree_with_solution() {
+        let line = include_str!("../../data/sudoku/grids/easy50.txt")
+            .lines()
+            .next()
+            .unwrap();
+        let board = Board::from_line(line, '.').unwrap();
+        let (solution, _, _) = solve(&board).unwrap();
+        let Hint { location, value } = hint(&board).unwrap().unwrap();
+        assert_eq!(board.get(location), BoardCell::Empty);
+        assert_eq!(solution.get(location), BoardCell::Value(value));
+        assert_eq!(hint(&solution).unwrap(), None);
+
+        let duplicate = Board::from_line(&format!("11{}", &line[2..]), '.').unwrap();
+        let errors = duplicate.validate_all();
+        assert!(!errors.is_empty());
+        assert!(errors
+            .iter()
+            .all(|error| error.group().contains(&Location::new(0, 0).unwrap())));
+        assert!(hint(&duplicate).is_err());
+    }
+
+    #[test]
+    fn checks_progress_against_solution() {
+        let line = include_str!("../../data/sudoku/grids/easy50.txt")
+            .lines()
+            .next()
+            .unwrap();
+        let mut board = Board::from_line(line, '.').unwrap();
+        let (solution, _, _) = solve(&board).unwrap();
+        assert!(board.check_against(&solution).is_empty());
+        let mut empty = (0..81)
+            .filter_map(Location::from_index)
+            .filter(|&loc| board.get(loc) == BoardCell::Empty);
+        let (right, wrong) = (empty.next().unwrap(), empty.next().unwrap());
+        board.set(right, solution.get(right));
+        let BoardCell::Value(value) = solution.get(wrong) else {
+            panic!("solution has an empty cell")
+        };
+        let other = ValueSet::ALL.iter().find(|&other| other != value).unwrap();
+        board.set(wrong, BoardCell::Value(other));
+        assert_eq!(board.check_against(&solution), [wrong]);
+    }
+
+    #[test]
+    fn group_masks_follow_placements() {
+        let line = include_str!("../../data/sudoku/grids/qqwing_expert.txt")
+            .lines()
+            .next()
+            .unwrap();
+        let mut state = SolveState::from_board(&Board::from_line(line, '.').unwrap());
+        group_engine().propagate(&mut state).unwrap();
+        for (group_id, group) in GROUPS.into_iter().enumerate() {
+            let placed = group
+                .into_iter()
+                .filter_map(|loc| state.get(loc).value())
+                .collect::<ValueSet>();
+            assert_eq!(state.used_values(group_id), placed);
+            assert_eq!(state.free_values(group_id), !placed);
+        }
+    }
+
+    #[test]
+    fn dirty_groups_reach_full_fixpoint() {
+        let engine = group_engine();
+        for line in include_str!("../../data/sudoku/grids/qqwing_expert.txt")
+            .lines()
+            .take(10)
+        {
+            let board = Board::from_line(line, '.').unwrap();
+            let (solution, _, _) = solve(&board).unwrap();
+            let mut dirty = SolveState::from_board(&board);
+            let mut full = dirty.clone();
+            propagate(&engine, &mut dirty).unwrap();
+            engine.propagate(&mut full).unwrap();
+            assert_eq!(dirty.cells(), full.cells());
+            if let Some((guess_loc, _)) = dirty.guess() {
+                let BoardCell::Value(value) = solution.get(guess_loc) else {
+                    panic!("solution has an empty cell")
+                };
+                dirty.place(guess_loc, value);
+                full.place(guess_loc, value);
+                propagate(&engine, &mut dirty).unwrap();
+                engine.propagate(&mut full).unwrap();
+                assert_eq!(dirty.cells(), full.cells());
+            }
+        }
+    }
+
+    #[test]
+    fn reduces_boxes_and_lines_both_ways() {
+        let value = |digit| CellValue::new(std::num::NonZeroU8::new(digit).unwrap()).unwrap();
+        let (one, two) = (value(1), value(2));
+        let cell = |row, col| Location::new(row, col).unwrap();
+        let mut state = SolveState::from_board(&Board::from_line(&".".repeat(81), '.').unwrap());
+        // 1 only fits the first row of the first block, and 2 only fits the first block of the fifth row.
+        for (row, col) in (1..3).flat_map(|row| (0..3).map(move |col| (row, col))) {
+            state
+                .restrict(cell(row, col), !ValueSet::from_value(one))
+                .unwrap();
+        }
+        for col in 3..9 {
+            state
+                .restrict(cell(4, col), !ValueSet::from_value(two))
+                .unwrap();
+        }
+        assert!(state.box_line_reductions().unwrap());
+        let possible =
+            |row, col, value| state.get(cell(row, col)).possible_values().contains(value);
+        assert!((3..9).all(|col| !possible(0, col, one)));
+        assert!((0..3).all(|col| possible(0, col, one)));
+        assert!([3, 5]
+            .iter()
+            .all(|&row| (0..3).all(|col| !possible(row, col, two))));
+        assert!((0..3).all(|col| possible(4, col, two)));
+        assert!(possible(8, 8, one) && possible(8, 8, two));
+        assert!(!state.box_line_reductions().unwrap());
+    }
+
+    #[test]
+    fn counts_passes_over_groups_as_steps() {
+        // A step is a pass over the groups that may still change, as it was before the groups were propagated by the
+        // engine, so these take the steps they always did.
+        let sets = [
+            (include_str!("../../data/sudoku/grids/qqwing_simple.txt"), 7),
+            (include_str!("../../data/sudoku/grids/easy50.txt"), 5),
+            (include_str!("../../data/sudoku/grids/hardest.txt"), 12),
+        ];
+        for (set, steps) in sets {
+            let board = Board::from_line(set.lines().next().unwrap(), '.').unwrap();
+            let (_, stats) = solve_with_stats(&board, &CancelToken::new()).unwrap();
+            assert_eq!((stats.steps, stats.guesses), (steps, 0));
+        }
+    }
+
+    #[test]
+    fn rates_nearly_empty_boards_by_searching() {
+        let blank = Board::from_line(&".".repeat(81), '.').unwrap();
+        let (_, _, completing) = solve(&blank).unwrap();
+        let (_, _, searching) = search(
+            &blank,
+            &Budget::default(),
+            &CancelToken::new(),
+            &mut SolveStats::default(),
+            &mut Scratch::default(),
+        )
+        .unwrap();
+        assert_eq!(rate(&blank).unwrap(), searching);
+        assert_ne!(completing, searching);
+
+        let line = include_str!("../../data/sudoku/grids/qqwing_expert.txt")
+            .lines()
+            .next()
+            .unwrap();
+        let board = Board::from_line(line, '.').unwrap();
+        assert_eq!(rate(&board).unwrap(), solve(&board).unwrap().2);
+    }
+
+    #[test]
+    fn samples_distinct_solutions() {
+        let blank = Board::from_line(&".".repeat(81), '.').unwrap();
+        let samples = sample_solutions(&blank, 5, 0).unwrap();
+        assert_eq!(samples.len(), 5);
+        assert!(samples
+            .iter()
+            .all(|sample| sample.finished() && sample.validate().is_ok()));
+        let ids = samples.iter().map(Board::id).collect::<HashSet<_>>();
+        assert_eq!(ids.len(), 5);
+        let again = sample_solutions(&blank, 5, 0)
+            .unwrap()
+            .iter()
+            .map(Board::id)
+            .collect::<HashSet<_>>();
+        assert_eq!(again, ids);
+        // The first cell is the first guess, so different random orders should not all agree on it.
+        let first_cells = (0..10)
+            .map(|seed| {
+                sample_solutions(&blank, 1, seed).unwrap()[0].get(Location::from_index(0).unwrap())
+            })
+            .collect::<Vec<_>>();
+        assert!(first_cells.iter().any(|&cell| cell != first_cells[0]));
+
+        // With a given removed from a minimal sudoku, the sampler finds every solution left and no more.
+        let line = include_str!("../../data/sudoku/grids/qqwing_expert.txt")
+            .lines()
+            .next()
+            .unwrap();
+        let mut board = Board::from_line(line, '.').unwrap();
+        let given = (0..81)
+            .filter_map(Location::from_index)
+            .find(|&loc| board.get(loc) != BoardCell::Empty)
+            .unwrap();
+        board.set(given, BoardCell::Empty);
+        let count = count_solutions(&board, 100);
+        let samples = sample_solutions(&board, 100, 1).unwrap();
+        assert_eq!(samples.len(), count);
+        assert!(samples
+            .iter()
+            .all(|sample| sample.finished() && sample.validate().is_ok()));
+        assert!(samples.iter().all(|sample| board
+            .diff(sample)
+            .iter()
+            .all(|&loc| board.get(loc) == BoardCell::Empty)));
+    }
 }
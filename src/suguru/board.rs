@@ -0,0 +1,175 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+    region,
+    value_set::ValueSet,
+};
+
+/// A suguru (tectonic) puzzle: fill every region of N cells with the numbers 1 to N such that no two cells that
+/// touch, not even diagonally, hold the same number.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    regions: Grid<usize>,
+    region_cells: Vec<Vec<Location>>,
+    givens: Grid<Option<u8>>,
+}
+
+impl Board {
+    pub fn new(regions: Grid<usize>, givens: Grid<Option<u8>>) -> Result<Self> {
+        let dim = regions.dim();
+        ensure!(
+            dim.0 > 0 && dim.1 > 0,
+            Invalid,
+            "Suguru grids must have at least one cell."
+        );
+        ensure!(
+            givens.dim() == dim,
+            Invalid,
+            "The givens are {:?}, but the regions are {dim:?}.",
+            givens.dim()
+        );
+        let num_regions = regions.iter().max().map_or(0, |&max| max + 1);
+        let mut region_cells = vec![vec![]; num_regions];
+        for loc in Location::grid_iter(dim) {
+            region_cells[regions[loc]].push(loc);
+        }
+        for (region, cells) in region_cells.iter().enumerate() {
+            ensure!(!cells.is_empty(), Invalid, "Region {region} has no cells.");
+            ensure!(
+                cells.len() <= ValueSet::MAX_VALUE as usize,
+                Invalid,
+                "Regions can have at most {} cells, but region {region} has {}.",
+                ValueSet::MAX_VALUE,
+                cells.len()
+            );
+            for &loc in cells {
+                if let Some(given) = givens[loc] {
+                    ensure!(
+                        (1..=cells.len()).contains(&(given as usize)),
+                        Invalid,
+                        "The given {given} at {loc} does not fit in a region of {} cells.",
+                        cells.len()
+                    );
+                }
+            }
+        }
+        Ok(Self {
+            regions,
+            region_cells,
+            givens,
+        })
+    }
+
+    /// Parses a grid of characters where each distinct character is a region, optionally followed by a blank line
+    /// and a grid of givens with `.` for empty cells.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let mut lines = string.as_ref().lines().map(str::trim);
+        let rows = lines
+            .by_ref()
+            .skip_while(|line| line.is_empty())
+            .take_while(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let (regions, _) = region::parse_regions(&rows)?;
+        let mut givens = Grid::from_elem(regions.dim(), None);
+        let given_rows = lines.filter(|line| !line.is_empty()).collect::<Vec<_>>();
+        if !given_rows.is_empty() {
+            ensure!(
+                given_rows.len() == rows.len(),
+                Parse,
+                "Expected {} rows of givens. Got {}.",
+                rows.len(),
+                given_rows.len()
+            );
+            for (row_index, row) in given_rows.into_iter().enumerate() {
+                if row.chars().count() != regions.width() {
+                    bail!(
+                        Parse,
+                        "Expected {} characters in row {row_index} of the givens. Got '{row}'.",
+                        regions.width()
+                    );
+                }
+                for (col_index, c) in row.chars().enumerate() {
+                    givens[Location::new(row_index, col_index)] = match c {
+                        '.' => None,
+                        _ => Some(c.to_digit(10).ok_or_else(|| {
+                            Error::Parse(format!(
+                                "Invalid given '{c}' at ({row_index}, {col_index})."
+                            ))
+                        })? as u8),
+                    };
+                }
+            }
+        }
+        Self::new(regions, givens)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("suguru", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.regions.dim()
+    }
+
+    pub fn region(&self, location: Location) -> usize {
+        self.regions[location]
+    }
+
+    /// The cells of each region.
+    pub fn regions(&self) -> &[Vec<Location>] {
+        &self.region_cells
+    }
+
+    pub fn given(&self, location: Location) -> Option<u8> {
+        self.givens[location]
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const NAMES: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+        for row in self.regions.rows() {
+            for &region in row {
+                write!(f, "{}", NAMES[region % NAMES.len()] as char)?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f)?;
+        for row in self.givens.rows() {
+            for &given in row {
+                write!(f, "{}", given.map_or('.', |given| (b'0' + given) as char))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_regions_and_givens() {
+        let board = Board::parse(include_str!("../../data/suguru/puzzles/eight01.txt")).unwrap();
+        assert_eq!(board.dim(), (8, 8));
+        assert_eq!(board.regions().len(), 15);
+        assert_eq!(
+            board.region(Location::new(0, 0)),
+            board.region(Location::new(1, 1))
+        );
+        assert_eq!(board.given(Location::new(0, 0)), Some(2));
+        assert_eq!(board.given(Location::new(0, 1)), None);
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        let blank = Board::parse("AB\nAB\n").unwrap();
+        assert_eq!(blank.given(Location::new(0, 0)), None);
+        assert!(Board::parse("AB\nAB\n\n3.\n..\n").is_err());
+        assert!(Board::parse("AB\nAB\n\n1.\n").is_err());
+        assert!(Board::parse("AB\nAB\n\nx.\n..\n").is_err());
+    }
+}
@@ -0,0 +1,194 @@
+use std::fmt::Display;
+
+use crate::{
+    grid::Grid,
+    location::Location,
+    search::{self, Contradiction, SearchState},
+    value_set::ValueSet,
+};
+
+use super::Board;
+
+/// The numbers of a solved puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    numbers: Grid<u8>,
+}
+
+impl Solution {
+    pub fn number(&self, location: Location) -> u8 {
+        self.numbers[location]
+    }
+}
+
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.numbers.rows() {
+            let row = row.map(|&number| char::from_digit(number as u32, 36));
+            writeln!(f, "{}", row.map(|c| c.unwrap_or('?')).collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct State<'a> {
+    board: &'a Board,
+    cells: Grid<ValueSet>,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board) -> Self {
+        let cells = Grid::from_fn(board.dim(), |loc| {
+            let size = board.regions()[board.region(loc)].len() as u8;
+            board
+                .given(loc)
+                .map_or(ValueSet::all(size), ValueSet::from_value)
+        });
+        Self { board, cells }
+    }
+
+    fn candidates(&self, location: Location) -> ValueSet {
+        self.cells[location]
+    }
+
+    fn restrict(&mut self, location: Location, allowed: ValueSet) -> Result<bool, Contradiction> {
+        let cell = &mut self.cells[location];
+        let restricted = *cell & allowed;
+        if restricted.is_empty() {
+            return Err(Contradiction);
+        }
+        let changed = restricted != *cell;
+        *cell = restricted;
+        Ok(changed)
+    }
+
+    /// Removes every decided number from the other cells of its region and the cells touching it.
+    fn apply_singles(&mut self) -> Result<bool, Contradiction> {
+        let dim = self.board.dim();
+        let mut changed = false;
+        for loc in Location::grid_iter(dim) {
+            let Some(number) = self.candidates(loc).single() else {
+                continue;
+            };
+            let region = &self.board.regions()[self.board.region(loc)];
+            let neighbors = loc.neighbors(dim).into_iter().flatten();
+            for other in region.iter().copied().chain(neighbors) {
+                if other != loc {
+                    let allowed = self.candidates(other) - ValueSet::from_value(number);
+                    changed |= self.restrict(other, allowed)?;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Every number of a region must go somewhere in it. A number with one place left goes there,
+    /// and a cell touching every place left for a number cannot hold it.
+    fn apply_regions(&mut self) -> Result<bool, Contradiction> {
+        let dim = self.board.dim();
+        let mut changed = false;
+        for region in self.board.regions() {
+            for number in 1..=region.len() as u8 {
+                let places = region
+                    .iter()
+                    .copied()
+                    .filter(|&loc| self.candidates(loc).contains(number))
+                    .collect::<Vec<_>>();
+                match places[..] {
+                    [] => return Err(Contradiction),
+                    [place] => changed |= self.restrict(place, ValueSet::from_value(number))?,
+                    _ => {
+                        let first = places[0];
+                        for other in first.neighbors(dim).into_iter().flatten() {
+                            let touches_all = places
+                                .iter()
+                                .all(|&place| place.neighbors(dim).contains(&Some(other)));
+                            if !places.contains(&other) && touches_all {
+                                let allowed = self.candidates(other) - ValueSet::from_value(number);
+                                changed |= self.restrict(other, allowed)?;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(changed)
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.apply_singles()?;
+            changed |= self.apply_regions()?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        let Some(location) = Location::grid_iter(self.board.dim())
+            .filter(|&loc| self.candidates(loc).len() > 1)
+            .min_by_key(|&loc| self.candidates(loc).len())
+        else {
+            return vec![];
+        };
+        self.candidates(location)
+            .iter()
+            .map(|number| {
+                let mut state = self.clone();
+                state.cells[location] = ValueSet::from_value(number);
+                state
+            })
+            .collect()
+    }
+}
+
+pub fn solve(board: &Board) -> Option<Solution> {
+    search::solve(State::new(board)).map(|state| Solution {
+        numbers: state
+            .cells
+            .map(|cell| cell.single().expect("Solved cells have a single number.")),
+    })
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    search::count_solutions(State::new(board), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+
+    fn follows_rules(board: &Board, solution: &Solution) -> bool {
+        let dim = board.dim();
+        let givens = Location::grid_iter(dim).all(|loc| {
+            board
+                .given(loc)
+                .is_none_or(|given| given == solution.number(loc))
+        });
+        let regions = board.regions().iter().all(|cells| {
+            let numbers = cells.iter().map(|&loc| solution.number(loc)).sorted();
+            numbers.eq(1..=cells.len() as u8)
+        });
+        let apart = Location::grid_iter(dim).all(|loc| {
+            loc.neighbors(dim)
+                .into_iter()
+                .flatten()
+                .all(|neighbor| solution.number(neighbor) != solution.number(loc))
+        });
+        givens && regions && apart
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/suguru/puzzles/eight01.txt")).unwrap();
+        let solution = solve(&board).unwrap();
+        assert!(follows_rules(&board, &solution));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
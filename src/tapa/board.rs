@@ -0,0 +1,141 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::Location,
+};
+
+/// A tapa puzzle: a grid with clue cells listing the lengths of the runs of shaded cells among their eight
+/// neighbors. Clue cells are unshaded, the shaded cells must be connected, and no 2x2 block may be entirely shaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    clues: Grid<Option<Vec<u8>>>,
+}
+
+impl Board {
+    pub fn new(clues: Grid<Option<Vec<u8>>>) -> Result<Self> {
+        let (height, width) = clues.dim();
+        ensure!(
+            height > 0 && width > 0,
+            Invalid,
+            "Tapa grids must have at least one cell."
+        );
+        for (location, clue) in clues.indexed_iter() {
+            let Some(clue) = clue else {
+                continue;
+            };
+            let total = clue.iter().map(|&run| run as usize).sum::<usize>();
+            ensure!(
+                !clue.is_empty() && clue.len() <= 4,
+                Invalid,
+                "The clue at {location} must have between 1 and 4 numbers."
+            );
+            ensure!(
+                clue == &[0] || !clue.contains(&0),
+                Invalid,
+                "The clue at {location} can only contain 0 on its own."
+            );
+            // Separate runs need at least one unshaded cell between them.
+            let space = if clue.len() == 1 { 8 } else { 8 - clue.len() };
+            ensure!(
+                total <= space,
+                Invalid,
+                "The clue at {location} needs more than the 8 surrounding cells."
+            );
+        }
+        Ok(Self { clues })
+    }
+
+    /// Parses rows of whitespace separated cells, where `.` is an empty cell and a clue is its numbers
+    /// written together, such as `31` for a run of three and a run of one.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| line.split_whitespace().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        let mut clues = Grid::from_elem((height, width), None);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} cells in row {row_index}. Got {}.",
+                    row.len()
+                );
+            }
+            for (col_index, token) in row.into_iter().enumerate() {
+                if token == "." {
+                    continue;
+                }
+                let clue = token
+                    .chars()
+                    .map(|c| {
+                        c.to_digit(10).map(|digit| digit as u8).ok_or_else(|| {
+                            Error::Parse(format!(
+                                "Invalid character '{c}' at ({row_index}, {col_index})."
+                            ))
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                clues[Location::new(row_index, col_index)] = Some(clue);
+            }
+        }
+        Self::new(clues)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("tapa", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.clues.dim()
+    }
+
+    /// The run lengths of the clue at `location`, if it is a clue cell.
+    pub fn clue(&self, location: Location) -> Option<&[u8]> {
+        self.clues[location].as_deref()
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.clues.rows() {
+            let row = row
+                .map(|clue| match clue {
+                    Some(clue) => clue.iter().map(u8::to_string).collect::<String>(),
+                    None => ".".to_string(),
+                })
+                .collect::<Vec<_>>();
+            writeln!(f, "{}", row.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_multi_number_clues() {
+        let board = Board::parse(include_str!("../../data/tapa/puzzles/eight01.txt")).unwrap();
+        assert_eq!(board.dim(), (8, 8));
+        assert_eq!(board.clue(Location::new(0, 0)), Some(&[3][..]));
+        assert_eq!(board.clue(Location::new(2, 5)), Some(&[3, 1][..]));
+        assert_eq!(board.clue(Location::new(0, 1)), None);
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        assert!(Board::parse("0 .\n. .\n").is_ok());
+        assert!(Board::parse("10 .\n. .\n").is_err());
+        assert!(Board::parse("11111 .\n. .\n").is_err());
+        assert!(Board::parse("333 .\n. .\n").is_err());
+        assert!(Board::parse("3 .\n.\n").is_err());
+        assert!(Board::parse("3x .\n. .\n").is_err());
+    }
+}
@@ -0,0 +1,68 @@
+//! The ways the eight neighbors of a clue can be shaded, as bit masks where bit `i` is the `i`th neighbor
+//! in the clockwise order of [`Location::neighbors`](crate::location::Location::neighbors), starting above.
+
+/// The lengths of the runs of shaded cells around `ring`, sorted in descending order.
+/// A run may wrap around from the last neighbor to the first.
+fn runs(ring: u8) -> Vec<u8> {
+    if ring == u8::MAX {
+        return vec![8];
+    }
+    // Start right after an unshaded cell so that no run wraps around.
+    let start = (0..8).find(|&i| ring & (1 << i) == 0).unwrap_or(0) + 1;
+    let mut runs = vec![];
+    let mut run = 0;
+    for i in start..start + 8 {
+        if ring & (1 << (i % 8)) != 0 {
+            run += 1;
+        } else if run > 0 {
+            runs.push(run);
+            run = 0;
+        }
+    }
+    if run > 0 {
+        runs.push(run);
+    }
+    if runs.is_empty() {
+        runs.push(0);
+    }
+    runs.sort_unstable_by(|a, b| b.cmp(a));
+    runs
+}
+
+/// The runs of every one of the 256 rings, computed once and shared by all clues.
+pub(super) struct Patterns {
+    runs: Vec<Vec<u8>>,
+}
+
+impl Patterns {
+    pub fn new() -> Self {
+        Self {
+            runs: (0..=u8::MAX).map(runs).collect(),
+        }
+    }
+
+    /// Every ring whose runs are the numbers of `clue`, in any order.
+    pub fn matching(&self, clue: &[u8]) -> Vec<u8> {
+        let mut clue = clue.to_vec();
+        clue.sort_unstable_by(|a, b| b.cmp(a));
+        (0..=u8::MAX)
+            .filter(|&ring| self.runs[ring as usize] == clue)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_wrap_around() {
+        assert_eq!(runs(0b1000_0011), vec![3]);
+        assert_eq!(runs(0b0101_0101), vec![1, 1, 1, 1]);
+        assert_eq!(runs(0), vec![0]);
+        let patterns = Patterns::new();
+        assert_eq!(patterns.matching(&[8]), vec![u8::MAX]);
+        assert_eq!(patterns.matching(&[1, 1, 1, 1]).len(), 2);
+        assert_eq!(patterns.matching(&[7]).len(), 8);
+    }
+}
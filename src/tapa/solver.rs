@@ -0,0 +1,213 @@
+use crate::{
+    location::Location,
+    search::{self, Contradiction, SearchState},
+    shading::{Shade, ShadeGrid},
+};
+
+use super::{patterns::Patterns, Board};
+
+/// The rings of shading around a clue cell that are still possible.
+#[derive(Clone, Debug)]
+struct Clue {
+    /// The eight neighbors in clockwise order, `None` where they fall outside the grid.
+    ring: [Option<Location>; 8],
+    rings: Vec<u8>,
+}
+
+impl Clue {
+    /// Removes the rings that disagree with the grid, then shades or unshades the neighbors
+    /// on which all remaining rings agree.
+    fn apply(&mut self, grid: &mut ShadeGrid) -> Result<bool, Contradiction> {
+        let ring = self.ring;
+        let before = self.rings.len();
+        self.rings.retain(|&pattern| {
+            ring.iter().enumerate().all(|(i, cell)| {
+                let shaded = pattern & (1 << i) != 0;
+                match cell {
+                    None => !shaded,
+                    Some(loc) => match grid.get(*loc) {
+                        Shade::Unknown => true,
+                        Shade::Shaded => shaded,
+                        Shade::Unshaded => !shaded,
+                    },
+                }
+            })
+        });
+        if self.rings.is_empty() {
+            return Err(Contradiction);
+        }
+        let mut changed = self.rings.len() != before;
+        let all = self
+            .rings
+            .iter()
+            .fold(u8::MAX, |all, &pattern| all & pattern);
+        let any = self.rings.iter().fold(0, |any, &pattern| any | pattern);
+        for (i, cell) in ring.into_iter().enumerate() {
+            let Some(loc) = cell else {
+                continue;
+            };
+            if all & (1 << i) != 0 {
+                changed |= grid.set(loc, Shade::Shaded)?;
+            } else if any & (1 << i) == 0 {
+                changed |= grid.set(loc, Shade::Unshaded)?;
+            }
+        }
+        Ok(changed)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct State {
+    grid: ShadeGrid,
+    clues: Vec<Clue>,
+}
+
+impl State {
+    fn new(board: &Board) -> Self {
+        let patterns = Patterns::new();
+        let dim = board.dim();
+        let mut grid = ShadeGrid::new(dim);
+        let clues = Location::grid_iter(dim)
+            .filter_map(|location| {
+                let clue = board.clue(location)?;
+                grid.set(location, Shade::Unshaded)
+                    .expect("Only clue cells have been set.");
+                Some(Clue {
+                    ring: location.neighbors(dim),
+                    rings: patterns.matching(clue),
+                })
+            })
+            .collect();
+        Self { grid, clues }
+    }
+}
+
+impl SearchState for State {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = false;
+            for clue in self.clues.iter_mut() {
+                changed |= clue.apply(&mut self.grid)?;
+            }
+            changed |= self.grid.apply_no_pools(Shade::Shaded)?;
+            changed |= self.grid.keep_connected(Shade::Shaded)?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        // Branching on the most constrained clue decides several cells at once.
+        if let Some(clue) = (0..self.clues.len())
+            .filter(|&clue| self.clues[clue].rings.len() > 1)
+            .min_by_key(|&clue| self.clues[clue].rings.len())
+        {
+            return self.clues[clue]
+                .rings
+                .iter()
+                .map(|&ring| {
+                    let mut state = self.clone();
+                    state.clues[clue].rings = vec![ring];
+                    state
+                })
+                .collect();
+        }
+        let Some(location) = self.grid.first_unknown() else {
+            return vec![];
+        };
+        self.grid
+            .branch_on(location)
+            .into_iter()
+            .map(|grid| Self {
+                grid,
+                clues: self.clues.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Returns the solved grid with shaded cells as `#` and unshaded cells as `.`, or `None` if the puzzle has no solution.
+pub fn solve(board: &Board) -> Option<ShadeGrid> {
+    search::solve(State::new(board)).map(|state| state.grid)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    search::count_solutions(State::new(board), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::region;
+
+    /// The lengths of the runs of shaded cells around `location`, in ascending order.
+    fn runs(grid: &ShadeGrid, location: Location) -> Vec<u8> {
+        let (height, width) = grid.dim();
+        let ring = [
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+            (1, 0),
+            (1, -1),
+            (0, -1),
+        ]
+        .map(|(dr, dc)| {
+            let row = location.row as isize + dr;
+            let col = location.col as isize + dc;
+            (0..height as isize).contains(&row)
+                && (0..width as isize).contains(&col)
+                && grid.is_shaded(Location::new(row as usize, col as usize))
+        });
+        let Some(start) = ring.iter().position(|&shaded| !shaded) else {
+            return vec![8];
+        };
+        let mut runs = vec![];
+        let mut run = 0;
+        for index in start..start + 9 {
+            if ring[index % 8] {
+                run += 1;
+            } else if run > 0 {
+                runs.push(run);
+                run = 0;
+            }
+        }
+        if runs.is_empty() {
+            runs.push(0);
+        }
+        runs.sort_unstable();
+        runs
+    }
+
+    fn follows_rules(board: &Board, grid: &ShadeGrid) -> bool {
+        let (height, width) = board.dim();
+        let dim = (height, width);
+        let clues = Location::grid_iter(dim).all(|loc| {
+            board.clue(loc).is_none_or(|clue| {
+                let mut clue = clue.to_vec();
+                clue.sort_unstable();
+                !grid.is_shaded(loc) && runs(grid, loc) == clue
+            })
+        });
+        let shaded = |loc| grid.is_shaded(loc);
+        let no_pools = (1..height).all(|row| {
+            (1..width).all(|col| {
+                !(row - 1..=row)
+                    .flat_map(|row| [col - 1, col].map(|col| Location::new(row, col)))
+                    .all(shaded)
+            })
+        });
+        clues && no_pools && region::is_connected(dim, shaded, shaded)
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/tapa/puzzles/eight01.txt")).unwrap();
+        let grid = solve(&board).unwrap();
+        assert!(grid.is_complete());
+        assert!(follows_rules(&board, &grid));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
@@ -0,0 +1,26 @@
+mod board;
+pub use board::{Board, Piece};
+mod solver;
+pub use solver::{count_solutions, solve, Solution};
+
+/// Tracks puzzles as a [`Puzzle`](crate::puzzle::Puzzle), in the text format of [`Board::parse`].
+pub struct Tracks;
+
+impl crate::puzzle::Puzzle for Tracks {
+    const NAME: &'static str = "tracks puzzle";
+
+    type Instance = Board;
+    type Solution = Solution;
+
+    fn parse(input: &str) -> crate::Result<Board> {
+        Board::parse(input)
+    }
+
+    fn solve(board: &Board) -> crate::Result<Option<Solution>> {
+        Ok(solve(board))
+    }
+
+    fn count_solutions(board: &Board, limit: usize) -> crate::Result<usize> {
+        Ok(count_solutions(board, limit))
+    }
+}
@@ -0,0 +1,255 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::{Direction, Location},
+};
+
+/// A piece of track, joining two sides of its cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Piece {
+    Horizontal,
+    Vertical,
+    UpRight,
+    UpLeft,
+    DownLeft,
+    DownRight,
+}
+
+impl Piece {
+    pub const ALL: [Self; 6] = [
+        Self::Horizontal,
+        Self::Vertical,
+        Self::UpRight,
+        Self::UpLeft,
+        Self::DownLeft,
+        Self::DownRight,
+    ];
+
+    /// The sides of the cell the piece joins.
+    pub fn directions(self) -> [Direction; 2] {
+        match self {
+            Piece::Horizontal => [Direction::Left, Direction::Right],
+            Piece::Vertical => [Direction::Up, Direction::Down],
+            Piece::UpRight => [Direction::Up, Direction::Right],
+            Piece::UpLeft => [Direction::Up, Direction::Left],
+            Piece::DownLeft => [Direction::Down, Direction::Left],
+            Piece::DownRight => [Direction::Down, Direction::Right],
+        }
+    }
+
+    /// The piece joining the sides `first` and `second`, if they are different.
+    pub fn from_directions(first: Direction, second: Direction) -> Option<Self> {
+        Self::ALL.into_iter().find(|piece| {
+            let directions = piece.directions();
+            directions == [first, second] || directions == [second, first]
+        })
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Piece::Horizontal => '-',
+            Piece::Vertical => '|',
+            Piece::UpRight => 'L',
+            Piece::UpLeft => 'J',
+            Piece::DownLeft => '7',
+            Piece::DownRight => 'F',
+        }
+    }
+
+    pub fn from_char(c: char) -> Option<Self> {
+        Self::ALL.into_iter().find(|piece| piece.to_char() == c)
+    }
+}
+
+/// A tracks puzzle: lay a single track through the grid from where it enters to where it leaves, passing through
+/// every given piece, such that the numbers outside the grid say how many cells of each row and column the track
+/// uses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    pieces: Grid<Option<Piece>>,
+    row_counts: Vec<Option<usize>>,
+    col_counts: Vec<Option<usize>>,
+    ends: [(Location, Direction); 2],
+}
+
+impl Board {
+    /// The track enters and leaves the grid at the two given pieces that point out of it.
+    pub fn new(
+        pieces: Grid<Option<Piece>>,
+        row_counts: Vec<Option<usize>>,
+        col_counts: Vec<Option<usize>>,
+    ) -> Result<Self> {
+        let dim = pieces.dim();
+        let (height, width) = dim;
+        ensure!(
+            height > 0 && width > 0,
+            Invalid,
+            "Tracks grids must have at least one cell."
+        );
+        ensure!(
+            row_counts.len() == height && col_counts.len() == width,
+            Invalid,
+            "Expected {height} row counts and {width} column counts, but got {} and {}.",
+            row_counts.len(),
+            col_counts.len()
+        );
+        ensure!(
+            row_counts.iter().flatten().all(|&count| count <= width)
+                && col_counts.iter().flatten().all(|&count| count <= height),
+            Invalid,
+            "Counts cannot be larger than the number of cells in their line."
+        );
+        let mut ends = vec![];
+        for (location, &piece) in pieces.indexed_iter() {
+            for direction in piece.iter().flat_map(|piece| piece.directions()) {
+                if location.step(direction, dim).is_none() {
+                    ends.push((location, direction));
+                }
+            }
+        }
+        let [first, second] = ends[..] else {
+            bail!(
+                Invalid,
+                "Exactly two given pieces must point out of the grid, but {} do.",
+                ends.len()
+            );
+        };
+        Ok(Self {
+            pieces,
+            row_counts,
+            col_counts,
+            ends: [first, second],
+        })
+    }
+
+    /// Parses a puzzle given as a line of row counts and a line of column counts, separated by commas with `?`
+    /// for a line without a count, followed by a grid with `.` for empty cells and the given pieces drawn as `-`,
+    /// `|`, `L`, `J`, `7` and `F`.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let mut lines = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty());
+        let mut counts = |name: &str| -> Result<Vec<Option<usize>>> {
+            let line = lines
+                .next()
+                .ok_or_else(|| Error::Parse(format!("No line of {name}.")))?;
+            line.split(',')
+                .map(|count| match count.trim() {
+                    "?" => Ok(None),
+                    count => count.parse::<usize>().map(Some),
+                })
+                .collect::<Result<_, _>>()
+                .map_err(|_| {
+                    Error::Parse(format!(
+                        "Expected {name} separated by commas. Got '{line}'."
+                    ))
+                })
+        };
+        let row_counts = counts("row counts")?;
+        let col_counts = counts("column counts")?;
+        let rows = lines.collect::<Vec<_>>();
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        let mut pieces = Grid::from_elem((height, width), None);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.chars().count() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} characters in row {row_index}. Got '{row}'."
+                );
+            }
+            for (col_index, c) in row.chars().enumerate() {
+                pieces[Location::new(row_index, col_index)] = match c {
+                    '.' => None,
+                    _ => Some(Piece::from_char(c).ok_or_else(|| {
+                        Error::Parse(format!(
+                            "Invalid character '{c}' at ({row_index}, {col_index})."
+                        ))
+                    })?),
+                };
+            }
+        }
+        Self::new(pieces, row_counts, col_counts)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("tracks", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.pieces.dim()
+    }
+
+    pub fn piece(&self, location: Location) -> Option<Piece> {
+        self.pieces[location]
+    }
+
+    pub fn row_counts(&self) -> &[Option<usize>] {
+        &self.row_counts
+    }
+
+    pub fn col_counts(&self) -> &[Option<usize>] {
+        &self.col_counts
+    }
+
+    /// The cells where the track enters and leaves the grid, each with the side it crosses the edge on.
+    pub fn ends(&self) -> [(Location, Direction); 2] {
+        self.ends
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let counts = |counts: &[Option<usize>]| {
+            counts
+                .iter()
+                .map(|count| count.map_or("?".to_string(), |count| count.to_string()))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        writeln!(f, "{}", counts(&self.row_counts))?;
+        writeln!(f, "{}", counts(&self.col_counts))?;
+        for row in self.pieces.rows() {
+            let row = row
+                .map(|piece| piece.map_or('.', Piece::to_char))
+                .collect::<String>();
+            writeln!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_counts_and_pieces() {
+        let board = Board::parse(include_str!("../../data/tracks/puzzles/eight01.txt")).unwrap();
+        assert_eq!(board.dim(), (8, 8));
+        assert_eq!(board.row_counts()[0], Some(4));
+        assert_eq!(board.col_counts()[0], Some(6));
+        assert_eq!(board.piece(Location::new(3, 0)), Some(Piece::UpLeft));
+        assert_eq!(
+            board.ends(),
+            [
+                (Location::new(3, 0), Direction::Left),
+                (Location::new(7, 2), Direction::Down)
+            ]
+        );
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        assert_eq!(
+            Piece::from_directions(Direction::Left, Direction::Down),
+            Some(Piece::DownLeft)
+        );
+        assert!(Board::parse("1,1\n1,1\n-.\n..\n").is_err());
+        assert!(Board::parse("1,1\n1,1\n-.\n.x\n").is_err());
+        assert!(Board::parse("1,1\n1\n-.\n.|\n").is_err());
+    }
+}
@@ -0,0 +1,260 @@
+use std::fmt::Display;
+
+use crate::{
+    grid::Grid,
+    location::{Direction, Location},
+    loop_grid::LoopGrid,
+    search::{self, Contradiction, SearchState},
+    shading::{self, Shade, ShadeGrid},
+};
+
+use super::{Board, Piece};
+
+/// The piece in every cell of a solved puzzle, with `None` for cells the track does not use.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    pieces: Grid<Option<Piece>>,
+}
+
+impl Solution {
+    pub fn piece(&self, location: Location) -> Option<Piece> {
+        self.pieces[location]
+    }
+}
+
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.pieces.rows() {
+            let row = row
+                .map(|piece| piece.map_or('.', Piece::to_char))
+                .collect::<String>();
+            writeln!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The node of the track grid for the cell at `location`.
+fn node(location: Location) -> Location {
+    Location::new(location.row + 1, location.col + 1)
+}
+
+/// The cells with track are shaded. The track itself lives on a loop grid with an extra ring of nodes around the
+/// puzzle, where a fixed route around the ring from the exit back to the entry closes it into a loop.
+#[derive(Clone, Debug)]
+struct State<'a> {
+    board: &'a Board,
+    counts: &'a [(Vec<Location>, usize)],
+    shading: ShadeGrid,
+    track: LoopGrid,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board, counts: &'a [(Vec<Location>, usize)]) -> Result<Self, Contradiction> {
+        let dim = board.dim();
+        let (height, width) = dim;
+        let mut shading = ShadeGrid::new(dim);
+        let mut track = LoopGrid::new((height + 2, width + 2));
+        for loc in Location::grid_iter(dim) {
+            let Some(piece) = board.piece(loc) else {
+                continue;
+            };
+            shading.set(loc, Shade::Shaded)?;
+            for direction in Direction::ALL {
+                let line = piece.directions().contains(&direction);
+                track.set_line(node(loc), direction, line)?;
+            }
+        }
+        // The edges from the grid to the ring are only used at the ends.
+        for loc in Location::grid_iter(dim) {
+            for direction in Direction::ALL {
+                if loc.step(direction, dim).is_none() {
+                    let line = board.ends().contains(&(loc, direction));
+                    track.set_line(node(loc), direction, line)?;
+                }
+            }
+        }
+        let ring = (0..width + 2)
+            .map(|col| Location::new(0, col))
+            .chain((1..height + 2).map(|row| Location::new(row, width + 1)))
+            .chain(
+                (0..width + 1)
+                    .rev()
+                    .map(|col| Location::new(height + 1, col)),
+            )
+            .chain((1..height + 1).rev().map(|row| Location::new(row, 0)))
+            .collect::<Vec<_>>();
+        let [exit, entry] = board.ends().map(|(loc, direction)| {
+            let outside = node(loc)
+                .step(direction, track.dim())
+                .expect("The ring surrounds the grid.");
+            ring.iter()
+                .position(|&other| other == outside)
+                .expect("Nodes next to the grid are on the ring.")
+        });
+        let mut on_route = true;
+        for index in 0..ring.len() {
+            let position = (exit + index) % ring.len();
+            if position == entry {
+                on_route = false;
+            }
+            let (from, to) = (ring[position], ring[(position + 1) % ring.len()]);
+            let direction = Direction::ALL
+                .into_iter()
+                .find(|&direction| from.step(direction, track.dim()) == Some(to))
+                .expect("The ring goes between adjacent nodes.");
+            track.set_line(from, direction, on_route)?;
+        }
+        Ok(Self {
+            board,
+            counts,
+            shading,
+            track,
+        })
+    }
+
+    /// Ties the shading to the track: unshaded cells have no lines, cells with lines are shaded,
+    /// and cells the track cannot pass through are unshaded.
+    fn apply_cells(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        for loc in Location::grid_iter(self.board.dim()) {
+            let node = node(loc);
+            if self.shading.get(loc) == Shade::Unshaded {
+                for direction in Direction::ALL {
+                    changed |= self.track.set_line(node, direction, false)?;
+                }
+            } else if self.track.lines(node) > 0 {
+                changed |= self.shading.set(loc, Shade::Shaded)?;
+            } else if self.track.undecided(node).count() < 2 {
+                changed |= self.shading.set(loc, Shade::Unshaded)?;
+            }
+        }
+        Ok(changed)
+    }
+
+    fn into_solution(self) -> Solution {
+        let pieces = Grid::from_fn(self.board.dim(), |loc| {
+            let node = node(loc);
+            let mut directions = Direction::ALL
+                .into_iter()
+                .filter(|&direction| self.track.line(node, direction) == Some(true));
+            Piece::from_directions(directions.next()?, directions.next()?)
+        });
+        Solution { pieces }
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.apply_cells()?;
+            for (cells, count) in self.counts {
+                changed |= self.shading.apply_count(cells, *count)?;
+            }
+            let (height, width) = self.board.dim();
+            let shading = &self.shading;
+            // The nodes of the ring are all decided from the start.
+            let on_track = |node: Location| {
+                (1..=height).contains(&node.row)
+                    && (1..=width).contains(&node.col)
+                    && shading.is_shaded(Location::new(node.row - 1, node.col - 1))
+            };
+            changed |= self.track.apply_degrees(on_track)?;
+            changed |= self.track.apply_single_loop(on_track)?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        if let Some(location) = self.shading.first_unknown() {
+            return self
+                .shading
+                .branch_on(location)
+                .into_iter()
+                .map(|shading| Self {
+                    shading,
+                    ..self.clone()
+                })
+                .collect();
+        }
+        let Some((location, direction)) = self.track.branch_edge() else {
+            return vec![];
+        };
+        self.track
+            .branch_on(location, direction)
+            .into_iter()
+            .map(|track| Self {
+                track,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+pub fn solve(board: &Board) -> Option<Solution> {
+    let counts = shading::counted_lines(board.row_counts(), board.col_counts());
+    let state = State::new(board, &counts).ok()?;
+    search::solve(state).map(State::into_solution)
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    let counts = shading::counted_lines(board.row_counts(), board.col_counts());
+    State::new(board, &counts).map_or(0, |state| search::count_solutions(state, limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follows_rules(board: &Board, solution: &Solution) -> bool {
+        let (height, width) = board.dim();
+        let dim = (height, width);
+        let [(start, entry), exit] = board.ends();
+        let (mut current, mut from) = (start, entry);
+        let mut visited = vec![];
+        loop {
+            let Some(piece) = solution.piece(current) else {
+                return false;
+            };
+            if !piece.directions().contains(&from) || visited.contains(&current) {
+                return false;
+            }
+            visited.push(current);
+            let to = piece
+                .directions()
+                .into_iter()
+                .find(|&direction| direction != from)
+                .unwrap();
+            match current.step(to, dim) {
+                Some(next) => (current, from) = (next, to.opposite()),
+                None if (current, to) == exit => break,
+                None => return false,
+            }
+        }
+        let track = Location::grid_iter(dim).filter(|&loc| solution.piece(loc).is_some());
+        let givens = Location::grid_iter(dim).all(|loc| {
+            board
+                .piece(loc)
+                .is_none_or(|piece| solution.piece(loc) == Some(piece))
+        });
+        let rows = (0..height).all(|row| {
+            board.row_counts()[row]
+                .is_none_or(|count| visited.iter().filter(|loc| loc.row == row).count() == count)
+        });
+        let cols = (0..width).all(|col| {
+            board.col_counts()[col]
+                .is_none_or(|count| visited.iter().filter(|loc| loc.col == col).count() == count)
+        });
+        track.count() == visited.len() && givens && rows && cols
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/tracks/puzzles/eight01.txt")).unwrap();
+        let solution = solve(&board).unwrap();
+        assert!(follows_rules(&board, &solution));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
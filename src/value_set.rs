@@ -0,0 +1,247 @@
+//! Sets of small positive cell values, as the lowest bits of a `u16`, so that the set operations of the solvers are
+//! single instructions. The values can be plain numbers up to [`ValueSet::MAX_VALUE`], as used by the Latin square
+//! based puzzles, where the range depends on the size of the grid, or any other [`SetValue`], such as the values of a
+//! sudoku cell.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    marker::PhantomData,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not, Sub, SubAssign},
+};
+
+/// A value that can be in a [`ValueSet`], which holds it as the bit of its number.
+pub trait SetValue: Copy {
+    /// The largest number of a value, which is at most 16.
+    const MAX: u8;
+
+    /// The number of the value, from 1 to [`SetValue::MAX`].
+    fn number(self) -> u8;
+
+    /// The value of the number, which is from 1 to [`SetValue::MAX`].
+    fn from_number(number: u8) -> Self;
+}
+
+impl SetValue for u8 {
+    const MAX: u8 = 16;
+
+    fn number(self) -> u8 {
+        self
+    }
+
+    fn from_number(number: u8) -> Self {
+        number
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ValueSet<V = u8> {
+    bits: u16,
+    values: PhantomData<V>,
+}
+
+impl ValueSet {
+    /// The largest plain number a set can hold.
+    pub const MAX_VALUE: u8 = <u8 as SetValue>::MAX;
+}
+
+impl<V: SetValue> ValueSet<V> {
+    pub const NONE: Self = Self::from_bits(0);
+
+    /// Every value up to [`SetValue::MAX`].
+    pub const ALL: Self = Self::all(V::MAX);
+
+    const fn from_bits(bits: u16) -> Self {
+        Self {
+            bits,
+            values: PhantomData,
+        }
+    }
+
+    /// The set of every value from 1 up to and including `max`.
+    pub const fn all(max: u8) -> Self {
+        assert!(max <= V::MAX);
+        Self::from_bits(((1u32 << max) - 1) as u16)
+    }
+
+    /// The set of every value in `min..=max`.
+    pub const fn range(min: u8, max: u8) -> Self {
+        let min = if min == 0 { 1 } else { min };
+        if min > max {
+            Self::NONE
+        } else {
+            Self::from_bits(Self::all(max).bits & !Self::all(min - 1).bits)
+        }
+    }
+
+    pub fn from_value(value: V) -> Self {
+        let number = value.number();
+        assert!(number > 0 && number <= V::MAX);
+        Self::from_bits(1 << (number - 1))
+    }
+
+    pub fn contains(self, value: V) -> bool {
+        let number = value.number();
+        number > 0 && number <= V::MAX && self.bits & (1 << (number - 1)) != 0
+    }
+
+    pub fn insert(&mut self, value: V) {
+        *self |= Self::from_value(value);
+    }
+
+    pub fn remove(&mut self, value: V) {
+        *self -= Self::from_value(value);
+    }
+
+    pub const fn len(self) -> usize {
+        self.bits.count_ones() as usize
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.bits == 0
+    }
+
+    pub fn single(self) -> Option<V> {
+        if self.bits.is_power_of_two() {
+            self.min()
+        } else {
+            None
+        }
+    }
+
+    /// The smallest value in the set, found from the lowest set bit without iterating.
+    pub fn min(self) -> Option<V> {
+        (!self.is_empty()).then(|| V::from_number(self.bits.trailing_zeros() as u8 + 1))
+    }
+
+    pub fn max(self) -> Option<V> {
+        (!self.is_empty()).then(|| V::from_number(16 - self.bits.leading_zeros() as u8))
+    }
+
+    pub fn iter(self) -> ValueSetIter<V> {
+        ValueSetIter {
+            bits: self.bits,
+            values: PhantomData,
+        }
+    }
+}
+
+/// The values of a [`ValueSet`] from smallest to largest, found by clearing the lowest set bit.
+#[derive(Clone, Debug)]
+pub struct ValueSetIter<V> {
+    bits: u16,
+    values: PhantomData<V>,
+}
+
+impl<V: SetValue> Iterator for ValueSetIter<V> {
+    type Item = V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bits == 0 {
+            return None;
+        }
+        let value = V::from_number(self.bits.trailing_zeros() as u8 + 1);
+        self.bits &= self.bits - 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.bits.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl<V: SetValue> ExactSizeIterator for ValueSetIter<V> {}
+
+impl<V: SetValue + Display> Display for ValueSet<V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        let mut first = true;
+        for value in self.iter() {
+            if first {
+                first = false;
+            } else {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", value)?;
+        }
+        write!(f, "]")
+    }
+}
+
+impl<V: SetValue> FromIterator<V> for ValueSet<V> {
+    fn from_iter<I: IntoIterator<Item = V>>(iter: I) -> Self {
+        let mut set = Self::NONE;
+        for value in iter {
+            set.insert(value);
+        }
+        set
+    }
+}
+
+/// The values up to [`SetValue::MAX`] that are not in the set.
+impl<V: SetValue> Not for ValueSet<V> {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self::from_bits(!self.bits & Self::ALL.bits)
+    }
+}
+
+impl<V: SetValue> Sub<V> for ValueSet<V> {
+    type Output = Self;
+
+    fn sub(self, rhs: V) -> Self::Output {
+        self - Self::from_value(rhs)
+    }
+}
+
+impl<V> Sub for ValueSet<V> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            bits: self.bits & !rhs.bits,
+            values: PhantomData,
+        }
+    }
+}
+
+impl<V> SubAssign for ValueSet<V> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.bits &= !rhs.bits;
+    }
+}
+
+impl<V> BitOr for ValueSet<V> {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self {
+            bits: self.bits | rhs.bits,
+            values: PhantomData,
+        }
+    }
+}
+
+impl<V> BitOrAssign for ValueSet<V> {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.bits |= rhs.bits;
+    }
+}
+
+impl<V> BitAnd for ValueSet<V> {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self {
+            bits: self.bits & rhs.bits,
+            values: PhantomData,
+        }
+    }
+}
+
+impl<V> BitAndAssign for ValueSet<V> {
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.bits &= rhs.bits;
+    }
+}
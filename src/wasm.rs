@@ -0,0 +1,19 @@
+//! Bindings for embedding the solvers in a browser.
+//! Everything goes through strings so no filesystem access is needed.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{camping, puzzle, sudoku::Sudoku};
+
+/// Solves a camping map given in its JSON form and returns the solved map as JSON.
+#[wasm_bindgen]
+pub fn solve_camping(json: &str) -> Result<String, JsError> {
+    camping::solve_json(json).map_err(|err| JsError::new(&err.to_string()))
+}
+
+/// Solves a sudoku given as an 81 character line with '.' for empty cells
+/// and returns the solution in the same format.
+#[wasm_bindgen]
+pub fn solve_sudoku(line: &str) -> Result<String, JsError> {
+    puzzle::solve_str::<Sudoku>(line).map_err(|err| JsError::new(&err.to_string()))
+}
@@ -0,0 +1,24 @@
+mod board;
+pub use board::Board;
+mod generator;
+pub use generator::generate;
+mod solver;
+pub use solver::{solve, Placement, Solution};
+
+/// Word search puzzles as a [`Puzzle`](crate::puzzle::Puzzle), in the text format of [`Board::parse`].
+pub struct Wordsearch;
+
+impl crate::puzzle::Puzzle for Wordsearch {
+    const NAME: &'static str = "word search puzzle";
+
+    type Instance = Board;
+    type Solution = Solution;
+
+    fn parse(input: &str) -> crate::Result<Board> {
+        Board::parse(input)
+    }
+
+    fn solve(board: &Board) -> crate::Result<Option<Solution>> {
+        Ok(Some(solve(board)))
+    }
+}
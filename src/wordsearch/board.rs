@@ -0,0 +1,108 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Result},
+    grid::Grid,
+    location::Location,
+};
+
+/// A word search: a grid of letters in which every word of a list is hidden in a straight line, horizontally,
+/// vertically or diagonally, and possibly backwards.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    letters: Grid<char>,
+    words: Vec<String>,
+}
+
+impl Board {
+    /// Letters are compared ignoring case, so they are stored in uppercase.
+    pub fn new(letters: Grid<char>, words: Vec<String>) -> Result<Self> {
+        let (height, width) = letters.dim();
+        ensure!(
+            height > 0 && width > 0,
+            Invalid,
+            "Word search grids cannot be empty."
+        );
+        for (index, word) in words.iter().enumerate() {
+            ensure!(!word.is_empty(), Invalid, "Word {index} is empty.");
+            ensure!(
+                !word.chars().any(char::is_whitespace),
+                Invalid,
+                "The word '{word}' contains whitespace."
+            );
+        }
+        Ok(Self {
+            letters: letters.map(char::to_ascii_uppercase),
+            words: words.iter().map(|word| normalize(word)).collect(),
+        })
+    }
+
+    /// Parses a grid of letters, followed by a blank line and the words to find, one per line. Spaces within words,
+    /// as in `ICE CREAM`, are ignored.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let mut lines = string.as_ref().lines().map(str::trim);
+        let rows = lines
+            .by_ref()
+            .skip_while(|line| line.is_empty())
+            .take_while(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        let mut letters = Grid::from_elem((height, width), ' ');
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.chars().count() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} characters in row {row_index}. Got '{row}'."
+                );
+            }
+            for (col_index, c) in row.chars().enumerate() {
+                letters[Location::new(row_index, col_index)] = c;
+            }
+        }
+        let words = lines
+            .filter(|line| !line.is_empty())
+            .map(normalize)
+            .collect();
+        Self::new(letters, words)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("word search", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.letters.dim()
+    }
+
+    pub fn letter(&self, location: Location) -> char {
+        self.letters[location]
+    }
+
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+}
+
+/// Removes spaces from a word and converts it to uppercase.
+pub(super) fn normalize(word: &str) -> String {
+    word.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.letters.rows() {
+            writeln!(f, "{}", row.collect::<String>())?;
+        }
+        writeln!(f)?;
+        for word in self.words.iter() {
+            writeln!(f, "{word}")?;
+        }
+        Ok(())
+    }
+}
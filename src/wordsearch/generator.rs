@@ -0,0 +1,99 @@
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    error::{bail, ensure, Result},
+    grid::Grid,
+    location::Location,
+};
+
+use super::{
+    board::normalize,
+    solver::{self, DIRECTIONS},
+    Board,
+};
+
+/// Places every word in the grid, longest first, each where it overlaps the most letters already placed.
+/// Returns `None` if some word does not fit.
+fn place_words(
+    words: &[String],
+    dim: (usize, usize),
+    rng: &mut impl Rng,
+) -> Option<Grid<Option<char>>> {
+    let mut grid = Grid::from_elem(dim, None);
+    let mut order = words.iter().collect::<Vec<_>>();
+    order.shuffle(rng);
+    order.sort_by_key(|word| std::cmp::Reverse(word.len()));
+    for word in order {
+        let letters = word.chars().collect::<Vec<_>>();
+        let mut best = vec![];
+        let mut best_overlap = 0;
+        for start in Location::grid_iter(dim) {
+            for direction in DIRECTIONS {
+                let Some(cells) = solver::line(start, direction, letters.len(), dim) else {
+                    continue;
+                };
+                let mut overlap = 0;
+                let fits =
+                    cells
+                        .iter()
+                        .zip(letters.iter())
+                        .all(|(loc, &letter)| match grid[*loc] {
+                            None => true,
+                            Some(other) => {
+                                overlap += 1;
+                                other == letter
+                            }
+                        });
+                // A word entirely inside another word cannot be told apart from it.
+                if !fits || overlap == letters.len() {
+                    continue;
+                }
+                if overlap > best_overlap {
+                    best.clear();
+                    best_overlap = overlap;
+                }
+                if overlap == best_overlap {
+                    best.push(cells);
+                }
+            }
+        }
+        let cells = best.choose(rng)?;
+        for (loc, letter) in cells.iter().zip(letters) {
+            grid[*loc] = Some(letter);
+        }
+    }
+    Some(grid)
+}
+
+/// Generates a grid of the given dimensions in which every word appears exactly once, giving up after `attempts`
+/// tries. Words are packed to overlap as much as possible, and the remaining cells are filled with letters drawn
+/// from the words, so that they blend in.
+pub fn generate(
+    words: &[String],
+    dim: (usize, usize),
+    attempts: usize,
+    rng: &mut impl Rng,
+) -> Result<Board> {
+    let words = words.iter().map(|word| normalize(word)).collect::<Vec<_>>();
+    let (height, width) = dim;
+    ensure!(!words.is_empty(), Generate, "No words to hide.");
+    let pool = words
+        .iter()
+        .flat_map(|word| word.chars())
+        .collect::<Vec<_>>();
+    ensure!(!pool.is_empty(), Generate, "Every word is empty.");
+    for _ in 0..attempts {
+        let Some(grid) = place_words(&words, dim, rng) else {
+            continue;
+        };
+        let letters = grid.map(|letter| letter.unwrap_or_else(|| *pool.choose(rng).unwrap()));
+        let board = Board::new(letters, words.clone())?;
+        if solver::solve(&board).is_unique() {
+            return Ok(board);
+        }
+    }
+    bail!(
+        Generate,
+        "Failed to hide every word exactly once in a {height}x{width} grid in {attempts} attempts."
+    )
+}
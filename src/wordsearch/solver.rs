@@ -0,0 +1,164 @@
+use std::fmt::Display;
+
+use crate::{grid::Grid, location::Location};
+
+use super::Board;
+
+/// The eight directions a word can run in, as steps in rows and columns, in the same order as
+/// [`Location::neighbors`].
+pub(super) const DIRECTIONS: [(isize, isize); 8] = [
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+];
+
+/// The cells of a line of `length` cells from `start` in `direction`, if it fits in the grid.
+pub(super) fn line(
+    start: Location,
+    (row_step, col_step): (isize, isize),
+    length: usize,
+    (height, width): (usize, usize),
+) -> Option<Vec<Location>> {
+    (0..length as isize)
+        .map(|index| {
+            let row = start.row.checked_add_signed(row_step * index)?;
+            let col = start.col.checked_add_signed(col_step * index)?;
+            (row < height && col < width).then(|| Location::new(row, col))
+        })
+        .collect()
+}
+
+/// Where a word was found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Placement {
+    /// The index of the word in [`Board::words`].
+    pub word: usize,
+    /// The cells of the word, from its first letter to its last.
+    pub cells: Vec<Location>,
+}
+
+impl Placement {
+    pub fn start(&self) -> Location {
+        self.cells[0]
+    }
+
+    pub fn end(&self) -> Location {
+        self.cells[self.cells.len() - 1]
+    }
+}
+
+/// Every placement of every word.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    words: Vec<String>,
+    letters: Grid<Option<char>>,
+    placements: Vec<Vec<Placement>>,
+}
+
+impl Solution {
+    /// The placements of the word with the given index in [`Board::words`].
+    pub fn placements(&self, word: usize) -> &[Placement] {
+        &self.placements[word]
+    }
+
+    /// Whether every word was found exactly once.
+    pub fn is_unique(&self) -> bool {
+        self.placements
+            .iter()
+            .all(|placements| placements.len() == 1)
+    }
+}
+
+/// The letters that are part of some word, with the rest shown as `.`, followed by the placements of every word.
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.letters.rows() {
+            let row = row.map(|letter| letter.unwrap_or('.')).collect::<String>();
+            writeln!(f, "{row}")?;
+        }
+        writeln!(f)?;
+        for (word, placements) in self.words.iter().zip(self.placements.iter()) {
+            if placements.is_empty() {
+                writeln!(f, "{word}: not found")?;
+            } else {
+                let placements = placements
+                    .iter()
+                    .map(|placement| format!("{} to {}", placement.start(), placement.end()))
+                    .collect::<Vec<_>>();
+                writeln!(f, "{word}: {}", placements.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Finds every placement of every word. Placements covering the same cells, such as a palindrome read in both
+/// directions, are only counted once.
+pub fn solve(board: &Board) -> Solution {
+    let dim = board.dim();
+    let mut letters = Grid::from_elem(dim, None);
+    let mut placements = vec![];
+    for (index, word) in board.words().iter().enumerate() {
+        let word = word.chars().collect::<Vec<_>>();
+        let mut found: Vec<Placement> = vec![];
+        for start in Location::grid_iter(dim) {
+            for direction in DIRECTIONS {
+                let Some(cells) = line(start, direction, word.len(), dim) else {
+                    continue;
+                };
+                let matches = cells
+                    .iter()
+                    .zip(word.iter())
+                    .all(|(&loc, &letter)| board.letter(loc) == letter);
+                let duplicate = found
+                    .iter()
+                    .any(|other| other.cells.iter().rev().eq(cells.iter()) || other.cells == cells);
+                if matches && !duplicate {
+                    found.push(Placement { word: index, cells });
+                }
+            }
+        }
+        for loc in found.iter().flat_map(|placement| placement.cells.iter()) {
+            letters[*loc] = Some(board.letter(*loc));
+        }
+        placements.push(found);
+    }
+    Solution {
+        words: board.words().to_vec(),
+        letters,
+        placements,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_words_in_every_direction() {
+        let board = Board::parse("CATX\nXAXA\nXXTX\nTACX\n\ncat\naxa\ndog").unwrap();
+        let solution = solve(&board);
+        let starts = solution
+            .placements(0)
+            .iter()
+            .map(|placement| (placement.start(), placement.end()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            starts,
+            [
+                (Location::new(0, 0), Location::new(0, 2)),
+                (Location::new(0, 0), Location::new(2, 2)),
+                (Location::new(3, 2), Location::new(3, 0)),
+            ]
+        );
+        // The palindrome is found once in its row and once in its column, not once in each direction.
+        assert_eq!(solution.placements(1).len(), 2);
+        assert!(solution.placements(2).is_empty());
+        assert!(!solution.is_unique());
+    }
+}
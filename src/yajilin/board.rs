@@ -0,0 +1,206 @@
+use std::{fmt::Display, path};
+
+use crate::{
+    error::{bail, ensure, read_to_string, Error, Result},
+    grid::Grid,
+    location::{Direction, Location},
+    puzzlink::{self, ArrowClue, Clue, PuzzLink},
+};
+
+/// A clue saying how many shaded cells there are from the clue to the edge of the grid in `direction`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Arrow {
+    pub direction: Direction,
+    pub count: usize,
+}
+
+impl Arrow {
+    pub fn to_char(self) -> char {
+        match self.direction {
+            Direction::Up => '^',
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+        }
+    }
+
+    fn from_char(c: char) -> Option<Direction> {
+        Some(match c {
+            '^' => Direction::Up,
+            '>' => Direction::Right,
+            'v' => Direction::Down,
+            '<' => Direction::Left,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cell {
+    /// A cell that is either shaded or on the loop.
+    Open,
+    /// A clue cell, which is neither. Clues without an arrow say nothing.
+    Clue(Option<Arrow>),
+}
+
+/// A yajilin puzzle: shade some of the open cells and draw a single loop through the centres of all the others.
+/// Shaded cells may not be orthogonally adjacent, and every arrow says how many cells it points at are shaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    cells: Grid<Cell>,
+}
+
+impl Board {
+    pub fn new(cells: Grid<Cell>) -> Result<Self> {
+        let dim = cells.dim();
+        ensure!(
+            dim.0 > 1 && dim.1 > 1,
+            Invalid,
+            "Yajilin grids must be at least 2x2, but got {}x{}.",
+            dim.0,
+            dim.1
+        );
+        for (location, &cell) in cells.indexed_iter() {
+            let Cell::Clue(Some(arrow)) = cell else {
+                continue;
+            };
+            let length = location.ray(arrow.direction, dim).count();
+            // Shaded cells cannot be adjacent, so at most every other cell is shaded.
+            ensure!(
+                arrow.count <= length.div_ceil(2), Invalid,
+                "The arrow at {location} points at {length} cells, so it cannot count {} shaded cells.",
+                arrow.count
+            );
+        }
+        Ok(Self { cells })
+    }
+
+    /// Parses rows of whitespace separated cells, where `.` is an open cell, `+` is a clue without an arrow
+    /// and an arrow is its count followed by one of `^`, `>`, `v` and `<`, such as `2>`.
+    pub fn parse(string: impl AsRef<str>) -> Result<Self> {
+        let rows = string
+            .as_ref()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| line.split_whitespace().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        let mut cells = Grid::from_elem((height, width), Cell::Open);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                bail!(
+                    Parse,
+                    "Expected {width} cells in row {row_index}. Got {}.",
+                    row.len()
+                );
+            }
+            for (col_index, token) in row.into_iter().enumerate() {
+                cells[Location::new(row_index, col_index)] = match token {
+                    "." => Cell::Open,
+                    "+" => Cell::Clue(None),
+                    _ => {
+                        let invalid =
+                            || format!("Invalid cell '{token}' at ({row_index}, {col_index}).");
+                        let arrow = token
+                            .chars()
+                            .last()
+                            .ok_or_else(|| Error::Parse(invalid()))?;
+                        let direction =
+                            Arrow::from_char(arrow).ok_or_else(|| Error::Parse(invalid()))?;
+                        let count = token[..token.len() - arrow.len_utf8()]
+                            .parse()
+                            .map_err(|_| Error::Parse(invalid()))?;
+                        Cell::Clue(Some(Arrow { direction, count }))
+                    }
+                };
+            }
+        }
+        Self::new(cells)
+    }
+
+    /// Imports a puzzle from a puzz.link or pzv.jp URL.
+    pub fn from_puzz_link(url: &str) -> Result<Self> {
+        let link = PuzzLink::parse(url)?;
+        link.expect_kind(&["yajilin", "yajikazu"])?;
+        let clues = puzzlink::decode_arrow_numbers(&link.body, link.width * link.height)?;
+        let cells = clues
+            .into_iter()
+            .map(|clue| match clue {
+                None => Cell::Open,
+                Some(ArrowClue {
+                    direction: Some(direction),
+                    number: Clue::Number(count),
+                }) => Cell::Clue(Some(Arrow {
+                    direction,
+                    count: count as usize,
+                })),
+                Some(_) => Cell::Clue(None),
+            })
+            .collect();
+        let cells = Grid::from_vec((link.height, link.width), cells)
+            .expect("The URL has one clue per cell.");
+        Self::new(cells)
+    }
+
+    pub fn from_file(path: impl AsRef<path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let string = read_to_string("yajilin", path)?;
+        Self::parse(string)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.cells.dim()
+    }
+
+    pub fn cell(&self, location: Location) -> Cell {
+        self.cells[location]
+    }
+
+    pub fn is_clue(&self, location: Location) -> bool {
+        matches!(self.cell(location), Cell::Clue(_))
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.cells.rows() {
+            let row = row
+                .map(|cell| match cell {
+                    Cell::Open => ".".to_string(),
+                    Cell::Clue(None) => "+".to_string(),
+                    Cell::Clue(Some(arrow)) => format!("{}{}", arrow.count, arrow.to_char()),
+                })
+                .collect::<Vec<_>>();
+            writeln!(f, "{}", row.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_text_and_puzz_links() {
+        let board = Board::parse(include_str!("../../data/yajilin/puzzles/seven01.txt")).unwrap();
+        assert_eq!(board.dim(), (7, 7));
+        assert_eq!(
+            board.cell(Location::new(0, 5)),
+            Cell::Clue(Some(Arrow {
+                direction: Direction::Left,
+                count: 2
+            }))
+        );
+        assert_eq!(board.cell(Location::new(1, 2)), Cell::Clue(None));
+        assert_eq!(board.cell(Location::new(0, 0)), Cell::Open);
+        assert_eq!(Board::parse(board.to_string()).unwrap(), board);
+        let link = "https://puzz.link/p?yajilin/7/7/e32a21a0.c0.b0.b0.g0.b310.e410.i0.";
+        assert_eq!(Board::from_puzz_link(link).unwrap(), board);
+        assert!(Board::parse("3> . .\n. . .\n").is_err());
+        assert!(Board::parse("1x .\n. .\n").is_err());
+        assert!(Board::parse("1> .\n.\n").is_err());
+    }
+}
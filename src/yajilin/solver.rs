@@ -0,0 +1,204 @@
+use std::fmt::Display;
+
+use crate::{
+    location::{Direction, Location},
+    loop_grid::LoopGrid,
+    search::{self, Contradiction, SearchState},
+    shading::{Shade, ShadeGrid},
+};
+
+use super::{Board, Cell};
+
+/// The shaded cells and the loop of a solved puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    board: Board,
+    shading: ShadeGrid,
+    lines: LoopGrid,
+}
+
+impl Solution {
+    pub fn is_shaded(&self, location: Location) -> bool {
+        self.shading.is_shaded(location)
+    }
+
+    /// Whether the loop leaves the cell at `location` in `direction`.
+    pub fn line(&self, location: Location, direction: Direction) -> bool {
+        self.lines.line(location, direction) == Some(true)
+    }
+}
+
+/// Clues are shown by their arrow or `+` if they have none, shaded cells as `#`, cells on the loop as `.`
+/// and the loop with `-` and `|` between the cells.
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rendered = self.lines.render(|loc| match self.board.cell(loc) {
+            Cell::Clue(Some(arrow)) => arrow.to_char(),
+            Cell::Clue(None) => '+',
+            Cell::Open => self.shading.get(loc).to_char(),
+        });
+        write!(f, "{rendered}")
+    }
+}
+
+/// Clue cells are unshaded in `shading`, so an open cell is on the loop exactly when it is unshaded.
+#[derive(Clone)]
+struct State<'a> {
+    board: &'a Board,
+    /// The cells each arrow points at, with the number of them that must be shaded.
+    arrows: &'a [(Vec<Location>, usize)],
+    shading: ShadeGrid,
+    lines: LoopGrid,
+}
+
+impl<'a> State<'a> {
+    fn new(board: &'a Board, arrows: &'a [(Vec<Location>, usize)]) -> Self {
+        let mut shading = ShadeGrid::new(board.dim());
+        for loc in Location::grid_iter(board.dim()) {
+            if board.is_clue(loc) {
+                shading
+                    .set(loc, Shade::Unshaded)
+                    .expect("Only clue cells have been set.");
+            }
+        }
+        Self {
+            board,
+            arrows,
+            shading,
+            lines: LoopGrid::new(board.dim()),
+        }
+    }
+
+    /// Ties the shading to the loop: clues and shaded cells have no lines, cells with lines are unshaded,
+    /// open cells the loop cannot pass through are shaded, and shaded cells are not adjacent.
+    fn apply_cells(&mut self) -> Result<bool, Contradiction> {
+        let dim = self.board.dim();
+        let mut changed = false;
+        for loc in Location::grid_iter(dim) {
+            if self.board.is_clue(loc) || self.shading.is_shaded(loc) {
+                for direction in Direction::ALL {
+                    changed |= self.lines.set_line(loc, direction, false)?;
+                }
+            } else if self.lines.lines(loc) > 0 {
+                changed |= self.shading.set(loc, Shade::Unshaded)?;
+            } else if self.lines.undecided(loc).count() < 2 {
+                changed |= self.shading.set(loc, Shade::Shaded)?;
+            }
+            if self.shading.is_shaded(loc) {
+                for adj in loc.adjacents(dim).into_iter().flatten() {
+                    changed |= self.shading.set(adj, Shade::Unshaded)?;
+                }
+            }
+        }
+        Ok(changed)
+    }
+}
+
+impl SearchState for State<'_> {
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        loop {
+            let mut changed = self.apply_cells()?;
+            for (cells, count) in self.arrows {
+                changed |= self.shading.apply_count(cells, *count)?;
+            }
+            let (board, shading) = (self.board, &self.shading);
+            let on_loop = |loc| !board.is_clue(loc) && shading.get(loc) == Shade::Unshaded;
+            changed |= self.lines.apply_degrees(on_loop)?;
+            changed |= self.lines.apply_single_loop(on_loop)?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn branches(&self) -> Vec<Self> {
+        if let Some(location) = self.shading.first_unknown() {
+            return self
+                .shading
+                .branch_on(location)
+                .into_iter()
+                .map(|shading| Self {
+                    shading,
+                    ..self.clone()
+                })
+                .collect();
+        }
+        let Some((location, direction)) = self.lines.branch_edge() else {
+            return vec![];
+        };
+        self.lines
+            .branch_on(location, direction)
+            .into_iter()
+            .map(|lines| Self {
+                lines,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+/// Every arrow with the cells it points at.
+fn arrows(board: &Board) -> Vec<(Vec<Location>, usize)> {
+    let dim = board.dim();
+    Location::grid_iter(dim)
+        .filter_map(|loc| match board.cell(loc) {
+            Cell::Clue(Some(arrow)) => Some((loc.ray(arrow.direction, dim).collect(), arrow.count)),
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn solve(board: &Board) -> Option<Solution> {
+    let arrows = arrows(board);
+    search::solve(State::new(board, &arrows)).map(|state| Solution {
+        board: board.clone(),
+        shading: state.shading,
+        lines: state.lines,
+    })
+}
+
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    let arrows = arrows(board);
+    search::count_solutions(State::new(board, &arrows), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follows_rules(board: &Board, solution: &Solution) -> bool {
+        let dim = board.dim();
+        let Some(path) = solution.lines.loop_nodes() else {
+            return false;
+        };
+        Location::grid_iter(dim).all(|loc| match board.cell(loc) {
+            Cell::Open if solution.is_shaded(loc) => {
+                !path.contains(&loc)
+                    && loc
+                        .adjacents(dim)
+                        .into_iter()
+                        .flatten()
+                        .all(|adj| !solution.is_shaded(adj))
+            }
+            Cell::Open => path.contains(&loc),
+            Cell::Clue(arrow) => {
+                !solution.is_shaded(loc)
+                    && !path.contains(&loc)
+                    && arrow.is_none_or(|arrow| {
+                        let shaded = loc
+                            .ray(arrow.direction, dim)
+                            .filter(|&other| solution.is_shaded(other));
+                        shaded.count() == arrow.count
+                    })
+            }
+        })
+    }
+
+    #[test]
+    fn solves_data_puzzle() {
+        let board = Board::parse(include_str!("../../data/yajilin/puzzles/seven01.txt")).unwrap();
+        let solution = solve(&board).unwrap();
+        assert!(follows_rules(&board, &solution));
+        assert_eq!(count_solutions(&board, 2), 1);
+    }
+}
@@ -0,0 +1,29 @@
+//! Runs the `puzzle` command as a user would.
+#![cfg(feature = "cli")]
+
+use std::process::{Command, Output};
+
+fn puzzle(args: &[&str]) -> Output {
+    let output_dir = std::env::temp_dir().join(format!("puzzles-cli-test-{}", std::process::id()));
+    Command::new(env!("CARGO_BIN_EXE_puzzle"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .arg("--quiet")
+        .arg("--output-dir")
+        .arg(&output_dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn reports_invalid_puzzles_before_solving() {
+    let sudoku = format!("11{}", ".".repeat(79));
+    let output = puzzle(&["sudoku", "solve", "--no-index", &sudoku]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("Invalid sudoku: Row 0 has duplicate value 1"),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("steps"), "{stderr}");
+}